@@ -1,9 +1,16 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::{ApiVersion, OneLoginRegion};
 use crate::core::error::Result;
 use crate::models::account::*;
+use crate::models::events::{Event, EventQueryParams};
+use crate::models::rate_limits::RateLimitStatus;
+use crate::models::PaginatedResponse;
 use std::sync::Arc;
-use tracing::instrument;
+use tracing::{debug, instrument};
+
+/// Maximum number of events sampled when deriving usage figures
+const OVERVIEW_EVENT_LIMIT: i32 = 1000;
 
 pub struct AccountApi {
     client: Arc<HttpClient>,
@@ -16,10 +23,15 @@ impl AccountApi {
         Self { client, cache }
     }
 
+    /// Subdomain of the tenant this client is connected to
+    pub fn subdomain(&self) -> &str {
+        &self.client.config().onelogin_subdomain
+    }
+
     /// Get global OneLogin account settings
     #[instrument(skip(self))]
     pub async fn get_account_settings(&self) -> Result<AccountSettings> {
-        self.client.get("/api/2/account").await
+        self.client.get(&ApiVersion::V2.path("account")).await
     }
 
     /// Update account settings
@@ -28,13 +40,13 @@ impl AccountApi {
         &self,
         request: UpdateAccountSettingsRequest,
     ) -> Result<AccountSettings> {
-        self.client.put("/api/2/account", Some(&request)).await
+        self.client.put(&ApiVersion::V2.path("account"), Some(&request)).await
     }
 
     /// Get list of enabled features for the account
     #[instrument(skip(self))]
     pub async fn get_account_features(&self) -> Result<Vec<AccountFeature>> {
-        self.client.get("/api/2/account/features").await
+        self.client.get(&ApiVersion::V2.path("account/features")).await
     }
 
     /// Get account usage statistics
@@ -44,7 +56,7 @@ impl AccountApi {
         start_date: Option<String>,
         end_date: Option<String>,
     ) -> Result<AccountUsage> {
-        let mut path = "/api/2/account/usage".to_string();
+        let mut path = ApiVersion::V2.path("account/usage");
         let mut params = vec![];
 
         if let Some(start) = start_date {
@@ -60,4 +72,75 @@ impl AccountApi {
 
         self.client.get(&path).await
     }
+
+    /// Build an account overview from available endpoints.
+    ///
+    /// Settings are included when `/api/2/account` responds; usage is derived from the
+    /// last `days` of events and rate-limit data comes from `/auth/rate_limit`.
+    #[instrument(skip(self))]
+    pub async fn get_account_overview(&self, days: i64) -> Result<AccountOverview> {
+        let config = self.client.config();
+        let mut notes = Vec::new();
+
+        let settings = match self.get_account_settings().await {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                debug!("Account settings unavailable: {}", e);
+                notes.push("Account settings endpoint is not available for this tenant".to_string());
+                None
+            }
+        };
+
+        let since = chrono::Utc::now() - chrono::Duration::days(days);
+        let params = EventQueryParams {
+            since: Some(since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            until: None,
+            user_id: None,
+            event_type_id: None,
+            client_id: None,
+            directory_id: None,
+            limit: Some(OVERVIEW_EVENT_LIMIT),
+            ..Default::default()
+        };
+        let mut path = ApiVersion::V1.path("events");
+        if let Ok(query) = serde_qs::to_string(&params) {
+            path.push('?');
+            path.push_str(&query);
+        }
+        let response: PaginatedResponse<Event> = self.client.get(&path).await?;
+        let events = response.data;
+        let events_truncated = response.pagination.after_cursor.is_some();
+        if events_truncated {
+            notes.push(format!(
+                "Usage is based on the most recent {} events; older events in the window were not counted",
+                events.len()
+            ));
+        }
+        notes.push(format!("Usage figures are derived from events over the last {} days", days));
+
+        let rate_limit = match self.client.get::<RateLimitStatus>(&ApiVersion::Root.path("auth/rate_limit")).await {
+            Ok(status) => status.data,
+            Err(e) => {
+                debug!("Rate limit status unavailable: {}", e);
+                notes.push("Rate limit status could not be retrieved".to_string());
+                None
+            }
+        };
+
+        let period = format!("{}/{}", since.format("%Y-%m-%d"), chrono::Utc::now().format("%Y-%m-%d"));
+
+        Ok(AccountOverview {
+            subdomain: config.onelogin_subdomain.clone(),
+            region: match config.onelogin_region {
+                OneLoginRegion::US => "us".to_string(),
+                OneLoginRegion::EU => "eu".to_string(),
+            },
+            settings,
+            usage: AccountUsage::from_events(&events, Some(period)),
+            events_sampled: events.len(),
+            events_truncated,
+            rate_limit,
+            notes,
+        })
+    }
 }
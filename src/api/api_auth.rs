@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::api_auth::*;
 use std::sync::Arc;
@@ -21,17 +22,38 @@ impl ApiAuthApi {
         Self { client, cache }
     }
 
+    /// Run the client-credentials grant for an OIDC app's `client_id`/`client_secret`,
+    /// asking for a token for the API identified by `resource`. Returns the HTTP status
+    /// and the token endpoint's JSON, including error responses.
+    #[instrument(skip(self, client_secret))]
+    pub async fn request_client_credentials_token(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        resource: &str,
+        scopes: &[String],
+    ) -> Result<(u16, serde_json::Value)> {
+        let scope = scopes.join(" ");
+        let mut form = vec![("grant_type", "client_credentials"), ("resource", resource)];
+        if !scope.is_empty() {
+            form.push(("scope", scope.as_str()));
+        }
+        self.client
+            .post_form_as_client(&ApiVersion::Root.path("oidc/2/token"), client_id, client_secret, &form)
+            .await
+    }
+
     #[instrument(skip(self))]
     pub async fn list_api_authorizations(&self) -> Result<Vec<ApiAuthorization>> {
         // Note: api_authorizations use API v2
-        self.client.get("/api/2/api_authorizations").await
+        self.client.get(&ApiVersion::V2.path("api_authorizations")).await
     }
 
     #[instrument(skip(self))]
     pub async fn get_api_authorization(&self, auth_id: &str) -> Result<ApiAuthorization> {
         // Note: api_authorizations use API v2
         self.client
-            .get(&format!("/api/2/api_authorizations/{}", auth_id))
+            .get(&ApiVersion::V2.path(&format!("api_authorizations/{}", auth_id)))
             .await
     }
 
@@ -43,7 +65,7 @@ impl ApiAuthApi {
         // Note: api_authorizations use API v2
         // API returns just {id} on create, so we need to fetch the full record
         let response: CreateApiAuthResponse = self.client
-            .post("/api/2/api_authorizations", Some(&request))
+            .post(&ApiVersion::V2.path("api_authorizations"), Some(&request))
             .await?;
         // Fetch the full authorization to return
         self.get_api_authorization(&response.id.to_string()).await
@@ -57,7 +79,7 @@ impl ApiAuthApi {
     ) -> Result<ApiAuthorization> {
         // Note: api_authorizations use API v2
         self.client
-            .put(&format!("/api/2/api_authorizations/{}", auth_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("api_authorizations/{}", auth_id)), Some(&request))
             .await
     }
 
@@ -65,7 +87,7 @@ impl ApiAuthApi {
     pub async fn delete_api_authorization(&self, auth_id: &str) -> Result<()> {
         // Note: api_authorizations use API v2
         self.client
-            .delete(&format!("/api/2/api_authorizations/{}", auth_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("api_authorizations/{}", auth_id)))
             .await
     }
 }
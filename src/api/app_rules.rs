@@ -1,13 +1,15 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::app_rules::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::sync::Arc;
 use tracing::instrument;
 
 pub struct AppRulesApi {
     client: Arc<HttpClient>,
-    #[allow(dead_code)]
     cache: Arc<CacheManager>,
 }
 
@@ -19,7 +21,7 @@ impl AppRulesApi {
     /// List all rules for an application
     #[instrument(skip(self))]
     pub async fn list_rules(&self, app_id: i64, params: Option<AppRuleQueryParams>) -> Result<Vec<AppRule>> {
-        let mut path = format!("/api/2/apps/{}/rules", app_id);
+        let mut path = ApiVersion::V2.path(&format!("apps/{}/rules", app_id));
         if let Some(p) = params {
             if let Ok(query) = serde_qs::to_string(&p) {
                 if !query.is_empty() {
@@ -35,7 +37,7 @@ impl AppRulesApi {
     #[instrument(skip(self))]
     pub async fn get_rule(&self, app_id: i64, rule_id: i64) -> Result<AppRule> {
         self.client
-            .get(&format!("/api/2/apps/{}/rules/{}", app_id, rule_id))
+            .get(&ApiVersion::V2.path(&format!("apps/{}/rules/{}", app_id, rule_id)))
             .await
     }
 
@@ -43,7 +45,7 @@ impl AppRulesApi {
     #[instrument(skip(self, request))]
     pub async fn create_rule(&self, app_id: i64, request: CreateAppRuleRequest) -> Result<AppRule> {
         self.client
-            .post(&format!("/api/2/apps/{}/rules", app_id), Some(&request))
+            .post(&ApiVersion::V2.path(&format!("apps/{}/rules", app_id)), Some(&request))
             .await
     }
 
@@ -57,7 +59,7 @@ impl AppRulesApi {
     ) -> Result<AppRule> {
         self.client
             .put(
-                &format!("/api/2/apps/{}/rules/{}", app_id, rule_id),
+                &ApiVersion::V2.path(&format!("apps/{}/rules/{}", app_id, rule_id)),
                 Some(&request),
             )
             .await
@@ -67,24 +69,20 @@ impl AppRulesApi {
     #[instrument(skip(self))]
     pub async fn delete_rule(&self, app_id: i64, rule_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/apps/{}/rules/{}", app_id, rule_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("apps/{}/rules/{}", app_id, rule_id)))
             .await
     }
 
     /// List available condition types for an application's rules
     #[instrument(skip(self))]
     pub async fn list_conditions(&self, app_id: i64) -> Result<Vec<RuleConditionDef>> {
-        self.client
-            .get(&format!("/api/2/apps/{}/rules/conditions", app_id))
-            .await
+        self.cached_catalog(format!("apps/{}/rules/conditions", app_id)).await
     }
 
     /// List available action types for an application's rules
     #[instrument(skip(self))]
     pub async fn list_actions(&self, app_id: i64) -> Result<Vec<RuleActionDef>> {
-        self.client
-            .get(&format!("/api/2/apps/{}/rules/actions", app_id))
-            .await
+        self.cached_catalog(format!("apps/{}/rules/actions", app_id)).await
     }
 
     /// List operators for a specific condition type
@@ -94,11 +92,7 @@ impl AppRulesApi {
         app_id: i64,
         condition_value: &str,
     ) -> Result<Vec<RuleConditionOperator>> {
-        self.client
-            .get(&format!(
-                "/api/2/apps/{}/rules/conditions/{}/operators",
-                app_id, condition_value
-            ))
+        self.cached_catalog(format!("apps/{}/rules/conditions/{}/operators", app_id, condition_value))
             .await
     }
 
@@ -109,11 +103,7 @@ impl AppRulesApi {
         app_id: i64,
         condition_value: &str,
     ) -> Result<Vec<RuleConditionValue>> {
-        self.client
-            .get(&format!(
-                "/api/2/apps/{}/rules/conditions/{}/values",
-                app_id, condition_value
-            ))
+        self.cached_catalog(format!("apps/{}/rules/conditions/{}/values", app_id, condition_value))
             .await
     }
 
@@ -124,19 +114,27 @@ impl AppRulesApi {
         app_id: i64,
         action_value: &str,
     ) -> Result<Vec<RuleActionValue>> {
-        self.client
-            .get(&format!(
-                "/api/2/apps/{}/rules/actions/{}/values",
-                app_id, action_value
-            ))
+        self.cached_catalog(format!("apps/{}/rules/actions/{}/values", app_id, action_value))
             .await
     }
 
+    /// Condition, operator and value catalogs only change with the app's connector,
+    /// so they are cached like other lookups; rule tools and argument completion share them
+    async fn cached_catalog<T: Serialize + DeserializeOwned>(&self, path: String) -> Result<Vec<T>> {
+        let key = CacheManager::build_key("app_rule_catalog", &[&path]);
+        if let Some(items) = self.cache.get(&key).await {
+            return Ok(items);
+        }
+        let items: Vec<T> = self.client.get(&ApiVersion::V2.path(&path)).await?;
+        self.cache.set(key, &items).await;
+        Ok(items)
+    }
+
     /// Sort/reorder rules for an application
     #[instrument(skip(self, request))]
     pub async fn sort_rules(&self, app_id: i64, request: SortRulesRequest) -> Result<Vec<i64>> {
         self.client
-            .put(&format!("/api/2/apps/{}/rules/sort", app_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("apps/{}/rules/sort", app_id)), Some(&request))
             .await
     }
 }
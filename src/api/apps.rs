@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::apps::*;
 use std::sync::Arc;
@@ -16,9 +17,18 @@ impl AppsApi {
     }
 
     #[instrument(skip(self))]
-    pub async fn list_apps(&self) -> Result<Vec<App>> {
+    pub async fn list_apps(&self, params: Option<AppQueryParams>) -> Result<Vec<App>> {
+        let mut path = ApiVersion::V2.path("apps");
+        if let Some(p) = params {
+            if let Ok(query) = serde_qs::to_string(&p) {
+                if !query.is_empty() {
+                    path.push('?');
+                    path.push_str(&query);
+                }
+            }
+        }
         // OneLogin API returns apps as a plain array, not wrapped in a pagination envelope
-        let response: Vec<App> = self.client.get("/apps").await?;
+        let response: Vec<App> = self.client.get(&path).await?;
         Ok(response)
     }
 
@@ -31,7 +41,7 @@ impl AppsApi {
         }
 
         // OneLogin API returns a plain app object, not wrapped
-        let app: App = self.client.get(&format!("/apps/{}", app_id)).await?;
+        let app: App = self.client.get(&ApiVersion::V2.path(&format!("apps/{}", app_id))).await?;
 
         self.cache.set(cache_key, &app).await;
         Ok(app)
@@ -40,7 +50,7 @@ impl AppsApi {
     #[instrument(skip(self, request))]
     pub async fn create_app(&self, request: CreateAppRequest) -> Result<App> {
         // OneLogin API returns a plain app object, not wrapped
-        let app: App = self.client.post("/apps", Some(&request)).await?;
+        let app: App = self.client.post(&ApiVersion::V2.path("apps"), Some(&request)).await?;
         Ok(app)
     }
 
@@ -51,7 +61,7 @@ impl AppsApi {
 
         // OneLogin API returns a plain app object, not wrapped
         let app: App = self.client
-            .put(&format!("/apps/{}", app_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("apps/{}", app_id)), Some(&request))
             .await?;
         Ok(app)
     }
@@ -61,7 +71,7 @@ impl AppsApi {
         let cache_key = CacheManager::build_key("app", &[&app_id.to_string()]);
         self.cache.invalidate(&cache_key).await;
 
-        self.client.delete(&format!("/apps/{}", app_id)).await
+        self.client.delete_no_content(&ApiVersion::V2.path(&format!("apps/{}", app_id))).await
     }
 
     #[instrument(skip(self))]
@@ -70,7 +80,7 @@ impl AppsApi {
         self.cache.invalidate(&cache_key).await;
 
         self.client
-            .delete(&format!("/apps/{}/parameters/{}", app_id, parameter_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("apps/{}/parameters/{}", app_id, parameter_id)))
             .await
     }
 }
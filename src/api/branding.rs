@@ -1,6 +1,7 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
-use crate::core::error::Result;
+use crate::core::config::ApiVersion;
+use crate::core::error::{OneLoginError, Result};
 use crate::models::branding::*;
 use std::sync::Arc;
 use tracing::instrument;
@@ -19,19 +20,19 @@ impl BrandingApi {
     #[instrument(skip(self))]
     pub async fn list_account_brands(&self) -> Result<Vec<AccountBrand>> {
         // OneLogin API v2 returns direct array
-        self.client.get("/api/2/branding/brands").await
+        self.client.get(&ApiVersion::V2.path("branding/brands")).await
     }
 
     #[instrument(skip(self))]
     pub async fn get_account_brand(&self, brand_id: i64) -> Result<AccountBrand> {
         // OneLogin API v2 returns direct object
-        self.client.get(&format!("/api/2/branding/brands/{}", brand_id)).await
+        self.client.get(&ApiVersion::V2.path(&format!("branding/brands/{}", brand_id))).await
     }
 
     #[instrument(skip(self, request))]
     pub async fn create_account_brand(&self, request: CreateBrandRequest) -> Result<AccountBrand> {
         // OneLogin API v2 returns direct object
-        self.client.post("/api/2/branding/brands", Some(&request)).await
+        self.client.post(&ApiVersion::V2.path("branding/brands"), Some(&request)).await
     }
 
     #[instrument(skip(self, request))]
@@ -41,19 +42,23 @@ impl BrandingApi {
         request: UpdateBrandRequest,
     ) -> Result<AccountBrand> {
         // OneLogin API v2 returns direct object
-        self.client.put(&format!("/api/2/branding/brands/{}", brand_id), Some(&request)).await
+        self.client.put(&ApiVersion::V2.path(&format!("branding/brands/{}", brand_id)), Some(&request)).await
     }
 
     #[instrument(skip(self))]
     pub async fn delete_account_brand(&self, brand_id: i64) -> Result<()> {
-        self.client.delete(&format!("/api/2/branding/brands/{}", brand_id)).await
+        self.client.delete_no_content(&ApiVersion::V2.path(&format!("branding/brands/{}", brand_id))).await
     }
 
     // Legacy methods for backward compatibility
+    /// Brands with their texts in `locale` where the account has translations
     #[instrument(skip(self))]
-    pub async fn get_branding_settings(&self) -> Result<BrandingSettings> {
+    pub async fn get_branding_settings(&self, locale: Option<&str>) -> Result<BrandingSettings> {
         // Map to list_account_brands for backward compatibility
-        let brands = self.list_account_brands().await?;
+        if let Some(locale) = locale {
+            validate_locale(locale).map_err(OneLoginError::InvalidInput)?;
+        }
+        let brands = self.client.get_localized(&ApiVersion::V2.path("branding/brands"), locale).await?;
         Ok(BrandingSettings { brands })
     }
 
@@ -70,27 +75,57 @@ impl BrandingApi {
 
     // ==================== MESSAGE TEMPLATES ====================
 
-    /// List all message templates for a brand
+    /// List all message templates for a brand, or only those in `locale`
     #[instrument(skip(self))]
-    pub async fn list_message_templates(&self, brand_id: i64) -> Result<Vec<MessageTemplate>> {
-        self.client
-            .get(&format!("/api/2/branding/brands/{}/templates", brand_id))
-            .await
+    pub async fn list_message_templates(&self, brand_id: i64, locale: Option<&str>) -> Result<Vec<MessageTemplate>> {
+        if let Some(locale) = locale {
+            validate_locale(locale).map_err(OneLoginError::InvalidInput)?;
+        }
+        let templates: Vec<MessageTemplate> = self.client
+            .get_localized(&ApiVersion::V2.path(&format!("branding/brands/{}/templates", brand_id)), locale)
+            .await?;
+        // Templates without a locale apply to every language
+        Ok(templates
+            .into_iter()
+            .filter(|t| match (locale, t.locale.as_deref()) {
+                (Some(wanted), Some(actual)) => same_locale(wanted, actual),
+                _ => true,
+            })
+            .collect())
     }
 
     /// Get a specific message template by ID
     #[instrument(skip(self))]
-    pub async fn get_message_template(&self, brand_id: i64, template_id: i64) -> Result<MessageTemplate> {
+    pub async fn get_message_template(
+        &self,
+        brand_id: i64,
+        template_id: i64,
+        locale: Option<&str>,
+    ) -> Result<MessageTemplate> {
+        if let Some(locale) = locale {
+            validate_locale(locale).map_err(OneLoginError::InvalidInput)?;
+        }
         self.client
-            .get(&format!("/api/2/branding/brands/{}/templates/{}", brand_id, template_id))
+            .get_localized(
+                &ApiVersion::V2.path(&format!("branding/brands/{}/templates/{}", brand_id, template_id)),
+                locale,
+            )
             .await
     }
 
-    /// Get a message template by type
+    /// Get a message template by type, in `locale` when given
     #[instrument(skip(self))]
-    pub async fn get_template_by_type(&self, brand_id: i64, template_type: &str) -> Result<MessageTemplate> {
+    pub async fn get_template_by_type(
+        &self,
+        brand_id: i64,
+        template_type: &str,
+        locale: Option<&str>,
+    ) -> Result<MessageTemplate> {
+        if let Some(locale) = locale {
+            return self.get_template_by_locale(brand_id, template_type, locale).await;
+        }
         self.client
-            .get(&format!("/api/2/branding/brands/{}/templates/{}", brand_id, template_type))
+            .get(&ApiVersion::V2.path(&format!("branding/brands/{}/templates/{}", brand_id, template_type)))
             .await
     }
 
@@ -102,11 +137,12 @@ impl BrandingApi {
         template_type: &str,
         locale: &str,
     ) -> Result<MessageTemplate> {
+        validate_locale(locale).map_err(OneLoginError::InvalidInput)?;
         self.client
-            .get(&format!(
-                "/api/2/branding/brands/{}/templates/{}/{}",
+            .get_localized(&ApiVersion::V2.path(&format!(
+                "branding/brands/{}/templates/{}/{}",
                 brand_id, template_type, locale
-            ))
+            )), Some(locale))
             .await
     }
 
@@ -117,8 +153,9 @@ impl BrandingApi {
         brand_id: i64,
         request: CreateMessageTemplateRequest,
     ) -> Result<MessageTemplate> {
+        request.validate().map_err(OneLoginError::InvalidInput)?;
         self.client
-            .post(&format!("/api/2/branding/brands/{}/templates", brand_id), Some(&request))
+            .post(&ApiVersion::V2.path(&format!("branding/brands/{}/templates", brand_id)), Some(&request))
             .await
     }
 
@@ -130,9 +167,10 @@ impl BrandingApi {
         template_id: i64,
         request: UpdateMessageTemplateRequest,
     ) -> Result<MessageTemplate> {
+        request.validate().map_err(OneLoginError::InvalidInput)?;
         self.client
             .put(
-                &format!("/api/2/branding/brands/{}/templates/{}", brand_id, template_id),
+                &ApiVersion::V2.path(&format!("branding/brands/{}/templates/{}", brand_id, template_id)),
                 Some(&request),
             )
             .await
@@ -147,12 +185,14 @@ impl BrandingApi {
         locale: &str,
         request: UpdateMessageTemplateRequest,
     ) -> Result<MessageTemplate> {
+        validate_locale(locale).map_err(OneLoginError::InvalidInput)?;
+        request.validate().map_err(OneLoginError::InvalidInput)?;
         self.client
             .put(
-                &format!(
-                    "/api/2/branding/brands/{}/templates/{}/{}",
+                &ApiVersion::V2.path(&format!(
+                    "branding/brands/{}/templates/{}/{}",
                     brand_id, template_type, locale
-                ),
+                )),
                 Some(&request),
             )
             .await
@@ -162,7 +202,12 @@ impl BrandingApi {
     #[instrument(skip(self))]
     pub async fn delete_message_template(&self, brand_id: i64, template_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/branding/brands/{}/templates/{}", brand_id, template_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("branding/brands/{}/templates/{}", brand_id, template_id)))
             .await
     }
 }
+
+/// Whether two locale codes name the same locale, ignoring case and `-` vs `_`
+fn same_locale(a: &str, b: &str) -> bool {
+    a.replace('_', "-").eq_ignore_ascii_case(&b.replace('_', "-"))
+}
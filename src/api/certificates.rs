@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::certificates::*;
 use std::sync::Arc;
@@ -19,14 +20,14 @@ impl CertificatesApi {
     /// List all certificates
     #[instrument(skip(self))]
     pub async fn list_certificates(&self) -> Result<Vec<Certificate>> {
-        self.client.get("/api/2/certificates").await
+        self.client.get(&ApiVersion::V2.path("certificates")).await
     }
 
     /// Get a specific certificate by ID
     #[instrument(skip(self))]
     pub async fn get_certificate(&self, cert_id: i64) -> Result<Certificate> {
         self.client
-            .get(&format!("/api/2/certificates/{}", cert_id))
+            .get(&ApiVersion::V2.path(&format!("certificates/{}", cert_id)))
             .await
     }
 
@@ -37,7 +38,7 @@ impl CertificatesApi {
         request: GenerateCertificateRequest,
     ) -> Result<Certificate> {
         self.client
-            .post("/api/2/certificates", Some(&request))
+            .post(&ApiVersion::V2.path("certificates"), Some(&request))
             .await
     }
 
@@ -46,7 +47,7 @@ impl CertificatesApi {
     pub async fn renew_certificate(&self, cert_id: i64) -> Result<Certificate> {
         self.client
             .put(
-                &format!("/api/2/certificates/{}/renew", cert_id),
+                &ApiVersion::V2.path(&format!("certificates/{}/renew", cert_id)),
                 None::<&()>,
             )
             .await
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::connectors::*;
 use std::sync::Arc;
@@ -17,14 +18,14 @@ impl ConnectorsApi {
 
     #[instrument(skip(self))]
     pub async fn list_connectors(&self) -> Result<Vec<Connector>> {
-        self.client.get("/api/2/connectors").await
+        self.client.get(&ApiVersion::V2.path("connectors")).await
     }
 
     /// Get a specific connector by ID
     #[instrument(skip(self))]
     pub async fn get_connector(&self, connector_id: i64) -> Result<Connector> {
         self.client
-            .get(&format!("/api/2/connectors/{}", connector_id))
+            .get(&ApiVersion::V2.path(&format!("connectors/{}", connector_id)))
             .await
     }
 }
@@ -1,10 +1,15 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
-use crate::core::error::Result;
+use crate::core::config::ApiVersion;
+use crate::core::error::{OneLoginError, Result};
 use crate::models::custom_attributes::*;
+use crate::utils::suggest;
 use std::sync::Arc;
 use tracing::instrument;
 
+/// Cache key of the attribute catalog; each tenant has its own cache
+const CATALOG_KEY: &str = "custom_attributes:all";
+
 pub struct CustomAttributesApi {
     client: Arc<HttpClient>,
     cache: Arc<CacheManager>,
@@ -15,10 +20,49 @@ impl CustomAttributesApi {
         Self { client, cache }
     }
 
+    /// The attribute catalog, cached for the cache TTL and refreshed on changes
     #[instrument(skip(self))]
     pub async fn list_custom_attributes(&self) -> Result<Vec<CustomAttribute>> {
+        if let Some(attributes) = self.cache.get(CATALOG_KEY).await {
+            return Ok(attributes);
+        }
         // OneLogin API v2 returns custom attributes as a direct array (no wrapper)
-        self.client.get("/api/2/users/custom_attributes").await
+        let attributes: Vec<CustomAttribute> =
+            self.client.get(&ApiVersion::V2.path("users/custom_attributes")).await?;
+        self.cache.set(CATALOG_KEY.to_string(), &attributes).await;
+        Ok(attributes)
+    }
+
+    /// Fail with suggestions when any shortname is not defined in the tenant
+    #[instrument(skip(self))]
+    pub async fn validate_shortnames(&self, shortnames: &[&str]) -> Result<()> {
+        if shortnames.is_empty() {
+            return Ok(());
+        }
+        // Read without get() so a validation cache hit doesn't mark the caller's result as cached
+        let catalog = match self.cache.get_with_age::<Vec<CustomAttribute>>(CATALOG_KEY).await {
+            Some((catalog, _)) => catalog,
+            None => self.list_custom_attributes().await?,
+        };
+        let unknown: Vec<String> = shortnames
+            .iter()
+            .filter(|name| !catalog.iter().any(|a| a.shortname == **name))
+            .map(|name| {
+                let suggestions = suggest::closest(name, catalog.iter().map(|a| a.shortname.as_str()));
+                if suggestions.is_empty() {
+                    format!("'{}'", name)
+                } else {
+                    format!("'{}' (did you mean {}?)", name, suggestions.join(", "))
+                }
+            })
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        Err(OneLoginError::InvalidInput(format!(
+            "Unknown custom attribute shortname {}. Defined shortnames come from onelogin_list_custom_attributes.",
+            unknown.join(", ")
+        )))
     }
 
     #[instrument(skip(self, request))]
@@ -27,7 +71,9 @@ impl CustomAttributesApi {
         request: CreateCustomAttributeRequest,
     ) -> Result<CustomAttribute> {
         // OneLogin API v2 returns direct object
-        self.client.post("/api/2/users/custom_attributes", Some(&request)).await
+        let attribute = self.client.post(&ApiVersion::V2.path("users/custom_attributes"), Some(&request)).await?;
+        self.cache.invalidate(CATALOG_KEY).await;
+        Ok(attribute)
     }
 
     #[instrument(skip(self, request))]
@@ -37,19 +83,24 @@ impl CustomAttributesApi {
         request: UpdateCustomAttributeRequest,
     ) -> Result<CustomAttribute> {
         // OneLogin API v2 returns direct object
-        self.client
+        let attribute = self
+            .client
             .put(
-                &format!("/api/2/users/custom_attributes/{}", attribute_id),
+                &ApiVersion::V2.path(&format!("users/custom_attributes/{}", attribute_id)),
                 Some(&request),
             )
-            .await
+            .await?;
+        self.cache.invalidate(CATALOG_KEY).await;
+        Ok(attribute)
     }
 
     #[instrument(skip(self))]
     pub async fn delete_custom_attribute(&self, attribute_id: i64) -> Result<()> {
         // OneLogin API v2
         self.client
-            .delete(&format!("/api/2/users/custom_attributes/{}", attribute_id))
-            .await
+            .delete_no_content(&ApiVersion::V2.path(&format!("users/custom_attributes/{}", attribute_id)))
+            .await?;
+        self.cache.invalidate(CATALOG_KEY).await;
+        Ok(())
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::device_trust::*;
 use std::sync::Arc;
@@ -19,7 +20,7 @@ impl DeviceTrustApi {
     /// List all trusted devices
     #[instrument(skip(self))]
     pub async fn list_devices(&self, query: DeviceQuery) -> Result<Vec<Device>> {
-        let mut path = "/api/2/devices".to_string();
+        let mut path = ApiVersion::V2.path("devices");
         let mut params = vec![];
 
         if let Some(user_id) = query.user_id {
@@ -46,14 +47,14 @@ impl DeviceTrustApi {
     #[instrument(skip(self))]
     pub async fn get_device(&self, device_id: &str) -> Result<Device> {
         self.client
-            .get(&format!("/api/2/devices/{}", device_id))
+            .get(&ApiVersion::V2.path(&format!("devices/{}", device_id)))
             .await
     }
 
     /// Register a new trusted device
     #[instrument(skip(self, request))]
     pub async fn register_device(&self, request: RegisterDeviceRequest) -> Result<Device> {
-        self.client.post("/api/2/devices", Some(&request)).await
+        self.client.post(&ApiVersion::V2.path("devices"), Some(&request)).await
     }
 
     /// Update a trusted device
@@ -64,7 +65,7 @@ impl DeviceTrustApi {
         request: UpdateDeviceRequest,
     ) -> Result<Device> {
         self.client
-            .put(&format!("/api/2/devices/{}", device_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("devices/{}", device_id)), Some(&request))
             .await
     }
 
@@ -72,7 +73,7 @@ impl DeviceTrustApi {
     #[instrument(skip(self))]
     pub async fn delete_device(&self, device_id: &str) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/devices/{}", device_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("devices/{}", device_id)))
             .await
     }
 }
@@ -0,0 +1,26 @@
+use crate::core::cache::CacheManager;
+use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
+use crate::core::error::Result;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::instrument;
+
+/// Raw API access for diagnostics that need the response as OneLogin sent it
+pub struct DiagnosticsApi {
+    client: Arc<HttpClient>,
+    #[allow(dead_code)]
+    cache: Arc<CacheManager>,
+}
+
+impl DiagnosticsApi {
+    pub fn new(client: Arc<HttpClient>, cache: Arc<CacheManager>) -> Self {
+        Self { client, cache }
+    }
+
+    /// GET `path` under `version` without deserializing into a model
+    #[instrument(skip(self))]
+    pub async fn get_raw(&self, version: ApiVersion, path: &str) -> Result<Value> {
+        self.client.get(&version.path(path)).await
+    }
+}
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::directories::*;
 use std::sync::Arc;
@@ -17,13 +18,13 @@ impl DirectoriesApi {
 
     #[instrument(skip(self))]
     pub async fn list_connectors(&self) -> Result<Vec<DirectoryConnector>> {
-        self.client.get("/directories").await
+        self.client.get(&ApiVersion::V2.path("directories")).await
     }
 
     #[instrument(skip(self))]
     pub async fn get_connector(&self, connector_id: &str) -> Result<DirectoryConnector> {
         self.client
-            .get(&format!("/directories/{}", connector_id))
+            .get(&ApiVersion::V2.path(&format!("directories/{}", connector_id)))
             .await
     }
 
@@ -32,7 +33,7 @@ impl DirectoriesApi {
         &self,
         request: CreateDirectoryConnectorRequest,
     ) -> Result<DirectoryConnector> {
-        self.client.post("/directories", Some(&request)).await
+        self.client.post(&ApiVersion::V2.path("directories"), Some(&request)).await
     }
 
     #[instrument(skip(self, request))]
@@ -42,28 +43,28 @@ impl DirectoriesApi {
         request: UpdateDirectoryConnectorRequest,
     ) -> Result<DirectoryConnector> {
         self.client
-            .put(&format!("/directories/{}", connector_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("directories/{}", connector_id)), Some(&request))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn delete_connector(&self, connector_id: &str) -> Result<()> {
         self.client
-            .delete(&format!("/directories/{}", connector_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("directories/{}", connector_id)))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn sync_directory(&self, connector_id: &str) -> Result<SyncStatus> {
         self.client
-            .post(&format!("/directories/{}/sync", connector_id), None::<&()>)
+            .post(&ApiVersion::V2.path(&format!("directories/{}/sync", connector_id)), None::<&()>)
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn get_sync_status(&self, connector_id: &str) -> Result<SyncStatus> {
         self.client
-            .get(&format!("/directories/{}/sync/status", connector_id))
+            .get(&ApiVersion::V2.path(&format!("directories/{}/sync/status", connector_id)))
             .await
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::embed_tokens::*;
 use std::sync::Arc;
@@ -21,12 +22,23 @@ impl EmbedTokensApi {
         request: GenerateEmbedTokenRequest,
     ) -> Result<EmbedToken> {
         // Note: embed_token uses API v1
-        self.client.post("/api/1/embed_token", Some(&request)).await
+        self.client.post(&ApiVersion::V1.path("embed_token"), Some(&request)).await
     }
 
     #[instrument(skip(self))]
     pub async fn list_embeddable_apps(&self) -> Result<Vec<EmbeddableApp>> {
         // Note: embed/apps uses API v2
-        self.client.get("/api/2/embed/apps").await
+        self.client.get(&ApiVersion::V2.path("embed/apps")).await
+    }
+
+    /// URL that launches `app_id` in the user's browser with an embed token, signing the
+    /// user in without the portal. Nothing is sent to OneLogin here.
+    pub fn launch_url(&self, app_id: i64, token: &str) -> String {
+        format!(
+            "{}/launch/{}?embed_token={}",
+            self.client.config().tenant_base_url(),
+            app_id,
+            urlencoding::encode(token)
+        )
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::events::*;
 use crate::models::users::LockUserResponse;
@@ -19,36 +20,68 @@ impl EventsApi {
 
     #[instrument(skip(self))]
     pub async fn list_events(&self, params: Option<EventQueryParams>) -> Result<Vec<Event>> {
-        let mut path = "/api/1/events".to_string();
-        if let Some(p) = params {
-            if let Ok(query) = serde_qs::to_string(&p) {
+        let (events, _) = self.list_events_page(&params.unwrap_or_default()).await?;
+        Ok(events)
+    }
+
+    /// One page of events and the cursor for the next page, if any
+    #[instrument(skip(self))]
+    pub async fn list_events_page(&self, params: &EventQueryParams) -> Result<(Vec<Event>, Option<String>)> {
+        let mut path = ApiVersion::V1.path("events");
+        if let Ok(query) = serde_qs::to_string(params) {
+            if !query.is_empty() {
                 path.push('?');
                 path.push_str(&query);
             }
         }
         // OneLogin API v1 returns events wrapped in a pagination envelope
         let response: PaginatedResponse<Event> = self.client.get(&path).await?;
-        Ok(response.data)
+        let next_cursor = response.pagination.after_cursor.filter(|c| !c.is_empty());
+        Ok((response.data, next_cursor))
+    }
+
+    /// Follow `after_cursor` from `params` for up to `max_pages` pages.
+    /// Returns the events, the number of pages fetched and the cursor to resume from.
+    #[instrument(skip(self))]
+    pub async fn list_events_paginated(
+        &self,
+        params: EventQueryParams,
+        max_pages: usize,
+    ) -> Result<(Vec<Event>, usize, Option<String>)> {
+        let mut params = params;
+        let mut events = Vec::new();
+        let mut pages = 0;
+        loop {
+            let (batch, next_cursor) = self.list_events_page(&params).await?;
+            pages += 1;
+            let empty = batch.is_empty();
+            events.extend(batch);
+            match next_cursor {
+                Some(cursor) if !empty && pages < max_pages => params.after_cursor = Some(cursor),
+                Some(cursor) if !empty => return Ok((events, pages, Some(cursor))),
+                _ => return Ok((events, pages, None)),
+            }
+        }
     }
 
     #[instrument(skip(self))]
     pub async fn get_event(&self, event_id: i64) -> Result<Event> {
         // OneLogin API v1 returns event wrapped in response envelope
-        let response: ApiResponse<Event> = self.client.get(&format!("/api/1/events/{}", event_id)).await?;
+        let response: ApiResponse<Event> = self.client.get(&ApiVersion::V1.path(&format!("events/{}", event_id))).await?;
         Ok(response.data)
     }
 
     #[instrument(skip(self, request))]
     pub async fn create_event(&self, request: CreateEventRequest) -> Result<()> {
         // OneLogin API v1 returns status response, not event data
-        let _response: LockUserResponse = self.client.post("/api/1/events", Some(&request)).await?;
+        let _response: LockUserResponse = self.client.post(&ApiVersion::V1.path("events"), Some(&request)).await?;
         Ok(())
     }
 
     #[instrument(skip(self))]
     pub async fn list_event_types(&self) -> Result<Vec<EventType>> {
         // OneLogin API v1 returns event types wrapped in response envelope
-        let response: ApiResponse<Vec<EventType>> = self.client.get("/api/1/events/types").await?;
+        let response: ApiResponse<Vec<EventType>> = self.client.get(&ApiVersion::V1.path("events/types")).await?;
         Ok(response.data)
     }
 }
@@ -1,10 +1,19 @@
+use crate::api::lookup::{self, Lookup, LookupKey};
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
-use crate::core::error::Result;
+use crate::core::config::ApiVersion;
+use crate::core::error::{OneLoginError, Result};
 use crate::models::groups::*;
 use crate::models::{ApiResponse, PaginatedResponse};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::instrument;
+use tracing::{instrument, warn};
+
+/// Cache key of the full group list used for client-side lookups
+const GROUPS_LIST_KEY: &str = "groups:all";
+
+/// Pages followed by `list_groups`; the v1 endpoint returns 50 groups per page
+const MAX_LIST_PAGES: usize = 200;
 
 pub struct GroupsApi {
     client: Arc<HttpClient>,
@@ -16,26 +25,120 @@ impl GroupsApi {
         Self { client, cache }
     }
 
+    /// Every group, following pagination cursors
     #[instrument(skip(self))]
     pub async fn list_groups(&self) -> Result<Vec<Group>> {
+        let (groups, _, next_cursor) = self.list_groups_paginated(None, MAX_LIST_PAGES).await?;
+        if next_cursor.is_some() {
+            warn!("Stopped listing groups after {} pages", MAX_LIST_PAGES);
+        }
+        Ok(groups)
+    }
+
+    /// One page of groups and the cursor for the next page, if any
+    #[instrument(skip(self))]
+    pub async fn list_groups_page(&self, after_cursor: Option<&str>) -> Result<(Vec<Group>, Option<String>)> {
+        let mut path = ApiVersion::V1.path("groups");
+        if let Some(cursor) = after_cursor {
+            path.push_str(&format!("?after_cursor={}", urlencoding::encode(cursor)));
+        }
         // OneLogin API v1 returns groups wrapped in a pagination envelope
-        let response: PaginatedResponse<Group> = self.client.get("/api/1/groups").await?;
-        Ok(response.data)
+        let response: PaginatedResponse<Group> = self.client.get(&path).await?;
+        let next_cursor = response.pagination.after_cursor.filter(|c| !c.is_empty());
+        Ok((response.data, next_cursor))
+    }
+
+    /// Follow cursors from `after_cursor` for up to `max_pages` pages.
+    /// Returns the groups, the number of pages fetched and the cursor to resume from.
+    #[instrument(skip(self))]
+    pub async fn list_groups_paginated(
+        &self,
+        after_cursor: Option<String>,
+        max_pages: usize,
+    ) -> Result<(Vec<Group>, usize, Option<String>)> {
+        let mut cursor = after_cursor;
+        let mut groups = Vec::new();
+        let mut pages = 0;
+        loop {
+            let (batch, next_cursor) = self.list_groups_page(cursor.as_deref()).await?;
+            pages += 1;
+            let empty = batch.is_empty();
+            groups.extend(batch);
+            match next_cursor {
+                Some(next) if !empty && pages < max_pages => cursor = Some(next),
+                Some(next) if !empty => return Ok((groups, pages, Some(next))),
+                _ => return Ok((groups, pages, None)),
+            }
+        }
     }
 
     #[instrument(skip(self))]
     pub async fn get_group(&self, group_id: i64) -> Result<Group> {
         // OneLogin API v1 returns group wrapped in response envelope with data as array
-        let response: ApiResponse<Vec<Group>> = self.client.get(&format!("/api/1/groups/{}", group_id)).await?;
+        let response: ApiResponse<Vec<Group>> = self.client.get(&ApiVersion::V1.path(&format!("groups/{}", group_id))).await?;
         response.data.into_iter().next().ok_or_else(|| {
             crate::core::error::OneLoginError::NotFound(format!("Group {} not found", group_id))
         })
     }
 
+    /// Get a group by id, or find groups by `name` or `reference`. The groups
+    /// endpoint has no filters, so those are matched against the cached list.
+    #[instrument(skip(self))]
+    pub async fn find_groups(&self, key: LookupKey<'_>) -> Result<Lookup<Group>> {
+        lookup::find(
+            &self.cache,
+            GROUPS_LIST_KEY,
+            key,
+            |id| self.get_group(id),
+            || self.list_groups(),
+            |group: &Group, field| match field {
+                "name" => Some(group.name.clone()),
+                "reference" => group.reference.clone(),
+                _ => None,
+            },
+        )
+        .await
+    }
+
+    /// Group names by id, from the cached group list
+    #[instrument(skip(self))]
+    pub async fn group_names(&self) -> Result<HashMap<i64, String>> {
+        // get_with_age: the names decorate fresh results, which shouldn't be reported as cached
+        let groups: Vec<Group> = match self.cache.get_with_age(GROUPS_LIST_KEY).await {
+            Some((groups, _)) => groups,
+            None => {
+                let groups = self.list_groups().await?;
+                self.cache.set(GROUPS_LIST_KEY.to_string(), &groups).await;
+                groups
+            }
+        };
+        Ok(groups.into_iter().map(|g| (g.id, g.name)).collect())
+    }
+
+    /// The group with exactly this name (case-insensitive), matched against the cached list.
+    /// Fails when no group or more than one group has the name.
+    #[instrument(skip(self))]
+    pub async fn get_group_by_name(&self, name: &str) -> Result<Group> {
+        let mut found = self
+            .find_groups(LookupKey::Filter { field: "name", pattern: name })
+            .await?
+            .items;
+        found.retain(|group| group.name.eq_ignore_ascii_case(name));
+        match found.len() {
+            0 => Err(OneLoginError::NotFound(format!("No group named '{}'", name))),
+            1 => Ok(found.remove(0)),
+            n => Err(OneLoginError::InvalidInput(format!(
+                "{} groups are named '{}'; use the group id instead",
+                n, name
+            ))),
+        }
+    }
+
     #[instrument(skip(self, request))]
     pub async fn create_group(&self, request: CreateGroupRequest) -> Result<Group> {
         // OneLogin API v1 returns group wrapped in response envelope
-        let response: ApiResponse<Group> = self.client.post("/api/1/groups", Some(&request)).await?;
+        let response: ApiResponse<Group> = self.client.post(&ApiVersion::V1.path("groups"), Some(&request)).await?;
+        self.cache.invalidate(GROUPS_LIST_KEY).await;
         Ok(response.data)
     }
 
@@ -43,13 +146,16 @@ impl GroupsApi {
     pub async fn update_group(&self, group_id: i64, request: UpdateGroupRequest) -> Result<Group> {
         // OneLogin API v1 returns group wrapped in response envelope
         let response: ApiResponse<Group> = self.client
-            .put(&format!("/api/1/groups/{}", group_id), Some(&request))
+            .put(&ApiVersion::V1.path(&format!("groups/{}", group_id)), Some(&request))
             .await?;
+        self.cache.invalidate(GROUPS_LIST_KEY).await;
         Ok(response.data)
     }
 
     #[instrument(skip(self))]
     pub async fn delete_group(&self, group_id: i64) -> Result<()> {
-        self.client.delete(&format!("/api/1/groups/{}", group_id)).await
+        self.client.delete_no_content(&ApiVersion::V1.path(&format!("groups/{}", group_id))).await?;
+        self.cache.invalidate(GROUPS_LIST_KEY).await;
+        Ok(())
     }
 }
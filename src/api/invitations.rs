@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::{OneLoginError, Result};
 use crate::models::invitations::*;
 use crate::models::{ApiResponse, StatusOnlyResponse};
@@ -25,7 +26,7 @@ impl InvitationsApi {
         // Response format: {"data":["https://...reset?token=..."],"status":{...}}
         let response: ApiResponse<Vec<String>> = self
             .client
-            .post("/api/1/invites/get_invite_link", Some(&request))
+            .post(&ApiVersion::V1.path("invites/get_invite_link"), Some(&request))
             .await?;
 
         let link = response.data.into_iter().next().ok_or_else(|| {
@@ -46,7 +47,7 @@ impl InvitationsApi {
         // Response format: {"status":{"type":"success","code":200,"message":"Email sent successfully to ...","error":false}}
         let response: StatusOnlyResponse = self
             .client
-            .post("/api/1/invites/send_invite_link", Some(&request))
+            .post(&ApiVersion::V1.path("invites/send_invite_link"), Some(&request))
             .await?;
 
         let message = response
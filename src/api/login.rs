@@ -1,10 +1,14 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::login::*;
 use std::sync::Arc;
 use tracing::instrument;
 
+/// Tenant-domain endpoint exchanging a session token for a browser session
+const SESSION_PATH: &str = "session_via_api_token";
+
 pub struct LoginApi {
     client: Arc<HttpClient>,
     #[allow(dead_code)]
@@ -24,7 +28,7 @@ impl LoginApi {
         request: SessionLoginRequest,
     ) -> Result<SessionLoginResponse> {
         self.client
-            .post("/api/1/login/auth", Some(&request))
+            .post(&ApiVersion::V1.path("login/auth"), Some(&request))
             .await
     }
 
@@ -35,16 +39,36 @@ impl LoginApi {
         request: VerifyFactorLoginRequest,
     ) -> Result<SessionLoginResponse> {
         self.client
-            .post("/api/1/login/verify_factor", Some(&request))
+            .post(&ApiVersion::V1.path("login/verify_factor"), Some(&request))
             .await
     }
 
-    /// Create a browser session from a session token
-    /// Note: This endpoint is on the main domain, not the API domain
-    #[instrument(skip(self, request))]
-    pub async fn create_session(&self, request: CreateSessionRequest) -> Result<serde_json::Value> {
-        self.client
-            .post("/session_via_api_token", Some(&request))
-            .await
+    /// Auto-submitting HTML form that posts the session token from the user's browser.
+    /// The session cookies OneLogin sets then belong to the browser; the server never
+    /// sends the single-use token itself, since it could not hand the session on.
+    pub fn session_form_html(&self, session_token: &str) -> String {
+        session_form_html(&self.session_url(), session_token)
+    }
+
+    fn session_url(&self) -> String {
+        format!("{}/{}", self.client.config().tenant_base_url(), SESSION_PATH)
     }
 }
+
+fn session_form_html(action_url: &str, session_token: &str) -> String {
+    format!(
+        "<form id=\"onelogin-session\" method=\"POST\" action=\"{}\">\
+         <input type=\"hidden\" name=\"session_token\" value=\"{}\"/></form>\
+         <script>document.getElementById('onelogin-session').submit();</script>",
+        html_escape(action_url),
+        html_escape(session_token)
+    )
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
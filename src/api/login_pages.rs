@@ -1,6 +1,7 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
-use crate::core::error::Result;
+use crate::core::config::ApiVersion;
+use crate::core::error::{OneLoginError, Result};
 use crate::models::login_pages::*;
 use std::sync::Arc;
 use tracing::instrument;
@@ -19,22 +20,23 @@ impl LoginPagesApi {
     /// List all custom login pages
     #[instrument(skip(self))]
     pub async fn list_login_pages(&self) -> Result<Vec<LoginPage>> {
-        self.client.get("/api/2/login_pages").await
+        self.client.get(&ApiVersion::V2.path("login_pages")).await
     }
 
     /// Get a specific login page by ID
     #[instrument(skip(self))]
     pub async fn get_login_page(&self, page_id: i64) -> Result<LoginPage> {
         self.client
-            .get(&format!("/api/2/login_pages/{}", page_id))
+            .get(&ApiVersion::V2.path(&format!("login_pages/{}", page_id)))
             .await
     }
 
     /// Create a new custom login page
     #[instrument(skip(self, request))]
     pub async fn create_login_page(&self, request: CreateLoginPageRequest) -> Result<LoginPage> {
+        request.validate().map_err(OneLoginError::InvalidInput)?;
         self.client
-            .post("/api/2/login_pages", Some(&request))
+            .post(&ApiVersion::V2.path("login_pages"), Some(&request))
             .await
     }
 
@@ -45,8 +47,9 @@ impl LoginPagesApi {
         page_id: i64,
         request: UpdateLoginPageRequest,
     ) -> Result<LoginPage> {
+        request.validate().map_err(OneLoginError::InvalidInput)?;
         self.client
-            .put(&format!("/api/2/login_pages/{}", page_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("login_pages/{}", page_id)), Some(&request))
             .await
     }
 
@@ -54,7 +57,23 @@ impl LoginPagesApi {
     #[instrument(skip(self))]
     pub async fn delete_login_page(&self, page_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/login_pages/{}", page_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("login_pages/{}", page_id)))
             .await
     }
+
+    /// Get the preview URL for a login page
+    #[instrument(skip(self))]
+    pub async fn get_login_page_preview_url(&self, page_id: i64) -> Result<LoginPagePreview> {
+        let page = self.get_login_page(page_id).await?;
+        match page.preview_url {
+            Some(url) if !url.is_empty() => Ok(LoginPagePreview {
+                page_id,
+                preview_url: Some(url),
+            }),
+            _ => Err(OneLoginError::NotFound(format!(
+                "Login page {} has no preview URL",
+                page_id
+            ))),
+        }
+    }
 }
@@ -0,0 +1,143 @@
+//! Lookup by id or by a field OneLogin cannot filter on server-side.
+//!
+//! Some endpoints (e.g. groups) have no query filters. `find` then fetches the
+//! full list once, caches it, and matches client-side; the returned `Lookup`
+//! carries a note saying so, which handlers pass on in their responses.
+
+use crate::core::cache::CacheManager;
+use crate::core::error::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+
+/// What to look up
+#[derive(Debug, Clone, Copy)]
+pub enum LookupKey<'a> {
+    /// A single resource, fetched directly
+    Id(i64),
+    /// Resources whose `field` matches `pattern` (case-insensitive, `*` wildcards)
+    Filter { field: &'a str, pattern: &'a str },
+}
+
+/// Matching resources, with a note when the match was made client-side
+#[derive(Debug, Clone, Serialize)]
+pub struct Lookup<T> {
+    pub items: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Fetch by id with `get`, or match the cached result of `list` with `field_value`.
+/// The list is cached under `list_key` for the cache TTL; mutations should invalidate it.
+pub async fn find<T, G, GF, L, LF, V>(
+    cache: &CacheManager,
+    list_key: &str,
+    key: LookupKey<'_>,
+    get: G,
+    list: L,
+    field_value: V,
+) -> Result<Lookup<T>>
+where
+    T: Serialize + DeserializeOwned,
+    G: FnOnce(i64) -> GF,
+    GF: Future<Output = Result<T>>,
+    L: FnOnce() -> LF,
+    LF: Future<Output = Result<Vec<T>>>,
+    V: Fn(&T, &str) -> Option<String>,
+{
+    let (field, pattern) = match key {
+        LookupKey::Id(id) => {
+            return Ok(Lookup {
+                items: vec![get(id).await?],
+                note: None,
+            })
+        }
+        LookupKey::Filter { field, pattern } => (field, pattern),
+    };
+
+    let (all, cached): (Vec<T>, bool) = match cache.get(list_key).await {
+        Some(all) => (all, true),
+        None => {
+            let all = list().await?;
+            cache.set(list_key.to_string(), &all).await;
+            (all, false)
+        }
+    };
+    let scanned = all.len();
+    let items: Vec<T> = all
+        .into_iter()
+        .filter(|item| field_value(item, field).is_some_and(|v| matches_pattern(&v, pattern)))
+        .collect();
+    Ok(Lookup {
+        items,
+        note: Some(format!(
+            "OneLogin has no server-side filter for {}; matched {} locally against {} {} resources",
+            field,
+            pattern,
+            scanned,
+            if cached { "cached" } else { "freshly listed" }
+        )),
+    })
+}
+
+/// Case-insensitive match where `*` matches any run of characters
+pub fn matches_pattern(value: &str, pattern: &str) -> bool {
+    let value = value.to_lowercase();
+    let pattern = pattern.trim().to_lowercase();
+    if !pattern.contains('*') {
+        return value == pattern;
+    }
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !value.starts_with(first) || value.len() < first.len() + last.len() || !value.ends_with(last) {
+        return false;
+    }
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("Engineering", "engineering"));
+        assert!(!matches_pattern("Engineering", "engineer"));
+        assert!(matches_pattern("Engineering", "engineer*"));
+        assert!(matches_pattern("EU Engineering", "*engineering"));
+        assert!(matches_pattern("EU Sales Team", "eu*team"));
+        assert!(matches_pattern("EU Sales Team", "*sales*"));
+        assert!(!matches_pattern("aba", "ab*ba"));
+    }
+
+    #[tokio::test]
+    async fn test_find_filters_cached_list() {
+        let cache = CacheManager::new(60, 100);
+        let names = || async { Ok(vec!["Admins".to_string(), "Sales EU".to_string(), "Sales US".to_string()]) };
+        let get = |_| async { Ok("unused".to_string()) };
+        let key = LookupKey::Filter { field: "name", pattern: "sales*" };
+
+        let found = find(&cache, "names", key, get, names, |n: &String, _| Some(n.clone())).await.unwrap();
+        assert_eq!(found.items, vec!["Sales EU", "Sales US"]);
+        assert!(found.note.unwrap().contains("freshly listed"));
+
+        // Second lookup is served from the cached list
+        let failing = || async { Err(crate::core::error::OneLoginError::Unknown("not called".to_string())) };
+        let found = find(&cache, "names", key, get, failing, |n: &String, _| Some(n.clone())).await.unwrap();
+        assert_eq!(found.items.len(), 2);
+        assert!(found.note.unwrap().contains("cached"));
+
+        let by_id = find(&cache, "names", LookupKey::Id(7), |id| async move { Ok(format!("#{}", id)) }, names, |n: &String, _| Some(n.clone()))
+            .await
+            .unwrap();
+        assert_eq!(by_id.items, vec!["#7"]);
+        assert!(by_id.note.is_none());
+    }
+}
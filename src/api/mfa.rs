@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::mfa::*;
 use std::sync::Arc;
@@ -20,7 +21,7 @@ impl MfaApi {
     pub async fn get_available_factors(&self, user_id: i64) -> Result<Vec<MfaFactor>> {
         // OneLogin API v2 returns direct array
         self.client
-            .get(&format!("/api/2/mfa/users/{}/factors", user_id))
+            .get(&ApiVersion::V2.path(&format!("mfa/users/{}/factors", user_id)))
             .await
     }
 
@@ -29,7 +30,7 @@ impl MfaApi {
     pub async fn list_factors(&self, user_id: i64) -> Result<Vec<MfaDevice>> {
         // OneLogin API v2 returns direct array
         self.client
-            .get(&format!("/api/2/mfa/users/{}/devices", user_id))
+            .get(&ApiVersion::V2.path(&format!("mfa/users/{}/devices", user_id)))
             .await
     }
 
@@ -43,7 +44,7 @@ impl MfaApi {
     ) -> Result<MfaDevice> {
         // OneLogin API v2 returns direct object
         self.client
-            .post(&format!("/api/2/mfa/users/{}/factors/{}", user_id, factor_id), Some(&request))
+            .post(&ApiVersion::V2.path(&format!("mfa/users/{}/factors/{}", user_id, factor_id)), Some(&request))
             .await
     }
 
@@ -57,7 +58,7 @@ impl MfaApi {
     ) -> Result<MfaVerificationResponse> {
         // OneLogin API v2 returns direct object
         self.client
-            .put(&format!("/api/2/mfa/users/{}/factors/{}/verify", user_id, factor_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("mfa/users/{}/factors/{}/verify", user_id, factor_id)), Some(&request))
             .await
     }
 
@@ -71,7 +72,7 @@ impl MfaApi {
     ) -> Result<MfaVerificationResponse> {
         // OneLogin API v2 returns direct object
         self.client
-            .post(&format!("/api/2/mfa/users/{}/devices/{}", user_id, device_id), request.as_ref())
+            .post(&ApiVersion::V2.path(&format!("mfa/users/{}/devices/{}", user_id, device_id)), request.as_ref())
             .await
     }
 
@@ -85,7 +86,7 @@ impl MfaApi {
     ) -> Result<MfaVerificationResponse> {
         // OneLogin API v2 returns direct object
         self.client
-            .put(&format!("/api/2/mfa/users/{}/devices/{}/verify", user_id, device_id), Some(&verification))
+            .put(&ApiVersion::V2.path(&format!("mfa/users/{}/devices/{}/verify", user_id, device_id)), Some(&verification))
             .await
     }
 
@@ -93,7 +94,7 @@ impl MfaApi {
     #[instrument(skip(self))]
     pub async fn remove_factor(&self, user_id: i64, device_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/mfa/users/{}/devices/{}", user_id, device_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("mfa/users/{}/devices/{}", user_id, device_id)))
             .await
     }
 
@@ -106,7 +107,7 @@ impl MfaApi {
         request: GenerateMfaTokenRequest,
     ) -> Result<MfaToken> {
         self.client
-            .post(&format!("/api/2/mfa/users/{}/mfa_token", user_id), Some(&request))
+            .post(&ApiVersion::V2.path(&format!("mfa/users/{}/mfa_token", user_id)), Some(&request))
             .await
     }
 
@@ -119,7 +120,7 @@ impl MfaApi {
         request: VerifyMfaTokenRequest,
     ) -> Result<VerifyMfaTokenResponse> {
         self.client
-            .post(&format!("/api/2/mfa/users/{}/mfa_token/verify", user_id), Some(&request))
+            .post(&ApiVersion::V2.path(&format!("mfa/users/{}/mfa_token/verify", user_id)), Some(&request))
             .await
     }
 }
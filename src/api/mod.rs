@@ -19,6 +19,8 @@ pub mod custom_attributes;
 #[allow(dead_code)]
 pub mod device_trust;
 #[allow(dead_code)]
+pub mod diagnostics;
+#[allow(dead_code)]
 pub mod directories;
 #[allow(dead_code)]
 pub mod embed_tokens;
@@ -30,6 +32,7 @@ pub mod groups;
 pub mod invitations;
 #[allow(dead_code)]
 pub mod login;
+pub mod lookup;
 #[allow(dead_code)]
 pub mod login_pages;
 #[allow(dead_code)]
@@ -108,6 +111,9 @@ pub struct OneLoginClient {
     pub device_trust: device_trust::DeviceTrustApi,
     pub login_pages: login_pages::LoginPagesApi,
     pub trusted_idps: trusted_idps::TrustedIdpsApi,
+    pub diagnostics: diagnostics::DiagnosticsApi,
+    /// Response cache shared by the API modules above
+    pub cache: Arc<CacheManager>,
 }
 
 impl OneLoginClient {
@@ -150,6 +156,8 @@ impl OneLoginClient {
             device_trust: device_trust::DeviceTrustApi::new(http_client.clone(), cache.clone()),
             login_pages: login_pages::LoginPagesApi::new(http_client.clone(), cache.clone()),
             trusted_idps: trusted_idps::TrustedIdpsApi::new(http_client.clone(), cache.clone()),
+            diagnostics: diagnostics::DiagnosticsApi::new(http_client.clone(), cache.clone()),
+            cache,
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::oauth::*;
 use std::sync::Arc;
@@ -18,14 +19,14 @@ impl OAuthApi {
     #[instrument(skip(self, request))]
     pub async fn generate_tokens(&self, request: TokenRequest) -> Result<TokenResponse> {
         self.client
-            .post("/auth/oauth2/v2/token", Some(&request))
+            .post(&ApiVersion::Root.path("auth/oauth2/v2/token"), Some(&request))
             .await
     }
 
     #[instrument(skip(self, request))]
     pub async fn revoke_token(&self, request: RevokeTokenRequest) -> Result<()> {
         self.client
-            .post("/auth/oauth2/revoke", Some(&request))
+            .post_no_content(&ApiVersion::Root.path("auth/oauth2/revoke"), Some(&request))
             .await
     }
 
@@ -35,7 +36,7 @@ impl OAuthApi {
         request: IntrospectTokenRequest,
     ) -> Result<crate::models::oidc::TokenIntrospection> {
         self.client
-            .post("/auth/oauth2/introspect", Some(&request))
+            .post(&ApiVersion::Root.path("auth/oauth2/introspect"), Some(&request))
             .await
     }
 }
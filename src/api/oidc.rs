@@ -1,34 +1,84 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::oidc::*;
-use std::sync::Arc;
-use tracing::instrument;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument};
+
+/// Shortest time between JWKS refetches caused by an unknown `kid`, so tokens with
+/// made-up key ids can't force a request each
+const JWKS_REFETCH_INTERVAL: Duration = Duration::from_secs(30);
 
 pub struct OidcApi {
     client: Arc<HttpClient>,
     cache: Arc<CacheManager>,
+    /// Last JWKS refetch triggered by an unknown `kid`
+    jwks_refetched_at: Mutex<Option<Instant>>,
 }
 
 impl OidcApi {
     pub fn new(client: Arc<HttpClient>, cache: Arc<CacheManager>) -> Self {
-        Self { client, cache }
+        Self {
+            client,
+            cache,
+            jwks_refetched_at: Mutex::new(None),
+        }
     }
 
     #[instrument(skip(self))]
     pub async fn get_well_known_configuration(&self) -> Result<OidcConfiguration> {
         // OneLogin OIDC well-known is under /oidc/2 path
-        self.client.get("/oidc/2/.well-known/openid-configuration").await
+        self.client.get(&ApiVersion::Root.path("oidc/2/.well-known/openid-configuration")).await
+    }
+
+    /// Discovery document from the cache; it rarely changes, so only the cache TTL
+    /// (or a forced refresh) fetches it again
+    #[instrument(skip(self))]
+    pub async fn get_well_known_configuration_cached(&self) -> Result<OidcConfiguration> {
+        let cache_key = CacheManager::build_key("oidc", &["well_known"]);
+        if let Some(config) = self.cache.get::<OidcConfiguration>(&cache_key).await {
+            return Ok(config);
+        }
+        let config = self.get_well_known_configuration().await?;
+        self.cache.set(cache_key, &config).await;
+        Ok(config)
     }
 
     #[instrument(skip(self))]
     pub async fn get_jwks(&self) -> Result<Jwks> {
-        self.client.get("/oidc/2/certs").await
+        self.client.get(&ApiVersion::Root.path("oidc/2/certs")).await
+    }
+
+    /// JWKS from the cache, refetched when `kid` is missing from the cached keys
+    /// (the tenant rotated its signing key). Such refetches happen at most once per
+    /// `JWKS_REFETCH_INTERVAL`; in between the cached keys are returned and the caller's
+    /// key lookup fails.
+    #[instrument(skip(self))]
+    pub async fn get_jwks_cached(&self, kid: Option<&str>) -> Result<Jwks> {
+        let cache_key = CacheManager::build_key("oidc", &["jwks"]);
+        if let Some(jwks) = self.cache.get::<Jwks>(&cache_key).await {
+            let Some(kid) = kid.filter(|kid| !jwks.keys.iter().any(|k| k.kid == *kid)) else {
+                return Ok(jwks);
+            };
+            let mut refetched_at = self.jwks_refetched_at.lock().expect("Mutex poisoned");
+            if refetched_at.is_some_and(|at| at.elapsed() < JWKS_REFETCH_INTERVAL) {
+                debug!("Unknown kid {}, but the JWKS was refetched recently; using the cached keys", kid);
+                return Ok(jwks);
+            }
+            debug!("Unknown kid {}, refetching the JWKS", kid);
+            *refetched_at = Some(Instant::now());
+        }
+
+        let jwks = self.get_jwks().await?;
+        self.cache.set(cache_key, &jwks).await;
+        Ok(jwks)
     }
 
     #[instrument(skip(self))]
     pub async fn get_userinfo(&self, access_token: &str) -> Result<UserInfo> {
         // UserInfo requires bearer token
-        self.client.get("/oidc/2/me").await
+        self.client.get(&ApiVersion::Root.path("oidc/2/me")).await
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::password_policies::*;
 use std::sync::Arc;
@@ -19,14 +20,14 @@ impl PasswordPoliciesApi {
     /// List all password policies
     #[instrument(skip(self))]
     pub async fn list_password_policies(&self) -> Result<Vec<PasswordPolicy>> {
-        self.client.get("/api/2/password_policies").await
+        self.client.get(&ApiVersion::V2.path("password_policies")).await
     }
 
     /// Get a specific password policy by ID
     #[instrument(skip(self))]
     pub async fn get_password_policy(&self, policy_id: i64) -> Result<PasswordPolicy> {
         self.client
-            .get(&format!("/api/2/password_policies/{}", policy_id))
+            .get(&ApiVersion::V2.path(&format!("password_policies/{}", policy_id)))
             .await
     }
 
@@ -37,7 +38,7 @@ impl PasswordPoliciesApi {
         request: CreatePasswordPolicyRequest,
     ) -> Result<PasswordPolicy> {
         self.client
-            .post("/api/2/password_policies", Some(&request))
+            .post(&ApiVersion::V2.path("password_policies"), Some(&request))
             .await
     }
 
@@ -50,9 +51,30 @@ impl PasswordPoliciesApi {
     ) -> Result<PasswordPolicy> {
         self.client
             .put(
-                &format!("/api/2/password_policies/{}", policy_id),
+                &ApiVersion::V2.path(&format!("password_policies/{}", policy_id)),
                 Some(&request),
             )
             .await
     }
+
+    /// Delete a password policy
+    #[instrument(skip(self))]
+    pub async fn delete_password_policy(&self, policy_id: i64) -> Result<()> {
+        self.client
+            .delete_no_content(&ApiVersion::V2.path(&format!("password_policies/{}", policy_id)))
+            .await
+    }
+
+    /// Assign a password policy to a user
+    #[instrument(skip(self))]
+    pub async fn assign_password_policy_to_user(
+        &self,
+        user_id: i64,
+        policy_id: i64,
+    ) -> Result<serde_json::Value> {
+        let request = AssignPasswordPolicyRequest { policy_id };
+        self.client
+            .put(&ApiVersion::V2.path(&format!("users/{}", user_id)), Some(&request))
+            .await
+    }
 }
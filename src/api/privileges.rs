@@ -17,6 +17,7 @@
 
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::privileges::*;
 use std::sync::Arc;
@@ -34,7 +35,7 @@ impl PrivilegesApi {
 
     #[instrument(skip(self))]
     pub async fn list_privileges(&self) -> Result<Vec<Privilege>> {
-        self.client.get("/api/1/privileges").await
+        self.client.get(&ApiVersion::V1.path("privileges")).await
     }
 
     #[instrument(skip(self))]
@@ -47,7 +48,7 @@ impl PrivilegesApi {
 
         let privilege: Privilege = self
             .client
-            .get(&format!("/api/1/privileges/{}", privilege_id))
+            .get(&ApiVersion::V1.path(&format!("privileges/{}", privilege_id)))
             .await?;
 
         self.cache.set(cache_key, &privilege).await;
@@ -57,7 +58,7 @@ impl PrivilegesApi {
     #[instrument(skip(self, request))]
     pub async fn create_privilege(&self, request: CreatePrivilegeRequest) -> Result<Privilege> {
         // Create returns only {"id": "..."}, so we need to fetch the full privilege after
-        let response: CreatePrivilegeResponse = self.client.post("/api/1/privileges", Some(&request)).await?;
+        let response: CreatePrivilegeResponse = self.client.post(&ApiVersion::V1.path("privileges"), Some(&request)).await?;
         // Fetch the full privilege details
         self.get_privilege(&response.id).await
     }
@@ -73,7 +74,7 @@ impl PrivilegesApi {
 
         // Update returns only {"id": "..."}, so we need to fetch the full privilege after
         let _response: CreatePrivilegeResponse = self.client
-            .put(&format!("/api/1/privileges/{}", privilege_id), Some(&request))
+            .put(&ApiVersion::V1.path(&format!("privileges/{}", privilege_id)), Some(&request))
             .await?;
         // Fetch the full privilege details
         self.get_privilege(privilege_id).await
@@ -85,15 +86,15 @@ impl PrivilegesApi {
         self.cache.invalidate(&cache_key).await;
 
         self.client
-            .delete(&format!("/api/1/privileges/{}", privilege_id))
+            .delete_no_content(&ApiVersion::V1.path(&format!("privileges/{}", privilege_id)))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn assign_to_user(&self, privilege_id: &str, user_id: i64) -> Result<()> {
         self.client
-            .post(
-                &format!("/api/1/privileges/{}/users/{}", privilege_id, user_id),
+            .post_no_content(
+                &ApiVersion::V1.path(&format!("privileges/{}/users/{}", privilege_id, user_id)),
                 None::<&()>,
             )
             .await
@@ -102,8 +103,8 @@ impl PrivilegesApi {
     #[instrument(skip(self))]
     pub async fn assign_to_role(&self, privilege_id: &str, role_id: i64) -> Result<()> {
         self.client
-            .post(
-                &format!("/api/1/privileges/{}/roles/{}", privilege_id, role_id),
+            .post_no_content(
+                &ApiVersion::V1.path(&format!("privileges/{}/roles/{}", privilege_id, role_id)),
                 None::<&()>,
             )
             .await
@@ -112,28 +113,28 @@ impl PrivilegesApi {
     #[instrument(skip(self))]
     pub async fn get_assigned_users(&self, privilege_id: &str) -> Result<Vec<i64>> {
         self.client
-            .get(&format!("/api/1/privileges/{}/users", privilege_id))
+            .get(&ApiVersion::V1.path(&format!("privileges/{}/users", privilege_id)))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn get_assigned_roles(&self, privilege_id: &str) -> Result<Vec<i64>> {
         self.client
-            .get(&format!("/api/1/privileges/{}/roles", privilege_id))
+            .get(&ApiVersion::V1.path(&format!("privileges/{}/roles", privilege_id)))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn remove_user(&self, privilege_id: &str, user_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/1/privileges/{}/users/{}", privilege_id, user_id))
+            .delete_no_content(&ApiVersion::V1.path(&format!("privileges/{}/users/{}", privilege_id, user_id)))
             .await
     }
 
     #[instrument(skip(self))]
     pub async fn remove_role(&self, privilege_id: &str, role_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/1/privileges/{}/roles/{}", privilege_id, role_id))
+            .delete_no_content(&ApiVersion::V1.path(&format!("privileges/{}/roles/{}", privilege_id, role_id)))
             .await
     }
 }
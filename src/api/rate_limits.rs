@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
-use crate::core::client::HttpClient;
+use crate::core::client::{ClientStats, HttpClient};
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::rate_limits::*;
 use std::sync::Arc;
@@ -20,13 +21,41 @@ impl RateLimitsApi {
     /// Endpoint: /auth/rate_limit (returns X-RateLimit-* values)
     #[instrument(skip(self))]
     pub async fn get_rate_limit_status(&self) -> Result<RateLimitStatus> {
-        self.client.get("/auth/rate_limit").await
+        self.client.get(&ApiVersion::Root.path("auth/rate_limit")).await
     }
 
     /// Get rate limit configuration (alias for status)
     /// Note: OneLogin only provides a single rate limit endpoint
     #[instrument(skip(self))]
     pub async fn get_rate_limits(&self) -> Result<RateLimitStatus> {
-        self.client.get("/auth/rate_limit").await
+        self.client.get(&ApiVersion::Root.path("auth/rate_limit")).await
+    }
+
+    /// Local limiter, circuit breaker and compression counters (no API call)
+    pub fn client_stats(&self) -> ClientStats {
+        self.client.stats()
+    }
+
+    /// Get OneLogin's reported rate limit alongside the local limiter state.
+    /// A failed /auth/rate_limit call is reported in the result rather than returned as an error.
+    #[instrument(skip(self))]
+    pub async fn get_rate_limit_overview(&self) -> Result<RateLimitOverview> {
+        let (onelogin, onelogin_error) = match self.get_rate_limit_status().await {
+            Ok(status) => (status.data, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let config = self.client.config();
+
+        Ok(RateLimitOverview {
+            onelogin,
+            onelogin_error,
+            local: self.client.rate_limiter_stats(),
+            compression: self.client.compression_stats(),
+            circuit_breaker: self.client.circuit_breaker_stats(),
+            max_retries: config.max_retries,
+            retry_initial_delay_ms: config.retry_initial_delay_ms,
+            retry_max_delay_ms: config.retry_max_delay_ms,
+            retry_budget: self.client.stats().retry_budget,
+        })
     }
 }
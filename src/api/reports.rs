@@ -1,9 +1,12 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::reports::*;
+use crate::core::error::OneLoginError;
 use std::sync::Arc;
-use tracing::instrument;
+use std::time::Duration;
+use tracing::{debug, instrument};
 
 pub struct ReportsApi {
     client: Arc<HttpClient>,
@@ -19,14 +22,14 @@ impl ReportsApi {
     /// List all available reports
     #[instrument(skip(self))]
     pub async fn list_reports(&self) -> Result<Vec<Report>> {
-        self.client.get("/api/2/reports").await
+        self.client.get(&ApiVersion::V2.path("reports")).await
     }
 
     /// Get a specific report by ID
     #[instrument(skip(self))]
     pub async fn get_report(&self, report_id: i64) -> Result<Report> {
         self.client
-            .get(&format!("/api/2/reports/{}", report_id))
+            .get(&ApiVersion::V2.path(&format!("reports/{}", report_id)))
             .await
     }
 
@@ -34,7 +37,7 @@ impl ReportsApi {
     #[instrument(skip(self, request))]
     pub async fn run_report(&self, report_id: i64, request: Option<RunReportRequest>) -> Result<ReportJob> {
         self.client
-            .post(&format!("/api/2/reports/{}/run", report_id), request.as_ref())
+            .post(&ApiVersion::V2.path(&format!("reports/{}/run", report_id)), request.as_ref())
             .await
     }
 
@@ -42,7 +45,60 @@ impl ReportsApi {
     #[instrument(skip(self))]
     pub async fn get_report_results(&self, report_id: i64, job_id: &str) -> Result<ReportJob> {
         self.client
-            .get(&format!("/api/2/reports/{}/results/{}", report_id, job_id))
+            .get(&ApiVersion::V2.path(&format!("reports/{}/results/{}", report_id, job_id)))
             .await
     }
+
+    /// Poll a report job until it finishes or `timeout` elapses
+    #[instrument(skip(self))]
+    pub async fn wait_for_results(
+        &self,
+        report_id: i64,
+        job_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<ReportJob> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let job = self.get_report_results(report_id, job_id).await?;
+            if job.is_finished() {
+                return Ok(job);
+            }
+            if tokio::time::Instant::now() + poll_interval > deadline {
+                return Err(OneLoginError::ApiRequestFailed(format!(
+                    "Report job {} did not complete within {}s (last status: {})",
+                    job_id,
+                    timeout.as_secs(),
+                    job.status.as_deref().unwrap_or("unknown")
+                )));
+            }
+            debug!(
+                "Report job {} status {:?}, progress {:?}; polling again in {:?}",
+                job_id, job.status, job.progress, poll_interval
+            );
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Run a report and wait for the job to complete
+    #[instrument(skip(self, request))]
+    pub async fn run_report_and_wait(
+        &self,
+        report_id: i64,
+        request: Option<RunReportRequest>,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<ReportJob> {
+        let job = self.run_report(report_id, request).await?;
+        if job.is_finished() {
+            return Ok(job);
+        }
+        match job.id.clone() {
+            Some(job_id) => {
+                self.wait_for_results(report_id, &job_id, poll_interval, timeout)
+                    .await
+            }
+            None => Ok(job),
+        }
+    }
 }
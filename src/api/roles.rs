@@ -1,13 +1,18 @@
 use crate::core::cache::CacheManager;
-use crate::core::client::HttpClient;
+use crate::core::client::{HttpClient, AFTER_CURSOR};
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::roles::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::instrument;
 
+/// Cache key of the full role list used to resolve role names
+const ROLES_LIST_KEY: &str = "roles:all";
+
 pub struct RolesApi {
     client: Arc<HttpClient>,
-    #[allow(dead_code)]
     cache: Arc<CacheManager>,
 }
 
@@ -19,21 +24,73 @@ impl RolesApi {
     #[instrument(skip(self))]
     pub async fn list_roles(&self) -> Result<Vec<Role>> {
         // OneLogin API returns roles as a plain array, not wrapped in a pagination envelope
-        let response: Vec<Role> = self.client.get("/roles").await?;
+        let response: Vec<Role> = self.client.get(&ApiVersion::V2.path("roles")).await?;
         Ok(response)
     }
 
+    /// Role names by id, from the cached role list
+    #[instrument(skip(self))]
+    pub async fn role_names(&self) -> Result<HashMap<i64, String>> {
+        // get_with_age: the names decorate fresh results, which shouldn't be reported as cached
+        let roles: Vec<Role> = match self.cache.get_with_age(ROLES_LIST_KEY).await {
+            Some((roles, _)) => roles,
+            None => {
+                let roles = self.list_roles().await?;
+                self.cache.set(ROLES_LIST_KEY.to_string(), &roles).await;
+                roles
+            }
+        };
+        Ok(roles.into_iter().filter_map(|r| Some((r.id, r.name?))).collect())
+    }
+
+    /// One filtered page of roles. The next page's cursor comes from the `After-Cursor` header.
+    #[instrument(skip(self))]
+    pub async fn list_roles_page(&self, params: RoleQueryParams) -> Result<RolePage> {
+        let mut path = ApiVersion::V2.path("roles");
+        if let Ok(query) = serde_qs::to_string(&params) {
+            if !query.is_empty() {
+                path.push('?');
+                path.push_str(&query);
+            }
+        }
+        AFTER_CURSOR
+            .scope(RefCell::new(None), async {
+                let roles: Vec<Role> = self.client.get(&path).await?;
+                let next_cursor = AFTER_CURSOR.with(|slot| slot.borrow_mut().take());
+                Ok(RolePage { roles, next_cursor })
+            })
+            .await
+    }
+
     #[instrument(skip(self))]
     pub async fn get_role(&self, role_id: i64) -> Result<Role> {
+        let cache_key = Self::cache_key(role_id);
+
+        if let Some(role) = self.cache.get(&cache_key).await {
+            return Ok(role);
+        }
+
         // OneLogin API returns a plain role object, not wrapped
-        let role: Role = self.client.get(&format!("/roles/{}", role_id)).await?;
+        let role: Role = self.client.get(&ApiVersion::V2.path(&format!("roles/{}", role_id))).await?;
+
+        self.cache.set(cache_key, &role).await;
         Ok(role)
     }
 
+    fn cache_key(role_id: i64) -> String {
+        CacheManager::build_key("role", &[&role_id.to_string()])
+    }
+
+    /// Cached roles embed their user, app and admin ids, so membership changes invalidate them
+    async fn invalidate(&self, role_id: i64) {
+        self.cache.invalidate(&Self::cache_key(role_id)).await;
+    }
+
     #[instrument(skip(self, request))]
     pub async fn create_role(&self, request: CreateRoleRequest) -> Result<Role> {
         // OneLogin API returns a plain role object, not wrapped
-        let role: Role = self.client.post("/roles", Some(&request)).await?;
+        let role: Role = self.client.post(&ApiVersion::V2.path("roles"), Some(&request)).await?;
+        self.cache.invalidate(ROLES_LIST_KEY).await;
         Ok(role)
     }
 
@@ -41,14 +98,19 @@ impl RolesApi {
     pub async fn update_role(&self, role_id: i64, request: UpdateRoleRequest) -> Result<Role> {
         // OneLogin API returns a plain role object, not wrapped
         let role: Role = self.client
-            .put(&format!("/roles/{}", role_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("roles/{}", role_id)), Some(&request))
             .await?;
+        self.invalidate(role_id).await;
+        self.cache.invalidate(ROLES_LIST_KEY).await;
         Ok(role)
     }
 
     #[instrument(skip(self))]
     pub async fn delete_role(&self, role_id: i64) -> Result<()> {
-        self.client.delete(&format!("/roles/{}", role_id)).await
+        self.client.delete_no_content(&ApiVersion::V2.path(&format!("roles/{}", role_id))).await?;
+        self.invalidate(role_id).await;
+        self.cache.invalidate(ROLES_LIST_KEY).await;
+        Ok(())
     }
 
     // Sub-resource methods
@@ -57,23 +119,25 @@ impl RolesApi {
     #[instrument(skip(self))]
     pub async fn get_role_apps(&self, role_id: i64) -> Result<Vec<RoleApp>> {
         self.client
-            .get(&format!("/roles/{}/apps", role_id))
+            .get(&ApiVersion::V2.path(&format!("roles/{}/apps", role_id)))
             .await
     }
 
     /// Set apps for a role (replaces existing apps)
     #[instrument(skip(self, request))]
     pub async fn set_role_apps(&self, role_id: i64, request: SetRoleAppsRequest) -> Result<Vec<i64>> {
-        self.client
-            .put(&format!("/roles/{}/apps", role_id), Some(&request))
-            .await
+        let app_ids = self.client
+            .put(&ApiVersion::V2.path(&format!("roles/{}/apps", role_id)), Some(&request))
+            .await?;
+        self.invalidate(role_id).await;
+        Ok(app_ids)
     }
 
     /// Get users assigned to a role
     #[instrument(skip(self))]
     pub async fn get_role_users(&self, role_id: i64) -> Result<Vec<RoleUser>> {
         self.client
-            .get(&format!("/roles/{}/users", role_id))
+            .get(&ApiVersion::V2.path(&format!("roles/{}/users", role_id)))
             .await
     }
 
@@ -81,7 +145,7 @@ impl RolesApi {
     #[instrument(skip(self))]
     pub async fn get_role_admins(&self, role_id: i64) -> Result<Vec<RoleAdmin>> {
         self.client
-            .get(&format!("/roles/{}/admins", role_id))
+            .get(&ApiVersion::V2.path(&format!("roles/{}/admins", role_id)))
             .await
     }
 
@@ -89,31 +153,43 @@ impl RolesApi {
     #[instrument(skip(self, request))]
     pub async fn add_role_admins(&self, role_id: i64, request: AddRoleAdminsRequest) -> Result<()> {
         self.client
-            .post(&format!("/roles/{}/admins", role_id), Some(&request))
-            .await
+            .post_no_content(&ApiVersion::V2.path(&format!("roles/{}/admins", role_id)), Some(&request))
+            .await?;
+        self.invalidate(role_id).await;
+        Ok(())
     }
 
     /// Remove an admin from a role
     #[instrument(skip(self))]
     pub async fn remove_role_admin(&self, role_id: i64, admin_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/roles/{}/admins/{}", role_id, admin_id))
-            .await
+            .delete_no_content(&ApiVersion::V2.path(&format!("roles/{}/admins/{}", role_id, admin_id)))
+            .await?;
+        self.invalidate(role_id).await;
+        Ok(())
     }
 
     /// Assign roles to a user
     #[instrument(skip(self, request))]
     pub async fn assign_roles_to_user(&self, user_id: i64, request: RoleIdsRequest) -> Result<()> {
         self.client
-            .put(&format!("/users/{}/add_roles", user_id), Some(&request))
-            .await
+            .put_no_content(&ApiVersion::V2.path(&format!("users/{}/add_roles", user_id)), Some(&request))
+            .await?;
+        for role_id in &request.role_id_array {
+            self.invalidate(*role_id).await;
+        }
+        Ok(())
     }
 
     /// Remove roles from a user
     #[instrument(skip(self, request))]
     pub async fn remove_roles_from_user(&self, user_id: i64, request: RoleIdsRequest) -> Result<()> {
         self.client
-            .put(&format!("/users/{}/remove_roles", user_id), Some(&request))
-            .await
+            .put_no_content(&ApiVersion::V2.path(&format!("users/{}/remove_roles", user_id)), Some(&request))
+            .await?;
+        for role_id in &request.role_id_array {
+            self.invalidate(*role_id).await;
+        }
+        Ok(())
     }
 }
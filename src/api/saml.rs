@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::saml::*;
 use std::sync::Arc;
@@ -20,7 +21,7 @@ impl SamlApi {
         &self,
         request: SamlAssertionRequest,
     ) -> Result<SamlAssertionResponse> {
-        self.client.post("/saml_assertion", Some(&request)).await
+        self.client.post(&ApiVersion::V2.path("saml_assertion"), Some(&request)).await
     }
 
     #[instrument(skip(self, request))]
@@ -29,7 +30,7 @@ impl SamlApi {
         request: SamlAssertionRequest,
     ) -> Result<SamlAssertionResponse> {
         self.client
-            .post("/api/2/saml_assertion", Some(&request))
+            .post(&ApiVersion::V2.path("saml_assertion"), Some(&request))
             .await
     }
 
@@ -39,7 +40,7 @@ impl SamlApi {
         request: VerifySamlFactorRequest,
     ) -> Result<SamlAssertionResponse> {
         self.client
-            .post("/saml_assertion/verify_factor", Some(&request))
+            .post(&ApiVersion::V2.path("saml_assertion/verify_factor"), Some(&request))
             .await
     }
 }
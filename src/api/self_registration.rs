@@ -1,6 +1,7 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
-use crate::core::error::Result;
+use crate::core::config::ApiVersion;
+use crate::core::error::{OneLoginError, Result};
 use crate::models::self_registration::*;
 use std::sync::Arc;
 use tracing::instrument;
@@ -20,7 +21,7 @@ impl SelfRegistrationApi {
     #[instrument(skip(self))]
     pub async fn list_profiles(&self) -> Result<Vec<SelfRegistrationProfile>> {
         let response: SelfRegistrationProfilesResponse =
-            self.client.get("/api/2/self_registration_profiles").await?;
+            self.client.get(&ApiVersion::V2.path("self_registration_profiles")).await?;
         Ok(response.self_registration_profiles)
     }
 
@@ -28,7 +29,7 @@ impl SelfRegistrationApi {
     #[instrument(skip(self))]
     pub async fn get_profile(&self, profile_id: i64) -> Result<SelfRegistrationProfile> {
         self.client
-            .get(&format!("/api/2/self_registration_profiles/{}", profile_id))
+            .get(&ApiVersion::V2.path(&format!("self_registration_profiles/{}", profile_id)))
             .await
     }
 
@@ -38,8 +39,9 @@ impl SelfRegistrationApi {
         &self,
         request: CreateSelfRegistrationProfileRequest,
     ) -> Result<SelfRegistrationProfile> {
+        request.validate().map_err(OneLoginError::InvalidInput)?;
         self.client
-            .post("/api/2/self_registration_profiles", Some(&request))
+            .post(&ApiVersion::V2.path("self_registration_profiles"), Some(&request))
             .await
     }
 
@@ -50,9 +52,10 @@ impl SelfRegistrationApi {
         profile_id: i64,
         request: UpdateSelfRegistrationProfileRequest,
     ) -> Result<SelfRegistrationProfile> {
+        request.validate().map_err(OneLoginError::InvalidInput)?;
         self.client
             .put(
-                &format!("/api/2/self_registration_profiles/{}", profile_id),
+                &ApiVersion::V2.path(&format!("self_registration_profiles/{}", profile_id)),
                 Some(&request),
             )
             .await
@@ -62,7 +65,7 @@ impl SelfRegistrationApi {
     #[instrument(skip(self))]
     pub async fn delete_profile(&self, profile_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/self_registration_profiles/{}", profile_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("self_registration_profiles/{}", profile_id)))
             .await
     }
 
@@ -70,10 +73,10 @@ impl SelfRegistrationApi {
     #[instrument(skip(self))]
     pub async fn list_registrations(&self, profile_id: i64) -> Result<Vec<Registration>> {
         self.client
-            .get(&format!(
-                "/api/2/self_registration_profiles/{}/registrations",
+            .get(&ApiVersion::V2.path(&format!(
+                "self_registration_profiles/{}/registrations",
                 profile_id
-            ))
+            )))
             .await
     }
 
@@ -87,10 +90,10 @@ impl SelfRegistrationApi {
     ) -> Result<Registration> {
         self.client
             .put(
-                &format!(
-                    "/api/2/self_registration_profiles/{}/registrations/{}",
+                &ApiVersion::V2.path(&format!(
+                    "self_registration_profiles/{}/registrations/{}",
                     profile_id, registration_id
-                ),
+                )),
                 Some(&request),
             )
             .await
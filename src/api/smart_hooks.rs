@@ -1,25 +1,35 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::smart_hooks::*;
-use std::sync::Arc;
+use crate::utils::secrets::fingerprint;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tracing::instrument;
 
 pub struct SmartHooksApi {
     client: Arc<HttpClient>,
     cache: Arc<CacheManager>,
+    /// SHA-256 of env var values set through this server, by env var ID.
+    /// OneLogin never returns the values, so this is what comparisons check against.
+    fingerprints: Mutex<HashMap<String, String>>,
 }
 
 impl SmartHooksApi {
     pub fn new(client: Arc<HttpClient>, cache: Arc<CacheManager>) -> Self {
-        Self { client, cache }
+        Self {
+            client,
+            cache,
+            fingerprints: Mutex::new(HashMap::new()),
+        }
     }
 
     #[instrument(skip(self, request))]
     pub async fn create_hook(&self, request: CreateHookRequest) -> Result<SmartHook> {
         // OneLogin API v2 returns direct object, not wrapped
         let hook: SmartHook =
-            self.client.post("/api/2/hooks", Some(&request)).await?;
+            self.client.post(&ApiVersion::V2.path("hooks"), Some(&request)).await?;
         Ok(hook)
     }
 
@@ -34,7 +44,7 @@ impl SmartHooksApi {
 
         // OneLogin API v2 returns direct object, not wrapped
         let hook: SmartHook = self.client
-            .put(&format!("/api/2/hooks/{}", hook_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("hooks/{}", hook_id)), Some(&request))
             .await?;
         Ok(hook)
     }
@@ -50,7 +60,7 @@ impl SmartHooksApi {
         self.cache.invalidate(&cache_key).await;
 
         let hook: SmartHook = self.client
-            .put(&format!("/api/2/hooks/{}", hook_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("hooks/{}", hook_id)), Some(&request))
             .await?;
         Ok(hook)
     }
@@ -60,7 +70,7 @@ impl SmartHooksApi {
         let cache_key = CacheManager::build_key("hook", &[hook_id]);
         self.cache.invalidate(&cache_key).await;
 
-        self.client.delete(&format!("/api/2/hooks/{}", hook_id)).await
+        self.client.delete_no_content(&ApiVersion::V2.path(&format!("hooks/{}", hook_id))).await
     }
 
     #[instrument(skip(self))]
@@ -73,7 +83,7 @@ impl SmartHooksApi {
 
         // OneLogin API v2 returns direct object, not wrapped
         let hook: SmartHook =
-            self.client.get(&format!("/api/2/hooks/{}", hook_id)).await?;
+            self.client.get(&ApiVersion::V2.path(&format!("hooks/{}", hook_id))).await?;
 
         self.cache.set(cache_key, &hook).await;
         Ok(hook)
@@ -83,7 +93,7 @@ impl SmartHooksApi {
     pub async fn list_hooks(&self) -> Result<Vec<SmartHook>> {
         // OneLogin API v2 returns direct array, not wrapped
         let hooks: Vec<SmartHook> =
-            self.client.get("/api/2/hooks").await?;
+            self.client.get(&ApiVersion::V2.path("hooks")).await?;
         Ok(hooks)
     }
 
@@ -91,7 +101,7 @@ impl SmartHooksApi {
     pub async fn get_hook_logs(&self, hook_id: &str) -> Result<Vec<HookLog>> {
         // OneLogin API v2 returns direct array, not wrapped
         let logs: Vec<HookLog> =
-            self.client.get(&format!("/api/2/hooks/{}/logs", hook_id)).await?;
+            self.client.get(&ApiVersion::V2.path(&format!("hooks/{}/logs", hook_id))).await?;
         Ok(logs)
     }
 
@@ -101,21 +111,23 @@ impl SmartHooksApi {
     /// List all environment variables in the account
     #[instrument(skip(self))]
     pub async fn list_env_vars(&self) -> Result<Vec<HookEnvVar>> {
-        self.client.get("/api/2/hooks/envs").await
+        self.client.get(&ApiVersion::V2.path("hooks/envs")).await
     }
 
     /// Get a specific environment variable by ID
     #[instrument(skip(self))]
     pub async fn get_env_var(&self, env_var_id: &str) -> Result<HookEnvVar> {
         self.client
-            .get(&format!("/api/2/hooks/envs/{}", env_var_id))
+            .get(&ApiVersion::V2.path(&format!("hooks/envs/{}", env_var_id)))
             .await
     }
 
     /// Create a new environment variable (account-level, shared by all hooks)
     #[instrument(skip(self, request))]
     pub async fn create_env_var(&self, request: CreateEnvVarRequest) -> Result<HookEnvVar> {
-        self.client.post("/api/2/hooks/envs", Some(&request)).await
+        let env_var: HookEnvVar = self.client.post(&ApiVersion::V2.path("hooks/envs"), Some(&request)).await?;
+        self.record_fingerprint(&env_var.id, &request.value);
+        Ok(env_var)
     }
 
     /// Update an environment variable's value (name cannot be changed)
@@ -125,16 +137,32 @@ impl SmartHooksApi {
         env_var_id: &str,
         request: UpdateEnvVarRequest,
     ) -> Result<HookEnvVar> {
-        self.client
-            .put(&format!("/api/2/hooks/envs/{}", env_var_id), Some(&request))
-            .await
+        let env_var: HookEnvVar = self.client
+            .put(&ApiVersion::V2.path(&format!("hooks/envs/{}", env_var_id)), Some(&request))
+            .await?;
+        self.record_fingerprint(env_var_id, &request.value);
+        Ok(env_var)
     }
 
     /// Delete an environment variable
     #[instrument(skip(self))]
     pub async fn delete_env_var(&self, env_var_id: &str) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/hooks/envs/{}", env_var_id))
-            .await
+            .delete_no_content(&ApiVersion::V2.path(&format!("hooks/envs/{}", env_var_id)))
+            .await?;
+        self.fingerprints.lock().expect("Mutex poisoned").remove(env_var_id);
+        Ok(())
+    }
+
+    /// Fingerprint of the value last set through this server, if any
+    pub fn recorded_fingerprint(&self, env_var_id: &str) -> Option<String> {
+        self.fingerprints.lock().expect("Mutex poisoned").get(env_var_id).cloned()
+    }
+
+    fn record_fingerprint(&self, env_var_id: &str, value: &str) {
+        self.fingerprints
+            .lock()
+            .expect("Mutex poisoned")
+            .insert(env_var_id.to_string(), fingerprint(value));
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::smart_mfa::*;
 use std::sync::Arc;
@@ -21,7 +22,7 @@ impl SmartMfaApi {
         request: SmartMfaValidateRequest,
     ) -> Result<SmartMfaValidateResponse> {
         self.client
-            .post("/api/2/smart_mfa/validate", Some(&request))
+            .post(&ApiVersion::V2.path("smart_mfa/validate"), Some(&request))
             .await
     }
 }
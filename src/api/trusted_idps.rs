@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::trusted_idps::*;
 use std::sync::Arc;
@@ -19,14 +20,14 @@ impl TrustedIdpsApi {
     /// List all trusted identity providers
     #[instrument(skip(self))]
     pub async fn list_trusted_idps(&self) -> Result<Vec<TrustedIdp>> {
-        self.client.get("/api/2/trusted_idps").await
+        self.client.get(&ApiVersion::V2.path("trusted_idps")).await
     }
 
     /// Get a specific trusted IDP by ID
     #[instrument(skip(self))]
     pub async fn get_trusted_idp(&self, idp_id: i64) -> Result<TrustedIdp> {
         self.client
-            .get(&format!("/api/2/trusted_idps/{}", idp_id))
+            .get(&ApiVersion::V2.path(&format!("trusted_idps/{}", idp_id)))
             .await
     }
 
@@ -34,7 +35,7 @@ impl TrustedIdpsApi {
     #[instrument(skip(self, request))]
     pub async fn create_trusted_idp(&self, request: CreateTrustedIdpRequest) -> Result<TrustedIdp> {
         self.client
-            .post("/api/2/trusted_idps", Some(&request))
+            .post(&ApiVersion::V2.path("trusted_idps"), Some(&request))
             .await
     }
 
@@ -46,7 +47,7 @@ impl TrustedIdpsApi {
         request: UpdateTrustedIdpRequest,
     ) -> Result<TrustedIdp> {
         self.client
-            .put(&format!("/api/2/trusted_idps/{}", idp_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("trusted_idps/{}", idp_id)), Some(&request))
             .await
     }
 
@@ -54,7 +55,7 @@ impl TrustedIdpsApi {
     #[instrument(skip(self))]
     pub async fn delete_trusted_idp(&self, idp_id: i64) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/trusted_idps/{}", idp_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("trusted_idps/{}", idp_id)))
             .await
     }
 
@@ -62,7 +63,7 @@ impl TrustedIdpsApi {
     #[instrument(skip(self))]
     pub async fn get_trusted_idp_metadata(&self, idp_id: i64) -> Result<String> {
         self.client
-            .get(&format!("/api/2/trusted_idps/{}/metadata", idp_id))
+            .get(&ApiVersion::V2.path(&format!("trusted_idps/{}/metadata", idp_id)))
             .await
     }
 
@@ -74,8 +75,8 @@ impl TrustedIdpsApi {
         request: UpdateTrustedIdpMetadataRequest,
     ) -> Result<()> {
         self.client
-            .put(
-                &format!("/api/2/trusted_idps/{}/metadata", idp_id),
+            .put_no_content(
+                &ApiVersion::V2.path(&format!("trusted_idps/{}/metadata", idp_id)),
                 Some(&request),
             )
             .await
@@ -85,7 +86,7 @@ impl TrustedIdpsApi {
     #[instrument(skip(self))]
     pub async fn get_trusted_idp_issuer(&self, idp_id: i64) -> Result<TrustedIdpIssuer> {
         self.client
-            .get(&format!("/api/2/trusted_idps/{}/issuer", idp_id))
+            .get(&ApiVersion::V2.path(&format!("trusted_idps/{}/issuer", idp_id)))
             .await
     }
 }
@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::user_mappings::*;
 use std::sync::Arc;
@@ -24,20 +25,20 @@ impl UserMappingsApi {
     #[instrument(skip(self))]
     pub async fn list_mappings(&self) -> Result<Vec<UserMapping>> {
         // Note: mappings use API v2
-        self.client.get("/api/2/mappings").await
+        self.client.get(&ApiVersion::V2.path("mappings")).await
     }
 
     #[instrument(skip(self))]
     pub async fn get_mapping(&self, mapping_id: &str) -> Result<UserMapping> {
         // Note: mappings use API v2
-        self.client.get(&format!("/api/2/mappings/{}", mapping_id)).await
+        self.client.get(&ApiVersion::V2.path(&format!("mappings/{}", mapping_id))).await
     }
 
     #[instrument(skip(self, request))]
     pub async fn create_mapping(&self, request: CreateMappingRequest) -> Result<UserMapping> {
         // Note: mappings use API v2
         // API returns just {id} on create, so we need to fetch the full mapping
-        let response: CreateMappingResponse = self.client.post("/api/2/mappings", Some(&request)).await?;
+        let response: CreateMappingResponse = self.client.post(&ApiVersion::V2.path("mappings"), Some(&request)).await?;
         // Fetch the full mapping to return
         self.get_mapping(&response.id.to_string()).await
     }
@@ -50,7 +51,7 @@ impl UserMappingsApi {
     ) -> Result<UserMapping> {
         // Note: mappings use API v2
         self.client
-            .put(&format!("/api/2/mappings/{}", mapping_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("mappings/{}", mapping_id)), Some(&request))
             .await
     }
 
@@ -58,19 +59,19 @@ impl UserMappingsApi {
     pub async fn delete_mapping(&self, mapping_id: &str) -> Result<()> {
         // Note: mappings use API v2
         self.client
-            .delete(&format!("/api/2/mappings/{}", mapping_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("mappings/{}", mapping_id)))
             .await
     }
 
     #[instrument(skip(self, request))]
     pub async fn sort_mapping_order(&self, request: SortMappingsRequest) -> Result<()> {
         // Note: mappings use API v2
-        self.client.post("/api/2/mappings/sort", Some(&request)).await
+        self.client.post_no_content(&ApiVersion::V2.path("mappings/sort"), Some(&request)).await
     }
 
     #[instrument(skip(self))]
     pub async fn list_conditions(&self) -> Result<Vec<AvailableCondition>> {
         // Note: mappings use API v2
-        self.client.get("/api/2/mappings/conditions").await
+        self.client.get(&ApiVersion::V2.path("mappings/conditions")).await
     }
 }
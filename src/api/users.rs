@@ -1,7 +1,9 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::users::*;
+use crate::models::ApiResponse;
 use serde_json::Value;
 use std::sync::Arc;
 use tracing::instrument;
@@ -18,9 +20,10 @@ impl UsersApi {
 
     #[instrument(skip(self))]
     pub async fn list_users(&self, params: Option<UserQueryParams>) -> Result<Vec<User>> {
-        let mut path = "/api/2/users".to_string();
+        let mut path = ApiVersion::V2.path("users");
         if let Some(p) = params {
-            if let Ok(query) = serde_qs::to_string(&p) {
+            let query = p.to_query();
+            if !query.is_empty() {
                 path.push('?');
                 path.push_str(&query);
             }
@@ -40,7 +43,7 @@ impl UsersApi {
         }
 
         // OneLogin API v2 returns a plain user object, not wrapped
-        let user: User = self.client.get(&format!("/api/2/users/{}", user_id)).await?;
+        let user: User = self.client.get(&ApiVersion::V2.path(&format!("users/{}", user_id))).await?;
 
         self.cache.set(cache_key, &user).await;
         Ok(user)
@@ -49,7 +52,7 @@ impl UsersApi {
     #[instrument(skip(self, request))]
     pub async fn create_user(&self, request: CreateUserRequest) -> Result<User> {
         // OneLogin API v2 returns a plain user object, not wrapped
-        let user: User = self.client.post("/api/2/users", Some(&request)).await?;
+        let user: User = self.client.post(&ApiVersion::V2.path("users"), Some(&request)).await?;
         Ok(user)
     }
 
@@ -60,7 +63,7 @@ impl UsersApi {
 
         // OneLogin API v2 returns a plain user object, not wrapped
         let user: User = self.client
-            .put(&format!("/api/2/users/{}", user_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("users/{}", user_id)), Some(&request))
             .await?;
         Ok(user)
     }
@@ -70,13 +73,25 @@ impl UsersApi {
         let cache_key = CacheManager::build_key("user", &[&user_id.to_string()]);
         self.cache.invalidate(&cache_key).await;
 
-        self.client.delete(&format!("/api/2/users/{}", user_id)).await
+        // Returns 204 No Content on success
+        self.client.delete_no_content(&ApiVersion::V2.path(&format!("users/{}", user_id))).await
     }
 
     #[instrument(skip(self))]
     pub async fn get_user_apps(&self, user_id: i64) -> Result<Vec<Value>> {
         // API returns direct array, not wrapped in ApiResponse
-        self.client.get(&format!("/api/2/users/{}/apps", user_id)).await
+        self.client.get(&ApiVersion::V2.path(&format!("users/{}/apps", user_id))).await
+    }
+
+    /// Security factors the user has registered (API v1)
+    #[instrument(skip(self))]
+    pub async fn get_otp_devices(&self, user_id: i64) -> Result<Vec<OtpDevice>> {
+        // OneLogin API v1 wraps the devices in data.otp_devices
+        let response: ApiResponse<OtpDevicesData> = self
+            .client
+            .get(&ApiVersion::V1.path(&format!("users/{}/otp_devices", user_id)))
+            .await?;
+        Ok(response.data.otp_devices)
     }
 
     #[instrument(skip(self))]
@@ -90,7 +105,7 @@ impl UsersApi {
     pub async fn unlock_user(&self, user_id: i64) -> Result<()> {
         // API returns JSON response, not empty body
         let _response: UnlockUserResponse = self.client
-            .post(&format!("/api/2/users/{}/unlock", user_id), None::<&()>)
+            .post(&ApiVersion::V2.path(&format!("users/{}/unlock", user_id)), None::<&()>)
             .await?;
         Ok(())
     }
@@ -98,11 +113,10 @@ impl UsersApi {
     #[instrument(skip(self))]
     pub async fn logout_user(&self, user_id: i64) -> Result<()> {
         // Note: logout_user uses API v1 with PUT method
-        // API returns JSON response (same structure as lock_user)
-        let _response: LockUserResponse = self.client
-            .put(&format!("/api/1/users/{}/logout", user_id), None::<&()>)
-            .await?;
-        Ok(())
+        // API returns a status envelope or an empty body depending on the tenant
+        self.client
+            .put_no_content(&ApiVersion::V1.path(&format!("users/{}/logout", user_id)), None::<&()>)
+            .await
     }
 
     #[instrument(skip(self))]
@@ -111,7 +125,7 @@ impl UsersApi {
         // Endpoint is /lock_user not /lock per OneLogin API docs
         // API returns JSON response, not empty body
         let _response: LockUserResponse = self.client
-            .put(&format!("/api/1/users/{}/lock_user", user_id), Some(&request))
+            .put(&ApiVersion::V1.path(&format!("users/{}/lock_user", user_id)), Some(&request))
             .await?;
         Ok(())
     }
@@ -121,8 +135,13 @@ impl UsersApi {
         // Note: assign_roles uses API v1
         // API returns JSON status response
         let _response: LockUserResponse = self.client
-            .put(&format!("/api/1/users/{}/add_roles", user_id), Some(&request))
+            .put(&ApiVersion::V1.path(&format!("users/{}/add_roles", user_id)), Some(&request))
             .await?;
+        // Cached roles list their members
+        for role_id in &request.role_id_array {
+            let cache_key = CacheManager::build_key("role", &[&role_id.to_string()]);
+            self.cache.invalidate(&cache_key).await;
+        }
         Ok(())
     }
 
@@ -131,8 +150,13 @@ impl UsersApi {
         // Note: remove_roles uses API v1
         // API returns JSON status response
         let _response: LockUserResponse = self.client
-            .put(&format!("/api/1/users/{}/remove_roles", user_id), Some(&request))
+            .put(&ApiVersion::V1.path(&format!("users/{}/remove_roles", user_id)), Some(&request))
             .await?;
+        // Cached roles list their members
+        for role_id in &request.role_id_array {
+            let cache_key = CacheManager::build_key("role", &[&role_id.to_string()]);
+            self.cache.invalidate(&cache_key).await;
+        }
         Ok(())
     }
 
@@ -149,7 +173,7 @@ impl UsersApi {
         // API returns JSON status response
         let _response: LockUserResponse = self.client
             .put(
-                &format!("/api/1/users/{}/set_custom_attributes", user_id),
+                &ApiVersion::V1.path(&format!("users/{}/set_custom_attributes", user_id)),
                 Some(&request),
             )
             .await?;
@@ -162,7 +186,7 @@ impl UsersApi {
         user_id: i64,
     ) -> Result<Vec<DelegatedPrivilege>> {
         self.client
-            .get(&format!("/api/2/users/{}/delegated_privileges", user_id))
+            .get(&ApiVersion::V2.path(&format!("users/{}/delegated_privileges", user_id)))
             .await
     }
 
@@ -176,7 +200,7 @@ impl UsersApi {
         // API returns JSON status response
         let _response: LockUserResponse = self.client
             .put(
-                &format!("/api/1/users/set_password_clear_text/{}", user_id),
+                &ApiVersion::V1.path(&format!("users/set_password_clear_text/{}", user_id)),
                 Some(&request),
             )
             .await?;
@@ -193,7 +217,7 @@ impl UsersApi {
         // API returns JSON status response
         let _response: LockUserResponse = self.client
             .put(
-                &format!("/api/1/users/set_password_using_salt/{}", user_id),
+                &ApiVersion::V1.path(&format!("users/set_password_using_salt/{}", user_id)),
                 Some(&request),
             )
             .await?;
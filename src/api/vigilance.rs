@@ -1,5 +1,6 @@
 use crate::core::cache::CacheManager;
 use crate::core::client::HttpClient;
+use crate::core::config::ApiVersion;
 use crate::core::error::Result;
 use crate::models::vigilance::*;
 use std::sync::Arc;
@@ -25,7 +26,7 @@ impl VigilanceApi {
         // OneLogin API v2 returns direct object: {score, triggers, messages}
         let risk_score: RiskScore = self.client
             .post(
-                "/api/2/risk/verify",
+                &ApiVersion::V2.path("risk/verify"),
                 Some(&serde_json::json!({
                     "ip": ip,
                     "user_agent": user_agent,
@@ -42,7 +43,7 @@ impl VigilanceApi {
     pub async fn validate_user(&self, request: UserValidationRequest) -> Result<ValidationResult> {
         // OneLogin API v2 returns direct object, not wrapped
         let result: ValidationResult = self.client
-            .post("/api/2/risk/validate", Some(&request))
+            .post(&ApiVersion::V2.path("risk/validate"), Some(&request))
             .await?;
         Ok(result)
     }
@@ -51,7 +52,7 @@ impl VigilanceApi {
     pub async fn list_risk_rules(&self) -> Result<Vec<RiskRule>> {
         // OneLogin API v2 returns direct array, not wrapped
         let rules: Vec<RiskRule> =
-            self.client.get("/api/2/risk/rules").await?;
+            self.client.get(&ApiVersion::V2.path("risk/rules")).await?;
         Ok(rules)
     }
 
@@ -59,7 +60,7 @@ impl VigilanceApi {
     pub async fn create_risk_rule(&self, request: CreateRiskRuleRequest) -> Result<RiskRule> {
         // OneLogin API v2 returns direct object, not wrapped
         let rule: RiskRule = self.client
-            .post("/api/2/risk/rules", Some(&request))
+            .post(&ApiVersion::V2.path("risk/rules"), Some(&request))
             .await?;
         Ok(rule)
     }
@@ -72,7 +73,7 @@ impl VigilanceApi {
     ) -> Result<RiskRule> {
         // OneLogin API v2 returns direct object, not wrapped
         let rule: RiskRule = self.client
-            .put(&format!("/api/2/risk/rules/{}", rule_id), Some(&request))
+            .put(&ApiVersion::V2.path(&format!("risk/rules/{}", rule_id)), Some(&request))
             .await?;
         Ok(rule)
     }
@@ -80,7 +81,7 @@ impl VigilanceApi {
     #[instrument(skip(self))]
     pub async fn delete_risk_rule(&self, rule_id: &str) -> Result<()> {
         self.client
-            .delete(&format!("/api/2/risk/rules/{}", rule_id))
+            .delete_no_content(&ApiVersion::V2.path(&format!("risk/rules/{}", rule_id)))
             .await
     }
 
@@ -88,7 +89,7 @@ impl VigilanceApi {
     pub async fn get_risk_events(&self, user_id: &str) -> Result<Vec<RiskEvent>> {
         // OneLogin API v2 returns direct array, not wrapped
         let events: Vec<RiskEvent> = self.client
-            .get(&format!("/api/2/risk/events?user_id={}", user_id))
+            .get(&ApiVersion::V2.path(&format!("risk/events?user_id={}", user_id)))
             .await?;
         Ok(events)
     }
@@ -96,7 +97,7 @@ impl VigilanceApi {
     #[instrument(skip(self, event))]
     pub async fn track_risk_event(&self, event: RiskEvent) -> Result<()> {
         self.client
-            .post("/api/2/risk/events", Some(&event))
+            .post_no_content(&ApiVersion::V2.path("risk/events"), Some(&event))
             .await
     }
 }
@@ -35,11 +35,11 @@ impl WebhooksApi {
             .expect("HMAC can take key of any size");
         mac.update(verification.payload.as_bytes());
 
-        let result = mac.finalize();
-        let code_bytes = result.into_bytes();
-
-        let expected = hex::encode(code_bytes);
-        expected == verification.signature
+        // Constant-time comparison so the signature can't be recovered byte by byte
+        match hex::decode(verification.signature.trim()) {
+            Ok(signature) => mac.verify_slice(&signature).is_ok(),
+            Err(_) => false,
+        }
     }
 }
 
@@ -14,6 +14,22 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    /// Serve fixture data instead of calling OneLogin (no credentials required)
+    #[arg(long, global = true)]
+    pub mock: bool,
+
+    /// Fixture JSON file or directory for --mock, merged over the built-in demo data
+    #[arg(long, value_name = "PATH", global = true, requires = "mock")]
+    pub fixtures: Option<PathBuf>,
+
+    /// Record every OneLogin API request/response pair to a cassette file
+    #[arg(long, value_name = "PATH", global = true, conflicts_with_all = ["replay", "mock"])]
+    pub record: Option<PathBuf>,
+
+    /// Replay API responses from a recorded cassette instead of calling OneLogin
+    #[arg(long, value_name = "PATH", global = true, conflicts_with = "mock")]
+    pub replay: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
@@ -25,6 +41,12 @@ pub enum Commands {
     },
     /// Run the MCP server (default if no command specified)
     Serve,
+    /// Run headless as an OS service (systemd Type=notify or the Windows Service Control Manager):
+    /// scheduled jobs, the webhook receiver and metrics keep running without an MCP client on stdio
+    Service,
+    /// Run one Smart Hook test read from stdin (used internally by onelogin_test_smart_hook)
+    #[command(hide = true)]
+    RunHook,
 }
 
 #[derive(Subcommand, Clone)]
@@ -77,7 +99,7 @@ pub fn get_config_path() -> Result<PathBuf> {
 }
 
 /// Load existing config or return default
-fn load_config(path: &PathBuf) -> ToolConfigFile {
+pub(crate) fn load_config(path: &PathBuf) -> ToolConfigFile {
     if path.exists() {
         match fs::read_to_string(path) {
             Ok(content) => match serde_json::from_str(&content) {
@@ -92,7 +114,7 @@ fn load_config(path: &PathBuf) -> ToolConfigFile {
 }
 
 /// Save config to file
-fn save_config(path: &PathBuf, config: &ToolConfigFile) -> Result<()> {
+pub(crate) fn save_config(path: &PathBuf, config: &ToolConfigFile) -> Result<()> {
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
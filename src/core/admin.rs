@@ -0,0 +1,227 @@
+//! Authenticated HTTP admin API for operating a running server.
+//!
+//! Enabled by `ONELOGIN_ADMIN_LISTEN`; every request must carry
+//! `Authorization: Bearer <ONELOGIN_ADMIN_TOKEN>`. It is separate from MCP and
+//! lets operators manage the server without shell access to the host:
+//!
+//! - `GET /admin/tenants`: configured tenants and the default
+//! - `GET /admin/stats[?tenant=]`: rate limiter, circuit breaker, retry budget and fallback state
+//! - `POST /admin/reload`: re-read the tool configuration file
+//! - `POST /admin/cache/flush[?tenant=]`: drop cached API responses
+
+use crate::core::tenant_manager::TenantManager;
+use crate::core::tool_config::ToolConfig;
+use crate::core::http_listener::{self, HttpRequest};
+use anyhow::{anyhow, Context, Result};
+use secrecy::{ExposeSecret, SecretString};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+pub const LISTEN_ENV_VAR: &str = "ONELOGIN_ADMIN_LISTEN";
+pub const TOKEN_ENV_VAR: &str = "ONELOGIN_ADMIN_TOKEN";
+
+/// Shorter tokens are refused; the admin API can flush caches and reload config
+const MIN_TOKEN_LEN: usize = 16;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+pub struct AdminConfig {
+    pub listen: SocketAddr,
+    pub token: SecretString,
+}
+
+impl AdminConfig {
+    /// Read admin settings from the environment. `None` when `ONELOGIN_ADMIN_LISTEN` is unset.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(listen) = std::env::var(LISTEN_ENV_VAR) else {
+            return Ok(None);
+        };
+        let listen: SocketAddr = listen
+            .parse()
+            .with_context(|| format!("Invalid {}: expected host:port, e.g. 127.0.0.1:9465", LISTEN_ENV_VAR))?;
+        let token = std::env::var(TOKEN_ENV_VAR).unwrap_or_default();
+        if token.trim().len() < MIN_TOKEN_LEN {
+            return Err(anyhow!(
+                "{} is set but {} is missing or shorter than {} characters",
+                LISTEN_ENV_VAR,
+                TOKEN_ENV_VAR,
+                MIN_TOKEN_LEN
+            ));
+        }
+        Ok(Some(Self {
+            listen,
+            token: SecretString::new(token.trim().to_string()),
+        }))
+    }
+}
+
+/// What the admin API acts on
+pub struct AdminState {
+    pub tenants: Arc<TenantManager>,
+    pub tool_config: Arc<ToolConfig>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Route {
+    Tenants,
+    Stats,
+    Reload,
+    FlushCache,
+}
+
+/// Resolve a request to a route, or the HTTP error to answer with
+fn route(method: &str, path: &str) -> std::result::Result<Route, u16> {
+    let (route, expected) = match path {
+        "/admin/tenants" => (Route::Tenants, "GET"),
+        "/admin/stats" => (Route::Stats, "GET"),
+        "/admin/reload" => (Route::Reload, "POST"),
+        "/admin/cache/flush" => (Route::FlushCache, "POST"),
+        _ => return Err(404),
+    };
+    if method == expected {
+        Ok(route)
+    } else {
+        Err(405)
+    }
+}
+
+/// Compare bearer tokens without leaking the position of the first mismatch
+pub(crate) fn authorized(header: Option<&str>, token: &SecretString) -> bool {
+    let Some(presented) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let expected = token.expose_secret().as_bytes();
+    let presented = presented.trim().as_bytes();
+    presented.len() == expected.len()
+        && presented.iter().zip(expected).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Value of `name` in a query string
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+/// Bind the listener and serve the admin API in the background
+pub async fn start(config: AdminConfig, state: AdminState) -> Result<()> {
+    let listener = TcpListener::bind(config.listen)
+        .await
+        .with_context(|| format!("Failed to bind admin API on {}", config.listen))?;
+    info!("Admin API listening on http://{}/admin", config.listen);
+
+    let config = Arc::new(config);
+    let state = Arc::new(state);
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let config = config.clone();
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &config, &state).await {
+                            debug!("Admin connection from {} failed: {:#}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Admin API accept failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn handle_connection(stream: TcpStream, config: &AdminConfig, state: &AdminState) -> Result<()> {
+    // No endpoint takes a body; it is read and discarded so the client sees a clean response
+    let mut reader = BufReader::new(stream);
+    let (status, body) = match http_listener::read_request(&mut reader, MAX_BODY_BYTES).await {
+        Ok(request) => process(request, config, state).await,
+        Err(e) => (400, json!({"error": e.to_string()})),
+    };
+    let headers: &[(&str, &str)] = if status == 401 { &[("WWW-Authenticate", "Bearer")] } else { &[] };
+    http_listener::write_json(reader.get_mut(), status, headers, &body).await
+}
+
+async fn process(request: HttpRequest, config: &AdminConfig, state: &AdminState) -> (u16, Value) {
+    let (path, query) = (request.path(), request.query());
+    let route = match route(&request.method, path) {
+        Ok(route) => route,
+        Err(404) => return (404, json!({"error": "Not found"})),
+        Err(status) => return (status, json!({"error": "Method not allowed"})),
+    };
+    if !authorized(request.header("authorization"), &config.token) {
+        warn!("Rejected admin request to {} with a missing or invalid token", path);
+        return (401, json!({"error": "Invalid or missing bearer token"}));
+    }
+    let tenant = query_param(query, "tenant");
+    info!("Admin API: {} {}", request.method, path);
+
+    match route {
+        Route::Tenants => (
+            200,
+            json!({
+                "default_tenant": state.tenants.default_tenant_name(),
+                "tenants": state.tenants.tenant_info(),
+            }),
+        ),
+        Route::Stats => {
+            let stats: serde_json::Map<String, Value> = state
+                .tenants
+                .client_stats()
+                .into_iter()
+                .filter(|(name, _)| tenant.is_none_or(|t| t == name))
+                .map(|(name, stats)| (name, json!(stats)))
+                .collect();
+            if stats.is_empty() {
+                return (404, json!({"error": format!("Unknown tenant '{}'", tenant.unwrap_or_default())}));
+            }
+            (200, json!({ "tenants": stats }))
+        }
+        Route::Reload => match state.tool_config.reload() {
+            Ok(()) => (
+                200,
+                json!({
+                    "reloaded": state.tool_config.config_path().is_some(),
+                    "config_path": state.tool_config.config_path(),
+                    "enabled_tools": state.tool_config.enabled_count(),
+                }),
+            ),
+            Err(e) => (500, json!({"error": format!("{:#}", e)})),
+        },
+        Route::FlushCache => match state.tenants.flush_caches(tenant).await {
+            Ok(flushed) => (200, json!({ "flushed": flushed })),
+            Err(e) => (404, json!({"error": e.to_string()})),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_and_methods() {
+        assert_eq!(route("GET", "/admin/stats"), Ok(Route::Stats));
+        assert_eq!(route("POST", "/admin/cache/flush"), Ok(Route::FlushCache));
+        assert_eq!(route("GET", "/admin/reload"), Err(405));
+        assert_eq!(route("GET", "/metrics"), Err(404));
+        assert_eq!(query_param("tenant=prod&x=1", "tenant"), Some("prod"));
+        assert_eq!(query_param("tenant=", "tenant"), None);
+    }
+
+    #[test]
+    fn test_bearer_token_required() {
+        let token = SecretString::new("0123456789abcdef".to_string());
+        assert!(authorized(Some("Bearer 0123456789abcdef"), &token));
+        assert!(!authorized(Some("Bearer 0123456789abcdeX"), &token));
+        assert!(!authorized(Some("Bearer 0123456789"), &token));
+        assert!(!authorized(Some("0123456789abcdef"), &token));
+        assert!(!authorized(None, &token));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::core::config::Config;
 use crate::core::error::{OneLoginError, Result};
+use crate::utils::redact;
 use chrono::{DateTime, Duration, Utc};
 use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
@@ -44,7 +45,10 @@ impl AuthManager {
     pub fn new(config: Arc<Config>) -> Self {
         Self {
             config: config.clone(),
-            client: reqwest::Client::new(),
+            client: reqwest::Client::builder()
+                .default_headers(config.default_headers())
+                .build()
+                .expect("Failed to build HTTP client"),
             token: Arc::new(RwLock::new(None)),
         }
     }
@@ -171,7 +175,8 @@ impl AuthManager {
             ))
         })?;
 
-        let body_str = String::from_utf8_lossy(&body_bytes);
+        // The body carries the access token; only its redacted form is ever logged
+        let body_str = redact::redact_body(&String::from_utf8_lossy(&body_bytes));
         debug!("Token response body: {}", body_str);
 
         let token_response: TokenResponse = serde_json::from_slice(&body_bytes).map_err(|e| {
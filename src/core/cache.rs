@@ -1,10 +1,59 @@
 use moka::future::Cache as MokaCache;
-use serde::{de::DeserializeOwned, Serialize};
-use std::time::Duration;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    /// When set to true, `CacheManager::get` misses so callers fetch fresh data
+    pub static FORCE_REFRESH: bool;
+
+    /// Age of the oldest cache entry served while this scope is active, so tool
+    /// calls can report how fresh their data is
+    pub static CACHE_HIT_AGE: RefCell<Option<Duration>>;
+}
+
+#[derive(Clone)]
+struct CacheEntry {
+    inserted_at: Instant,
+    bytes: Arc<Vec<u8>>,
+    /// `bytes` holds LZ4 (size-prepended) JSON rather than raw JSON
+    compressed: bool,
+}
+
+/// Counters for entries compressed on insert
+#[derive(Default)]
+struct CacheCompressionCounters {
+    writes: AtomicU64,
+    compressed_writes: AtomicU64,
+    uncompressed_bytes: AtomicU64,
+    stored_bytes: AtomicU64,
+}
+
+/// Snapshot of cache size and compression savings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Entries currently held (approximate until pending evictions run)
+    pub entries: u64,
+    /// Entries of at least this many bytes are compressed; 0 means disabled
+    pub compression_threshold_bytes: usize,
+    pub writes: u64,
+    /// Writes that were stored compressed
+    pub compressed_writes: u64,
+    /// JSON size of the compressed writes
+    pub uncompressed_bytes: u64,
+    /// Stored size of the compressed writes
+    pub stored_bytes: u64,
+    /// uncompressed_bytes - stored_bytes
+    pub saved_bytes: u64,
+}
 
 #[allow(dead_code)]
 pub struct CacheManager {
-    cache: MokaCache<String, Vec<u8>>,
+    cache: MokaCache<String, CacheEntry>,
+    compression_threshold: usize,
+    counters: CacheCompressionCounters,
 }
 
 #[allow(dead_code)]
@@ -15,17 +64,82 @@ impl CacheManager {
             .time_to_live(Duration::from_secs(ttl_seconds))
             .build();
 
-        Self { cache }
+        Self {
+            cache,
+            compression_threshold: 0,
+            counters: CacheCompressionCounters::default(),
+        }
+    }
+
+    /// LZ4-compress entries whose JSON is at least `threshold` bytes (0 disables)
+    pub fn with_compression(mut self, threshold: usize) -> Self {
+        self.compression_threshold = threshold;
+        self
     }
 
     pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
-        let bytes = self.cache.get(key).await?;
-        serde_json::from_slice(&bytes).ok()
+        let (value, age) = self.get_with_age(key).await?;
+        let _ = CACHE_HIT_AGE.try_with(|slot| {
+            let mut slot = slot.borrow_mut();
+            *slot = Some(slot.map_or(age, |oldest| oldest.max(age)));
+        });
+        Some(value)
+    }
+
+    /// Cached value and how long ago it was stored. Misses inside a `FORCE_REFRESH` scope.
+    pub async fn get_with_age<T: DeserializeOwned>(&self, key: &str) -> Option<(T, Duration)> {
+        if FORCE_REFRESH.try_with(|force| *force).unwrap_or(false) {
+            return None;
+        }
+        let entry = self.cache.get(key).await?;
+        let value = if entry.compressed {
+            let bytes = lz4_flex::decompress_size_prepended(&entry.bytes).ok()?;
+            serde_json::from_slice(&bytes).ok()?
+        } else {
+            serde_json::from_slice(&entry.bytes).ok()?
+        };
+        Some((value, entry.inserted_at.elapsed()))
     }
 
     pub async fn set<T: Serialize>(&self, key: String, value: &T) {
         if let Ok(bytes) = serde_json::to_vec(value) {
-            self.cache.insert(key, bytes).await;
+            self.counters.writes.fetch_add(1, Ordering::Relaxed);
+            let (bytes, compressed) = self.maybe_compress(bytes);
+            let entry = CacheEntry {
+                inserted_at: Instant::now(),
+                bytes: Arc::new(bytes),
+                compressed,
+            };
+            self.cache.insert(key, entry).await;
+        }
+    }
+
+    /// Compress JSON over the threshold, keeping the raw bytes when LZ4 doesn't shrink them
+    fn maybe_compress(&self, bytes: Vec<u8>) -> (Vec<u8>, bool) {
+        if self.compression_threshold == 0 || bytes.len() < self.compression_threshold {
+            return (bytes, false);
+        }
+        let compressed = lz4_flex::compress_prepend_size(&bytes);
+        if compressed.len() >= bytes.len() {
+            return (bytes, false);
+        }
+        self.counters.compressed_writes.fetch_add(1, Ordering::Relaxed);
+        self.counters.uncompressed_bytes.fetch_add(bytes.len() as u64, Ordering::Relaxed);
+        self.counters.stored_bytes.fetch_add(compressed.len() as u64, Ordering::Relaxed);
+        (compressed, true)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let uncompressed_bytes = self.counters.uncompressed_bytes.load(Ordering::Relaxed);
+        let stored_bytes = self.counters.stored_bytes.load(Ordering::Relaxed);
+        CacheStats {
+            entries: self.cache.entry_count(),
+            compression_threshold_bytes: self.compression_threshold,
+            writes: self.counters.writes.load(Ordering::Relaxed),
+            compressed_writes: self.counters.compressed_writes.load(Ordering::Relaxed),
+            uncompressed_bytes,
+            stored_bytes,
+            saved_bytes: uncompressed_bytes.saturating_sub(stored_bytes),
         }
     }
 
@@ -78,6 +192,47 @@ mod tests {
         assert_eq!(retrieved, None);
     }
 
+    #[tokio::test]
+    async fn test_cache_hit_age_and_force_refresh() {
+        let cache = CacheManager::new(300, 1000);
+        let data = TestData {
+            value: "test".to_string(),
+        };
+        cache.set("test:age".to_string(), &data).await;
+
+        let age = CACHE_HIT_AGE
+            .scope(RefCell::new(None), async {
+                let _: Option<TestData> = cache.get("test:age").await;
+                CACHE_HIT_AGE.with(|slot| *slot.borrow())
+            })
+            .await;
+        assert!(age.is_some());
+
+        let refreshed: Option<TestData> = FORCE_REFRESH.scope(true, cache.get("test:age")).await;
+        assert_eq!(refreshed, None);
+    }
+
+    #[tokio::test]
+    async fn test_large_entries_compressed() {
+        let cache = CacheManager::new(300, 1000).with_compression(1024);
+        let large = TestData {
+            value: "onelogin ".repeat(500),
+        };
+        let small = TestData {
+            value: "small".to_string(),
+        };
+        cache.set("test:large".to_string(), &large).await;
+        cache.set("test:small".to_string(), &small).await;
+
+        assert_eq!(cache.get::<TestData>("test:large").await, Some(large));
+        assert_eq!(cache.get::<TestData>("test:small").await, Some(small));
+        let stats = cache.stats();
+        assert_eq!(stats.writes, 2);
+        assert_eq!(stats.compressed_writes, 1);
+        assert!(stats.saved_bytes > 0);
+        assert_eq!(stats.saved_bytes, stats.uncompressed_bytes - stats.stored_bytes);
+    }
+
     #[test]
     fn test_build_key() {
         let key = CacheManager::build_key("user", &["123", "profile"]);
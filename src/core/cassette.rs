@@ -0,0 +1,283 @@
+//! HTTP record-and-replay ("cassette") support for `HttpClient`.
+//!
+//! In record mode every API request/response pair is appended to a JSON file.
+//! In replay mode requests are answered from that file in recorded order, with
+//! no network access or credentials, so issues captured against a real tenant
+//! can be reproduced deterministically. Authorization headers and token requests
+//! are never recorded, and credentials in request and response bodies are
+//! redacted like logged traffic (`utils::redact`). Directory data such as names
+//! and email addresses is kept, so review a cassette before sharing it.
+
+use crate::core::error::{OneLoginError, Result};
+use crate::utils::redact::{redact_body, redact_json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Cassette file format version
+const CASSETTE_VERSION: u32 = 1;
+
+/// Closes the interaction list; each recording overwrites it with the new interaction
+const FILE_END: &str = "\n]}\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+/// A response as read off the wire (or from a cassette)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub content_type: String,
+    #[serde(default)]
+    pub body: String,
+    /// `After-Cursor` header of v2 list endpoints that page by cursor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    /// API path including query string, e.g. `/api/2/users?limit=50`
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Value>,
+    pub response: RecordedResponse,
+    #[serde(default)]
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    interactions: Vec<Interaction>,
+}
+
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// Replay: which recorded interactions have been served
+    used: Vec<bool>,
+    /// Record: interactions written to the file so far
+    recorded: usize,
+}
+
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+}
+
+impl Cassette {
+    /// Start a new recording at `path` (an existing file is overwritten on first write)
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        info!("Recording HTTP interactions to {}", path.display());
+        Self {
+            path,
+            mode: CassetteMode::Record,
+            state: Mutex::new(CassetteState {
+                interactions: Vec::new(),
+                used: Vec::new(),
+                recorded: 0,
+            }),
+        }
+    }
+
+    /// Load a recorded cassette for replay
+    pub fn replay(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read cassette {}: {}", path.display(), e))?;
+        let file: CassetteFile = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse cassette {}: {}", path.display(), e))?;
+        if file.version > CASSETTE_VERSION {
+            warn!(
+                "Cassette {} has version {}, newer than supported version {}",
+                path.display(),
+                file.version,
+                CASSETTE_VERSION
+            );
+        }
+        info!(
+            "Replaying {} HTTP interactions from {}",
+            file.interactions.len(),
+            path.display()
+        );
+        let used = vec![false; file.interactions.len()];
+        Ok(Self {
+            path,
+            mode: CassetteMode::Replay,
+            state: Mutex::new(CassetteState {
+                interactions: file.interactions,
+                used,
+                recorded: 0,
+            }),
+        })
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.mode == CassetteMode::Replay
+    }
+
+    /// Append an interaction to the cassette file
+    pub fn record_interaction(
+        &self,
+        method: &str,
+        path: &str,
+        request_body: Option<&Value>,
+        response: &RecordedResponse,
+    ) {
+        if self.mode != CassetteMode::Record {
+            return;
+        }
+        let mut state = self.state.lock().expect("Mutex poisoned");
+        let interaction = Interaction {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            request_body: request_body.map(redact_json),
+            response: RecordedResponse {
+                body: redact_body(&response.body),
+                ..response.clone()
+            },
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+        };
+        match append_interaction(&self.path, state.recorded, &interaction) {
+            Ok(()) => {
+                state.recorded += 1;
+                debug!("Recorded {} {} ({})", method, path, response.status);
+            }
+            Err(e) => warn!("Failed to write cassette {}: {}", self.path.display(), e),
+        }
+    }
+
+    /// Serve the next unused interaction matching method, path and (if recorded) body.
+    /// Once all matches are used, the last match is served again so polling loops terminate.
+    pub fn replay_interaction(
+        &self,
+        method: &str,
+        path: &str,
+        request_body: Option<&Value>,
+    ) -> Result<RecordedResponse> {
+        let mut state = self.state.lock().expect("Mutex poisoned");
+        let method = method.to_uppercase();
+        let body = request_body.map(redact_json);
+
+        let matches: Vec<usize> = state
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| {
+                i.method == method
+                    && i.path == path
+                    && (i.request_body.is_none() || i.request_body == body)
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let Some(&last) = matches.last() else {
+            return Err(OneLoginError::ApiRequestFailed(format!(
+                "No recorded interaction for {} {} in cassette {}",
+                method,
+                path,
+                self.path.display()
+            )));
+        };
+        let index = matches
+            .iter()
+            .copied()
+            .find(|&idx| !state.used[idx])
+            .unwrap_or(last);
+        state.used[index] = true;
+        debug!("Replaying interaction #{} for {} {}", index, method, path);
+        Ok(state.interactions[index].response.clone())
+    }
+}
+
+/// Write `interaction` after the `recorded` ones already in the file. The file is
+/// valid JSON after every call: the closing `]}` is overwritten and written again.
+fn append_interaction(path: &Path, recorded: usize, interaction: &Interaction) -> std::io::Result<()> {
+    let entry = serde_json::to_string(interaction)?;
+    if recorded == 0 {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let header = format!("{{\"version\":{},\"interactions\":[\n", CASSETTE_VERSION);
+        return std::fs::write(path, format!("{}{}{}", header, entry, FILE_END));
+    }
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    file.seek(SeekFrom::End(-(FILE_END.len() as i64)))?;
+    file.write_all(format!(",\n{}{}", entry, FILE_END).as_bytes())?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(status: u16, body: &str) -> RecordedResponse {
+        RecordedResponse {
+            status,
+            content_type: "application/json".to_string(),
+            body: body.to_string(),
+            after_cursor: None,
+        }
+    }
+
+    #[test]
+    fn test_record_then_replay_in_order() {
+        let path = std::env::temp_dir().join(format!("cassette-test-{}.json", std::process::id()));
+        let recorder = Cassette::record(&path);
+        recorder.record_interaction("GET", "/api/2/users/1", None, &response(500, "oops"));
+        recorder.record_interaction("GET", "/api/2/users/1", None, &response(200, "{\"id\":1}"));
+        recorder.record_interaction(
+            "POST",
+            "/api/2/users",
+            Some(&json!({"email": "a@example.com", "password": "hunter2"})),
+            &response(201, "{\"id\":2}"),
+        );
+        recorder.record_interaction(
+            "POST",
+            "/api/1/login/auth",
+            None,
+            &response(200, "{\"data\":[{\"session_token\":\"s3cret\",\"user\":{\"id\":1}}]}"),
+        );
+
+        let player = Cassette::replay(&path).unwrap();
+        assert_eq!(player.replay_interaction("GET", "/api/2/users/1", None).unwrap().status, 500);
+        assert_eq!(player.replay_interaction("GET", "/api/2/users/1", None).unwrap().status, 200);
+        // Exhausted: the last match repeats
+        assert_eq!(player.replay_interaction("GET", "/api/2/users/1", None).unwrap().status, 200);
+        // Bodies are matched after redaction, so secrets never need to be on disk
+        let created = player
+            .replay_interaction(
+                "POST",
+                "/api/2/users",
+                Some(&json!({"email": "a@example.com", "password": "different"})),
+            )
+            .unwrap();
+        assert_eq!(created.status, 201);
+        assert!(player.replay_interaction("DELETE", "/api/2/users/1", None).is_err());
+
+        let login = player.replay_interaction("POST", "/api/1/login/auth", None).unwrap();
+        let login: Value = serde_json::from_str(&login.body).unwrap();
+        assert_eq!(login["data"][0]["session_token"], "[REDACTED]");
+        assert_eq!(login["data"][0]["user"]["id"], 1);
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("hunter2"));
+        assert!(!saved.contains("s3cret"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
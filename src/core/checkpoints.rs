@@ -0,0 +1,156 @@
+//! Persistent event checkpoints.
+//!
+//! Stores the highest event id returned by each "get new events" stream, keyed
+//! by tenant and checkpoint name, so repeated calls only return newer events.
+//! Checkpoints survive restarts in `~/.config/onelogin-mcp/checkpoints.json`
+//! (override with `ONELOGIN_MCP_CHECKPOINTS`).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Overrides the default checkpoint file location
+pub const CHECKPOINTS_ENV_VAR: &str = "ONELOGIN_MCP_CHECKPOINTS";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Highest event id already returned
+    pub last_event_id: i64,
+    /// `created_at` of that event, as reported by OneLogin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_event_at: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CheckpointFile {
+    #[serde(default)]
+    checkpoints: HashMap<String, Checkpoint>,
+}
+
+pub struct CheckpointStore {
+    /// `None` keeps checkpoints in memory only (mock mode, tests)
+    path: Option<PathBuf>,
+    checkpoints: Mutex<HashMap<String, Checkpoint>>,
+}
+
+impl CheckpointStore {
+    /// Default checkpoint file: `ONELOGIN_MCP_CHECKPOINTS`, then the platform config dir
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var(CHECKPOINTS_ENV_VAR)
+            .map(PathBuf::from)
+            .ok()
+            .or_else(|| dirs::config_dir().map(|d| d.join("onelogin-mcp").join("checkpoints.json")))
+    }
+
+    /// Load checkpoints from `path`; a missing or unreadable file starts empty
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let checkpoints = match &path {
+            Some(p) if p.exists() => match Self::read_file(p) {
+                Ok(file) => file.checkpoints,
+                Err(e) => {
+                    warn!("Ignoring unreadable checkpoint file {}: {:#}", p.display(), e);
+                    HashMap::new()
+                }
+            },
+            _ => HashMap::new(),
+        };
+        Self {
+            path,
+            checkpoints: Mutex::new(checkpoints),
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        Self::load(None)
+    }
+
+    fn read_file(path: &Path) -> Result<CheckpointFile> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn key(tenant: &str, name: &str) -> String {
+        format!("{}:{}", tenant, name)
+    }
+
+    pub fn get(&self, tenant: &str, name: &str) -> Option<Checkpoint> {
+        self.checkpoints
+            .lock()
+            .expect("Mutex poisoned")
+            .get(&Self::key(tenant, name))
+            .cloned()
+    }
+
+    /// Advance a checkpoint. Never moves backwards.
+    pub fn advance(&self, tenant: &str, name: &str, last_event_id: i64, last_event_at: Option<String>) -> Result<()> {
+        let mut checkpoints = self.checkpoints.lock().expect("Mutex poisoned");
+        let key = Self::key(tenant, name);
+        if checkpoints.get(&key).is_some_and(|c| c.last_event_id >= last_event_id) {
+            return Ok(());
+        }
+        debug!("Advancing event checkpoint {} to {}", key, last_event_id);
+        checkpoints.insert(
+            key,
+            Checkpoint {
+                last_event_id,
+                last_event_at,
+                updated_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        self.save(&checkpoints)
+    }
+
+    pub fn reset(&self, tenant: &str, name: &str) -> Result<()> {
+        let mut checkpoints = self.checkpoints.lock().expect("Mutex poisoned");
+        if checkpoints.remove(&Self::key(tenant, name)).is_some() {
+            self.save(&checkpoints)?;
+        }
+        Ok(())
+    }
+
+    fn save(&self, checkpoints: &HashMap<String, Checkpoint>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+        }
+        let file = CheckpointFile {
+            checkpoints: checkpoints.clone(),
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&file)?)
+            .with_context(|| format!("Failed to write checkpoint file {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoints_persist_and_never_move_backwards() {
+        let path = std::env::temp_dir().join(format!("checkpoints-test-{}.json", std::process::id()));
+        let store = CheckpointStore::load(Some(path.clone()));
+        assert!(store.get("prod", "default").is_none());
+
+        store.advance("prod", "default", 100, None).unwrap();
+        store.advance("prod", "default", 50, None).unwrap();
+        store.advance("staging", "default", 7, None).unwrap();
+
+        let reloaded = CheckpointStore::load(Some(path.clone()));
+        assert_eq!(reloaded.get("prod", "default").unwrap().last_event_id, 100);
+        assert_eq!(reloaded.get("staging", "default").unwrap().last_event_id, 7);
+
+        reloaded.reset("prod", "default").unwrap();
+        assert!(CheckpointStore::load(Some(path.clone())).get("prod", "default").is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,156 @@
+//! Per-tenant circuit breaker around OneLogin requests.
+//!
+//! After `threshold` consecutive transport errors or 5xx responses the breaker
+//! opens and requests fail fast with `CircuitBreakerOpen` for `reset_after`.
+//! The next request is then let through half-open: success closes the breaker,
+//! failure opens it again. State changes are tracked for the metrics surfaces.
+
+use failsafe::backoff::{self, Constant};
+use failsafe::failure_policy::{self, ConsecutiveFailures};
+use failsafe::{Instrument, StateMachine};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const STATE_CLOSED: u8 = 0;
+const STATE_OPEN: u8 = 1;
+const STATE_HALF_OPEN: u8 = 2;
+
+#[derive(Default)]
+struct Counters {
+    state: AtomicU8,
+    trips: AtomicU64,
+    rejected_calls: AtomicU64,
+    failures: AtomicU64,
+}
+
+/// Records failsafe state transitions into `Counters`
+struct BreakerInstrument(Arc<Counters>);
+
+impl Instrument for BreakerInstrument {
+    fn on_call_rejected(&self) {
+        self.0.rejected_calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_open(&self) {
+        self.0.state.store(STATE_OPEN, Ordering::Relaxed);
+        self.0.trips.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_half_open(&self) {
+        self.0.state.store(STATE_HALF_OPEN, Ordering::Relaxed);
+    }
+
+    fn on_closed(&self) {
+        self.0.state.store(STATE_CLOSED, Ordering::Relaxed);
+    }
+}
+
+pub struct CircuitBreaker {
+    /// `None` when disabled (`CIRCUIT_BREAKER_THRESHOLD=0`)
+    machine: Option<StateMachine<ConsecutiveFailures<Constant>, BreakerInstrument>>,
+    counters: Arc<Counters>,
+    threshold: u32,
+    reset_after: Duration,
+}
+
+/// Snapshot of the breaker's state and counters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerStats {
+    pub enabled: bool,
+    /// closed, open or half_open
+    pub state: String,
+    /// Consecutive failures that open the breaker
+    pub failure_threshold: u32,
+    pub reset_after_seconds: u64,
+    /// Times the breaker has opened since startup
+    pub trips: u64,
+    /// Requests failed fast while open
+    pub rejected_calls: u64,
+    /// Transport errors and 5xx responses seen
+    pub failures: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, reset_after: Duration) -> Self {
+        let counters = Arc::new(Counters::default());
+        let machine = (threshold > 0).then(|| {
+            failsafe::Config::new()
+                .failure_policy(failure_policy::consecutive_failures(threshold, backoff::constant(reset_after)))
+                .instrument(BreakerInstrument(counters.clone()))
+                .build()
+        });
+        Self {
+            machine,
+            counters,
+            threshold,
+            reset_after,
+        }
+    }
+
+    /// Whether a request may be sent now
+    pub fn is_call_permitted(&self) -> bool {
+        self.machine.as_ref().is_none_or(|m| m.is_call_permitted())
+    }
+
+    pub fn record_success(&self) {
+        if let Some(machine) = &self.machine {
+            machine.on_success();
+        }
+    }
+
+    pub fn record_failure(&self) {
+        self.counters.failures.fetch_add(1, Ordering::Relaxed);
+        if let Some(machine) = &self.machine {
+            machine.on_error();
+        }
+    }
+
+    pub fn stats(&self) -> CircuitBreakerStats {
+        let state = match self.counters.state.load(Ordering::Relaxed) {
+            STATE_OPEN => "open",
+            STATE_HALF_OPEN => "half_open",
+            _ => "closed",
+        };
+        CircuitBreakerStats {
+            enabled: self.machine.is_some(),
+            state: state.to_string(),
+            failure_threshold: self.threshold,
+            reset_after_seconds: self.reset_after.as_secs(),
+            trips: self.counters.trips.load(Ordering::Relaxed),
+            rejected_calls: self.counters.rejected_calls.load(Ordering::Relaxed),
+            failures: self.counters.failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.is_call_permitted());
+        breaker.record_failure();
+        assert!(!breaker.is_call_permitted());
+
+        let stats = breaker.stats();
+        assert_eq!(stats.state, "open");
+        assert_eq!(stats.trips, 1);
+        assert_eq!(stats.rejected_calls, 1);
+        assert_eq!(stats.failures, 2);
+    }
+
+    #[test]
+    fn test_disabled_breaker_always_permits() {
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            breaker.record_failure();
+        }
+        assert!(breaker.is_call_permitted());
+        assert!(!breaker.stats().enabled);
+    }
+}
@@ -1,11 +1,71 @@
 use crate::core::auth::AuthManager;
+use crate::core::cache::CacheStats;
+use crate::core::cassette::{Cassette, RecordedResponse};
+use crate::core::circuit_breaker::{CircuitBreaker, CircuitBreakerStats};
+use crate::core::compression::{self, CompressionStats, CompressionStatsSnapshot};
 use crate::core::config::Config;
-use crate::core::error::{OneLoginError, Result};
-use crate::core::rate_limit::RateLimiter;
+use crate::core::error::{ApiError, OneLoginError, Result};
+use crate::core::mock::MockStore;
+use crate::core::rate_limit::{RateLimiter, RateLimiterStats};
+use crate::core::retry::{self, RetryBudget, RetryBudgetStats, RetryDiagnostics};
+use crate::core::usage_budget::{UsageBudget, UsageBudgetStats, UsageStore};
+use crate::utils::redact;
 use reqwest::{header, Method, StatusCode};
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, de::IgnoredAny, Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tracing::{debug, error, warn, instrument};
+use tracing::{debug, error, info, warn, instrument};
+
+tokio::task_local! {
+    /// Base URL of the fallback region when any response in this scope came from it
+    pub static SERVED_FROM_FALLBACK: std::cell::RefCell<Option<String>>;
+
+    /// `After-Cursor` header of the last successful response in this scope, for cursor-paged lists
+    pub static AFTER_CURSOR: std::cell::RefCell<Option<String>>;
+
+    /// Attempts, backoff and rate limiting accumulated by the requests in this scope
+    pub static RETRY_DIAGNOSTICS: std::cell::RefCell<RetryDiagnostics>;
+
+    /// Locale sent as `Accept-Language` by the requests in this scope
+    static ACCEPT_LANGUAGE: Option<String>;
+}
+
+fn record_diagnostics(update: impl FnOnce(&mut RetryDiagnostics)) {
+    let _ = RETRY_DIAGNOSTICS.try_with(|slot| update(&mut slot.borrow_mut()));
+}
+
+/// First 500 characters of a response body, with credentials redacted
+fn body_preview(body: &str) -> String {
+    redact::redact_body(body).chars().take(500).collect()
+}
+
+/// Error response body for the logs: credentials redacted, JSON pretty-printed
+fn loggable_error_body(content_type: &str, body: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(json) if content_type.contains("json") => {
+            serde_json::to_string_pretty(&redact::redact_json(&json)).unwrap_or_else(|_| redact::redact_body(body))
+        }
+        _ => redact::redact_body(body),
+    }
+}
+
+/// Secondary region of a tenant, used while the primary region's circuit breaker is open
+struct Fallback {
+    base_url: String,
+    circuit_breaker: CircuitBreaker,
+    requests: AtomicU64,
+}
+
+/// Fallback region state, for `ClientStats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FallbackStats {
+    pub base_url: String,
+    /// Whether requests are currently going to the fallback region
+    pub active: bool,
+    /// Requests sent to the fallback region since startup
+    pub requests: u64,
+    pub circuit_breaker: CircuitBreakerStats,
+}
 
 #[allow(dead_code)]
 pub struct HttpClient {
@@ -13,6 +73,39 @@ pub struct HttpClient {
     client: reqwest::Client,
     auth_manager: Arc<AuthManager>,
     rate_limiter: Arc<RateLimiter>,
+    /// When set, requests are answered from fixtures instead of the network
+    mock: Option<Arc<MockStore>>,
+    /// Record-and-replay of request/response pairs
+    cassette: Option<Arc<Cassette>>,
+    /// Wire vs decoded response sizes
+    compression: CompressionStats,
+    /// Fails requests fast while OneLogin is erroring
+    circuit_breaker: CircuitBreaker,
+    /// Caps retries; shared by all tenants when built by `TenantManager`
+    retry_budget: Arc<RetryBudget>,
+    /// Set when the tenant declares a `fallback_base_url`
+    fallback: Option<Fallback>,
+    /// Requests sent today and the optional daily budget
+    usage: UsageBudget,
+}
+
+/// Live counters of a tenant's HTTP client, for `onelogin_server_stats` and `/metrics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientStats {
+    pub rate_limiter: RateLimiterStats,
+    /// Limiter shared by all tenants (`GLOBAL_RATE_LIMIT_RPS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global_rate_limiter: Option<RateLimiterStats>,
+    pub circuit_breaker: CircuitBreakerStats,
+    pub compression: CompressionStatsSnapshot,
+    pub retry_budget: RetryBudgetStats,
+    /// Requests sent today against the tenant's daily budget
+    pub usage: UsageBudgetStats,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<FallbackStats>,
+    /// Response cache size and compression savings; filled in by `TenantManager`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheStats>,
 }
 
 #[allow(dead_code)]
@@ -25,22 +118,80 @@ impl HttpClient {
         let client = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
             .pool_max_idle_per_host(10)
+            .default_headers(config.default_headers())
             .build()
             .expect("Failed to build HTTP client");
 
+        let breaker = || {
+            CircuitBreaker::new(
+                config.circuit_breaker_threshold,
+                std::time::Duration::from_secs(config.circuit_breaker_reset_seconds),
+            )
+        };
         Self {
+            compression: CompressionStats::new(config.http_compression),
+            circuit_breaker: breaker(),
+            fallback: config.fallback_base_url.clone().map(|base_url| Fallback {
+                base_url,
+                circuit_breaker: breaker(),
+                requests: AtomicU64::new(0),
+            }),
+            retry_budget: Arc::new(RetryBudget::new(
+                config.retry_budget_ratio,
+                config.retry_budget_min_per_second,
+            )),
+            usage: UsageBudget::new(config.daily_request_budget, config.budget_exclude_reads),
             config,
             client,
             auth_manager,
             rate_limiter,
+            mock: None,
+            cassette: None,
         }
     }
 
+    /// Answer every request from `store` instead of calling OneLogin (no credentials needed)
+    pub fn with_mock(mut self, store: Arc<MockStore>) -> Self {
+        self.mock = Some(store);
+        self
+    }
+
+    /// Record every request/response pair to a cassette, or replay one instead of calling OneLogin
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Draw retries from a budget shared with other clients
+    pub fn with_retry_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.retry_budget = budget;
+        self
+    }
+
+    /// Keep the daily usage counters under `tenant` in a store shared with other clients
+    pub fn with_usage_store(mut self, tenant: &str, store: Arc<UsageStore>) -> Self {
+        self.usage = self.usage.with_store(tenant, store);
+        self
+    }
+
+    /// Whether this client is serving fixture data
+    pub fn is_mock(&self) -> bool {
+        self.mock.is_some()
+    }
+
     #[instrument(skip(self))]
     pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
         self.request(Method::GET, path, None::<&()>).await
     }
 
+    /// GET with an `Accept-Language` header, for endpoints that return translated content
+    #[instrument(skip(self))]
+    pub async fn get_localized<T: DeserializeOwned>(&self, path: &str, locale: Option<&str>) -> Result<T> {
+        ACCEPT_LANGUAGE
+            .scope(locale.map(String::from), self.request(Method::GET, path, None::<&()>))
+            .await
+    }
+
     #[instrument(skip(self, body))]
     pub async fn post<T: DeserializeOwned, B: Serialize>(
         &self,
@@ -73,6 +224,65 @@ impl HttpClient {
         self.request(Method::PATCH, path, body).await
     }
 
+    /// POST a form authenticated with another OAuth client's credentials (HTTP Basic)
+    /// instead of the server's token, e.g. to test that client's token flow. Returns the
+    /// status and JSON body whether or not the request succeeded; nothing is retried.
+    /// Counted against the daily request budget like every other request.
+    #[instrument(skip(self, client_secret, form))]
+    pub async fn post_form_as_client(
+        &self,
+        path: &str,
+        client_id: &str,
+        client_secret: &str,
+        form: &[(&str, &str)],
+    ) -> Result<(u16, serde_json::Value)> {
+        if self.mock.is_some() || self.cassette.is_some() {
+            return Err(OneLoginError::InvalidInput(
+                "Requests with other client credentials are not available with mock data or cassettes".to_string(),
+            ));
+        }
+        let url = self.config.api_url(path);
+        debug!("Making form POST to {} as client {}", url, client_id);
+        self.usage.charge(&Method::POST).map_err(OneLoginError::BudgetExceeded)?;
+        self.rate_limiter.wait().await;
+        let response = self
+            .client
+            .post(&url)
+            .basic_auth(client_id, Some(client_secret))
+            .form(form)
+            .send()
+            .await?;
+        let status = response.status().as_u16();
+        let bytes = response.bytes().await?;
+        let body = serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+        Ok((status, body))
+    }
+
+    /// POST and discard the response body (for 204 No Content / status-only responses)
+    #[instrument(skip(self, body))]
+    pub async fn post_no_content<B: Serialize>(&self, path: &str, body: Option<&B>) -> Result<()> {
+        self.request::<IgnoredAny, B>(Method::POST, path, body)
+            .await
+            .map(|_| ())
+    }
+
+    /// PUT and discard the response body (for 204 No Content / status-only responses)
+    #[instrument(skip(self, body))]
+    pub async fn put_no_content<B: Serialize>(&self, path: &str, body: Option<&B>) -> Result<()> {
+        self.request::<IgnoredAny, B>(Method::PUT, path, body)
+            .await
+            .map(|_| ())
+    }
+
+    /// DELETE and discard the response body (for 204 No Content / status-only responses)
+    #[instrument(skip(self))]
+    pub async fn delete_no_content(&self, path: &str) -> Result<()> {
+        self.request::<IgnoredAny, ()>(Method::DELETE, path, None)
+            .await
+            .map(|_| ())
+    }
+
     #[instrument(skip(self, body))]
     async fn request<T: DeserializeOwned, B: Serialize>(
         &self,
@@ -80,121 +290,322 @@ impl HttpClient {
         path: &str,
         body: Option<&B>,
     ) -> Result<T> {
+        if let Some(store) = &self.mock {
+            return self.mock_request(store, method, path, body);
+        }
+
         let max_retries = self.config.max_retries;
         let mut attempt = 0;
+        let replaying = self.cassette.as_ref().filter(|c| c.is_replay());
+        let recording = self.cassette.as_ref().filter(|c| !c.is_replay());
+        let body_value = match (&self.cassette, body) {
+            (Some(_), Some(b)) => serde_json::to_value(b).ok(),
+            _ => None,
+        };
 
         loop {
-            // Apply rate limiting
-            self.rate_limiter.wait().await;
-
-            // Get access token
-            let token = self.auth_manager.get_token().await?;
-
             // Build URL
-            let url = self.config.api_url(path);
+            let mut url = self.config.api_url(path);
             if attempt == 0 {
                 debug!("Making {} request to {}", method, url);
             } else {
                 debug!("Retry attempt {} for {} request to {}", attempt, method, url);
             }
+            record_diagnostics(|d| {
+                d.attempts += 1;
+                if attempt > 0 {
+                    d.retries += 1;
+                }
+            });
 
-            // Build request
-            let mut request = self
-                .client
-                .request(method.clone(), &url)
-                .header(header::AUTHORIZATION, format!("Bearer {}", token));
-
-            // Add body if provided
-            let request_body_debug = if let Some(b) = body {
-                let body_json = serde_json::to_string(b).unwrap_or_else(|_| "<serialization error>".to_string());
-                debug!("Request body: {}", body_json);
-                request = request.json(b);
-                Some(body_json)
+            let raw = if let Some(cassette) = replaying {
+                cassette.replay_interaction(method.as_str(), path, body_value.as_ref())?
             } else {
-                None
-            };
+                if attempt == 0 {
+                    self.retry_budget.record_request();
+                }
+                // The primary region is used whenever its breaker lets calls through (including
+                // the half-open trial that moves traffic back); otherwise the fallback region, if any
+                let breaker = if self.circuit_breaker.is_call_permitted() {
+                    &self.circuit_breaker
+                } else {
+                    match self.fallback.as_ref().filter(|f| f.circuit_breaker.is_call_permitted()) {
+                        Some(fallback) => {
+                            url = self.config.api_url_at(&fallback.base_url, path);
+                            fallback.requests.fetch_add(1, Ordering::Relaxed);
+                            let _ = SERVED_FROM_FALLBACK.try_with(|slot| {
+                                *slot.borrow_mut() = Some(fallback.base_url.clone());
+                            });
+                            debug!("Primary region unavailable, sending {} {} to the fallback region", method, url);
+                            &fallback.circuit_breaker
+                        }
+                        None => {
+                            return Err(OneLoginError::CircuitBreakerOpen(format!(
+                                "OneLogin requests are failing; {} {} was not sent. Retrying after {}s",
+                                method, url, self.config.circuit_breaker_reset_seconds
+                            )));
+                        }
+                    }
+                };
+
+                // Charged only once the request will really be sent. Retries are charged
+                // too: each one uses up the tenant's allocation
+                self.usage.charge(&method).map_err(OneLoginError::BudgetExceeded)?;
+
+                // Apply rate limiting
+                let waiting_since = std::time::Instant::now();
+                self.rate_limiter.wait().await;
+                let waited_ms = waiting_since.elapsed().as_millis() as u64;
+                record_diagnostics(|d| d.record_rate_limit_wait(waited_ms));
+
+                // Get access token
+                let token = self.auth_manager.get_token().await?;
+
+                // Build request
+                let mut request = self
+                    .client
+                    .request(method.clone(), &url)
+                    .header(header::AUTHORIZATION, format!("Bearer {}", token));
+                if self.config.http_compression {
+                    request = request.header(header::ACCEPT_ENCODING, compression::ACCEPT_ENCODING);
+                }
+                if let Some(locale) = ACCEPT_LANGUAGE.try_with(|l| l.clone()).ok().flatten() {
+                    request = request.header(header::ACCEPT_LANGUAGE, locale);
+                }
 
-            // Execute request
-            let response = match request.send().await {
-                Ok(resp) => resp,
-                Err(e) => {
-                    let error = OneLoginError::HttpClientError(e);
-                    if attempt < max_retries && error.is_retriable() {
-                        attempt += 1;
+                // Add body if provided
+                let request_body_debug = if let Some(b) = body {
+                    let body_json = serde_json::to_string(b).unwrap_or_else(|_| "<serialization error>".to_string());
+                    debug!("Request body: {}", redact::redact_body(&body_json));
+                    request = request.json(b);
+                    Some(body_json)
+                } else {
+                    None
+                };
+
+                if self.config.log_http_bodies {
+                    self.log_request(&request, &method, &url, request_body_debug.as_deref());
+                }
+
+                // Execute request
+                let response = match request.send().await {
+                    Ok(resp) => resp,
+                    Err(e) => {
+                        breaker.record_failure();
+                        let error = OneLoginError::HttpClientError(e);
+                        if self.should_retry(attempt, &method, &error, false) {
+                            attempt += 1;
+                            error!(
+                                "HTTP request failed (attempt {}/{}): {} {} - Error: {} - Will retry after backoff",
+                                attempt, max_retries, method, url, error
+                            );
+                            self.exponential_backoff(attempt).await;
+                            continue;
+                        }
                         error!(
-                            "HTTP request failed (attempt {}/{}): {} {} - Error: {} - Will retry after backoff",
-                            attempt, max_retries, method, url, error
+                            "HTTP request failed permanently: {} {} - Error: {} - Request body: {:?}",
+                            method, url, error, request_body_debug.as_deref().map(redact::redact_body)
                         );
-                        self.exponential_backoff(attempt).await;
-                        continue;
+                        return Err(error);
                     }
-                    error!(
-                        "HTTP request failed permanently: {} {} - Error: {} - Request body: {:?}",
-                        method, url, error, request_body_debug
-                    );
-                    return Err(error);
+                };
+
+                let raw = Self::read_response(response, &method, &url, &self.compression).await?;
+                if StatusCode::from_u16(raw.status).is_ok_and(|s| s.is_server_error()) {
+                    breaker.record_failure();
+                } else {
+                    breaker.record_success();
                 }
+                if let Some(cassette) = recording {
+                    cassette.record_interaction(method.as_str(), path, body_value.as_ref(), &raw);
+                }
+                raw
             };
 
-            let status = response.status();
+            let status = StatusCode::from_u16(raw.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
             debug!("Received response with status: {} for {} {}", status, method, url);
+            if self.config.log_http_bodies {
+                info!(
+                    "HTTP response {} {} -> {} ({})\nBody: {}",
+                    method, url, status, raw.content_type, redact::redact_body(&raw.body)
+                );
+            }
 
             if !status.is_success() {
-                let result = self.handle_error_response(status, response, &method, &url).await;
+                let result = self.handle_error_response(status, &raw, &method, &url).await;
                 if let Err(ref e) = result {
-                    if attempt < max_retries && e.is_retriable() {
+                    if self.should_retry(attempt, &method, e, replaying.is_some()) {
                         attempt += 1;
                         warn!(
                             "Non-success response (attempt {}/{}): {} {} - Status: {} - Error: {} - Will retry",
                             attempt, max_retries, method, url, status, e
                         );
-                        self.exponential_backoff(attempt).await;
+                        if replaying.is_none() {
+                            self.exponential_backoff(attempt).await;
+                        }
                         continue;
                     }
                 }
                 return result;
             }
 
-            return self.parse_success_response(response, &method, &url).await;
+            if let Some(cursor) = raw.after_cursor.as_ref().filter(|c| !c.is_empty()) {
+                let _ = AFTER_CURSOR.try_with(|slot| *slot.borrow_mut() = Some(cursor.clone()));
+            }
+            return self.parse_success_body(status, &raw.content_type, raw.body.as_bytes(), &method, &url);
         }
     }
 
+    /// Log an outgoing request with credentials redacted (`LOG_HTTP_BODIES`)
+    fn log_request(&self, request: &reqwest::RequestBuilder, method: &Method, url: &str, body: Option<&str>) {
+        let mut headers = self.config.default_headers();
+        if let Some(built) = request.try_clone().and_then(|r| r.build().ok()) {
+            headers.extend(built.headers().clone());
+        }
+        let headers: Vec<String> = headers
+            .iter()
+            .map(|(name, value)| {
+                let value = value.to_str().unwrap_or("<binary>");
+                format!("{}: {}", name, redact::redact_header(name.as_str(), value))
+            })
+            .collect();
+        info!(
+            "HTTP request {} {}\nHeaders: {}\nBody: {}",
+            method,
+            url,
+            headers.join(", "),
+            body.map(redact::redact_body).unwrap_or_default()
+        );
+    }
+
+    /// Serve a request from the in-memory fixture store (mock mode)
+    fn mock_request<T: DeserializeOwned, B: Serialize>(
+        &self,
+        store: &MockStore,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T> {
+        let url = self.config.api_url(path);
+        debug!("Serving {} {} from mock fixtures", method, url);
+        let body = body.map(serde_json::to_value).transpose()?;
+        let response = store.handle(method.as_str(), path, body)?;
+        let (status, bytes) = match response {
+            Some(value) => (StatusCode::OK, serde_json::to_vec(&value)?),
+            None => (StatusCode::NO_CONTENT, Vec::new()),
+        };
+        self.parse_success_body(status, "application/json", &bytes, &method, &url)
+    }
+
+    /// Retry only when attempts remain, the error is transient, resending is safe for the
+    /// method (or the request was never processed) and the retry budget allows it.
+    /// Replays repeat the recorded attempts without consulting the budget.
+    fn should_retry(
+        &self,
+        attempt: u32,
+        method: &Method,
+        error: &OneLoginError,
+        replaying: bool,
+    ) -> bool {
+        if attempt >= self.config.max_retries || !error.is_retryable() {
+            return false;
+        }
+        if !retry::is_idempotent(method) && !retry::was_not_processed(error) {
+            debug!("Not retrying {} that may have been processed: {}", method, error);
+            self.retry_budget.record_not_idempotent();
+            return false;
+        }
+        if replaying || self.retry_budget.try_retry() {
+            return true;
+        }
+        warn!("Retry budget exhausted, not retrying {} after: {}", method, error);
+        false
+    }
+
     async fn exponential_backoff(&self, attempt: u32) {
         let delay_ms = std::cmp::min(
             self.config.retry_initial_delay_ms * 2u64.pow(attempt - 1),
             self.config.retry_max_delay_ms,
         );
         debug!("Waiting {}ms before retry", delay_ms);
+        record_diagnostics(|d| d.total_backoff_ms += delay_ms);
         tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
     }
 
-    async fn parse_success_response<T: DeserializeOwned>(
-        &self,
+    /// Read status, content type and body. Body read failures are errors for
+    /// successful responses; for error responses the failure text stands in for the body.
+    async fn read_response(
         response: reqwest::Response,
         method: &Method,
         url: &str,
-    ) -> Result<T> {
+        stats: &CompressionStats,
+    ) -> Result<RecordedResponse> {
         let status = response.status();
-        let headers = response.headers().clone();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("unknown")
+            .to_string();
+        let encoding = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let after_cursor = response
+            .headers()
+            .get("after-cursor")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
 
-        let bytes = response.bytes().await.map_err(|e| {
-            error!(
-                "Failed to read response body for {} {} - Status: {} - Error: {}",
-                method, url, status, e
-            );
-            OneLoginError::InvalidResponse(format!(
-                "Failed to read response body for {} {} (status {}): {}",
-                method, url, status, e
-            ))
-        })?;
+        let decoded = match response.bytes().await {
+            Ok(bytes) => compression::decode(encoding.as_deref(), &bytes)
+                .inspect(|decoded| stats.record(encoding.is_some(), bytes.len(), decoded.len()))
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let body = match decoded {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) if status.is_success() => {
+                error!(
+                    "Failed to read response body for {} {} - Status: {} - Error: {}",
+                    method, url, status, e
+                );
+                return Err(OneLoginError::InvalidResponse(format!(
+                    "Failed to read response body for {} {} (status {}): {}",
+                    method, url, status, e
+                )));
+            }
+            Err(e) => {
+                error!("Failed to read error response body: {}", e);
+                format!("<failed to read body: {}>", e)
+            }
+        };
 
+        Ok(RecordedResponse {
+            status: status.as_u16(),
+            content_type,
+            body,
+            after_cursor,
+        })
+    }
+
+    /// Deserialize a successful response body, tolerating empty bodies, envelopes and non-JSON text
+    fn parse_success_body<T: DeserializeOwned>(
+        &self,
+        status: StatusCode,
+        content_type: &str,
+        bytes: &[u8],
+        method: &Method,
+        url: &str,
+    ) -> Result<T> {
         // Handle various "empty" or "success indicator" responses
-        let body = if bytes.is_empty() {
+        let body = if bytes.is_empty() || status == StatusCode::NO_CONTENT {
             debug!("Empty response body for {} {}, treating as null", method, url);
             b"null".to_vec()
         } else {
             // Check for plain text success indicators (e.g., "Accepted" for 202 responses)
-            let text = String::from_utf8_lossy(&bytes);
+            let text = String::from_utf8_lossy(bytes);
             let trimmed = text.trim();
 
             // If it's a simple success indicator text (not JSON), treat as null
@@ -212,16 +623,47 @@ impl HttpClient {
 
         // Try to parse the response
         let body_str = String::from_utf8_lossy(&body);
-        debug!("Response body for {} {} (first 500 chars): {}", method, url, &body_str.chars().take(500).collect::<String>());
+        debug!("Response body for {} {} (first 500 chars): {}", method, url, body_preview(&body_str));
+
+        // v1-style {status, data} envelopes: unwrap when the caller expects the payload itself
+        if let Ok(serde_json::Value::Object(mut envelope)) = serde_json::from_slice::<serde_json::Value>(&body) {
+            if envelope.contains_key("status") || envelope.contains_key("pagination") {
+                if let Some(data) = envelope.remove("data") {
+                    if serde_json::from_slice::<T>(&body).is_err() {
+                        // Single-object lookups in v1 arrive as a one-element data array
+                        let single = match &data {
+                            serde_json::Value::Array(items) if items.len() == 1 => Some(items[0].clone()),
+                            _ => None,
+                        };
+                        if let Ok(v) = serde_json::from_value::<T>(data) {
+                            debug!("Unwrapped data envelope for {} {}", method, url);
+                            return Ok(v);
+                        }
+                        if let Some(Ok(v)) = single.map(serde_json::from_value::<T>) {
+                            debug!("Unwrapped single-item data envelope for {} {}", method, url);
+                            return Ok(v);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Non-JSON bodies (e.g. SAML metadata XML) can still be read into String targets
+        if serde_json::from_slice::<serde_json::Value>(&body).is_err() {
+            if let Ok(v) = serde_json::from_value::<T>(serde_json::Value::String(body_str.to_string())) {
+                return Ok(v);
+            }
+        }
 
         serde_json::from_slice::<T>(&body).map_err(|e| {
             // Get the expected type name if possible
             let type_name = std::any::type_name::<T>();
 
             // Try to parse as generic JSON to see what we actually got
-            let actual_structure = match serde_json::from_slice::<serde_json::Value>(&body) {
-                Ok(v) => {
-                    match &v {
+            let raw = serde_json::from_slice::<serde_json::Value>(&body).ok();
+            let actual_structure = match &raw {
+                Some(v) => {
+                    match v {
                         serde_json::Value::Null => "null".to_string(),
                         serde_json::Value::Bool(_) => "boolean".to_string(),
                         serde_json::Value::Number(_) => "number".to_string(),
@@ -243,13 +685,9 @@ impl HttpClient {
                         }
                     }
                 },
-                Err(_) => "invalid JSON".to_string()
+                None => "invalid JSON".to_string()
             };
 
-            let content_type = headers.get("content-type")
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or("unknown");
-
             error!(
                 "JSON PARSING FAILED for {} {}\n\
                  Status: {}\n\
@@ -264,7 +702,7 @@ impl HttpClient {
                 body.len()
             );
 
-            OneLoginError::InvalidResponse(format!(
+            let message = format!(
                 "JSON parsing failed for {} {} (status {})\n\
                  Expected: {}\n\
                  Actual: {}\n\
@@ -276,38 +714,29 @@ impl HttpClient {
                  This usually means the API returned a different structure than expected.\n\
                  Check the response body above to see what was actually returned.",
                 method, url, status, type_name, actual_structure, e, content_type,
-                &body_str.chars().take(500).collect::<String>(),
+                body_preview(&body_str),
                 body.len()
-            ))
+            );
+            match raw {
+                Some(raw) => OneLoginError::UnexpectedResponse { message, raw: Box::new(raw) },
+                None => OneLoginError::InvalidResponse(message),
+            }
         })
     }
 
     async fn handle_error_response<T>(
         &self,
         status: StatusCode,
-        response: reqwest::Response,
+        response: &RecordedResponse,
         method: &Method,
         url: &str,
     ) -> Result<T> {
-        let headers = response.headers().clone();
-        let content_type = headers.get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("unknown");
-
-        let body = response.text().await.unwrap_or_else(|e| {
-            error!("Failed to read error response body: {}", e);
-            format!("<failed to read body: {}>", e)
-        });
-
-        // Try to pretty-print JSON error responses
-        let formatted_body = if content_type.contains("json") {
-            match serde_json::from_str::<serde_json::Value>(&body) {
-                Ok(json) => serde_json::to_string_pretty(&json).unwrap_or(body.clone()),
-                Err(_) => body.clone()
-            }
-        } else {
-            body.clone()
-        };
+        let content_type = response.content_type.as_str();
+        let body = response.body.clone();
+
+        let formatted_body = loggable_error_body(content_type, &body);
+
+        let api_error = OneLoginError::Api(Box::new(ApiError::parse(status.as_u16(), method.as_str(), url, &body)));
 
         match status.as_u16() {
             401 => {
@@ -321,10 +750,7 @@ impl HttpClient {
                     method, url, content_type, formatted_body
                 );
                 self.auth_manager.invalidate_token().await;
-                Err(OneLoginError::AuthenticationFailed(format!(
-                    "Authentication failed for {} {}\nStatus: 401\nResponse: {}",
-                    method, url, formatted_body
-                )))
+                Err(api_error)
             }
             403 => {
                 error!(
@@ -336,10 +762,7 @@ impl HttpClient {
                      The authenticated user does not have permission to access this resource.",
                     method, url, content_type, formatted_body
                 );
-                Err(OneLoginError::PermissionDenied(format!(
-                    "Permission denied for {} {}\nStatus: 403\nResponse: {}",
-                    method, url, formatted_body
-                )))
+                Err(api_error)
             }
             404 => {
                 error!(
@@ -351,12 +774,11 @@ impl HttpClient {
                      The requested resource does not exist.",
                     method, url, content_type, formatted_body
                 );
-                Err(OneLoginError::NotFound(format!(
-                    "Resource not found for {} {}\nStatus: 404\nResponse: {}",
-                    method, url, formatted_body
-                )))
+                Err(api_error)
             }
             429 => {
+                self.rate_limiter.record_upstream_limit();
+                record_diagnostics(|d| d.rate_limited = true);
                 error!(
                     "RATE LIMIT EXCEEDED for {} {}\n\
                      Status: 429 Too Many Requests\n\
@@ -378,10 +800,7 @@ impl HttpClient {
                      The request was malformed or contains invalid parameters.",
                     method, url, content_type, formatted_body
                 );
-                Err(OneLoginError::ApiRequestFailed(format!(
-                    "Bad request for {} {}\nStatus: 400\nResponse: {}",
-                    method, url, formatted_body
-                )))
+                Err(api_error)
             }
             500..=599 => {
                 error!(
@@ -393,10 +812,7 @@ impl HttpClient {
                      The OneLogin API server encountered an error. This is typically a temporary issue.",
                     method, url, status, content_type, formatted_body
                 );
-                Err(OneLoginError::ApiRequestFailed(format!(
-                    "Server error for {} {}\nStatus: {}\nResponse: {}",
-                    method, url, status, formatted_body
-                )))
+                Err(api_error)
             }
             _ => {
                 error!(
@@ -408,10 +824,7 @@ impl HttpClient {
                      An unexpected error occurred.",
                     method, url, status, content_type, formatted_body
                 );
-                Err(OneLoginError::ApiRequestFailed(format!(
-                    "Request failed for {} {}\nStatus: {}\nResponse: {}",
-                    method, url, status, formatted_body
-                )))
+                Err(api_error)
             }
         }
     }
@@ -423,4 +836,55 @@ impl HttpClient {
     pub fn http_client(&self) -> &reqwest::Client {
         &self.client
     }
+
+    pub fn rate_limiter_stats(&self) -> RateLimiterStats {
+        self.rate_limiter.stats()
+    }
+
+    pub fn compression_stats(&self) -> CompressionStatsSnapshot {
+        self.compression.snapshot()
+    }
+
+    pub fn circuit_breaker_stats(&self) -> CircuitBreakerStats {
+        self.circuit_breaker.stats()
+    }
+
+    pub fn stats(&self) -> ClientStats {
+        ClientStats {
+            rate_limiter: self.rate_limiter_stats(),
+            global_rate_limiter: self.rate_limiter.global_stats(),
+            circuit_breaker: self.circuit_breaker_stats(),
+            compression: self.compression_stats(),
+            retry_budget: self.retry_budget.stats(),
+            usage: self.usage.stats(),
+            fallback: self.fallback.as_ref().map(|f| FallbackStats {
+                base_url: f.base_url.clone(),
+                active: self.circuit_breaker.stats().state != "closed",
+                requests: f.requests.load(Ordering::Relaxed),
+                circuit_breaker: f.circuit_breaker.stats(),
+            }),
+            cache: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logged_bodies_never_contain_passwords() {
+        let body = r#"{"email":"a@example.com","password":"hunter2","state_token":"abc"}"#;
+        for logged in [
+            body_preview(body),
+            loggable_error_body("application/json", body),
+            loggable_error_body("text/plain", body),
+            loggable_error_body("application/x-www-form-urlencoded", "username=a&password=hunter2"),
+        ] {
+            assert!(!logged.contains("hunter2"), "{}", logged);
+            assert!(logged.contains(redact::REDACTED), "{}", logged);
+        }
+        assert!(!body_preview(body).contains("abc"));
+        assert!(loggable_error_body("application/json", body).contains("a@example.com"));
+    }
 }
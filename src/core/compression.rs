@@ -0,0 +1,124 @@
+//! Compressed response negotiation for `HttpClient`.
+//!
+//! Responses are decoded here rather than by reqwest so the wire size stays
+//! visible: every response records its encoded and decoded byte counts, which
+//! `onelogin_get_rate_limits` reports as the compression ratio.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// `Accept-Encoding` sent when compression is enabled, preferred first
+pub const ACCEPT_ENCODING: &str = "br, gzip";
+
+/// Decode a response body according to its `Content-Encoding`
+pub fn decode(encoding: Option<&str>, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let encoding = encoding.map(|e| e.trim().to_ascii_lowercase());
+    match encoding.as_deref() {
+        None | Some("") | Some("identity") => Ok(bytes.to_vec()),
+        Some("gzip") | Some("x-gzip") => {
+            let mut out = Vec::with_capacity(bytes.len() * 4);
+            flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut out)?;
+            Ok(out)
+        }
+        Some("br") => {
+            let mut out = Vec::with_capacity(bytes.len() * 4);
+            brotli::Decompressor::new(bytes, 4096).read_to_end(&mut out)?;
+            Ok(out)
+        }
+        Some(other) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Unsupported Content-Encoding '{}'", other),
+        )),
+    }
+}
+
+/// Counters for response sizes on the wire and after decoding
+#[derive(Default)]
+pub struct CompressionStats {
+    enabled: AtomicBool,
+    responses: AtomicU64,
+    compressed_responses: AtomicU64,
+    wire_bytes: AtomicU64,
+    decoded_bytes: AtomicU64,
+}
+
+/// Snapshot of `CompressionStats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionStatsSnapshot {
+    /// Whether compressed responses are requested (`HTTP_COMPRESSION`)
+    pub enabled: bool,
+    pub responses: u64,
+    /// Responses that arrived gzip or brotli encoded
+    pub compressed_responses: u64,
+    /// Bytes received from OneLogin
+    pub wire_bytes: u64,
+    /// Bytes after decoding
+    pub decoded_bytes: u64,
+    /// decoded_bytes / wire_bytes; 1.0 means no saving
+    pub ratio: f64,
+}
+
+impl CompressionStats {
+    pub fn new(enabled: bool) -> Self {
+        let stats = Self::default();
+        stats.enabled.store(enabled, Ordering::Relaxed);
+        stats
+    }
+
+    pub fn record(&self, compressed: bool, wire_bytes: usize, decoded_bytes: usize) {
+        self.responses.fetch_add(1, Ordering::Relaxed);
+        if compressed {
+            self.compressed_responses.fetch_add(1, Ordering::Relaxed);
+        }
+        self.wire_bytes.fetch_add(wire_bytes as u64, Ordering::Relaxed);
+        self.decoded_bytes.fetch_add(decoded_bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> CompressionStatsSnapshot {
+        let wire_bytes = self.wire_bytes.load(Ordering::Relaxed);
+        let decoded_bytes = self.decoded_bytes.load(Ordering::Relaxed);
+        let ratio = if wire_bytes == 0 {
+            1.0
+        } else {
+            (decoded_bytes as f64 / wire_bytes as f64 * 100.0).round() / 100.0
+        };
+        CompressionStatsSnapshot {
+            enabled: self.enabled.load(Ordering::Relaxed),
+            responses: self.responses.load(Ordering::Relaxed),
+            compressed_responses: self.compressed_responses.load(Ordering::Relaxed),
+            wire_bytes,
+            decoded_bytes,
+            ratio,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_decode_gzip_brotli_and_identity() {
+        let body = br#"{"data":[{"id":1,"email":"a@example.com"},{"id":2,"email":"b@example.com"}]}"#.repeat(20);
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(&body).unwrap();
+        let gz = gz.finish().unwrap();
+        assert_eq!(decode(Some("gzip"), &gz).unwrap(), body);
+
+        let mut br = Vec::new();
+        brotli::CompressorWriter::new(&mut br, 4096, 5, 22).write_all(&body).unwrap();
+        assert_eq!(decode(Some("br"), &br).unwrap(), body);
+
+        assert_eq!(decode(None, &body).unwrap(), body);
+        assert!(decode(Some("zstd"), &body).is_err());
+
+        let stats = CompressionStats::new(true);
+        stats.record(true, gz.len(), body.len());
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.compressed_responses, 1);
+        assert!(snapshot.ratio > 5.0);
+    }
+}
@@ -20,6 +20,120 @@ pub struct Config {
     pub retry_max_delay_ms: u64,
     /// Path to tool configuration file (JSON)
     pub tool_config_path: Option<PathBuf>,
+    /// User-Agent sent with every OneLogin request
+    pub user_agent: String,
+    /// Extra headers sent with every OneLogin request (e.g. for egress proxies)
+    pub extra_headers: Vec<(String, String)>,
+    /// Request gzip/brotli compressed responses
+    pub http_compression: bool,
+    /// Consecutive failures that open the circuit breaker (0 disables it)
+    pub circuit_breaker_threshold: u32,
+    /// How long the circuit breaker stays open before a trial request
+    pub circuit_breaker_reset_seconds: u64,
+    /// Retries earned per request, shared across tenants
+    pub retry_budget_ratio: f64,
+    /// Retries always allowed per second regardless of the ratio
+    pub retry_budget_min_per_second: u32,
+    /// Secondary base URL used while the primary region's circuit breaker is open
+    pub fallback_base_url: Option<String>,
+    /// Cache entries of at least this many JSON bytes are stored LZ4-compressed (0 disables)
+    pub cache_compression_threshold: usize,
+    /// Requests per second shared by all tenants (e.g. behind one egress IP); `None` disables
+    pub global_rate_limit_rps: Option<u32>,
+    /// Log every request and response body (secrets redacted) for debugging
+    pub log_http_bodies: bool,
+    /// OneLogin API requests allowed per UTC day; `None` only tracks usage
+    pub daily_request_budget: Option<u64>,
+    /// GET requests neither use up nor are refused by the daily budget
+    pub budget_exclude_reads: bool,
+}
+
+/// User-Agent when `ONELOGIN_USER_AGENT` is not set
+pub const DEFAULT_USER_AGENT: &str = concat!("onelogin-mcp-server/", env!("CARGO_PKG_VERSION"));
+
+/// `ONELOGIN_USER_AGENT` with `{version}` replaced by the crate version
+fn user_agent_from_env() -> String {
+    match env::var("ONELOGIN_USER_AGENT") {
+        Ok(agent) if !agent.trim().is_empty() => agent.trim().replace("{version}", env!("CARGO_PKG_VERSION")),
+        _ => DEFAULT_USER_AGENT.to_string(),
+    }
+}
+
+/// Parse `ONELOGIN_EXTRA_HEADERS`: `Name: value` pairs separated by `;` or newlines
+fn parse_extra_headers(raw: &str) -> Result<Vec<(String, String)>> {
+    let mut headers = Vec::new();
+    for entry in raw.split([';', '\n']).map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, value) = entry
+            .split_once(':')
+            .with_context(|| format!("Invalid ONELOGIN_EXTRA_HEADERS entry '{}': expected 'Name: value'", entry))?;
+        let (name, value) = (name.trim(), value.trim());
+        reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("Invalid header name '{}' in ONELOGIN_EXTRA_HEADERS", name))?;
+        reqwest::header::HeaderValue::from_str(value)
+            .with_context(|| format!("Invalid value for header '{}' in ONELOGIN_EXTRA_HEADERS", name))?;
+        if name.eq_ignore_ascii_case("authorization") || name.eq_ignore_ascii_case("user-agent") {
+            anyhow::bail!("ONELOGIN_EXTRA_HEADERS cannot set '{}'", name);
+        }
+        headers.push((name.to_string(), value.to_string()));
+    }
+    Ok(headers)
+}
+
+/// `GLOBAL_RATE_LIMIT_RPS`; unset or 0 means no cap across tenants
+fn global_rate_limit_from_env() -> Result<Option<u32>> {
+    Ok(Some(env_number("GLOBAL_RATE_LIMIT_RPS", 0u32)?).filter(|rps| *rps > 0))
+}
+
+fn daily_request_budget_from_env() -> Result<Option<u64>> {
+    Ok(Some(env_number("DAILY_REQUEST_BUDGET", 0u64)?).filter(|budget| *budget > 0))
+}
+
+fn budget_exclude_reads_from_env() -> bool {
+    env::var("DAILY_BUDGET_EXCLUDE_READS")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "true" | "1" | "on" | "yes"))
+        .unwrap_or(false)
+}
+
+fn log_http_bodies_from_env() -> bool {
+    env::var("LOG_HTTP_BODIES")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "true" | "1" | "on" | "yes"))
+        .unwrap_or(false)
+}
+
+fn http_compression_from_env() -> bool {
+    env::var("HTTP_COMPRESSION")
+        .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "false" | "0" | "off" | "no"))
+        .unwrap_or(true)
+}
+
+/// Validate a fallback base URL: absolute https, no trailing slash
+fn parse_fallback_base_url(raw: &str) -> Result<String> {
+    let url = url::Url::parse(raw.trim()).with_context(|| format!("Invalid fallback base URL '{}'", raw))?;
+    if url.scheme() != "https" {
+        anyhow::bail!("Fallback base URL '{}' must use https", raw);
+    }
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
+
+fn fallback_base_url_from_env() -> Result<Option<String>> {
+    match env::var("ONELOGIN_FALLBACK_BASE_URL") {
+        Ok(raw) if !raw.trim().is_empty() => parse_fallback_base_url(&raw).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn env_number<T: std::str::FromStr>(name: &str, default: T) -> Result<T> {
+    match env::var(name) {
+        Ok(v) => v.trim().parse().map_err(|_| anyhow::anyhow!("Invalid {}", name)),
+        Err(_) => Ok(default),
+    }
+}
+
+fn extra_headers_from_env() -> Result<Vec<(String, String)>> {
+    match env::var("ONELOGIN_EXTRA_HEADERS") {
+        Ok(raw) => parse_extra_headers(&raw),
+        Err(_) => Ok(Vec::new()),
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -37,6 +151,33 @@ impl OneLoginRegion {
     }
 }
 
+/// Base path of a OneLogin API family. Each API module names the family it targets
+/// when building paths, so nothing depends on implicit prefixing in `Config::api_url`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    /// Legacy endpoints under `/api/1` (status/data envelopes)
+    V1,
+    /// Current endpoints under `/api/2`
+    V2,
+    /// Endpoints outside the versioned API (`/auth`, `/oidc`, `/scim`, ...)
+    Root,
+}
+
+impl ApiVersion {
+    pub fn base(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/api/1",
+            ApiVersion::V2 => "/api/2",
+            ApiVersion::Root => "",
+        }
+    }
+
+    /// Build an absolute API path, e.g. `ApiVersion::V2.path("users/1")` -> `/api/2/users/1`
+    pub fn path(&self, path: &str) -> String {
+        format!("{}/{}", self.base(), path.trim_start_matches('/'))
+    }
+}
+
 impl Config {
     pub fn from_env() -> Result<Self> {
         dotenv::dotenv().ok();
@@ -105,6 +246,19 @@ impl Config {
             retry_initial_delay_ms,
             retry_max_delay_ms,
             tool_config_path,
+            user_agent: user_agent_from_env(),
+            extra_headers: extra_headers_from_env()?,
+            http_compression: http_compression_from_env(),
+            circuit_breaker_threshold: env_number("CIRCUIT_BREAKER_THRESHOLD", 5)?,
+            circuit_breaker_reset_seconds: env_number("CIRCUIT_BREAKER_RESET_SECONDS", 30)?,
+            retry_budget_ratio: env_number("RETRY_BUDGET_RATIO", 0.2)?,
+            retry_budget_min_per_second: env_number("RETRY_BUDGET_MIN_PER_SECOND", 1)?,
+            cache_compression_threshold: env_number("CACHE_COMPRESSION_THRESHOLD", 16 * 1024)?,
+            global_rate_limit_rps: global_rate_limit_from_env()?,
+            log_http_bodies: log_http_bodies_from_env(),
+            daily_request_budget: daily_request_budget_from_env()?,
+            budget_exclude_reads: budget_exclude_reads_from_env(),
+            fallback_base_url: fallback_base_url_from_env()?,
         })
     }
 
@@ -113,13 +267,37 @@ impl Config {
             .tenant_base_url(&self.onelogin_subdomain)
     }
 
+    /// Headers applied to every request: User-Agent and `extra_headers`
+    pub fn default_headers(&self) -> reqwest::header::HeaderMap {
+        use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
+        let mut headers = HeaderMap::new();
+        if let Ok(agent) = HeaderValue::from_str(&self.user_agent) {
+            headers.insert(USER_AGENT, agent);
+        }
+        for (name, value) in &self.extra_headers {
+            // Validated when the config was loaded
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                headers.append(name, value);
+            }
+        }
+        headers
+    }
+
     pub fn token_url(&self) -> String {
         format!("{}/auth/oauth2/v2/token", self.tenant_base_url())
     }
 
+    /// Resolve an API path against the tenant base URL.
+    ///
+    /// Paths built with `ApiVersion` are already absolute. Bare paths are still
+    /// treated as v2 for compatibility.
     pub fn api_url(&self, path: &str) -> String {
+        self.api_url_at(&self.tenant_base_url(), path)
+    }
+
+    /// Resolve an API path against `base` instead of the tenant base URL (e.g. the fallback region)
+    pub fn api_url_at(&self, base: &str, path: &str) -> String {
         let trimmed = path.trim_start_matches('/');
-        let base = self.tenant_base_url();
         let needs_absolute = trimmed.starts_with("api/")
             || trimmed.starts_with("auth/")
             || trimmed.starts_with("scim/")
@@ -185,18 +363,34 @@ impl Config {
             retry_initial_delay_ms,
             retry_max_delay_ms,
             tool_config_path,
+            user_agent: user_agent_from_env(),
+            extra_headers: extra_headers_from_env()?,
+            http_compression: http_compression_from_env(),
+            circuit_breaker_threshold: env_number("CIRCUIT_BREAKER_THRESHOLD", 5)?,
+            circuit_breaker_reset_seconds: env_number("CIRCUIT_BREAKER_RESET_SECONDS", 30)?,
+            retry_budget_ratio: env_number("RETRY_BUDGET_RATIO", 0.2)?,
+            retry_budget_min_per_second: env_number("RETRY_BUDGET_MIN_PER_SECOND", 1)?,
+            cache_compression_threshold: env_number("CACHE_COMPRESSION_THRESHOLD", 16 * 1024)?,
+            global_rate_limit_rps: global_rate_limit_from_env()?,
+            log_http_bodies: log_http_bodies_from_env(),
+            daily_request_budget: daily_request_budget_from_env()?,
+            budget_exclude_reads: budget_exclude_reads_from_env(),
+            fallback_base_url: None,
         })
     }
 
-    /// Load multi-tenant configuration from file.
-    /// Checks ONELOGIN_TENANTS_CONFIG env var first, then default platform path.
-    pub fn load_tenants_file() -> Result<Option<TenantsConfigFile>> {
-        let path = env::var("ONELOGIN_TENANTS_CONFIG")
+    /// Location of tenants.json: `ONELOGIN_TENANTS_CONFIG`, then the platform config dir
+    pub fn tenants_file_path() -> Option<PathBuf> {
+        env::var("ONELOGIN_TENANTS_CONFIG")
             .map(PathBuf::from)
             .ok()
-            .or_else(|| dirs::config_dir().map(|d| d.join("onelogin-mcp").join("tenants.json")));
+            .or_else(|| dirs::config_dir().map(|d| d.join("onelogin-mcp").join("tenants.json")))
+    }
 
-        match path {
+    /// Load multi-tenant configuration from file.
+    /// Checks ONELOGIN_TENANTS_CONFIG env var first, then default platform path.
+    pub fn load_tenants_file() -> Result<Option<TenantsConfigFile>> {
+        match Self::tenants_file_path() {
             Some(p) if p.exists() => {
                 let content = std::fs::read_to_string(&p)
                     .with_context(|| format!("Failed to read tenants config: {}", p.display()))?;
@@ -219,6 +413,18 @@ pub struct TenantEntry {
     pub subdomain: String,
     #[serde(default)]
     pub default: bool,
+    /// Secondary base URL to fail over to, e.g. `https://acme.eu.onelogin.com`
+    #[serde(default)]
+    pub fallback_base_url: Option<String>,
+    /// Requests per second for this tenant, overriding `RATE_LIMIT_RPS`
+    #[serde(default)]
+    pub rate_limit_rps: Option<u32>,
+    /// OneLogin API requests allowed per UTC day for this tenant, overriding `DAILY_REQUEST_BUDGET` (0 disables)
+    #[serde(default)]
+    pub daily_request_budget: Option<u64>,
+    /// Overrides `DAILY_BUDGET_EXCLUDE_READS` for this tenant
+    #[serde(default)]
+    pub budget_exclude_reads: Option<bool>,
 }
 
 impl TenantEntry {
@@ -229,18 +435,39 @@ impl TenantEntry {
             "eu" => OneLoginRegion::EU,
             _ => anyhow::bail!("Invalid region '{}' for tenant '{}'", self.region, self.name),
         };
+        if self.rate_limit_rps == Some(0) {
+            anyhow::bail!("rate_limit_rps for tenant '{}' must be greater than 0", self.name);
+        }
         Ok(Config {
             onelogin_client_id: self.client_id.clone(),
             onelogin_client_secret: Secret::new(self.client_secret.clone()),
             onelogin_region: region,
             onelogin_subdomain: self.subdomain.clone(),
             cache_ttl_seconds: base.cache_ttl_seconds,
-            rate_limit_requests_per_second: base.rate_limit_requests_per_second,
+            rate_limit_requests_per_second: self.rate_limit_rps.unwrap_or(base.rate_limit_requests_per_second),
             enable_metrics: base.enable_metrics,
             max_retries: base.max_retries,
             retry_initial_delay_ms: base.retry_initial_delay_ms,
             retry_max_delay_ms: base.retry_max_delay_ms,
             tool_config_path: base.tool_config_path.clone(),
+            user_agent: base.user_agent.clone(),
+            extra_headers: base.extra_headers.clone(),
+            http_compression: base.http_compression,
+            circuit_breaker_threshold: base.circuit_breaker_threshold,
+            circuit_breaker_reset_seconds: base.circuit_breaker_reset_seconds,
+            retry_budget_ratio: base.retry_budget_ratio,
+            retry_budget_min_per_second: base.retry_budget_min_per_second,
+            cache_compression_threshold: base.cache_compression_threshold,
+            global_rate_limit_rps: base.global_rate_limit_rps,
+            log_http_bodies: base.log_http_bodies,
+            daily_request_budget: self.daily_request_budget.or(base.daily_request_budget).filter(|budget| *budget > 0),
+            budget_exclude_reads: self.budget_exclude_reads.unwrap_or(base.budget_exclude_reads),
+            fallback_base_url: self
+                .fallback_base_url
+                .as_deref()
+                .map(parse_fallback_base_url)
+                .transpose()
+                .with_context(|| format!("Invalid fallback_base_url for tenant '{}'", self.name))?,
         })
     }
 }
@@ -281,8 +508,25 @@ mod tests {
             retry_initial_delay_ms: 100,
             retry_max_delay_ms: 10000,
             tool_config_path: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            extra_headers: vec![("X-Proxy-Tag".to_string(), "mcp".to_string())],
+            http_compression: true,
+            circuit_breaker_threshold: 5,
+            circuit_breaker_reset_seconds: 30,
+            retry_budget_ratio: 0.2,
+            retry_budget_min_per_second: 1,
+            fallback_base_url: Some("https://tenant.eu.onelogin.com".to_string()),
+            cache_compression_threshold: 16 * 1024,
+            global_rate_limit_rps: None,
+            log_http_bodies: false,
+            daily_request_budget: None,
+            budget_exclude_reads: false,
         };
 
+        let headers = config.default_headers();
+        assert!(headers["user-agent"].to_str().unwrap().starts_with("onelogin-mcp-server/"));
+        assert_eq!(headers["x-proxy-tag"], "mcp");
+
         assert_eq!(
             config.api_url("/users"),
             "https://tenant.onelogin.com/api/2/users"
@@ -295,5 +539,47 @@ mod tests {
             config.api_url("/auth/oauth2/v2/token"),
             "https://tenant.onelogin.com/auth/oauth2/v2/token"
         );
+        assert_eq!(
+            config.api_url(&ApiVersion::V1.path("events")),
+            "https://tenant.onelogin.com/api/1/events"
+        );
+        assert_eq!(
+            config.api_url_at(config.fallback_base_url.as_deref().unwrap(), "/users"),
+            "https://tenant.eu.onelogin.com/api/2/users"
+        );
+    }
+
+    #[test]
+    fn test_parse_fallback_base_url() {
+        assert_eq!(
+            parse_fallback_base_url(" https://acme.eu.onelogin.com/ ").unwrap(),
+            "https://acme.eu.onelogin.com"
+        );
+        assert!(parse_fallback_base_url("http://acme.onelogin.com").is_err());
+        assert!(parse_fallback_base_url("acme.onelogin.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_extra_headers() {
+        let headers = parse_extra_headers("X-Company-Id: 42; X-Route: egress-a\nX-Empty:").unwrap();
+        assert_eq!(
+            headers,
+            vec![
+                ("X-Company-Id".to_string(), "42".to_string()),
+                ("X-Route".to_string(), "egress-a".to_string()),
+                ("X-Empty".to_string(), String::new()),
+            ]
+        );
+        assert!(parse_extra_headers("").unwrap().is_empty());
+        assert!(parse_extra_headers("no-colon").is_err());
+        assert!(parse_extra_headers("Bad Name: x").is_err());
+        assert!(parse_extra_headers("Authorization: Bearer x").is_err());
+    }
+
+    #[test]
+    fn test_api_version_paths() {
+        assert_eq!(ApiVersion::V1.path("users/1/logout"), "/api/1/users/1/logout");
+        assert_eq!(ApiVersion::V2.path("/users"), "/api/2/users");
+        assert_eq!(ApiVersion::Root.path("auth/rate_limit"), "/auth/rate_limit");
     }
 }
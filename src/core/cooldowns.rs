@@ -0,0 +1,71 @@
+//! Minimum intervals between calls of sensitive tools.
+//!
+//! `tool_cooldowns` in the tool config maps a tool to seconds, e.g.
+//! `{"onelogin_delete_app": 300}` lets apps be deleted at most once every five
+//! minutes. The limit is per tool across all tenants, so an agent stuck in a
+//! loop is stopped after its first call instead of working through the tenant.
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When each cooled-down tool last started a call
+#[derive(Default)]
+pub struct ToolCooldowns {
+    last_run: Mutex<HashMap<String, Instant>>,
+}
+
+impl ToolCooldowns {
+    /// Claim a call of `tool`, or Err with the time left while its previous call is
+    /// within `interval`. Returns the previous start to hand back to `release`.
+    pub fn claim(&self, tool: &str, interval: Duration) -> Result<Option<Instant>> {
+        let mut last_run = self.last_run.lock().expect("cooldown log poisoned");
+        let previous = last_run.get(tool).copied();
+        if let Some(at) = previous {
+            let elapsed = at.elapsed();
+            if elapsed < interval {
+                let wait = interval - elapsed;
+                bail!(
+                    "Tool '{}' is cooling down: the tool config allows one call every {}s and the last one started {}s ago. Retry in {}s, and check that repeating this call is intended.",
+                    tool,
+                    interval.as_secs(),
+                    elapsed.as_secs(),
+                    wait.as_secs().max(1)
+                );
+            }
+        }
+        last_run.insert(tool.to_string(), Instant::now());
+        Ok(previous)
+    }
+
+    /// Undo a claim whose call failed, so an error doesn't start the cooldown
+    pub fn release(&self, tool: &str, previous: Option<Instant>) {
+        let mut last_run = self.last_run.lock().expect("cooldown log poisoned");
+        match previous {
+            Some(at) => last_run.insert(tool.to_string(), at),
+            None => last_run.remove(tool),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_and_release() {
+        let cooldowns = ToolCooldowns::default();
+        let interval = Duration::from_secs(300);
+        let previous = cooldowns.claim("onelogin_delete_app", interval).unwrap();
+        assert_eq!(previous, None);
+
+        let err = cooldowns.claim("onelogin_delete_app", interval).unwrap_err();
+        assert!(err.to_string().contains("one call every 300s"));
+        assert!(cooldowns.claim("onelogin_delete_role", interval).is_ok());
+
+        cooldowns.release("onelogin_delete_app", previous);
+        assert!(cooldowns.claim("onelogin_delete_app", interval).is_ok());
+        assert!(cooldowns.claim("onelogin_delete_app", Duration::ZERO).is_ok());
+    }
+}
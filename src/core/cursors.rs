@@ -0,0 +1,231 @@
+//! Persistent pagination cursors for resumable listings.
+//!
+//! When an auto-paginated listing stops early (max_pages, max_results or the
+//! tool deadline), the position of the next page is stored under a hash of the
+//! tool name and its query arguments. Calling the same listing again with
+//! `resume=true` starts from that position instead of page one. Each entry keeps
+//! the chain of positions already visited so callers can see how far a listing
+//! got. Cursors survive restarts in `~/.config/onelogin-mcp/cursors.json`
+//! (override with `ONELOGIN_MCP_CURSORS`).
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Overrides the default cursor file location
+pub const CURSORS_ENV_VAR: &str = "ONELOGIN_MCP_CURSORS";
+
+/// Cursors older than this are treated as gone; OneLogin cursors expire too
+const CURSOR_TTL_HOURS: i64 = 24;
+
+/// Most recent listings kept; older ones are dropped first
+const MAX_ENTRIES: usize = 200;
+
+/// Arguments that control how a listing is paged or returned rather than what it
+/// lists, so they are left out of the query hash
+const CONTROL_ARGS: &[&str] = &[
+    "page",
+    "after_cursor",
+    "resume",
+    "auto_paginate",
+    "max_pages",
+    "max_results",
+    "output_path",
+    "output_format",
+    "force_refresh",
+    "tool_timeout_seconds",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorChain {
+    pub tool: String,
+    /// Positions already fetched, oldest first (page numbers or API cursors)
+    pub visited: Vec<String>,
+    /// Where the next call resumes
+    pub next: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CursorFile {
+    #[serde(default)]
+    cursors: HashMap<String, CursorChain>,
+}
+
+pub struct CursorStore {
+    /// `None` keeps cursors in memory only (mock mode, tests)
+    path: Option<PathBuf>,
+    cursors: Mutex<HashMap<String, CursorChain>>,
+}
+
+impl CursorStore {
+    /// Default cursor file: `ONELOGIN_MCP_CURSORS`, then the platform config dir
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var(CURSORS_ENV_VAR)
+            .map(PathBuf::from)
+            .ok()
+            .or_else(|| dirs::config_dir().map(|d| d.join("onelogin-mcp").join("cursors.json")))
+    }
+
+    /// Load cursors from `path`; a missing or unreadable file starts empty
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let cursors = match &path {
+            Some(p) if p.exists() => match Self::read_file(p) {
+                Ok(file) => file.cursors,
+                Err(e) => {
+                    warn!("Ignoring unreadable cursor file {}: {:#}", p.display(), e);
+                    HashMap::new()
+                }
+            },
+            _ => HashMap::new(),
+        };
+        Self {
+            path,
+            cursors: Mutex::new(cursors),
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        Self::load(None)
+    }
+
+    fn read_file(path: &Path) -> Result<CursorFile> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Key for a listing: tenant, tool and a hash of the arguments that select what is listed
+    pub fn key(tenant: &str, tool: &str, args: &Value) -> String {
+        let query: BTreeMap<&String, &Value> = args
+            .as_object()
+            .map(|args| {
+                args.iter()
+                    .filter(|(name, value)| !CONTROL_ARGS.contains(&name.as_str()) && name.as_str() != "tenant" && !value.is_null())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let digest = Sha256::digest(serde_json::to_vec(&query).unwrap_or_default());
+        format!("{}:{}:{}", tenant, tool, hex::encode(&digest[..8]))
+    }
+
+    /// The stored chain for `key`, unless it has expired
+    pub fn get(&self, key: &str) -> Option<CursorChain> {
+        let chain = self.cursors.lock().expect("Mutex poisoned").get(key).cloned()?;
+        let updated = DateTime::parse_from_rfc3339(&chain.updated_at).ok()?;
+        (Utc::now() - updated.with_timezone(&Utc) < Duration::hours(CURSOR_TTL_HOURS)).then_some(chain)
+    }
+
+    /// Record that a listing fetched `visited` and continues at `next`. A `None`
+    /// next means the listing is complete and its chain is dropped.
+    pub fn record(&self, key: &str, tool: &str, visited: Vec<String>, next: Option<String>) -> Result<()> {
+        let mut cursors = self.cursors.lock().expect("Mutex poisoned");
+        let Some(next) = next else {
+            if cursors.remove(key).is_some() {
+                debug!("Listing {} complete, dropping its cursor", key);
+                self.save(&cursors)?;
+            }
+            return Ok(());
+        };
+
+        let mut chain = cursors
+            .remove(key)
+            .filter(|c| c.tool == tool)
+            .map(|c| c.visited)
+            .unwrap_or_default();
+        chain.extend(visited);
+        debug!("Saving cursor for {}: next {}", key, next);
+        cursors.insert(
+            key.to_string(),
+            CursorChain {
+                tool: tool.to_string(),
+                visited: chain,
+                next,
+                updated_at: Utc::now().to_rfc3339(),
+            },
+        );
+        Self::prune(&mut cursors);
+        self.save(&cursors)
+    }
+
+    pub fn reset(&self, key: &str) -> Result<()> {
+        let mut cursors = self.cursors.lock().expect("Mutex poisoned");
+        if cursors.remove(key).is_some() {
+            self.save(&cursors)?;
+        }
+        Ok(())
+    }
+
+    /// Drop expired chains, then the oldest ones beyond `MAX_ENTRIES`
+    fn prune(cursors: &mut HashMap<String, CursorChain>) {
+        let cutoff = (Utc::now() - Duration::hours(CURSOR_TTL_HOURS)).to_rfc3339();
+        cursors.retain(|_, c| c.updated_at > cutoff);
+        if cursors.len() > MAX_ENTRIES {
+            let mut by_age: Vec<(String, String)> =
+                cursors.iter().map(|(k, c)| (c.updated_at.clone(), k.clone())).collect();
+            by_age.sort();
+            for (_, key) in by_age.into_iter().take(cursors.len() - MAX_ENTRIES) {
+                cursors.remove(&key);
+            }
+        }
+    }
+
+    fn save(&self, cursors: &HashMap<String, CursorChain>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+        }
+        let file = CursorFile {
+            cursors: cursors.clone(),
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&file)?)
+            .with_context(|| format!("Failed to write cursor file {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_ignores_paging_arguments() {
+        let a = CursorStore::key("prod", "onelogin_list_users", &json!({"role_id": 5, "page": 3, "max_pages": 2}));
+        let b = CursorStore::key("prod", "onelogin_list_users", &json!({"max_pages": 10, "role_id": 5, "resume": true}));
+        let other = CursorStore::key("prod", "onelogin_list_users", &json!({"role_id": 6}));
+        assert_eq!(a, b);
+        assert_ne!(a, other);
+        assert_ne!(a, CursorStore::key("staging", "onelogin_list_users", &json!({"role_id": 5})));
+    }
+
+    #[test]
+    fn test_chain_persists_and_completes() {
+        let path = std::env::temp_dir().join(format!("cursors-test-{}.json", std::process::id()));
+        let store = CursorStore::load(Some(path.clone()));
+        let key = CursorStore::key("prod", "onelogin_list_events", &json!({"event_type_id": 5}));
+        assert!(store.get(&key).is_none());
+
+        store.record(&key, "onelogin_list_events", vec!["start".into()], Some("c1".into())).unwrap();
+        store.record(&key, "onelogin_list_events", vec!["c1".into()], Some("c2".into())).unwrap();
+
+        let reloaded = CursorStore::load(Some(path.clone()));
+        let chain = reloaded.get(&key).unwrap();
+        assert_eq!(chain.visited, vec!["start", "c1"]);
+        assert_eq!(chain.next, "c2");
+
+        reloaded.record(&key, "onelogin_list_events", vec!["c2".into()], None).unwrap();
+        assert!(CursorStore::load(Some(path.clone())).get(&key).is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+}
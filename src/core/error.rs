@@ -1,3 +1,6 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
 use thiserror::Error;
 
 // Allow dead code - error variants defined for completeness even if not all used yet
@@ -10,9 +13,17 @@ pub enum OneLoginError {
     #[error("API request failed: {0}")]
     ApiRequestFailed(String),
 
+    /// OneLogin answered with an error status; the body is parsed into typed fields
+    #[error("{0}")]
+    Api(Box<ApiError>),
+
     #[error("Invalid response from API: {0}")]
     InvalidResponse(String),
 
+    /// The body was JSON, but not in the expected shape; `raw` keeps it so a tool can still return it
+    #[error("Invalid response from API: {message}")]
+    UnexpectedResponse { message: String, raw: Box<Value> },
+
     #[error("Resource not found: {0}")]
     NotFound(String),
 
@@ -28,6 +39,9 @@ pub enum OneLoginError {
     #[error("Circuit breaker open: {0}")]
     CircuitBreakerOpen(String),
 
+    #[error("API budget exceeded: {0}")]
+    BudgetExceeded(String),
+
     #[error("Cache error: {0}")]
     CacheError(String),
 
@@ -46,27 +60,232 @@ pub enum OneLoginError {
 
 pub type Result<T> = std::result::Result<T, OneLoginError>;
 
+/// A validation problem OneLogin reported for one request field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// An error response from the OneLogin API.
+///
+/// OneLogin reports errors in several shapes depending on the API version:
+/// `{"statusCode", "name", "message", "errors": [{"field", "message"}]}` (v2),
+/// `{"status": {"error", "code", "type", "message"}}` (v1) and
+/// `{"errors": {"field": ["message"]}}`. `parse` reads all of them.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+    pub status: u16,
+    pub method: String,
+    pub url: String,
+    /// OneLogin's error name or type, e.g. `BadRequest` or `bad request`
+    pub code: Option<String>,
+    pub message: Option<String>,
+    pub field_errors: Vec<FieldError>,
+    /// Response body, pretty-printed when it is JSON
+    pub body: String,
+}
+
+impl ApiError {
+    pub fn parse(status: u16, method: &str, url: &str, body: &str) -> Self {
+        let json: Option<Value> = serde_json::from_str(body).ok();
+        let mut error = Self {
+            status,
+            method: method.to_string(),
+            url: url.to_string(),
+            code: None,
+            message: None,
+            field_errors: Vec::new(),
+            body: json
+                .as_ref()
+                .and_then(|j| serde_json::to_string_pretty(j).ok())
+                .unwrap_or_else(|| body.to_string()),
+        };
+        let Some(json) = json else {
+            return error;
+        };
+        // v1 wraps the details in a status object
+        let details = json.get("status").filter(|s| s.is_object()).unwrap_or(&json);
+
+        error.code = ["name", "type", "error_code", "code"]
+            .iter()
+            .filter_map(|key| details.get(*key))
+            .find_map(|v| match v {
+                Value::String(s) if !s.is_empty() => Some(s.clone()),
+                _ => None,
+            });
+        error.message = ["message", "description", "error_description", "error"]
+            .iter()
+            .filter_map(|key| details.get(*key))
+            .find_map(message_text);
+
+        match json.get("errors").or_else(|| details.get("errors")) {
+            Some(Value::Array(items)) => {
+                for item in items {
+                    let field = item.get("field").or_else(|| item.get("attribute")).and_then(|f| f.as_str());
+                    let message = item.get("message").and_then(message_text).or_else(|| message_text(item));
+                    if let Some(message) = message {
+                        error.field_errors.push(FieldError {
+                            field: field.unwrap_or_default().to_string(),
+                            message,
+                        });
+                    }
+                }
+            }
+            Some(Value::Object(fields)) => {
+                for (field, messages) in fields {
+                    if let Some(message) = message_text(messages) {
+                        error.field_errors.push(FieldError {
+                            field: field.clone(),
+                            message,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        error
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.status {
+            401 => "Authentication failed",
+            403 => "Permission denied",
+            404 => "Resource not found",
+            400 | 409 | 422 => "Request rejected",
+            500..=599 => "Server error",
+            _ => "API request failed",
+        }
+    }
+}
+
+/// A message that may be a string or a list of strings
+fn message_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Array(items) => {
+            let parts: Vec<&str> = items.iter().filter_map(|v| v.as_str()).collect();
+            (!parts.is_empty()).then(|| parts.join("; "))
+        }
+        _ => None,
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {} returned {}", self.kind(), self.method, self.url, self.status)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        for e in &self.field_errors {
+            if e.field.is_empty() {
+                write!(f, "\n- {}", e.message)?;
+            } else {
+                write!(f, "\n- {}: {}", e.field, e.message)?;
+            }
+        }
+        write!(f, "\nStatus: {}\nResponse: {}", self.status, self.body)
+    }
+}
+
 #[allow(dead_code)]
 impl OneLoginError {
-    pub fn is_retriable(&self) -> bool {
-        matches!(
-            self,
+    /// Transient failures that may succeed when sent again
+    pub fn is_retryable(&self) -> bool {
+        match self {
             OneLoginError::RateLimitExceeded
-                | OneLoginError::ApiRequestFailed(_)
-                | OneLoginError::HttpClientError(_)
-                | OneLoginError::AuthenticationFailed(_) // Token may be expired, retry with new token
-        )
+            | OneLoginError::ApiRequestFailed(_)
+            | OneLoginError::HttpClientError(_)
+            | OneLoginError::AuthenticationFailed(_) => true, // Token may be expired, retry with new token
+            OneLoginError::Api(e) => matches!(e.status, 401 | 408 | 500..=599),
+            _ => false,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, OneLoginError::NotFound(_)) || self.api_status() == Some(404)
+    }
+
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, OneLoginError::PermissionDenied(_)) || self.api_status() == Some(403)
+    }
+
+    pub fn is_authentication_failure(&self) -> bool {
+        matches!(self, OneLoginError::AuthenticationFailed(_)) || self.api_status() == Some(401)
+    }
+
+    /// Details of an error response from OneLogin
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            OneLoginError::Api(e) => Some(&**e),
+            _ => None,
+        }
+    }
+
+    /// Raw JSON of a response that could not be deserialized into the expected type
+    pub fn raw_response(&self) -> Option<&Value> {
+        match self {
+            OneLoginError::UnexpectedResponse { raw, .. } => Some(raw),
+            _ => None,
+        }
+    }
+
+    /// Field validation errors OneLogin reported; empty for other errors
+    pub fn field_errors(&self) -> &[FieldError] {
+        self.api_error().map_or(&[], |e| e.field_errors.as_slice())
+    }
+
+    fn api_status(&self) -> Option<u16> {
+        self.api_error().map(|e| e.status)
     }
 
     pub fn status_code(&self) -> u16 {
         match self {
+            OneLoginError::Api(e) => e.status,
             OneLoginError::NotFound(_) => 404,
             OneLoginError::PermissionDenied(_) => 403,
             OneLoginError::AuthenticationFailed(_) => 401,
             OneLoginError::InvalidInput(_) => 400,
             OneLoginError::RateLimitExceeded => 429,
             OneLoginError::CircuitBreakerOpen(_) => 503,
+            OneLoginError::BudgetExceeded(_) => 429,
             _ => 500,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_v2_validation_error() {
+        let body = r#"{"statusCode":422,"name":"UnprocessableEntity","message":"Validation Failed","errors":[{"field":"email","message":["is invalid","is taken"]}]}"#;
+        let error = OneLoginError::Api(Box::new(ApiError::parse(422, "POST", "https://x/api/2/users", body)));
+        let api = error.api_error().unwrap();
+        assert_eq!(api.code.as_deref(), Some("UnprocessableEntity"));
+        assert_eq!(api.message.as_deref(), Some("Validation Failed"));
+        assert_eq!(
+            error.field_errors(),
+            &[FieldError { field: "email".to_string(), message: "is invalid; is taken".to_string() }]
+        );
+        assert!(!error.is_retryable());
+        assert!(error.to_string().starts_with("Request rejected: POST https://x/api/2/users returned 422: Validation Failed\n- email: is invalid; is taken"));
+    }
+
+    #[test]
+    fn test_parse_v1_and_non_json_errors() {
+        let body = r#"{"status":{"error":true,"code":404,"type":"not found","message":"User not found"}}"#;
+        let error = OneLoginError::Api(Box::new(ApiError::parse(404, "GET", "https://x/api/1/users/1", body)));
+        assert!(error.is_not_found());
+        assert_eq!(error.status_code(), 404);
+        assert_eq!(error.api_error().unwrap().code.as_deref(), Some("not found"));
+        assert!(error.to_string().starts_with("Resource not found:"));
+
+        let error = OneLoginError::Api(Box::new(ApiError::parse(503, "GET", "https://x", "<html>busy</html>")));
+        assert!(error.is_retryable());
+        assert!(error.api_error().unwrap().message.is_none());
+        assert!(OneLoginError::NotFound("x".to_string()).is_not_found());
+        assert!(!OneLoginError::InvalidInput("x".to_string()).is_not_found());
+    }
+}
@@ -0,0 +1,172 @@
+//! Minimal HTTP/1.1 handling shared by the admin API, the webhook receiver and
+//! the metrics exporter.
+//!
+//! Each connection carries exactly one request and is closed after the
+//! response. Reading is bounded: a fixed number of header lines, a per-listener
+//! body limit, and an overall timeout.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const MAX_HEADER_LINES: usize = 100;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct HttpRequest {
+    pub method: String,
+    /// Request target including any query string
+    pub target: String,
+    /// Header names are lowercased
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Path without the query string
+    pub fn path(&self) -> &str {
+        self.target.split_once('?').map_or(self.target.as_str(), |(path, _)| path)
+    }
+
+    /// Query string without the leading `?`, empty when there is none
+    pub fn query(&self) -> &str {
+        self.target.split_once('?').map_or("", |(_, query)| query)
+    }
+
+    /// First value of a header, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read one request: request line, headers, then a body of at most `max_body_bytes`
+pub async fn read_request<R>(reader: &mut R, max_body_bytes: usize) -> Result<HttpRequest>
+where
+    R: AsyncBufRead + Unpin,
+{
+    tokio::time::timeout(READ_TIMEOUT, read_request_inner(reader, max_body_bytes))
+        .await
+        .map_err(|_| anyhow!("Timed out reading request"))?
+}
+
+async fn read_request_inner<R>(reader: &mut R, max_body_bytes: usize) -> Result<HttpRequest>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| anyhow!("Empty request"))?.to_string();
+    let target = parts.next().ok_or_else(|| anyhow!("Missing request path"))?.to_string();
+
+    let mut headers = Vec::new();
+    let mut content_length = 0usize;
+    for _ in 0..MAX_HEADER_LINES {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            break;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim().to_string();
+            if name == "content-length" {
+                content_length = value.parse().context("Invalid Content-Length")?;
+            }
+            headers.push((name, value));
+        }
+    }
+    if content_length > max_body_bytes {
+        return Err(anyhow!("Body exceeds {} bytes", max_body_bytes));
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(HttpRequest { method, target, headers, body })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        500 => "Internal Server Error",
+        _ => "Error",
+    }
+}
+
+/// Write a complete response and close the exchange
+pub async fn write_response<W>(
+    stream: &mut W,
+    status: u16,
+    content_type: &str,
+    extra_headers: &[(&str, &str)],
+    body: &str,
+) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        reason(status),
+        content_type,
+        body.len()
+    );
+    for (name, value) in extra_headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Write a JSON response
+pub async fn write_json<W>(stream: &mut W, status: u16, extra_headers: &[(&str, &str)], body: &Value) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    write_response(stream, status, "application/json", extra_headers, &body.to_string()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_headers_and_bounded_body() {
+        let raw = b"POST /hook?x=1 HTTP/1.1\r\nX-Sig: abc\r\nContent-Length: 4\r\n\r\nbody";
+        let request = read_request(&mut &raw[..], 16).await.unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path(), "/hook");
+        assert_eq!(request.query(), "x=1");
+        assert_eq!(request.header("x-sig"), Some("abc"));
+        assert_eq!(request.body, b"body");
+
+        assert!(read_request(&mut &raw[..], 3).await.is_err());
+        assert!(read_request(&mut &b""[..], 16).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_writes_json_response() {
+        let mut out = Vec::new();
+        write_json(&mut out, 401, &[("WWW-Authenticate", "Bearer")], &serde_json::json!({"error": "no"}))
+            .await
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+        assert!(out.contains("Content-Length: 14\r\n"));
+        assert!(out.contains("WWW-Authenticate: Bearer\r\n"));
+        assert!(out.ends_with("\r\n\r\n{\"error\":\"no\"}"));
+    }
+}
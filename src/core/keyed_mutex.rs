@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Async mutexes created on demand per key, so tasks touching the same key run one
+/// at a time while tasks on different keys proceed in parallel.
+///
+/// Entries are held weakly: a key's mutex is dropped once no guard or waiter
+/// refers to it, and stale entries are pruned as new keys are locked.
+#[derive(Default)]
+pub struct KeyedMutex {
+    locks: Mutex<HashMap<String, Weak<AsyncMutex<()>>>>,
+}
+
+/// Guards for the keys locked by `KeyedMutex::lock_all`; released on drop
+pub struct KeyedGuards {
+    _guards: Vec<OwnedMutexGuard<()>>,
+}
+
+impl KeyedMutex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn mutex_for(&self, key: &str) -> Arc<AsyncMutex<()>> {
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(mutex) = locks.get(key).and_then(Weak::upgrade) {
+            return mutex;
+        }
+        locks.retain(|_, weak| weak.strong_count() > 0);
+        let mutex = Arc::new(AsyncMutex::new(()));
+        locks.insert(key.to_string(), Arc::downgrade(&mutex));
+        mutex
+    }
+
+    /// Lock every key, waiting for current holders. Keys are taken in sorted order
+    /// so two callers locking overlapping sets cannot deadlock.
+    pub async fn lock_all(&self, keys: &[String]) -> KeyedGuards {
+        let mut keys: Vec<&String> = keys.iter().collect();
+        keys.sort();
+        keys.dedup();
+        let mut guards = Vec::with_capacity(keys.len());
+        for key in keys {
+            guards.push(self.mutex_for(key).lock_owned().await);
+        }
+        KeyedGuards { _guards: guards }
+    }
+
+    /// Whether a task currently holds `key`
+    pub fn is_locked(&self, key: &str) -> bool {
+        let locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks
+            .get(key)
+            .and_then(Weak::upgrade)
+            .is_some_and(|mutex| mutex.try_lock().is_err())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_key_is_serialized() {
+        let locks = Arc::new(KeyedMutex::new());
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let (locks, running, peak) = (locks.clone(), running.clone(), peak.clone());
+                tokio::spawn(async move {
+                    let _guard = locks.lock_all(&["user:1".to_string()]).await;
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+        assert!(!locks.is_locked("user:1"));
+    }
+
+    #[tokio::test]
+    async fn test_different_keys_do_not_block() {
+        let locks = KeyedMutex::new();
+        let _user = locks.lock_all(&["user:1".to_string()]).await;
+        assert!(locks.is_locked("user:1"));
+        assert!(!locks.is_locked("user:2"));
+
+        let other = tokio::time::timeout(Duration::from_millis(50), locks.lock_all(&["user:2".to_string()])).await;
+        assert!(other.is_ok());
+
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(20),
+            locks.lock_all(&["app:7".to_string(), "user:1".to_string()]),
+        )
+        .await;
+        assert!(blocked.is_err());
+    }
+}
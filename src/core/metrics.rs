@@ -0,0 +1,214 @@
+//! Prometheus exporter for the per-tenant HTTP client counters.
+//!
+//! Enabled by `ENABLE_METRICS=true`. Serves the text exposition format on
+//! `GET /metrics` at `METRICS_LISTEN` (default 127.0.0.1:9464): rate limiter
+//! permits, throttling and available tokens, circuit breaker state and trips,
+//! response compression, each labelled by tenant, and the shared retry budget.
+
+use crate::core::client::ClientStats;
+use crate::core::tenant_manager::TenantManager;
+use crate::core::http_listener;
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+pub const LISTEN_ENV_VAR: &str = "METRICS_LISTEN";
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:9464";
+const METRICS_PATH: &str = "/metrics";
+/// Scrapes are GETs; a body is tolerated but never used
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Address to serve metrics on, from `METRICS_LISTEN`
+pub fn listen_from_env() -> Result<SocketAddr> {
+    let listen = std::env::var(LISTEN_ENV_VAR).unwrap_or_else(|_| DEFAULT_LISTEN.to_string());
+    listen
+        .parse()
+        .with_context(|| format!("Invalid {}: expected host:port, e.g. {}", LISTEN_ENV_VAR, DEFAULT_LISTEN))
+}
+
+/// Bind the listener and serve `/metrics` in the background
+pub async fn start(listen: SocketAddr, tenants: Arc<TenantManager>) -> Result<()> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to bind metrics exporter on {}", listen))?;
+    info!("Prometheus metrics available at http://{}{}", listen, METRICS_PATH);
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let tenants = tenants.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &tenants).await {
+                            debug!("Metrics connection from {} failed: {:#}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Metrics exporter accept failed: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn handle_connection(stream: TcpStream, tenants: &TenantManager) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let request = http_listener::read_request(&mut reader, MAX_BODY_BYTES).await?;
+
+    let (status, content_type, body) = match (request.method.as_str(), request.path()) {
+        ("GET", METRICS_PATH) => (200, "text/plain; version=0.0.4", render(&tenants.client_stats())),
+        (_, METRICS_PATH) => (405, "text/plain", "Method not allowed\n".to_string()),
+        _ => (404, "text/plain", "Not found\n".to_string()),
+    };
+    http_listener::write_response(reader.get_mut(), status, content_type, &[], &body).await
+}
+
+/// One metric family: HELP/TYPE lines, then a sample per tenant
+fn family<F>(out: &mut String, name: &str, kind: &str, help: &str, tenants: &[(String, ClientStats)], value: F)
+where
+    F: Fn(&ClientStats) -> f64,
+{
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (tenant, stats) in tenants {
+        let _ = writeln!(out, "{}{{tenant=\"{}\"}} {}", name, escape_label(tenant), value(stats));
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Render the text exposition format for every tenant
+pub fn render(tenants: &[(String, ClientStats)]) -> String {
+    let mut out = String::new();
+    family(&mut out, "onelogin_rate_limiter_requests_per_second", "gauge",
+        "Configured local request rate", tenants, |s| s.rate_limiter.requests_per_second as f64);
+    family(&mut out, "onelogin_rate_limiter_tokens_available", "gauge",
+        "Requests that can be sent now without waiting (estimated)", tenants, |s| s.rate_limiter.tokens_available as f64);
+    family(&mut out, "onelogin_rate_limiter_permits_granted_total", "counter",
+        "Requests let through by the local limiter", tenants, |s| s.rate_limiter.permits_granted as f64);
+    family(&mut out, "onelogin_rate_limiter_throttled_requests_total", "counter",
+        "Requests that waited for a permit", tenants, |s| s.rate_limiter.throttled_requests as f64);
+    family(&mut out, "onelogin_rate_limiter_wait_seconds_total", "counter",
+        "Time requests spent waiting for a permit", tenants, |s| s.rate_limiter.wait_time_ms as f64 / 1000.0);
+    family(&mut out, "onelogin_upstream_rate_limited_total", "counter",
+        "429 responses received from OneLogin", tenants, |s| s.rate_limiter.upstream_rate_limited as f64);
+
+    let _ = writeln!(out, "# HELP onelogin_circuit_breaker_state Current circuit breaker state (1 for the active state)");
+    let _ = writeln!(out, "# TYPE onelogin_circuit_breaker_state gauge");
+    for (tenant, stats) in tenants {
+        for state in ["closed", "open", "half_open"] {
+            let active = u8::from(stats.circuit_breaker.state == state);
+            let _ = writeln!(
+                out,
+                "onelogin_circuit_breaker_state{{tenant=\"{}\",state=\"{}\"}} {}",
+                escape_label(tenant), state, active
+            );
+        }
+    }
+    family(&mut out, "onelogin_circuit_breaker_trips_total", "counter",
+        "Times the circuit breaker opened", tenants, |s| s.circuit_breaker.trips as f64);
+    family(&mut out, "onelogin_circuit_breaker_rejected_calls_total", "counter",
+        "Requests failed fast while the breaker was open", tenants, |s| s.circuit_breaker.rejected_calls as f64);
+    family(&mut out, "onelogin_circuit_breaker_failures_total", "counter",
+        "Transport errors and 5xx responses", tenants, |s| s.circuit_breaker.failures as f64);
+    family(&mut out, "onelogin_fallback_region_active", "gauge",
+        "1 while requests go to the tenant's fallback region", tenants, |s| {
+            s.fallback.as_ref().map_or(0.0, |f| f64::from(u8::from(f.active)))
+        });
+    family(&mut out, "onelogin_fallback_region_requests_total", "counter",
+        "Requests sent to the tenant's fallback region", tenants, |s| {
+            s.fallback.as_ref().map_or(0.0, |f| f.requests as f64)
+        });
+
+    family(&mut out, "onelogin_api_requests_today", "gauge",
+        "Requests sent to OneLogin since midnight UTC", tenants, |s| s.usage.requests as f64);
+    family(&mut out, "onelogin_api_budget_remaining", "gauge",
+        "Requests left in today's budget (absent budget: -1)", tenants, |s| s.usage.remaining.map_or(-1.0, |r| r as f64));
+    family(&mut out, "onelogin_api_budget_rejected_today", "gauge",
+        "Requests refused today because the daily budget was used up", tenants, |s| s.usage.rejected as f64);
+
+    family(&mut out, "onelogin_http_responses_total", "counter",
+        "Responses read from OneLogin", tenants, |s| s.compression.responses as f64);
+    family(&mut out, "onelogin_http_wire_bytes_total", "counter",
+        "Response bytes received on the wire", tenants, |s| s.compression.wire_bytes as f64);
+    family(&mut out, "onelogin_http_decoded_bytes_total", "counter",
+        "Response bytes after decompression", tenants, |s| s.compression.decoded_bytes as f64);
+
+    family(&mut out, "onelogin_cache_entries", "gauge",
+        "Entries in the response cache", tenants, |s| s.cache.as_ref().map_or(0.0, |c| c.entries as f64));
+    family(&mut out, "onelogin_cache_compressed_writes_total", "counter",
+        "Cache writes stored LZ4-compressed", tenants, |s| s.cache.as_ref().map_or(0.0, |c| c.compressed_writes as f64));
+    family(&mut out, "onelogin_cache_compression_saved_bytes_total", "counter",
+        "Bytes saved by compressing cache entries", tenants, |s| s.cache.as_ref().map_or(0.0, |c| c.saved_bytes as f64));
+
+    // The global limiter is shared by all tenants
+    if let Some(global) = tenants.iter().find_map(|(_, s)| s.global_rate_limiter.as_ref()) {
+        let _ = writeln!(out, "# HELP onelogin_global_rate_limiter_permits_granted_total Requests let through by the limiter shared by all tenants");
+        let _ = writeln!(out, "# TYPE onelogin_global_rate_limiter_permits_granted_total counter");
+        let _ = writeln!(out, "onelogin_global_rate_limiter_permits_granted_total {}", global.permits_granted);
+        let _ = writeln!(out, "# HELP onelogin_global_rate_limiter_throttled_requests_total Requests that waited for the global limiter");
+        let _ = writeln!(out, "# TYPE onelogin_global_rate_limiter_throttled_requests_total counter");
+        let _ = writeln!(out, "onelogin_global_rate_limiter_throttled_requests_total {}", global.throttled_requests);
+    }
+
+    // The retry budget is shared by all tenants
+    if let Some((_, stats)) = tenants.first() {
+        let budget = &stats.retry_budget;
+        let _ = writeln!(out, "# HELP onelogin_retry_budget_available Retries that can be made now");
+        let _ = writeln!(out, "# TYPE onelogin_retry_budget_available gauge");
+        let _ = writeln!(out, "onelogin_retry_budget_available {}", budget.available);
+        let _ = writeln!(out, "# HELP onelogin_retries_total Retried requests");
+        let _ = writeln!(out, "# TYPE onelogin_retries_total counter");
+        let _ = writeln!(out, "onelogin_retries_total {}", budget.retries);
+        let _ = writeln!(out, "# HELP onelogin_retries_skipped_total Failed requests not retried");
+        let _ = writeln!(out, "# TYPE onelogin_retries_skipped_total counter");
+        let _ = writeln!(out, "onelogin_retries_skipped_total{{reason=\"budget\"}} {}", budget.denied_by_budget);
+        let _ = writeln!(out, "onelogin_retries_skipped_total{{reason=\"not_idempotent\"}} {}", budget.not_idempotent);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::circuit_breaker::CircuitBreaker;
+    use crate::core::compression::CompressionStats;
+    use crate::core::rate_limit::RateLimiter;
+    use crate::core::retry::RetryBudget;
+    use crate::core::usage_budget::UsageBudget;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_render_exposition_format() {
+        let limiter = RateLimiter::new(10);
+        limiter.wait().await;
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(30));
+        breaker.record_failure();
+        let stats = ClientStats {
+            rate_limiter: limiter.stats(),
+            global_rate_limiter: None,
+            circuit_breaker: breaker.stats(),
+            compression: CompressionStats::new(true).snapshot(),
+            retry_budget: RetryBudget::new(0.2, 1).stats(),
+            usage: UsageBudget::new(Some(100), false).stats(),
+            fallback: None,
+            cache: None,
+        };
+
+        let text = render(&[("acme \"prod\"".to_string(), stats)]);
+        assert!(text.contains("# TYPE onelogin_circuit_breaker_trips_total counter\n"));
+        assert!(text.contains("onelogin_rate_limiter_permits_granted_total{tenant=\"acme \\\"prod\\\"\"} 1\n"));
+        assert!(text.contains("onelogin_circuit_breaker_state{tenant=\"acme \\\"prod\\\"\",state=\"open\"} 1\n"));
+        assert!(text.contains("state=\"closed\"} 0\n"));
+        assert!(text.contains("onelogin_fallback_region_active{tenant=\"acme \\\"prod\\\"\"} 0\n"));
+        assert!(text.contains("# TYPE onelogin_cache_compression_saved_bytes_total counter\n"));
+        assert!(text.contains("onelogin_retries_skipped_total{reason=\"budget\"} 0\n"));
+    }
+}
@@ -0,0 +1,372 @@
+//! In-memory fixture store backing mock/offline mode.
+//!
+//! Fixtures describe collections of records keyed by API path (e.g. `/api/2/users`)
+//! plus canned responses for one-off endpoints. Collections support list, get by
+//! id, create, update and delete, so agent workflows can be exercised end to end
+//! without credentials or a real tenant.
+
+use crate::core::error::{OneLoginError, Result};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+use tracing::{debug, info};
+
+/// Query parameters that control paging rather than filter records
+const PAGING_PARAMS: &[&str] = &[
+    "limit", "page", "cursor", "after_cursor", "before_cursor", "sort", "fields",
+];
+
+/// Fixture file layout. Multiple files are merged, later files winning.
+#[derive(Debug, Default, Deserialize)]
+pub struct FixtureFile {
+    /// Collection path -> records. Each record should carry an `id`.
+    #[serde(default)]
+    pub collections: HashMap<String, Vec<Value>>,
+    /// "METHOD /path" -> fixed response body
+    #[serde(default)]
+    pub responses: HashMap<String, Value>,
+}
+
+pub struct MockStore {
+    collections: RwLock<HashMap<String, Vec<Value>>>,
+    responses: HashMap<String, Value>,
+}
+
+impl MockStore {
+    pub fn from_fixtures(fixtures: FixtureFile) -> Self {
+        let collections = fixtures
+            .collections
+            .into_iter()
+            .map(|(path, records)| (normalize_path(&path), records))
+            .collect();
+        let responses = fixtures
+            .responses
+            .into_iter()
+            .map(|(key, value)| match key.split_once(' ') {
+                Some((method, path)) => (format!("{} {}", method.to_uppercase(), normalize_path(path)), value),
+                None => (format!("GET {}", normalize_path(&key)), value),
+            })
+            .collect();
+        Self {
+            collections: RwLock::new(collections),
+            responses,
+        }
+    }
+
+    /// Built-in demo tenant used when no fixture path is given
+    pub fn with_demo_data() -> Self {
+        let fixtures: FixtureFile = serde_json::from_value(demo_fixtures())
+            .expect("built-in demo fixtures are valid");
+        Self::from_fixtures(fixtures)
+    }
+
+    /// Load fixtures from a JSON file, or from every `*.json` file in a directory
+    /// (merged in file-name order) on top of the demo data.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut merged: FixtureFile = serde_json::from_value(demo_fixtures())?;
+
+        let mut files = Vec::new();
+        if path.is_dir() {
+            for entry in std::fs::read_dir(path)? {
+                let file = entry?.path();
+                if file.extension().and_then(|e| e.to_str()) == Some("json") {
+                    files.push(file);
+                }
+            }
+            files.sort();
+        } else {
+            files.push(path.to_path_buf());
+        }
+
+        for file in files {
+            let content = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read fixture {}: {}", file.display(), e))?;
+            let fixture: FixtureFile = serde_json::from_str(&content)
+                .map_err(|e| anyhow::anyhow!("Failed to parse fixture {}: {}", file.display(), e))?;
+            info!(
+                "Loaded fixture {} ({} collections, {} responses)",
+                file.display(),
+                fixture.collections.len(),
+                fixture.responses.len()
+            );
+            merged.collections.extend(fixture.collections);
+            merged.responses.extend(fixture.responses);
+        }
+
+        Ok(Self::from_fixtures(merged))
+    }
+
+    /// Handle one API call. `Ok(None)` means an empty (204) response.
+    pub fn handle(&self, method: &str, path: &str, body: Option<Value>) -> Result<Option<Value>> {
+        let (path, query) = match path.split_once('?') {
+            Some((p, q)) => (normalize_path(p), q),
+            None => (normalize_path(path), ""),
+        };
+        let method = method.to_uppercase();
+        debug!("Mock {} {} (query: {})", method, path, query);
+
+        if let Some(value) = self.responses.get(&format!("{} {}", method, path)) {
+            return Ok(Some(value.clone()));
+        }
+
+        let mut collections = self.collections.write().expect("RwLock poisoned");
+
+        // Exact collection path: list or create
+        if let Some(records) = collections.get_mut(&path) {
+            return match method.as_str() {
+                "GET" => Ok(Some(wrap_v1(&path, Value::Array(filter_records(records, query))))),
+                "POST" => {
+                    let record = create_record(records, body);
+                    Ok(Some(wrap_v1(&path, record)))
+                }
+                _ => Err(not_found(&method, &path)),
+            };
+        }
+
+        // Item path: {collection}/{id}
+        if let Some((collection, id)) = path.rsplit_once('/') {
+            if let Some(records) = collections.get_mut(collection) {
+                let index = records.iter().position(|r| id_matches(r, id));
+                return match (method.as_str(), index) {
+                    ("GET", Some(i)) => Ok(Some(wrap_v1(collection, records[i].clone()))),
+                    ("PUT" | "PATCH", Some(i)) => {
+                        merge_into(&mut records[i], body);
+                        Ok(Some(wrap_v1(collection, records[i].clone())))
+                    }
+                    ("DELETE", Some(i)) => {
+                        records.remove(i);
+                        Ok(None)
+                    }
+                    _ => Err(OneLoginError::NotFound(format!(
+                        "Mock record {} not found in {}",
+                        id, collection
+                    ))),
+                };
+            }
+        }
+
+        // Writes to unknown sub-resources (e.g. /users/1/lock) succeed without effect
+        if method != "GET" {
+            return Ok(None);
+        }
+        Err(not_found(&method, &path))
+    }
+}
+
+fn not_found(method: &str, path: &str) -> OneLoginError {
+    OneLoginError::NotFound(format!(
+        "No mock fixture for {} {}. Add it under \"collections\" or \"responses\" in a fixture file.",
+        method, path
+    ))
+}
+
+fn normalize_path(path: &str) -> String {
+    format!("/{}", path.trim_matches('/'))
+}
+
+/// v1 endpoints wrap payloads in a status/data envelope
+fn wrap_v1(path: &str, value: Value) -> Value {
+    if !path.starts_with("/api/1/") {
+        return value;
+    }
+    let data = match value {
+        Value::Array(items) => Value::Array(items),
+        other => Value::Array(vec![other]),
+    };
+    json!({
+        "status": {"error": false, "code": 200, "type": "success", "message": "Success"},
+        "pagination": {"before_cursor": null, "after_cursor": null, "previous_link": null, "next_link": null},
+        "data": data
+    })
+}
+
+fn id_matches(record: &Value, id: &str) -> bool {
+    match record.get("id") {
+        Some(Value::String(s)) => s == id,
+        Some(Value::Number(n)) => n.to_string() == id,
+        _ => false,
+    }
+}
+
+fn filter_records(records: &[Value], query: &str) -> Vec<Value> {
+    let mut limit = None;
+    let mut since_id = None;
+    let mut until_id = None;
+    let mut filters = Vec::new();
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = urlencoding::decode(value).map(|v| v.into_owned()).unwrap_or_default();
+        if key == "limit" {
+            limit = value.parse::<usize>().ok();
+        } else if key == "since_id" {
+            since_id = value.parse::<i64>().ok();
+        } else if key == "until_id" {
+            until_id = value.parse::<i64>().ok();
+        } else if !PAGING_PARAMS.contains(&key) {
+            filters.push((key.to_string(), value));
+        }
+    }
+
+    let id_in_range = |record: &Value| {
+        let id = record.get("id").and_then(Value::as_i64);
+        if let Some(since) = since_id {
+            if id.is_none_or(|id| id <= since) {
+                return false;
+            }
+        }
+        if let Some(until) = until_id {
+            if id.is_none_or(|id| id >= until) {
+                return false;
+            }
+        }
+        true
+    };
+
+    records
+        .iter()
+        .filter(|record| id_in_range(record))
+        .filter(|record| {
+            filters.iter().all(|(key, expected)| match record.get(key) {
+                // Filters on fields the fixtures don't carry are ignored
+                None => true,
+                Some(actual) => {
+                    let actual = match actual {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    match expected.strip_suffix('*') {
+                        Some(prefix) => actual.starts_with(prefix),
+                        None => actual.eq_ignore_ascii_case(expected),
+                    }
+                }
+            })
+        })
+        .take(limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect()
+}
+
+fn create_record(records: &mut Vec<Value>, body: Option<Value>) -> Value {
+    let next_id = records
+        .iter()
+        .filter_map(|r| r.get("id").and_then(|v| v.as_i64()))
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut record = Value::Object(Map::new());
+    merge_into(&mut record, body);
+    if let Value::Object(map) = &mut record {
+        map.entry("id").or_insert_with(|| json!(next_id));
+        let now = chrono::Utc::now().to_rfc3339();
+        map.entry("created_at").or_insert_with(|| json!(now));
+        map.insert("updated_at".to_string(), json!(now));
+    }
+    records.push(record.clone());
+    record
+}
+
+fn merge_into(record: &mut Value, body: Option<Value>) {
+    if let (Value::Object(target), Some(Value::Object(source))) = (record, body) {
+        for (key, value) in source {
+            target.insert(key, value);
+        }
+    }
+}
+
+fn demo_fixtures() -> Value {
+    json!({
+        "collections": {
+            "/api/2/users": [
+                {"id": 1001, "email": "ada.lovelace@example.com", "username": "ada", "firstname": "Ada", "lastname": "Lovelace",
+                 "title": "Chief Engineer", "department": "Engineering", "status": 1, "state": 1, "role_ids": [201, 202],
+                 "group_id": 301, "invalid_login_attempts": 0, "last_login": "2026-01-10T09:15:00Z", "created_at": "2024-03-01T12:00:00Z"},
+                {"id": 1002, "email": "grace.hopper@example.com", "username": "grace", "firstname": "Grace", "lastname": "Hopper",
+                 "title": "Platform Lead", "department": "Engineering", "status": 1, "state": 1, "role_ids": [202],
+                 "group_id": 301, "invalid_login_attempts": 0, "last_login": "2026-01-09T17:42:00Z", "created_at": "2024-04-12T08:30:00Z",
+                 "manager_user_id": 1001},
+                {"id": 1003, "email": "alan.turing@example.com", "username": "alan", "firstname": "Alan", "lastname": "Turing",
+                 "title": "Analyst", "department": "Finance", "status": 3, "state": 1, "role_ids": [203],
+                 "group_id": 302, "invalid_login_attempts": 5, "locked_until": "2026-01-11T00:00:00Z", "created_at": "2024-06-20T10:00:00Z"}
+            ],
+            "/api/2/roles": [
+                {"id": 201, "name": "Administrators", "users": [1001], "apps": [401, 402], "admins": [1001]},
+                {"id": 202, "name": "Engineering", "users": [1001, 1002], "apps": [402], "admins": []},
+                {"id": 203, "name": "Finance", "users": [1003], "apps": [403], "admins": []}
+            ],
+            "/api/2/groups": [
+                {"id": 301, "name": "Engineering", "reference": null},
+                {"id": 302, "name": "Finance", "reference": null}
+            ],
+            "/api/2/apps": [
+                {"id": 401, "name": "AWS Console", "connector_id": 50534, "visible": true, "auth_method": 2},
+                {"id": 402, "name": "GitHub Enterprise", "connector_id": 110016, "visible": true, "auth_method": 2},
+                {"id": 403, "name": "NetSuite", "connector_id": 28596, "visible": true, "auth_method": 2}
+            ],
+            "/api/2/connectors": [
+                {"id": 110016, "name": "SAML Custom Connector (Advanced)", "auth_method": 2},
+                {"id": 108419, "name": "OpenId Connect (OIDC)", "auth_method": 8}
+            ],
+            "/api/1/events": [
+                {"id": 900001, "event_type_id": 5, "user_id": 1001, "user_name": "Ada Lovelace", "ipaddr": "203.0.113.10",
+                 "created_at": "2026-01-10T09:15:00Z"},
+                {"id": 900002, "event_type_id": 6, "user_id": 1003, "user_name": "Alan Turing", "ipaddr": "198.51.100.7",
+                 "created_at": "2026-01-10T11:02:00Z"}
+            ]
+        },
+        "responses": {
+            "GET /auth/rate_limit": {
+                "status": {"error": false, "code": 200, "type": "success", "message": "Success"},
+                "data": {"X-RateLimit-Limit": 5000, "X-RateLimit-Remaining": 4990, "X-RateLimit-Reset": 1200}
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crud_on_collection() {
+        let store = MockStore::with_demo_data();
+
+        let users = store.handle("GET", "/api/2/users?email=grace*&limit=10", None).unwrap().unwrap();
+        assert_eq!(users.as_array().unwrap().len(), 1);
+        assert_eq!(users[0]["id"], 1002);
+
+        let created = store
+            .handle("POST", "/api/2/users", Some(json!({"email": "new@example.com", "username": "new"})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(created["id"], 1004);
+
+        let updated = store
+            .handle("PUT", "/api/2/users/1004", Some(json!({"title": "Tester"})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated["title"], "Tester");
+        assert_eq!(updated["email"], "new@example.com");
+
+        assert!(store.handle("DELETE", "/api/2/users/1004", None).unwrap().is_none());
+        assert!(matches!(
+            store.handle("GET", "/api/2/users/1004", None),
+            Err(OneLoginError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_v1_envelope_and_canned_responses() {
+        let store = MockStore::with_demo_data();
+        let events = store.handle("GET", "/api/1/events?since=2026-01-01", None).unwrap().unwrap();
+        assert_eq!(events["data"].as_array().unwrap().len(), 2);
+        assert_eq!(events["status"]["code"], 200);
+
+        let limits = store.handle("GET", "/auth/rate_limit", None).unwrap().unwrap();
+        assert_eq!(limits["data"]["X-RateLimit-Limit"], 5000);
+
+        assert!(store.handle("GET", "/api/2/unknown", None).is_err());
+        assert!(store.handle("PUT", "/api/2/users/1001/lock", None).unwrap().is_none());
+    }
+}
@@ -1,8 +1,27 @@
+pub mod admin;
 pub mod auth;
 pub mod cache;
+pub mod cassette;
+pub mod checkpoints;
+pub mod circuit_breaker;
 pub mod client;
+pub mod compression;
 pub mod config;
+pub mod cooldowns;
+pub mod cursors;
 pub mod error;
+pub mod http_listener;
+pub mod keyed_mutex;
+pub mod metrics;
+pub mod mock;
+pub mod onboarding;
 pub mod rate_limit;
+pub mod response_cache;
+pub mod retry;
+pub mod role_snapshots;
+pub mod scheduler;
+pub mod service;
 pub mod tenant_manager;
 pub mod tool_config;
+pub mod usage_budget;
+pub mod webhook_receiver;
@@ -0,0 +1,257 @@
+//! Adding a tenant from inside an MCP session.
+//!
+//! The `onelogin-onboard-tenant` prompt walks an agent through creating API
+//! credentials in OneLogin, checking them with
+//! `onelogin_validate_tenant_credentials`, writing the tenant to tenants.json
+//! with `onelogin_add_tenant` and choosing tool categories with
+//! `onelogin_set_tool_categories`. tenants.json holds client secrets, so it is
+//! rewritten through a temporary file and, on Unix, readable by the owner only.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::Duration;
+
+use super::config::{ApiVersion, OneLoginRegion};
+
+/// Deadline of each request made while checking credentials
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Subdomain and, when the input was a URL naming it, region of a tenant.
+/// Accepts "acme", "acme.onelogin.com" or "https://acme.eu.onelogin.com/admin".
+pub fn normalize_subdomain(input: &str) -> Result<(String, Option<OneLoginRegion>)> {
+    let host = input
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let (subdomain, region) = if let Some(sub) = host.strip_suffix(".eu.onelogin.com") {
+        (sub, Some(OneLoginRegion::EU))
+    } else if let Some(sub) = host.strip_suffix(".onelogin.com") {
+        (sub, Some(OneLoginRegion::US))
+    } else {
+        (host.as_str(), None)
+    };
+    let valid = !subdomain.is_empty()
+        && !subdomain.starts_with('-')
+        && !subdomain.ends_with('-')
+        && subdomain.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(anyhow!(
+            "Invalid subdomain '{}': expected the part before .onelogin.com, e.g. 'acme' for acme.onelogin.com",
+            input.trim()
+        ));
+    }
+    Ok((subdomain.to_string(), region))
+}
+
+/// Tenant names are used as the `tenant` argument of every tool
+pub fn validate_tenant_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(anyhow!(
+            "Invalid tenant name '{}': use 1-64 letters, digits, '-' or '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+pub fn parse_region(region: &str) -> Result<OneLoginRegion> {
+    match region.to_lowercase().as_str() {
+        "us" => Ok(OneLoginRegion::US),
+        "eu" => Ok(OneLoginRegion::EU),
+        _ => Err(anyhow!("Invalid region '{}': expected 'us' or 'eu'", region)),
+    }
+}
+
+/// Add `entry` to the tenants file at `path`, creating the file if needed.
+/// An existing tenant of the same name is only replaced with `replace`.
+/// Fields this server doesn't know are kept as they are.
+pub fn upsert_tenant(path: &Path, entry: Value, replace: bool) -> Result<Value> {
+    let name = entry["name"].as_str().ok_or_else(|| anyhow!("Tenant entry has no name"))?.to_string();
+    let created_file = !path.exists();
+    let mut file = if created_file {
+        json!({"tenants": []})
+    } else {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tenants config: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse tenants config: {}", path.display()))?
+    };
+    if !file["tenants"].is_array() {
+        return Err(anyhow!("{} has no 'tenants' array", path.display()));
+    }
+    let tenants = file["tenants"].as_array_mut().expect("checked above");
+
+    let existing = tenants.iter().position(|t| t["name"].as_str() == Some(name.as_str()));
+    if existing.is_some() && !replace {
+        return Err(anyhow!(
+            "Tenant '{}' already exists in {}; pass replace=true to overwrite it",
+            name,
+            path.display()
+        ));
+    }
+    if entry["default"].as_bool() == Some(true) {
+        for tenant in tenants.iter_mut() {
+            if let Some(fields) = tenant.as_object_mut() {
+                fields.insert("default".to_string(), json!(false));
+            }
+        }
+    }
+    match existing {
+        Some(index) => tenants[index] = entry,
+        None => tenants.push(entry),
+    }
+    let tenant_count = tenants.len();
+
+    write_private(path, &serde_json::to_string_pretty(&file)?)?;
+    Ok(json!({
+        "path": path.display().to_string(),
+        "created_file": created_file,
+        "replaced": existing.is_some(),
+        "tenant_count": tenant_count
+    }))
+}
+
+/// Write `content` through a temporary file in the same directory, so a crash
+/// never leaves a truncated file behind, with owner-only permissions on Unix
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    let tmp = path.with_extension(format!("json.tmp-{}", std::process::id()));
+    std::fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Failed to restrict permissions of {}", tmp.display()))?;
+    }
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Request a token with the credentials and probe what they may read.
+/// The secret is never part of the result or of error messages.
+pub async fn check_credentials(
+    subdomain: &str,
+    region: OneLoginRegion,
+    client_id: &str,
+    client_secret: &str,
+) -> Value {
+    let base_url = region.tenant_base_url(subdomain);
+    let http = match reqwest::Client::builder().timeout(CHECK_TIMEOUT).build() {
+        Ok(http) => http,
+        Err(e) => return json!({"valid": false, "base_url": base_url, "error": format!("Failed to build HTTP client: {}", e)}),
+    };
+
+    let response = http
+        .post(format!("{}{}", base_url, ApiVersion::Root.path("auth/oauth2/v2/token")))
+        .basic_auth(client_id, Some(client_secret))
+        .json(&json!({"grant_type": "client_credentials"}))
+        .send()
+        .await;
+    let token = match response {
+        Ok(r) if r.status().is_success() => r
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body["access_token"].as_str().map(str::to_string)),
+        Ok(r) => {
+            let status = r.status();
+            let hint = match status.as_u16() {
+                400 | 401 => "the client ID or secret is wrong, or the credential was deleted",
+                404 => "no OneLogin account at this subdomain and region",
+                _ => "unexpected response from OneLogin",
+            };
+            return json!({"valid": false, "base_url": base_url, "error": format!("Token request failed with HTTP {}: {}", status.as_u16(), hint)});
+        }
+        Err(e) => {
+            return json!({"valid": false, "base_url": base_url, "error": format!("Could not reach {}: {}", base_url, e)});
+        }
+    };
+    let Some(token) = token else {
+        return json!({"valid": false, "base_url": base_url, "error": "Token response had no access_token"});
+    };
+
+    // Credentials scoped to "Authentication only" get a token but can't call the API
+    let mut scopes = serde_json::Map::new();
+    for (scope, path) in [("read_users", "users?limit=1"), ("read_all", "apps?limit=1")] {
+        let allowed = http
+            .get(format!("{}{}", base_url, ApiVersion::V2.path(path)))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .is_ok_and(|r| r.status().is_success());
+        scopes.insert(scope.to_string(), json!(allowed));
+    }
+    json!({
+        "valid": true,
+        "base_url": base_url,
+        "token_issued": true,
+        "scopes": scopes
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_subdomain() {
+        let (sub, region) = normalize_subdomain("Acme").unwrap();
+        assert_eq!((sub.as_str(), region), ("acme", None));
+        let (sub, region) = normalize_subdomain("acme-corp.onelogin.com").unwrap();
+        assert_eq!((sub.as_str(), region), ("acme-corp", Some(OneLoginRegion::US)));
+        let (sub, region) = normalize_subdomain("https://acme.eu.onelogin.com/admin2").unwrap();
+        assert_eq!((sub.as_str(), region), ("acme", Some(OneLoginRegion::EU)));
+        assert!(normalize_subdomain("").is_err());
+        assert!(normalize_subdomain("acme.example.com").is_err());
+        assert!(normalize_subdomain("-acme").is_err());
+    }
+
+    #[test]
+    fn test_validate_tenant_name() {
+        assert!(validate_tenant_name("prod_eu-1").is_ok());
+        assert!(validate_tenant_name("").is_err());
+        assert!(validate_tenant_name("prod eu").is_err());
+    }
+
+    #[test]
+    fn test_upsert_tenant() {
+        let path = std::env::temp_dir().join(format!("tenants-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let prod = json!({"name": "prod", "subdomain": "acme", "region": "us", "client_id": "a", "client_secret": "s", "default": true});
+        let result = upsert_tenant(&path, prod.clone(), false).unwrap();
+        assert_eq!(result["created_file"], true);
+
+        let staging = json!({"name": "staging", "subdomain": "acme-stg", "region": "us", "client_id": "b", "client_secret": "t", "default": true});
+        let result = upsert_tenant(&path, staging, false).unwrap();
+        assert_eq!((result["tenant_count"].as_u64(), result["replaced"].as_bool()), (Some(2), Some(false)));
+        assert!(upsert_tenant(&path, prod.clone(), false).is_err());
+        assert_eq!(upsert_tenant(&path, prod, true).unwrap()["replaced"], true);
+
+        let file: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let defaults: Vec<&str> = file["tenants"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|t| t["default"] == true)
+            .filter_map(|t| t["name"].as_str())
+            .collect();
+        assert_eq!(defaults, vec!["prod"]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+}
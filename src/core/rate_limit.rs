@@ -3,10 +3,41 @@ use governor::{
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter as GovernorRateLimiter,
 };
+use serde::{Deserialize, Serialize};
 use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 pub struct RateLimiter {
     limiter: GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>,
+    requests_per_second: u32,
+    permits_granted: AtomicU64,
+    throttled_requests: AtomicU64,
+    upstream_rate_limited: AtomicU64,
+    /// Total time requests spent waiting for a permit
+    wait_micros: AtomicU64,
+    /// Theoretical arrival time of the next permit (mirrors governor's GCRA state,
+    /// which it does not expose) for estimating available tokens
+    next_permit_at: Mutex<Instant>,
+    /// Cap shared with other tenants' limiters, taken after this limiter's own permit
+    global: Option<Arc<RateLimiter>>,
+}
+
+/// Snapshot of the local limiter's configuration and counters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimiterStats {
+    pub requests_per_second: u32,
+    /// Requests let through by the limiter since startup
+    pub permits_granted: u64,
+    /// Requests that had to wait for a permit
+    pub throttled_requests: u64,
+    /// 429 responses received from OneLogin
+    pub upstream_rate_limited: u64,
+    /// Permits that could be taken right now without waiting (estimated)
+    pub tokens_available: u32,
+    /// Time requests have spent waiting for a permit since startup
+    pub wait_time_ms: u64,
 }
 
 impl RateLimiter {
@@ -16,13 +47,80 @@ impl RateLimiter {
         );
         let limiter = GovernorRateLimiter::direct(quota);
 
-        Self { limiter }
+        Self {
+            limiter,
+            requests_per_second,
+            permits_granted: AtomicU64::new(0),
+            throttled_requests: AtomicU64::new(0),
+            upstream_rate_limited: AtomicU64::new(0),
+            wait_micros: AtomicU64::new(0),
+            next_permit_at: Mutex::new(Instant::now()),
+            global: None,
+        }
+    }
+
+    /// Also wait on `global`, a limiter shared by every tenant
+    pub fn with_global(mut self, global: Arc<RateLimiter>) -> Self {
+        self.global = Some(global);
+        self
+    }
+
+    /// Counters of the shared global limiter, if this limiter has one
+    pub fn global_stats(&self) -> Option<RateLimiterStats> {
+        self.global.as_ref().map(|global| global.stats())
+    }
+
+    fn permit_interval(&self) -> Duration {
+        Duration::from_secs(1) / self.requests_per_second
     }
 
     pub async fn wait(&self) {
+        let started = Instant::now();
+        let mut throttled = false;
         while self.limiter.check().is_err() {
+            throttled = true;
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
         }
+        if throttled {
+            self.throttled_requests.fetch_add(1, Ordering::Relaxed);
+            self.wait_micros
+                .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+        }
+        self.permits_granted.fetch_add(1, Ordering::Relaxed);
+
+        {
+            let now = Instant::now();
+            let mut next = self.next_permit_at.lock().expect("Mutex poisoned");
+            *next = (*next).max(now) + self.permit_interval();
+        }
+
+        if let Some(global) = &self.global {
+            Box::pin(global.wait()).await;
+        }
+    }
+
+    /// Burst capacity minus permits not yet replenished
+    fn tokens_available(&self) -> u32 {
+        let next = *self.next_permit_at.lock().expect("Mutex poisoned");
+        let backlog = next.saturating_duration_since(Instant::now());
+        let pending = backlog.as_nanos().div_ceil(self.permit_interval().as_nanos().max(1));
+        self.requests_per_second.saturating_sub(pending as u32)
+    }
+
+    /// Record a 429 response from the OneLogin API
+    pub fn record_upstream_limit(&self) {
+        self.upstream_rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> RateLimiterStats {
+        RateLimiterStats {
+            requests_per_second: self.requests_per_second,
+            permits_granted: self.permits_granted.load(Ordering::Relaxed),
+            throttled_requests: self.throttled_requests.load(Ordering::Relaxed),
+            upstream_rate_limited: self.upstream_rate_limited.load(Ordering::Relaxed),
+            tokens_available: self.tokens_available(),
+            wait_time_ms: self.wait_micros.load(Ordering::Relaxed) / 1000,
+        }
     }
 }
 
@@ -36,4 +134,42 @@ mod tests {
         // Should not block
         limiter.wait().await;
     }
+
+    #[tokio::test]
+    async fn test_rate_limiter_stats() {
+        let limiter = RateLimiter::new(10);
+        limiter.wait().await;
+        limiter.wait().await;
+        limiter.record_upstream_limit();
+
+        let stats = limiter.stats();
+        assert_eq!(stats.requests_per_second, 10);
+        assert_eq!(stats.permits_granted, 2);
+        assert_eq!(stats.upstream_rate_limited, 1);
+        assert!(stats.tokens_available <= 8);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_limiters_share_global_cap() {
+        let global = Arc::new(RateLimiter::new(3));
+        let acme = RateLimiter::new(10).with_global(global.clone());
+        let globex = RateLimiter::new(10).with_global(global.clone());
+        acme.wait().await;
+        acme.wait().await;
+        globex.wait().await;
+
+        // Each tenant only counts its own permits; the global limiter sees all of them
+        assert_eq!(acme.stats().permits_granted, 2);
+        assert_eq!(globex.stats().permits_granted, 1);
+        assert_eq!(global.stats().permits_granted, 3);
+        assert_eq!(acme.global_stats().unwrap().permits_granted, 3);
+        assert!(RateLimiter::new(10).global_stats().is_none());
+
+        // The global burst is spent, so the next request waits even though the tenant has tokens
+        let started = Instant::now();
+        globex.wait().await;
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(global.stats().throttled_requests, 1);
+        assert_eq!(globex.stats().throttled_requests, 0);
+    }
 }
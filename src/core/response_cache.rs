@@ -0,0 +1,116 @@
+//! Computed results of expensive report and analytics tools.
+//!
+//! `CacheManager` holds individual API responses; this holds a tool's whole
+//! output, keyed by tenant, tool and arguments, so repeating a report skips both
+//! the API calls and the aggregation. Lifetimes come from `response_cache_ttls`
+//! in the tool config. A call with `force_refresh=true` recomputes and replaces
+//! the entry, and a successful write to a tenant drops that tenant's entries.
+
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Arguments that don't change a tool's result
+const IGNORED_ARGS: &[&str] = &["tenant", "force_refresh", "tool_timeout_seconds"];
+
+/// Most results held; expired ones are dropped first, then the oldest
+const MAX_ENTRIES: usize = 256;
+
+struct Entry {
+    tenant: String,
+    stored_at: Instant,
+    ttl: Duration,
+    result: Value,
+}
+
+/// A cached result with its age and remaining lifetime
+pub struct CachedResponse {
+    pub result: Value,
+    pub age: Duration,
+    pub expires_in: Duration,
+}
+
+#[derive(Default)]
+pub struct ResponseCache {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl ResponseCache {
+    /// Cache key of a call: the same arguments in any order give the same key
+    pub fn key(tenant: &str, tool: &str, args: &Value) -> String {
+        let relevant: BTreeMap<&String, &Value> = args
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(name, value)| !IGNORED_ARGS.contains(&name.as_str()) && !value.is_null())
+            .collect();
+        format!("{}:{}:{}", tenant, tool, serde_json::to_string(&relevant).unwrap_or_default())
+    }
+
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.entries.lock().expect("response cache poisoned");
+        let entry = entries.get(key)?;
+        let age = entry.stored_at.elapsed();
+        if age >= entry.ttl {
+            entries.remove(key);
+            return None;
+        }
+        Some(CachedResponse { result: entry.result.clone(), age, expires_in: entry.ttl - age })
+    }
+
+    pub fn insert(&self, key: String, tenant: &str, ttl: Duration, result: &Value) {
+        let mut entries = self.entries.lock().expect("response cache poisoned");
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+            entries.retain(|_, e| e.stored_at.elapsed() < e.ttl);
+            if entries.len() >= MAX_ENTRIES {
+                if let Some(oldest) = entries.iter().min_by_key(|(_, e)| e.stored_at).map(|(k, _)| k.clone()) {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, Entry { tenant: tenant.to_string(), stored_at: Instant::now(), ttl, result: result.clone() });
+    }
+
+    /// Drop every result computed for `tenant`, after a write may have changed its data
+    pub fn invalidate_tenant(&self, tenant: &str) {
+        self.entries.lock().expect("response cache poisoned").retain(|_, e| e.tenant != tenant);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_ignores_order_and_call_options() {
+        let a = ResponseCache::key("prod", "onelogin_app_access_report", &json!({"app_id": 5, "include_roles": true}));
+        let b = ResponseCache::key(
+            "prod",
+            "onelogin_app_access_report",
+            &json!({"include_roles": true, "force_refresh": true, "app_id": 5, "tenant": "prod", "note": null}),
+        );
+        assert_eq!(a, b);
+        assert_ne!(a, ResponseCache::key("prod", "onelogin_app_access_report", &json!({"app_id": 6, "include_roles": true})));
+        assert_ne!(a, ResponseCache::key("staging", "onelogin_app_access_report", &json!({"app_id": 5, "include_roles": true})));
+    }
+
+    #[test]
+    fn test_get_insert_and_invalidate() {
+        let cache = ResponseCache::default();
+        let report = json!({"users": 42});
+        cache.insert("prod:report:{}".to_string(), "prod", Duration::from_secs(300), &report);
+        cache.insert("staging:report:{}".to_string(), "staging", Duration::from_secs(300), &report);
+        cache.insert("prod:expired:{}".to_string(), "prod", Duration::ZERO, &report);
+
+        let hit = cache.get("prod:report:{}").unwrap();
+        assert_eq!(hit.result, report);
+        assert!(hit.expires_in <= Duration::from_secs(300));
+        assert!(cache.get("prod:expired:{}").is_none());
+
+        cache.invalidate_tenant("prod");
+        assert!(cache.get("prod:report:{}").is_none());
+        assert!(cache.get("staging:report:{}").is_some());
+    }
+}
@@ -0,0 +1,219 @@
+//! Retry policy for `HttpClient`.
+//!
+//! Only requests that are safe to repeat are retried: GET, HEAD, OPTIONS, PUT
+//! and DELETE. POST and PATCH are retried only when OneLogin demonstrably did
+//! not process them (429, 401 or a failed connection). Retries also draw from a budget shared by all tenants, so a
+//! failing tenant cannot multiply traffic: every request deposits `ratio` of a
+//! retry, and a small per-second reserve lets occasional retries through.
+
+use crate::core::error::OneLoginError;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Deposited retries are capped so an idle period can't bank a retry storm
+const MAX_DEPOSITED: f64 = 100.0;
+
+/// Local rate limiter waits at least this long are reported as rate limiting
+pub const NOTABLE_RATE_LIMIT_WAIT_MS: u64 = 100;
+
+/// Methods that can be repeated without changing the outcome
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE)
+}
+
+/// Errors showing the request was not acted on, so any method can be resent
+pub fn was_not_processed(error: &OneLoginError) -> bool {
+    match error {
+        OneLoginError::RateLimitExceeded => true,
+        OneLoginError::HttpClientError(e) => e.is_connect(),
+        e => e.is_authentication_failure(),
+    }
+}
+
+/// Retries and rate limit waits behind one tool call, across all of its requests
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RetryDiagnostics {
+    /// HTTP attempts made, first attempts included
+    pub attempts: u32,
+    pub retries: u32,
+    /// Time spent in exponential backoff between attempts
+    pub total_backoff_ms: u64,
+    /// Time spent waiting on the client-side rate limiter
+    pub rate_limit_wait_ms: u64,
+    /// OneLogin answered 429, or the rate limiter held a request noticeably
+    pub rate_limited: bool,
+}
+
+impl RetryDiagnostics {
+    pub fn record_rate_limit_wait(&mut self, waited_ms: u64) {
+        self.rate_limit_wait_ms += waited_ms;
+        if waited_ms >= NOTABLE_RATE_LIMIT_WAIT_MS {
+            self.rate_limited = true;
+        }
+    }
+
+    /// Whether the call was slowed enough to tell the caller about
+    pub fn is_notable(&self) -> bool {
+        self.retries > 0 || self.rate_limited
+    }
+}
+
+struct Balance {
+    /// Retries earned by requests (`ratio` per request)
+    deposited: f64,
+    /// Per-second allowance, refilled continuously up to `min_per_second`
+    reserve: f64,
+    refilled_at: Instant,
+}
+
+pub struct RetryBudget {
+    ratio: f64,
+    min_per_second: u32,
+    balance: Mutex<Balance>,
+    retries: AtomicU64,
+    denied_by_budget: AtomicU64,
+    not_idempotent: AtomicU64,
+}
+
+/// Snapshot of the retry budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryBudgetStats {
+    /// Retries earned per request
+    pub ratio: f64,
+    pub min_retries_per_second: u32,
+    /// Retries that could be made right now
+    pub available: u64,
+    /// Retries made since startup
+    pub retries: u64,
+    /// Retries skipped because the budget was exhausted
+    pub denied_by_budget: u64,
+    /// Failed POST/PATCH requests not retried because OneLogin may have processed them
+    pub not_idempotent: u64,
+}
+
+impl RetryBudget {
+    pub fn new(ratio: f64, min_per_second: u32) -> Self {
+        Self {
+            ratio: ratio.max(0.0),
+            min_per_second,
+            balance: Mutex::new(Balance {
+                deposited: 0.0,
+                reserve: min_per_second as f64,
+                refilled_at: Instant::now(),
+            }),
+            retries: AtomicU64::new(0),
+            denied_by_budget: AtomicU64::new(0),
+            not_idempotent: AtomicU64::new(0),
+        }
+    }
+
+    fn refill(&self, balance: &mut Balance) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(balance.refilled_at).as_secs_f64();
+        balance.reserve = (balance.reserve + elapsed * self.min_per_second as f64).min(self.min_per_second as f64);
+        balance.refilled_at = now;
+    }
+
+    /// Credit the budget for a new (first-attempt) request
+    pub fn record_request(&self) {
+        let mut balance = self.balance.lock().expect("Mutex poisoned");
+        balance.deposited = (balance.deposited + self.ratio).min(MAX_DEPOSITED);
+    }
+
+    /// Take one retry from the budget; false when exhausted
+    pub fn try_retry(&self) -> bool {
+        let mut balance = self.balance.lock().expect("Mutex poisoned");
+        self.refill(&mut balance);
+        let allowed = if balance.reserve >= 1.0 {
+            balance.reserve -= 1.0;
+            true
+        } else if balance.deposited >= 1.0 {
+            balance.deposited -= 1.0;
+            true
+        } else {
+            false
+        };
+        let counter = if allowed { &self.retries } else { &self.denied_by_budget };
+        counter.fetch_add(1, Ordering::Relaxed);
+        allowed
+    }
+
+    /// Count a failed request that was not retried because it is not idempotent
+    pub fn record_not_idempotent(&self) {
+        self.not_idempotent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> RetryBudgetStats {
+        let available = {
+            let mut balance = self.balance.lock().expect("Mutex poisoned");
+            self.refill(&mut balance);
+            (balance.reserve.floor() + balance.deposited.floor()) as u64
+        };
+        RetryBudgetStats {
+            ratio: self.ratio,
+            min_retries_per_second: self.min_per_second,
+            available,
+            retries: self.retries.load(Ordering::Relaxed),
+            denied_by_budget: self.denied_by_budget.load(Ordering::Relaxed),
+            not_idempotent: self.not_idempotent.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_notable_after_retry_or_long_wait() {
+        let mut diagnostics = RetryDiagnostics { attempts: 1, ..Default::default() };
+        diagnostics.record_rate_limit_wait(5);
+        assert!(!diagnostics.is_notable());
+        diagnostics.record_rate_limit_wait(NOTABLE_RATE_LIMIT_WAIT_MS);
+        assert!(diagnostics.rate_limited);
+        assert_eq!(diagnostics.rate_limit_wait_ms, 5 + NOTABLE_RATE_LIMIT_WAIT_MS);
+
+        let retried = RetryDiagnostics { attempts: 2, retries: 1, ..Default::default() };
+        assert!(retried.is_notable());
+    }
+
+    #[test]
+    fn test_idempotent_methods() {
+        assert!(is_idempotent(&Method::GET));
+        assert!(is_idempotent(&Method::PUT));
+        assert!(is_idempotent(&Method::DELETE));
+        assert!(!is_idempotent(&Method::POST));
+        assert!(!is_idempotent(&Method::PATCH));
+
+        assert!(was_not_processed(&OneLoginError::RateLimitExceeded));
+        assert!(!was_not_processed(&OneLoginError::ApiRequestFailed("502".to_string())));
+    }
+
+    #[test]
+    fn test_budget_limits_retries() {
+        let budget = RetryBudget::new(0.5, 0);
+        assert!(!budget.try_retry());
+
+        // Two requests earn one retry
+        budget.record_request();
+        budget.record_request();
+        assert!(budget.try_retry());
+        assert!(!budget.try_retry());
+
+        let stats = budget.stats();
+        assert_eq!(stats.retries, 1);
+        assert_eq!(stats.denied_by_budget, 2);
+        assert_eq!(stats.available, 0);
+    }
+
+    #[test]
+    fn test_reserve_allows_occasional_retries() {
+        let budget = RetryBudget::new(0.0, 2);
+        assert!(budget.try_retry());
+        assert!(budget.try_retry());
+        assert!(!budget.try_retry());
+    }
+}
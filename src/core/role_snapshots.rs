@@ -0,0 +1,355 @@
+//! Persistent snapshots of role memberships.
+//!
+//! OneLogin only keeps events for a limited time, so "who was added to the
+//! Admin role this month" can't always be answered from the event log. A
+//! snapshot records every role's members at one moment; scheduling
+//! `onelogin_snapshot_role_memberships` builds a history that two snapshots can
+//! be diffed against. Snapshots are kept per tenant in
+//! `~/.config/onelogin-mcp/role_snapshots.json` (override with
+//! `ONELOGIN_MCP_ROLE_SNAPSHOTS`).
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Overrides the default snapshot file location
+pub const ROLE_SNAPSHOTS_ENV_VAR: &str = "ONELOGIN_MCP_ROLE_SNAPSHOTS";
+
+/// Snapshots kept per tenant unless the caller asks for another limit
+pub const DEFAULT_KEEP: usize = 90;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleMember {
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleMembers {
+    pub role_id: i64,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub members: Vec<RoleMember>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleSnapshot {
+    /// `taken_at` as a compact UTC timestamp, e.g. `20240601T000000Z`
+    pub id: String,
+    pub taken_at: String,
+    pub roles: Vec<RoleMembers>,
+}
+
+impl RoleSnapshot {
+    pub fn new(taken_at: DateTime<Utc>, roles: Vec<RoleMembers>) -> Self {
+        Self {
+            id: taken_at.format("%Y%m%dT%H%M%SZ").to_string(),
+            taken_at: taken_at.to_rfc3339(),
+            roles,
+        }
+    }
+
+    /// Id, time and size, without the member lists
+    pub fn summary(&self) -> Value {
+        json!({
+            "id": self.id,
+            "taken_at": self.taken_at,
+            "roles": self.roles.len(),
+            "memberships": self.roles.iter().map(|r| r.members.len()).sum::<usize>()
+        })
+    }
+
+    fn taken_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.taken_at).ok().map(|t| t.with_timezone(&Utc))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SnapshotFile {
+    /// Snapshots per tenant, oldest first
+    #[serde(default)]
+    tenants: HashMap<String, Vec<RoleSnapshot>>,
+}
+
+pub struct RoleSnapshotStore {
+    /// `None` keeps snapshots in memory only (mock mode, tests)
+    path: Option<PathBuf>,
+    tenants: Mutex<HashMap<String, Vec<RoleSnapshot>>>,
+}
+
+impl RoleSnapshotStore {
+    /// Default snapshot file: `ONELOGIN_MCP_ROLE_SNAPSHOTS`, then the platform config dir
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var(ROLE_SNAPSHOTS_ENV_VAR)
+            .map(PathBuf::from)
+            .ok()
+            .or_else(|| dirs::config_dir().map(|d| d.join("onelogin-mcp").join("role_snapshots.json")))
+    }
+
+    /// Load snapshots from `path`; a missing or unreadable file starts empty
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let tenants = match &path {
+            Some(p) if p.exists() => match Self::read_file(p) {
+                Ok(file) => file.tenants,
+                Err(e) => {
+                    warn!("Ignoring unreadable role snapshot file {}: {:#}", p.display(), e);
+                    HashMap::new()
+                }
+            },
+            _ => HashMap::new(),
+        };
+        Self {
+            path,
+            tenants: Mutex::new(tenants),
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        Self::load(None)
+    }
+
+    fn read_file(path: &Path) -> Result<SnapshotFile> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Store a snapshot, dropping the oldest beyond `keep`. Returns how many are kept.
+    pub fn add(&self, tenant: &str, snapshot: RoleSnapshot, keep: usize) -> Result<usize> {
+        let mut tenants = self.tenants.lock().expect("Mutex poisoned");
+        let snapshots = tenants.entry(tenant.to_string()).or_default();
+        snapshots.retain(|s| s.id != snapshot.id);
+        debug!("Storing role snapshot {} for tenant {}", snapshot.id, tenant);
+        snapshots.push(snapshot);
+        let excess = snapshots.len().saturating_sub(keep.max(1));
+        snapshots.drain(..excess);
+        let kept = snapshots.len();
+        self.save(&tenants)?;
+        Ok(kept)
+    }
+
+    pub fn list(&self, tenant: &str) -> Vec<Value> {
+        self.tenants
+            .lock()
+            .expect("Mutex poisoned")
+            .get(tenant)
+            .map(|snapshots| snapshots.iter().map(RoleSnapshot::summary).collect())
+            .unwrap_or_default()
+    }
+
+    /// Find a snapshot by id, `latest`, or date/time (the newest snapshot taken at or
+    /// before it; a bare date means the start of that day, UTC)
+    pub fn find(&self, tenant: &str, selector: &str) -> Result<RoleSnapshot> {
+        let tenants = self.tenants.lock().expect("Mutex poisoned");
+        let snapshots = tenants
+            .get(tenant)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("No role snapshots for tenant '{}'. Take one with onelogin_snapshot_role_memberships.", tenant))?;
+        select_snapshot(snapshots, selector).cloned()
+    }
+
+    fn save(&self, tenants: &HashMap<String, Vec<RoleSnapshot>>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+        }
+        let file = SnapshotFile {
+            tenants: tenants.clone(),
+        };
+        std::fs::write(path, serde_json::to_string(&file)?)
+            .with_context(|| format!("Failed to write role snapshot file {}", path.display()))
+    }
+}
+
+fn select_snapshot<'a>(snapshots: &'a [RoleSnapshot], selector: &str) -> Result<&'a RoleSnapshot> {
+    if selector.eq_ignore_ascii_case("latest") {
+        return snapshots.last().ok_or_else(|| anyhow!("No role snapshots"));
+    }
+    if let Some(snapshot) = snapshots.iter().find(|s| s.id == selector) {
+        return Ok(snapshot);
+    }
+    let at = DateTime::parse_from_rfc3339(selector)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|| {
+            NaiveDate::parse_from_str(selector, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|t| t.and_utc())
+        })
+        .ok_or_else(|| anyhow!("'{}' is not a snapshot id, 'latest' or an ISO 8601 date", selector))?;
+    snapshots
+        .iter()
+        .rev()
+        .find(|s| s.taken_at().is_some_and(|t| t <= at))
+        .ok_or_else(|| {
+            anyhow!(
+                "No role snapshot taken on or before {}; the oldest is {}",
+                selector,
+                snapshots.first().map(|s| s.taken_at.as_str()).unwrap_or("-")
+            )
+        })
+}
+
+/// Membership changes per role between two snapshots. `roles` limits the diff to
+/// roles whose id or name (case-insensitive) is listed.
+pub fn diff_snapshots(from: &RoleSnapshot, to: &RoleSnapshot, roles: Option<&[String]>) -> Vec<Value> {
+    let wanted = |r: &RoleMembers| {
+        roles.is_none_or(|roles| {
+            roles.iter().any(|wanted| {
+                wanted == &r.role_id.to_string()
+                    || r.name.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(wanted))
+            })
+        })
+    };
+    let before: BTreeMap<i64, &RoleMembers> = from.roles.iter().filter(|r| wanted(r)).map(|r| (r.role_id, r)).collect();
+    let after: BTreeMap<i64, &RoleMembers> = to.roles.iter().filter(|r| wanted(r)).map(|r| (r.role_id, r)).collect();
+
+    let mut changes = Vec::new();
+    for role_id in before.keys().chain(after.keys().filter(|id| !before.contains_key(id))) {
+        let old = before.get(role_id);
+        let new = after.get(role_id);
+        let old_members = old.map(|r| r.members.as_slice()).unwrap_or_default();
+        let new_members = new.map(|r| r.members.as_slice()).unwrap_or_default();
+        let added: Vec<&RoleMember> = new_members
+            .iter()
+            .filter(|m| !old_members.iter().any(|o| o.id == m.id))
+            .collect();
+        let removed: Vec<&RoleMember> = old_members
+            .iter()
+            .filter(|m| !new_members.iter().any(|n| n.id == m.id))
+            .collect();
+        let status = match (old, new) {
+            (None, Some(_)) => "role_created",
+            (Some(_), None) => "role_deleted",
+            _ if added.is_empty() && removed.is_empty() => continue,
+            _ => "changed",
+        };
+        changes.push(json!({
+            "role_id": role_id,
+            "name": new.or(old).and_then(|r| r.name.clone()),
+            "status": status,
+            "added": added,
+            "removed": removed
+        }));
+    }
+    changes
+}
+
+/// Memberships of high-privilege roles present in `to` but not in `from`, one entry per
+/// user and role, ordered by user. `reasons` maps each high-privilege role id to why it
+/// counts as one; every member of a role created in between is reported.
+pub fn new_grants(from: &RoleSnapshot, to: &RoleSnapshot, reasons: &HashMap<i64, Vec<String>>) -> Vec<Value> {
+    let mut grants: Vec<(i64, i64, Value)> = Vec::new();
+    for role in to.roles.iter().filter(|r| reasons.contains_key(&r.role_id)) {
+        let before = from.roles.iter().find(|r| r.role_id == role.role_id);
+        for member in &role.members {
+            if before.is_some_and(|b| b.members.iter().any(|m| m.id == member.id)) {
+                continue;
+            }
+            grants.push((
+                member.id,
+                role.role_id,
+                json!({
+                    "user_id": member.id,
+                    "email": member.email,
+                    "name": member.name,
+                    "role_id": role.role_id,
+                    "role_name": role.name,
+                    "role_created": before.is_none(),
+                    "reasons": reasons[&role.role_id]
+                }),
+            ));
+        }
+    }
+    grants.sort_by_key(|(user_id, role_id, _)| (*user_id, *role_id));
+    grants.into_iter().map(|(_, _, grant)| grant).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(id: i64) -> RoleMember {
+        RoleMember { id, email: Some(format!("user{}@example.com", id)), name: None }
+    }
+
+    fn snapshot(at: &str, roles: Vec<(i64, &str, Vec<i64>)>) -> RoleSnapshot {
+        let at = DateTime::parse_from_rfc3339(at).unwrap().with_timezone(&Utc);
+        RoleSnapshot::new(
+            at,
+            roles
+                .into_iter()
+                .map(|(role_id, name, ids)| RoleMembers {
+                    role_id,
+                    name: Some(name.to_string()),
+                    members: ids.into_iter().map(member).collect(),
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let from = snapshot("2024-05-01T00:00:00Z", vec![(1, "Admin", vec![10, 11]), (2, "Sales", vec![20]), (3, "Old", vec![30])]);
+        let to = snapshot("2024-06-01T00:00:00Z", vec![(1, "Admin", vec![11, 12]), (2, "Sales", vec![20]), (4, "New", vec![40])]);
+
+        let changes = diff_snapshots(&from, &to, None);
+        let statuses: Vec<(i64, &str)> = changes.iter().map(|c| (c["role_id"].as_i64().unwrap(), c["status"].as_str().unwrap())).collect();
+        assert_eq!(statuses, vec![(1, "changed"), (3, "role_deleted"), (4, "role_created")]);
+        assert_eq!(changes[0]["added"][0]["id"], 12);
+        assert_eq!(changes[0]["removed"][0]["id"], 10);
+
+        let admin_only = diff_snapshots(&from, &to, Some(&["admin".to_string()]));
+        assert_eq!(admin_only.len(), 1);
+    }
+
+    #[test]
+    fn test_new_grants_only_reports_high_privilege_roles() {
+        let from = snapshot("2024-05-01T00:00:00Z", vec![(1, "Admin", vec![10]), (2, "Sales", vec![20])]);
+        let to = snapshot("2024-06-01T00:00:00Z", vec![(1, "Admin", vec![10, 12]), (2, "Sales", vec![20, 21]), (5, "Billing Admin", vec![11])]);
+        let reasons = HashMap::from([
+            (1, vec!["name".to_string()]),
+            (5, vec!["privilege: Billing".to_string()]),
+        ]);
+
+        let grants = new_grants(&from, &to, &reasons);
+        let pairs: Vec<(i64, i64)> = grants.iter().map(|g| (g["user_id"].as_i64().unwrap(), g["role_id"].as_i64().unwrap())).collect();
+        assert_eq!(pairs, vec![(11, 5), (12, 1)]);
+        assert_eq!(grants[0]["role_created"], true);
+        assert_eq!(grants[1]["role_created"], false);
+        assert_eq!(grants[0]["reasons"][0], "privilege: Billing");
+    }
+
+    #[test]
+    fn test_store_keeps_newest_and_selects_by_date() {
+        let path = std::env::temp_dir().join(format!("role-snapshots-test-{}.json", std::process::id()));
+        let store = RoleSnapshotStore::load(Some(path.clone()));
+        for day in 1..=3 {
+            let at = format!("2024-06-0{}T12:00:00Z", day);
+            store.add("prod", snapshot(&at, vec![(1, "Admin", vec![day])]), 2).unwrap();
+        }
+
+        let reloaded = RoleSnapshotStore::load(Some(path.clone()));
+        assert_eq!(reloaded.list("prod").len(), 2);
+        assert_eq!(reloaded.find("prod", "latest").unwrap().id, "20240603T120000Z");
+        assert_eq!(reloaded.find("prod", "2024-06-03").unwrap().id, "20240602T120000Z");
+        assert_eq!(reloaded.find("prod", "20240602T120000Z").unwrap().taken_at, "2024-06-02T12:00:00+00:00");
+        assert!(reloaded.find("prod", "2024-06-01").is_err());
+        assert!(reloaded.find("staging", "latest").is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,379 @@
+//! Recurring background jobs.
+//!
+//! Jobs are declared in the `jobs` section of the tool config file. Each job
+//! runs one tool with fixed arguments on a cron schedule (UTC), and the last
+//! few results are kept in memory for the jobs tools to return.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_keep_runs() -> usize {
+    5
+}
+
+/// A scheduled tool call, as written in the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobConfig {
+    pub name: String,
+    /// Five-field cron expression in UTC (`min hour day month weekday`) or
+    /// `@hourly`, `@daily`, `@weekly`, `@monthly`
+    pub schedule: String,
+    pub tool: String,
+    #[serde(default)]
+    pub arguments: Value,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Number of past runs kept for retrieval
+    #[serde(default = "default_keep_runs")]
+    pub keep_runs: usize,
+}
+
+/// Parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month and day-of-week were both restricted: either may match
+    either_day: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(anyhow!(
+                "Invalid cron expression '{}': expected 5 fields (minute hour day month weekday)",
+                expr
+            ));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7).context("weekday")?;
+        // 7 is an alias for Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59).context("minute")?,
+            hours: parse_field(hour, 0, 23).context("hour")?,
+            days: parse_field(day, 1, 31).context("day of month")?,
+            months: parse_field(month, 1, 12).context("month")?,
+            weekdays,
+            either_day: day != "*" && weekday != "*",
+        })
+    }
+
+    fn day_matches(&self, t: &DateTime<Utc>) -> bool {
+        let dom = self.days & (1 << t.day()) != 0;
+        let dow = self.weekdays & (1 << t.weekday().num_days_from_sunday()) != 0;
+        if self.either_day {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        // Skipping whole months/days/hours keeps this short; the bound covers leap-day schedules
+        for _ in 0..100_000 {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(&t) {
+                t = (t + ChronoDuration::days(1)).with_hour(0)?.with_minute(0)?;
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = (t + ChronoDuration::hours(1)).with_minute(0)?;
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += ChronoDuration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// Parse one cron field (`*`, `5`, `1-5`, `*/15`, `0-30/10`, comma-separated) into a bitmask
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().with_context(|| format!("invalid step '{}'", step))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(anyhow!("step must be positive"));
+        }
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (a.parse::<u32>()?, b.parse::<u32>()?)
+        } else {
+            let value = range.parse::<u32>().with_context(|| format!("invalid value '{}'", range))?;
+            // "5/10" means from 5 to the end in steps of 10
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start < min || end > max || start > end {
+            return Err(anyhow!("'{}' is outside {}-{}", part, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+/// Runs the tool behind a job
+#[async_trait]
+pub trait JobRunner: Send + Sync {
+    async fn run_job(&self, tool: &str, arguments: &Value) -> Result<Value>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRun {
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct JobState {
+    config: JobConfig,
+    schedule: CronSchedule,
+    next_run: Option<DateTime<Utc>>,
+    runs: VecDeque<JobRun>,
+}
+
+/// Configured jobs and their recent results
+#[derive(Default)]
+pub struct JobStore {
+    jobs: Mutex<HashMap<String, JobState>>,
+}
+
+impl JobStore {
+    /// Register jobs, skipping (with a warning) any that are disabled or have an invalid schedule
+    pub fn from_configs(configs: &[JobConfig]) -> Self {
+        let mut jobs = HashMap::new();
+        for config in configs {
+            if !config.enabled {
+                continue;
+            }
+            match CronSchedule::parse(&config.schedule) {
+                Ok(schedule) => {
+                    let next_run = schedule.next_after(Utc::now());
+                    jobs.insert(
+                        config.name.clone(),
+                        JobState {
+                            config: config.clone(),
+                            schedule,
+                            next_run,
+                            runs: VecDeque::new(),
+                        },
+                    );
+                }
+                Err(e) => warn!("Skipping job '{}': {:#}", config.name, e),
+            }
+        }
+        Self {
+            jobs: Mutex::new(jobs),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.lock().expect("Mutex poisoned").is_empty()
+    }
+
+    pub fn job(&self, name: &str) -> Option<JobConfig> {
+        self.jobs.lock().expect("Mutex poisoned").get(name).map(|j| j.config.clone())
+    }
+
+    /// Summary of every job, sorted by name
+    pub fn list(&self) -> Vec<Value> {
+        let jobs = self.jobs.lock().expect("Mutex poisoned");
+        let mut list: Vec<Value> = jobs
+            .values()
+            .map(|job| {
+                let last = job.runs.back();
+                json!({
+                    "name": job.config.name,
+                    "schedule": job.config.schedule,
+                    "tool": job.config.tool,
+                    "nextRun": job.next_run.map(|t| t.to_rfc3339()),
+                    "lastRun": last.map(|r| r.started_at.clone()),
+                    "lastStatus": last.map(|r| r.status),
+                    "storedRuns": job.runs.len()
+                })
+            })
+            .collect();
+        list.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        list
+    }
+
+    /// Most recent runs of a job, newest first
+    pub fn runs(&self, name: &str, limit: usize) -> Option<Vec<JobRun>> {
+        let jobs = self.jobs.lock().expect("Mutex poisoned");
+        jobs.get(name).map(|job| job.runs.iter().rev().take(limit).cloned().collect())
+    }
+
+    fn next_run(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.jobs.lock().expect("Mutex poisoned").get(name).and_then(|j| j.next_run)
+    }
+
+    fn record(&self, name: &str, run: JobRun) {
+        let mut jobs = self.jobs.lock().expect("Mutex poisoned");
+        if let Some(job) = jobs.get_mut(name) {
+            job.runs.push_back(run);
+            while job.runs.len() > job.config.keep_runs.max(1) {
+                job.runs.pop_front();
+            }
+            job.next_run = job.schedule.next_after(Utc::now());
+        }
+    }
+
+    /// Run a job now and record the outcome
+    pub async fn run_now(&self, name: &str, runner: &dyn JobRunner) -> Result<JobRun> {
+        let config = self.job(name).ok_or_else(|| anyhow!("Unknown job '{}'", name))?;
+        let started = Utc::now();
+        let clock = std::time::Instant::now();
+        let outcome = runner.run_job(&config.tool, &config.arguments).await;
+        let duration_ms = clock.elapsed().as_millis() as u64;
+        let run = match outcome {
+            Ok(result) => JobRun {
+                started_at: started.to_rfc3339(),
+                duration_ms,
+                status: "ok",
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => {
+                warn!("Job '{}' failed: {:#}", name, e);
+                JobRun {
+                    started_at: started.to_rfc3339(),
+                    duration_ms,
+                    status: "error",
+                    result: None,
+                    error: Some(format!("{:#}", e)),
+                }
+            }
+        };
+        info!("Job '{}' finished ({}) in {} ms", name, run.status, duration_ms);
+        self.record(name, run.clone());
+        Ok(run)
+    }
+}
+
+/// Spawn one timer task per job. Tasks run until the process exits.
+pub fn start(store: Arc<JobStore>, runner: Arc<dyn JobRunner>) {
+    let names: Vec<String> = store.jobs.lock().expect("Mutex poisoned").keys().cloned().collect();
+    for name in names {
+        info!("Scheduled job '{}' next runs at {:?}", name, store.next_run(&name).map(|t| t.to_rfc3339()));
+        let store = store.clone();
+        let runner = runner.clone();
+        tokio::spawn(async move {
+            while let Some(next) = store.next_run(&name) {
+                let wait = (next - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+                if let Err(e) = store.run_now(&name, runner.as_ref()).await {
+                    warn!("Job '{}' could not run: {:#}", name, e);
+                    break;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let hourly = CronSchedule::parse("@hourly").unwrap();
+        assert_eq!(hourly.next_after(at("2024-03-10T10:15:30Z")), Some(at("2024-03-10T11:00:00Z")));
+
+        let nightly = CronSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(nightly.next_after(at("2024-03-10T02:30:00Z")), Some(at("2024-03-11T02:30:00Z")));
+
+        // Mondays at 09:00; 2024-03-10 is a Sunday
+        let weekly = CronSchedule::parse("0 9 * * 1").unwrap();
+        assert_eq!(weekly.next_after(at("2024-03-10T12:00:00Z")), Some(at("2024-03-11T09:00:00Z")));
+
+        let stepped = CronSchedule::parse("*/20 8-9 * 12 *").unwrap();
+        assert_eq!(stepped.next_after(at("2024-03-10T12:00:00Z")), Some(at("2024-12-01T08:00:00Z")));
+        assert_eq!(stepped.next_after(at("2024-12-01T09:40:00Z")), Some(at("2024-12-02T08:00:00Z")));
+
+        // Sunday as 7
+        assert_eq!(CronSchedule::parse("0 0 * * 7").unwrap(), CronSchedule::parse("@weekly").unwrap());
+    }
+
+    #[test]
+    fn test_cron_rejects_invalid_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+    }
+
+    struct EchoRunner;
+
+    #[async_trait]
+    impl JobRunner for EchoRunner {
+        async fn run_job(&self, tool: &str, arguments: &Value) -> Result<Value> {
+            if tool == "fail" {
+                return Err(anyhow!("boom"));
+            }
+            Ok(json!({"tool": tool, "arguments": arguments}))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_store_keeps_recent_runs() {
+        let config = |name: &str, tool: &str| JobConfig {
+            name: name.to_string(),
+            schedule: "@daily".to_string(),
+            tool: tool.to_string(),
+            arguments: json!({"limit": 5}),
+            enabled: true,
+            keep_runs: 2,
+        };
+        let store = JobStore::from_configs(&[config("events", "onelogin_list_events"), config("broken", "fail")]);
+
+        for _ in 0..3 {
+            store.run_now("events", &EchoRunner).await.unwrap();
+        }
+        assert_eq!(store.runs("events", 10).unwrap().len(), 2);
+        assert_eq!(store.runs("events", 1).unwrap()[0].result.as_ref().unwrap()["arguments"]["limit"], 5);
+
+        let failed = store.run_now("broken", &EchoRunner).await.unwrap();
+        assert_eq!(failed.status, "error");
+        assert!(store.run_now("missing", &EchoRunner).await.is_err());
+        assert_eq!(store.list()[0]["lastStatus"], "error");
+    }
+}
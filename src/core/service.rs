@@ -0,0 +1,240 @@
+//! Running as an OS service.
+//!
+//! `onelogin-mcp-server service` runs the server headless. A service's stdin is
+//! closed, so MCP clients are served over the WebSocket transport, which must be
+//! configured with `ONELOGIN_WS_LISTEN`; scheduled jobs, the webhook receiver and
+//! the metrics exporter run alongside it. Under systemd (`Type=notify`) readiness,
+//! watchdog keep-alives and shutdown are reported over `NOTIFY_SOCKET`; on Windows
+//! the process registers with the Service Control Manager.
+
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::debug;
+
+/// Log file used in service mode instead of stderr
+pub const LOG_FILE_ENV_VAR: &str = "ONELOGIN_MCP_LOG_FILE";
+
+/// Log file for service mode: `ONELOGIN_MCP_LOG_FILE`, else on Windows (where a
+/// service has no console) `server.log` in the platform data dir. `None` logs to
+/// stderr, which systemd forwards to the journal.
+pub fn log_file_from_env() -> Option<PathBuf> {
+    match env::var(LOG_FILE_ENV_VAR) {
+        Ok(path) if !path.trim().is_empty() => Some(PathBuf::from(path.trim())),
+        _ if cfg!(windows) => dirs::data_dir().map(|d| d.join("onelogin-mcp").join("server.log")),
+        _ => None,
+    }
+}
+
+/// Whether stderr is connected to the systemd journal, which timestamps lines itself
+pub fn under_journald() -> bool {
+    env::var_os("JOURNAL_STREAM").is_some()
+}
+
+/// Send a state string (e.g. `READY=1`) to the systemd notification socket.
+/// Returns false when not running under systemd or the message could not be sent.
+#[cfg(unix)]
+pub fn sd_notify(state: &str) -> bool {
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(path) = env::var_os("NOTIFY_SOCKET") else {
+        return false;
+    };
+    let Ok(socket) = UnixDatagram::unbound() else {
+        return false;
+    };
+    let path = path.to_string_lossy();
+    let sent = match path.strip_prefix('@') {
+        // Abstract socket namespace (Linux only)
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())
+                .and_then(|addr| socket.send_to_addr(state.as_bytes(), &addr))
+        }
+        _ => socket.send_to(state.as_bytes(), path.as_ref()),
+    };
+    if let Err(e) = &sent {
+        debug!("Failed to notify systemd ({}): {}", state, e);
+    }
+    sent.is_ok()
+}
+
+#[cfg(not(unix))]
+pub fn sd_notify(_state: &str) -> bool {
+    false
+}
+
+/// How often to send watchdog keep-alives: half of `WATCHDOG_USEC`, when the
+/// watchdog is enabled for this process
+pub fn watchdog_interval() -> Option<Duration> {
+    if let Some(pid) = env::var("WATCHDOG_PID").ok().and_then(|p| p.trim().parse::<u32>().ok()) {
+        if pid != std::process::id() {
+            return None;
+        }
+    }
+    let usec: u64 = env::var("WATCHDOG_USEC").ok()?.trim().parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec / 2))
+}
+
+/// Ping the systemd watchdog until the returned task is aborted or the process exits
+pub fn spawn_watchdog() -> Option<tokio::task::JoinHandle<()>> {
+    let interval = watchdog_interval()?;
+    debug!("systemd watchdog enabled, pinging every {:?}", interval);
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            sd_notify("WATCHDOG=1");
+        }
+    }))
+}
+
+/// Report that the service finished starting
+pub fn notify_ready() {
+    sd_notify("READY=1\nSTATUS=Serving scheduled jobs, webhooks and metrics");
+    #[cfg(windows)]
+    windows::set_running();
+}
+
+/// Report that the service is shutting down
+pub fn notify_stopping() {
+    sd_notify("STOPPING=1");
+}
+
+/// Resolves on SIGTERM or SIGINT (Ctrl+C elsewhere)
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match (signal(SignalKind::terminate()), signal(SignalKind::interrupt())) {
+            (Ok(mut term), Ok(mut int)) => {
+                tokio::select! {
+                    _ = term.recv() => {}
+                    _ = int.recv() => {}
+                }
+                return;
+            }
+            _ => debug!("Could not install signal handlers, waiting for Ctrl+C"),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Windows Service Control Manager integration
+#[cfg(windows)]
+pub mod windows {
+    use anyhow::{Context, Result};
+    use futures::future::BoxFuture;
+    use std::ffi::OsString;
+    use std::sync::OnceLock;
+    use std::time::Duration;
+    use tokio::runtime::Handle;
+    use tokio::sync::watch;
+    use tracing::error;
+    use windows_service::service::{
+        ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult, ServiceStatusHandle};
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    /// Name the service is registered under (`sc.exe create onelogin-mcp-server ...`)
+    pub const SERVICE_NAME: &str = "onelogin-mcp-server";
+
+    /// The headless server, run until the receiver reports a stop request
+    pub type ServiceBody = fn(watch::Receiver<bool>) -> BoxFuture<'static, Result<()>>;
+
+    static RUNTIME: OnceLock<(Handle, ServiceBody)> = OnceLock::new();
+    static STATUS: OnceLock<ServiceStatusHandle> = OnceLock::new();
+
+    /// Hand the current thread to the Service Control Manager, which calls back
+    /// into `body` on its own thread. Returns once the service has stopped.
+    pub fn run_dispatcher(handle: Handle, body: ServiceBody) -> Result<()> {
+        let _ = RUNTIME.set((handle, body));
+        service_dispatcher::start(SERVICE_NAME, ffi_service_main).context(
+            "Failed to connect to the Service Control Manager. The 'service' command must be started by Windows \
+             (e.g. after 'sc.exe create onelogin-mcp-server binPath= \"...\\onelogin-mcp-server.exe service\"')",
+        )
+    }
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    fn service_main(_arguments: Vec<OsString>) {
+        if let Err(e) = run_service() {
+            error!("Windows service failed: {:#}", e);
+        }
+    }
+
+    fn status(state: ServiceState, exit_code: u32) -> ServiceStatus {
+        ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: match state {
+                ServiceState::Running => ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+                _ => ServiceControlAccept::empty(),
+            },
+            exit_code: ServiceExitCode::Win32(exit_code),
+            checkpoint: 0,
+            wait_hint: match state {
+                ServiceState::StartPending | ServiceState::StopPending => Duration::from_secs(30),
+                _ => Duration::default(),
+            },
+            process_id: None,
+        }
+    }
+
+    /// Report `Running` once the server has started
+    pub fn set_running() {
+        if let Some(handle) = STATUS.get() {
+            let _ = handle.set_service_status(status(ServiceState::Running, 0));
+        }
+    }
+
+    fn run_service() -> Result<()> {
+        let (runtime, body) = RUNTIME.get().context("Service started without a runtime")?;
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let handle = service_control_handler::register(SERVICE_NAME, move |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                let _ = stop_tx.send(true);
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        })
+        .context("Failed to register the service control handler")?;
+        let _ = STATUS.set(handle);
+        handle.set_service_status(status(ServiceState::StartPending, 0))?;
+
+        let result = runtime.block_on(body(stop_rx));
+        let exit_code = if result.is_ok() { 0 } else { 1 };
+        handle.set_service_status(status(ServiceState::Stopped, exit_code))?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sd_notify() {
+        use std::os::unix::net::UnixDatagram;
+
+        env::remove_var("NOTIFY_SOCKET");
+        assert!(!sd_notify("READY=1"));
+
+        let path = env::temp_dir().join(format!("notify-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let receiver = UnixDatagram::bind(&path).unwrap();
+        env::set_var("NOTIFY_SOCKET", &path);
+        let sent = sd_notify("READY=1");
+        env::remove_var("NOTIFY_SOCKET");
+
+        assert!(sent);
+        let mut buf = [0u8; 64];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,9 +1,13 @@
 use crate::api::OneLoginClient;
 use crate::core::auth::AuthManager;
 use crate::core::cache::CacheManager;
-use crate::core::client::HttpClient;
+use crate::core::cassette::Cassette;
+use crate::core::client::{ClientStats, HttpClient};
 use crate::core::config::{Config, TenantEntry};
+use crate::core::mock::MockStore;
 use crate::core::rate_limit::RateLimiter;
+use crate::core::retry::RetryBudget;
+use crate::core::usage_budget::UsageStore;
 use anyhow::{anyhow, Result};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -26,17 +30,37 @@ pub struct TenantManager {
 
 impl TenantManager {
     /// Build a full client stack for a single Config.
-    fn build_client(config: Config) -> Arc<OneLoginClient> {
+    fn build_client(
+        tenant: &str,
+        config: Config,
+        cassette: Option<Arc<Cassette>>,
+        retry_budget: Arc<RetryBudget>,
+        global_limiter: Option<Arc<RateLimiter>>,
+        usage: Arc<UsageStore>,
+    ) -> Arc<OneLoginClient> {
         let config = Arc::new(config);
         let auth_manager = Arc::new(AuthManager::new(config.clone()));
-        let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit_requests_per_second));
-        let http_client = Arc::new(HttpClient::new(config.clone(), auth_manager, rate_limiter));
-        let cache = Arc::new(CacheManager::new(config.cache_ttl_seconds, 10000));
+        let mut rate_limiter = RateLimiter::new(config.rate_limit_requests_per_second);
+        if let Some(global) = global_limiter {
+            rate_limiter = rate_limiter.with_global(global);
+        }
+        let rate_limiter = Arc::new(rate_limiter);
+        let mut http_client = HttpClient::new(config.clone(), auth_manager, rate_limiter)
+            .with_retry_budget(retry_budget)
+            .with_usage_store(tenant, usage);
+        if let Some(cassette) = cassette {
+            http_client = http_client.with_cassette(cassette);
+        }
+        let http_client = Arc::new(http_client);
+        let cache = Arc::new(
+            CacheManager::new(config.cache_ttl_seconds, 10000).with_compression(config.cache_compression_threshold),
+        );
         Arc::new(OneLoginClient::new(http_client, cache))
     }
 
     /// Create a single-tenant manager from environment config (backward compatible).
-    pub fn from_single(config: Config) -> Self {
+    /// With a cassette, HTTP traffic is recorded to it or replayed from it.
+    pub fn from_single(config: Config, cassette: Option<Arc<Cassette>>) -> Self {
         let tenant_name = config.onelogin_subdomain.clone();
         let info = TenantInfo {
             name: tenant_name.clone(),
@@ -44,7 +68,13 @@ impl TenantManager {
             region: format!("{:?}", config.onelogin_region).to_lowercase(),
             is_default: true,
         };
-        let client = Self::build_client(config);
+        let retry_budget = Arc::new(RetryBudget::new(
+            config.retry_budget_ratio,
+            config.retry_budget_min_per_second,
+        ));
+        let global_limiter = config.global_rate_limit_rps.map(|rps| Arc::new(RateLimiter::new(rps)));
+        let usage = UsageStore::open(UsageStore::default_path());
+        let client = Self::build_client(&tenant_name, config, cassette, retry_budget, global_limiter, usage);
         let mut clients = HashMap::new();
         clients.insert(tenant_name.clone(), client);
 
@@ -60,10 +90,25 @@ impl TenantManager {
         let mut clients = HashMap::new();
         let mut tenant_info = Vec::new();
         let mut default_tenant: Option<String> = None;
+        // One budget for all tenants, so a failing tenant can't multiply traffic
+        let retry_budget = Arc::new(RetryBudget::new(
+            base_config.retry_budget_ratio,
+            base_config.retry_budget_min_per_second,
+        ));
+        // Each tenant keeps its own limiter; this one caps their combined rate
+        let global_limiter = base_config.global_rate_limit_rps.map(|rps| Arc::new(RateLimiter::new(rps)));
+        let usage = UsageStore::open(UsageStore::default_path());
 
         for entry in entries {
             let config = entry.to_config(base_config)?;
-            let client = Self::build_client(config);
+            let client = Self::build_client(
+                &entry.name,
+                config,
+                None,
+                retry_budget.clone(),
+                global_limiter.clone(),
+                usage.clone(),
+            );
 
             let info = TenantInfo {
                 name: entry.name.clone(),
@@ -95,6 +140,32 @@ impl TenantManager {
         })
     }
 
+    /// Create a single-tenant manager whose client serves fixture data instead of calling OneLogin.
+    pub fn from_mock(config: Config, store: Arc<MockStore>) -> Self {
+        let tenant_name = "mock".to_string();
+        let info = TenantInfo {
+            name: tenant_name.clone(),
+            subdomain: config.onelogin_subdomain.clone(),
+            region: format!("{:?}", config.onelogin_region).to_lowercase(),
+            is_default: true,
+        };
+        let config = Arc::new(config);
+        let auth_manager = Arc::new(AuthManager::new(config.clone()));
+        let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit_requests_per_second));
+        let http_client = Arc::new(
+            HttpClient::new(config.clone(), auth_manager, rate_limiter).with_mock(store),
+        );
+        let cache = Arc::new(CacheManager::new(config.cache_ttl_seconds, 10000));
+        let mut clients = HashMap::new();
+        clients.insert(tenant_name.clone(), Arc::new(OneLoginClient::new(http_client, cache)));
+
+        TenantManager {
+            clients,
+            default_tenant: tenant_name,
+            tenant_info: vec![info],
+        }
+    }
+
     /// Resolve tenant name to client. None or empty string means default.
     pub fn resolve(&self, tenant: Option<&str>) -> Result<Arc<OneLoginClient>> {
         let name = match tenant {
@@ -122,4 +193,35 @@ impl TenantManager {
     pub fn is_multi_tenant(&self) -> bool {
         self.clients.len() > 1
     }
+
+    /// Drop cached API responses for one tenant, or all of them. Returns the tenants flushed.
+    pub async fn flush_caches(&self, tenant: Option<&str>) -> Result<Vec<String>> {
+        let mut names: Vec<String> = match tenant {
+            Some(name) => {
+                self.resolve(Some(name))?;
+                vec![name.to_string()]
+            }
+            None => self.clients.keys().cloned().collect(),
+        };
+        names.sort();
+        for name in &names {
+            self.clients[name].cache.invalidate_all().await;
+        }
+        Ok(names)
+    }
+
+    /// Live HTTP client counters for every tenant, sorted by name
+    pub fn client_stats(&self) -> Vec<(String, ClientStats)> {
+        let mut stats: Vec<(String, ClientStats)> = self
+            .clients
+            .iter()
+            .map(|(name, client)| {
+                let mut stats = client.rate_limits.client_stats();
+                stats.cache = Some(client.cache.stats());
+                (name.clone(), stats)
+            })
+            .collect();
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        stats
+    }
 }
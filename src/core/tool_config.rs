@@ -2,12 +2,14 @@
 //!
 //! Supports category-level and tool-level granularity with sensible defaults.
 
+use crate::core::scheduler::JobConfig;
 use anyhow::{Context, Result};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tracing::{error, info, warn};
 
 /// Configuration version for future migrations
@@ -27,6 +29,17 @@ pub enum CategoryConfig {
     },
 }
 
+/// What happens to a tool result estimated above `response_token_budget`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverBudgetAction {
+    /// Return it whole with a `token_budget_warning`
+    #[default]
+    Warn,
+    /// Return the part of its largest list that fits, with a `page_token` for the rest
+    Paginate,
+}
+
 impl Default for CategoryConfig {
     fn default() -> Self {
         CategoryConfig::Simple(false)
@@ -70,7 +83,9 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
         name: "users",
         tools: &[
             "onelogin_list_users",
+            "onelogin_export_users",
             "onelogin_get_user",
+            "onelogin_get_users_batch",
             "onelogin_create_user",
             "onelogin_update_user",
             "onelogin_delete_user",
@@ -83,6 +98,8 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
             "onelogin_lock_user",
             "onelogin_set_password",
             "onelogin_set_custom_attributes",
+            "onelogin_transition_user_status",
+            "onelogin_list_user_devices",
         ],
         default_enabled: true,
     },
@@ -139,6 +156,7 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
         tools: &[
             "onelogin_generate_invite_link",
             "onelogin_send_invite_link",
+            "onelogin_send_bulk_invites",
         ],
         default_enabled: true,
     },
@@ -149,6 +167,8 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
             "onelogin_get_event",
             "onelogin_create_event",
             "onelogin_list_event_types",
+            "onelogin_get_new_events",
+            "onelogin_get_received_events",
         ],
         default_enabled: true,
     },
@@ -162,6 +182,11 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
         ],
         default_enabled: true,
     },
+    ToolCategory {
+        name: "account",
+        tools: &["onelogin_get_account_overview"],
+        default_enabled: true,
+    },
     // === DISABLED BY DEFAULT ===
     ToolCategory {
         name: "app_rules",
@@ -201,6 +226,8 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
             "onelogin_get_saml_assertion",
             "onelogin_verify_saml_factor",
             "onelogin_get_saml_assertion_v2",
+            "onelogin_generate_sp_metadata",
+            "onelogin_parse_idp_metadata",
         ],
         default_enabled: false,
     },
@@ -213,12 +240,16 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
             "onelogin_get_smart_hook",
             "onelogin_list_smart_hooks",
             "onelogin_get_smart_hook_logs",
+            "onelogin_check_hook_packages",
+            "onelogin_test_smart_hook",
             // Hook environment variables (account-level, shared by all hooks)
             "onelogin_list_hook_env_vars",
             "onelogin_get_hook_env_var",
             "onelogin_create_hook_env_var",
             "onelogin_update_hook_env_var",
             "onelogin_delete_hook_env_var",
+            "onelogin_set_hook_env_var",
+            "onelogin_compare_hook_env_var",
         ],
         default_enabled: false,
     },
@@ -265,7 +296,11 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
     },
     ToolCategory {
         name: "embed_tokens",
-        tools: &["onelogin_generate_embed_token", "onelogin_list_embeddable_apps"],
+        tools: &[
+            "onelogin_generate_embed_token",
+            "onelogin_list_embeddable_apps",
+            "onelogin_build_embed_launch_url",
+        ],
         default_enabled: false,
     },
     ToolCategory {
@@ -327,6 +362,7 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
             "onelogin_delete_self_registration_profile",
             "onelogin_list_registrations",
             "onelogin_approve_registration",
+            "onelogin_moderate_registrations",
         ],
         default_enabled: false,
     },
@@ -347,17 +383,65 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
             "onelogin_create_api_authorization",
             "onelogin_update_api_authorization",
             "onelogin_delete_api_authorization",
+            "onelogin_test_api_auth_token",
         ],
         default_enabled: false,
     },
     // NOTE: The following categories were removed because no public OneLogin API exists for them:
-    // - account (no /api/2/account endpoint)
-    // - password_policies (no /api/2/password_policies endpoint)
+    // - account settings/features/usage (no /api/2/account endpoint; the "account" category
+    //   only exposes the overview tool, which derives its data from events and rate limits)
     // - certificates (no /api/2/certificates endpoint)
-    // - devices (no /api/2/devices endpoint - Device Trust managed via admin portal)
-    // - login_pages (no management API - /api/1/login-page is for session creation only)
-    // - trusted_idps (no /api/2/trusted_idps endpoint - configured via admin portal)
     // - webhooks_crud (no CRUD API - webhooks must be configured via admin portal)
+    ToolCategory {
+        name: "password_policies",
+        tools: &[
+            "onelogin_list_password_policies",
+            "onelogin_get_password_policy",
+            "onelogin_create_password_policy",
+            "onelogin_update_password_policy",
+            "onelogin_delete_password_policy",
+            "onelogin_assign_password_policy_to_user",
+            "onelogin_bulk_assign_password_policy",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "login_pages",
+        tools: &[
+            "onelogin_list_login_pages",
+            "onelogin_get_login_page",
+            "onelogin_create_login_page",
+            "onelogin_update_login_page",
+            "onelogin_delete_login_page",
+            "onelogin_get_login_page_preview_url",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "devices",
+        tools: &[
+            "onelogin_list_devices",
+            "onelogin_get_device",
+            "onelogin_register_device",
+            "onelogin_update_device",
+            "onelogin_delete_device",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "trusted_idps",
+        tools: &[
+            "onelogin_list_trusted_idps",
+            "onelogin_get_trusted_idp",
+            "onelogin_create_trusted_idp",
+            "onelogin_update_trusted_idp",
+            "onelogin_delete_trusted_idp",
+            "onelogin_get_trusted_idp_metadata",
+            "onelogin_update_trusted_idp_metadata",
+            "onelogin_get_trusted_idp_issuer",
+        ],
+        default_enabled: false,
+    },
     ToolCategory {
         name: "role_resources",
         tools: &[
@@ -382,6 +466,75 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
         name: "risk",
         tools: &[
             "onelogin_get_risk_rule",
+            "onelogin_test_risk_rule",
+            "onelogin_detect_brute_force",
+            "onelogin_detect_impossible_travel",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "compliance",
+        tools: &[
+            "onelogin_inactive_users_report",
+            "onelogin_elevated_access_report",
+            "onelogin_app_access_report",
+            "onelogin_locked_users_report",
+            "onelogin_password_expiry_report",
+            "onelogin_certificate_expiry_report",
+            "onelogin_app_usage_report",
+            "onelogin_mapping_compliance_report",
+            "onelogin_session_compliance_report",
+            "onelogin_snapshot_role_memberships",
+            "onelogin_list_role_snapshots",
+            "onelogin_diff_role_snapshots",
+            "onelogin_privilege_escalation_report",
+            "onelogin_lint_naming",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "terraform",
+        tools: &[
+            "onelogin_export_terraform",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "backup",
+        tools: &[
+            "onelogin_export_backup",
+            "onelogin_restore_backup",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "dependencies",
+        tools: &[
+            "onelogin_resource_graph",
+            "onelogin_deletion_impact",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "org_chart",
+        tools: &[
+            "onelogin_get_manager_chain",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "diagnostics",
+        tools: &[
+            "onelogin_check_schema_drift",
+        ],
+        default_enabled: false,
+    },
+    ToolCategory {
+        name: "onboarding",
+        tools: &[
+            "onelogin_validate_tenant_credentials",
+            "onelogin_add_tenant",
+            "onelogin_set_tool_categories",
         ],
         default_enabled: false,
     },
@@ -389,6 +542,18 @@ pub static TOOL_CATEGORIES: &[ToolCategory] = &[
         name: "tenant_management",
         tools: &[
             "onelogin_list_tenants",
+            "onelogin_server_stats",
+            "onelogin_explain_tool",
+            "onelogin_get_result_page",
+        ],
+        default_enabled: true,
+    },
+    ToolCategory {
+        name: "jobs",
+        tools: &[
+            "onelogin_list_jobs",
+            "onelogin_get_job_results",
+            "onelogin_run_job",
         ],
         default_enabled: true,
     },
@@ -408,8 +573,82 @@ pub struct ToolConfigFile {
     /// Category configurations
     #[serde(default)]
     pub categories: HashMap<String, CategoryConfig>,
+
+    /// Prefix used in place of `onelogin_` for exposed tool names (e.g. "ol_")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_prefix: Option<String>,
+
+    /// Extra names for tools: alias -> canonical tool name (e.g. "find_user" -> "onelogin_get_user")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub aliases: HashMap<String, String>,
+
+    /// Deadline for a single tool call in seconds (0 disables). Default: 120
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_timeout_seconds: Option<u64>,
+
+    /// Recurring background jobs, each running one tool on a cron schedule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jobs: Vec<JobConfig>,
+
+    /// Refuse to delete roles, apps and user mappings unless onelogin_deletion_impact
+    /// was run for the same resource shortly before
+    #[serde(default)]
+    pub require_deletion_impact: bool,
+
+    /// Minimum seconds between calls of a tool, e.g. {"onelogin_delete_app": 300}
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_cooldowns: HashMap<String, u64>,
+
+    /// Seconds a tool's computed result is reused for calls with the same arguments
+    /// (0 disables), e.g. {"onelogin_app_access_report": 900}. Read-only tools only;
+    /// `DEFAULT_RESPONSE_CACHE_TOOLS` are cached for `DEFAULT_RESPONSE_CACHE_TTL_SECS` unless set here
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub response_cache_ttls: HashMap<String, u64>,
+
+    /// Estimated tokens a single tool result may take in the client's context (0 disables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_token_budget: Option<u64>,
+
+    /// What to do with results over `response_token_budget`: "warn" (default) or "paginate"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub over_token_budget: Option<OverBudgetAction>,
 }
 
+/// Prefix of the canonical tool names defined in `TOOL_CATEGORIES`
+pub const CANONICAL_TOOL_PREFIX: &str = "onelogin_";
+
+/// Longest tool name accepted by common MCP clients
+pub const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Default deadline for a single tool call
+pub const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 120;
+
+/// Report and analytics tools whose results are cached by default
+pub const DEFAULT_RESPONSE_CACHE_TOOLS: &[&str] = &[
+    "onelogin_inactive_users_report",
+    "onelogin_elevated_access_report",
+    "onelogin_app_access_report",
+    "onelogin_password_expiry_report",
+    "onelogin_certificate_expiry_report",
+    "onelogin_app_usage_report",
+    "onelogin_mapping_compliance_report",
+    "onelogin_session_compliance_report",
+    "onelogin_privilege_escalation_report",
+    "onelogin_resource_graph",
+];
+
+/// Lifetime of a cached result of `DEFAULT_RESPONSE_CACHE_TOOLS`
+pub const DEFAULT_RESPONSE_CACHE_TTL_SECS: u64 = 300;
+
+/// Overrides `tool_timeout_seconds` from the config file
+pub const TOOL_TIMEOUT_ENV_VAR: &str = "ONELOGIN_MCP_TOOL_TIMEOUT";
+
+/// Comma-separated categories/tools to enable on top of the config file
+pub const ENABLE_ENV_VAR: &str = "ONELOGIN_MCP_ENABLE";
+
+/// Comma-separated categories/tools to disable; wins over `ONELOGIN_MCP_ENABLE`
+pub const DISABLE_ENV_VAR: &str = "ONELOGIN_MCP_DISABLE";
+
 fn default_version() -> String {
     CURRENT_VERSION.to_string()
 }
@@ -427,6 +666,15 @@ impl Default for ToolConfigFile {
             version: CURRENT_VERSION.to_string(),
             hot_reload: false,
             categories,
+            tool_prefix: None,
+            aliases: HashMap::new(),
+            tool_timeout_seconds: None,
+            jobs: Vec::new(),
+            require_deletion_impact: false,
+            tool_cooldowns: HashMap::new(),
+            response_cache_ttls: HashMap::new(),
+            response_token_budget: None,
+            over_token_budget: None,
         }
     }
 }
@@ -466,6 +714,8 @@ impl ToolConfig {
                     }
                 }
 
+                Self::validate_naming(&config);
+
                 config
             }
             Some(path) => {
@@ -547,6 +797,7 @@ impl ToolConfig {
         let new_config: ToolConfigFile = serde_json::from_str(&content)
             .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
 
+        Self::validate_naming(&new_config);
         let new_enabled = Self::compute_enabled_tools(&new_config);
 
         let old_count = self.enabled_count();
@@ -563,6 +814,177 @@ impl ToolConfig {
         Ok(())
     }
 
+    /// Configured background jobs (read at startup; changes need a restart)
+    pub fn jobs(&self) -> Vec<JobConfig> {
+        self.config.read().expect("RwLock poisoned").jobs.clone()
+    }
+
+    /// Whether delete tools require a recent deletion impact analysis
+    pub fn require_deletion_impact(&self) -> bool {
+        self.config.read().expect("RwLock poisoned").require_deletion_impact
+    }
+
+    /// Configured minimum interval between calls of `tool` (canonical name); `None` when
+    /// the tool has no cooldown or it is set to 0
+    pub fn tool_cooldown(&self, tool: &str) -> Option<Duration> {
+        let config = self.config.read().expect("RwLock poisoned");
+        let prefix = config.tool_prefix.as_deref();
+        config
+            .tool_cooldowns
+            .iter()
+            .find(|(name, _)| Self::strip_prefix(prefix, name) == tool)
+            .map(|(_, secs)| *secs)
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// How long a result of `tool` (canonical name) is reused: the configured TTL, else
+    /// the default for `DEFAULT_RESPONSE_CACHE_TOOLS`; `None` when not cached
+    pub fn response_cache_ttl(&self, tool: &str) -> Option<Duration> {
+        let config = self.config.read().expect("RwLock poisoned");
+        let prefix = config.tool_prefix.as_deref();
+        config
+            .response_cache_ttls
+            .iter()
+            .find(|(name, _)| Self::strip_prefix(prefix, name) == tool)
+            .map(|(_, secs)| *secs)
+            .or_else(|| DEFAULT_RESPONSE_CACHE_TOOLS.contains(&tool).then_some(DEFAULT_RESPONSE_CACHE_TTL_SECS))
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Configured response token budget and what happens above it; `None` when unset or 0
+    pub fn token_budget(&self) -> Option<(u64, OverBudgetAction)> {
+        let config = self.config.read().expect("RwLock poisoned");
+        config
+            .response_token_budget
+            .filter(|budget| *budget > 0)
+            .map(|budget| (budget, config.over_token_budget.unwrap_or_default()))
+    }
+
+    /// Default deadline for a tool call: `ONELOGIN_MCP_TOOL_TIMEOUT`, then the config file,
+    /// then `DEFAULT_TOOL_TIMEOUT_SECS`. `None` when set to 0 (no deadline).
+    pub fn tool_timeout(&self) -> Option<Duration> {
+        let env = std::env::var(TOOL_TIMEOUT_ENV_VAR).ok();
+        let configured = self.config.read().expect("RwLock poisoned").tool_timeout_seconds;
+        Self::resolve_timeout(env.as_deref(), configured)
+    }
+
+    fn resolve_timeout(env: Option<&str>, configured: Option<u64>) -> Option<Duration> {
+        let from_env = env.and_then(|v| match v.trim().parse::<u64>() {
+            Ok(secs) => Some(secs),
+            Err(_) => {
+                warn!("Ignoring invalid {}={:?}, expected seconds", TOOL_TIMEOUT_ENV_VAR, v);
+                None
+            }
+        });
+        match from_env.or(configured).unwrap_or(DEFAULT_TOOL_TIMEOUT_SECS) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    /// Exposed name for a canonical tool, applying the configured prefix
+    pub fn exposed_name(&self, canonical: &str) -> String {
+        let config = self.config.read().expect("RwLock poisoned");
+        Self::apply_prefix(config.tool_prefix.as_deref(), canonical)
+    }
+
+    /// Map a name sent by a client (alias, prefixed or canonical) to the canonical tool name
+    pub fn resolve_tool_name(&self, requested: &str) -> String {
+        let config = self.config.read().expect("RwLock poisoned");
+        let prefix = config.tool_prefix.as_deref();
+        let name = config.aliases.get(requested).map(String::as_str).unwrap_or(requested);
+        Self::strip_prefix(prefix, name)
+    }
+
+    /// Configured aliases as (alias, canonical tool name) pairs, sorted by alias
+    pub fn aliases(&self) -> Vec<(String, String)> {
+        let config = self.config.read().expect("RwLock poisoned");
+        let prefix = config.tool_prefix.as_deref();
+        let mut aliases: Vec<(String, String)> = config
+            .aliases
+            .iter()
+            .map(|(alias, target)| (alias.clone(), Self::strip_prefix(prefix, target)))
+            .collect();
+        aliases.sort();
+        aliases
+    }
+
+    /// Rewrite canonical tool names in free text (tool descriptions cross-reference each other)
+    pub fn rewrite_tool_references(&self, text: &str) -> String {
+        let config = self.config.read().expect("RwLock poisoned");
+        match config.tool_prefix.as_deref() {
+            Some(prefix) if prefix != CANONICAL_TOOL_PREFIX => {
+                text.replace(CANONICAL_TOOL_PREFIX, prefix)
+            }
+            _ => text.to_string(),
+        }
+    }
+
+    fn apply_prefix(prefix: Option<&str>, canonical: &str) -> String {
+        match (prefix, canonical.strip_prefix(CANONICAL_TOOL_PREFIX)) {
+            (Some(prefix), Some(rest)) => format!("{}{}", prefix, rest),
+            _ => canonical.to_string(),
+        }
+    }
+
+    fn strip_prefix(prefix: Option<&str>, name: &str) -> String {
+        match prefix {
+            Some(prefix) if !prefix.is_empty() && !name.starts_with(CANONICAL_TOOL_PREFIX) => name
+                .strip_prefix(prefix)
+                .map(|rest| format!("{}{}", CANONICAL_TOOL_PREFIX, rest))
+                .unwrap_or_else(|| name.to_string()),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Warn about prefixes and aliases that clients would reject or that point nowhere
+    fn validate_naming(config: &ToolConfigFile) {
+        let valid_chars = |s: &str| s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        let known = |name: &str| TOOL_CATEGORIES.iter().any(|c| c.tools.contains(&name));
+        let prefix = config.tool_prefix.as_deref();
+
+        if let Some(prefix) = prefix {
+            if !valid_chars(prefix) {
+                warn!("tool_prefix '{}' contains characters MCP clients may reject", prefix);
+            }
+            let longest = TOOL_CATEGORIES
+                .iter()
+                .flat_map(|c| c.tools.iter())
+                .map(|t| Self::apply_prefix(Some(prefix), t).len())
+                .max()
+                .unwrap_or(0);
+            if longest > MAX_TOOL_NAME_LEN {
+                warn!(
+                    "tool_prefix '{}' makes some tool names {} characters long (limit {})",
+                    prefix, longest, MAX_TOOL_NAME_LEN
+                );
+            }
+        }
+
+        for (alias, target) in &config.aliases {
+            if !valid_chars(alias) || alias.len() > MAX_TOOL_NAME_LEN {
+                warn!("Alias '{}' is not a valid MCP tool name", alias);
+            }
+            if !known(&Self::strip_prefix(prefix, target)) {
+                warn!("Alias '{}' points to unknown tool '{}' (will be ignored)", alias, target);
+            }
+        }
+
+        for tool in config.tool_cooldowns.keys() {
+            if !known(&Self::strip_prefix(prefix, tool)) {
+                warn!("Cooldown set for unknown tool '{}' (will be ignored)", tool);
+            }
+        }
+
+        for tool in config.response_cache_ttls.keys() {
+            if !known(&Self::strip_prefix(prefix, tool)) {
+                warn!("Response cache TTL set for unknown tool '{}' (will be ignored)", tool);
+            }
+        }
+    }
+
     /// Compute which tools are enabled based on config
     fn compute_enabled_tools(config: &ToolConfigFile) -> HashSet<String> {
         let mut enabled = HashSet::new();
@@ -582,9 +1004,50 @@ impl ToolConfig {
             }
         }
 
+        Self::apply_overrides(
+            &mut enabled,
+            std::env::var(ENABLE_ENV_VAR).ok().as_deref(),
+            std::env::var(DISABLE_ENV_VAR).ok().as_deref(),
+        );
+
         enabled
     }
 
+    /// Apply comma-separated enable/disable lists. Entries are category names,
+    /// tool names, or `all`; disables are applied last so they always win.
+    fn apply_overrides(enabled: &mut HashSet<String>, enable: Option<&str>, disable: Option<&str>) {
+        if let Some(list) = enable {
+            for tool in Self::expand_override_list(ENABLE_ENV_VAR, list) {
+                enabled.insert(tool);
+            }
+        }
+        if let Some(list) = disable {
+            for tool in Self::expand_override_list(DISABLE_ENV_VAR, list) {
+                enabled.remove(&tool);
+            }
+        }
+    }
+
+    fn expand_override_list(var: &str, list: &str) -> Vec<String> {
+        let mut tools = Vec::new();
+        for entry in list.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            if entry.eq_ignore_ascii_case("all") {
+                tools.extend(
+                    TOOL_CATEGORIES
+                        .iter()
+                        .flat_map(|c| c.tools.iter().map(|t| t.to_string())),
+                );
+            } else if let Some(category) = TOOL_CATEGORIES.iter().find(|c| c.name == entry) {
+                tools.extend(category.tools.iter().map(|t| t.to_string()));
+            } else if TOOL_CATEGORIES.iter().any(|c| c.tools.contains(&entry)) {
+                tools.push(entry.to_string());
+            } else {
+                warn!("Unknown category or tool '{}' in {} (will be ignored)", entry, var);
+            }
+        }
+        tools
+    }
+
     /// Start watching config file for changes (hot reload)
     pub fn start_watcher(self: &Arc<Self>) -> Result<Option<RecommendedWatcher>> {
         if !self.hot_reload_enabled() {
@@ -660,10 +1123,22 @@ impl ToolConfig {
             }
         }
 
+        let mut aliases = HashMap::new();
+        aliases.insert("find_user".to_string(), "onelogin_get_user".to_string());
+
         let config = ToolConfigFile {
             version: CURRENT_VERSION.to_string(),
             hot_reload: true,
             categories,
+            tool_prefix: None,
+            aliases,
+            tool_timeout_seconds: Some(DEFAULT_TOOL_TIMEOUT_SECS),
+            jobs: Vec::new(),
+            require_deletion_impact: false,
+            tool_cooldowns: HashMap::from([("onelogin_delete_app".to_string(), 300)]),
+            response_cache_ttls: HashMap::from([("onelogin_app_access_report".to_string(), 900)]),
+            response_token_budget: Some(20_000),
+            over_token_budget: Some(OverBudgetAction::Warn),
         };
 
         serde_json::to_string_pretty(&config).expect("Failed to serialize example config")
@@ -798,4 +1273,137 @@ mod tests {
         let example = ToolConfig::generate_example_config();
         let _: ToolConfigFile = serde_json::from_str(&example).unwrap();
     }
+
+    fn config_with_naming(prefix: Option<&str>, aliases: &[(&str, &str)]) -> ToolConfig {
+        let file = ToolConfigFile {
+            tool_prefix: prefix.map(str::to_string),
+            aliases: aliases
+                .iter()
+                .map(|(a, t)| (a.to_string(), t.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        let enabled = ToolConfig::compute_enabled_tools(&file);
+        ToolConfig {
+            config_path: None,
+            config: RwLock::new(file),
+            enabled_tools: RwLock::new(enabled),
+        }
+    }
+
+    #[test]
+    fn test_tool_prefix_round_trip() {
+        let config = config_with_naming(Some("ol_"), &[]);
+        assert_eq!(config.exposed_name("onelogin_list_users"), "ol_list_users");
+        assert_eq!(config.resolve_tool_name("ol_list_users"), "onelogin_list_users");
+        // Canonical names keep working
+        assert_eq!(config.resolve_tool_name("onelogin_list_users"), "onelogin_list_users");
+        assert_eq!(
+            config.rewrite_tool_references("Use onelogin_get_user first"),
+            "Use ol_get_user first"
+        );
+    }
+
+    #[test]
+    fn test_aliases_resolve_to_canonical() {
+        let config = config_with_naming(Some("ol_"), &[("find_user", "ol_get_user")]);
+        assert_eq!(config.resolve_tool_name("find_user"), "onelogin_get_user");
+        assert_eq!(
+            config.aliases(),
+            vec![("find_user".to_string(), "onelogin_get_user".to_string())]
+        );
+
+        let config = config_with_naming(None, &[("find_user", "onelogin_get_user")]);
+        assert_eq!(config.exposed_name("onelogin_get_user"), "onelogin_get_user");
+        assert_eq!(config.resolve_tool_name("find_user"), "onelogin_get_user");
+    }
+
+    #[test]
+    fn test_env_style_overrides() {
+        let mut enabled = ToolConfig::compute_enabled_tools(&ToolConfigFile::default());
+        ToolConfig::apply_overrides(
+            &mut enabled,
+            Some("mfa, onelogin_get_rate_limits"),
+            Some("users,onelogin_list_mfa_factors, not_a_tool"),
+        );
+        assert!(enabled.contains("onelogin_get_rate_limits"));
+        assert!(enabled.contains("onelogin_enroll_mfa_factor"));
+        // Disable wins over enable
+        assert!(!enabled.contains("onelogin_list_mfa_factors"));
+        assert!(!enabled.contains("onelogin_list_users"));
+        // Untouched categories keep their defaults
+        assert!(enabled.contains("onelogin_list_apps"));
+
+        let mut enabled = HashSet::new();
+        ToolConfig::apply_overrides(&mut enabled, Some("all"), Some("users"));
+        assert!(enabled.contains("onelogin_get_risk_rule"));
+        assert!(!enabled.contains("onelogin_get_user"));
+    }
+
+    #[test]
+    fn test_tool_timeout_resolution() {
+        let default = Some(Duration::from_secs(DEFAULT_TOOL_TIMEOUT_SECS));
+        assert_eq!(ToolConfig::resolve_timeout(None, None), default);
+        assert_eq!(ToolConfig::resolve_timeout(None, Some(30)), Some(Duration::from_secs(30)));
+        assert_eq!(ToolConfig::resolve_timeout(Some("5"), Some(30)), Some(Duration::from_secs(5)));
+        assert_eq!(ToolConfig::resolve_timeout(Some("0"), Some(30)), None);
+        assert_eq!(ToolConfig::resolve_timeout(Some("soon"), Some(30)), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_tool_cooldowns() {
+        let config: ToolConfigFile = serde_json::from_str(
+            r#"{"tool_prefix": "ol_", "tool_cooldowns": {"onelogin_delete_app": 300, "ol_delete_role": 60, "onelogin_delete_user": 0}}"#,
+        )
+        .unwrap();
+        let tool_config = ToolConfig {
+            config_path: None,
+            enabled_tools: RwLock::new(ToolConfig::compute_enabled_tools(&config)),
+            config: RwLock::new(config),
+        };
+        assert_eq!(tool_config.tool_cooldown("onelogin_delete_app"), Some(Duration::from_secs(300)));
+        assert_eq!(tool_config.tool_cooldown("onelogin_delete_role"), Some(Duration::from_secs(60)));
+        assert_eq!(tool_config.tool_cooldown("onelogin_delete_user"), None);
+        assert_eq!(tool_config.tool_cooldown("onelogin_get_app"), None);
+    }
+
+    #[test]
+    fn test_response_cache_ttls() {
+        let config: ToolConfigFile = serde_json::from_str(
+            r#"{"tool_prefix": "ol_", "response_cache_ttls": {"ol_app_usage_report": 900, "onelogin_app_access_report": 0, "onelogin_list_roles": 60}}"#,
+        )
+        .unwrap();
+        let tool_config = ToolConfig {
+            config_path: None,
+            enabled_tools: RwLock::new(ToolConfig::compute_enabled_tools(&config)),
+            config: RwLock::new(config),
+        };
+        assert_eq!(tool_config.response_cache_ttl("onelogin_app_usage_report"), Some(Duration::from_secs(900)));
+        assert_eq!(tool_config.response_cache_ttl("onelogin_app_access_report"), None);
+        assert_eq!(tool_config.response_cache_ttl("onelogin_list_roles"), Some(Duration::from_secs(60)));
+        assert_eq!(
+            tool_config.response_cache_ttl("onelogin_inactive_users_report"),
+            Some(Duration::from_secs(DEFAULT_RESPONSE_CACHE_TTL_SECS))
+        );
+        assert_eq!(tool_config.response_cache_ttl("onelogin_get_user"), None);
+    }
+
+    #[test]
+    fn test_token_budget() {
+        let config: ToolConfigFile =
+            serde_json::from_str(r#"{"response_token_budget": 8000, "over_token_budget": "paginate"}"#).unwrap();
+        let tool_config = ToolConfig {
+            config_path: None,
+            enabled_tools: RwLock::new(ToolConfig::compute_enabled_tools(&config)),
+            config: RwLock::new(config),
+        };
+        assert_eq!(tool_config.token_budget(), Some((8000, OverBudgetAction::Paginate)));
+
+        let config: ToolConfigFile = serde_json::from_str(r#"{"response_token_budget": 8000}"#).unwrap();
+        *tool_config.config.write().unwrap() = config;
+        assert_eq!(tool_config.token_budget(), Some((8000, OverBudgetAction::Warn)));
+
+        *tool_config.config.write().unwrap() = serde_json::from_str(r#"{"response_token_budget": 0}"#).unwrap();
+        assert_eq!(tool_config.token_budget(), None);
+    }
 }
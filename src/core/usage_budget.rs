@@ -0,0 +1,319 @@
+//! Daily budget of OneLogin API requests per tenant.
+//!
+//! OneLogin's rate allocation is shared by every integration of a tenant, so an
+//! agent looping through reports can starve the others long before it hits a
+//! 429. Every request sent is counted per UTC day; with `DAILY_REQUEST_BUDGET`
+//! (or `daily_request_budget` in tenants.json) set, requests past the budget fail
+//! with `BudgetExceeded` until midnight UTC. With `DAILY_BUDGET_EXCLUDE_READS`
+//! GET requests are counted but neither use up nor are refused by the budget,
+//! so lookups keep working while changes are held back. Counters are kept per
+//! tenant and UTC date in `usage.json` next to tenants.json, so restarting the
+//! server does not reset the day's usage. Requests only update the counters in
+//! memory; a background task writes them out every few seconds, so a crash loses
+//! at most the last few seconds of counts.
+
+use crate::core::config::Config;
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::warn;
+
+/// How often changed counters are written to `usage.json`
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// One tenant's counters for one UTC day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Day {
+    requests: u64,
+    reads: u64,
+    /// Requests counted against the budget
+    charged: u64,
+    rejected: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageFile {
+    /// Keyed by `tenant:YYYY-MM-DD`
+    #[serde(default)]
+    days: HashMap<String, Day>,
+}
+
+/// Day counters of every tenant, saved so a restart does not hand out a fresh budget
+pub struct UsageStore {
+    /// `None` keeps counters in memory only (mock mode, tests)
+    path: Option<PathBuf>,
+    days: Mutex<HashMap<String, Day>>,
+    /// Counters changed since the last flush
+    dirty: AtomicBool,
+}
+
+impl UsageStore {
+    /// `usage.json` next to tenants.json
+    pub fn default_path() -> Option<PathBuf> {
+        Config::tenants_file_path().map(|p| p.with_file_name("usage.json"))
+    }
+
+    /// Load counters from `path`; a missing or unreadable file starts empty
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let days = match &path {
+            Some(p) if p.exists() => match Self::read_file(p) {
+                Ok(file) => file.days,
+                Err(e) => {
+                    warn!("Ignoring unreadable usage file {}: {:#}", p.display(), e);
+                    HashMap::new()
+                }
+            },
+            _ => HashMap::new(),
+        };
+        Self {
+            path,
+            days: Mutex::new(days),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Load counters from `path` and write changes back in the background until
+    /// the store is dropped
+    pub fn open(path: Option<PathBuf>) -> Arc<Self> {
+        let store = Arc::new(Self::load(path));
+        if store.path.is_some() {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(Self::flush_periodically(Arc::downgrade(&store)));
+            }
+        }
+        store
+    }
+
+    async fn flush_periodically(store: std::sync::Weak<Self>) {
+        let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let Some(store) = store.upgrade() else {
+                return;
+            };
+            let flushed = tokio::task::spawn_blocking(move || store.flush()).await;
+            if let Ok(Err(e)) = flushed {
+                warn!("Failed to save API usage: {:#}", e);
+            }
+        }
+    }
+
+    pub fn in_memory() -> Self {
+        Self::load(None)
+    }
+
+    fn read_file(path: &Path) -> Result<UsageFile> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn key(tenant: &str, date: NaiveDate) -> String {
+        format!("{}:{}", tenant, date)
+    }
+
+    /// Write changed counters out, keeping only today's. The file is replaced
+    /// through a temporary file, so a crash never leaves a truncated one behind.
+    pub fn flush(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return Ok(());
+        }
+        let file = {
+            let mut days = self.days.lock().expect("usage budget poisoned");
+            let suffix = format!(":{}", Utc::now().date_naive());
+            days.retain(|key, _| key.ends_with(&suffix));
+            UsageFile { days: days.clone() }
+        };
+        let written = Self::write_file(path, &file);
+        if written.is_err() {
+            self.dirty.store(true, Ordering::Release);
+        }
+        written
+    }
+
+    fn write_file(path: &Path, file: &UsageFile) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let tmp = path.with_extension(format!("json.tmp-{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_string_pretty(file)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("Failed to replace usage file {}", path.display()))
+    }
+}
+
+impl Drop for UsageStore {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Failed to save API usage: {:#}", e);
+        }
+    }
+}
+
+/// Snapshot of today's usage, for `onelogin_server_stats` and `/metrics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageBudgetStats {
+    /// UTC day the counters cover
+    pub date: String,
+    /// Requests sent today (reads included)
+    pub requests: u64,
+    pub reads: u64,
+    /// `None` when no budget is configured
+    pub daily_budget: Option<u64>,
+    pub exclude_reads: bool,
+    /// Budget left today; `None` when no budget is configured
+    pub remaining: Option<u64>,
+    /// Requests refused today because the budget was used up
+    pub rejected: u64,
+}
+
+pub struct UsageBudget {
+    limit: Option<u64>,
+    exclude_reads: bool,
+    tenant: String,
+    store: Arc<UsageStore>,
+}
+
+impl UsageBudget {
+    /// `limit` of `None` or 0 only tracks usage. Counters stay in memory until `with_store`.
+    pub fn new(limit: Option<u64>, exclude_reads: bool) -> Self {
+        Self {
+            limit: limit.filter(|l| *l > 0),
+            exclude_reads,
+            tenant: String::new(),
+            store: Arc::new(UsageStore::in_memory()),
+        }
+    }
+
+    /// Keep this tenant's counters in a (persistent) store shared with other tenants
+    pub fn with_store(mut self, tenant: &str, store: Arc<UsageStore>) -> Self {
+        self.tenant = tenant.to_string();
+        self.store = store;
+        self
+    }
+
+    /// Count a request about to be sent, or Err with the reason when the budget is used up
+    pub fn charge(&self, method: &Method) -> Result<(), String> {
+        self.charge_on(Utc::now().date_naive(), method)
+    }
+
+    fn charge_on(&self, today: NaiveDate, method: &Method) -> Result<(), String> {
+        let mut days = self.store.days.lock().expect("usage budget poisoned");
+        let day = days.entry(UsageStore::key(&self.tenant, today)).or_default();
+        let read = *method == Method::GET;
+        let exempt = read && self.exclude_reads;
+        let result = match self.limit.filter(|limit| !exempt && day.charged >= *limit) {
+            Some(limit) => {
+                day.rejected += 1;
+                Err(format!(
+                    "Daily API budget of {} requests for this tenant is used up; {} was not sent. The budget resets at midnight UTC{}",
+                    limit,
+                    method,
+                    if self.exclude_reads { " (read requests are still allowed)" } else { "" }
+                ))
+            }
+            None => {
+                day.requests += 1;
+                if read {
+                    day.reads += 1;
+                }
+                if !exempt {
+                    day.charged += 1;
+                }
+                Ok(())
+            }
+        };
+        self.store.dirty.store(true, Ordering::Release);
+        result
+    }
+
+    pub fn stats(&self) -> UsageBudgetStats {
+        let today = Utc::now().date_naive();
+        let day = self
+            .store
+            .days
+            .lock()
+            .expect("usage budget poisoned")
+            .get(&UsageStore::key(&self.tenant, today))
+            .cloned()
+            .unwrap_or_default();
+        UsageBudgetStats {
+            date: today.to_string(),
+            requests: day.requests,
+            reads: day.reads,
+            daily_budget: self.limit,
+            exclude_reads: self.exclude_reads,
+            remaining: self.limit.map(|limit| limit.saturating_sub(day.charged)),
+            rejected: day.rejected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_and_daily_reset() {
+        let day1 = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let budget = UsageBudget::new(Some(2), false);
+        assert!(budget.charge_on(day1, &Method::GET).is_ok());
+        assert!(budget.charge_on(day1, &Method::POST).is_ok());
+        let err = budget.charge_on(day1, &Method::GET).unwrap_err();
+        assert!(err.contains("budget of 2 requests"));
+
+        let day2 = day1.succ_opt().unwrap();
+        assert!(budget.charge_on(day2, &Method::DELETE).is_ok());
+    }
+
+    #[test]
+    fn test_counters_survive_restart() {
+        let path = std::env::temp_dir().join(format!("usage-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let today = Utc::now().date_naive();
+        let store = Arc::new(UsageStore::load(Some(path.clone())));
+        let prod = UsageBudget::new(Some(2), false).with_store("prod", store.clone());
+        let dev = UsageBudget::new(Some(2), false).with_store("dev", store.clone());
+        assert!(prod.charge_on(today, &Method::POST).is_ok());
+        assert!(prod.charge_on(today, &Method::POST).is_ok());
+        assert!(dev.charge_on(today, &Method::POST).is_ok());
+        assert!(!path.exists(), "requests must not write the file themselves");
+        store.flush().unwrap();
+
+        let store = Arc::new(UsageStore::load(Some(path.clone())));
+        let prod = UsageBudget::new(Some(2), false).with_store("prod", store.clone());
+        let dev = UsageBudget::new(Some(2), false).with_store("dev", store);
+        assert!(prod.charge_on(today, &Method::POST).is_err());
+        assert_eq!(dev.stats().remaining, Some(1));
+        drop((prod, dev));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_reads_excluded() {
+        let today = Utc::now().date_naive();
+        let budget = UsageBudget::new(Some(1), true);
+        assert!(budget.charge_on(today, &Method::PUT).is_ok());
+        assert!(budget.charge_on(today, &Method::GET).is_ok());
+        assert!(budget.charge_on(today, &Method::PUT).is_err());
+        assert!(budget.charge_on(today, &Method::GET).is_ok());
+
+        let stats = budget.stats();
+        assert_eq!((stats.requests, stats.reads, stats.rejected), (3, 2, 1));
+        assert_eq!(stats.remaining, Some(0));
+
+        let unlimited = UsageBudget::new(Some(0), false);
+        assert!((0..10).all(|_| unlimited.charge_on(today, &Method::POST).is_ok()));
+        assert_eq!(unlimited.stats().remaining, None);
+    }
+}
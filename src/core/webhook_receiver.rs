@@ -0,0 +1,297 @@
+//! Optional HTTP listener for OneLogin event webhooks (the event broadcaster).
+//!
+//! Enabled by `ONELOGIN_WEBHOOK_LISTEN`. Every POST to the webhook path must
+//! carry an `X-OneLogin-Signature` header with the hex HMAC-SHA256 of the body
+//! under `ONELOGIN_WEBHOOK_SECRET`. Accepted events are kept in a bounded
+//! in-memory buffer for `onelogin_get_received_events` and broadcast to the MCP
+//! server, which forwards them to the client as notifications.
+
+use crate::api::webhooks::WebhooksApi;
+use crate::models::webhooks::WebhookSignatureVerification;
+use crate::core::http_listener::{self, HttpRequest};
+use anyhow::{anyhow, Context, Result};
+use secrecy::{ExposeSecret, SecretString};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::BufReader;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+pub const LISTEN_ENV_VAR: &str = "ONELOGIN_WEBHOOK_LISTEN";
+pub const SECRET_ENV_VAR: &str = "ONELOGIN_WEBHOOK_SECRET";
+pub const PATH_ENV_VAR: &str = "ONELOGIN_WEBHOOK_PATH";
+pub const BUFFER_ENV_VAR: &str = "ONELOGIN_WEBHOOK_BUFFER";
+
+const DEFAULT_PATH: &str = "/webhooks/onelogin";
+const DEFAULT_BUFFER_SIZE: usize = 1000;
+const SIGNATURE_HEADER: &str = "x-onelogin-signature";
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+pub struct WebhookReceiverConfig {
+    pub listen: SocketAddr,
+    pub secret: SecretString,
+    pub path: String,
+    pub buffer_size: usize,
+}
+
+impl WebhookReceiverConfig {
+    /// Read receiver settings from the environment. `None` when `ONELOGIN_WEBHOOK_LISTEN` is unset.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(listen) = std::env::var(LISTEN_ENV_VAR) else {
+            return Ok(None);
+        };
+        let listen: SocketAddr = listen
+            .parse()
+            .with_context(|| format!("Invalid {}: expected host:port, e.g. 127.0.0.1:8787", LISTEN_ENV_VAR))?;
+        let secret = std::env::var(SECRET_ENV_VAR)
+            .ok()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("{} is set but {} is missing; unsigned webhooks are never accepted", LISTEN_ENV_VAR, SECRET_ENV_VAR))?;
+        let path = std::env::var(PATH_ENV_VAR).unwrap_or_else(|_| DEFAULT_PATH.to_string());
+        let buffer_size = match std::env::var(BUFFER_ENV_VAR) {
+            Ok(v) => v.parse().with_context(|| format!("Invalid {}", BUFFER_ENV_VAR))?,
+            Err(_) => DEFAULT_BUFFER_SIZE,
+        };
+        Ok(Some(Self {
+            listen,
+            secret: SecretString::new(secret),
+            path,
+            buffer_size: buffer_size.max(1),
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceivedEvent {
+    /// Monotonic sequence number assigned on receipt
+    pub seq: u64,
+    pub received_at: String,
+    pub event: Value,
+}
+
+struct BufferState {
+    events: VecDeque<ReceivedEvent>,
+    next_seq: u64,
+    dropped: u64,
+}
+
+/// Bounded buffer of received events; the oldest are dropped when full
+pub struct WebhookBuffer {
+    capacity: usize,
+    listen: Option<SocketAddr>,
+    state: Mutex<BufferState>,
+    sender: broadcast::Sender<ReceivedEvent>,
+}
+
+impl WebhookBuffer {
+    pub fn new(capacity: usize, listen: Option<SocketAddr>) -> Self {
+        let (sender, _) = broadcast::channel(capacity.clamp(16, 1024));
+        Self {
+            capacity,
+            listen,
+            state: Mutex::new(BufferState {
+                events: VecDeque::new(),
+                next_seq: 1,
+                dropped: 0,
+            }),
+            sender,
+        }
+    }
+
+    pub fn push(&self, event: Value) -> ReceivedEvent {
+        let mut state = self.state.lock().expect("Mutex poisoned");
+        let received = ReceivedEvent {
+            seq: state.next_seq,
+            received_at: chrono::Utc::now().to_rfc3339(),
+            event,
+        };
+        state.next_seq += 1;
+        if state.events.len() == self.capacity {
+            state.events.pop_front();
+            state.dropped += 1;
+        }
+        state.events.push_back(received.clone());
+        // No subscribers is fine: the buffer is the source of truth
+        let _ = self.sender.send(received.clone());
+        received
+    }
+
+    /// Buffered events with `seq > since_seq`, oldest first
+    pub fn since(&self, since_seq: u64, limit: usize) -> Vec<ReceivedEvent> {
+        let state = self.state.lock().expect("Mutex poisoned");
+        state
+            .events
+            .iter()
+            .filter(|e| e.seq > since_seq)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ReceivedEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn status(&self) -> Value {
+        let state = self.state.lock().expect("Mutex poisoned");
+        json!({
+            "listen": self.listen.map(|a| a.to_string()),
+            "buffered": state.events.len(),
+            "capacity": self.capacity,
+            "dropped": state.dropped,
+            "latestSeq": state.next_seq - 1
+        })
+    }
+}
+
+/// Bind the listener and serve webhooks in the background
+pub async fn start(config: WebhookReceiverConfig) -> Result<Arc<WebhookBuffer>> {
+    let listener = TcpListener::bind(config.listen)
+        .await
+        .with_context(|| format!("Failed to bind webhook receiver on {}", config.listen))?;
+    let buffer = Arc::new(WebhookBuffer::new(config.buffer_size, Some(config.listen)));
+    info!("Webhook receiver listening on http://{}{}", config.listen, config.path);
+
+    let config = Arc::new(config);
+    let accept_buffer = buffer.clone();
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let config = config.clone();
+                    let buffer = accept_buffer.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &config, &buffer).await {
+                            debug!("Webhook connection from {} failed: {:#}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Webhook receiver accept failed: {}", e),
+            }
+        }
+    });
+    Ok(buffer)
+}
+
+async fn handle_connection(stream: TcpStream, config: &WebhookReceiverConfig, buffer: &WebhookBuffer) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let (status, body) = match http_listener::read_request(&mut reader, MAX_BODY_BYTES).await {
+        Ok(request) => process(request, config, buffer),
+        Err(e) => (400, json!({"error": e.to_string()})),
+    };
+    http_listener::write_json(reader.get_mut(), status, &[], &body).await
+}
+
+fn process(request: HttpRequest, config: &WebhookReceiverConfig, buffer: &WebhookBuffer) -> (u16, Value) {
+    if request.path() != config.path {
+        return (404, json!({"error": "Not found"}));
+    }
+    if request.method != "POST" {
+        return (405, json!({"error": "Method not allowed"}));
+    }
+    let Ok(payload) = std::str::from_utf8(&request.body) else {
+        return (400, json!({"error": "Body is not UTF-8"}));
+    };
+    let verified = request.header(SIGNATURE_HEADER).is_some_and(|signature| {
+        WebhooksApi::verify_signature(WebhookSignatureVerification {
+            signature: signature.trim_start_matches("sha256=").to_string(),
+            payload: payload.to_string(),
+            secret: config.secret.expose_secret().clone(),
+        })
+    });
+    if !verified {
+        warn!("Rejected webhook with missing or invalid signature");
+        return (401, json!({"error": "Invalid signature"}));
+    }
+
+    let events = parse_events(payload);
+    if events.is_empty() {
+        return (400, json!({"error": "No events in payload"}));
+    }
+    let count = events.len();
+    for event in events {
+        buffer.push(event);
+    }
+    debug!("Accepted {} webhook events", count);
+    (200, json!({"accepted": count}))
+}
+
+/// The broadcaster sends a JSON array, a single object, or newline-delimited JSON
+fn parse_events(payload: &str) -> Vec<Value> {
+    match serde_json::from_str::<Value>(payload) {
+        Ok(Value::Array(events)) => events,
+        Ok(Value::Object(map)) => match map.get("events").or_else(|| map.get("data")) {
+            Some(Value::Array(events)) => events.clone(),
+            _ => vec![Value::Object(map)],
+        },
+        Ok(_) => Vec::new(),
+        Err(_) => payload
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+            .filter(Value::is_object)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    fn sign(secret: &str, payload: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(payload.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn config() -> WebhookReceiverConfig {
+        WebhookReceiverConfig {
+            listen: "127.0.0.1:0".parse().unwrap(),
+            secret: SecretString::new("s3cret".to_string()),
+            path: DEFAULT_PATH.to_string(),
+            buffer_size: 2,
+        }
+    }
+
+    fn request(payload: &str, signature: Option<String>) -> HttpRequest {
+        HttpRequest {
+            method: "POST".to_string(),
+            target: DEFAULT_PATH.to_string(),
+            headers: signature.map(|s| (SIGNATURE_HEADER.to_string(), s)).into_iter().collect(),
+            body: payload.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_signed_events_are_buffered_and_unsigned_rejected() {
+        let config = config();
+        let buffer = WebhookBuffer::new(config.buffer_size, None);
+        let payload = r#"[{"id": 1, "event_type_id": 5}, {"id": 2, "event_type_id": 6}, {"id": 3}]"#;
+
+        let (status, _) = process(request(payload, None), &config, &buffer);
+        assert_eq!(status, 401);
+        let (status, _) = process(request(payload, Some(sign("wrong", payload))), &config, &buffer);
+        assert_eq!(status, 401);
+
+        let (status, body) = process(request(payload, Some(sign("s3cret", payload))), &config, &buffer);
+        assert_eq!(status, 200);
+        assert_eq!(body["accepted"], 3);
+
+        // Capacity 2: the oldest event was dropped
+        let events = buffer.since(0, 10);
+        assert_eq!(events.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(buffer.since(2, 10)[0].event["id"], 3);
+        assert_eq!(buffer.status()["dropped"], 1);
+    }
+
+    #[test]
+    fn test_parse_ndjson_payload() {
+        let events = parse_events("{\"id\": 1}\n{\"id\": 2}\n");
+        assert_eq!(events.len(), 2);
+    }
+}
@@ -1,6 +1,7 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use clap::Parser;
 use tracing::{error, info, Level};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 mod api;
 mod cli;
@@ -10,8 +11,16 @@ mod models;
 mod utils;
 
 use crate::cli::{Cli, Commands};
+use crate::core::cassette::Cassette;
 use crate::core::config::Config;
+use crate::core::mock::MockStore;
+use crate::core::webhook_receiver::WebhookReceiverConfig;
+use crate::core::service;
 use crate::mcp::server::McpServer;
+use crate::mcp::websocket::{self, WebSocketConfig};
+use notify::RecommendedWatcher;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -23,26 +32,187 @@ async fn main() -> Result<()> {
         Some(Commands::Config { action }) => {
             return cli::execute_config_action(action.clone());
         }
+        Some(Commands::Service) => {
+            init_logging(true)?;
+            return run_as_service().await;
+        }
+        // Stdout carries the result, so nothing may log to it
+        Some(Commands::RunHook) => {
+            return utils::hook_runtime::run_job_from_stdin();
+        }
         _ => {}
     }
 
-    // Initialize tracing for server mode - IMPORTANT: Write logs to stderr, not stdout
-    // MCP protocol requires stdout to only contain JSON-RPC messages
-    tracing_subscriber::fmt()
+    init_logging(false)?;
+
+    // Run the MCP server (default command)
+    if cli.mock {
+        return run_mock_server(cli.fixtures).await;
+    }
+    let cassette = match (cli.record, cli.replay) {
+        (Some(path), _) => Some(Arc::new(Cassette::record(path))),
+        (None, Some(path)) => Some(Arc::new(Cassette::replay(path)?)),
+        (None, None) => None,
+    };
+    run_server(cassette).await
+}
+
+/// Initialize tracing. IMPORTANT: logs never go to stdout, which the MCP protocol
+/// reserves for JSON-RPC messages. Services may log to a file instead of stderr.
+fn init_logging(service: bool) -> Result<()> {
+    let log_file = if service { service::log_file_from_env() } else { None };
+    let writer = match &log_file {
+        Some(path) => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create log directory {}", parent.display()))?;
+            }
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            BoxMakeWriter::new(Arc::new(file))
+        }
+        None => BoxMakeWriter::new(std::io::stderr),
+    };
+    let builder = tracing_subscriber::fmt()
         .with_max_level(Level::INFO)
         .with_target(false)
-        .with_writer(std::io::stderr) // Logs go to stderr
-        .with_ansi(false) // Disable color codes
-        .init();
+        .with_writer(writer)
+        .with_ansi(false); // Disable color codes
+    // The journal timestamps every line itself
+    if service && log_file.is_none() && service::under_journald() {
+        builder.without_time().init();
+    } else {
+        builder.init();
+    }
+    Ok(())
+}
 
-    // Run the MCP server (default command)
-    run_server().await
+/// Run headless until the service manager stops the process
+async fn run_as_service() -> Result<()> {
+    #[cfg(windows)]
+    {
+        let handle = tokio::runtime::Handle::current();
+        tokio::task::block_in_place(|| {
+            service::windows::run_dispatcher(handle, |mut stop| {
+                Box::pin(run_headless(async move {
+                    let _ = stop.changed().await;
+                }))
+            })
+        })
+    }
+    #[cfg(not(windows))]
+    {
+        run_headless(service::shutdown_signal()).await
+    }
 }
 
-async fn run_server() -> Result<()> {
-    info!("Starting OneLogin MCP Server v{}", env!("CARGO_PKG_VERSION"));
+async fn run_headless(shutdown: impl std::future::Future<Output = ()>) -> Result<()> {
+    info!("Starting OneLogin MCP Server v{} as a service", env!("CARGO_PKG_VERSION"));
+    // A service has no stdio client, so MCP clients can only reach it over WebSocket
+    let transport = WebSocketConfig::from_env()
+        .context("Invalid WebSocket transport configuration")?
+        .ok_or_else(|| {
+            anyhow!(
+                "Service mode serves MCP clients over WebSocket; set {} (e.g. 127.0.0.1:8765)",
+                websocket::LISTEN_ENV_VAR
+            )
+        })?;
+    let listen = transport.listen;
+    let (server, _watcher) = start_server(None).await?;
+    let watchdog = service::spawn_watchdog();
+    service::notify_ready();
+    info!("Service running: serving MCP clients over WebSocket on {}", listen);
+
+    let result = tokio::select! {
+        result = server.run_websocket(transport) => {
+            result.context("WebSocket transport stopped")
+        }
+        _ = shutdown => Ok(()),
+    };
+    info!("Stopping service");
+    service::notify_stopping();
+    if let Some(watchdog) = watchdog {
+        watchdog.abort();
+    }
+    result
+}
+
+/// Run against in-memory fixtures: no credentials, no network calls to OneLogin
+async fn run_mock_server(fixtures: Option<PathBuf>) -> Result<()> {
+    info!("Starting OneLogin MCP Server v{} in mock mode", env!("CARGO_PKG_VERSION"));
+
+    let store = match &fixtures {
+        Some(path) => MockStore::load(path)
+            .with_context(|| format!("Failed to load fixtures from {}", path.display()))?,
+        None => {
+            info!("No --fixtures given, using built-in demo data");
+            MockStore::with_demo_data()
+        }
+    };
+
+    let mut config = Config::from_env_base().context("Failed to load base configuration")?;
+    config.onelogin_subdomain = "mock".to_string();
+
+    let server = McpServer::new_mock(config, Arc::new(store)).await?;
+    let server = Arc::new(with_webhook_receiver(server).await?);
+    server.start_metrics_exporter().await.context("Failed to start metrics exporter")?;
+    server.start_admin_api().await.context("Failed to start admin API")?;
+    server.start_scheduler();
+    let _watcher = server.start_config_watcher().context(
+        "Failed to start configuration file watcher for hot reload"
+    )?;
+    serve(server).await
+}
+
+/// Serve MCP clients over WebSocket when ONELOGIN_WS_LISTEN is set, else over stdio
+async fn serve(server: Arc<McpServer>) -> Result<()> {
+    match WebSocketConfig::from_env().context("Invalid WebSocket transport configuration")? {
+        Some(config) => server.run_websocket(config).await,
+        None => server.run().await,
+    }
+}
+
+/// Start the webhook receiver when ONELOGIN_WEBHOOK_LISTEN is set
+async fn with_webhook_receiver(server: McpServer) -> Result<McpServer> {
+    match WebhookReceiverConfig::from_env().context("Invalid webhook receiver configuration")? {
+        Some(config) => server.with_webhook_receiver(config).await,
+        None => Ok(server),
+    }
+}
+
+async fn run_server(cassette: Option<Arc<Cassette>>) -> Result<()> {
+    // Note: _watcher must be kept alive for the duration of the server
+    let (server, _watcher) = start_server(cassette).await?;
     info!("Logs are written to stderr, MCP messages to stdout");
 
+    info!("Starting MCP server main loop...");
+    if let Err(e) = serve(server).await {
+        error!(
+            "MCP SERVER ERROR\n\
+             \n\
+             The MCP server encountered a fatal error and will shut down:\n\
+             {:#}\n\
+             \n\
+             Full error chain is shown above.",
+            e
+        );
+
+        return Err(e);
+    }
+
+    info!("MCP server shut down gracefully");
+    Ok(())
+}
+
+/// Load configuration, build the server and start its background services
+/// (webhook receiver, metrics exporter, scheduler, config watcher)
+async fn start_server(cassette: Option<Arc<Cassette>>) -> Result<(Arc<McpServer>, Option<RecommendedWatcher>)> {
+    let replaying = cassette.as_ref().is_some_and(|c| c.is_replay());
+    info!("Starting OneLogin MCP Server v{}", env!("CARGO_PKG_VERSION"));
+
     // Load configuration
     info!("Loading configuration...");
     let config = match Config::from_env() {
@@ -50,6 +220,13 @@ async fn run_server() -> Result<()> {
             info!("Configuration loaded from environment variables");
             c
         }
+        // Replays never contact OneLogin, so credentials are optional
+        Err(_) if replaying => {
+            info!("Replaying a cassette without credentials");
+            let mut config = Config::from_env_base().context("Failed to load base configuration")?;
+            config.onelogin_subdomain = "replay".to_string();
+            config
+        }
         Err(env_err) => {
             // If env vars are missing but tenants.json exists, use base config
             match Config::load_tenants_file() {
@@ -87,7 +264,7 @@ async fn run_server() -> Result<()> {
 
     // Create and run MCP server
     info!("Initializing MCP server...");
-    let server = McpServer::new(config).await.context(
+    let server = McpServer::new(config, cassette).await.context(
         "Failed to initialize MCP server.\n\
          \n\
          This could be due to:\n\
@@ -98,28 +275,14 @@ async fn run_server() -> Result<()> {
          Check the detailed error message above for more information."
     )?;
     info!("MCP server initialized successfully");
+    let server = Arc::new(with_webhook_receiver(server).await?);
+    server.start_metrics_exporter().await.context("Failed to start metrics exporter")?;
+    server.start_admin_api().await.context("Failed to start admin API")?;
+    server.start_scheduler();
 
     // Start hot reload watcher if enabled in config
-    // Note: _watcher must be kept alive for the duration of the server
-    let _watcher = server.start_config_watcher().context(
+    let watcher = server.start_config_watcher().context(
         "Failed to start configuration file watcher for hot reload"
     )?;
-
-    info!("Starting MCP server main loop...");
-    if let Err(e) = server.run().await {
-        error!(
-            "MCP SERVER ERROR\n\
-             \n\
-             The MCP server encountered a fatal error and will shut down:\n\
-             {:#}\n\
-             \n\
-             Full error chain is shown above.",
-            e
-        );
-
-        return Err(e);
-    }
-
-    info!("MCP server shut down gracefully");
-    Ok(())
+    Ok((server, watcher))
 }
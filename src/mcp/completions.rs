@@ -0,0 +1,151 @@
+//! Argument completion (`completion/complete`).
+//!
+//! App rule arguments must use values from per-app catalogs (condition sources,
+//! operators, condition and action values), which are easy to get wrong when
+//! typed freely. Besides the standard `ref/prompt` and `ref/resource`
+//! references, completion accepts `{"type": "ref/tool", "name": <tool>}` for the
+//! app rule tools and suggests catalog values. `app_id` and, for operators and
+//! values, the chosen `source` or `action` are read from `context.arguments`.
+//! Nested rule fields are named like `conditions.source` or `actions.value`.
+
+use crate::api::OneLoginClient;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use tracing::debug;
+
+/// Most values returned in one completion, as the MCP spec allows
+const MAX_VALUES: usize = 100;
+
+/// Tools whose arguments are completed from app rule catalogs
+const APP_RULE_TOOLS: &[&str] = &[
+    "onelogin_create_app_rule",
+    "onelogin_update_app_rule",
+    "onelogin_list_condition_operators",
+    "onelogin_list_condition_values",
+    "onelogin_list_action_values",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Catalog {
+    Conditions,
+    Operators,
+    ConditionValues,
+    Actions,
+    ActionValues,
+}
+
+pub fn completes_tool(tool: &str) -> bool {
+    APP_RULE_TOOLS.contains(&tool)
+}
+
+/// Catalog an argument's values come from, by field name
+fn catalog_for(argument: &str) -> Option<Catalog> {
+    let (parent, field) = match argument.rsplit_once('.') {
+        Some((parent, field)) => (parent, field),
+        None => ("", argument),
+    };
+    match field {
+        "source" | "condition_value" => Some(Catalog::Conditions),
+        "operator" => Some(Catalog::Operators),
+        "action" | "action_value" => Some(Catalog::Actions),
+        "value" if parent.starts_with("actions") => Some(Catalog::ActionValues),
+        "value" => Some(Catalog::ConditionValues),
+        _ => None,
+    }
+}
+
+/// First string among `keys` in the completion context
+fn context_str<'a>(context: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| context.get(*key).and_then(Value::as_str)).filter(|s| !s.is_empty())
+}
+
+/// Catalog entries (`value`, `name`) matching what the user typed: prefix matches on the
+/// value first, then any value or name containing it
+fn rank(entries: Vec<(String, String)>, typed: &str) -> Vec<String> {
+    let typed = typed.to_lowercase();
+    let (mut prefixed, mut rest): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .filter(|(value, name)| value.to_lowercase().contains(&typed) || name.to_lowercase().contains(&typed))
+        .partition(|(value, _)| value.to_lowercase().starts_with(&typed));
+    prefixed.append(&mut rest);
+    let mut values: Vec<String> = Vec::new();
+    for (value, _) in prefixed {
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// Suggested values for `argument` of an app rule tool, or `None` if the argument has
+/// no catalog or the context lacks what the catalog needs
+pub async fn complete_app_rule_argument(
+    client: &OneLoginClient,
+    argument: &str,
+    typed: &str,
+    context: &Map<String, Value>,
+) -> Result<Option<Vec<String>>> {
+    let Some(catalog) = catalog_for(argument) else {
+        return Ok(None);
+    };
+    let Some(app_id) = context.get("app_id").and_then(crate::mcp::tools::value_as_i64) else {
+        debug!("No app_id in completion context for {}", argument);
+        return Ok(None);
+    };
+    let source = context_str(context, &["source", "conditions.source", "condition_value"]);
+    let action = context_str(context, &["action", "actions.action", "action_value"]);
+    let entries: Vec<(String, String)> = match (catalog, source, action) {
+        (Catalog::Conditions, _, _) => client.app_rules.list_conditions(app_id).await?
+            .into_iter().map(|c| (c.value, c.name)).collect(),
+        (Catalog::Actions, _, _) => client.app_rules.list_actions(app_id).await?
+            .into_iter().map(|a| (a.value, a.name)).collect(),
+        (Catalog::Operators, Some(source), _) => client.app_rules.list_condition_operators(app_id, source).await?
+            .into_iter().map(|o| (o.value, o.name)).collect(),
+        (Catalog::ConditionValues, Some(source), _) => client.app_rules.list_condition_values(app_id, source).await?
+            .into_iter().map(|v| (v.value, v.name)).collect(),
+        (Catalog::ActionValues, _, Some(action)) => client.app_rules.list_action_values(app_id, action).await?
+            .into_iter().map(|v| (v.value, v.name)).collect(),
+        _ => return Ok(None),
+    };
+    Ok(Some(rank(entries, typed)))
+}
+
+/// `completion/complete` result for a list of suggestions
+pub fn completion_result(values: Vec<String>) -> Value {
+    let total = values.len();
+    json!({
+        "completion": {
+            "values": values.into_iter().take(MAX_VALUES).collect::<Vec<_>>(),
+            "total": total,
+            "hasMore": total > MAX_VALUES
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_for_argument_names() {
+        assert_eq!(catalog_for("conditions.source"), Some(Catalog::Conditions));
+        assert_eq!(catalog_for("condition_value"), Some(Catalog::Conditions));
+        assert_eq!(catalog_for("conditions[0].operator"), Some(Catalog::Operators));
+        assert_eq!(catalog_for("value"), Some(Catalog::ConditionValues));
+        assert_eq!(catalog_for("actions.value"), Some(Catalog::ActionValues));
+        assert_eq!(catalog_for("action_value"), Some(Catalog::Actions));
+        assert_eq!(catalog_for("name"), None);
+    }
+
+    #[test]
+    fn test_rank_prefers_prefix_matches() {
+        let entries = vec![
+            ("has_role".to_string(), "Roles".to_string()),
+            ("member_of".to_string(), "MemberOf".to_string()),
+            ("role_id".to_string(), "Role".to_string()),
+        ];
+        assert_eq!(rank(entries.clone(), "ROLE"), vec!["role_id", "has_role"]);
+        assert_eq!(rank(entries, ""), vec!["has_role", "member_of", "role_id"]);
+        assert_eq!(completion_result(vec!["a".to_string()])["completion"]["hasMore"], false);
+    }
+}
@@ -0,0 +1,122 @@
+//! Field-level before/after diffs for update tools.
+//!
+//! Each update tool is paired with the get tool that reads the same resource.
+//! The registry fetches the resource before and after the update and reports
+//! what changed, so callers (and the audit log) see the effect of the call
+//! rather than just the request that was sent.
+
+use serde_json::{json, Map, Value};
+
+/// An update tool and the get tool used to snapshot the resource it changes
+pub(crate) struct DiffSource {
+    pub update_tool: &'static str,
+    pub get_tool: &'static str,
+    /// Arguments copied from the update call to the get call
+    pub key_args: &'static [&'static str],
+}
+
+pub(crate) const UPDATE_DIFF_SOURCES: &[DiffSource] = &[
+    DiffSource { update_tool: "onelogin_update_user", get_tool: "onelogin_get_user", key_args: &["user_id"] },
+    DiffSource { update_tool: "onelogin_update_app", get_tool: "onelogin_get_app", key_args: &["app_id"] },
+    DiffSource { update_tool: "onelogin_update_app_rule", get_tool: "onelogin_get_app_rule", key_args: &["app_id", "rule_id"] },
+    DiffSource { update_tool: "onelogin_update_role", get_tool: "onelogin_get_role", key_args: &["role_id"] },
+    DiffSource { update_tool: "onelogin_update_group", get_tool: "onelogin_get_group", key_args: &["group_id"] },
+    DiffSource { update_tool: "onelogin_update_smart_hook", get_tool: "onelogin_get_smart_hook", key_args: &["hook_id"] },
+    DiffSource { update_tool: "onelogin_update_hook_env_var", get_tool: "onelogin_get_hook_env_var", key_args: &["env_var_id"] },
+    DiffSource { update_tool: "onelogin_update_privilege", get_tool: "onelogin_get_privilege", key_args: &["privilege_id"] },
+    DiffSource { update_tool: "onelogin_update_user_mapping", get_tool: "onelogin_get_user_mapping", key_args: &["mapping_id"] },
+    DiffSource { update_tool: "onelogin_update_message_template", get_tool: "onelogin_get_message_template", key_args: &["brand_id", "template_id"] },
+    DiffSource { update_tool: "onelogin_update_template_by_locale", get_tool: "onelogin_get_template_by_locale", key_args: &["brand_id", "template_type", "locale"] },
+    DiffSource { update_tool: "onelogin_update_self_registration_profile", get_tool: "onelogin_get_self_registration_profile", key_args: &["profile_id"] },
+    DiffSource { update_tool: "onelogin_update_api_authorization", get_tool: "onelogin_get_api_authorization", key_args: &["auth_id"] },
+    DiffSource { update_tool: "onelogin_update_directory_connector", get_tool: "onelogin_get_directory_connector", key_args: &["connector_id"] },
+    DiffSource { update_tool: "onelogin_update_branding_settings", get_tool: "onelogin_get_branding_settings", key_args: &[] },
+    DiffSource { update_tool: "onelogin_update_account_settings", get_tool: "onelogin_get_account_settings", key_args: &[] },
+    DiffSource { update_tool: "onelogin_update_device", get_tool: "onelogin_get_device", key_args: &["device_id"] },
+    DiffSource { update_tool: "onelogin_update_login_page", get_tool: "onelogin_get_login_page", key_args: &["page_id"] },
+    DiffSource { update_tool: "onelogin_update_trusted_idp", get_tool: "onelogin_get_trusted_idp", key_args: &["idp_id"] },
+    DiffSource { update_tool: "onelogin_update_trusted_idp_metadata", get_tool: "onelogin_get_trusted_idp_metadata", key_args: &["idp_id"] },
+    DiffSource { update_tool: "onelogin_update_password_policy", get_tool: "onelogin_get_password_policy", key_args: &["policy_id"] },
+];
+
+pub(crate) fn diff_source(update_tool: &str) -> Option<&'static DiffSource> {
+    UPDATE_DIFF_SOURCES.iter().find(|s| s.update_tool == update_tool)
+}
+
+impl DiffSource {
+    /// Arguments for the get call: the key arguments plus the tenant selector
+    pub fn get_args(&self, update_args: &Value) -> Value {
+        let mut args = Map::new();
+        for key in self.key_args.iter().copied().chain(["tenant"]) {
+            if let Some(value) = update_args.get(key) {
+                args.insert(key.to_string(), value.clone());
+            }
+        }
+        Value::Object(args)
+    }
+}
+
+/// Field-level changes between two snapshots as `[{field, before, after}]`.
+///
+/// Objects are compared key by key using dotted paths; arrays and scalars are
+/// compared as whole values. Fields missing on one side are reported as `null`.
+pub(crate) fn diff_values(before: &Value, after: &Value) -> Vec<Value> {
+    let mut changes = Vec::new();
+    collect_changes("", before, after, &mut changes);
+    changes
+}
+
+fn collect_changes(path: &str, before: &Value, after: &Value, changes: &mut Vec<Value>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let field = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                collect_changes(
+                    &field,
+                    old.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if before != after => changes.push(json!({
+            "field": if path.is_empty() { "(value)" } else { path },
+            "before": before,
+            "after": after
+        })),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports_changed_added_and_removed_fields() {
+        let before = json!({"id": 1, "firstname": "Ann", "custom_attributes": {"dept": "IT"}, "phone": "123"});
+        let after = json!({"id": 1, "firstname": "Anna", "custom_attributes": {"dept": "HR", "site": "NYC"}});
+        let changes = diff_values(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                json!({"field": "custom_attributes.dept", "before": "IT", "after": "HR"}),
+                json!({"field": "custom_attributes.site", "before": null, "after": "NYC"}),
+                json!({"field": "firstname", "before": "Ann", "after": "Anna"}),
+                json!({"field": "phone", "before": "123", "after": null}),
+            ]
+        );
+        assert!(diff_values(&before, &before).is_empty());
+        assert_eq!(diff_values(&json!("a"), &json!("b"))[0]["field"], "(value)");
+    }
+
+    #[test]
+    fn test_get_args_copy_keys_and_tenant() {
+        let source = diff_source("onelogin_update_app_rule").unwrap();
+        let args = source.get_args(&json!({"app_id": 1, "rule_id": 2, "name": "x", "tenant": "prod"}));
+        assert_eq!(args, json!({"app_id": 1, "rule_id": 2, "tenant": "prod"}));
+    }
+}
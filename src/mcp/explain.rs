@@ -0,0 +1,209 @@
+//! Self-description of tools for `onelogin_explain_tool`.
+//!
+//! Everything here is derived from the tool name, its category and its
+//! definition, so new tools are explained without extra bookkeeping: the verb
+//! gives the read/write classification, the category the API credential scope
+//! and the input schema the example arguments.
+
+use super::tools::WRITE_TOOL_VERBS;
+use serde_json::{json, Map, Value};
+
+/// Most related tools listed for one tool
+const MAX_RELATED: usize = 12;
+
+/// Verbs whose tools delete data or take access away
+const DESTRUCTIVE_VERBS: &[&str] = &["delete", "remove", "revoke", "logout", "lock"];
+
+/// Verbs outside `WRITE_TOOL_VERBS` whose tools still change state in OneLogin:
+/// they mint tokens, send messages, consume one-time codes or apply decisions
+const SIDE_EFFECT_VERBS: &[&str] = &["generate", "send", "sync", "verify", "moderate", "build"];
+
+/// Tools answered by this server alone, without a OneLogin API call
+const LOCAL_TOOLS: &[&str] = &[
+    "onelogin_list_tenants",
+    "onelogin_server_stats",
+    "onelogin_list_jobs",
+    "onelogin_get_job_results",
+    "onelogin_list_role_snapshots",
+    "onelogin_diff_role_snapshots",
+    "onelogin_explain_tool",
+    "onelogin_set_tool_categories",
+    "onelogin_get_result_page",
+];
+
+/// Tools that call OneLogin with credentials passed as arguments, not the tenant's
+const OWN_CREDENTIAL_TOOLS: &[&str] = &["onelogin_validate_tenant_credentials", "onelogin_add_tenant"];
+
+/// Categories whose tools only touch users, covered by the user-level API scopes
+const USER_SCOPED_CATEGORIES: &[&str] = &["users", "invitations", "custom_attributes"];
+
+fn verb(tool: &str) -> &str {
+    tool.trim_start_matches("onelogin_").split('_').next().unwrap_or_default()
+}
+
+/// "read_only", "write" or "destructive"
+pub fn classify(tool: &str) -> &'static str {
+    let verb = verb(tool);
+    if DESTRUCTIVE_VERBS.contains(&verb) {
+        "destructive"
+    } else if WRITE_TOOL_VERBS.contains(&verb) || SIDE_EFFECT_VERBS.contains(&verb) || tool == "onelogin_run_job" {
+        "write"
+    } else {
+        "read_only"
+    }
+}
+
+pub fn is_local(tool: &str) -> bool {
+    LOCAL_TOOLS.contains(&tool)
+}
+
+/// Least OneLogin API credential scope the tool needs. Scopes are cumulative:
+/// Read users < Read All < Manage users < Manage All.
+pub fn minimum_api_scope(tool: &str, category: Option<&str>) -> &'static str {
+    if is_local(tool) {
+        return "none (no OneLogin API call)";
+    }
+    if OWN_CREDENTIAL_TOOLS.contains(&tool) {
+        return "none (uses the credentials passed as arguments)";
+    }
+    if tool == "onelogin_run_job" {
+        return "that of the job's tool";
+    }
+    let writes = classify(tool) != "read_only";
+    match category {
+        Some("login") => "Authentication Only",
+        Some(category) if USER_SCOPED_CATEGORIES.contains(&category) => {
+            if writes {
+                "Manage users"
+            } else {
+                "Read users"
+            }
+        }
+        _ if writes => "Manage All",
+        _ => "Read All",
+    }
+}
+
+/// Arguments object filling every required property of `schema` with a placeholder
+/// of the right type: the first enum value, a sample id, email, URL or date by name
+pub fn example_arguments(schema: &Value) -> Value {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let mut example = Map::new();
+    for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+        let property = properties.and_then(|p| p.get(name)).unwrap_or(&Value::Null);
+        example.insert(name.to_string(), example_value(name, property));
+    }
+    Value::Object(example)
+}
+
+fn example_value(name: &str, property: &Value) -> Value {
+    if let Some(first) = property.get("enum").and_then(Value::as_array).and_then(|e| e.first()) {
+        return first.clone();
+    }
+    let kind = match property.get("type") {
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|t| *t != "null").unwrap_or("string"),
+        Some(kind) => kind.as_str().unwrap_or("string"),
+        None => "string",
+    };
+    match kind {
+        "integer" => json!(12345),
+        "number" => json!(1),
+        "boolean" => json!(true),
+        "array" => {
+            let item = property.get("items").map(|i| example_value(name.trim_end_matches('s'), i));
+            Value::Array(item.into_iter().collect())
+        }
+        "object" => json!({}),
+        _ if name.ends_with("email") => json!("user@example.com"),
+        _ if name.ends_with("url") || name.ends_with("uri") => json!("https://example.com"),
+        _ if name.ends_with("_id") || name == "id" => json!("12345"),
+        _ if ["since", "until", "date", "_at"].iter().any(|s| name.ends_with(s)) => json!("2024-01-01T00:00:00Z"),
+        _ => json!(format!("<{}>", name)),
+    }
+}
+
+/// What a tool acts on: its name without the prefix and verb, each word singular
+fn resource(tool: &str) -> String {
+    tool.trim_start_matches("onelogin_")
+        .split('_')
+        .skip(1)
+        .map(|word| match word.strip_suffix('s') {
+            Some(singular) if !word.ends_with("ss") && !singular.is_empty() => singular,
+            _ => word,
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Tools named in `description`, then tools of the same category acting on the same resource
+pub fn related_tools<'a>(tool: &str, description: &str, category_tools: &[&'a str], catalog: &[&'a str]) -> Vec<&'a str> {
+    let mut related: Vec<&str> = Vec::new();
+    for word in description.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')) {
+        if let Some(name) = catalog.iter().find(|name| **name == word && **name != tool) {
+            if !related.contains(name) {
+                related.push(*name);
+            }
+        }
+    }
+    let own = resource(tool);
+    for name in category_tools {
+        let other = resource(name);
+        let same_resource = !own.is_empty() && !other.is_empty() && (own.contains(&other) || other.contains(&own));
+        if *name != tool && same_resource && !related.contains(name) {
+            related.push(*name);
+        }
+    }
+    related.truncate(MAX_RELATED);
+    related
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_and_scope() {
+        assert_eq!(classify("onelogin_delete_user"), "destructive");
+        assert_eq!(classify("onelogin_update_user"), "write");
+        assert_eq!(classify("onelogin_generate_mfa_token"), "write");
+        assert_eq!(classify("onelogin_list_users"), "read_only");
+        assert_eq!(minimum_api_scope("onelogin_list_users", Some("users")), "Read users");
+        assert_eq!(minimum_api_scope("onelogin_update_user", Some("users")), "Manage users");
+        assert_eq!(minimum_api_scope("onelogin_delete_app", Some("apps")), "Manage All");
+        assert_eq!(minimum_api_scope("onelogin_list_apps", Some("apps")), "Read All");
+        assert!(minimum_api_scope("onelogin_server_stats", Some("tenant_management")).starts_with("none"));
+    }
+
+    #[test]
+    fn test_example_arguments() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "user_id": {"type": "integer"},
+                "email": {"type": "string"},
+                "status": {"type": "string", "enum": ["approved", "rejected"]},
+                "role_ids": {"type": "array", "items": {"type": "integer"}},
+                "note": {"type": ["string", "null"]},
+                "limit": {"type": "integer"}
+            },
+            "required": ["user_id", "email", "status", "role_ids", "note"]
+        });
+        assert_eq!(
+            example_arguments(&schema),
+            json!({"user_id": 12345, "email": "user@example.com", "status": "approved", "role_ids": [12345], "note": "<note>"})
+        );
+        assert_eq!(example_arguments(&json!({"properties": {}})), json!({}));
+    }
+
+    #[test]
+    fn test_related_tools() {
+        let catalog = ["onelogin_get_user", "onelogin_list_users", "onelogin_delete_user", "onelogin_get_user_apps", "onelogin_list_apps"];
+        let related = related_tools(
+            "onelogin_get_user",
+            "Use onelogin_list_users to find user IDs first.",
+            &catalog[..4],
+            &catalog,
+        );
+        assert_eq!(related, vec!["onelogin_list_users", "onelogin_delete_user", "onelogin_get_user_apps"]);
+    }
+}
@@ -1,2 +1,13 @@
+pub mod completions;
+pub mod diff;
+pub mod explain;
+pub mod outcome;
+pub mod prompts;
+pub mod providers;
+pub mod resources;
+pub mod schema;
 pub mod server;
+pub mod token_budget;
 pub mod tools;
+pub mod websocket;
+pub mod writer;
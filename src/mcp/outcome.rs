@@ -0,0 +1,125 @@
+//! Result envelope shared by bulk and composite tools.
+//!
+//! Every tool that applies several changes in one call reports them with the
+//! same fields, so an agent can resume or compensate after a mixed outcome
+//! without knowing the tool:
+//!
+//! - `complete`: every item was applied and none was undone
+//! - `succeeded`: items applied and still in effect
+//! - `failed`: items attempted that failed, each with a `reason`
+//! - `not_attempted`: items skipped because an earlier failure stopped the run;
+//!   retrying exactly these (plus `failed`) resumes the operation
+//! - `rollback_performed`: whether applied items were undone after a failure,
+//!   with `rolled_back` and `rollback_failed` (with reasons) listing them
+//!
+//! Items are small objects naming what was changed, e.g. `{"user_id": 7}`.
+//! Tool-specific fields stay next to the envelope. Dry runs apply nothing and
+//! carry no envelope.
+
+use serde_json::{json, Value};
+use std::fmt::Display;
+
+#[derive(Debug, Default)]
+pub struct Outcome {
+    succeeded: Vec<Value>,
+    failed: Vec<Value>,
+    not_attempted: Vec<Value>,
+    rolled_back: Vec<Value>,
+    rollback_failed: Vec<Value>,
+}
+
+/// `item` with a `reason` field, wrapping items that aren't objects
+fn with_reason(item: Value, reason: impl Display) -> Value {
+    match item {
+        Value::Object(mut fields) => {
+            fields.insert("reason".to_string(), json!(reason.to_string()));
+            Value::Object(fields)
+        }
+        other => json!({"item": other, "reason": reason.to_string()}),
+    }
+}
+
+impl Outcome {
+    pub fn succeeded(&mut self, item: Value) {
+        self.succeeded.push(item);
+    }
+
+    pub fn failed(&mut self, item: Value, reason: impl Display) {
+        self.failed.push(with_reason(item, reason));
+    }
+
+    pub fn not_attempted(&mut self, item: Value) {
+        self.not_attempted.push(item);
+    }
+
+    /// An applied item that was undone after a later failure
+    pub fn rolled_back(&mut self, item: Value) {
+        self.rolled_back.push(item);
+    }
+
+    /// An applied item that could not be undone and is still in effect
+    pub fn rollback_failed(&mut self, item: Value, reason: impl Display) {
+        self.rollback_failed.push(with_reason(item, reason));
+    }
+
+    pub fn has_failures(&self) -> bool {
+        !self.failed.is_empty()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty() && self.not_attempted.is_empty() && !self.rollback_performed()
+    }
+
+    pub fn rollback_performed(&self) -> bool {
+        !self.rolled_back.is_empty() || !self.rollback_failed.is_empty()
+    }
+
+    /// Add the envelope fields to the tool's `result` object
+    pub fn merge_into(self, result: &mut Value) {
+        let Some(fields) = result.as_object_mut() else {
+            return;
+        };
+        fields.insert("complete".to_string(), json!(self.is_complete()));
+        fields.insert("rollback_performed".to_string(), json!(self.rollback_performed()));
+        fields.insert("succeeded".to_string(), json!(self.succeeded));
+        fields.insert("failed".to_string(), json!(self.failed));
+        fields.insert("not_attempted".to_string(), json!(self.not_attempted));
+        fields.insert("rolled_back".to_string(), json!(self.rolled_back));
+        fields.insert("rollback_failed".to_string(), json!(self.rollback_failed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope() {
+        let mut outcome = Outcome::default();
+        outcome.succeeded(json!({"user_id": 1}));
+        outcome.failed(json!({"user_id": 2}), "Not found");
+        outcome.not_attempted(json!({"user_id": 3}));
+        assert!(outcome.has_failures());
+        let mut result = json!({"policy_id": 9});
+        outcome.merge_into(&mut result);
+        assert_eq!(result["policy_id"], 9);
+        assert_eq!(result["complete"], false);
+        assert_eq!(result["rollback_performed"], false);
+        assert_eq!(result["succeeded"], json!([{"user_id": 1}]));
+        assert_eq!(result["failed"], json!([{"user_id": 2, "reason": "Not found"}]));
+        assert_eq!(result["not_attempted"], json!([{"user_id": 3}]));
+
+        let mut outcome = Outcome::default();
+        outcome.rolled_back(json!({"role": "Admins"}));
+        outcome.rollback_failed(json!("step"), "HTTP 500");
+        outcome.failed(json!({"role": "Ops"}), "HTTP 422");
+        let mut result = json!({});
+        outcome.merge_into(&mut result);
+        assert_eq!(result["rollback_performed"], true);
+        assert_eq!(result["rollback_failed"], json!([{"item": "step", "reason": "HTTP 500"}]));
+
+        let mut outcome = Outcome::default();
+        outcome.succeeded(json!({"email": "a@example.com"}));
+        assert!(outcome.is_complete());
+    }
+}
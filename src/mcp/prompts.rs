@@ -0,0 +1,398 @@
+//! Prompts served through `prompts/list` and `prompts/get`.
+//!
+//! Each prompt is a curated admin workflow: the steps to follow, the tools to
+//! call for each and where to stop for the user's confirmation. Arguments given
+//! to `prompts/get` are filled into the text, optional ones that are missing
+//! become an instruction to ask the user. Tools a workflow needs that are
+//! disabled in the tool config are named at the end of the text, with their
+//! category, so the agent can ask for them to be enabled instead of failing
+//! half-way through.
+
+use crate::core::tool_config::{ToolConfig, TOOL_CATEGORIES};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+struct PromptArgument {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+struct Prompt {
+    name: &'static str,
+    description: &'static str,
+    arguments: &'static [PromptArgument],
+    render: fn(&PromptArgs) -> String,
+}
+
+/// Arguments of a `prompts/get` request; blank values count as missing
+struct PromptArgs<'a>(&'a Value);
+
+impl PromptArgs<'_> {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// The value in backticks, or `ask` in parentheses when it wasn't given
+    fn or_ask(&self, name: &str, ask: &str) -> String {
+        self.get(name)
+            .map(|v| format!("`{}`", v))
+            .unwrap_or_else(|| format!("({})", ask))
+    }
+}
+
+static PROMPTS: &[Prompt] = &[
+    Prompt {
+        name: "onelogin-usage-guide",
+        description: "Important guidelines for using OneLogin MCP tools effectively",
+        arguments: &[],
+        render: usage_guide,
+    },
+    Prompt {
+        name: "onelogin-onboard-tenant",
+        description: "Step-by-step setup of a new OneLogin tenant: API credentials, validation, tenants.json and tool categories",
+        arguments: &[
+            PromptArgument { name: "tenant_name", description: "Name to give the tenant, e.g. 'prod'", required: false },
+            PromptArgument { name: "subdomain", description: "Tenant subdomain or URL, e.g. 'acme' or https://acme.onelogin.com", required: false },
+            PromptArgument { name: "region", description: "'us' or 'eu'", required: false },
+        ],
+        render: onboard_tenant,
+    },
+    Prompt {
+        name: "onelogin-offboard-employee",
+        description: "Offboard a departing employee: review their access, suspend them, end sessions, remove roles and MFA factors",
+        arguments: &[
+            PromptArgument { name: "user", description: "Email, username or user ID of the employee", required: true },
+            PromptArgument { name: "reason", description: "Why the employee is offboarded, for the summary", required: false },
+            PromptArgument { name: "keep_roles", description: "'true' to leave role assignments in place, e.g. for a later rehire", required: false },
+        ],
+        render: offboard_employee,
+    },
+    Prompt {
+        name: "onelogin-audit-app-access",
+        description: "Audit who can access an app and through which role or rule, flagging inactive and suspended users",
+        arguments: &[
+            PromptArgument { name: "app", description: "App ID or name", required: true },
+            PromptArgument { name: "inactive_days", description: "Days without login after which access is flagged (default 90)", required: false },
+        ],
+        render: audit_app_access,
+    },
+    Prompt {
+        name: "onelogin-investigate-suspicious-login",
+        description: "Investigate a suspicious login: recent events, impossible travel, brute force, devices and MFA, then contain if needed",
+        arguments: &[
+            PromptArgument { name: "user", description: "Email, username or user ID of the affected user", required: true },
+            PromptArgument { name: "hours", description: "How far back to look, in hours (default 72)", required: false },
+            PromptArgument { name: "ip", description: "IP address of the suspicious login, if known", required: false },
+        ],
+        render: investigate_suspicious_login,
+    },
+];
+
+pub struct PromptRegistry {
+    tool_config: Arc<ToolConfig>,
+}
+
+impl PromptRegistry {
+    pub fn new(tool_config: Arc<ToolConfig>) -> Self {
+        Self { tool_config }
+    }
+
+    /// Entries of a `prompts/list` result
+    pub fn list(&self) -> Vec<Value> {
+        PROMPTS
+            .iter()
+            .map(|prompt| {
+                let mut entry = json!({"name": prompt.name, "description": prompt.description});
+                if !prompt.arguments.is_empty() {
+                    entry["arguments"] = prompt
+                        .arguments
+                        .iter()
+                        .map(|a| json!({"name": a.name, "description": a.description, "required": a.required}))
+                        .collect();
+                }
+                entry
+            })
+            .collect()
+    }
+
+    /// Result of `prompts/get`; the error is for an unknown prompt or a missing required argument
+    pub fn get(&self, name: &str, arguments: &Value) -> Result<Value, String> {
+        let prompt = PROMPTS
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| format!("Prompt not found: {}", name))?;
+        let args = PromptArgs(arguments);
+        if let Some(missing) = prompt.arguments.iter().find(|a| a.required && args.get(a.name).is_none()) {
+            return Err(format!("Missing required argument '{}' for prompt {}", missing.name, name));
+        }
+
+        let mut text = (prompt.render)(&args);
+        let disabled: Vec<String> = referenced_tools(&text)
+            .into_iter()
+            .filter(|tool| !self.tool_config.is_tool_enabled(tool))
+            .map(|tool| match category_of(tool) {
+                Some(category) => format!("- `{}` (category `{}`)", tool, category),
+                None => format!("- `{}`", tool),
+            })
+            .collect();
+        if !disabled.is_empty() {
+            text.push_str(&format!(
+                "\n\n## Tools to enable first\nThese tools are disabled on this server. Before starting, ask the user to enable their categories in the tool config or with `onelogin_set_tool_categories`, or skip the steps that use them:\n{}",
+                disabled.join("\n")
+            ));
+        }
+
+        Ok(json!({
+            "description": prompt.description,
+            "messages": [{
+                "role": "user",
+                "content": {"type": "text", "text": self.tool_config.rewrite_tool_references(&text)}
+            }]
+        }))
+    }
+}
+
+/// Canonical tool names mentioned in `text`, in order of first mention
+fn referenced_tools(text: &str) -> Vec<&str> {
+    let mut tools: Vec<&str> = Vec::new();
+    for (start, _) in text.match_indices("onelogin_") {
+        let end = text[start..]
+            .find(|c: char| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+            .map_or(text.len(), |len| start + len);
+        let tool = &text[start..end];
+        if !tools.contains(&tool) {
+            tools.push(tool);
+        }
+    }
+    tools
+}
+
+fn category_of(tool: &str) -> Option<&'static str> {
+    TOOL_CATEGORIES.iter().find(|c| c.tools.contains(&tool)).map(|c| c.name)
+}
+
+fn usage_guide(_: &PromptArgs) -> String {
+    r#"# OneLogin MCP Server Usage Guide
+
+## IMPORTANT: Always Read Tool Descriptions
+Before using any tool, carefully read its description. The descriptions contain critical information about:
+- Required vs optional parameters
+- Valid enum values and their meanings
+- Which tools to use together (e.g., unlock vs update_user)
+- Limitations and edge cases
+
+## Key User Status Values
+- 0 = Unactivated (never logged in)
+- 1 = Active (can log in)
+- 2 = Suspended (admin disabled)
+- 3 = Locked (too many failed attempts OR manually locked)
+- 4 = Password expired
+- 5 = Awaiting password reset
+
+## Key User State Values
+- 0 = Unapproved (pending approval)
+- 1 = Approved (licensed, normal user)
+- 2 = Rejected
+- 3 = Unlicensed
+
+## Lock/Unlock Behavior
+- `onelogin_lock_user`: Locks user for specified duration. To unlock early, use `onelogin_update_user` with status=1
+- `onelogin_unlock_user`: ONLY works for users locked due to failed login attempts, NOT for manually locked users
+
+## Role Management
+- Use `onelogin_assign_roles` and `onelogin_remove_roles` instead of update_user for role changes
+- These are additive/subtractive operations, not replacements
+
+## Error Handling
+- 403 errors on Account Owner users are expected - you cannot lock/modify the Account Owner
+- Always check error messages for specific guidance
+
+## Best Practices
+1. Use `onelogin_list_users` with filters (email, username) to find users before operations
+2. Verify user status after lock/unlock operations
+3. Use `onelogin_get_user` to confirm changes were applied"#
+        .to_string()
+}
+
+fn onboard_tenant(args: &PromptArgs) -> String {
+    let tenant_name = args.or_ask("tenant_name", "ask the user; e.g. `prod`");
+    let subdomain = args.or_ask("subdomain", "ask the user; the part before .onelogin.com");
+    let region = args.or_ask("region", "ask the user; `eu` if the admin URL ends in .eu.onelogin.com, else `us`");
+    format!(
+        r#"# Onboard a OneLogin tenant
+
+Guide the user through adding a tenant to this server. Do one step at a time and confirm each before moving on.
+
+- Tenant name: {tenant_name}
+- Subdomain: {subdomain}
+- Region: {region}
+
+## 1. Create API credentials in OneLogin
+Ask the user to sign in to the OneLogin admin portal as an account owner or administrator, open Developers > API Credentials, click New Credential, give it a name such as "MCP server" and choose a scope:
+- Read users: user lookups only
+- Read All: reports and audits without changes
+- Manage users: user changes
+- Manage All: every tool of this server
+Recommend the least scope that covers what the user plans to do; `onelogin_explain_tool` shows the scope each tool needs. The user then copies the client ID and client secret.
+
+## 2. Validate the credentials
+Call `onelogin_validate_tenant_credentials` with the subdomain, region, client_id and client_secret. If `valid` is false, explain the error and go back to step 1. Report which scopes were confirmed. Never repeat the client secret back to the user.
+
+## 3. Choose tool categories
+Explain that tools are grouped into categories and that specialized ones are disabled by default. Ask which areas the user needs (e.g. compliance reports, MFA, SAML, risk) and call `onelogin_set_tool_categories` with the categories to enable or disable. Skip this step if the defaults are fine.
+
+## 4. Save the tenant
+Call `onelogin_add_tenant` with the tenant name and the validated credentials. Set `default` to true if it should be used when no tenant is named. Set `replace` only after the user confirms overwriting an existing tenant of the same name.
+
+## 5. Restart and verify
+Tell the user to restart the MCP server so it loads the new tenant, then call `onelogin_list_tenants` and confirm the tenant is listed with the right subdomain and region."#
+    )
+}
+
+fn offboard_employee(args: &PromptArgs) -> String {
+    let user = args.or_ask("user", "ask the user");
+    let reason = args.or_ask("reason", "not given; ask the user if it should be recorded");
+    let roles_step = if args.get("keep_roles") == Some("true") {
+        "Keep the role assignments: the user asked for them to stay in place. List them in the summary so they can be removed later."
+    } else {
+        "Call `onelogin_remove_roles` with the user ID and every role ID from step 2. If some roles must stay (e.g. for a rehire), ask the user which before calling."
+    };
+    format!(
+        r#"# Offboard an employee
+
+Remove the access of a departing employee. Steps 1-2 only read; before step 3 show the user what will change and wait for a clear confirmation. Never delete the user: a suspended user keeps the audit trail and can be restored.
+
+- Employee: {user}
+- Reason: {reason}
+
+## 1. Find the user
+Look the user up with `onelogin_list_users` (filter by email or username) or, given an ID, `onelogin_get_user`. If several users match, ask which one is meant. Stop if the user is the account owner: OneLogin refuses changes to it.
+
+## 2. Review their access
+Call `onelogin_get_user_roles` and `onelogin_get_user_apps` and show the roles and apps the user has. Note any admin or otherwise privileged roles.
+
+## 3. Suspend and end sessions
+Call `onelogin_transition_user_status` with the user ID, `to` = "suspended", `revoke_sessions` = true and `dry_run` = true, and show the plan. After the user confirms, call it again without `dry_run`. If the transition tool is not available, call `onelogin_update_user` with status=2 and then `onelogin_logout_user`.
+
+## 4. Remove roles
+{roles_step}
+
+## 5. Remove MFA factors
+Call `onelogin_list_mfa_factors` and, after confirmation, `onelogin_remove_mfa_factor` for each enrolled device so a recovered phone or token can't be used.
+
+## 6. Verify and summarize
+Call `onelogin_get_user` and confirm the status is 2 (suspended). Summarize what was changed, the roles and apps removed, and anything left for the user to do outside OneLogin (e.g. accounts in apps without provisioning)."#
+    )
+}
+
+fn audit_app_access(args: &PromptArgs) -> String {
+    let app = args.or_ask("app", "ask the user");
+    let inactive_days = args.get("inactive_days").unwrap_or("90");
+    format!(
+        r#"# Audit access to an app
+
+Find out who can access an app, how they got that access and whether they should still have it. This audit only reads: recommend changes, don't make them.
+
+- App: {app}
+- Inactive after: {inactive_days} days without login
+
+## 1. Identify the app
+Given a name, call `onelogin_list_apps` with the `name` filter; given an ID, call `onelogin_get_app`. If several apps match, ask which one is meant. Note the app's connector and whether it provisions users.
+
+## 2. List who has access
+Call `onelogin_app_access_report` with the `app_id` and `include_direct` = true. It lists the users with access and the role or mapping each one gets it through.
+
+## 3. Flag stale and risky access
+Call `onelogin_inactive_users_report` with `inactive_days` = {inactive_days} and cross-check it with the users from step 2. Also flag users from step 2 whose status is not 1 (active), and users with direct assignments that bypass roles. Call `onelogin_elevated_access_report` if the app is sensitive and the user wants admins called out.
+
+## 4. Report
+Give counts first: users with access, by role and by direct assignment. Then list the flagged users with the reason for each flag and a recommendation: remove the role, remove the direct assignment, or suspend the user. Offer to carry out the recommendations with `onelogin_remove_roles`, but only after the user confirms each one."#
+    )
+}
+
+fn investigate_suspicious_login(args: &PromptArgs) -> String {
+    let user = args.or_ask("user", "ask the user");
+    let hours = args.get("hours").unwrap_or("72");
+    let ip = args.or_ask("ip", "not known; look for unfamiliar IPs in the events");
+    format!(
+        r#"# Investigate a suspicious login
+
+Work out whether a login was the real user or an attacker, then contain it if needed. Steps 1-5 only read; take no containment action before the user confirms it.
+
+- User: {user}
+- Look back: {hours} hours
+- Suspicious IP: {ip}
+
+## 1. Find the user
+Look the user up with `onelogin_list_users` (filter by email or username) or, given an ID, `onelogin_get_user`. Note the current status, `last_login` and `locked_until`.
+
+## 2. Review recent events
+Call `onelogin_list_events` with the `user_id` and `since` set {hours} hours back. Build a timeline of logins, failed logins, MFA challenges, password changes and factor enrollments, with IP and location. Mark events from the suspicious IP.
+
+## 3. Check for impossible travel
+Call `onelogin_detect_impossible_travel` with `user_ids` = [the user ID] and `hours` = {hours}. Report any pair of logins too far apart to travel between.
+
+## 4. Check for brute force
+Call `onelogin_detect_brute_force` with `hours` = {hours} and look for the user or the suspicious IP among the results; a burst of failures from one IP followed by a success is the strongest signal.
+
+## 5. Review devices and MFA
+Call `onelogin_list_user_devices` and `onelogin_list_mfa_factors`. Flag factors enrolled during the window of the suspicious login: attackers add their own device to keep access.
+
+## 6. Conclude and contain
+State whether the login looks legitimate, suspicious or compromised, citing the events behind the verdict. If compromised, propose containment and carry out each step only after the user confirms it:
+- End all sessions with `onelogin_logout_user`
+- Lock the user with `onelogin_transition_user_status` (`to` = "locked", `revoke_sessions` = true), running it with `dry_run` = true first
+- Remove factors enrolled by the attacker with `onelogin_remove_mfa_factor`
+Then recommend a password reset and a follow-up review of the apps the user accessed (`onelogin_get_user_apps`)."#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> PromptRegistry {
+        PromptRegistry::new(Arc::new(ToolConfig::load(None).unwrap()))
+    }
+
+    fn text(result: &Value) -> &str {
+        result["messages"][0]["content"]["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_prompts_reference_existing_tools() {
+        for prompt in PROMPTS {
+            let args = json!({"user": "jane@example.com", "app": "Slack"});
+            let rendered = (prompt.render)(&PromptArgs(&args));
+            for tool in referenced_tools(&rendered) {
+                assert!(category_of(tool).is_some(), "{} references unknown tool {}", prompt.name, tool);
+            }
+        }
+        assert_eq!(registry().list().len(), PROMPTS.len());
+    }
+
+    #[test]
+    fn test_get_prompt() {
+        let registry = registry();
+        let result = registry
+            .get("onelogin-investigate-suspicious-login", &json!({"user": " jane@example.com ", "hours": "24"}))
+            .unwrap();
+        assert!(text(&result).contains("- User: `jane@example.com`"));
+        assert!(text(&result).contains("`hours` = 24"));
+        assert!(text(&result).contains("not known; look for unfamiliar IPs"));
+        // Risk and MFA tools are disabled by default
+        assert!(text(&result).contains("## Tools to enable first"));
+        assert!(text(&result).contains("`onelogin_list_mfa_factors` (category `mfa`)"));
+
+        let guide = registry.get("onelogin-usage-guide", &Value::Null).unwrap();
+        assert!(!text(&guide).contains("Tools to enable first"));
+
+        assert!(registry.get("onelogin-offboard-employee", &json!({"user": ""})).unwrap_err().contains("'user'"));
+        assert!(registry.get("onelogin-unknown", &json!({})).is_err());
+    }
+}
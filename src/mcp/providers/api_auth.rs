@@ -0,0 +1,242 @@
+use crate::api::OneLoginClient;
+use crate::models::api_auth::ApiAuthConfig;
+use crate::utils::jwt;
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Difference in seconds tolerated between the token lifetime and the configured one
+const LIFETIME_TOLERANCE_SECONDS: i64 = 60;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct TestApiAuthTokenArgs {
+    /// The API authorization server ID
+    pub auth_server_id: i64,
+    /// Client ID of an OIDC app granted access to the API
+    pub client_id: String,
+    /// Client secret of that app. Used for the token request only and never returned
+    pub client_secret: String,
+    /// Scopes to request. Default: every scope configured on the server
+    pub scopes: Option<Vec<String>>,
+    /// Claims the token must carry. Default: the server's configured claims, which are only reported when absent because user attribute claims can't be filled for a client without a user
+    pub expected_claims: Option<Vec<String>>,
+}
+
+tool_provider! {
+    /// End-to-end checks of API authorization servers
+    pub struct ApiAuthProvider("api_auth") {
+        "onelogin_test_api_auth_token" => test_api_auth_token(TestApiAuthTokenArgs):
+            "Run a client-credentials flow against an API authorization server with a test client and check the issued token against the server's configuration: that a token is issued, its signature (against the tenant JWKS), audience, granted scopes, lifetime and configured claims. Returns a pass/warn/fail/skipped status per check, 'passed' (no failed check), the token's response metadata and decoded claims. The access token itself is never returned.",
+    }
+}
+
+impl ApiAuthProvider {
+    async fn test_api_auth_token(&self, client: &OneLoginClient, args: TestApiAuthTokenArgs) -> Result<Value> {
+        let server = client.api_auth.get_api_authorization(&args.auth_server_id.to_string()).await
+            .map_err(|e| anyhow!("Failed to get API authorization {}: {}", args.auth_server_id, e))?;
+        let config = &server.configuration;
+        let scopes = args.scopes.clone().unwrap_or_else(|| {
+            config.scopes.iter().flatten().map(|s| s.value.clone()).collect()
+        });
+        let (status, body) = client
+            .api_auth
+            .request_client_credentials_token(&args.client_id, &args.client_secret, &config.resource_identifier, &scopes)
+            .await
+            .map_err(|e| anyhow!("Failed to request a token: {}", e))?;
+        let summary = json!({
+            "id": server.id,
+            "name": server.name,
+            "resource_identifier": config.resource_identifier,
+            "audiences": config.audiences
+        });
+
+        let token = body.get("access_token").and_then(Value::as_str).filter(|_| (200..300).contains(&status));
+        let Some(token) = token else {
+            let detail = match (body.get("error").and_then(Value::as_str), body.get("error_description").and_then(Value::as_str)) {
+                (Some(error), Some(description)) => format!("HTTP {}: {} ({})", status, error, description),
+                (Some(error), None) => format!("HTTP {}: {}", status, error),
+                _ => format!("HTTP {} without an access_token", status),
+            };
+            return Ok(json!({
+                "auth_server": summary,
+                "requested_scopes": scopes,
+                "passed": false,
+                "checks": [check("token_issued", "fail", detail)]
+            }));
+        };
+
+        let claims = jwt::decode_claims(token);
+        let signature = if jwt::looks_like_jwt(token) {
+            let kid = jwt::key_id(token);
+            let keys = match client.oidc.get_well_known_configuration_cached().await {
+                Ok(discovery) => client.oidc.get_jwks_cached(kid.as_deref()).await.map(|jwks| (discovery.issuer, jwks)),
+                Err(e) => Err(e),
+            };
+            match keys {
+                // Only the signature and claims matter here; a token issued moments ago is not revoked
+                Ok((issuer, jwks)) => match jwt::introspect_locally(token, &jwks, &issuer, &config.audiences, false) {
+                    Ok(result) if result["valid"] == json!(true) => check("signature", "pass", "signature, iss, exp, nbf and aud are valid".to_string()),
+                    Ok(result) => check("signature", "fail", result["reason"].as_str().unwrap_or("token is not valid").to_string()),
+                    Err(e) => check("signature", "fail", e),
+                },
+                Err(e) => check("signature", "skipped", format!("Could not fetch the OIDC configuration or JWKS: {}", e)),
+            }
+        } else {
+            check("signature", "skipped", "the token is opaque, not a JWT".to_string())
+        };
+
+        let granted_scope = body
+            .get("scope")
+            .and_then(Value::as_str)
+            .or_else(|| claims.as_ref().and_then(|c| c.get("scope")).and_then(Value::as_str));
+        let mut checks = vec![check("token_issued", "pass", format!("HTTP {}", status)), signature];
+        checks.extend(evaluate_token(
+            config,
+            &scopes,
+            granted_scope,
+            body.get("expires_in").and_then(Value::as_i64),
+            claims.as_ref(),
+            args.expected_claims.as_deref(),
+        ));
+        let passed = checks.iter().all(|c| c["status"] != "fail");
+
+        Ok(json!({
+            "auth_server": summary,
+            "requested_scopes": scopes,
+            "passed": passed,
+            "checks": checks,
+            "token": {
+                "token_type": body.get("token_type"),
+                "expires_in": body.get("expires_in"),
+                "scope": granted_scope,
+                "claims": claims
+            }
+        }))
+    }
+}
+
+fn check(name: &str, status: &str, detail: String) -> Value {
+    json!({"check": name, "status": status, "detail": detail})
+}
+
+/// Compare an issued token with the server configuration: audience, scopes, lifetime
+/// and claims. `claims` is None for opaque tokens, whose claim checks are skipped.
+fn evaluate_token(
+    config: &ApiAuthConfig,
+    requested_scopes: &[String],
+    granted_scope: Option<&str>,
+    expires_in: Option<i64>,
+    claims: Option<&Value>,
+    expected_claims: Option<&[String]>,
+) -> Vec<Value> {
+    let mut checks = Vec::new();
+
+    checks.push(match claims.and_then(|c| c.get("aud")) {
+        None => check("audience", "skipped", "the token carries no aud claim".to_string()),
+        Some(_) if config.audiences.is_empty() => check("audience", "skipped", "the server has no audiences configured".to_string()),
+        Some(aud) => {
+            let token_audiences: Vec<&str> = match aud {
+                Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+                other => other.as_str().into_iter().collect(),
+            };
+            if token_audiences.iter().any(|a| config.audiences.iter().any(|c| c == a)) {
+                check("audience", "pass", format!("aud {:?} matches the configured audiences", token_audiences))
+            } else {
+                check("audience", "fail", format!("aud {:?} is not one of {:?}", token_audiences, config.audiences))
+            }
+        }
+    });
+
+    let granted: Vec<&str> = granted_scope.map(|s| s.split_whitespace().collect()).unwrap_or_default();
+    let configured: Vec<&str> = config.scopes.iter().flatten().map(|s| s.value.as_str()).collect();
+    let missing: Vec<&String> = requested_scopes.iter().filter(|s| !granted.contains(&s.as_str())).collect();
+    let unexpected: Vec<&&str> = granted.iter().filter(|s| !configured.contains(s)).collect();
+    checks.push(if granted_scope.is_none() && !requested_scopes.is_empty() {
+        check("scopes", "fail", format!("no scope was granted; requested {:?}", requested_scopes))
+    } else if !missing.is_empty() {
+        check("scopes", "fail", format!("requested but not granted: {:?}", missing))
+    } else if !unexpected.is_empty() {
+        check("scopes", "warn", format!("granted but not configured on the server: {:?}", unexpected))
+    } else {
+        check("scopes", "pass", format!("granted {:?}", granted))
+    });
+
+    let lifetime = claims
+        .and_then(|c| Some(c.get("exp")?.as_i64()? - c.get("iat")?.as_i64()?))
+        .or(expires_in);
+    checks.push(match (config.access_token_expiration_minutes, lifetime) {
+        (None, _) => check("lifetime", "skipped", "the server has no access token expiration configured".to_string()),
+        (Some(_), None) => check("lifetime", "skipped", "the token has no exp/iat and the response no expires_in".to_string()),
+        (Some(minutes), Some(seconds)) => {
+            let expected = i64::from(minutes) * 60;
+            let status = if (seconds - expected).abs() <= LIFETIME_TOLERANCE_SECONDS { "pass" } else { "fail" };
+            check("lifetime", status, format!("{}s, configured {}s", seconds, expected))
+        }
+    });
+
+    let (names, missing_status): (Vec<String>, &str) = match expected_claims {
+        Some(names) => (names.to_vec(), "fail"),
+        None => {
+            let mut names: Vec<String> = config.claims.iter().flatten().map(|(name, _)| name.clone()).collect();
+            names.sort();
+            (names, "warn")
+        }
+    };
+    checks.push(match claims {
+        _ if names.is_empty() => check("claims", "skipped", "no claims are configured or expected".to_string()),
+        None => check("claims", "skipped", "the token is opaque, not a JWT".to_string()),
+        Some(claims) => {
+            let absent: Vec<&String> = names.iter().filter(|n| claims.get(n.as_str()).is_none_or(Value::is_null)).collect();
+            if absent.is_empty() {
+                check("claims", "pass", format!("present: {:?}", names))
+            } else {
+                check("claims", missing_status, format!("absent: {:?}", absent))
+            }
+        }
+    });
+
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ApiAuthConfig {
+        serde_json::from_value(json!({
+            "resource_identifier": "https://api.example.com",
+            "audiences": ["https://api.example.com"],
+            "access_token_expiration_minutes": 10,
+            "scopes": [{"value": "read"}, {"value": "write"}],
+            "claims": {"department": {"attribute": "department"}}
+        }))
+        .unwrap()
+    }
+
+    fn status_of<'a>(checks: &'a [Value], name: &str) -> &'a str {
+        checks.iter().find(|c| c["check"] == name).unwrap()["status"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_evaluate_token() {
+        let requested = vec!["read".to_string(), "write".to_string()];
+        let claims = json!({"aud": ["https://api.example.com"], "iat": 1000, "exp": 1600, "department": "IT"});
+        let checks = evaluate_token(&config(), &requested, Some("read write"), None, Some(&claims), None);
+        assert!(checks.iter().all(|c| c["status"] == "pass"), "{:?}", checks);
+
+        let claims = json!({"aud": "other", "iat": 1000, "exp": 4600});
+        let checks = evaluate_token(&config(), &requested, Some("read admin"), None, Some(&claims), None);
+        assert_eq!(status_of(&checks, "audience"), "fail");
+        assert_eq!(status_of(&checks, "scopes"), "fail");
+        assert_eq!(status_of(&checks, "lifetime"), "fail");
+        assert_eq!(status_of(&checks, "claims"), "warn");
+
+        let expected = vec!["department".to_string()];
+        let checks = evaluate_token(&config(), &["read".to_string()], Some("read admin"), Some(600), None, Some(&expected));
+        assert_eq!(status_of(&checks, "audience"), "skipped");
+        assert_eq!(status_of(&checks, "scopes"), "warn");
+        assert_eq!(status_of(&checks, "lifetime"), "pass");
+        assert_eq!(status_of(&checks, "claims"), "skipped");
+    }
+}
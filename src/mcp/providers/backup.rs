@@ -0,0 +1,803 @@
+use crate::api::OneLoginClient;
+use crate::mcp::outcome::Outcome;
+use crate::models::backup::{BackupArchive, BACKUP_FORMAT, BACKUP_SECTIONS, BACKUP_VERSION};
+use crate::models::custom_attributes::{CreateCustomAttributeRequest, UpdateCustomAttributeRequest};
+use crate::models::password_policies::{CreatePasswordPolicyRequest, UpdatePasswordPolicyRequest};
+use crate::models::roles::{CreateRoleRequest, SetRoleAppsRequest, UpdateRoleRequest};
+use crate::models::user_mappings::{CreateMappingRequest, UpdateMappingRequest};
+use anyhow::{anyhow, bail, Context, Result};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// How each restorable section is matched and compared.
+///
+/// Apps and branding are backed up but not restored: creating apps needs
+/// connector-specific setup that an archive cannot reproduce safely.
+struct SectionSpec {
+    section: &'static str,
+    /// Field identifying the same object in both tenants
+    key: &'static str,
+    /// Fields compared and written on update
+    fields: &'static [&'static str],
+}
+
+const RESTORE_SPECS: &[SectionSpec] = &[
+    SectionSpec {
+        section: "custom_attributes",
+        key: "shortname",
+        fields: &["name", "required", "user_visible"],
+    },
+    SectionSpec {
+        section: "password_policies",
+        key: "name",
+        fields: &[
+            "min_length",
+            "require_uppercase",
+            "require_lowercase",
+            "require_numbers",
+            "require_special_chars",
+            "password_history",
+            "expiration_days",
+            "max_failed_attempts",
+            "lockout_duration_minutes",
+        ],
+    },
+    SectionSpec {
+        section: "roles",
+        key: "name",
+        fields: &["description", "apps"],
+    },
+    SectionSpec {
+        section: "user_mappings",
+        key: "name",
+        fields: &["match", "enabled", "conditions", "actions"],
+    },
+];
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ExportBackupArgs {
+    /// File to write. Default: ~/.config/onelogin-mcp/backups/<tenant>-<timestamp>.json
+    pub output_path: Option<String>,
+    /// Sections to include: custom_attributes, password_policies, roles, apps, user_mappings, branding. Default: all
+    pub sections: Option<Vec<String>>,
+    /// Tenant selector, recorded in the archive metadata
+    #[schemars(skip)]
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RestoreBackupArgs {
+    /// Path of an archive written by onelogin_export_backup
+    pub path: String,
+    /// Sections to restore: custom_attributes, password_policies, roles, user_mappings. Default: all present in the archive
+    pub sections: Option<Vec<String>>,
+    /// Only compute and return the plan without changing anything. Default: true
+    pub dry_run: Option<bool>,
+    /// When a change fails, undo the changes already applied (deletes created objects, restores updated ones). Default: true
+    pub rollback_on_failure: Option<bool>,
+}
+
+tool_provider! {
+    /// Tenant configuration backup and restore
+    pub struct BackupProvider("backup") {
+        "onelogin_export_backup" => export_backup(ExportBackupArgs):
+            "Back up tenant configuration to a single versioned JSON archive on disk: custom attributes, password policies, roles (with app assignments), apps (with parameters and rules), user mappings and branding (settings and brands). The archive records the tenant, subdomain and timestamp. Sections the account cannot read are skipped and listed in 'warnings'. Returns the file path and item counts. Read-only against OneLogin.",
+        "onelogin_restore_backup" => restore_backup(RestoreBackupArgs):
+            "Restore configuration from a backup archive. Objects are matched by name (custom attributes by shortname) and each gets a planned action: create, update (with the changed fields) or skip. Role app assignments and mapping role IDs are translated by name, so archives can be restored into another tenant. dry_run (default true) only returns the plan; with dry_run=false the plan is applied in order with a per-object outcome, stopping at the first failure and rolling back the changes already applied; the standard bulk result (complete, succeeded, failed, not_attempted, rollback_performed, rolled_back, rollback_failed) tells what is still in effect. Apps and branding are not restored.",
+    }
+}
+
+impl BackupProvider {
+    async fn export_backup(&self, client: &OneLoginClient, args: ExportBackupArgs) -> Result<Value> {
+        let sections: Vec<&str> = match &args.sections {
+            Some(requested) => {
+                if let Some(unknown) = requested.iter().find(|s| !BACKUP_SECTIONS.contains(&s.as_str())) {
+                    bail!("Unknown backup section '{}'. Expected one of: {}", unknown, BACKUP_SECTIONS.join(", "));
+                }
+                BACKUP_SECTIONS.iter().copied().filter(|s| requested.iter().any(|r| r == s)).collect()
+            }
+            None => BACKUP_SECTIONS.to_vec(),
+        };
+
+        let mut archive = BackupArchive::new(args.tenant.clone(), client.account.subdomain().to_string());
+        for section in sections {
+            match export_section(client, section).await {
+                Ok(value) => {
+                    archive.sections.insert(section.to_string(), value);
+                }
+                Err(e) => archive.warnings.push(format!("{}: {:#}", section, e)),
+            }
+        }
+
+        let path = match &args.output_path {
+            Some(path) => PathBuf::from(path),
+            None => default_backup_path(&archive)?,
+        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let body = serde_json::to_string_pretty(&archive)?;
+        std::fs::write(&path, &body).with_context(|| format!("Failed to write backup to {}", path.display()))?;
+
+        Ok(json!({
+            "status": "exported",
+            "path": path.display().to_string(),
+            "format": archive.format,
+            "version": archive.version,
+            "created_at": archive.created_at,
+            "tenant": archive.tenant,
+            "subdomain": archive.subdomain,
+            "counts": archive.counts(),
+            "bytes_written": body.len(),
+            "warnings": archive.warnings
+        }))
+    }
+
+    async fn restore_backup(&self, client: &OneLoginClient, args: RestoreBackupArgs) -> Result<Value> {
+        let content = std::fs::read_to_string(&args.path)
+            .with_context(|| format!("Failed to read backup {}", args.path))?;
+        let archive: BackupArchive = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a backup archive", args.path))?;
+        if archive.format != BACKUP_FORMAT || archive.version > BACKUP_VERSION {
+            bail!(
+                "Unsupported backup: format '{}' version {} (this server reads '{}' up to version {})",
+                archive.format, archive.version, BACKUP_FORMAT, BACKUP_VERSION
+            );
+        }
+        if let Some(requested) = &args.sections {
+            if let Some(unknown) = requested.iter().find(|s| !RESTORE_SPECS.iter().any(|spec| spec.section == s.as_str())) {
+                bail!("Section '{}' cannot be restored", unknown);
+            }
+        }
+        let specs: Vec<&SectionSpec> = RESTORE_SPECS
+            .iter()
+            .filter(|spec| archive.sections.contains_key(spec.section))
+            .filter(|spec| args.sections.as_ref().is_none_or(|r| r.iter().any(|s| s == spec.section)))
+            .collect();
+
+        let mut ctx = RestoreContext::load(client, &archive).await?;
+        let mut warnings = ctx.warnings.clone();
+        let mut plan = Vec::new();
+        for spec in &specs {
+            let archived = archive.sections[spec.section].as_array().cloned().unwrap_or_default();
+            let mut desired = Vec::new();
+            for item in &archived {
+                let mut item = item.clone();
+                match spec.section {
+                    "roles" => warnings.extend(ctx.remap_role_apps(&mut item)),
+                    "user_mappings" => {
+                        if let Err(e) = ctx.remap_mapping_roles(&mut item) {
+                            warnings.push(e);
+                        }
+                    }
+                    _ => {}
+                }
+                desired.push(item);
+            }
+            let current = current_section(client, spec.section).await?;
+            let items = plan_section(spec, &archived, &desired, &current);
+            // Mappings may reference roles this restore creates
+            if spec.section == "roles" {
+                for item in items.iter().filter(|i| i.action == PlanAction::Create) {
+                    ctx.target_roles.entry(item.name.clone()).or_insert(PENDING_ID);
+                }
+            }
+            plan.extend(items);
+        }
+
+        let summary = json!({
+            "create": plan.iter().filter(|i| i.action == PlanAction::Create).count(),
+            "update": plan.iter().filter(|i| i.action == PlanAction::Update).count(),
+            "skip": plan.iter().filter(|i| i.action == PlanAction::Skip).count()
+        });
+        let archive_info = json!({
+            "path": args.path,
+            "created_at": archive.created_at,
+            "tenant": archive.tenant,
+            "subdomain": archive.subdomain,
+            "version": archive.version
+        });
+
+        if args.dry_run.unwrap_or(true) {
+            return Ok(json!({
+                "dry_run": true,
+                "archive": archive_info,
+                "summary": summary,
+                "warnings": warnings,
+                "plan": plan.iter().map(PlanItem::to_json).collect::<Vec<_>>()
+            }));
+        }
+
+        let rollback = args.rollback_on_failure.unwrap_or(true);
+        let mut results: Vec<Value> = Vec::new();
+        let mut applied: Vec<(usize, Option<String>)> = Vec::new();
+        // Item that failed after creating its object, which still needs undoing
+        let mut partial: Option<(usize, Option<String>)> = None;
+        let mut outcome = Outcome::default();
+        let mut failed = false;
+        for (index, item) in plan.iter().enumerate() {
+            let mut result = item.to_json();
+            if failed || item.action == PlanAction::Skip {
+                result["status"] = json!(if failed { "not_attempted" } else { "skipped" });
+                if failed {
+                    outcome.not_attempted(item.outcome_item(None));
+                }
+                results.push(result);
+                continue;
+            }
+            match apply_item(client, item, &mut ctx).await {
+                Ok(created_id) => {
+                    result["status"] = json!("applied");
+                    if let Some(id) = &created_id {
+                        result["target_id"] = json!(id);
+                    }
+                    applied.push((index, created_id));
+                }
+                Err(e) => {
+                    let created_id = ctx.created_before_failure.take();
+                    result["status"] = json!("failed");
+                    result["error"] = json!(format!("{:#}", e));
+                    if let Some(id) = &created_id {
+                        result["target_id"] = json!(id);
+                    }
+                    outcome.failed(item.outcome_item(created_id.as_deref()), format!("{:#}", e));
+                    partial = created_id.map(|id| (index, Some(id)));
+                    failed = true;
+                }
+            }
+            results.push(result);
+        }
+
+        if failed && rollback {
+            for (index, created_id) in partial.into_iter().chain(applied.into_iter().rev()) {
+                let item = plan[index].outcome_item(created_id.as_deref());
+                let status = match rollback_item(client, &plan[index], created_id.as_deref()).await {
+                    Ok(()) => {
+                        outcome.rolled_back(item);
+                        json!("rolled_back")
+                    }
+                    Err(e) => {
+                        results[index]["rollback_error"] = json!(format!("{:#}", e));
+                        outcome.rollback_failed(item, format!("{:#}", e));
+                        json!("rollback_failed")
+                    }
+                };
+                results[index]["status"] = status;
+            }
+        } else {
+            for (index, created_id) in applied {
+                outcome.succeeded(plan[index].outcome_item(created_id.as_deref()));
+            }
+        }
+
+        let mut result = json!({
+            "dry_run": false,
+            "archive": archive_info,
+            "summary": summary,
+            "warnings": warnings,
+            "results": results
+        });
+        outcome.merge_into(&mut result);
+        Ok(result)
+    }
+}
+
+/// Placeholder target ID for roles the restore has yet to create
+const PENDING_ID: i64 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlanAction {
+    Create,
+    Update,
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+struct PlanItem {
+    section: &'static str,
+    name: String,
+    action: PlanAction,
+    /// Matching object in the target tenant
+    target_id: Option<String>,
+    changes: Vec<String>,
+    reason: Option<String>,
+    /// Archived object as stored in the backup
+    source: Value,
+    /// Archived object with IDs translated to the target tenant
+    desired: Value,
+    /// Current target object, used to roll back updates
+    current: Option<Value>,
+}
+
+impl PlanItem {
+    fn action_name(&self) -> &'static str {
+        match self.action {
+            PlanAction::Create => "create",
+            PlanAction::Update => "update",
+            PlanAction::Skip => "skip",
+        }
+    }
+
+    /// What an applied, failed or skipped item changes, for the bulk result envelope
+    fn outcome_item(&self, created_id: Option<&str>) -> Value {
+        json!({
+            "section": self.section,
+            "name": self.name,
+            "action": self.action_name(),
+            "target_id": created_id.or(self.target_id.as_deref())
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "section": self.section,
+            "name": self.name,
+            "action": self.action_name(),
+            "target_id": self.target_id,
+            "changes": self.changes,
+            "reason": self.reason
+        })
+    }
+}
+
+/// Compare archived objects with the target tenant. `archived` and `desired`
+/// are parallel: the raw archive entries and the same entries with IDs remapped.
+fn plan_section(spec: &SectionSpec, archived: &[Value], desired: &[Value], current: &[Value]) -> Vec<PlanItem> {
+    archived
+        .iter()
+        .zip(desired)
+        .map(|(source, desired)| {
+            let name = desired[spec.key].as_str().unwrap_or_default().to_string();
+            let mut item = PlanItem {
+                section: spec.section,
+                name: name.clone(),
+                action: PlanAction::Skip,
+                target_id: None,
+                changes: Vec::new(),
+                reason: None,
+                source: source.clone(),
+                desired: desired.clone(),
+                current: None,
+            };
+            if name.is_empty() {
+                item.reason = Some(format!("archived object has no {}", spec.key));
+                return item;
+            }
+            match current.iter().find(|c| c[spec.key].as_str() == Some(name.as_str())) {
+                Some(existing) => {
+                    item.target_id = value_id(&existing["id"]);
+                    item.changes = spec
+                        .fields
+                        .iter()
+                        .filter(|f| !desired[**f].is_null() && desired[**f] != existing[**f])
+                        .map(|f| f.to_string())
+                        .collect();
+                    if item.changes.is_empty() {
+                        item.reason = Some("unchanged".to_string());
+                    } else {
+                        item.action = PlanAction::Update;
+                    }
+                    item.current = Some(existing.clone());
+                }
+                None => {
+                    item.action = PlanAction::Create;
+                    item.changes = spec.fields.iter().filter(|f| !desired[**f].is_null()).map(|f| f.to_string()).collect();
+                }
+            }
+            item
+        })
+        .collect()
+}
+
+fn value_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Copy the non-null `fields` of `value` into a new object
+fn pick(value: &Value, fields: &[&str]) -> Value {
+    let mut out = Map::new();
+    for field in fields {
+        if let Some(v) = value.get(*field).filter(|v| !v.is_null()) {
+            out.insert(field.to_string(), v.clone());
+        }
+    }
+    Value::Object(out)
+}
+
+fn request<T: DeserializeOwned>(value: Value) -> Result<T> {
+    serde_json::from_value(value).context("Archived object does not match the API request shape")
+}
+
+fn parse_id(id: Option<&str>) -> Result<i64> {
+    id.and_then(|id| id.parse().ok()).ok_or_else(|| anyhow!("Missing target ID"))
+}
+
+/// ID translation between the archived tenant and the target tenant, by name
+struct RestoreContext {
+    source_apps: HashMap<i64, String>,
+    target_apps: HashMap<String, i64>,
+    source_roles: HashMap<i64, String>,
+    target_roles: HashMap<String, i64>,
+    warnings: Vec<String>,
+    /// Object created by the item being applied, kept until the item's later steps succeed
+    created_before_failure: Option<String>,
+}
+
+impl RestoreContext {
+    async fn load(client: &OneLoginClient, archive: &BackupArchive) -> Result<Self> {
+        let names = |section: &str| -> HashMap<i64, String> {
+            archive
+                .sections
+                .get(section)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|v| Some((v["id"].as_i64()?, v["name"].as_str()?.to_string())))
+                .collect()
+        };
+        let mut warnings = Vec::new();
+        if !archive.sections.contains_key("apps") {
+            warnings.push("Archive has no apps section; role app assignments are not restored".to_string());
+        }
+        let target_apps = client.apps.list_apps(None).await
+            .map_err(|e| anyhow!("Failed to list apps: {}", e))?
+            .into_iter()
+            .map(|a| (a.name, a.id))
+            .collect();
+        let target_roles = client.roles.list_roles().await
+            .map_err(|e| anyhow!("Failed to list roles: {}", e))?
+            .into_iter()
+            .filter_map(|r| Some((r.name?, r.id)))
+            .collect();
+        Ok(Self {
+            source_apps: names("apps"),
+            target_apps,
+            source_roles: names("roles"),
+            target_roles,
+            warnings,
+            created_before_failure: None,
+        })
+    }
+
+    /// Translate a role's `apps` to target app IDs; returns warnings for apps missing in the target
+    fn remap_role_apps(&self, role: &mut Value) -> Vec<String> {
+        if self.source_apps.is_empty() {
+            role["apps"] = Value::Null;
+            return Vec::new();
+        }
+        let mut warnings = Vec::new();
+        let mut apps: Vec<i64> = role["apps"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_i64)
+            .filter_map(|id| {
+                let target = self.source_apps.get(&id).and_then(|name| self.target_apps.get(name)).copied();
+                if target.is_none() {
+                    warnings.push(format!(
+                        "Role '{}': app {} has no match in the target tenant and is not assigned",
+                        role["name"].as_str().unwrap_or_default(),
+                        self.source_apps.get(&id).cloned().unwrap_or_else(|| id.to_string())
+                    ));
+                }
+                target
+            })
+            .collect();
+        apps.sort_unstable();
+        role["apps"] = json!(apps);
+        warnings
+    }
+
+    /// Translate role IDs in a mapping's role actions to target role IDs
+    fn remap_mapping_roles(&self, mapping: &mut Value) -> std::result::Result<(), String> {
+        let name = mapping["name"].as_str().unwrap_or_default().to_string();
+        let Some(actions) = mapping.get_mut("actions").and_then(Value::as_array_mut) else {
+            return Ok(());
+        };
+        for action in actions {
+            if !action["action"].as_str().unwrap_or_default().contains("role") {
+                continue;
+            }
+            let Some(values) = action.get_mut("value").and_then(Value::as_array_mut) else { continue };
+            for value in values {
+                let Some(source_id) = value.as_str().and_then(|v| v.trim().parse::<i64>().ok()) else { continue };
+                // Without a roles section the archive IDs are used as they are
+                if self.source_roles.is_empty() {
+                    continue;
+                }
+                let target = self
+                    .source_roles
+                    .get(&source_id)
+                    .and_then(|role| self.target_roles.get(role))
+                    .ok_or_else(|| format!("Mapping '{}': role {} has no match in the target tenant", name, source_id))?;
+                *value = json!(target.to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Current objects for a section, shaped like the archive entries
+async fn current_section(client: &OneLoginClient, section: &str) -> Result<Vec<Value>> {
+    let mut items = export_section(client, section).await?.as_array().cloned().unwrap_or_default();
+    // Remapped archive app lists are sorted; compare like with like
+    for item in items.iter_mut() {
+        if let Some(apps) = item.get_mut("apps").and_then(Value::as_array_mut) {
+            apps.sort_by_key(|v| v.as_i64());
+        }
+    }
+    Ok(items)
+}
+
+/// Apply one planned change; returns the ID of a created object
+async fn apply_item(client: &OneLoginClient, item: &PlanItem, ctx: &mut RestoreContext) -> Result<Option<String>> {
+    let spec = RESTORE_SPECS.iter().find(|s| s.section == item.section).expect("planned section has a spec");
+    let create = item.action == PlanAction::Create;
+    let id = if create { None } else { Some(parse_id(item.target_id.as_deref())?) };
+    match item.section {
+        "custom_attributes" => {
+            if create {
+                let fields = ["name", "shortname", "data_type", "required", "user_visible"];
+                let created = client.custom_attributes.create_custom_attribute(request::<CreateCustomAttributeRequest>(pick(&item.desired, &fields))?).await
+                    .map_err(|e| anyhow!("Failed to create custom attribute: {}", e))?;
+                return Ok(Some(created.id.to_string()));
+            }
+            client.custom_attributes.update_custom_attribute(id.unwrap(), request::<UpdateCustomAttributeRequest>(pick(&item.desired, spec.fields))?).await
+                .map_err(|e| anyhow!("Failed to update custom attribute: {}", e))?;
+        }
+        "password_policies" => {
+            if create {
+                let mut fields = vec!["name"];
+                fields.extend_from_slice(spec.fields);
+                let created = client.password_policies.create_password_policy(request::<CreatePasswordPolicyRequest>(pick(&item.desired, &fields))?).await
+                    .map_err(|e| anyhow!("Failed to create password policy: {}", e))?;
+                return Ok(created.id.map(|id| id.to_string()));
+            }
+            client.password_policies.update_password_policy(id.unwrap(), request::<UpdatePasswordPolicyRequest>(pick(&item.desired, spec.fields))?).await
+                .map_err(|e| anyhow!("Failed to update password policy: {}", e))?;
+        }
+        "roles" => {
+            let role_id = match id {
+                Some(id) => id,
+                None => {
+                    let created = client.roles.create_role(CreateRoleRequest { name: item.name.clone() }).await
+                        .map_err(|e| anyhow!("Failed to create role: {}", e))?;
+                    ctx.target_roles.insert(item.name.clone(), created.id);
+                    ctx.created_before_failure = Some(created.id.to_string());
+                    created.id
+                }
+            };
+            set_role_state(client, role_id, &item.desired, &item.changes).await?;
+            ctx.created_before_failure = None;
+            return Ok(create.then(|| role_id.to_string()));
+        }
+        "user_mappings" => {
+            // Re-translate now that roles created by this restore have real IDs
+            let mut desired = item.source.clone();
+            ctx.remap_mapping_roles(&mut desired).map_err(|e| anyhow!(e))?;
+            if create {
+                let fields = ["name", "match", "enabled", "conditions", "actions"];
+                let created = client.user_mappings.create_mapping(request::<CreateMappingRequest>(pick(&desired, &fields))?).await
+                    .map_err(|e| anyhow!("Failed to create user mapping: {}", e))?;
+                return Ok(Some(created.id.to_string()));
+            }
+            client.user_mappings.update_mapping(&id.unwrap().to_string(), request::<UpdateMappingRequest>(pick(&desired, spec.fields))?).await
+                .map_err(|e| anyhow!("Failed to update user mapping: {}", e))?;
+        }
+        other => bail!("Section '{}' cannot be restored", other),
+    }
+    Ok(None)
+}
+
+/// Write a role's description and app assignments for the changed fields
+async fn set_role_state(client: &OneLoginClient, role_id: i64, state: &Value, changes: &[String]) -> Result<()> {
+    if changes.iter().any(|c| c == "description") {
+        let request = UpdateRoleRequest {
+            name: None,
+            description: state["description"].as_str().map(String::from),
+        };
+        client.roles.update_role(role_id, request).await
+            .map_err(|e| anyhow!("Failed to update role {}: {}", role_id, e))?;
+    }
+    if changes.iter().any(|c| c == "apps") {
+        let app_id_array = state["apps"].as_array().into_iter().flatten().filter_map(Value::as_i64).collect();
+        client.roles.set_role_apps(role_id, SetRoleAppsRequest { app_id_array }).await
+            .map_err(|e| anyhow!("Failed to set apps for role {}: {}", role_id, e))?;
+    }
+    Ok(())
+}
+
+/// Undo an applied change: delete what was created, write back what was updated
+async fn rollback_item(client: &OneLoginClient, item: &PlanItem, created_id: Option<&str>) -> Result<()> {
+    let spec = RESTORE_SPECS.iter().find(|s| s.section == item.section).expect("planned section has a spec");
+    if item.action == PlanAction::Create {
+        let id = parse_id(created_id)?;
+        match item.section {
+            "custom_attributes" => client.custom_attributes.delete_custom_attribute(id).await,
+            "password_policies" => client.password_policies.delete_password_policy(id).await,
+            "roles" => client.roles.delete_role(id).await,
+            _ => client.user_mappings.delete_mapping(&id.to_string()).await,
+        }
+        .map_err(|e| anyhow!("Failed to delete {} {}: {}", item.section, id, e))?;
+        return Ok(());
+    }
+
+    let id = parse_id(item.target_id.as_deref())?;
+    let previous = item.current.as_ref().ok_or_else(|| anyhow!("No previous state recorded"))?;
+    match item.section {
+        "custom_attributes" => {
+            client.custom_attributes.update_custom_attribute(id, request::<UpdateCustomAttributeRequest>(pick(previous, spec.fields))?).await
+                .map(|_| ())
+        }
+        "password_policies" => {
+            client.password_policies.update_password_policy(id, request::<UpdatePasswordPolicyRequest>(pick(previous, spec.fields))?).await
+                .map(|_| ())
+        }
+        "roles" => return set_role_state(client, id, previous, &item.changes).await,
+        _ => {
+            client.user_mappings.update_mapping(&id.to_string(), request::<UpdateMappingRequest>(pick(previous, spec.fields))?).await
+                .map(|_| ())
+        }
+    }
+    .map_err(|e| anyhow!("Failed to restore {} {}: {}", item.section, id, e))
+}
+
+async fn export_section(client: &OneLoginClient, section: &str) -> Result<Value> {
+    let value = match section {
+        "custom_attributes" => serde_json::to_value(
+            client.custom_attributes.list_custom_attributes().await
+                .map_err(|e| anyhow!("Failed to list custom attributes: {}", e))?,
+        )?,
+        "password_policies" => serde_json::to_value(
+            client.password_policies.list_password_policies().await
+                .map_err(|e| anyhow!("Failed to list password policies: {}", e))?,
+        )?,
+        "roles" => {
+            let roles = client.roles.list_roles().await
+                .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+            let mut out = Vec::new();
+            for role in roles {
+                let apps = client.roles.get_role_apps(role.id).await
+                    .map_err(|e| anyhow!("Failed to get apps for role {}: {}", role.id, e))?;
+                let mut value = serde_json::to_value(&role)?;
+                value["apps"] = json!(apps.iter().filter_map(|a| a.id).collect::<Vec<_>>());
+                out.push(value);
+            }
+            Value::Array(out)
+        }
+        "apps" => {
+            let apps = client.apps.list_apps(None).await
+                .map_err(|e| anyhow!("Failed to list apps: {}", e))?;
+            let mut out = Vec::new();
+            for summary in apps {
+                // The full app carries configuration and parameters
+                let app = client.apps.get_app(summary.id).await
+                    .map_err(|e| anyhow!("Failed to get app {}: {}", summary.id, e))?;
+                let rules = client.app_rules.list_rules(app.id, None).await
+                    .map_err(|e| anyhow!("Failed to list rules for app {}: {}", app.id, e))?;
+                let mut value = serde_json::to_value(&app)?;
+                value["rules"] = serde_json::to_value(rules)?;
+                out.push(value);
+            }
+            Value::Array(out)
+        }
+        "user_mappings" => serde_json::to_value(
+            client.user_mappings.list_mappings().await
+                .map_err(|e| anyhow!("Failed to list user mappings: {}", e))?,
+        )?,
+        "branding" => {
+            let settings = client.branding.get_branding_settings(None).await
+                .map_err(|e| anyhow!("Failed to get branding settings: {}", e))?;
+            let brands = client.branding.list_account_brands().await
+                .map_err(|e| anyhow!("Failed to list brands: {}", e))?;
+            json!({"settings": settings, "brands": brands})
+        }
+        other => bail!("Unknown backup section '{}'", other),
+    };
+    Ok(value)
+}
+
+/// `<config dir>/onelogin-mcp/backups/<tenant or subdomain>-<UTC timestamp>.json`
+fn default_backup_path(archive: &BackupArchive) -> Result<PathBuf> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| anyhow!("No config directory available; pass output_path"))?
+        .join("onelogin-mcp")
+        .join("backups");
+    Ok(dir.join(backup_file_name(archive)))
+}
+
+fn backup_file_name(archive: &BackupArchive) -> String {
+    let label: String = archive
+        .tenant
+        .as_deref()
+        .unwrap_or(&archive.subdomain)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let stamp = chrono::DateTime::parse_from_rfc3339(&archive.created_at)
+        .map(|t| t.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_default();
+    format!("{}-{}.json", label, stamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> RestoreContext {
+        RestoreContext {
+            source_apps: HashMap::from([(1, "Slack".to_string()), (2, "Legacy".to_string())]),
+            target_apps: HashMap::from([("Slack".to_string(), 101)]),
+            source_roles: HashMap::from([(5, "Engineering".to_string())]),
+            target_roles: HashMap::from([("Engineering".to_string(), 505)]),
+            warnings: Vec::new(),
+            created_before_failure: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_section_create_update_skip() {
+        let spec = &RESTORE_SPECS[1];
+        let archived = vec![
+            json!({"id": 1, "name": "Strict", "min_length": 12}),
+            json!({"id": 2, "name": "Default", "min_length": 8, "expiration_days": 90}),
+            json!({"id": 3, "name": "Same", "min_length": 8}),
+        ];
+        let current = vec![
+            json!({"id": 20, "name": "Default", "min_length": 8, "expiration_days": 0}),
+            json!({"id": 30, "name": "Same", "min_length": 8}),
+        ];
+        let plan = plan_section(spec, &archived, &archived, &current);
+        assert_eq!(plan[0].action, PlanAction::Create);
+        assert_eq!(plan[0].changes, vec!["min_length"]);
+        assert_eq!(plan[1].action, PlanAction::Update);
+        assert_eq!(plan[1].changes, vec!["expiration_days"]);
+        assert_eq!(plan[1].target_id.as_deref(), Some("20"));
+        assert_eq!(plan[2].action, PlanAction::Skip);
+        assert_eq!(plan[2].reason.as_deref(), Some("unchanged"));
+    }
+
+    #[test]
+    fn test_remap_ids_by_name() {
+        let ctx = context();
+        let mut role = json!({"name": "Engineering", "apps": [2, 1]});
+        let warnings = ctx.remap_role_apps(&mut role);
+        assert_eq!(role["apps"], json!([101]));
+        assert_eq!(warnings.len(), 1);
+
+        let mut mapping = json!({"name": "Eng", "actions": [
+            {"action": "set_role_ids", "value": ["5"]},
+            {"action": "set_status", "value": ["1"]}
+        ]});
+        ctx.remap_mapping_roles(&mut mapping).unwrap();
+        assert_eq!(mapping["actions"][0]["value"], json!(["505"]));
+        assert_eq!(mapping["actions"][1]["value"], json!(["1"]));
+
+        let mut unknown = json!({"name": "X", "actions": [{"action": "set_role_ids", "value": ["9"]}]});
+        assert!(ctx.remap_mapping_roles(&mut unknown).is_err());
+    }
+
+    #[test]
+    fn test_backup_file_name_and_counts() {
+        let mut archive = BackupArchive::new(Some("prod/eu".into()), "acme".into());
+        archive.created_at = "2024-06-01T12:30:00+00:00".into();
+        assert_eq!(backup_file_name(&archive), "prod_eu-20240601T123000Z.json");
+        archive.tenant = None;
+        assert_eq!(backup_file_name(&archive), "acme-20240601T123000Z.json");
+
+        archive.sections.insert("roles".into(), json!([{"id": 1}, {"id": 2}]));
+        archive.sections.insert("branding".into(), json!({"settings": {}}));
+        let counts = archive.counts();
+        assert_eq!(counts["roles"], 2);
+        assert_eq!(counts["branding"], 1);
+    }
+}
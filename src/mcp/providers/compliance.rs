@@ -0,0 +1,1423 @@
+use crate::api::OneLoginClient;
+use crate::mcp::outcome::Outcome;
+use crate::models::account::AccountSettings;
+use crate::models::apps::App;
+use crate::models::certificates::Certificate;
+use crate::models::events::{Event, EventQueryParams};
+use crate::models::groups::Group;
+use crate::models::password_policies::PasswordPolicy;
+use crate::models::user_mappings::{MappingAction, MappingCondition, UserMapping};
+use crate::models::users::{User, UserQueryParams};
+use crate::utils::certs::certificate_validity;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Users requested per page when scanning the whole directory
+const SCAN_PAGE_SIZE: i32 = 100;
+
+/// User status codes (see `User::status`)
+pub(crate) const STATUS_UNACTIVATED: i32 = 0;
+const STATUS_ACTIVE: i32 = 1;
+const STATUS_SUSPENDED: i32 = 2;
+const STATUS_LOCKED: i32 = 3;
+
+/// Event type recorded when a user launches an app
+const EVENT_APP_LOGIN: i32 = 8;
+
+/// `App::auth_method` for SAML 2.0 apps
+const AUTH_METHOD_SAML: i32 = 2;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct InactiveUsersReportArgs {
+    /// Active users whose last login is older than this many days are reported as inactive. Default: 90
+    pub inactive_days: Option<i64>,
+    /// Users created more than this many days ago that never activated or never logged in are reported. Default: 14
+    pub grace_days: Option<i64>,
+    /// Max pages of 100 users to scan. Default: 50
+    pub max_pages: Option<u32>,
+    /// Max users listed per category (counts are always complete for the scanned users). Default: 200
+    pub max_per_category: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ElevatedAccessReportArgs {
+    /// Roles treated as sensitive, by ID or exact name (case-insensitive). Default: every role whose name contains "admin"
+    pub sensitive_roles: Option<Vec<String>>,
+    /// Include delegated administration privileges, both direct and through roles. Default: true
+    pub include_privileges: Option<bool>,
+    /// Include users who administer a sensitive role. Default: true
+    pub include_role_admins: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AppAccessReportArgs {
+    /// The app to report on
+    pub app_id: i64,
+    /// Also scan every user's app list to find access not explained by a role (one request per user). Default: false
+    pub include_direct: Option<bool>,
+    /// Max pages of 100 users to scan when include_direct is set. Default: 50
+    pub max_pages: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct LockedUsersReportArgs {
+    /// Unlocked users with at least this many failed logins are listed as at risk. Default: 5
+    pub min_failed_attempts: Option<i32>,
+    /// Max pages of 100 users to scan. Default: 50
+    pub max_pages: Option<u32>,
+    /// Users to unlock. Only IDs found locked by this scan are unlocked; others are skipped
+    pub unlock_user_ids: Option<Vec<i64>>,
+    /// Must be true to perform the unlocks; otherwise the tool only previews them. Default: false
+    pub confirm_unlock: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct PasswordExpiryReportArgs {
+    /// Report passwords expiring within this many days. Default: 14
+    pub within_days: Option<i64>,
+    /// Also list users whose password has already expired. Default: true
+    pub include_expired: Option<bool>,
+    /// Max pages of 100 users to scan. Default: 50
+    pub max_pages: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct CertificateExpiryReportArgs {
+    /// Flag certificates expiring within this many days. Default: 30
+    pub within_days: Option<i64>,
+    /// Also list apps whose certificates are outside the window. Default: false
+    pub include_healthy: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct AppUsageReportArgs {
+    /// Period to analyze, in days back from now. Default: 30
+    pub days: Option<i64>,
+    /// Event type counted as an app launch. Default: 8 (app login)
+    pub event_type_id: Option<i32>,
+    /// Max pages of events to read. Default: 20
+    pub max_pages: Option<usize>,
+    /// Number of apps in the most and least used lists. Default: 10
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct MappingComplianceReportArgs {
+    /// Max pages of 100 users to scan. Default: 50
+    pub max_pages: Option<u32>,
+    /// Max drifted users to list (the count is always complete). Default: 200
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct SessionComplianceReportArgs {
+    /// Longest acceptable idle session timeout in minutes. Default: 60
+    pub max_idle_minutes: Option<i32>,
+    /// Longest acceptable absolute session duration in hours. Default: 24
+    pub max_session_hours: Option<i32>,
+    /// Fail the account policy unless MFA is required. Default: true
+    pub require_mfa: Option<bool>,
+    /// Fail password policies that allow more failed logins than this before locking, or never lock. Default: 10
+    pub max_failed_attempts: Option<i32>,
+}
+
+/// Limits a session compliance report checks against
+struct SessionThresholds {
+    max_idle_minutes: i32,
+    max_session_minutes: i32,
+    require_mfa: bool,
+    max_failed_attempts: i32,
+}
+
+impl From<&SessionComplianceReportArgs> for SessionThresholds {
+    fn from(args: &SessionComplianceReportArgs) -> Self {
+        Self {
+            max_idle_minutes: args.max_idle_minutes.unwrap_or(60).max(1),
+            max_session_minutes: args.max_session_hours.unwrap_or(24).max(1) * 60,
+            require_mfa: args.require_mfa.unwrap_or(true),
+            max_failed_attempts: args.max_failed_attempts.unwrap_or(10).max(1),
+        }
+    }
+}
+
+tool_provider! {
+    /// Identity governance and hygiene reports built from paged API data
+    pub struct ComplianceProvider("compliance") {
+        "onelogin_inactive_users_report" => inactive_users_report(InactiveUsersReportArgs):
+            "Scan all users and report accounts that need review: 'inactive' (active but no login in inactive_days), 'never_logged_in' (active for longer than grace_days without a login), 'never_activated' (still unactivated after grace_days) and 'suspended_with_roles' (suspended but still holding roles). Returns counts and user lists per category. Read-only.",
+        "onelogin_elevated_access_report" => elevated_access_report(ElevatedAccessReportArgs):
+            "Build an access review worksheet of every user with elevated access: delegated admin privileges (assigned directly or through a role), membership in sensitive roles, and admin rights over sensitive roles. Sensitive roles default to roles named like 'admin' and can be set with sensitive_roles. Returns one row per user listing each grant, with empty decision fields for the reviewer. Read-only.",
+        "onelogin_app_access_report" => app_access_report(AppAccessReportArgs):
+            "Answer 'who can reach app X': resolve the app's role assignments and the user mapping rules that grant those roles, and return the effective user list with the path by which each user gains access ('role' for manual role membership, 'rule' for membership granted by a mapping, 'direct' for access outside any role when include_direct is set). Read-only.",
+        "onelogin_locked_users_report" => locked_users_report(LockedUsersReportArgs):
+            "List locked accounts (status Locked or locked_until in the future) and unlocked accounts with many failed logins. Optionally unlock a subset in the same call: pass unlock_user_ids and confirm_unlock=true. Without confirm_unlock the unlocks are only previewed, and IDs not found locked by the scan are never unlocked. Confirmed unlocks carry the standard bulk result (complete, succeeded, failed with reasons) under 'unlock'.",
+        "onelogin_password_expiry_report" => password_expiry_report(PasswordExpiryReportArgs):
+            "List users whose passwords expire within within_days, grouped by password policy. Expiry is computed from each user's password_changed_at and the expiration_days of their assigned policy (or the account default policy). Useful for proactive reminder emails. Read-only.",
+        "onelogin_certificate_expiry_report" => certificate_expiry_report(CertificateExpiryReportArgs):
+            "Scan all SAML apps, read each app's signing certificate and report when it expires. Certificates already expired or expiring within within_days are flagged so they can be rotated before SSO breaks; apps whose certificate could not be read are listed as 'unknown'. Read-only.",
+        "onelogin_app_usage_report" => app_usage_report(AppUsageReportArgs):
+            "Aggregate app launch events over the last N days: launches, unique users and last launch per app, the most and least used apps, and apps with no launches at all (candidates for license reclamation). Counts only cover the events read; 'truncated' is true when max_pages stopped the scan early. Read-only.",
+        "onelogin_mapping_compliance_report" => mapping_compliance_report(MappingComplianceReportArgs):
+            "Compare each user's current roles and group with what the enabled user mappings would assign from their attributes, and report users who drifted from mapping intent: 'missing_roles' (a matching mapping assigns the role but the user lacks it), 'unexpected_roles' (a mapping-managed role no matching mapping grants, e.g. kept after a department change) and group mismatches. Roles no mapping manages are ignored. Mappings with conditions that cannot be evaluated locally (e.g. member_of, regex) are skipped and listed. Read-only.",
+        "onelogin_session_compliance_report" => session_compliance_report(SessionComplianceReportArgs):
+            "Check session and lockout settings against compliance thresholds and return a pass/fail report per policy. The account policy is checked for idle timeout (max_idle_minutes), absolute session length (max_session_hours) and whether MFA is required; each password policy is checked for account lockout (max_failed_attempts). Each check reports the actual value and threshold, and is 'unknown' when OneLogin does not return the setting. Suitable for scheduled runs. Read-only.",
+    }
+}
+
+impl ComplianceProvider {
+    async fn inactive_users_report(&self, client: &OneLoginClient, args: InactiveUsersReportArgs) -> Result<Value> {
+        let inactive_days = args.inactive_days.unwrap_or(90).max(1);
+        let grace_days = args.grace_days.unwrap_or(14).max(0);
+        let max_per_category = args.max_per_category.unwrap_or(200);
+        let (mut users, truncated) = fetch_all_users(client, args.max_pages.unwrap_or(50)).await?;
+
+        // List responses may omit role_ids; look them up for the suspended users only
+        for user in users.iter_mut().filter(|u| u.status == STATUS_SUSPENDED && u.role_ids.is_none()) {
+            let role_ids = client.users.get_user_roles(user.id).await
+                .map_err(|e| anyhow!("Failed to get roles for user {}: {}", user.id, e))?;
+            user.role_ids = Some(role_ids);
+        }
+
+        let now = Utc::now();
+        let report = classify_inactive_users(&users, inactive_days, grace_days, now);
+        let mut categories = serde_json::Map::new();
+        let mut counts = serde_json::Map::new();
+        for (category, matched) in report {
+            counts.insert(category.to_string(), json!(matched.len()));
+            let listed: Vec<Value> = matched
+                .iter()
+                .take(max_per_category)
+                .map(|user| user_summary(user, now))
+                .collect();
+            categories.insert(category.to_string(), Value::Array(listed));
+        }
+
+        Ok(json!({
+            "generated_at": now.to_rfc3339(),
+            "thresholds": {"inactive_days": inactive_days, "grace_days": grace_days},
+            "users_scanned": users.len(),
+            "truncated": truncated,
+            "counts": counts,
+            "categories": categories
+        }))
+    }
+
+    async fn elevated_access_report(&self, client: &OneLoginClient, args: ElevatedAccessReportArgs) -> Result<Value> {
+        let roles = client.roles.list_roles().await
+            .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+        let role_names: HashMap<i64, String> = roles
+            .iter()
+            .map(|r| (r.id, r.name.clone().unwrap_or_else(|| format!("role {}", r.id))))
+            .collect();
+        let sensitive: Vec<i64> = roles
+            .iter()
+            .filter(|r| is_sensitive_role(r.id, r.name.as_deref(), args.sensitive_roles.as_deref()))
+            .map(|r| r.id)
+            .collect();
+
+        let mut grants = Vec::new();
+        let mut people: HashMap<i64, Value> = HashMap::new();
+        let mut members: HashMap<i64, Vec<i64>> = HashMap::new();
+        let mut warnings = Vec::new();
+
+        for &role_id in &sensitive {
+            let users = client.roles.get_role_users(role_id).await
+                .map_err(|e| anyhow!("Failed to get users for role {}: {}", role_id, e))?;
+            for user in &users {
+                let Some(user_id) = user.id else { continue };
+                people.entry(user_id).or_insert_with(|| {
+                    json!({"email": user.email, "username": user.username, "name": user.name})
+                });
+                grants.push(AccessGrant {
+                    user_id,
+                    kind: "role",
+                    id: role_id.to_string(),
+                    name: role_names[&role_id].clone(),
+                    via_role: None,
+                    added_at: user.added_at.clone(),
+                });
+            }
+            members.insert(role_id, users.iter().filter_map(|u| u.id).collect());
+
+            if args.include_role_admins.unwrap_or(true) {
+                let admins = client.roles.get_role_admins(role_id).await
+                    .map_err(|e| anyhow!("Failed to get admins for role {}: {}", role_id, e))?;
+                for admin in admins {
+                    let Some(user_id) = admin.id else { continue };
+                    people.entry(user_id).or_insert_with(|| {
+                        json!({"email": admin.email, "username": admin.username, "name": admin.name})
+                    });
+                    grants.push(AccessGrant {
+                        user_id,
+                        kind: "role_admin",
+                        id: role_id.to_string(),
+                        name: role_names[&role_id].clone(),
+                        via_role: None,
+                        added_at: admin.added_at,
+                    });
+                }
+            }
+        }
+
+        if args.include_privileges.unwrap_or(true) {
+            // Delegated administration is a subscription feature; report it as missing rather than failing
+            match client.privileges.list_privileges().await {
+                Ok(privileges) => {
+                    for privilege in privileges {
+                        let user_ids = client.privileges.get_assigned_users(&privilege.id).await
+                            .map_err(|e| anyhow!("Failed to get users for privilege {}: {}", privilege.id, e))?;
+                        for user_id in user_ids {
+                            grants.push(AccessGrant {
+                                user_id,
+                                kind: "privilege",
+                                id: privilege.id.clone(),
+                                name: privilege.name.clone(),
+                                via_role: None,
+                                added_at: None,
+                            });
+                        }
+                        let role_ids = client.privileges.get_assigned_roles(&privilege.id).await
+                            .map_err(|e| anyhow!("Failed to get roles for privilege {}: {}", privilege.id, e))?;
+                        for role_id in role_ids {
+                            if let Entry::Vacant(entry) = members.entry(role_id) {
+                                let users = client.roles.get_role_users(role_id).await
+                                    .map_err(|e| anyhow!("Failed to get users for role {}: {}", role_id, e))?;
+                                entry.insert(users.iter().filter_map(|u| u.id).collect());
+                            }
+                            let via = role_names.get(&role_id).cloned().unwrap_or_else(|| format!("role {}", role_id));
+                            for &user_id in &members[&role_id] {
+                                grants.push(AccessGrant {
+                                    user_id,
+                                    kind: "privilege",
+                                    id: privilege.id.clone(),
+                                    name: privilege.name.clone(),
+                                    via_role: Some(via.clone()),
+                                    added_at: None,
+                                });
+                            }
+                        }
+                    }
+                }
+                Err(e) => warnings.push(format!("Privileges were not included: {}", e)),
+            }
+        }
+
+        // Privilege grants only carry user IDs; fill in who they are
+        for user_id in grants.iter().map(|g| g.user_id).collect::<Vec<_>>() {
+            if people.contains_key(&user_id) {
+                continue;
+            }
+            let info = match client.users.get_user(user_id).await {
+                Ok(user) => json!({
+                    "email": user.email,
+                    "username": user.username,
+                    "name": display_name(&user),
+                    "status": user.status,
+                    "last_login": user.last_login
+                }),
+                Err(e) => json!({"lookup_error": e.to_string()}),
+            };
+            people.insert(user_id, info);
+        }
+
+        let rows = access_worksheet(grants, &people);
+        Ok(json!({
+            "generated_at": Utc::now().to_rfc3339(),
+            "sensitive_roles": sensitive.iter().map(|id| json!({"id": id, "name": role_names[id]})).collect::<Vec<_>>(),
+            "users_with_elevated_access": rows.len(),
+            "warnings": warnings,
+            "worksheet": rows
+        }))
+    }
+
+    async fn app_access_report(&self, client: &OneLoginClient, args: AppAccessReportArgs) -> Result<Value> {
+        let app = client.apps.get_app(args.app_id).await
+            .map_err(|e| anyhow!("Failed to get app {}: {}", args.app_id, e))?;
+        let roles = client.roles.list_roles().await
+            .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+
+        let mut granting_roles = Vec::new();
+        for role in &roles {
+            let apps = client.roles.get_role_apps(role.id).await
+                .map_err(|e| anyhow!("Failed to get apps for role {}: {}", role.id, e))?;
+            if apps.iter().any(|a| a.id == Some(args.app_id)) {
+                granting_roles.push((role.id, role.name.clone().unwrap_or_else(|| format!("role {}", role.id))));
+            }
+        }
+
+        // Mapping rules that add each role; a missing mappings API just means no rule paths
+        let mut warnings = Vec::new();
+        let mut role_rules: HashMap<i64, Vec<Value>> = HashMap::new();
+        match client.user_mappings.list_mappings().await {
+            Ok(mappings) => {
+                for mapping in mappings.iter().filter(|m| m.enabled) {
+                    for role_id in mapping_role_ids(&mapping.actions) {
+                        role_rules
+                            .entry(role_id)
+                            .or_default()
+                            .push(json!({"rule_id": mapping.id, "rule_name": mapping.name}));
+                    }
+                }
+            }
+            Err(e) => warnings.push(format!("User mappings were not checked: {}", e)),
+        }
+
+        let mut people: HashMap<i64, Value> = HashMap::new();
+        let mut paths: BTreeMap<i64, Vec<Value>> = BTreeMap::new();
+        for (role_id, role_name) in &granting_roles {
+            let users = client.roles.get_role_users(*role_id).await
+                .map_err(|e| anyhow!("Failed to get users for role {}: {}", role_id, e))?;
+            for user in users {
+                let Some(user_id) = user.id else { continue };
+                people.entry(user_id).or_insert_with(|| {
+                    json!({"email": user.email, "username": user.username, "name": user.name})
+                });
+                paths.entry(user_id).or_default().extend(membership_paths(
+                    *role_id,
+                    role_name,
+                    user.assigned,
+                    role_rules.get(role_id).map(Vec::as_slice).unwrap_or_default(),
+                ));
+            }
+        }
+
+        let mut truncated = false;
+        if args.include_direct.unwrap_or(false) {
+            let (users, more) = fetch_all_users(client, args.max_pages.unwrap_or(50)).await?;
+            truncated = more;
+            let candidates: Vec<&User> = users.iter().filter(|u| !paths.contains_key(&u.id)).collect();
+            for user in candidates {
+                let apps = client.users.get_user_apps(user.id).await
+                    .map_err(|e| anyhow!("Failed to get apps for user {}: {}", user.id, e))?;
+                if apps.iter().any(|a| a.get("id").and_then(Value::as_i64) == Some(args.app_id)) {
+                    people.insert(
+                        user.id,
+                        json!({"email": user.email, "username": user.username, "name": display_name(user)}),
+                    );
+                    paths.insert(user.id, vec![json!({"type": "direct"})]);
+                }
+            }
+        }
+
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        let users: Vec<Value> = paths
+            .into_iter()
+            .map(|(user_id, paths)| {
+                for path in &paths {
+                    *counts.entry(path["type"].as_str().unwrap_or_default().to_string()).or_default() += 1;
+                }
+                let mut row = json!({"user_id": user_id});
+                if let Some(Value::Object(info)) = people.get(&user_id) {
+                    row.as_object_mut().unwrap().extend(info.clone());
+                }
+                row["paths"] = Value::Array(paths);
+                row
+            })
+            .collect();
+
+        Ok(json!({
+            "app": {"id": app.id, "name": app.name},
+            "granting_roles": granting_roles.iter().map(|(id, name)| json!({"id": id, "name": name})).collect::<Vec<_>>(),
+            "user_count": users.len(),
+            "path_counts": counts,
+            "truncated": truncated,
+            "warnings": warnings,
+            "users": users
+        }))
+    }
+
+    async fn locked_users_report(&self, client: &OneLoginClient, args: LockedUsersReportArgs) -> Result<Value> {
+        let min_failed_attempts = args.min_failed_attempts.unwrap_or(5).max(1);
+        let (users, truncated) = fetch_all_users(client, args.max_pages.unwrap_or(50)).await?;
+        let now = Utc::now();
+
+        let mut locked = Vec::new();
+        let mut at_risk = Vec::new();
+        for user in &users {
+            let entry = || {
+                let mut summary = user_summary(user, now);
+                summary["locked_until"] = json!(user.locked_until);
+                summary["invalid_login_attempts"] = json!(user.invalid_login_attempts);
+                summary
+            };
+            match lock_state(user, now, min_failed_attempts) {
+                Some(LockState::Locked) => locked.push((user.id, entry())),
+                Some(LockState::AtRisk) => at_risk.push(entry()),
+                None => {}
+            }
+        }
+
+        let mut unlock = Value::Null;
+        if let Some(requested) = args.unlock_user_ids.filter(|ids| !ids.is_empty()) {
+            let confirmed = args.confirm_unlock.unwrap_or(false);
+            let mut results = Vec::new();
+            let mut outcome = Outcome::default();
+            for user_id in requested {
+                let result = if !locked.iter().any(|(id, _)| *id == user_id) {
+                    json!({"user_id": user_id, "status": "skipped", "reason": "not locked"})
+                } else if !confirmed {
+                    json!({"user_id": user_id, "status": "would_unlock"})
+                } else {
+                    match client.users.unlock_user(user_id).await {
+                        Ok(()) => {
+                            outcome.succeeded(json!({"user_id": user_id}));
+                            json!({"user_id": user_id, "status": "unlocked"})
+                        }
+                        Err(e) => {
+                            outcome.failed(json!({"user_id": user_id}), &e);
+                            json!({"user_id": user_id, "status": "failed", "error": e.to_string()})
+                        }
+                    }
+                };
+                results.push(result);
+            }
+            unlock = json!({"confirmed": confirmed, "results": results});
+            if confirmed {
+                outcome.merge_into(&mut unlock);
+            }
+        }
+
+        Ok(json!({
+            "generated_at": now.to_rfc3339(),
+            "users_scanned": users.len(),
+            "truncated": truncated,
+            "counts": {"locked": locked.len(), "at_risk": at_risk.len()},
+            "locked": locked.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>(),
+            "at_risk": at_risk,
+            "unlock": unlock
+        }))
+    }
+
+    async fn password_expiry_report(&self, client: &OneLoginClient, args: PasswordExpiryReportArgs) -> Result<Value> {
+        let within_days = args.within_days.unwrap_or(14).max(0);
+        let include_expired = args.include_expired.unwrap_or(true);
+        let policies = client.password_policies.list_password_policies().await
+            .map_err(|e| anyhow!("Failed to list password policies: {}", e))?;
+        let default_policy = policies.iter().find(|p| p.default == Some(true)).and_then(|p| p.id);
+        let (users, truncated) = fetch_all_users(client, args.max_pages.unwrap_or(50)).await?;
+        let now = Utc::now();
+
+        let mut groups: BTreeMap<Option<i64>, Vec<(i64, Value)>> = BTreeMap::new();
+        let mut unknown_change_date = 0;
+        for user in &users {
+            let policy_id = user.policy_id.or(default_policy);
+            let policy = policies.iter().find(|p| p.id.is_some() && p.id == policy_id);
+            let Some(expiration_days) = policy.and_then(|p| p.expiration_days).filter(|d| *d > 0) else {
+                continue;
+            };
+            let Some((expires_at, days_left)) =
+                password_expiry(user.password_changed_at.as_deref(), expiration_days, now)
+            else {
+                unknown_change_date += 1;
+                continue;
+            };
+            let expired = expires_at <= now;
+            if (expired && !include_expired) || (!expired && days_left > within_days) {
+                continue;
+            }
+            let mut entry = user_summary(user, now);
+            entry["password_changed_at"] = json!(user.password_changed_at);
+            entry["expires_at"] = json!(expires_at.to_rfc3339());
+            entry["days_until_expiry"] = json!(days_left);
+            entry["expired"] = json!(expired);
+            groups.entry(policy_id).or_default().push((days_left, entry));
+        }
+
+        let mut total = 0;
+        let by_policy: Vec<Value> = groups
+            .into_iter()
+            .map(|(policy_id, mut entries)| {
+                entries.sort_by_key(|(days_left, _)| *days_left);
+                total += entries.len();
+                let policy = policies.iter().find(|p| p.id.is_some() && p.id == policy_id);
+                json!({
+                    "policy_id": policy_id,
+                    "policy_name": policy.and_then(|p| p.name.clone()),
+                    "expiration_days": policy.and_then(|p| p.expiration_days),
+                    "count": entries.len(),
+                    "users": entries.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "generated_at": now.to_rfc3339(),
+            "within_days": within_days,
+            "users_scanned": users.len(),
+            "truncated": truncated,
+            "expiring_count": total,
+            "unknown_password_change_date": unknown_change_date,
+            "by_policy": by_policy
+        }))
+    }
+
+    async fn certificate_expiry_report(&self, client: &OneLoginClient, args: CertificateExpiryReportArgs) -> Result<Value> {
+        let within_days = args.within_days.unwrap_or(30).max(0);
+        let apps = client.apps.list_apps(None).await
+            .map_err(|e| anyhow!("Failed to list apps: {}", e))?;
+        // Account certificates carry expiry dates even when an app only references one by ID
+        let mut warnings = Vec::new();
+        let certificates = client.certificates.list_certificates().await.unwrap_or_else(|e| {
+            warnings.push(format!("Account certificates were not loaded: {}", e));
+            Vec::new()
+        });
+        let now = Utc::now();
+
+        let mut rows = Vec::new();
+        for summary in apps.iter().filter(|a| a.auth_method == Some(AUTH_METHOD_SAML)) {
+            // List responses omit SSO details
+            let app = client.apps.get_app(summary.id).await
+                .map_err(|e| anyhow!("Failed to get app {}: {}", summary.id, e))?;
+            let cert = app_certificate(&app);
+            let not_after = certificate_expiry(&cert, &certificates);
+            let (status, days_left) = match not_after {
+                Some(not_after) => {
+                    let days_left = (not_after - now).num_days();
+                    let status = if not_after <= now {
+                        "expired"
+                    } else if days_left <= within_days {
+                        "expiring"
+                    } else {
+                        "ok"
+                    };
+                    (status, Some(days_left))
+                }
+                None => ("unknown", None),
+            };
+            rows.push(json!({
+                "app_id": app.id,
+                "app_name": app.name,
+                "certificate_id": cert.id,
+                "certificate_name": cert.name,
+                "not_after": not_after.map(|t| t.to_rfc3339()),
+                "days_until_expiry": days_left,
+                "status": status
+            }));
+        }
+
+        rows.sort_by_key(|row| row["days_until_expiry"].as_i64().unwrap_or(i64::MAX));
+        let scanned = rows.len();
+        let count = |status: &str| rows.iter().filter(|r| r["status"] == status).count();
+        let counts = json!({
+            "expired": count("expired"),
+            "expiring": count("expiring"),
+            "ok": count("ok"),
+            "unknown": count("unknown")
+        });
+        if !args.include_healthy.unwrap_or(false) {
+            rows.retain(|r| r["status"] != "ok");
+        }
+
+        Ok(json!({
+            "generated_at": now.to_rfc3339(),
+            "within_days": within_days,
+            "saml_apps_scanned": scanned,
+            "counts": counts,
+            "warnings": warnings,
+            "apps": rows
+        }))
+    }
+
+    async fn app_usage_report(&self, client: &OneLoginClient, args: AppUsageReportArgs) -> Result<Value> {
+        let days = args.days.unwrap_or(30).max(1);
+        let top = args.top.unwrap_or(10);
+        let now = Utc::now();
+        let since = now - Duration::days(days);
+        let params = EventQueryParams {
+            since: Some(since.to_rfc3339()),
+            event_type_id: Some(args.event_type_id.unwrap_or(EVENT_APP_LOGIN)),
+            ..Default::default()
+        };
+        let (events, pages, next_cursor) = client.events.list_events_paginated(params, args.max_pages.unwrap_or(20).max(1)).await
+            .map_err(|e| anyhow!("Failed to list events: {}", e))?;
+        let apps = client.apps.list_apps(None).await
+            .map_err(|e| anyhow!("Failed to list apps: {}", e))?;
+
+        let usage = aggregate_app_usage(&events);
+        let app_name = |id: i64, fallback: Option<&str>| {
+            apps.iter()
+                .find(|a| a.id == id)
+                .map(|a| a.name.clone())
+                .or_else(|| fallback.map(String::from))
+        };
+        let mut used: Vec<Value> = usage
+            .iter()
+            .map(|(app_id, u)| {
+                json!({
+                    "app_id": app_id,
+                    "app_name": app_name(*app_id, u.name.as_deref()),
+                    "launches": u.launches,
+                    "unique_users": u.users.len(),
+                    "last_launch": u.last_launch
+                })
+            })
+            .collect();
+        used.sort_by(|a, b| b["launches"].as_u64().cmp(&a["launches"].as_u64()));
+        let most_used: Vec<Value> = used.iter().take(top).cloned().collect();
+        let least_used: Vec<Value> = used.iter().rev().take(top).cloned().collect();
+        let unused: Vec<Value> = apps
+            .iter()
+            .filter(|a| !usage.contains_key(&a.id))
+            .map(|a| json!({"app_id": a.id, "app_name": a.name, "visible": a.visible}))
+            .collect();
+
+        Ok(json!({
+            "period": {"since": since.to_rfc3339(), "until": now.to_rfc3339(), "days": days},
+            "events_scanned": events.len(),
+            "pages": pages,
+            "truncated": next_cursor.is_some(),
+            "counts": {"apps": apps.len(), "used_apps": used.len(), "unused_apps": unused.len()},
+            "most_used": most_used,
+            "least_used": least_used,
+            "unused_apps": unused,
+            "per_app": used
+        }))
+    }
+
+    async fn mapping_compliance_report(&self, client: &OneLoginClient, args: MappingComplianceReportArgs) -> Result<Value> {
+        let mut mappings: Vec<UserMapping> = client.user_mappings.list_mappings().await
+            .map_err(|e| anyhow!("Failed to list user mappings: {}", e))?
+            .into_iter()
+            .filter(|m| m.enabled)
+            .collect();
+        mappings.sort_by_key(|m| m.position.unwrap_or(i32::MAX));
+        let groups = client.groups.list_groups().await
+            .map_err(|e| anyhow!("Failed to list groups: {}", e))?;
+
+        // Mappings we cannot evaluate would make every role they manage look unexpected
+        let (mappings, skipped): (Vec<UserMapping>, Vec<UserMapping>) =
+            mappings.into_iter().partition(|m| m.conditions.iter().all(condition_supported));
+        let managed_roles: HashSet<i64> = mappings.iter().flat_map(|m| mapping_role_ids(&m.actions)).collect();
+
+        let (mut users, truncated) = fetch_all_users(client, args.max_pages.unwrap_or(50)).await?;
+        let now = Utc::now();
+        let mut drifted = Vec::new();
+        for user in users.iter_mut() {
+            if user.role_ids.is_none() {
+                let role_ids = client.users.get_user_roles(user.id).await
+                    .map_err(|e| anyhow!("Failed to get roles for user {}: {}", user.id, e))?;
+                user.role_ids = Some(role_ids);
+            }
+            let drift = mapping_drift(user, &mappings, &managed_roles, &groups);
+            if drift.has_drift() {
+                let mut entry = user_summary(user, now);
+                entry["matched_mappings"] = json!(drift.matched);
+                entry["missing_roles"] = json!(drift.missing_roles);
+                entry["unexpected_roles"] = json!(drift.unexpected_roles);
+                entry["expected_group_id"] = json!(drift.expected_group);
+                entry["group_id"] = json!(user.group_id);
+                drifted.push(entry);
+            }
+        }
+
+        let drifted_count = drifted.len();
+        drifted.truncate(args.max_results.unwrap_or(200));
+        Ok(json!({
+            "generated_at": now.to_rfc3339(),
+            "users_scanned": users.len(),
+            "truncated": truncated,
+            "mappings_evaluated": mappings.iter().map(|m| json!({"id": m.id, "name": m.name})).collect::<Vec<_>>(),
+            "mappings_skipped": skipped.iter().map(|m| json!({"id": m.id, "name": m.name, "reason": "condition cannot be evaluated locally"})).collect::<Vec<_>>(),
+            "managed_role_ids": managed_roles.iter().collect::<BTreeSet<_>>(),
+            "drifted_count": drifted_count,
+            "drifted_users": drifted
+        }))
+    }
+
+    async fn session_compliance_report(&self, client: &OneLoginClient, args: SessionComplianceReportArgs) -> Result<Value> {
+        let thresholds = SessionThresholds::from(&args);
+        let settings = client.account.get_account_settings().await
+            .map_err(|e| anyhow!("Failed to get account settings: {}", e))?;
+        let mut warnings = Vec::new();
+        let password_policies = match client.password_policies.list_password_policies().await {
+            Ok(policies) => policies,
+            Err(e) => {
+                warnings.push(format!("Password policies could not be read, lockout checks skipped: {}", e));
+                Vec::new()
+            }
+        };
+
+        let mut policies = vec![policy_report(
+            json!({"type": "account", "name": settings.name.clone().unwrap_or_else(|| "Account".to_string())}),
+            session_checks(&settings, &thresholds),
+        )];
+        for policy in &password_policies {
+            policies.push(policy_report(
+                json!({
+                    "type": "password_policy",
+                    "id": policy.id,
+                    "name": policy.name,
+                    "default": policy.default,
+                    "usage_count": policy.usage_count
+                }),
+                lockout_checks(policy, &thresholds),
+            ));
+        }
+        let count = |status: &str| policies.iter().filter(|p| p["status"] == status).count();
+        let summary = json!({
+            "policies": policies.len(),
+            "pass": count("pass"),
+            "fail": count("fail"),
+            "unknown": count("unknown")
+        });
+
+        Ok(json!({
+            "generated_at": Utc::now().to_rfc3339(),
+            "compliant": count("fail") == 0,
+            "thresholds": {
+                "max_idle_minutes": thresholds.max_idle_minutes,
+                "max_session_hours": thresholds.max_session_minutes / 60,
+                "require_mfa": thresholds.require_mfa,
+                "max_failed_attempts": thresholds.max_failed_attempts
+            },
+            "summary": summary,
+            "warnings": warnings,
+            "policies": policies
+        }))
+    }
+}
+
+/// Account-wide session checks
+fn session_checks(settings: &AccountSettings, thresholds: &SessionThresholds) -> Vec<Value> {
+    vec![
+        limit_check("idle_timeout_minutes", settings.session_timeout, thresholds.max_idle_minutes),
+        limit_check("absolute_session_minutes", settings.absolute_session_timeout, thresholds.max_session_minutes),
+        json!({
+            "check": "mfa_required",
+            "status": match (settings.mfa_required, thresholds.require_mfa) {
+                (_, false) | (Some(true), true) => "pass",
+                (Some(false), true) => "fail",
+                (None, true) => "unknown",
+            },
+            "actual": settings.mfa_required,
+            "threshold": thresholds.require_mfa
+        }),
+    ]
+}
+
+/// Password policy lockout checks
+fn lockout_checks(policy: &PasswordPolicy, thresholds: &SessionThresholds) -> Vec<Value> {
+    vec![limit_check("max_failed_attempts", policy.max_failed_attempts, thresholds.max_failed_attempts)]
+}
+
+/// A setting that must be enabled and at most `max`. Zero means unlimited or disabled, which fails.
+fn limit_check(name: &str, actual: Option<i32>, max: i32) -> Value {
+    let status = match actual {
+        None => "unknown",
+        Some(value) if value > 0 && value <= max => "pass",
+        Some(_) => "fail",
+    };
+    json!({"check": name, "status": status, "actual": actual, "threshold": max})
+}
+
+/// A policy fails if any check fails; otherwise it is unknown if any check could not be evaluated
+fn policy_report(mut policy: Value, checks: Vec<Value>) -> Value {
+    let has = |status: &str| checks.iter().any(|c| c["status"] == status);
+    policy["status"] = json!(if has("fail") {
+        "fail"
+    } else if has("unknown") {
+        "unknown"
+    } else {
+        "pass"
+    });
+    policy["checks"] = json!(checks);
+    policy
+}
+
+/// Differences between a user's access and what matching mappings assign
+#[derive(Debug, Default)]
+struct MappingDrift {
+    matched: Vec<String>,
+    missing_roles: Vec<i64>,
+    unexpected_roles: Vec<i64>,
+    /// Group the first matching `set_groups` mapping assigns, when the user is in another
+    expected_group: Option<i64>,
+}
+
+impl MappingDrift {
+    fn has_drift(&self) -> bool {
+        !self.missing_roles.is_empty() || !self.unexpected_roles.is_empty() || self.expected_group.is_some()
+    }
+}
+
+fn mapping_drift(user: &User, mappings: &[UserMapping], managed_roles: &HashSet<i64>, groups: &[Group]) -> MappingDrift {
+    let mut drift = MappingDrift::default();
+    let mut expected_roles = HashSet::new();
+    let mut expected_group = None;
+    for mapping in mappings.iter().filter(|m| mapping_matches(m, user)) {
+        drift.matched.push(mapping.name.clone());
+        expected_roles.extend(mapping_role_ids(&mapping.actions));
+        if expected_group.is_none() {
+            expected_group = mapping
+                .actions
+                .iter()
+                .filter(|a| a.action == "set_groups")
+                .flat_map(|a| a.value.iter())
+                .find_map(|v| resolve_group(v, groups));
+        }
+    }
+    let held: HashSet<i64> = user.role_ids.iter().flatten().copied().collect();
+    drift.missing_roles = expected_roles.difference(&held).copied().collect();
+    drift.unexpected_roles = held
+        .iter()
+        .filter(|r| managed_roles.contains(r) && !expected_roles.contains(r))
+        .copied()
+        .collect();
+    drift.missing_roles.sort_unstable();
+    drift.unexpected_roles.sort_unstable();
+    drift.expected_group = expected_group.filter(|g| user.group_id != Some(*g));
+    drift
+}
+
+/// Group ID from a `set_groups` value, which may be an ID or a group name
+fn resolve_group(value: &str, groups: &[Group]) -> Option<i64> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .or_else(|| groups.iter().find(|g| g.name.eq_ignore_ascii_case(value.trim())).map(|g| g.id))
+}
+
+fn mapping_matches(mapping: &UserMapping, user: &User) -> bool {
+    let mut results = mapping.conditions.iter().map(|c| condition_matches(c, user));
+    if mapping.match_type == "any" {
+        results.any(|r| r)
+    } else {
+        results.all(|r| r)
+    }
+}
+
+fn condition_supported(condition: &MappingCondition) -> bool {
+    ["=", "!=", "~", "!~", ">", "<"].contains(&condition.operator.as_str())
+        && (condition.source.starts_with("custom_attribute_")
+            || ["email", "username", "firstname", "lastname", "title", "department", "company", "phone"]
+                .contains(&condition.source.as_str()))
+}
+
+/// Evaluate a supported condition; comparisons are case-insensitive and missing attributes are empty
+fn condition_matches(condition: &MappingCondition, user: &User) -> bool {
+    let attribute = match condition.source.as_str() {
+        "email" => user.email.clone(),
+        "username" => user.username.clone(),
+        "firstname" => user.firstname.clone(),
+        "lastname" => user.lastname.clone(),
+        "title" => user.title.clone(),
+        "department" => user.department.clone(),
+        "company" => user.company.clone(),
+        "phone" => user.phone.clone(),
+        source => source.strip_prefix("custom_attribute_").and_then(|name| {
+            user.custom_attributes.as_ref()?.get(name).map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+        }),
+    }
+    .unwrap_or_default()
+    .to_lowercase();
+    let expected = condition.value.to_lowercase();
+    match condition.operator.as_str() {
+        "=" => attribute == expected,
+        "!=" => attribute != expected,
+        "~" => attribute.contains(&expected),
+        "!~" => !attribute.contains(&expected),
+        ">" => attribute.starts_with(&expected),
+        "<" => attribute.ends_with(&expected),
+        _ => false,
+    }
+}
+
+/// Launch statistics for one app
+#[derive(Debug, Default)]
+struct AppUsage {
+    name: Option<String>,
+    launches: usize,
+    users: HashSet<i64>,
+    last_launch: Option<String>,
+}
+
+fn aggregate_app_usage(events: &[Event]) -> BTreeMap<i64, AppUsage> {
+    let mut usage: BTreeMap<i64, AppUsage> = BTreeMap::new();
+    for event in events {
+        let Some(app_id) = event.app_id else { continue };
+        let entry = usage.entry(app_id).or_default();
+        entry.launches += 1;
+        if entry.name.is_none() {
+            entry.name = event.app_name.clone();
+        }
+        if let Some(user_id) = event.user_id {
+            entry.users.insert(user_id);
+        }
+        // RFC 3339 timestamps in the same zone compare correctly as strings
+        if event.created_at > entry.last_launch {
+            entry.last_launch = event.created_at.clone();
+        }
+    }
+    usage
+}
+
+/// Signing certificate referenced by an app
+#[derive(Debug, Default, PartialEq)]
+struct AppCertificate {
+    id: Option<i64>,
+    name: Option<String>,
+    /// PEM body when the app response includes it
+    value: Option<String>,
+}
+
+/// Read the certificate from `sso.certificate`, falling back to `configuration.certificate_id`
+fn app_certificate(app: &App) -> AppCertificate {
+    let sso_cert = app
+        .extra
+        .as_ref()
+        .and_then(|extra| extra.get("sso"))
+        .and_then(|sso| sso.get("certificate"));
+    let configured_id = app
+        .configuration
+        .as_ref()
+        .and_then(|c| c.get("certificate_id"))
+        .and_then(Value::as_i64);
+    AppCertificate {
+        id: sso_cert.and_then(|c| c.get("id")).and_then(Value::as_i64).or(configured_id),
+        name: sso_cert.and_then(|c| c.get("name")).and_then(Value::as_str).map(String::from),
+        value: sso_cert.and_then(|c| c.get("value")).and_then(Value::as_str).map(String::from),
+    }
+}
+
+/// Expiry from the certificate body, or from the matching account certificate
+fn certificate_expiry(cert: &AppCertificate, certificates: &[Certificate]) -> Option<DateTime<Utc>> {
+    if let Some(validity) = cert.value.as_deref().and_then(|pem| certificate_validity(pem).ok()) {
+        return Some(validity.not_after);
+    }
+    let account_cert = certificates.iter().find(|c| c.id.is_some() && c.id == cert.id)?;
+    if let Some(not_after) = account_cert.not_after.as_deref() {
+        if let Ok(t) = DateTime::parse_from_rfc3339(not_after) {
+            return Some(t.with_timezone(&Utc));
+        }
+    }
+    account_cert
+        .certificate
+        .as_deref()
+        .and_then(|pem| certificate_validity(pem).ok())
+        .map(|v| v.not_after)
+}
+
+/// Expiry time and whole days remaining (negative once expired) for a password
+/// last changed at `changed_at` under a policy expiring after `expiration_days`
+fn password_expiry(changed_at: Option<&str>, expiration_days: i32, now: DateTime<Utc>) -> Option<(DateTime<Utc>, i64)> {
+    let changed_at = DateTime::parse_from_rfc3339(changed_at?).ok()?.with_timezone(&Utc);
+    let expires_at = changed_at + Duration::days(expiration_days as i64);
+    Some((expires_at, (expires_at - now).num_days()))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum LockState {
+    Locked,
+    /// Not locked, but failed logins have reached the threshold
+    AtRisk,
+}
+
+fn lock_state(user: &User, now: DateTime<Utc>, min_failed_attempts: i32) -> Option<LockState> {
+    let locked_until_future = user
+        .locked_until
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| t.with_timezone(&Utc) > now);
+    if user.status == STATUS_LOCKED || locked_until_future {
+        Some(LockState::Locked)
+    } else if user.invalid_login_attempts >= min_failed_attempts {
+        Some(LockState::AtRisk)
+    } else {
+        None
+    }
+}
+
+/// Role IDs assigned by a mapping's role actions (`set_role_ids`, ...)
+pub(crate) fn mapping_role_ids(actions: &[MappingAction]) -> Vec<i64> {
+    actions
+        .iter()
+        .filter(|a| a.action.contains("role"))
+        .flat_map(|a| a.value.iter().filter_map(|v| v.trim().parse().ok()))
+        .collect()
+}
+
+/// How a role member reaches the role's apps. Members OneLogin reports as not
+/// manually assigned got the role from a mapping rule.
+fn membership_paths(role_id: i64, role_name: &str, assigned: Option<bool>, rules: &[Value]) -> Vec<Value> {
+    if assigned == Some(false) && !rules.is_empty() {
+        rules
+            .iter()
+            .map(|rule| {
+                json!({
+                    "type": "rule",
+                    "rule_id": rule["rule_id"],
+                    "rule_name": rule["rule_name"],
+                    "role_id": role_id,
+                    "role_name": role_name
+                })
+            })
+            .collect()
+    } else {
+        vec![json!({"type": "role", "role_id": role_id, "role_name": role_name})]
+    }
+}
+
+/// One reason a user has elevated access
+struct AccessGrant {
+    user_id: i64,
+    /// `privilege`, `role` or `role_admin`
+    kind: &'static str,
+    id: String,
+    name: String,
+    /// Role through which a privilege is inherited
+    via_role: Option<String>,
+    added_at: Option<String>,
+}
+
+pub(crate) fn is_sensitive_role(id: i64, name: Option<&str>, configured: Option<&[String]>) -> bool {
+    let name = name.unwrap_or_default().to_lowercase();
+    match configured {
+        Some(patterns) => patterns
+            .iter()
+            .any(|p| p.trim() == id.to_string() || p.trim().to_lowercase() == name),
+        None => name.contains("admin"),
+    }
+}
+
+/// Group grants into one review row per user, ordered by user ID
+fn access_worksheet(grants: Vec<AccessGrant>, people: &HashMap<i64, Value>) -> Vec<Value> {
+    let mut by_user: BTreeMap<i64, Vec<Value>> = BTreeMap::new();
+    for grant in grants {
+        let access = by_user.entry(grant.user_id).or_default();
+        let entry = json!({
+            "type": grant.kind,
+            "id": grant.id,
+            "name": grant.name,
+            "via_role": grant.via_role,
+            "added_at": grant.added_at
+        });
+        if !access.contains(&entry) {
+            access.push(entry);
+        }
+    }
+    by_user
+        .into_iter()
+        .map(|(user_id, access)| {
+            let mut row = json!({"user_id": user_id});
+            if let Some(Value::Object(info)) = people.get(&user_id) {
+                row.as_object_mut().unwrap().extend(info.clone());
+            }
+            row["access"] = Value::Array(access);
+            row["decision"] = Value::Null;
+            row["reviewer_notes"] = Value::Null;
+            row
+        })
+        .collect()
+}
+
+/// Page through every user. Returns the users and whether `max_pages` cut the scan short.
+pub(crate) async fn fetch_all_users(client: &OneLoginClient, max_pages: u32) -> Result<(Vec<User>, bool)> {
+    let mut users = Vec::new();
+    for page in 1..=max_pages.max(1) {
+        let params = UserQueryParams {
+            limit: Some(SCAN_PAGE_SIZE),
+            page: Some(page as i32),
+            ..Default::default()
+        };
+        let batch = client.users.list_users(Some(params)).await
+            .map_err(|e| anyhow!("Failed to list users (page {}): {}", page, e))?;
+        let last_page = batch.len() < SCAN_PAGE_SIZE as usize;
+        users.extend(batch);
+        if last_page {
+            return Ok((users, false));
+        }
+    }
+    Ok((users, true))
+}
+
+/// Whole days from an API timestamp to `now`; `None` when missing or unparseable
+pub(crate) fn days_since(timestamp: Option<&str>, now: DateTime<Utc>) -> Option<i64> {
+    let parsed = DateTime::parse_from_rfc3339(timestamp?).ok()?;
+    Some((now - parsed.with_timezone(&Utc)).num_days())
+}
+
+/// "First Last", skipping missing parts
+pub(crate) fn display_name(user: &User) -> String {
+    [user.firstname.as_deref(), user.lastname.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Compact user entry for report lists
+pub(crate) fn user_summary(user: &User, now: DateTime<Utc>) -> Value {
+    json!({
+        "id": user.id,
+        "email": user.email,
+        "username": user.username,
+        "name": display_name(user),
+        "status": user.status,
+        "last_login": user.last_login,
+        "days_since_login": days_since(user.last_login.as_deref(), now),
+        "created_at": user.created_at,
+        "role_ids": user.role_ids
+    })
+}
+
+fn classify_inactive_users(
+    users: &[User],
+    inactive_days: i64,
+    grace_days: i64,
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, Vec<&User>)> {
+    let older_than_grace = |user: &User| {
+        // Unknown creation dates count as old enough to report
+        !matches!(days_since(user.created_at.as_deref(), now), Some(days) if days < grace_days)
+    };
+    let mut inactive = Vec::new();
+    let mut never_logged_in = Vec::new();
+    let mut never_activated = Vec::new();
+    let mut suspended_with_roles = Vec::new();
+
+    for user in users {
+        match user.status {
+            STATUS_ACTIVE => match days_since(user.last_login.as_deref(), now) {
+                Some(days) if days >= inactive_days => inactive.push(user),
+                None if older_than_grace(user) => never_logged_in.push(user),
+                _ => {}
+            },
+            STATUS_UNACTIVATED if older_than_grace(user) => never_activated.push(user),
+            STATUS_SUSPENDED if user.role_ids.as_ref().is_some_and(|r| !r.is_empty()) => {
+                suspended_with_roles.push(user)
+            }
+            _ => {}
+        }
+    }
+
+    vec![
+        ("inactive", inactive),
+        ("never_logged_in", never_logged_in),
+        ("never_activated", never_activated),
+        ("suspended_with_roles", suspended_with_roles),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: i64, status: i32, last_login: Option<&str>, role_ids: Option<Vec<i64>>) -> User {
+        serde_json::from_value(json!({
+            "id": id,
+            "status": status,
+            "last_login": last_login,
+            "created_at": "2023-01-01T00:00:00Z",
+            "role_ids": role_ids
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_classify_inactive_users() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let users = vec![
+            user(1, STATUS_ACTIVE, Some("2024-05-30T00:00:00Z"), None),
+            user(2, STATUS_ACTIVE, Some("2023-12-01T00:00:00Z"), None),
+            user(3, STATUS_ACTIVE, None, None),
+            user(4, STATUS_UNACTIVATED, None, None),
+            user(5, STATUS_SUSPENDED, None, Some(vec![10])),
+            user(6, STATUS_SUSPENDED, None, Some(vec![])),
+        ];
+        let report = classify_inactive_users(&users, 90, 14, now);
+        let ids = |name: &str| -> Vec<i64> {
+            report.iter().find(|(c, _)| *c == name).unwrap().1.iter().map(|u| u.id).collect()
+        };
+        assert_eq!(ids("inactive"), vec![2]);
+        assert_eq!(ids("never_logged_in"), vec![3]);
+        assert_eq!(ids("never_activated"), vec![4]);
+        assert_eq!(ids("suspended_with_roles"), vec![5]);
+    }
+
+    #[test]
+    fn test_sensitive_roles_and_worksheet() {
+        assert!(is_sensitive_role(1, Some("Super Admins"), None));
+        assert!(!is_sensitive_role(2, Some("Sales"), None));
+        let configured = vec!["sales".to_string(), "7".to_string()];
+        assert!(is_sensitive_role(2, Some("Sales"), Some(&configured)));
+        assert!(is_sensitive_role(7, None, Some(&configured)));
+        assert!(!is_sensitive_role(1, Some("Super Admins"), Some(&configured)));
+
+        let grant = |user_id, kind, via_role: Option<&str>| AccessGrant {
+            user_id,
+            kind,
+            id: "1".into(),
+            name: "Admins".into(),
+            via_role: via_role.map(String::from),
+            added_at: None,
+        };
+        let grants = vec![
+            grant(20, "role", None),
+            grant(10, "privilege", Some("Admins")),
+            grant(10, "privilege", Some("Admins")),
+            grant(10, "role_admin", None),
+        ];
+        let people = HashMap::from([(10, json!({"email": "a@example.com"}))]);
+        let rows = access_worksheet(grants, &people);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["user_id"], 10);
+        assert_eq!(rows[0]["email"], "a@example.com");
+        assert_eq!(rows[0]["access"].as_array().unwrap().len(), 2);
+        assert!(rows[0]["decision"].is_null());
+        assert_eq!(rows[1]["access"][0]["type"], "role");
+    }
+
+    #[test]
+    fn test_lock_state() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let with = |status: i32, locked_until: Option<&str>, attempts: i32| -> User {
+            serde_json::from_value(json!({
+                "id": 1,
+                "status": status,
+                "locked_until": locked_until,
+                "invalid_login_attempts": attempts
+            }))
+            .unwrap()
+        };
+        assert_eq!(lock_state(&with(STATUS_LOCKED, None, 0), now, 5), Some(LockState::Locked));
+        assert_eq!(lock_state(&with(STATUS_ACTIVE, Some("2024-06-01T01:00:00Z"), 0), now, 5), Some(LockState::Locked));
+        assert_eq!(lock_state(&with(STATUS_ACTIVE, Some("2024-05-31T00:00:00Z"), 6), now, 5), Some(LockState::AtRisk));
+        assert_eq!(lock_state(&with(STATUS_ACTIVE, None, 2), now, 5), None);
+    }
+
+    #[test]
+    fn test_password_expiry() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let (expires_at, days_left) = password_expiry(Some("2024-05-01T12:00:00Z"), 45, now).unwrap();
+        assert_eq!(expires_at.to_rfc3339(), "2024-06-15T12:00:00+00:00");
+        assert_eq!(days_left, 14);
+        assert_eq!(password_expiry(Some("2024-04-01T00:00:00Z"), 30, now).unwrap().1, -31);
+        assert!(password_expiry(None, 30, now).is_none());
+    }
+
+    #[test]
+    fn test_app_certificate_sources() {
+        let app: App = serde_json::from_value(json!({
+            "id": 1,
+            "configuration": {"certificate_id": 42},
+            "sso": {"certificate": {"id": 7, "name": "Standard"}}
+        }))
+        .unwrap();
+        let cert = app_certificate(&app);
+        assert_eq!(cert, AppCertificate { id: Some(7), name: Some("Standard".into()), value: None });
+
+        let app: App = serde_json::from_value(json!({"id": 2, "configuration": {"certificate_id": 42}})).unwrap();
+        let cert = app_certificate(&app);
+        assert_eq!(cert.id, Some(42));
+
+        let certificates: Vec<Certificate> =
+            serde_json::from_value(json!([{"id": 42, "not_after": "2030-01-01T00:00:00Z"}])).unwrap();
+        assert_eq!(
+            certificate_expiry(&cert, &certificates).unwrap().to_rfc3339(),
+            "2030-01-01T00:00:00+00:00"
+        );
+        assert!(certificate_expiry(&AppCertificate::default(), &certificates).is_none());
+    }
+
+    #[test]
+    fn test_aggregate_app_usage() {
+        let events: Vec<Event> = serde_json::from_value(json!([
+            {"id": 1, "event_type_id": 8, "app_id": 10, "app_name": "Slack", "user_id": 1, "created_at": "2024-06-01T10:00:00Z"},
+            {"id": 2, "event_type_id": 8, "app_id": 10, "user_id": 1, "created_at": "2024-06-03T10:00:00Z"},
+            {"id": 3, "event_type_id": 8, "app_id": 10, "user_id": 2, "created_at": "2024-06-02T10:00:00Z"},
+            {"id": 4, "event_type_id": 8, "app_id": 20, "user_id": 2},
+            {"id": 5, "event_type_id": 8, "user_id": 3}
+        ]))
+        .unwrap();
+        let usage = aggregate_app_usage(&events);
+        assert_eq!(usage.len(), 2);
+        let slack = &usage[&10];
+        assert_eq!(slack.launches, 3);
+        assert_eq!(slack.users.len(), 2);
+        assert_eq!(slack.name.as_deref(), Some("Slack"));
+        assert_eq!(slack.last_launch.as_deref(), Some("2024-06-03T10:00:00Z"));
+        assert_eq!(usage[&20].launches, 1);
+    }
+
+    #[test]
+    fn test_mapping_drift() {
+        let mappings: Vec<UserMapping> = serde_json::from_value(json!([
+            {"id": 1, "name": "Engineering", "match": "all", "enabled": true,
+             "conditions": [{"source": "department", "operator": "=", "value": "engineering"}],
+             "actions": [{"action": "set_role_ids", "value": ["10"]}, {"action": "set_groups", "value": ["Eng"]}]},
+            {"id": 2, "name": "Sales", "match": "any", "enabled": true,
+             "conditions": [{"source": "department", "operator": "=", "value": "Sales"},
+                            {"source": "email", "operator": "<", "value": "@sales.example.com"}],
+             "actions": [{"action": "set_role_ids", "value": ["20"]}]}
+        ]))
+        .unwrap();
+        let groups: Vec<Group> = serde_json::from_value(json!([{"id": 5, "name": "Eng"}])).unwrap();
+        let managed: HashSet<i64> = [10, 20].into();
+        // Moved from Sales to Engineering but kept the Sales role, and 99 is managed by hand
+        let user: User = serde_json::from_value(json!({
+            "id": 1, "department": "Engineering", "email": "a@example.com", "role_ids": [20, 99]
+        }))
+        .unwrap();
+        let drift = mapping_drift(&user, &mappings, &managed, &groups);
+        assert_eq!(drift.matched, vec!["Engineering"]);
+        assert_eq!(drift.missing_roles, vec![10]);
+        assert_eq!(drift.unexpected_roles, vec![20]);
+        assert_eq!(drift.expected_group, Some(5));
+
+        let user: User = serde_json::from_value(json!({
+            "id": 2, "email": "b@SALES.example.com", "role_ids": [20]
+        }))
+        .unwrap();
+        assert!(!mapping_drift(&user, &mappings, &managed, &groups).has_drift());
+
+        let unsupported = MappingCondition { source: "member_of".into(), operator: "=".into(), value: "x".into() };
+        assert!(!condition_supported(&unsupported));
+    }
+
+    #[test]
+    fn test_session_checks() {
+        let thresholds = SessionThresholds::from(&SessionComplianceReportArgs::default());
+        let settings: AccountSettings = serde_json::from_value(json!({
+            "session_timeout": 30,
+            "absolute_session_timeout": 2880,
+            "mfa_required": true
+        }))
+        .unwrap();
+        let report = policy_report(json!({"type": "account"}), session_checks(&settings, &thresholds));
+        assert_eq!(report["status"], "fail");
+        let statuses: Vec<&str> = report["checks"].as_array().unwrap().iter().map(|c| c["status"].as_str().unwrap()).collect();
+        assert_eq!(statuses, vec!["pass", "fail", "pass"]);
+
+        let policy: PasswordPolicy = serde_json::from_value(json!({"id": 1, "max_failed_attempts": 0})).unwrap();
+        assert_eq!(lockout_checks(&policy, &thresholds)[0]["status"], "fail");
+        let policy: PasswordPolicy = serde_json::from_value(json!({"id": 2, "max_failed_attempts": 5})).unwrap();
+        assert_eq!(policy_report(json!({}), lockout_checks(&policy, &thresholds))["status"], "pass");
+
+        let unknown = policy_report(json!({}), vec![limit_check("idle_timeout_minutes", None, 60)]);
+        assert_eq!(unknown["status"], "unknown");
+    }
+
+    #[test]
+    fn test_app_access_paths() {
+        let actions = vec![
+            MappingAction { action: "set_role_ids".into(), value: vec!["5".into(), "6".into()] },
+            MappingAction { action: "set_status".into(), value: vec!["1".into()] },
+        ];
+        assert_eq!(mapping_role_ids(&actions), vec![5, 6]);
+
+        let rules = vec![json!({"rule_id": 9, "rule_name": "Engineering"})];
+        let mapped = membership_paths(5, "Eng", Some(false), &rules);
+        assert_eq!(mapped, vec![json!({"type": "rule", "rule_id": 9, "rule_name": "Engineering", "role_id": 5, "role_name": "Eng"})]);
+        assert_eq!(membership_paths(5, "Eng", Some(true), &rules)[0]["type"], "role");
+        assert_eq!(membership_paths(5, "Eng", Some(false), &[])[0]["type"], "role");
+    }
+}
@@ -0,0 +1,650 @@
+use crate::api::OneLoginClient;
+use crate::mcp::providers::compliance::{display_name, mapping_role_ids};
+use crate::models::users::User;
+use anyhow::{anyhow, bail, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Resource types the graph can start from
+const RESOURCE_TYPES: &[&str] = &["user", "role", "app", "mapping", "privilege"];
+
+const DEFAULT_DEPTH: usize = 2;
+const MAX_DEPTH: usize = 4;
+const DEFAULT_MAX_NODES: usize = 200;
+const MAX_NODES: usize = 1000;
+
+pub const DELETION_IMPACT_TOOL: &str = "onelogin_deletion_impact";
+
+/// How long an impact analysis satisfies `require_deletion_impact`
+const IMPACT_VALID_FOR: Duration = Duration::from_secs(15 * 60);
+
+/// Delete tools guarded by `require_deletion_impact`: (tool, ID argument, resource type)
+const GUARDED_DELETES: &[(&str, &str, &str)] = &[
+    ("onelogin_delete_role", "role_id", "role"),
+    ("onelogin_delete_app", "app_id", "app"),
+    ("onelogin_delete_user_mapping", "mapping_id", "mapping"),
+];
+
+/// Longest list of referencing resources included per kind
+const IMPACT_SAMPLE: usize = 25;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ResourceGraphArgs {
+    /// Starting resource type: user, role, app, mapping or privilege
+    pub resource_type: String,
+    /// ID of the starting resource
+    pub resource_id: String,
+    /// How many relationship hops to follow from the start. Default: 2, max: 4
+    pub depth: Option<usize>,
+    /// 'json' for nodes and edges or 'dot' for Graphviz. Default: json
+    pub format: Option<String>,
+    /// Stop adding nodes after this many. Default: 200, max: 1000
+    pub max_nodes: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct DeletionImpactArgs {
+    /// Resource about to be deleted: role, app or mapping
+    pub resource_type: String,
+    /// ID of the resource
+    pub resource_id: String,
+}
+
+tool_provider! {
+    /// Relationships between users, roles, apps, mappings and privileges
+    pub struct DependenciesProvider("dependencies") {
+        "onelogin_resource_graph" => resource_graph(ResourceGraphArgs):
+            "Build the graph of resources connected to a user, role, app, user mapping or privilege: users -has_role-> roles -grants-> apps, mappings -assigns-> roles, privileges -assigned_to-> roles/users. Follows relationships in both directions up to 'depth' hops. format='json' returns nodes and edges; format='dot' returns Graphviz source. Use before deleting or changing a resource to see what depends on it. Read-only.",
+        "onelogin_deletion_impact" => deletion_impact(DeletionImpactArgs):
+            "Report what references a role, app or user mapping before deleting it: for a role, its users (and how many came from mappings), admins, apps, the mappings that assign it and the privileges granted to it; for an app, the roles granting it, the users reaching it through them and its app rules; for a mapping, the roles it assigns and the users holding them through mappings. When the server is configured with require_deletion_impact, the delete tools refuse to run until this analysis was done for the same resource in the last 15 minutes. Read-only.",
+    }
+}
+
+impl DependenciesProvider {
+    async fn resource_graph(&self, client: &OneLoginClient, args: ResourceGraphArgs) -> Result<Value> {
+        let format = args.format.as_deref().unwrap_or("json");
+        if format != "json" && format != "dot" {
+            bail!("Unknown format '{}'. Expected 'json' or 'dot'", format);
+        }
+        let depth = args.depth.unwrap_or(DEFAULT_DEPTH).min(MAX_DEPTH);
+        let max_nodes = args.max_nodes.unwrap_or(DEFAULT_MAX_NODES).clamp(1, MAX_NODES);
+
+        let mut index = GraphIndex::default();
+        let graph = build_graph(client, &mut index, &args.resource_type, &args.resource_id, depth, max_nodes).await?;
+
+        if format == "dot" {
+            return Ok(json!({
+                "root": graph.root,
+                "format": "dot",
+                "node_count": graph.nodes.len(),
+                "edge_count": graph.edges.len(),
+                "truncated": graph.truncated,
+                "warnings": index.warnings,
+                "content": graph.render_dot()
+            }));
+        }
+        let mut result = graph.to_json();
+        result["depth"] = json!(depth);
+        result["warnings"] = json!(index.warnings);
+        Ok(result)
+    }
+}
+
+impl DependenciesProvider {
+    async fn deletion_impact(&self, client: &OneLoginClient, args: DeletionImpactArgs) -> Result<Value> {
+        if !GUARDED_DELETES.iter().any(|(_, _, kind)| *kind == args.resource_type) {
+            bail!("Deletion impact covers role, app and mapping, not '{}'", args.resource_type);
+        }
+        let resource = load_node(client, &args.resource_type, &args.resource_id).await?;
+        let mut index = GraphIndex::default();
+        let mut references = Map::new();
+        let mut details = Map::new();
+
+        match resource.kind {
+            "role" => {
+                let role_id: i64 = resource.id.parse()?;
+                let users = client.roles.get_role_users(role_id).await
+                    .map_err(|e| anyhow!("Failed to get users for role {}: {}", role_id, e))?;
+                let via_mappings = users.iter().filter(|u| u.assigned == Some(false)).count();
+                add_references(&mut references, &mut details, "users", users.iter().map(|u| json!({"id": u.id, "name": u.name, "email": u.email})).collect());
+                references.insert("users_assigned_by_mappings".to_string(), json!(via_mappings));
+                let admins = client.roles.get_role_admins(role_id).await
+                    .map_err(|e| anyhow!("Failed to get admins for role {}: {}", role_id, e))?;
+                add_references(&mut references, &mut details, "admins", admins.iter().map(|a| json!({"id": a.id, "name": a.name})).collect());
+                let apps = client.roles.get_role_apps(role_id).await
+                    .map_err(|e| anyhow!("Failed to get apps for role {}: {}", role_id, e))?;
+                add_references(&mut references, &mut details, "apps", apps.iter().map(|a| json!({"id": a.id, "name": a.name})).collect());
+                let mappings = index.mappings(client).await?.iter()
+                    .filter(|(_, _, roles)| roles.contains(&role_id))
+                    .map(|(id, name, _)| json!({"id": id, "name": name}))
+                    .collect();
+                add_references(&mut references, &mut details, "mappings", mappings);
+                let privileges = index.privileges(client).await.iter()
+                    .filter(|(_, _, _, roles)| roles.contains(&role_id))
+                    .map(|(id, name, _, _)| json!({"id": id, "name": name}))
+                    .collect();
+                add_references(&mut references, &mut details, "privileges", privileges);
+            }
+            "app" => {
+                let app_id: i64 = resource.id.parse()?;
+                let role_ids = index.app_roles(client).await?.get(&app_id).cloned().unwrap_or_default();
+                let mut roles = Vec::new();
+                let mut users = HashSet::new();
+                for role_id in role_ids {
+                    roles.push(json!({"id": role_id, "name": index.role(client, role_id).await?.label}));
+                    for user in client.roles.get_role_users(role_id).await
+                        .map_err(|e| anyhow!("Failed to get users for role {}: {}", role_id, e))?
+                    {
+                        users.extend(user.id);
+                    }
+                }
+                add_references(&mut references, &mut details, "roles", roles);
+                references.insert("users_with_access".to_string(), json!(users.len()));
+                let rules = client.app_rules.list_rules(app_id, None).await
+                    .map_err(|e| anyhow!("Failed to list rules for app {}: {}", app_id, e))?;
+                add_references(&mut references, &mut details, "rules", rules.iter().map(|r| json!({"id": r.id, "name": r.name, "enabled": r.enabled})).collect());
+            }
+            _ => {
+                let mapping = client.user_mappings.get_mapping(&resource.id).await
+                    .map_err(|e| anyhow!("Failed to get user mapping {}: {}", resource.id, e))?;
+                let mut roles = Vec::new();
+                let mut users = HashSet::new();
+                for role_id in mapping_role_ids(&mapping.actions) {
+                    roles.push(json!({"id": role_id, "name": index.role(client, role_id).await?.label}));
+                    for user in client.roles.get_role_users(role_id).await
+                        .map_err(|e| anyhow!("Failed to get users for role {}: {}", role_id, e))?
+                    {
+                        if user.assigned == Some(false) {
+                            users.extend(user.id);
+                        }
+                    }
+                }
+                add_references(&mut references, &mut details, "roles", roles);
+                references.insert("users_assigned_by_mappings".to_string(), json!(users.len()));
+                references.insert("conditions".to_string(), json!(mapping.conditions.len()));
+                references.insert("actions".to_string(), json!(mapping.actions.len()));
+            }
+        }
+
+        let total: u64 = ["users", "admins", "apps", "mappings", "privileges", "roles", "rules", "users_with_access"]
+            .iter()
+            .filter_map(|k| references.get(*k).and_then(Value::as_u64))
+            .sum();
+        Ok(json!({
+            "resource": {"type": resource.kind, "id": resource.id, "name": resource.label},
+            "references": references,
+            "details": details,
+            "total_references": total,
+            "safe_to_delete": total == 0,
+            "analyzed_at": chrono::Utc::now().to_rfc3339(),
+            "valid_for_minutes": IMPACT_VALID_FOR.as_secs() / 60,
+            "warnings": index.warnings
+        }))
+    }
+}
+
+/// Count `items` under `kind` and keep the first few as examples
+fn add_references(references: &mut Map<String, Value>, details: &mut Map<String, Value>, kind: &str, items: Vec<Value>) {
+    references.insert(kind.to_string(), json!(items.len()));
+    details.insert(kind.to_string(), json!(items.into_iter().take(IMPACT_SAMPLE).collect::<Vec<_>>()));
+}
+
+/// Recent deletion impact analyses, keyed by tenant and resource
+#[derive(Default)]
+pub struct ImpactLog {
+    analyzed: Mutex<HashMap<String, Instant>>,
+}
+
+impl ImpactLog {
+    /// Remember a successful `onelogin_deletion_impact` call
+    pub fn record(&self, tenant: &str, args: &Value) {
+        let (Some(kind), Some(id)) = (args["resource_type"].as_str(), arg_id(&args["resource_id"])) else {
+            return;
+        };
+        let mut analyzed = self.analyzed.lock().expect("impact log poisoned");
+        analyzed.retain(|_, at| at.elapsed() < IMPACT_VALID_FOR);
+        analyzed.insert(impact_key(tenant, kind, &id), Instant::now());
+    }
+
+    /// Err when `tool` is a guarded delete without a recent analysis of its target
+    pub fn check(&self, tenant: &str, tool: &str, args: &Value) -> Result<()> {
+        let Some((_, arg, kind)) = GUARDED_DELETES.iter().find(|(name, _, _)| *name == tool) else {
+            return Ok(());
+        };
+        let Some(id) = arg_id(&args[*arg]) else {
+            return Ok(());
+        };
+        let analyzed = self.analyzed.lock().expect("impact log poisoned");
+        match analyzed.get(&impact_key(tenant, kind, &id)) {
+            Some(at) if at.elapsed() < IMPACT_VALID_FOR => Ok(()),
+            _ => bail!(
+                "Deleting this {} requires a deletion impact analysis first. Run {} with resource_type='{}' and resource_id='{}', review the result, then retry.",
+                kind, DELETION_IMPACT_TOOL, kind, id
+            ),
+        }
+    }
+}
+
+fn impact_key(tenant: &str, kind: &str, id: &str) -> String {
+    format!("{}/{}:{}", tenant, kind, id)
+}
+
+fn arg_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Graph of resources around `resource_type`/`resource_id`, breadth first
+pub(crate) async fn build_graph(
+    client: &OneLoginClient,
+    index: &mut GraphIndex,
+    resource_type: &str,
+    resource_id: &str,
+    depth: usize,
+    max_nodes: usize,
+) -> Result<ResourceGraph> {
+    let start = load_node(client, resource_type, resource_id).await?;
+    let mut graph = ResourceGraph::new(start.clone());
+    let mut queue = VecDeque::from([(start, 0)]);
+    'walk: while let Some((node, hops)) = queue.pop_front() {
+        if hops >= depth {
+            continue;
+        }
+        for link in expand(client, index, &node).await? {
+            if !graph.nodes.contains_key(&link.node.key()) {
+                if graph.nodes.len() >= max_nodes {
+                    graph.truncated = true;
+                    break 'walk;
+                }
+                graph.add_node(link.node.clone(), hops + 1);
+                queue.push_back((link.node.clone(), hops + 1));
+            }
+            let (from, to) = if link.outgoing {
+                (node.key(), link.node.key())
+            } else {
+                (link.node.key(), node.key())
+            };
+            graph.edges.insert((from, to, link.relation));
+        }
+    }
+    Ok(graph)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NodeRef {
+    pub kind: &'static str,
+    pub id: String,
+    pub label: String,
+}
+
+impl NodeRef {
+    fn new(kind: &'static str, id: impl ToString, label: impl Into<String>) -> Self {
+        Self {
+            kind,
+            id: id.to_string(),
+            label: label.into(),
+        }
+    }
+
+    /// Node identifier within the graph, e.g. `role:123`
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.kind, self.id)
+    }
+}
+
+/// A neighbour of a node and the relationship to it
+struct Link {
+    node: NodeRef,
+    relation: &'static str,
+    /// Edge points from the expanded node to `node`
+    outgoing: bool,
+}
+
+impl Link {
+    fn outgoing(node: NodeRef, relation: &'static str) -> Self {
+        Self { node, relation, outgoing: true }
+    }
+
+    fn incoming(node: NodeRef, relation: &'static str) -> Self {
+        Self { node, relation, outgoing: false }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ResourceGraph {
+    pub root: String,
+    /// Node key -> (node, hops from the root)
+    pub nodes: BTreeMap<String, (NodeRef, usize)>,
+    /// (from key, to key, relation)
+    pub edges: BTreeSet<(String, String, &'static str)>,
+    pub truncated: bool,
+}
+
+impl ResourceGraph {
+    fn new(root: NodeRef) -> Self {
+        let mut graph = Self {
+            root: root.key(),
+            nodes: BTreeMap::new(),
+            edges: BTreeSet::new(),
+            truncated: false,
+        };
+        graph.add_node(root, 0);
+        graph
+    }
+
+    fn add_node(&mut self, node: NodeRef, hops: usize) {
+        self.nodes.entry(node.key()).or_insert((node, hops));
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "root": self.root,
+            "nodes": self.nodes.iter().map(|(key, (node, hops))| json!({
+                "key": key,
+                "type": node.kind,
+                "id": node.id,
+                "label": node.label,
+                "hops": hops
+            })).collect::<Vec<_>>(),
+            "edges": self.edges.iter().map(|(from, to, relation)| json!({
+                "from": from,
+                "to": to,
+                "relation": relation
+            })).collect::<Vec<_>>(),
+            "truncated": self.truncated
+        })
+    }
+
+    /// Graphviz source with one shape per resource type
+    pub fn render_dot(&self) -> String {
+        let mut out = String::from("digraph onelogin {\n  rankdir=LR;\n");
+        for (key, (node, _)) in &self.nodes {
+            let shape = match node.kind {
+                "user" => "ellipse",
+                "role" => "box",
+                "app" => "component",
+                "mapping" => "diamond",
+                _ => "hexagon",
+            };
+            let style = if *key == self.root { ", style=bold" } else { "" };
+            let _ = writeln!(
+                out,
+                "  {} [label={}, shape={}{}];",
+                dot_string(key),
+                dot_string(&format!("{}\n{}", node.kind, node.label)),
+                shape,
+                style
+            );
+        }
+        for (from, to, relation) in &self.edges {
+            let _ = writeln!(out, "  {} -> {} [label={}];", dot_string(from), dot_string(to), dot_string(relation));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// (privilege ID, name, user IDs, role IDs)
+type PrivilegeAssignments = (String, String, Vec<i64>, Vec<i64>);
+
+/// Tenant-wide lookups loaded on first use and shared across the walk
+#[derive(Default)]
+pub(crate) struct GraphIndex {
+    role_names: Option<HashMap<i64, String>>,
+    /// App ID -> roles granting it
+    app_roles: Option<HashMap<i64, Vec<i64>>>,
+    mappings: Option<Vec<(i64, String, Vec<i64>)>>,
+    privileges: Option<Vec<PrivilegeAssignments>>,
+    pub warnings: Vec<String>,
+}
+
+impl GraphIndex {
+    async fn role_names(&mut self, client: &OneLoginClient) -> Result<&HashMap<i64, String>> {
+        if self.role_names.is_none() {
+            let roles = client.roles.list_roles().await
+                .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+            self.role_names = Some(roles.into_iter().map(|r| (r.id, r.name.unwrap_or_default())).collect());
+        }
+        Ok(self.role_names.as_ref().unwrap())
+    }
+
+    async fn role(&mut self, client: &OneLoginClient, role_id: i64) -> Result<NodeRef> {
+        let name = self.role_names(client).await?.get(&role_id).cloned();
+        Ok(NodeRef::new("role", role_id, name.unwrap_or_else(|| format!("Role {}", role_id))))
+    }
+
+    async fn app_roles(&mut self, client: &OneLoginClient) -> Result<&HashMap<i64, Vec<i64>>> {
+        if self.app_roles.is_none() {
+            let role_ids: Vec<i64> = self.role_names(client).await?.keys().copied().collect();
+            let mut index: HashMap<i64, Vec<i64>> = HashMap::new();
+            for role_id in role_ids {
+                let apps = client.roles.get_role_apps(role_id).await
+                    .map_err(|e| anyhow!("Failed to get apps for role {}: {}", role_id, e))?;
+                for app_id in apps.into_iter().filter_map(|a| a.id) {
+                    index.entry(app_id).or_default().push(role_id);
+                }
+            }
+            self.app_roles = Some(index);
+        }
+        Ok(self.app_roles.as_ref().unwrap())
+    }
+
+    async fn mappings(&mut self, client: &OneLoginClient) -> Result<&[(i64, String, Vec<i64>)]> {
+        if self.mappings.is_none() {
+            let mappings = client.user_mappings.list_mappings().await
+                .map_err(|e| anyhow!("Failed to list user mappings: {}", e))?;
+            self.mappings = Some(
+                mappings
+                    .into_iter()
+                    .map(|m| (m.id, m.name, mapping_role_ids(&m.actions)))
+                    .collect(),
+            );
+        }
+        Ok(self.mappings.as_deref().unwrap())
+    }
+
+    /// Privileges with their assignments. Tenants without Delegated
+    /// Administration have none; that is recorded as a warning.
+    async fn privileges(&mut self, client: &OneLoginClient) -> &[PrivilegeAssignments] {
+        if self.privileges.is_none() {
+            let loaded = async {
+                let mut out = Vec::new();
+                for privilege in client.privileges.list_privileges().await? {
+                    let users = client.privileges.get_assigned_users(&privilege.id).await?;
+                    let roles = client.privileges.get_assigned_roles(&privilege.id).await?;
+                    out.push((privilege.id, privilege.name, users, roles));
+                }
+                Ok::<_, crate::core::error::OneLoginError>(out)
+            }
+            .await;
+            self.privileges = Some(loaded.unwrap_or_else(|e| {
+                self.warnings.push(format!("Privileges were not included: {}", e));
+                Vec::new()
+            }));
+        }
+        self.privileges.as_deref().unwrap()
+    }
+}
+
+/// Look up the starting resource, which also checks that it exists
+async fn load_node(client: &OneLoginClient, resource_type: &str, resource_id: &str) -> Result<NodeRef> {
+    let numeric = || {
+        resource_id
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| anyhow!("{} IDs are numeric, got '{}'", resource_type, resource_id))
+    };
+    let node = match resource_type {
+        "user" => {
+            let user = client.users.get_user(numeric()?).await
+                .map_err(|e| anyhow!("Failed to get user {}: {}", resource_id, e))?;
+            NodeRef::new("user", user.id, user_label(&user))
+        }
+        "role" => {
+            let role = client.roles.get_role(numeric()?).await
+                .map_err(|e| anyhow!("Failed to get role {}: {}", resource_id, e))?;
+            NodeRef::new("role", role.id, role.name.unwrap_or_default())
+        }
+        "app" => {
+            let app = client.apps.get_app(numeric()?).await
+                .map_err(|e| anyhow!("Failed to get app {}: {}", resource_id, e))?;
+            NodeRef::new("app", app.id, app.name)
+        }
+        "mapping" => {
+            let mapping = client.user_mappings.get_mapping(resource_id).await
+                .map_err(|e| anyhow!("Failed to get user mapping {}: {}", resource_id, e))?;
+            NodeRef::new("mapping", mapping.id, mapping.name)
+        }
+        "privilege" => {
+            let privilege = client.privileges.get_privilege(resource_id).await
+                .map_err(|e| anyhow!("Failed to get privilege {}: {}", resource_id, e))?;
+            NodeRef::new("privilege", privilege.id, privilege.name)
+        }
+        other => bail!("Unknown resource type '{}'. Expected one of: {}", other, RESOURCE_TYPES.join(", ")),
+    };
+    Ok(node)
+}
+
+fn user_label(user: &User) -> String {
+    let name = display_name(user);
+    if !name.is_empty() {
+        return name;
+    }
+    user.email.clone().or_else(|| user.username.clone()).unwrap_or_else(|| format!("User {}", user.id))
+}
+
+/// Resources directly related to `node`
+async fn expand(client: &OneLoginClient, index: &mut GraphIndex, node: &NodeRef) -> Result<Vec<Link>> {
+    let mut links = Vec::new();
+    match node.kind {
+        "user" => {
+            let user_id: i64 = node.id.parse()?;
+            for role_id in client.users.get_user_roles(user_id).await
+                .map_err(|e| anyhow!("Failed to get roles for user {}: {}", user_id, e))?
+            {
+                links.push(Link::outgoing(index.role(client, role_id).await?, "has_role"));
+            }
+            for (id, name, users, _) in index.privileges(client).await {
+                if users.contains(&user_id) {
+                    links.push(Link::incoming(NodeRef::new("privilege", id, name.clone()), "assigned_to"));
+                }
+            }
+        }
+        "role" => {
+            let role_id: i64 = node.id.parse()?;
+            for app in client.roles.get_role_apps(role_id).await
+                .map_err(|e| anyhow!("Failed to get apps for role {}: {}", role_id, e))?
+            {
+                if let Some(id) = app.id {
+                    links.push(Link::outgoing(NodeRef::new("app", id, app.name.unwrap_or_default()), "grants"));
+                }
+            }
+            for user in client.roles.get_role_users(role_id).await
+                .map_err(|e| anyhow!("Failed to get users for role {}: {}", role_id, e))?
+            {
+                if let Some(id) = user.id {
+                    let label = user.name.or(user.email).or(user.username).unwrap_or_else(|| format!("User {}", id));
+                    links.push(Link::incoming(NodeRef::new("user", id, label), "has_role"));
+                }
+            }
+            for (id, name, roles) in index.mappings(client).await? {
+                if roles.contains(&role_id) {
+                    links.push(Link::incoming(NodeRef::new("mapping", id, name.clone()), "assigns"));
+                }
+            }
+            for (id, name, _, roles) in index.privileges(client).await {
+                if roles.contains(&role_id) {
+                    links.push(Link::incoming(NodeRef::new("privilege", id, name.clone()), "assigned_to"));
+                }
+            }
+        }
+        "app" => {
+            let app_id: i64 = node.id.parse()?;
+            let role_ids = index.app_roles(client).await?.get(&app_id).cloned().unwrap_or_default();
+            for role_id in role_ids {
+                links.push(Link::incoming(index.role(client, role_id).await?, "grants"));
+            }
+        }
+        "mapping" => {
+            let role_ids = index
+                .mappings(client)
+                .await?
+                .iter()
+                .find(|(id, _, _)| id.to_string() == node.id)
+                .map(|(_, _, roles)| roles.clone())
+                .unwrap_or_default();
+            for role_id in role_ids {
+                links.push(Link::outgoing(index.role(client, role_id).await?, "assigns"));
+            }
+        }
+        _ => {
+            let assignments = index
+                .privileges(client)
+                .await
+                .iter()
+                .find(|(id, _, _, _)| *id == node.id)
+                .map(|(_, _, users, roles)| (users.clone(), roles.clone()));
+            if let Some((users, roles)) = assignments {
+                for role_id in roles {
+                    links.push(Link::outgoing(index.role(client, role_id).await?, "assigned_to"));
+                }
+                for user_id in users {
+                    links.push(Link::outgoing(NodeRef::new("user", user_id, format!("User {}", user_id)), "assigned_to"));
+                }
+            }
+        }
+    }
+    Ok(links)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impact_log_guards_deletes() {
+        let log = ImpactLog::default();
+        let delete = json!({"role_id": 42});
+        assert!(log.check("prod", "onelogin_delete_role", &delete).is_err());
+        assert!(log.check("prod", "onelogin_get_role", &delete).is_ok());
+
+        log.record("prod", &json!({"resource_type": "role", "resource_id": "42"}));
+        assert!(log.check("prod", "onelogin_delete_role", &delete).is_ok());
+        assert!(log.check("staging", "onelogin_delete_role", &delete).is_err());
+        assert!(log.check("prod", "onelogin_delete_app", &json!({"app_id": 42})).is_err());
+    }
+
+    #[test]
+    fn test_graph_json_and_dot() {
+        let role = NodeRef::new("role", 7, "Eng \"core\"");
+        let mut graph = ResourceGraph::new(role.clone());
+        let app = NodeRef::new("app", 3, "Slack");
+        graph.add_node(app.clone(), 1);
+        graph.add_node(NodeRef::new("app", 3, "duplicate"), 2);
+        graph.edges.insert((role.key(), app.key(), "grants"));
+
+        let value = graph.to_json();
+        assert_eq!(value["root"], "role:7");
+        assert_eq!(value["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(value["nodes"][0]["label"], "Slack");
+        assert_eq!(value["nodes"][0]["hops"], 1);
+        assert_eq!(value["edges"][0], json!({"from": "role:7", "to": "app:3", "relation": "grants"}));
+
+        let dot = graph.render_dot();
+        assert!(dot.starts_with("digraph onelogin {\n"));
+        assert!(dot.contains("\"role:7\" [label=\"role\\nEng \\\"core\\\"\", shape=box, style=bold];"));
+        assert!(dot.contains("\"role:7\" -> \"app:3\" [label=\"grants\"];"));
+    }
+}
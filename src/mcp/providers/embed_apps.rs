@@ -0,0 +1,85 @@
+use crate::api::OneLoginClient;
+use crate::models::embed_tokens::GenerateEmbedTokenRequest;
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BuildEmbedLaunchUrlArgs {
+    /// ID of the app to launch (see onelogin_list_embeddable_apps)
+    pub app_id: i64,
+    /// Email address of the user to launch the app for. Either email or user_id is required
+    pub email: Option<String>,
+    /// ID of the user to launch the app for; their email is looked up
+    pub user_id: Option<i64>,
+    /// Session duration in minutes. Default is based on account settings
+    pub session_duration: Option<i32>,
+    /// URL to redirect the user to after the app session ends. Must be within allowed domains
+    pub return_to_url: Option<String>,
+    /// Fail when app_id is not among the embeddable apps. Default: true
+    pub verify_app: Option<bool>,
+}
+
+tool_provider! {
+    /// Launch URLs for embeddable apps
+    pub struct EmbedAppsProvider("embed_tokens") {
+        "onelogin_build_embed_launch_url" => build_embed_launch_url(BuildEmbedLaunchUrlArgs):
+            "Build the URL that launches an embeddable app for a user: generates an embed token for the user and returns the launch URL to hand to your web app, with the token's expiry. The URL signs the user in, so treat it as a credential and hand it out before it expires. Checks that the app is embeddable unless verify_app is false.",
+    }
+}
+
+impl EmbedAppsProvider {
+    async fn build_embed_launch_url(&self, client: &OneLoginClient, args: BuildEmbedLaunchUrlArgs) -> Result<Value> {
+        let app_id = args.app_id;
+        let email = match (args.email, args.user_id) {
+            (Some(email), _) => email,
+            (None, Some(user_id)) => client
+                .users
+                .get_user(user_id)
+                .await
+                .map_err(|e| anyhow!("Failed to get user {}: {}", user_id, e))?
+                .email
+                .ok_or_else(|| anyhow!("User {} has no email address to generate an embed token for", user_id))?,
+            (None, None) => return Err(anyhow!("email or user_id is required")),
+        };
+
+        let app = if args.verify_app.unwrap_or(true) {
+            let apps = client
+                .embed_tokens
+                .list_embeddable_apps()
+                .await
+                .map_err(|e| anyhow!("Failed to list embeddable apps: {}", e))?;
+            let app = apps
+                .into_iter()
+                .find(|a| a.id == app_id)
+                .ok_or_else(|| anyhow!("App {} is not embeddable; see onelogin_list_embeddable_apps", app_id))?;
+            Some(app)
+        } else {
+            None
+        };
+
+        let request = GenerateEmbedTokenRequest {
+            email: email.clone(),
+            session_duration: args.session_duration,
+            return_to_url: args.return_to_url,
+        };
+        let token = client
+            .embed_tokens
+            .generate_embed_token(request)
+            .await
+            .map_err(|e| anyhow!("Failed to generate embed token: {}", e))?;
+        let expires_in_seconds = chrono::DateTime::parse_from_rfc3339(&token.expires_at)
+            .ok()
+            .map(|at| (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds().max(0));
+
+        Ok(json!({
+            "launch_url": client.embed_tokens.launch_url(app_id, &token.token),
+            "app_id": app_id,
+            "app_name": app.map(|a| a.name),
+            "email": email,
+            "expires_at": token.expires_at,
+            "expires_in_seconds": expires_in_seconds
+        }))
+    }
+}
@@ -0,0 +1,204 @@
+use crate::api::OneLoginClient;
+use crate::core::error::OneLoginError;
+use crate::mcp::outcome::Outcome;
+use crate::mcp::providers::compliance::{fetch_all_users, STATUS_UNACTIVATED};
+use crate::models::invitations::SendInviteLinkRequest;
+use crate::models::users::{User, UserQueryParams};
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::time::Duration;
+
+const DEFAULT_DELAY_MS: u64 = 250;
+const DEFAULT_MAX_INVITES: usize = 200;
+/// Longest pause after OneLogin rejects a send with 429
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct BulkInviteArgs {
+    /// Email addresses to invite. Each must belong to an existing user
+    pub emails: Option<Vec<String>>,
+    /// Invite every unactivated user instead of a list of emails. Default: false
+    pub all_unactivated: Option<bool>,
+    /// Message included in every invite email
+    pub custom_message: Option<String>,
+    /// Pause between sends in milliseconds, on top of the client rate limit. Default: 250
+    pub delay_ms: Option<u64>,
+    /// Maximum invites to send in one call; further recipients are reported as not sent. Default: 200
+    pub max_invites: Option<usize>,
+    /// Max pages of 100 users to scan when all_unactivated is set. Default: 50
+    pub max_pages: Option<u32>,
+    /// Must be true to send; otherwise the tool only previews who would be invited. Default: false
+    pub confirm: Option<bool>,
+}
+
+tool_provider! {
+    /// Bulk invitation sending
+    pub struct InvitationsProvider("invitations") {
+        "onelogin_send_bulk_invites" => send_bulk_invites(BulkInviteArgs):
+            "Send invite emails to a list of users by email, or to every unactivated user (all_unactivated=true). Users that are already activated, unknown or listed twice are skipped. Sends are paced by delay_ms and back off when OneLogin rate limits. Without confirm=true the tool only previews the recipients. Returns a per-recipient outcome: 'sent', 'would_send', 'skipped', 'failed' or 'not_sent', with a reason for anything not sent. Confirmed runs also return the standard bulk result: complete, succeeded, failed (with reasons) and not_attempted recipients.",
+    }
+}
+
+impl InvitationsProvider {
+    async fn send_bulk_invites(&self, client: &OneLoginClient, args: BulkInviteArgs) -> Result<Value> {
+        let all_unactivated = args.all_unactivated.unwrap_or(false);
+        let emails = args.emails.unwrap_or_default();
+        if all_unactivated != emails.is_empty() {
+            return Err(anyhow!("Provide either emails or all_unactivated=true"));
+        }
+
+        let (users, requested, truncated) = if all_unactivated {
+            let (users, truncated) = fetch_all_users(client, args.max_pages.unwrap_or(50)).await?;
+            let requested = users
+                .iter()
+                .filter(|u| u.status == STATUS_UNACTIVATED)
+                .filter_map(|u| u.email.clone())
+                .collect();
+            (users, requested, truncated)
+        } else {
+            let mut users = Vec::new();
+            for email in emails.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+                let params = UserQueryParams {
+                    email: Some(email.to_string()),
+                    ..Default::default()
+                };
+                let found = client.users.list_users(Some(params)).await
+                    .map_err(|e| anyhow!("Failed to look up user {}: {}", email, e))?;
+                users.extend(found);
+            }
+            (users, emails, false)
+        };
+
+        let plan = plan_recipients(&requested, &users);
+        let confirmed = args.confirm.unwrap_or(false);
+        let max_invites = args.max_invites.unwrap_or(DEFAULT_MAX_INVITES);
+        let mut delay = Duration::from_millis(args.delay_ms.unwrap_or(DEFAULT_DELAY_MS));
+        let mut results = plan.skipped;
+        let mut sent = 0;
+        let mut failed = 0;
+        let mut outcome = Outcome::default();
+
+        for (i, email) in plan.send.iter().enumerate() {
+            if i >= max_invites {
+                outcome.not_attempted(json!({"email": email}));
+                results.push(json!({"email": email, "status": "not_sent", "reason": format!("max_invites ({}) reached", max_invites)}));
+                continue;
+            }
+            if !confirmed {
+                results.push(json!({"email": email, "status": "would_send"}));
+                continue;
+            }
+            if i > 0 {
+                tokio::time::sleep(delay).await;
+            }
+
+            let mut sent_invite = send_invite(client, email, args.custom_message.clone()).await;
+            if matches!(sent_invite, Err(OneLoginError::RateLimitExceeded)) {
+                // Slow down for the rest of the run and give this recipient one more try
+                delay = (delay * 2).clamp(Duration::from_secs(1), MAX_BACKOFF);
+                tokio::time::sleep(delay).await;
+                sent_invite = send_invite(client, email, args.custom_message.clone()).await;
+            }
+            match sent_invite {
+                Ok(message) => {
+                    sent += 1;
+                    outcome.succeeded(json!({"email": email}));
+                    results.push(json!({"email": email, "status": "sent", "message": message}));
+                }
+                Err(e) => {
+                    failed += 1;
+                    outcome.failed(json!({"email": email}), &e);
+                    results.push(json!({"email": email, "status": "failed", "reason": e.to_string()}));
+                }
+            }
+        }
+
+        let skipped = results.iter().filter(|r| r["status"] == "skipped").count();
+        let not_sent = results.iter().filter(|r| r["status"] == "not_sent").count();
+        let mut result = json!({
+            "confirmed": confirmed,
+            "users_scanned_truncated": truncated,
+            "counts": {
+                "recipients": plan.send.len(),
+                "sent": sent,
+                "failed": failed,
+                "skipped": skipped,
+                "not_sent": not_sent
+            },
+            "results": results
+        });
+        if confirmed {
+            outcome.merge_into(&mut result);
+        }
+        Ok(result)
+    }
+}
+
+async fn send_invite(client: &OneLoginClient, email: &str, custom_message: Option<String>) -> crate::core::error::Result<String> {
+    let request = SendInviteLinkRequest {
+        email: email.to_string(),
+        personal_email: None,
+        custom_message,
+    };
+    client.invitations.send_invite_link(request).await.map(|r| r.message)
+}
+
+/// Emails to invite, and the skipped ones with reasons
+struct InvitePlan {
+    send: Vec<String>,
+    skipped: Vec<Value>,
+}
+
+/// Match requested emails (case-insensitively) against the users found for them
+fn plan_recipients(requested: &[String], users: &[User]) -> InvitePlan {
+    let mut send = Vec::new();
+    let mut skipped = Vec::new();
+    let mut seen = HashSet::new();
+    for email in requested {
+        let email = email.trim();
+        let skip = |reason: String| json!({"email": email, "status": "skipped", "reason": reason});
+        if email.is_empty() {
+            continue;
+        }
+        if !seen.insert(email.to_lowercase()) {
+            skipped.push(skip("listed more than once".to_string()));
+            continue;
+        }
+        match users.iter().find(|u| u.email.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(email))) {
+            None => skipped.push(skip("no user with this email".to_string())),
+            Some(user) if user.status != STATUS_UNACTIVATED => {
+                skipped.push(skip(format!("already activated (user {}, status {})", user.id, user.status)))
+            }
+            Some(_) => send.push(email.to_string()),
+        }
+    }
+    InvitePlan { send, skipped }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: i64, email: &str, status: i32) -> User {
+        serde_json::from_value(json!({"id": id, "email": email, "status": status})).unwrap()
+    }
+
+    #[test]
+    fn test_plan_recipients_skips_activated_unknown_and_duplicates() {
+        let users = vec![user(1, "new@example.com", 0), user(2, "active@example.com", 1)];
+        let requested: Vec<String> = ["New@Example.com", "active@example.com", "ghost@example.com", "new@example.com"]
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+
+        let plan = plan_recipients(&requested, &users);
+        assert_eq!(plan.send, vec!["New@Example.com"]);
+        let reasons: Vec<&str> = plan.skipped.iter().map(|s| s["reason"].as_str().unwrap()).collect();
+        assert!(reasons[0].starts_with("already activated"));
+        assert_eq!(reasons[1], "no user with this email");
+        assert_eq!(reasons[2], "listed more than once");
+    }
+}
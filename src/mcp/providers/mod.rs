@@ -0,0 +1,191 @@
+//! Self-contained tool groups.
+//!
+//! A provider owns the definitions and handlers for one API area. The
+//! `ToolRegistry` lists and dispatches every registered provider before
+//! falling back to the tools still defined in `tools.rs`, so a new area only
+//! needs a module here and an entry in `default_providers`.
+
+use crate::api::OneLoginClient;
+use anyhow::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Declare a provider from a list of tools.
+///
+/// Each entry names the tool, the handler method, its typed argument struct and
+/// a description. The macro generates the provider struct and its `ToolProvider`
+/// impl: definitions use `input_schema::<Args>()`, and calls coerce string-encoded
+/// scalars, deserialize into `Args` and invoke
+/// `async fn handler(&self, client: &OneLoginClient, args: Args) -> Result<Value>`.
+///
+/// ```ignore
+/// tool_provider! {
+///     /// Rate limit tools
+///     pub struct RateLimitsProvider("rate_limits") {
+///         "onelogin_get_rate_limits" => get_rate_limits(NoArgs): "Get rate limits",
+///     }
+/// }
+/// ```
+macro_rules! tool_provider {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $provider:ident($category:literal) {
+            $( $tool:literal => $handler:ident($args:ty): $description:expr ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $provider;
+
+        #[async_trait::async_trait]
+        impl $crate::mcp::providers::ToolProvider for $provider {
+            fn name(&self) -> &'static str {
+                $category
+            }
+
+            fn tools(&self) -> Vec<serde_json::Value> {
+                vec![
+                    $(
+                        serde_json::json!({
+                            "name": $tool,
+                            "description": $description,
+                            "inputSchema": $crate::mcp::schema::input_schema::<$args>(),
+                        }),
+                    )*
+                ]
+            }
+
+            async fn call(
+                &self,
+                client: &$crate::api::OneLoginClient,
+                tool: &str,
+                args: &serde_json::Value,
+            ) -> Option<anyhow::Result<serde_json::Value>> {
+                let result = match tool {
+                    $(
+                        $tool => {
+                            let schema = $crate::mcp::schema::input_schema::<$args>();
+                            let args = $crate::mcp::schema::coerce_to_schema(args, &schema);
+                            match serde_json::from_value::<$args>(args) {
+                                Ok(parsed) => self.$handler(client, parsed).await,
+                                Err(e) => Err(anyhow::anyhow!("Invalid arguments for {}: {}", $tool, e)),
+                            }
+                        }
+                    )*
+                    _ => return None,
+                };
+                Some(result)
+            }
+        }
+    };
+}
+
+pub mod api_auth;
+pub mod backup;
+pub mod compliance;
+pub mod dependencies;
+pub mod embed_apps;
+pub mod invitations;
+pub mod naming;
+pub mod org_chart;
+pub mod password_policies;
+pub mod rate_limits;
+pub mod registrations;
+pub mod risk_rules;
+pub mod saml_metadata;
+pub mod schema_drift;
+pub mod smart_hooks;
+pub mod terraform;
+pub mod threats;
+pub mod user_batch;
+pub mod user_devices;
+pub mod user_export;
+pub mod user_lifecycle;
+
+/// Arguments for tools that take no parameters
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct NoArgs {}
+
+#[async_trait]
+pub trait ToolProvider: Send + Sync {
+    /// Short identifier for logging (usually the matching tool category)
+    fn name(&self) -> &'static str;
+
+    /// Tool definitions (`name`, `description`, `inputSchema`) served by this provider
+    fn tools(&self) -> Vec<Value>;
+
+    /// Run `tool` against the resolved tenant client.
+    /// Returns `None` when the tool does not belong to this provider.
+    async fn call(&self, client: &OneLoginClient, tool: &str, args: &Value) -> Option<Result<Value>>;
+}
+
+/// All providers registered with the tool registry
+pub fn default_providers() -> Vec<Box<dyn ToolProvider>> {
+    vec![
+        Box::new(rate_limits::RateLimitsProvider),
+        Box::new(password_policies::PasswordPoliciesProvider),
+        Box::new(compliance::ComplianceProvider),
+        Box::new(terraform::TerraformProvider),
+        Box::new(backup::BackupProvider),
+        Box::new(dependencies::DependenciesProvider),
+        Box::new(org_chart::OrgChartProvider),
+        Box::new(invitations::InvitationsProvider),
+        Box::new(user_lifecycle::UserLifecycleProvider),
+        Box::new(user_devices::UserDevicesProvider),
+        Box::new(user_batch::UserBatchProvider),
+        Box::new(user_export::UserExportProvider),
+        Box::new(smart_hooks::SmartHooksProvider),
+        Box::new(saml_metadata::SamlMetadataProvider),
+        Box::new(risk_rules::RiskRulesProvider),
+        Box::new(schema_drift::SchemaDriftProvider),
+        Box::new(registrations::RegistrationsProvider),
+        Box::new(api_auth::ApiAuthProvider),
+        Box::new(naming::NamingProvider),
+        Box::new(threats::ThreatsProvider),
+        Box::new(embed_apps::EmbedAppsProvider),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tool_config::TOOL_CATEGORIES;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_provider_tools_are_unique_and_categorized() {
+        let categorized: HashSet<&str> = TOOL_CATEGORIES
+            .iter()
+            .flat_map(|c| c.tools.iter().copied())
+            .collect();
+        let mut seen = HashSet::new();
+        for provider in default_providers() {
+            for tool in provider.tools() {
+                let name = tool["name"].as_str().unwrap().to_string();
+                assert!(tool.get("inputSchema").is_some(), "{} has no inputSchema", name);
+                assert!(categorized.contains(name.as_str()), "{} ({}) has no category", name, provider.name());
+                assert!(seen.insert(name.clone()), "{} registered twice", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_macro_generates_schemas_from_args() {
+        let tools = password_policies::PasswordPoliciesProvider.tools();
+        let update = tools
+            .iter()
+            .find(|t| t["name"] == "onelogin_update_password_policy")
+            .unwrap();
+        let props = update["inputSchema"]["properties"].as_object().unwrap();
+        assert!(props.contains_key("policy_id"));
+        assert!(props.contains_key("min_length"));
+        assert_eq!(update["inputSchema"]["required"], serde_json::json!(["policy_id"]));
+
+        let list = tools
+            .iter()
+            .find(|t| t["name"] == "onelogin_list_password_policies")
+            .unwrap();
+        assert_eq!(list["inputSchema"]["type"], "object");
+    }
+}
@@ -0,0 +1,259 @@
+use crate::api::OneLoginClient;
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Naming convention for one kind of resource. Every check given must pass.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct NamingRule {
+    /// Regex every name must match, e.g. "^(IT|HR|ENG)-[A-Za-z0-9 ]+$"
+    pub pattern: Option<String>,
+    /// Regex no name may match, e.g. "(?i)\\b(test|temp|copy of)\\b"
+    pub forbidden_pattern: Option<String>,
+    /// Regex whose first capture group extracts the tag from a name, e.g. "^\\[(\\w+)\\]". Names without a tag are violations
+    pub tag_pattern: Option<String>,
+    /// Tags a name may carry (with tag_pattern). Default: any tag
+    pub allowed_tags: Option<Vec<String>>,
+    /// Longest allowed name, in characters
+    pub max_length: Option<usize>,
+    /// Flag resources without a description (roles and apps only). Default: false
+    pub require_description: Option<bool>,
+    /// Names exempt from this rule (exact, case-insensitive)
+    pub exempt: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct LintNamingArgs {
+    /// Convention for role names
+    pub roles: Option<NamingRule>,
+    /// Convention for app names
+    pub apps: Option<NamingRule>,
+    /// Convention for user mapping names
+    pub mappings: Option<NamingRule>,
+    /// Also flag names used by more than one resource of the same kind (case-insensitive). Default: true
+    pub flag_duplicates: Option<bool>,
+}
+
+tool_provider! {
+    /// Naming convention checks for roles, apps and user mappings
+    pub struct NamingProvider("compliance") {
+        "onelogin_lint_naming" => lint_naming(LintNamingArgs):
+            "Check role, app and user mapping names against naming conventions and report every violation. Each kind takes its own rule: a regex names must match (pattern), one they must not match (forbidden_pattern), a tag extracted by tag_pattern's capture group that must be present and in allowed_tags, max_length, require_description and exempt names. Duplicate names are flagged unless flag_duplicates=false. Only kinds with a rule are checked. Read-only; suited to a scheduled job with fixed rules. Returns per kind the resources checked and the violations with their problems, plus 'passed' when there are none.",
+    }
+}
+
+impl NamingProvider {
+    async fn lint_naming(&self, client: &OneLoginClient, args: LintNamingArgs) -> Result<Value> {
+        if args.roles.is_none() && args.apps.is_none() && args.mappings.is_none() {
+            bail!("Pass a naming rule for at least one of roles, apps or mappings");
+        }
+        let flag_duplicates = args.flag_duplicates.unwrap_or(true);
+        // Compile every rule before fetching anything, so a bad regex fails fast
+        let roles_rule = args.roles.as_ref().map(|r| CompiledRule::compile("roles", r)).transpose()?;
+        let apps_rule = args.apps.as_ref().map(|r| CompiledRule::compile("apps", r)).transpose()?;
+        let mappings_rule = args.mappings.as_ref().map(|r| CompiledRule::compile("mappings", r)).transpose()?;
+
+        let mut report = serde_json::Map::new();
+        let mut total = 0;
+        if let Some(rule) = roles_rule {
+            let roles = client.roles.list_roles().await
+                .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+            let resources: Vec<Resource> = roles
+                .iter()
+                .map(|r| Resource { id: r.id, name: r.name.clone().unwrap_or_default(), description: r.description.clone() })
+                .collect();
+            let result = lint(&rule, &resources, flag_duplicates);
+            total += result.len();
+            report.insert("roles".to_string(), json!({"checked": resources.len(), "violations": result}));
+        }
+        if let Some(rule) = apps_rule {
+            let apps = client.apps.list_apps(None).await
+                .map_err(|e| anyhow!("Failed to list apps: {}", e))?;
+            let resources: Vec<Resource> = apps
+                .iter()
+                .map(|a| Resource { id: a.id, name: a.name.clone(), description: a.description.clone() })
+                .collect();
+            let result = lint(&rule, &resources, flag_duplicates);
+            total += result.len();
+            report.insert("apps".to_string(), json!({"checked": resources.len(), "violations": result}));
+        }
+        if let Some(rule) = mappings_rule {
+            let mappings = client.user_mappings.list_mappings().await
+                .map_err(|e| anyhow!("Failed to list user mappings: {}", e))?;
+            // Mappings have no description field
+            let resources: Vec<Resource> = mappings
+                .iter()
+                .map(|m| Resource { id: m.id, name: m.name.clone(), description: None })
+                .collect();
+            let result = lint(&rule.without_description_check(), &resources, flag_duplicates);
+            total += result.len();
+            report.insert("mappings".to_string(), json!({"checked": resources.len(), "violations": result}));
+        }
+
+        report.insert("total_violations".to_string(), json!(total));
+        report.insert("passed".to_string(), json!(total == 0));
+        Ok(Value::Object(report))
+    }
+}
+
+/// The parts of a role, app or mapping the linter looks at
+struct Resource {
+    id: i64,
+    name: String,
+    description: Option<String>,
+}
+
+/// A `NamingRule` with its regexes compiled
+#[derive(Debug)]
+struct CompiledRule {
+    pattern: Option<Regex>,
+    forbidden_pattern: Option<Regex>,
+    tag_pattern: Option<Regex>,
+    allowed_tags: Option<Vec<String>>,
+    max_length: Option<usize>,
+    require_description: bool,
+    exempt: Vec<String>,
+}
+
+impl CompiledRule {
+    fn compile(kind: &str, rule: &NamingRule) -> Result<Self> {
+        let regex = |field: &str, source: &Option<String>| -> Result<Option<Regex>> {
+            source
+                .as_deref()
+                .map(|s| Regex::new(s).map_err(|e| anyhow!("Invalid {}.{} regex '{}': {}", kind, field, s, e)))
+                .transpose()
+        };
+        let tag_pattern = regex("tag_pattern", &rule.tag_pattern)?;
+        if tag_pattern.as_ref().is_some_and(|t| t.captures_len() < 2) {
+            bail!("{}.tag_pattern needs a capture group around the tag, e.g. \"^\\\\[(\\\\w+)\\\\]\"", kind);
+        }
+        if rule.allowed_tags.is_some() && tag_pattern.is_none() {
+            bail!("{}.allowed_tags needs a tag_pattern to extract tags from names", kind);
+        }
+        Ok(Self {
+            pattern: regex("pattern", &rule.pattern)?,
+            forbidden_pattern: regex("forbidden_pattern", &rule.forbidden_pattern)?,
+            tag_pattern,
+            allowed_tags: rule.allowed_tags.clone(),
+            max_length: rule.max_length,
+            require_description: rule.require_description.unwrap_or(false),
+            exempt: rule.exempt.iter().flatten().map(|n| n.to_lowercase()).collect(),
+        })
+    }
+
+    fn without_description_check(self) -> Self {
+        Self { require_description: false, ..self }
+    }
+
+    /// Problems with one resource; empty when it follows the convention or is exempt
+    fn problems(&self, name: &str, description: Option<&str>) -> Vec<String> {
+        if self.exempt.contains(&name.to_lowercase()) {
+            return Vec::new();
+        }
+        let mut problems = Vec::new();
+        if name.trim().is_empty() {
+            problems.push("name is empty".to_string());
+        }
+        if let Some(pattern) = &self.pattern {
+            if !pattern.is_match(name) {
+                problems.push(format!("does not match pattern '{}'", pattern.as_str()));
+            }
+        }
+        if let Some(forbidden) = self.forbidden_pattern.as_ref().and_then(|f| f.find(name)) {
+            problems.push(format!("contains forbidden '{}'", forbidden.as_str()));
+        }
+        if let Some(tag_pattern) = &self.tag_pattern {
+            match tag_pattern.captures(name).and_then(|c| c.get(1)) {
+                None => problems.push("has no tag".to_string()),
+                Some(tag) => {
+                    if let Some(allowed) = self.allowed_tags.as_ref().filter(|a| !a.iter().any(|t| t == tag.as_str())) {
+                        problems.push(format!("tag '{}' is not one of {:?}", tag.as_str(), allowed));
+                    }
+                }
+            }
+        }
+        if let Some(max) = self.max_length {
+            let length = name.chars().count();
+            if length > max {
+                problems.push(format!("is {} characters long, more than {}", length, max));
+            }
+        }
+        if self.require_description && description.is_none_or(|d| d.trim().is_empty()) {
+            problems.push("has no description".to_string());
+        }
+        problems
+    }
+}
+
+/// Violations among `resources`, each with every problem found
+fn lint(rule: &CompiledRule, resources: &[Resource], flag_duplicates: bool) -> Vec<Value> {
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+    for resource in resources {
+        *name_counts.entry(resource.name.trim().to_lowercase()).or_default() += 1;
+    }
+    resources
+        .iter()
+        .filter_map(|resource| {
+            let mut problems = rule.problems(&resource.name, resource.description.as_deref());
+            let count = name_counts[&resource.name.trim().to_lowercase()];
+            if flag_duplicates && count > 1 && !resource.name.trim().is_empty() {
+                problems.push(format!("name is shared by {} resources", count));
+            }
+            (!problems.is_empty()).then(|| json!({"id": resource.id, "name": resource.name, "problems": problems}))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(id: i64, name: &str, description: Option<&str>) -> Resource {
+        Resource { id, name: name.to_string(), description: description.map(String::from) }
+    }
+
+    #[test]
+    fn test_rule_problems() {
+        let rule = CompiledRule::compile("roles", &NamingRule {
+            pattern: Some("^\\[[A-Z]+\\] ".to_string()),
+            forbidden_pattern: Some("(?i)\\btemp\\b".to_string()),
+            tag_pattern: Some("^\\[(\\w+)\\]".to_string()),
+            allowed_tags: Some(vec!["IT".to_string(), "HR".to_string()]),
+            max_length: Some(20),
+            require_description: Some(true),
+            exempt: Some(vec!["Default".to_string()]),
+        })
+        .unwrap();
+
+        assert!(rule.problems("[IT] Admins", Some("IT admins")).is_empty());
+        assert!(rule.problems("default", None).is_empty());
+        let problems = rule.problems("[OPS] Temp access for contractors", None);
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems[0].contains("forbidden 'Temp'"));
+        assert!(problems[1].contains("tag 'OPS'"));
+        assert!(problems[3].contains("no description"));
+        let problems = rule.problems("Admins", Some("x"));
+        assert!(problems[0].contains("does not match"));
+        assert_eq!(problems[1], "has no tag");
+    }
+
+    #[test]
+    fn test_compile_errors_and_duplicates() {
+        let bad = NamingRule { pattern: Some("([".to_string()), ..Default::default() };
+        assert!(CompiledRule::compile("apps", &bad).unwrap_err().to_string().contains("apps.pattern"));
+        let no_group = NamingRule { tag_pattern: Some("^\\[\\w+\\]".to_string()), ..Default::default() };
+        assert!(CompiledRule::compile("apps", &no_group).is_err());
+        let tags_only = NamingRule { allowed_tags: Some(vec!["IT".to_string()]), ..Default::default() };
+        assert!(CompiledRule::compile("apps", &tags_only).is_err());
+
+        let rule = CompiledRule::compile("apps", &NamingRule::default()).unwrap();
+        let resources = vec![resource(1, "Slack", None), resource(2, "slack ", None), resource(3, "Zoom", None)];
+        let violations = lint(&rule, &resources, true);
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0]["problems"][0].as_str().unwrap().contains("shared by 2"));
+        assert!(lint(&rule, &resources, false).is_empty());
+    }
+}
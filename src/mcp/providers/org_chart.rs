@@ -0,0 +1,175 @@
+use crate::api::OneLoginClient;
+use crate::mcp::providers::compliance::{display_name, fetch_all_users};
+use crate::models::users::User;
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::future::Future;
+
+const DEFAULT_MAX_DEPTH: usize = 20;
+const DEFAULT_MAX_PAGES: u32 = 50;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ManagerChainArgs {
+    /// The user whose management chain to resolve
+    pub user_id: i64,
+    /// Also list the user's direct reports. Requires scanning all users. Default: false
+    pub include_direct_reports: Option<bool>,
+    /// Maximum number of levels to walk up. Default: 20
+    pub max_depth: Option<usize>,
+    /// Maximum pages of 100 users to scan for direct reports. Default: 50
+    pub max_pages: Option<u32>,
+}
+
+tool_provider! {
+    /// Reporting lines from users' manager_user_id
+    pub struct OrgChartProvider("org_chart") {
+        "onelogin_get_manager_chain" => get_manager_chain(ManagerChainArgs):
+            "Resolve a user's management chain by following manager_user_id links: the direct manager first, up to the top of the organization. Optionally lists the user's direct reports. Useful for access reviews and approvals ('who is this user's manager's manager?'). stopped_because is 'top_of_chain', 'max_depth', 'cycle' (a manager loop in the data) or 'missing_manager' (a manager that no longer exists). Read-only.",
+    }
+}
+
+impl OrgChartProvider {
+    async fn get_manager_chain(&self, client: &OneLoginClient, args: ManagerChainArgs) -> Result<Value> {
+        let user = client.users.get_user(args.user_id).await
+            .map_err(|e| anyhow!("Failed to get user {}: {}", args.user_id, e))?;
+        let max_depth = args.max_depth.unwrap_or(DEFAULT_MAX_DEPTH).max(1);
+        let chain = walk_chain(&user, max_depth, |id| client.users.get_user(id)).await?;
+
+        let mut warnings = chain.warnings;
+        if user.manager_user_id.is_none() && user.manager_ad_id.is_some() {
+            warnings.push("The user's manager is only set by directory id (manager_ad_id), which cannot be resolved to a OneLogin user".to_string());
+        }
+
+        let mut result = json!({
+            "user": person(&user),
+            "chain": chain
+                .managers
+                .iter()
+                .enumerate()
+                .map(|(i, manager)| {
+                    let mut entry = person(manager);
+                    entry["level"] = json!(i + 1);
+                    entry
+                })
+                .collect::<Vec<_>>(),
+            "depth": chain.managers.len(),
+            "stopped_because": chain.stopped_because,
+            "warnings": warnings
+        });
+
+        if args.include_direct_reports.unwrap_or(false) {
+            let (users, truncated) = fetch_all_users(client, args.max_pages.unwrap_or(DEFAULT_MAX_PAGES)).await?;
+            let reports: Vec<Value> = users
+                .iter()
+                .filter(|u| u.manager_user_id == Some(user.id))
+                .map(person)
+                .collect();
+            result["direct_reports"] = json!(reports);
+            result["direct_reports_truncated"] = json!(truncated);
+        }
+        Ok(result)
+    }
+}
+
+/// Managers above a user, nearest first
+struct ManagerChain {
+    managers: Vec<User>,
+    stopped_because: &'static str,
+    warnings: Vec<String>,
+}
+
+async fn walk_chain<F, Fut>(user: &User, max_depth: usize, mut fetch: F) -> Result<ManagerChain>
+where
+    F: FnMut(i64) -> Fut,
+    Fut: Future<Output = crate::core::error::Result<User>>,
+{
+    let mut managers: Vec<User> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut seen = HashSet::from([user.id]);
+    let mut next = user.manager_user_id;
+    let stopped_because = loop {
+        let Some(manager_id) = next else {
+            break "top_of_chain";
+        };
+        if managers.len() >= max_depth {
+            break "max_depth";
+        }
+        if !seen.insert(manager_id) {
+            warnings.push(format!("Manager loop: user {} appears twice in the chain", manager_id));
+            break "cycle";
+        }
+        match fetch(manager_id).await {
+            Ok(manager) => {
+                next = manager.manager_user_id;
+                managers.push(manager);
+            }
+            Err(e) if e.is_not_found() => {
+                let below = managers.last().map_or(user.id, |m| m.id);
+                warnings.push(format!("User {} has manager_user_id {}, which does not exist", below, manager_id));
+                break "missing_manager";
+            }
+            Err(e) => return Err(anyhow!("Failed to get manager {}: {}", manager_id, e)),
+        }
+    };
+    Ok(ManagerChain { managers, stopped_because, warnings })
+}
+
+fn person(user: &User) -> Value {
+    json!({
+        "id": user.id,
+        "name": display_name(user),
+        "email": user.email,
+        "title": user.title,
+        "department": user.department,
+        "manager_user_id": user.manager_user_id
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::OneLoginError;
+    use std::collections::HashMap;
+
+    fn user(id: i64, manager: Option<i64>) -> User {
+        serde_json::from_value(json!({"id": id, "firstname": format!("U{}", id), "manager_user_id": manager})).unwrap()
+    }
+
+    async fn chain_for(users: &HashMap<i64, User>, start: i64, max_depth: usize) -> ManagerChain {
+        walk_chain(&users[&start], max_depth, |id| {
+            let found = users.get(&id).cloned().ok_or_else(|| OneLoginError::NotFound(id.to_string()));
+            async move { found }
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_walk_chain_stops_at_top_cycle_and_missing() {
+        let users: HashMap<i64, User> = [
+            user(1, Some(2)),
+            user(2, Some(3)),
+            user(3, None),
+            user(4, Some(5)),
+            user(5, Some(4)),
+            user(6, Some(99)),
+        ]
+        .into_iter()
+        .map(|u| (u.id, u))
+        .collect();
+
+        let chain = chain_for(&users, 1, 20).await;
+        assert_eq!(chain.managers.iter().map(|m| m.id).collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(chain.stopped_because, "top_of_chain");
+
+        assert_eq!(chain_for(&users, 1, 1).await.stopped_because, "max_depth");
+        assert_eq!(chain_for(&users, 4, 20).await.stopped_because, "cycle");
+
+        let missing = chain_for(&users, 6, 20).await;
+        assert_eq!(missing.stopped_because, "missing_manager");
+        assert!(missing.warnings[0].contains("99"));
+    }
+}
@@ -0,0 +1,313 @@
+use super::NoArgs;
+use crate::api::OneLoginClient;
+use crate::mcp::outcome::Outcome;
+use crate::models::password_policies::{CreatePasswordPolicyRequest, UpdatePasswordPolicyRequest};
+use crate::models::users::{User, UserQueryParams};
+use anyhow::{anyhow, bail, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+
+/// Most users one bulk assignment may select
+const MAX_BULK_USERS: usize = 1000;
+const DEFAULT_CHUNK_SIZE: usize = 25;
+const MAX_CHUNK_SIZE: usize = 50;
+/// Users requested per page when resolving a filter
+const FILTER_PAGE_SIZE: i32 = 100;
+
+/// Arguments for tools addressing a single policy
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PolicyIdArgs {
+    /// The password policy ID
+    pub policy_id: i64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdatePasswordPolicyArgs {
+    /// The password policy ID
+    pub policy_id: i64,
+    #[serde(flatten)]
+    pub policy: UpdatePasswordPolicyRequest,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AssignPasswordPolicyArgs {
+    /// The user ID
+    pub user_id: i64,
+    /// The password policy ID to assign
+    pub policy_id: i64,
+}
+
+/// Users to select by attribute; every criterion given must match
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct BulkUserFilter {
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub role_id: Option<i64>,
+    pub directory_id: Option<i64>,
+    /// Custom attribute values by shortname
+    pub custom_attributes: Option<BTreeMap<String, String>>,
+}
+
+impl BulkUserFilter {
+    fn is_empty(&self) -> bool {
+        self.email.is_none()
+            && self.username.is_none()
+            && self.role_id.is_none()
+            && self.directory_id.is_none()
+            && self.custom_attributes.as_ref().is_none_or(|c| c.is_empty())
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BulkAssignPasswordPolicyArgs {
+    /// The password policy ID to assign
+    pub policy_id: i64,
+    /// Users to assign the policy to
+    pub user_ids: Option<Vec<i64>>,
+    /// Select users by attribute; combined with user_ids when both are given
+    pub filter: Option<BulkUserFilter>,
+    /// Only preview the users that would change. Default: true
+    pub dry_run: Option<bool>,
+    /// Users updated concurrently per chunk, 1-50. Default: 25
+    pub chunk_size: Option<usize>,
+    /// Stop after the first chunk with a failure; later users are reported as not attempted. Default: true
+    pub stop_on_error: Option<bool>,
+}
+
+/// A user selected for a bulk assignment
+#[derive(Debug, Clone, PartialEq)]
+struct Target {
+    id: i64,
+    email: Option<String>,
+    /// Unknown for users given by ID only
+    current_policy_id: Option<i64>,
+}
+
+impl Target {
+    fn summary(&self) -> Value {
+        json!({"id": self.id, "email": self.email, "current_policy_id": self.current_policy_id})
+    }
+}
+
+tool_provider! {
+    /// Password policy CRUD and user assignment
+    pub struct PasswordPoliciesProvider("password_policies") {
+        "onelogin_list_password_policies" => list_password_policies(NoArgs):
+            "List all password policies",
+        "onelogin_get_password_policy" => get_password_policy(PolicyIdArgs):
+            "Get a specific password policy by ID",
+        "onelogin_create_password_policy" => create_password_policy(CreatePasswordPolicyRequest):
+            "Create a new password policy",
+        "onelogin_update_password_policy" => update_password_policy(UpdatePasswordPolicyArgs):
+            "Update an existing password policy",
+        "onelogin_delete_password_policy" => delete_password_policy(PolicyIdArgs):
+            "Delete a password policy. Users assigned to the policy fall back to the account default policy.",
+        "onelogin_assign_password_policy_to_user" => assign_password_policy_to_user(AssignPasswordPolicyArgs):
+            "Assign a password policy to a user. The policy applies the next time the user sets or changes their password.",
+        "onelogin_bulk_assign_password_policy" => bulk_assign_password_policy(BulkAssignPasswordPolicyArgs):
+            "Assign a password policy to many users, given as user_ids and/or a filter (email, username, role_id, directory_id, custom_attributes; at least one criterion). dry_run defaults to true and only previews the selected users; users found by the filter who already have the policy are skipped. With dry_run=false users are updated in chunks of chunk_size, and by default the run stops after a chunk with failures. Returns the standard bulk result: complete, succeeded, failed (with reasons) and not_attempted user lists. At most 1000 users per call.",
+    }
+}
+
+impl PasswordPoliciesProvider {
+    async fn list_password_policies(&self, client: &OneLoginClient, _args: NoArgs) -> Result<Value> {
+        let result = client.password_policies.list_password_policies().await
+            .map_err(|e| anyhow!("Failed to list password policies: {}", e))?;
+        Ok(serde_json::to_value(result)?)
+    }
+
+    async fn get_password_policy(&self, client: &OneLoginClient, args: PolicyIdArgs) -> Result<Value> {
+        let result = client.password_policies.get_password_policy(args.policy_id).await
+            .map_err(|e| anyhow!("Failed to get password policy: {}", e))?;
+        Ok(serde_json::to_value(result)?)
+    }
+
+    async fn create_password_policy(
+        &self,
+        client: &OneLoginClient,
+        args: CreatePasswordPolicyRequest,
+    ) -> Result<Value> {
+        let result = client.password_policies.create_password_policy(args).await
+            .map_err(|e| anyhow!("Failed to create password policy: {}", e))?;
+        Ok(serde_json::to_value(result)?)
+    }
+
+    async fn update_password_policy(
+        &self,
+        client: &OneLoginClient,
+        args: UpdatePasswordPolicyArgs,
+    ) -> Result<Value> {
+        let result = client.password_policies.update_password_policy(args.policy_id, args.policy).await
+            .map_err(|e| anyhow!("Failed to update password policy: {}", e))?;
+        Ok(serde_json::to_value(result)?)
+    }
+
+    async fn delete_password_policy(&self, client: &OneLoginClient, args: PolicyIdArgs) -> Result<Value> {
+        client.password_policies.delete_password_policy(args.policy_id).await
+            .map_err(|e| anyhow!("Failed to delete password policy: {}", e))?;
+        Ok(json!({"success": true, "policy_id": args.policy_id}))
+    }
+
+    async fn assign_password_policy_to_user(
+        &self,
+        client: &OneLoginClient,
+        args: AssignPasswordPolicyArgs,
+    ) -> Result<Value> {
+        client.password_policies.assign_password_policy_to_user(args.user_id, args.policy_id).await
+            .map_err(|e| anyhow!("Failed to assign password policy: {}", e))?;
+        Ok(json!({"success": true, "user_id": args.user_id, "policy_id": args.policy_id}))
+    }
+
+    async fn bulk_assign_password_policy(
+        &self,
+        client: &OneLoginClient,
+        args: BulkAssignPasswordPolicyArgs,
+    ) -> Result<Value> {
+        let chunk_size = args.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE).clamp(1, MAX_CHUNK_SIZE);
+        let policy = client.password_policies.get_password_policy(args.policy_id).await
+            .map_err(|e| anyhow!("Failed to get password policy {}: {}", args.policy_id, e))?;
+
+        let mut listed = Vec::new();
+        if let Some(filter) = &args.filter {
+            if filter.is_empty() {
+                bail!("filter needs at least one criterion; pass user_ids to target specific users");
+            }
+            listed = fetch_filtered_users(client, filter).await?;
+        }
+        let user_ids = args.user_ids.unwrap_or_default();
+        if user_ids.is_empty() && args.filter.is_none() {
+            bail!("Pass user_ids and/or filter to select users");
+        }
+        let (targets, already_assigned) = select_targets(&user_ids, &listed, args.policy_id);
+        if targets.len() > MAX_BULK_USERS {
+            bail!(
+                "{} users selected; at most {} can be assigned per call. Narrow the filter or split the user_ids.",
+                targets.len(),
+                MAX_BULK_USERS
+            );
+        }
+
+        let policy_summary = json!({"id": args.policy_id, "name": policy.name});
+        if args.dry_run.unwrap_or(true) {
+            return Ok(json!({
+                "dry_run": true,
+                "policy": policy_summary,
+                "to_assign": targets.len(),
+                "chunks": targets.len().div_ceil(chunk_size),
+                "already_assigned": already_assigned,
+                "users": targets.iter().map(Target::summary).collect::<Vec<_>>()
+            }));
+        }
+
+        let stop_on_error = args.stop_on_error.unwrap_or(true);
+        let mut outcome = Outcome::default();
+        for chunk in targets.chunks(chunk_size) {
+            if stop_on_error && outcome.has_failures() {
+                for target in chunk {
+                    outcome.not_attempted(json!({"user_id": target.id}));
+                }
+                continue;
+            }
+            let results = futures::future::join_all(
+                chunk.iter().map(|t| client.password_policies.assign_password_policy_to_user(t.id, args.policy_id)),
+            )
+            .await;
+            for (target, result) in chunk.iter().zip(results) {
+                match result {
+                    Ok(_) => outcome.succeeded(json!({"user_id": target.id})),
+                    Err(e) => outcome.failed(json!({"user_id": target.id}), e),
+                }
+            }
+        }
+
+        let mut result = json!({
+            "dry_run": false,
+            "policy": policy_summary,
+            "already_assigned": already_assigned
+        });
+        outcome.merge_into(&mut result);
+        Ok(result)
+    }
+}
+
+/// Every user matching `filter`, up to one page past the bulk limit so oversize selections are detected
+async fn fetch_filtered_users(client: &OneLoginClient, filter: &BulkUserFilter) -> Result<Vec<User>> {
+    let mut users = Vec::new();
+    let mut page = 1;
+    loop {
+        let params = UserQueryParams {
+            limit: Some(FILTER_PAGE_SIZE),
+            page: Some(page),
+            email: filter.email.clone(),
+            username: filter.username.clone(),
+            role_id: filter.role_id,
+            directory_id: filter.directory_id,
+            custom_attributes: filter.custom_attributes.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+        let batch = client.users.list_users(Some(params)).await
+            .map_err(|e| anyhow!("Failed to list users (page {}): {}", page, e))?;
+        let last_page = batch.len() < FILTER_PAGE_SIZE as usize;
+        users.extend(batch);
+        if last_page || users.len() > MAX_BULK_USERS {
+            return Ok(users);
+        }
+        page += 1;
+    }
+}
+
+/// Users to update, in the order given, and the listed users that already have the policy.
+/// Explicit IDs are always assigned since their current policy is not known.
+fn select_targets(user_ids: &[i64], listed: &[User], policy_id: i64) -> (Vec<Target>, Vec<i64>) {
+    let mut seen = HashSet::new();
+    let mut targets = Vec::new();
+    let mut already_assigned = Vec::new();
+    for user in listed {
+        if !seen.insert(user.id) {
+            continue;
+        }
+        if user.policy_id == Some(policy_id) {
+            already_assigned.push(user.id);
+        } else {
+            targets.push(Target {
+                id: user.id,
+                email: user.email.clone(),
+                current_policy_id: user.policy_id,
+            });
+        }
+    }
+    for &id in user_ids {
+        if seen.insert(id) {
+            targets.push(Target {
+                id,
+                email: None,
+                current_policy_id: None,
+            });
+        }
+    }
+    (targets, already_assigned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_targets() {
+        let listed: Vec<User> = serde_json::from_value(json!([
+            {"id": 1, "email": "a@example.com", "policy_id": 7},
+            {"id": 2, "email": "b@example.com", "policy_id": 3},
+            {"id": 2, "email": "b@example.com", "policy_id": 3},
+            {"id": 4, "email": "d@example.com"}
+        ]))
+        .unwrap();
+        let (targets, already) = select_targets(&[2, 5, 5], &listed, 7);
+        assert_eq!(targets.iter().map(|t| t.id).collect::<Vec<_>>(), vec![2, 4, 5]);
+        assert_eq!(targets[0].current_policy_id, Some(3));
+        assert_eq!(targets[2].email, None);
+        assert_eq!(already, vec![1]);
+        assert!(BulkUserFilter::default().is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+use super::NoArgs;
+use crate::api::OneLoginClient;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+tool_provider! {
+    /// Rate limit status and local limiter statistics
+    pub struct RateLimitsProvider("rate_limits") {
+        "onelogin_get_rate_limit_status" => get_rate_limit_status(NoArgs):
+            "Get current API rate limit status including remaining requests and reset time",
+        "onelogin_get_rate_limits" => get_rate_limits(NoArgs):
+            "Get a combined view of rate limiting: OneLogin's reported limit, remaining requests and reset time from /auth/rate_limit, plus this server's local limiter (configured requests/second, requests throttled locally, 429 responses received), circuit breaker state, response compression savings (bytes on the wire vs decoded), retry settings and the shared retry budget (retries made, skipped for budget or because a POST/PATCH may already have been processed)",
+    }
+}
+
+impl RateLimitsProvider {
+    async fn get_rate_limit_status(&self, client: &OneLoginClient, _args: NoArgs) -> Result<Value> {
+        let result = client.rate_limits.get_rate_limit_status().await
+            .map_err(|e| anyhow!("Failed to get rate limit status: {}", e))?;
+        Ok(serde_json::to_value(result)?)
+    }
+
+    async fn get_rate_limits(&self, client: &OneLoginClient, _args: NoArgs) -> Result<Value> {
+        let result = client.rate_limits.get_rate_limit_overview().await
+            .map_err(|e| anyhow!("Failed to get rate limits: {}", e))?;
+        Ok(serde_json::to_value(result)?)
+    }
+}
@@ -0,0 +1,326 @@
+use crate::api::OneLoginClient;
+use crate::mcp::outcome::Outcome;
+use crate::models::self_registration::{ApproveRegistrationRequest, Registration};
+use crate::models::vigilance::{RiskContext, UserValidationRequest};
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const DEFAULT_MAX_RISK_LOOKUPS: usize = 50;
+/// Sent to risk/validate when the registration did not record the registrant's browser
+const UNKNOWN_USER_AGENT: &str = "unknown";
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RegistrationDecision {
+    /// Profile the registration belongs to
+    pub profile_id: i64,
+    /// Registration to decide on
+    pub registration_id: i64,
+    /// "approved" or "rejected"
+    pub status: String,
+    /// Why the registration is rejected. Falls back to the call's rejection_reason
+    pub rejection_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ModerateRegistrationsArgs {
+    /// Profiles to review. Default: every self-registration profile
+    pub profile_ids: Option<Vec<i64>>,
+    /// Score each pending registrant's email and IP with Vigilance risk/validate when the IP is known. Default: true
+    pub include_risk: Option<bool>,
+    /// Most registrations to score in one call; the rest are listed without a score. Default: 50
+    pub max_risk_lookups: Option<usize>,
+    /// Approvals and rejections to apply. Without decisions the tool only lists pending registrations
+    pub decisions: Option<Vec<RegistrationDecision>>,
+    /// Reason used for rejections that don't carry their own
+    pub rejection_reason: Option<String>,
+    /// Must be true to apply decisions; otherwise they are only validated and previewed. Default: false
+    pub confirm: Option<bool>,
+}
+
+tool_provider! {
+    /// Moderation of pending self-registrations
+    pub struct RegistrationsProvider("self_registration") {
+        "onelogin_moderate_registrations" => moderate_registrations(ModerateRegistrationsArgs):
+            "Review and decide pending self-registrations across profiles. Lists every pending registration (all profiles, or profile_ids) with its profile, and a Vigilance risk score for the registrant's email and IP when the registration recorded an IP (no MFA is sent). Pass decisions to approve or reject registrations in bulk; rejections need a reason, per decision or through rejection_reason. Without confirm=true decisions are only validated and previewed. Returns the pending list and a per-decision outcome: 'approved', 'rejected', 'would_approve', 'would_reject', 'skipped' or 'failed'. Confirmed runs also return the standard bulk result: complete, succeeded and failed (with reasons) decisions.",
+    }
+}
+
+impl RegistrationsProvider {
+    async fn moderate_registrations(&self, client: &OneLoginClient, args: ModerateRegistrationsArgs) -> Result<Value> {
+        let profiles = client.self_registration.list_profiles().await
+            .map_err(|e| anyhow!("Failed to list self-registration profiles: {}", e))?;
+        let decisions = args.decisions.unwrap_or_default();
+        let mut profile_ids: Vec<i64> = match &args.profile_ids {
+            Some(ids) => ids.clone(),
+            None => profiles.iter().map(|p| p.id).collect(),
+        };
+        // Decided registrations are checked against their profile's pending list
+        for decision in &decisions {
+            if !profile_ids.contains(&decision.profile_id) {
+                profile_ids.push(decision.profile_id);
+            }
+        }
+
+        let mut pending: Vec<(i64, Registration)> = Vec::new();
+        let mut warnings = Vec::new();
+        for &profile_id in &profile_ids {
+            match client.self_registration.list_registrations(profile_id).await {
+                Ok(registrations) => pending.extend(
+                    registrations
+                        .into_iter()
+                        .filter(is_pending)
+                        .map(|r| (profile_id, r)),
+                ),
+                Err(e) => warnings.push(format!("Could not list registrations of profile {}: {}", profile_id, e)),
+            }
+        }
+
+        let include_risk = args.include_risk.unwrap_or(true);
+        let max_lookups = args.max_risk_lookups.unwrap_or(DEFAULT_MAX_RISK_LOOKUPS);
+        let mut lookups = 0;
+        let mut rows = Vec::with_capacity(pending.len());
+        for (profile_id, registration) in &pending {
+            let profile_name = profiles.iter().find(|p| p.id == *profile_id).map(|p| p.name.clone());
+            let mut row = json!({
+                "profile_id": profile_id,
+                "profile_name": profile_name,
+                "registration": registration,
+                "risk": null
+            });
+            if include_risk {
+                row["risk"] = match (&registration.email, &registration.ip_address) {
+                    (Some(_), Some(_)) if lookups >= max_lookups => json!({"skipped": format!("max_risk_lookups ({}) reached", max_lookups)}),
+                    (Some(email), Some(ip)) => {
+                        lookups += 1;
+                        registrant_risk(client, email, ip, registration.user_agent.as_deref()).await
+                    }
+                    _ => json!({"skipped": "the registration has no email or IP address"}),
+                };
+            }
+            rows.push(row);
+        }
+
+        let confirmed = args.confirm.unwrap_or(false);
+        let (planned, mut outcomes) = plan_decisions(decisions, &pending, args.rejection_reason.as_deref());
+        let mut failed = 0;
+        let mut outcome = Outcome::default();
+        for decision in planned {
+            if !confirmed {
+                outcomes.push(decision.outcome(&format!("would_{}", decision.verb())));
+                continue;
+            }
+            let request = ApproveRegistrationRequest {
+                status: decision.status.clone(),
+                rejection_reason: decision.rejection_reason.clone(),
+            };
+            match client.self_registration.approve_registration(decision.profile_id, decision.registration_id, request).await {
+                Ok(_) => {
+                    outcome.succeeded(decision.item());
+                    outcomes.push(decision.outcome(&decision.status));
+                }
+                Err(e) => {
+                    failed += 1;
+                    outcome.failed(decision.item(), &e);
+                    let mut outcome = decision.outcome("failed");
+                    outcome["reason"] = json!(e.to_string());
+                    outcomes.push(outcome);
+                }
+            }
+        }
+
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for outcome in &outcomes {
+            *counts.entry(outcome["status"].as_str().unwrap_or_default().to_string()).or_default() += 1;
+        }
+        let mut result = json!({
+            "confirmed": confirmed,
+            "profiles_reviewed": profile_ids.len(),
+            "pending": rows.len(),
+            "risk_lookups": lookups,
+            "warnings": warnings,
+            "registrations": rows,
+            "decision_counts": counts,
+            "decisions_failed": failed,
+            "decisions": outcomes
+        });
+        if confirmed {
+            outcome.merge_into(&mut result);
+        }
+        Ok(result)
+    }
+}
+
+/// Registrations still waiting for a moderator; a missing status counts as pending
+fn is_pending(registration: &Registration) -> bool {
+    registration
+        .status
+        .as_deref()
+        .is_none_or(|s| s.eq_ignore_ascii_case("pending"))
+}
+
+/// Score a registrant with risk/validate. No phone or email is sent, so no MFA is delivered.
+async fn registrant_risk(client: &OneLoginClient, email: &str, ip: &str, user_agent: Option<&str>) -> Value {
+    let request = UserValidationRequest {
+        user_identifier: email.to_string(),
+        phone: None,
+        email: None,
+        context: RiskContext {
+            ip_address: ip.to_string(),
+            user_agent: user_agent.unwrap_or(UNKNOWN_USER_AGENT).to_string(),
+            device_id: None,
+            location: None,
+        },
+    };
+    match client.vigilance.validate_user(request).await {
+        Ok(result) => json!({
+            "score": result.risk_score.score,
+            "risk_level": result.risk_score.risk_level,
+            "factors": result.risk_score.factors
+        }),
+        Err(e) => json!({"error": format!("Risk lookup failed: {}", e)}),
+    }
+}
+
+/// A validated approval or rejection
+#[derive(Debug)]
+struct PlannedDecision {
+    profile_id: i64,
+    registration_id: i64,
+    status: String,
+    rejection_reason: Option<String>,
+}
+
+impl PlannedDecision {
+    fn verb(&self) -> &'static str {
+        if self.status == "approved" {
+            "approve"
+        } else {
+            "reject"
+        }
+    }
+
+    /// The decision as an item of the bulk result envelope
+    fn item(&self) -> Value {
+        json!({"profile_id": self.profile_id, "registration_id": self.registration_id, "decision": self.status})
+    }
+
+    fn outcome(&self, status: &str) -> Value {
+        json!({
+            "profile_id": self.profile_id,
+            "registration_id": self.registration_id,
+            "status": status,
+            "rejection_reason": self.rejection_reason
+        })
+    }
+}
+
+/// Split decisions into those to apply and skipped ones (with the reason): unknown
+/// statuses, rejections without a reason, registrations that aren't pending in that
+/// profile and repeated decisions for the same registration
+fn plan_decisions(
+    decisions: Vec<RegistrationDecision>,
+    pending: &[(i64, Registration)],
+    default_reason: Option<&str>,
+) -> (Vec<PlannedDecision>, Vec<Value>) {
+    let mut planned: Vec<PlannedDecision> = Vec::new();
+    let mut skipped = Vec::new();
+    for decision in decisions {
+        let skip = |reason: String| {
+            json!({
+                "profile_id": decision.profile_id,
+                "registration_id": decision.registration_id,
+                "status": "skipped",
+                "reason": reason
+            })
+        };
+        let status = decision.status.trim().to_lowercase();
+        let status = match status.as_str() {
+            "approved" | "approve" => "approved",
+            "rejected" | "reject" => "rejected",
+            _ => {
+                skipped.push(skip(format!("status '{}' is not 'approved' or 'rejected'", decision.status)));
+                continue;
+            }
+        };
+        let rejection_reason = decision
+            .rejection_reason
+            .as_deref()
+            .or(default_reason)
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(String::from);
+        if status == "rejected" && rejection_reason.is_none() {
+            skipped.push(skip("rejections need a rejection_reason".to_string()));
+            continue;
+        }
+        if !pending.iter().any(|(p, r)| *p == decision.profile_id && r.id == decision.registration_id) {
+            skipped.push(skip("no pending registration with this id in the profile".to_string()));
+            continue;
+        }
+        if planned.iter().any(|d| d.profile_id == decision.profile_id && d.registration_id == decision.registration_id) {
+            skipped.push(skip("registration decided more than once in this call".to_string()));
+            continue;
+        }
+        planned.push(PlannedDecision {
+            profile_id: decision.profile_id,
+            registration_id: decision.registration_id,
+            status: status.to_string(),
+            rejection_reason: if status == "rejected" { rejection_reason } else { None },
+        });
+    }
+    (planned, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registration(id: i64, status: Option<&str>) -> Registration {
+        serde_json::from_value(json!({"id": id, "status": status})).unwrap()
+    }
+
+    fn decision(registration_id: i64, status: &str, reason: Option<&str>) -> RegistrationDecision {
+        RegistrationDecision {
+            profile_id: 1,
+            registration_id,
+            status: status.to_string(),
+            rejection_reason: reason.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_is_pending() {
+        assert!(is_pending(&registration(1, Some("Pending"))));
+        assert!(is_pending(&registration(2, None)));
+        assert!(!is_pending(&registration(3, Some("approved"))));
+    }
+
+    #[test]
+    fn test_plan_decisions() {
+        let pending = vec![(1, registration(10, None)), (1, registration(11, None)), (1, registration(12, None))];
+        let decisions = vec![
+            decision(10, "approve", Some("ignored")),
+            decision(11, "rejected", None),
+            decision(12, "maybe", None),
+            decision(99, "approved", None),
+            decision(10, "rejected", Some("spam")),
+        ];
+
+        let (planned, skipped) = plan_decisions(decisions, &pending, None);
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].status, "approved");
+        assert_eq!(planned[0].rejection_reason, None);
+        let reasons: Vec<&str> = skipped.iter().map(|s| s["reason"].as_str().unwrap()).collect();
+        assert!(reasons[0].contains("rejection_reason"));
+        assert!(reasons[1].contains("'maybe'"));
+        assert!(reasons[2].contains("no pending registration"));
+        assert!(reasons[3].contains("more than once"));
+
+        let (planned, skipped) = plan_decisions(vec![decision(11, "reject", None)], &pending, Some("Unknown domain"));
+        assert!(skipped.is_empty());
+        assert_eq!(planned[0].rejection_reason.as_deref(), Some("Unknown domain"));
+    }
+}
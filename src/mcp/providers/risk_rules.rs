@@ -0,0 +1,303 @@
+use crate::api::OneLoginClient;
+use crate::models::vigilance::{Location, RiskCondition, RiskContext, UserValidationRequest};
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::net::IpAddr;
+
+const DEFAULT_USER_AGENT: &str =
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0 Safari/537.36";
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConditionArg {
+    /// Context field: ip, user_agent, country, city, device_id or risk_score
+    pub field: String,
+    /// equals, not_equals, contains, not_contains, starts_with, ends_with, in, not_in, gt, gte, lt, lte or cidr
+    pub operator: String,
+    /// Value to compare against; comma-separated for in/not_in, a network like 10.0.0.0/8 for cidr
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TestRiskRuleArgs {
+    /// Existing risk rule to test. Either rule_id or conditions is required
+    pub rule_id: Option<String>,
+    /// Conditions of a proposed rule (all must match for it to fire)
+    pub conditions: Option<Vec<ConditionArg>>,
+    /// Test user the synthetic login is evaluated for
+    pub test_user_id: i64,
+    /// Source IP of the synthetic login
+    pub ip: String,
+    /// User agent of the synthetic login. Default: a desktop Chrome user agent
+    pub user_agent: Option<String>,
+    /// Country of the synthetic login (ISO code, e.g. US)
+    pub country: Option<String>,
+    /// City of the synthetic login
+    pub city: Option<String>,
+    /// Latitude of the synthetic login; sent to risk/validate together with longitude
+    pub latitude: Option<f64>,
+    /// Longitude of the synthetic login
+    pub longitude: Option<f64>,
+    /// Device ID of the synthetic login
+    pub device_id: Option<String>,
+    /// Also call risk/validate with the synthetic context (no phone or email is sent, so no MFA is delivered). Default: true
+    pub include_validation: Option<bool>,
+}
+
+tool_provider! {
+    /// Risk rule testing against synthetic login contexts
+    pub struct RiskRulesProvider("risk") {
+        "onelogin_test_risk_rule" => test_risk_rule(TestRiskRuleArgs):
+            "Test an existing (rule_id) or proposed (conditions) Vigilance risk rule against a synthetic login context (IP, user agent, country/city, device) for a test user, and report whether it would fire. Each condition is evaluated locally and the tenant's risk score for the context is fetched from risk/verify (and risk/validate unless include_validation=false) so risk_score conditions use the real score. Non-destructive: no risk events are tracked, no rule is created or changed and no MFA is sent. Use a dedicated test user.",
+    }
+}
+
+impl RiskRulesProvider {
+    async fn test_risk_rule(&self, client: &OneLoginClient, args: TestRiskRuleArgs) -> Result<Value> {
+        let mut warnings = Vec::new();
+        let (rule, conditions) = match (&args.rule_id, args.conditions) {
+            (Some(rule_id), _) => {
+                let rules = client.vigilance.list_risk_rules().await
+                    .map_err(|e| anyhow!("Failed to list risk rules: {}", e))?;
+                let rule = rules
+                    .into_iter()
+                    .find(|r| &r.id == rule_id)
+                    .ok_or_else(|| anyhow!("Risk rule {} not found", rule_id))?;
+                if !rule.enabled {
+                    warnings.push("The rule is disabled; it does not fire in production even when its conditions match".to_string());
+                }
+                let conditions = rule.conditions.clone();
+                (Some(rule), conditions)
+            }
+            (None, Some(conditions)) => {
+                let conditions = conditions
+                    .into_iter()
+                    .map(|c| RiskCondition { field: c.field, operator: c.operator, value: c.value })
+                    .collect();
+                (None, conditions)
+            }
+            (None, None) => return Err(anyhow!("Provide either rule_id or conditions")),
+        };
+        if conditions.is_empty() {
+            return Err(anyhow!("The rule has no conditions to evaluate"));
+        }
+
+        let user = client.users.get_user(args.test_user_id).await
+            .map_err(|e| anyhow!("Failed to get test user {}: {}", args.test_user_id, e))?;
+        let user_agent = args.user_agent.unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+
+        // risk/verify only scores the context; nothing is recorded for the user
+        let score = match client.vigilance.get_risk_score(&user.id.to_string(), &args.ip, &user_agent).await {
+            Ok(score) => Some(score),
+            Err(e) => {
+                warnings.push(format!("Could not get a risk score for the context; risk_score conditions are not evaluated: {}", e));
+                None
+            }
+        };
+
+        let validation = if args.include_validation.unwrap_or(true) {
+            let location = match (args.latitude, args.longitude) {
+                (Some(latitude), Some(longitude)) => Some(Location {
+                    latitude,
+                    longitude,
+                    city: args.city.clone(),
+                    country: args.country.clone(),
+                }),
+                _ => None,
+            };
+            let request = UserValidationRequest {
+                user_identifier: user.email.clone().or_else(|| user.username.clone()).unwrap_or_else(|| user.id.to_string()),
+                phone: None,
+                email: None,
+                context: RiskContext {
+                    ip_address: args.ip.clone(),
+                    user_agent: user_agent.clone(),
+                    device_id: args.device_id.clone(),
+                    location,
+                },
+            };
+            match client.vigilance.validate_user(request).await {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    warnings.push(format!("risk/validate failed: {}", e));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let context = json!({
+            "ip": args.ip,
+            "user_agent": user_agent,
+            "country": args.country,
+            "city": args.city,
+            "device_id": args.device_id,
+            "risk_score": score.as_ref().map(|s| s.score),
+        });
+        let results: Vec<Value> = conditions.iter().map(|c| evaluate_condition(c, &context)).collect();
+        let would_fire = combine(&results);
+        if would_fire.is_none() {
+            warnings.push("Some conditions could not be evaluated, so whether the rule fires is undetermined".to_string());
+        }
+
+        Ok(json!({
+            "would_fire": would_fire,
+            "rule": rule,
+            "conditions": results,
+            "context": context,
+            "risk_score": score,
+            "validation": validation,
+            "test_user": {"id": user.id, "email": user.email, "username": user.username},
+            "warnings": warnings
+        }))
+    }
+}
+
+/// Rules fire when every condition matches. `None` if a condition could not be
+/// evaluated and no other condition already rules the rule out
+fn combine(results: &[Value]) -> Option<bool> {
+    let matches: Vec<Option<bool>> = results.iter().map(|r| r["matched"].as_bool()).collect();
+    if matches.contains(&Some(false)) {
+        Some(false)
+    } else if matches.contains(&None) {
+        None
+    } else {
+        Some(true)
+    }
+}
+
+/// Evaluate one condition against the synthetic context
+fn evaluate_condition(condition: &RiskCondition, context: &Value) -> Value {
+    let field = match condition.field.to_lowercase().as_str() {
+        "ip_address" | "ip" => "ip",
+        "score" | "risk_score" => "risk_score",
+        "country_code" | "country" => "country",
+        other => match context.get(other) {
+            Some(_) => other,
+            None => return unevaluated(condition, format!("Unknown field '{}'", condition.field)),
+        },
+    }
+    .to_string();
+    let actual = match &context[&field] {
+        Value::Null => return unevaluated(condition, format!("No {} in the synthetic context", field)),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match compare(&condition.operator, &actual, &condition.value) {
+        Ok(matched) => json!({
+            "field": condition.field,
+            "operator": condition.operator,
+            "value": condition.value,
+            "actual": actual,
+            "matched": matched
+        }),
+        Err(reason) => unevaluated(condition, reason),
+    }
+}
+
+fn unevaluated(condition: &RiskCondition, reason: String) -> Value {
+    json!({
+        "field": condition.field,
+        "operator": condition.operator,
+        "value": condition.value,
+        "matched": null,
+        "reason": reason
+    })
+}
+
+fn compare(operator: &str, actual: &str, expected: &str) -> std::result::Result<bool, String> {
+    let (a, e) = (actual.to_lowercase(), expected.to_lowercase());
+    let list = || e.split(',').map(str::trim).any(|item| item == a);
+    let numbers = || -> std::result::Result<(f64, f64), String> {
+        let a = actual.parse::<f64>().map_err(|_| format!("'{}' is not a number", actual))?;
+        let e = expected.parse::<f64>().map_err(|_| format!("'{}' is not a number", expected))?;
+        Ok((a, e))
+    };
+    Ok(match operator.to_lowercase().as_str() {
+        "equals" | "eq" | "==" => a == e,
+        "not_equals" | "neq" | "!=" => a != e,
+        "contains" => a.contains(&e),
+        "not_contains" => !a.contains(&e),
+        "starts_with" => a.starts_with(&e),
+        "ends_with" => a.ends_with(&e),
+        "in" => list(),
+        "not_in" => !list(),
+        "gt" | ">" => numbers().map(|(a, e)| a > e)?,
+        "gte" | ">=" => numbers().map(|(a, e)| a >= e)?,
+        "lt" | "<" => numbers().map(|(a, e)| a < e)?,
+        "lte" | "<=" => numbers().map(|(a, e)| a <= e)?,
+        "cidr" | "in_cidr" => in_cidr(actual, expected)?,
+        "not_cidr" | "not_in_cidr" => !in_cidr(actual, expected)?,
+        other => return Err(format!("Unsupported operator '{}'", other)),
+    })
+}
+
+fn in_cidr(ip: &str, network: &str) -> std::result::Result<bool, String> {
+    let ip: IpAddr = ip.parse().map_err(|_| format!("'{}' is not an IP address", ip))?;
+    let (base, prefix) = network.split_once('/').unwrap_or((network, ""));
+    let base: IpAddr = base.trim().parse().map_err(|_| format!("'{}' is not a network", network))?;
+    let bits = match (ip, base) {
+        (IpAddr::V4(ip), IpAddr::V4(base)) => (u32::from(ip) as u128, u32::from(base) as u128, 32),
+        (IpAddr::V6(ip), IpAddr::V6(base)) => (u128::from(ip), u128::from(base), 128),
+        _ => return Ok(false),
+    };
+    let prefix: u32 = if prefix.is_empty() {
+        bits.2
+    } else {
+        prefix.trim().parse().ok().filter(|p| *p <= bits.2).ok_or_else(|| format!("'{}' has an invalid prefix length", network))?
+    };
+    if prefix == 0 {
+        return Ok(true);
+    }
+    let shift = bits.2 - prefix;
+    Ok(bits.0 >> shift == bits.1 >> shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition(field: &str, operator: &str, value: &str) -> RiskCondition {
+        RiskCondition { field: field.to_string(), operator: operator.to_string(), value: value.to_string() }
+    }
+
+    #[test]
+    fn test_in_cidr() {
+        assert_eq!(in_cidr("10.1.2.3", "10.0.0.0/8"), Ok(true));
+        assert_eq!(in_cidr("11.1.2.3", "10.0.0.0/8"), Ok(false));
+        assert_eq!(in_cidr("203.0.113.10", "203.0.113.10"), Ok(true));
+        assert_eq!(in_cidr("2001:db8::1", "2001:db8::/32"), Ok(true));
+        assert_eq!(in_cidr("10.0.0.1", "2001:db8::/32"), Ok(false));
+        assert!(in_cidr("10.0.0.1", "10.0.0.0/33").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let context = json!({"ip": "203.0.113.10", "user_agent": "curl/8.0", "country": "RU", "city": null, "risk_score": 80});
+        let fired: Vec<Value> = [
+            condition("country", "in", "RU, KP"),
+            condition("ip_address", "not_cidr", "10.0.0.0/8"),
+            condition("risk_score", "gte", "75"),
+            condition("user_agent", "starts_with", "CURL"),
+        ]
+        .iter()
+        .map(|c| evaluate_condition(c, &context))
+        .collect();
+        assert_eq!(combine(&fired), Some(true));
+
+        let missed = evaluate_condition(&condition("risk_score", "lt", "50"), &context);
+        assert_eq!(missed["matched"], false);
+        assert_eq!(combine(&[fired[0].clone(), missed]), Some(false));
+
+        let unknown = evaluate_condition(&condition("city", "equals", "Boston"), &context);
+        assert!(unknown["matched"].is_null());
+        assert_eq!(combine(&[fired[0].clone(), unknown]), None);
+
+        assert!(evaluate_condition(&condition("ip", "regex", ".*"), &context)["reason"]
+            .as_str()
+            .unwrap()
+            .contains("Unsupported operator"));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::api::OneLoginClient;
+use crate::utils::certs::certificate_validity;
+use crate::utils::saml_metadata::{self, to_pem, IdpMetadata, SpMetadata};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Largest metadata document downloaded; real IdP metadata is a few KB
+const MAX_METADATA_BYTES: usize = 5 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 5;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GenerateSpMetadataArgs {
+    /// SP entity ID (audience), usually a URL identifying the application
+    pub entity_id: String,
+    /// Assertion consumer service URL(s); the first is the default
+    pub acs_urls: Vec<String>,
+    /// NameID format: email, persistent, transient, unspecified or a full URN. Default: email
+    pub name_id_format: Option<String>,
+    /// ACS binding: post or redirect. Default: post
+    pub acs_binding: Option<String>,
+    /// Single logout URL of the SP
+    pub slo_url: Option<String>,
+    /// SP certificate (PEM) published for signing and encryption
+    pub certificate: Option<String>,
+    /// Whether the SP signs its AuthnRequests. Default: false
+    pub authn_requests_signed: Option<bool>,
+    /// Whether the SP requires signed assertions. Default: true
+    pub want_assertions_signed: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ParseIdpMetadataArgs {
+    /// IdP metadata XML. Either xml or url is required
+    pub xml: Option<String>,
+    /// https URL to download the IdP metadata from (at most 5 MB; redirects must stay on https)
+    pub url: Option<String>,
+    /// Name for the trusted IdP in the prefilled arguments. Default: the IdP's host
+    pub name: Option<String>,
+}
+
+tool_provider! {
+    /// SAML metadata generation and parsing
+    pub struct SamlMetadataProvider("saml") {
+        "onelogin_generate_sp_metadata" => generate_sp_metadata(GenerateSpMetadataArgs):
+            "Generate SAML 2.0 service provider metadata XML from an entity ID, ACS URL(s) and NameID format, ready to paste into a SAML app configuration or hand to a partner. Optionally includes SLO and the SP certificate. Runs locally; nothing is sent to OneLogin.",
+        "onelogin_parse_idp_metadata" => parse_idp_metadata(ParseIdpMetadataArgs):
+            "Parse an identity provider's SAML metadata (pasted XML or a URL) and return its entity ID, SSO/SLO endpoints, signing certificates with expiry dates and NameID formats, plus create_trusted_idp_args prefilled for onelogin_create_trusted_idp. Does not create anything.",
+    }
+}
+
+impl SamlMetadataProvider {
+    async fn generate_sp_metadata(&self, _client: &OneLoginClient, args: GenerateSpMetadataArgs) -> Result<Value> {
+        let sp = SpMetadata {
+            entity_id: args.entity_id,
+            acs_urls: args.acs_urls,
+            acs_binding: args.acs_binding,
+            slo_url: args.slo_url,
+            name_id_format: args.name_id_format.unwrap_or_else(|| "email".to_string()),
+            certificate: args.certificate,
+            authn_requests_signed: args.authn_requests_signed.unwrap_or(false),
+            want_assertions_signed: args.want_assertions_signed.unwrap_or(true),
+        };
+        let xml = saml_metadata::generate_sp_metadata(&sp).map_err(|e| anyhow!(e))?;
+        Ok(json!({
+            "entity_id": sp.entity_id,
+            "acs_url": sp.acs_urls[0],
+            "metadata_xml": xml
+        }))
+    }
+
+    async fn parse_idp_metadata(&self, _client: &OneLoginClient, args: ParseIdpMetadataArgs) -> Result<Value> {
+        let xml = match (args.xml, &args.url) {
+            (Some(xml), _) => xml,
+            (None, Some(url)) => fetch_metadata(url).await?,
+            (None, None) => return Err(anyhow!("Provide either xml or url")),
+        };
+        let metadata = saml_metadata::parse_idp_metadata(&xml).map_err(|e| anyhow!(e))?;
+
+        let now = Utc::now();
+        let mut warnings = Vec::new();
+        let certificates: Vec<Value> = metadata
+            .signing_certificates
+            .iter()
+            .map(|body| {
+                let pem = to_pem(body);
+                match certificate_validity(&pem) {
+                    Ok(validity) => {
+                        let days_left = (validity.not_after - now).num_days();
+                        if days_left < 0 {
+                            warnings.push(format!("A signing certificate expired on {}", validity.not_after.to_rfc3339()));
+                        } else if days_left < 30 {
+                            warnings.push(format!("A signing certificate expires in {} days", days_left));
+                        }
+                        json!({"pem": pem, "expires_at": validity.not_after.to_rfc3339(), "days_left": days_left})
+                    }
+                    Err(e) => {
+                        warnings.push(format!("Could not read a signing certificate: {}", e));
+                        json!({"pem": pem})
+                    }
+                }
+            })
+            .collect();
+
+        let sso = IdpMetadata::preferred(&metadata.sso_endpoints);
+        let slo = IdpMetadata::preferred(&metadata.slo_endpoints);
+        if sso.is_none() {
+            warnings.push("No SingleSignOnService endpoint in the metadata".to_string());
+        }
+        if certificates.is_empty() {
+            warnings.push("No signing certificate in the metadata; OneLogin cannot verify the IdP's responses".to_string());
+        }
+        if certificates.len() > 1 {
+            warnings.push("Several signing certificates (likely a rollover); the first is used in create_trusted_idp_args".to_string());
+        }
+
+        let name = args.name.or_else(|| {
+            metadata
+                .entity_id
+                .as_deref()
+                .and_then(|id| url::Url::parse(id).ok())
+                .and_then(|u| u.host_str().map(str::to_string))
+        });
+        Ok(json!({
+            "entity_id": metadata.entity_id,
+            "sso_endpoints": metadata.sso_endpoints,
+            "slo_endpoints": metadata.slo_endpoints,
+            "signing_certificates": certificates,
+            "name_id_formats": metadata.name_id_formats,
+            "warnings": warnings,
+            "create_trusted_idp_args": {
+                "name": name,
+                "type": "saml",
+                "enabled": true,
+                "issuer": metadata.entity_id,
+                "sso_endpoint": sso.map(|e| &e.location),
+                "slo_endpoint": slo.map(|e| &e.location),
+                "certificate": certificates.first().map(|c| &c["pem"])
+            }
+        }))
+    }
+}
+
+async fn fetch_metadata(url: &str) -> Result<String> {
+    let parsed = url::Url::parse(url).map_err(|e| anyhow!("Invalid metadata URL: {}", e))?;
+    if parsed.scheme() != "https" {
+        return Err(anyhow!("Metadata URL must use https"));
+    }
+    // Redirects must stay on https, like the URL itself
+    let redirects = reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if attempt.url().scheme() != "https" {
+            attempt.error("redirect away from https refused")
+        } else {
+            attempt.follow()
+        }
+    });
+    let http = reqwest::Client::builder().timeout(FETCH_TIMEOUT).redirect(redirects).build()?;
+    let mut response = http.get(parsed).send().await
+        .map_err(|e| anyhow!("Failed to download metadata: {}", e))?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Failed to download metadata: HTTP {}", response.status()));
+    }
+    let too_large = || anyhow!("Metadata exceeds {} bytes", MAX_METADATA_BYTES);
+    if response.content_length().is_some_and(|len| len > MAX_METADATA_BYTES as u64) {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await
+        .map_err(|e| anyhow!("Failed to download metadata: {}", e))?
+    {
+        if body.len() + chunk.len() > MAX_METADATA_BYTES {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    String::from_utf8(body).map_err(|_| anyhow!("Metadata is not valid UTF-8"))
+}
@@ -0,0 +1,146 @@
+use crate::api::OneLoginClient;
+use crate::core::config::ApiVersion;
+use crate::models::{apps::App, groups::Group, privileges::Privilege, roles::Role, smart_hooks::SmartHook, user_mappings::UserMapping, users::User};
+use crate::utils::schema_drift::{self, ModelDrift};
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CheckSchemaDriftArgs {
+    /// Endpoints to check: users, apps, roles, groups, privileges, smart_hooks, user_mappings. Default: all
+    pub endpoints: Option<Vec<String>>,
+}
+
+/// An endpoint sampled by the drift check and the model its objects deserialize into
+struct DriftEndpoint {
+    name: &'static str,
+    version: ApiVersion,
+    path: &'static str,
+    /// JSON pointer to the first object in the response
+    sample: &'static str,
+    model: &'static str,
+    compare: fn(&Value) -> ModelDrift,
+}
+
+const ENDPOINTS: &[DriftEndpoint] = &[
+    DriftEndpoint {
+        name: "users",
+        version: ApiVersion::V2,
+        path: "users?limit=1",
+        sample: "/0",
+        model: "User",
+        compare: |v| schema_drift::compare(v, |_: &mut User| {}),
+    },
+    DriftEndpoint {
+        name: "apps",
+        version: ApiVersion::V2,
+        path: "apps?limit=1",
+        sample: "/0",
+        model: "App",
+        compare: |v| schema_drift::compare(v, |app: &mut App| app.extra = None),
+    },
+    DriftEndpoint {
+        name: "roles",
+        version: ApiVersion::V2,
+        path: "roles?limit=1",
+        sample: "/0",
+        model: "Role",
+        compare: |v| schema_drift::compare(v, |_: &mut Role| {}),
+    },
+    DriftEndpoint {
+        name: "groups",
+        version: ApiVersion::V1,
+        path: "groups",
+        sample: "/data/0",
+        model: "Group",
+        compare: |v| schema_drift::compare(v, |_: &mut Group| {}),
+    },
+    DriftEndpoint {
+        name: "privileges",
+        version: ApiVersion::V1,
+        path: "privileges",
+        sample: "/0",
+        model: "Privilege",
+        compare: |v| schema_drift::compare(v, |_: &mut Privilege| {}),
+    },
+    DriftEndpoint {
+        name: "smart_hooks",
+        version: ApiVersion::V2,
+        path: "hooks",
+        sample: "/0",
+        model: "SmartHook",
+        compare: |v| schema_drift::compare(v, |_: &mut SmartHook| {}),
+    },
+    DriftEndpoint {
+        name: "user_mappings",
+        version: ApiVersion::V2,
+        path: "mappings",
+        sample: "/0",
+        model: "UserMapping",
+        compare: |v| schema_drift::compare(v, |_: &mut UserMapping| {}),
+    },
+];
+
+tool_provider! {
+    /// Live comparison of OneLogin responses with this server's models
+    pub struct SchemaDriftProvider("diagnostics") {
+        "onelogin_check_schema_drift" => check_schema_drift(CheckSchemaDriftArgs):
+            "Maintainer diagnostic: fetch one sample object from each major endpoint (users, apps, roles, groups, privileges, smart hooks, user mappings) and compare its fields with the server's models. Reports per endpoint the unknown fields (sent by OneLogin but dropped by the model, as dotted paths), missing fields (in the model but not in the sample; often just optional fields) and any deserialization error, which is what breaks tools after an API change. Endpoints without objects or not licensed are reported, not failed. Read-only.",
+    }
+}
+
+impl SchemaDriftProvider {
+    async fn check_schema_drift(&self, client: &OneLoginClient, args: CheckSchemaDriftArgs) -> Result<Value> {
+        if let Some(unknown) = args
+            .endpoints
+            .iter()
+            .flatten()
+            .find(|name| !ENDPOINTS.iter().any(|e| e.name == name.as_str()))
+        {
+            let names: Vec<&str> = ENDPOINTS.iter().map(|e| e.name).collect();
+            return Err(anyhow!("Unknown endpoint '{}'. Expected one of: {}", unknown, names.join(", ")));
+        }
+
+        let mut results = Vec::new();
+        let mut drifted = Vec::new();
+        for endpoint in ENDPOINTS {
+            if args.endpoints.as_ref().is_some_and(|names| !names.iter().any(|n| n == endpoint.name)) {
+                continue;
+            }
+            let mut result = json!({
+                "endpoint": endpoint.name,
+                "path": endpoint.version.path(endpoint.path),
+                "model": endpoint.model,
+            });
+            match client.diagnostics.get_raw(endpoint.version, endpoint.path).await {
+                Err(e) => {
+                    result["status"] = json!("error");
+                    result["error"] = json!(e.to_string());
+                }
+                Ok(raw) => match raw.pointer(endpoint.sample) {
+                    None => result["status"] = json!("no_sample"),
+                    Some(sample) => {
+                        let drift = (endpoint.compare)(sample);
+                        let clean = drift.is_clean();
+                        if !clean {
+                            drifted.push(endpoint.name);
+                        }
+                        result["status"] = json!(if clean { "ok" } else { "drift" });
+                        if let Value::Object(fields) = serde_json::to_value(drift)? {
+                            result.as_object_mut().expect("result is an object").extend(fields);
+                        }
+                    }
+                },
+            }
+            results.push(result);
+        }
+
+        Ok(json!({
+            "checked": results.len(),
+            "drifted": drifted,
+            "endpoints": results,
+        }))
+    }
+}
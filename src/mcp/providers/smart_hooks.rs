@@ -0,0 +1,209 @@
+use crate::api::OneLoginClient;
+use crate::models::smart_hooks::{CreateEnvVarRequest, UpdateEnvVarRequest};
+use crate::utils::base64_decode;
+use crate::utils::hook_runtime::{check_decision, run_hook_isolated, sample_payload, HookJob, SAMPLE_HOOK_TYPES};
+use crate::utils::secrets::{fingerprint, fingerprint_matches, MASK};
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TestSmartHookArgs {
+    /// Hook function source (plain JavaScript or base64). Either function or hook_id is required
+    pub function: Option<String>,
+    /// Run the function of an existing hook instead
+    pub hook_id: Option<String>,
+    /// Hook type, used to pick the sample payload and check the decision: pre-authentication or user-migration. Default: the hook's type, else pre-authentication
+    pub hook_type: Option<String>,
+    /// The context object passed to the handler. Default: a sample payload for hook_type
+    pub payload: Option<Value>,
+    /// Values for process.env, keyed by env var name
+    pub env_vars: Option<HashMap<String, String>>,
+    /// Values returned by require(name) for packages the function uses, keyed by package name
+    pub mocks: Option<HashMap<String, Value>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetHookEnvVarArgs {
+    /// Variable name, e.g. API_KEY
+    pub name: String,
+    /// Secret value
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CompareHookEnvVarArgs {
+    /// The environment variable ID. Either env_var_id or name is required
+    pub env_var_id: Option<String>,
+    /// The environment variable name. Either env_var_id or name is required
+    pub name: Option<String>,
+    /// Candidate value to compare
+    pub value: Option<String>,
+    /// Hex SHA-256 of the candidate value, instead of the value itself
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CheckHookPackagesArgs {
+    /// Packages to check, as {"package": "version"}
+    pub packages: HashMap<String, String>,
+}
+
+tool_provider! {
+    /// Local Smart Hook test runs, package checks and write-only env var handling
+    pub struct SmartHooksProvider("smart_hooks") {
+        "onelogin_test_smart_hook" => test_smart_hook(TestSmartHookArgs):
+            "Run a Smart Hook function locally in an embedded JavaScript engine against a sample pre-authentication or user-migration payload, without deploying it. Pass the function source, or hook_id to run a deployed hook's code. Returns the handler's decision, any thrown error, captured console output and warnings about the decision's shape. The sandbox has no network: packages are only available through mocks, and process.env only holds env_vars. Each run is stopped after 5 seconds. Nothing is sent to OneLogin except fetching the hook when hook_id is given.",
+        "onelogin_check_hook_packages" => check_hook_packages(CheckHookPackagesArgs):
+            "Verify a Smart Hook packages map before deploying it: each package must be a valid npm name (not a Node.js built-in), pinned to an exact version that exists on the npm registry, and in the supported set when SMART_HOOK_ALLOWED_PACKAGES is configured. Returns per-package errors with fixes (e.g. the latest available version) and deprecation warnings. Create and update run this check automatically.",
+        "onelogin_set_hook_env_var" => set_hook_env_var(SetHookEnvVarArgs):
+            "Write-only set of an ACCOUNT-LEVEL Smart Hook environment variable by name: updates it if it exists, otherwise creates it. The value is never echoed back; the response only confirms the name, ID and whether it was created or updated. Use onelogin_compare_hook_env_var later to check the value without revealing it.",
+        "onelogin_compare_hook_env_var" => compare_hook_env_var(CompareHookEnvVarArgs):
+            "Check whether a Smart Hook environment variable holds an expected value without revealing it. Pass the candidate as 'value' or as its hex 'sha256'. OneLogin never returns env var values, so the comparison uses the fingerprint recorded when the value was last set through this server (create, update or set); 'matches' is null when no fingerprint is known.",
+    }
+}
+
+impl SmartHooksProvider {
+    async fn test_smart_hook(&self, client: &OneLoginClient, args: TestSmartHookArgs) -> Result<Value> {
+        let (source, deployed_type) = match (args.function, &args.hook_id) {
+            (Some(function), _) => (function, None),
+            (None, Some(hook_id)) => {
+                let hook = client.smart_hooks.get_hook(hook_id).await
+                    .map_err(|e| anyhow!("Failed to get smart hook {}: {}", hook_id, e))?;
+                let function = hook.function.ok_or_else(|| anyhow!("Smart hook {} has no function code", hook_id))?;
+                (function, Some(hook.hook_type))
+            }
+            (None, None) => return Err(anyhow!("Provide either function or hook_id")),
+        };
+        let function = if source.contains("handler") {
+            source
+        } else {
+            base64_decode(source.trim()).map_err(|e| anyhow!("function is neither JavaScript nor base64: {}", e))?
+        };
+
+        let hook_type = args
+            .hook_type
+            .or(deployed_type)
+            .unwrap_or_else(|| SAMPLE_HOOK_TYPES[0].to_string());
+        let payload = match args.payload {
+            Some(payload) => payload,
+            None => sample_payload(&hook_type).ok_or_else(|| {
+                anyhow!(
+                    "No sample payload for hook type '{}' (samples: {}); pass payload",
+                    hook_type,
+                    SAMPLE_HOOK_TYPES.join(", ")
+                )
+            })?,
+        };
+        // Runs in a child process so a runaway handler can be killed
+        let job = HookJob {
+            function,
+            payload: payload.clone(),
+            env: args.env_vars.unwrap_or_default(),
+            mocks: args.mocks.unwrap_or_default(),
+        };
+        let run = run_hook_isolated(&job).await.map_err(|e| anyhow!("Hook run failed: {:#}", e))?;
+
+        let warnings = run.result.as_ref().map(|r| check_decision(&hook_type, r)).unwrap_or_default();
+        Ok(json!({
+            "hook_type": hook_type,
+            "succeeded": run.error.is_none(),
+            "decision": run.result,
+            "error": run.error,
+            "logs": run.logs,
+            "warnings": warnings,
+            "payload": payload
+        }))
+    }
+
+    async fn check_hook_packages(&self, _client: &OneLoginClient, args: CheckHookPackagesArgs) -> Result<Value> {
+        let checks = crate::utils::npm::check_packages(&args.packages).await.map_err(|e| anyhow!(e))?;
+        Ok(json!({
+            "ok": checks.iter().all(|c| c.ok),
+            "packages": checks
+        }))
+    }
+
+    async fn set_hook_env_var(&self, client: &OneLoginClient, args: SetHookEnvVarArgs) -> Result<Value> {
+        let existing = client
+            .smart_hooks
+            .list_env_vars()
+            .await
+            .map_err(|e| anyhow!("Failed to list environment variables: {}", e))?
+            .into_iter()
+            .find(|v| v.name == args.name);
+
+        let (env_var, action) = match existing {
+            Some(current) => {
+                let env_var = client
+                    .smart_hooks
+                    .update_env_var(&current.id, UpdateEnvVarRequest { value: args.value })
+                    .await
+                    .map_err(|e| anyhow!("Failed to update environment variable: {}", e))?;
+                (env_var, "updated")
+            }
+            None => {
+                let request = CreateEnvVarRequest { name: args.name, value: args.value };
+                let env_var = client
+                    .smart_hooks
+                    .create_env_var(request)
+                    .await
+                    .map_err(|e| anyhow!("Failed to create environment variable: {}", e))?;
+                (env_var, "created")
+            }
+        };
+        Ok(json!({
+            "success": true,
+            "action": action,
+            "id": env_var.id,
+            "name": env_var.name,
+            "value": MASK
+        }))
+    }
+
+    async fn compare_hook_env_var(&self, client: &OneLoginClient, args: CompareHookEnvVarArgs) -> Result<Value> {
+        let candidate = match (args.value, args.sha256) {
+            (Some(value), None) => fingerprint(&value),
+            (None, Some(sha256)) => sha256,
+            _ => return Err(anyhow!("Provide exactly one of value or sha256")),
+        };
+        let env_var = match (args.env_var_id, args.name) {
+            (Some(id), _) => client
+                .smart_hooks
+                .get_env_var(&id)
+                .await
+                .map_err(|e| anyhow!("Failed to get environment variable: {}", e))?,
+            (None, Some(name)) => client
+                .smart_hooks
+                .list_env_vars()
+                .await
+                .map_err(|e| anyhow!("Failed to list environment variables: {}", e))?
+                .into_iter()
+                .find(|v| v.name == name)
+                .ok_or_else(|| anyhow!("No environment variable named {}", name))?,
+            (None, None) => return Err(anyhow!("env_var_id or name is required")),
+        };
+
+        // Prefer the live value when the tenant returns one; otherwise the recorded fingerprint
+        let (expected, basis) = match env_var.value.as_deref().filter(|v| *v != MASK) {
+            Some(value) => (Some(fingerprint(value)), "api_value"),
+            None => match client.smart_hooks.recorded_fingerprint(&env_var.id) {
+                Some(recorded) => (Some(recorded), "recorded_fingerprint"),
+                None => (None, "unknown"),
+            },
+        };
+        let matches = expected.map(|expected| fingerprint_matches(&expected, &candidate));
+        let mut result = json!({
+            "id": env_var.id,
+            "name": env_var.name,
+            "matches": matches,
+            "basis": basis
+        });
+        if matches.is_none() {
+            result["note"] = json!("OneLogin does not return env var values and this value was not set through this server since it started; set it again with onelogin_set_hook_env_var to enable comparisons");
+        }
+        Ok(result)
+    }
+}
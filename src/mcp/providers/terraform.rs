@@ -0,0 +1,255 @@
+use crate::api::OneLoginClient;
+use crate::models::apps::App;
+use crate::utils::terraform::{HclBlock, TerraformExport};
+use anyhow::{anyhow, bail, Context, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Resource kinds the exporter understands, in output order
+const RESOURCE_KINDS: &[&str] = &["apps", "roles", "user_mappings", "privileges"];
+
+/// `App::auth_method` values with dedicated provider resources
+const AUTH_METHOD_SAML: i32 = 2;
+const AUTH_METHOD_OIDC: i32 = 8;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ExportTerraformArgs {
+    /// Resource kinds to export: apps, roles, user_mappings, privileges. Default: all
+    pub resources: Option<Vec<String>>,
+    /// 'hcl' for resource blocks (with import blocks) or 'import_script' for a shell script of import commands. Default: hcl
+    pub format: Option<String>,
+    /// Append Terraform 1.5+/OpenTofu import blocks to the HCL output. Default: true
+    pub include_imports: Option<bool>,
+    /// Write the output to this file instead of returning it inline
+    pub output_path: Option<String>,
+}
+
+tool_provider! {
+    /// Infrastructure-as-code export of tenant configuration
+    pub struct TerraformProvider("terraform") {
+        "onelogin_export_terraform" => export_terraform(ExportTerraformArgs):
+            "Export apps, roles, user mappings and privileges as Terraform/OpenTofu configuration for the onelogin/onelogin provider, so a tenant configured through the admin UI can be brought under infrastructure-as-code. format='hcl' returns resource blocks plus import blocks; format='import_script' returns a shell script of import commands (set TF=tofu for OpenTofu). Review the output before applying: only commonly managed attributes are exported. Read-only.",
+    }
+}
+
+impl TerraformProvider {
+    async fn export_terraform(&self, client: &OneLoginClient, args: ExportTerraformArgs) -> Result<Value> {
+        let kinds: Vec<&str> = match &args.resources {
+            Some(requested) => {
+                if let Some(unknown) = requested.iter().find(|k| !RESOURCE_KINDS.contains(&k.as_str())) {
+                    bail!("Unknown resource kind '{}'. Expected one of: {}", unknown, RESOURCE_KINDS.join(", "));
+                }
+                RESOURCE_KINDS.iter().copied().filter(|k| requested.iter().any(|r| r == k)).collect()
+            }
+            None => RESOURCE_KINDS.to_vec(),
+        };
+        let format = args.format.as_deref().unwrap_or("hcl");
+        if format != "hcl" && format != "import_script" {
+            bail!("Unknown format '{}'. Expected 'hcl' or 'import_script'", format);
+        }
+
+        let mut export = TerraformExport::new();
+        let mut counts = serde_json::Map::new();
+        let mut warnings = Vec::new();
+        for kind in kinds {
+            let before = export.len();
+            match kind {
+                "apps" => export_apps(client, &mut export).await?,
+                "roles" => export_roles(client, &mut export).await?,
+                "user_mappings" => export_user_mappings(client, &mut export).await?,
+                _ => {
+                    // Privileges need the Delegated Administration subscription
+                    if let Err(e) = export_privileges(client, &mut export).await {
+                        warnings.push(format!("Privileges were not exported: {:#}", e));
+                    }
+                }
+            }
+            counts.insert(kind.to_string(), json!(export.len() - before));
+        }
+
+        if export.is_empty() {
+            warnings.push("No resources found to export".to_string());
+        }
+
+        let content = match format {
+            "import_script" => export.render_import_script(),
+            _ => export.render_hcl(args.include_imports.unwrap_or(true)),
+        };
+
+        if let Some(output_path) = &args.output_path {
+            let path = std::path::Path::new(output_path);
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            std::fs::write(path, &content).with_context(|| format!("Failed to write {}", output_path))?;
+            return Ok(json!({
+                "status": "exported",
+                "path": output_path,
+                "format": format,
+                "resource_counts": counts,
+                "bytes_written": content.len(),
+                "warnings": warnings
+            }));
+        }
+
+        Ok(json!({
+            "format": format,
+            "resource_counts": counts,
+            "warnings": warnings,
+            "content": content
+        }))
+    }
+}
+
+async fn export_apps(client: &OneLoginClient, export: &mut TerraformExport) -> Result<()> {
+    let apps = client.apps.list_apps(None).await
+        .map_err(|e| anyhow!("Failed to list apps: {}", e))?;
+    for summary in apps {
+        // List responses omit configuration
+        let app = client.apps.get_app(summary.id).await
+            .map_err(|e| anyhow!("Failed to get app {}: {}", summary.id, e))?;
+        let (resource_type, body) = app_resource(&app);
+        export.add_resource(resource_type, &app.name, &app.id.to_string(), body);
+    }
+    Ok(())
+}
+
+/// Provider resource type and body for an app
+fn app_resource(app: &App) -> (&'static str, HclBlock) {
+    let resource_type = match app.auth_method {
+        Some(AUTH_METHOD_SAML) => "onelogin_saml_apps",
+        Some(AUTH_METHOD_OIDC) => "onelogin_oidc_apps",
+        _ => "onelogin_apps",
+    };
+    let mut body = HclBlock::new("")
+        .attr("name", app.name.clone())
+        .attr("connector_id", app.connector_id)
+        .attr("description", json!(app.description))
+        .attr("notes", json!(app.notes))
+        .attr("visible", json!(app.visible))
+        .attr("allow_assumed_signin", json!(app.allow_assumed_signin))
+        .attr("policy_id", json!(app.policy_id))
+        .attr("brand_id", json!(app.brand_id));
+    // Only scalar settings; generated secrets and certificates are not part of the configuration
+    let scalars: Vec<(String, Value)> = app
+        .configuration
+        .iter()
+        .flatten()
+        .filter(|(_, v)| v.is_string() || v.is_number() || v.is_boolean())
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    if !scalars.is_empty() {
+        let mut configuration = HclBlock::new("configuration");
+        let mut scalars = scalars;
+        scalars.sort_by(|a, b| a.0.cmp(&b.0));
+        configuration.attributes = scalars;
+        body = body.block(configuration);
+    }
+    (resource_type, body)
+}
+
+async fn export_roles(client: &OneLoginClient, export: &mut TerraformExport) -> Result<()> {
+    let roles = client.roles.list_roles().await
+        .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+    for role in roles {
+        let apps: Vec<i64> = match role.apps {
+            Some(apps) => apps,
+            None => client.roles.get_role_apps(role.id).await
+                .map_err(|e| anyhow!("Failed to get apps for role {}: {}", role.id, e))?
+                .into_iter()
+                .filter_map(|a| a.id)
+                .collect(),
+        };
+        let name = role.name.clone().unwrap_or_default();
+        let body = HclBlock::new("")
+            .attr("name", name.clone())
+            .attr("apps", apps);
+        export.add_resource("onelogin_roles", &name, &role.id.to_string(), body);
+    }
+    Ok(())
+}
+
+async fn export_user_mappings(client: &OneLoginClient, export: &mut TerraformExport) -> Result<()> {
+    let mappings = client.user_mappings.list_mappings().await
+        .map_err(|e| anyhow!("Failed to list user mappings: {}", e))?;
+    for mapping in mappings {
+        let mut body = HclBlock::new("")
+            .attr("name", mapping.name.clone())
+            .attr("match", mapping.match_type.clone())
+            .attr("enabled", mapping.enabled)
+            .attr("position", json!(mapping.position));
+        for condition in &mapping.conditions {
+            body = body.block(
+                HclBlock::new("conditions")
+                    .attr("source", condition.source.clone())
+                    .attr("operator", condition.operator.clone())
+                    .attr("value", condition.value.clone()),
+            );
+        }
+        for action in &mapping.actions {
+            body = body.block(
+                HclBlock::new("actions")
+                    .attr("action", action.action.clone())
+                    .attr("value", action.value.clone()),
+            );
+        }
+        export.add_resource("onelogin_user_mappings", &mapping.name, &mapping.id.to_string(), body);
+    }
+    Ok(())
+}
+
+async fn export_privileges(client: &OneLoginClient, export: &mut TerraformExport) -> Result<()> {
+    let privileges = client.privileges.list_privileges().await
+        .map_err(|e| anyhow!("Failed to list privileges: {}", e))?;
+    for privilege in privileges {
+        let user_ids = client.privileges.get_assigned_users(&privilege.id).await
+            .map_err(|e| anyhow!("Failed to get users for privilege {}: {}", privilege.id, e))?;
+        let role_ids = client.privileges.get_assigned_roles(&privilege.id).await
+            .map_err(|e| anyhow!("Failed to get roles for privilege {}: {}", privilege.id, e))?;
+        let mut statement = HclBlock::new("privilege").attr("version", privilege.privilege.version.clone());
+        for item in &privilege.privilege.statement {
+            statement = statement.block(
+                HclBlock::new("statement")
+                    .attr("effect", item.effect.clone())
+                    .attr("action", item.action.clone())
+                    .attr("scope", item.scope.clone()),
+            );
+        }
+        let body = HclBlock::new("")
+            .attr("name", privilege.name.clone())
+            .attr("description", json!(privilege.description))
+            .attr("user_ids", user_ids)
+            .attr("role_ids", role_ids)
+            .block(statement);
+        export.add_resource("onelogin_privileges", &privilege.name, &privilege.id, body);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_resource_type_and_configuration() {
+        let app: App = serde_json::from_value(json!({
+            "id": 5,
+            "name": "Salesforce",
+            "connector_id": 110016,
+            "auth_method": 2,
+            "visible": true,
+            "configuration": {"signature_algorithm": "SHA-256", "certificate_id": 9, "nested": {"x": 1}}
+        }))
+        .unwrap();
+        let (resource_type, body) = app_resource(&app);
+        assert_eq!(resource_type, "onelogin_saml_apps");
+        let hcl = body.render();
+        assert!(hcl.contains("connector_id = 110016"));
+        assert!(!hcl.contains("notes"));
+        assert!(hcl.contains("configuration {\n"));
+        assert!(hcl.contains("signature_algorithm = \"SHA-256\""));
+        assert!(!hcl.contains("nested"));
+    }
+}
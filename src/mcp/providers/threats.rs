@@ -0,0 +1,590 @@
+use crate::api::OneLoginClient;
+use crate::models::events::{Event, EventQueryParams};
+use crate::utils::geoip::{self, GeoLocation};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Event type recorded when a OneLogin login fails
+const EVENT_LOGIN_FAILED: i32 = 6;
+/// Event type recorded when a user logs in to OneLogin
+const EVENT_LOGIN: i32 = 5;
+/// Minutes a suggested lockout lasts
+const SUGGESTED_LOCK_MINUTES: i64 = 60;
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct DetectBruteForceArgs {
+    /// Hours of events to scan, ending now. Default: 24
+    pub hours: Option<i64>,
+    /// Sliding window the thresholds are counted in, in minutes. Default: 15
+    pub window_minutes: Option<i64>,
+    /// Failures from one IP within the window that count as an attack. Default: 20
+    pub ip_threshold: Option<usize>,
+    /// Failures against one user within the window that count as an attack. Default: 10
+    pub user_threshold: Option<usize>,
+    /// Distinct users targeted from one IP that mark credential stuffing. Default: 5
+    pub stuffing_min_users: Option<usize>,
+    /// Distinct IPs attacking one user that mark a distributed attack. Default: 5
+    pub distributed_min_ips: Option<usize>,
+    /// Event types counted as failed logins. Default: [6] (failed OneLogin login); see onelogin_list_event_types
+    pub failure_event_type_ids: Option<Vec<i32>>,
+    /// Event types counted as successful logins, to flag attacks that were followed by a success. Default: [5]; [] skips the check
+    pub success_event_type_ids: Option<Vec<i32>>,
+    /// Source IPs never reported, e.g. a corporate NAT or load balancer
+    pub exempt_ips: Option<Vec<String>>,
+    /// Max pages of events fetched per event type. Default: 20
+    pub max_pages: Option<usize>,
+}
+
+/// Coordinates supplied for an IP instead of looking it up
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct KnownLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub city: Option<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct DetectImpossibleTravelArgs {
+    /// Hours of events to scan, ending now. Default: 72
+    pub hours: Option<i64>,
+    /// Only check these users
+    pub user_ids: Option<Vec<i64>>,
+    /// Event types counted as logins. Default: [5] (OneLogin login); see onelogin_list_event_types
+    pub event_type_ids: Option<Vec<i32>>,
+    /// Travel speed above which a pair of logins is flagged, in km/h. Default: 1000 (faster than a commercial flight)
+    pub max_speed_kmh: Option<f64>,
+    /// Logins closer than this are never flagged, since GeoIP is only accurate to a region. Default: 300
+    pub min_distance_km: Option<f64>,
+    /// Locations by IP, used instead of the GeoIP service, e.g. for office egress IPs or when GEOIP_URL is not set
+    pub locations: Option<HashMap<String, KnownLocation>>,
+    /// Source IPs never compared, e.g. VPN gateways whose location says nothing about the user
+    pub exempt_ips: Option<Vec<String>>,
+    /// Most distinct IPs looked up with the GeoIP service. Default: 200
+    pub max_lookups: Option<usize>,
+    /// Max pages of events fetched per event type. Default: 20
+    pub max_pages: Option<usize>,
+}
+
+tool_provider! {
+    /// Detection of attacks in authentication events
+    pub struct ThreatsProvider("risk") {
+        "onelogin_detect_brute_force" => detect_brute_force(DetectBruteForceArgs):
+            "Scan recent failed-login events for brute-force and credential-stuffing patterns. Failures are grouped by source IP and by target user and counted in a sliding window: an IP over ip_threshold is brute force, or credential stuffing when it targets stuffing_min_users or more users; a user over user_threshold is a brute-force target, distributed when attacked from distributed_min_ips or more IPs. Findings say whether a successful login followed the failures (likely compromise, severity high) and carry the evidence (counts, window, first/last seen, IPs and users) and suggested actions as ready-to-run tool calls (lock, logout, password reset, IP block rule) — nothing is applied. Read-only.",
+        "onelogin_detect_impossible_travel" => detect_impossible_travel(DetectImpossibleTravelArgs):
+            "Flag logins that imply infeasible travel. Each user's login events are sequenced by time, their source IPs geolocated, and consecutive logins from different places compared: a pair farther apart than min_distance_km whose implied speed exceeds max_speed_kmh is a finding. IPs are located with the service in GEOIP_URL (a URL template with {ip}) or from the locations argument; private addresses are skipped. Findings carry the evidence for an investigation — both events (ids, times, IPs, cities, countries, coordinates), distance, elapsed time, speed, whether the browser fingerprint matched — and suggested actions as ready-to-run tool calls; nothing is applied. Read-only.",
+    }
+}
+
+impl ThreatsProvider {
+    async fn detect_brute_force(&self, client: &OneLoginClient, args: DetectBruteForceArgs) -> Result<Value> {
+        let hours = args.hours.unwrap_or(24).max(1);
+        let thresholds = Thresholds {
+            window: Duration::minutes(args.window_minutes.unwrap_or(15).max(1)),
+            ip: args.ip_threshold.unwrap_or(20).max(1),
+            user: args.user_threshold.unwrap_or(10).max(1),
+            stuffing_users: args.stuffing_min_users.unwrap_or(5).max(2),
+            distributed_ips: args.distributed_min_ips.unwrap_or(5).max(2),
+        };
+        let max_pages = args.max_pages.unwrap_or(20).max(1);
+        let now = Utc::now();
+        let since = now - Duration::hours(hours);
+
+        let failure_types = args.failure_event_type_ids.clone().unwrap_or_else(|| vec![EVENT_LOGIN_FAILED]);
+        let success_types = args.success_event_type_ids.clone().unwrap_or_else(|| vec![EVENT_LOGIN]);
+        let (mut failures, failure_pages, failures_truncated) = fetch_events(client, &failure_types, since, max_pages).await?;
+        let (successes, _, successes_truncated) = fetch_events(client, &success_types, since, max_pages).await?;
+        let exempt: BTreeSet<&str> = args.exempt_ips.iter().flatten().map(String::as_str).collect();
+        failures.retain(|e| e.ipaddr.as_deref().is_none_or(|ip| !exempt.contains(ip)));
+
+        let findings = detect(&failures, &successes, &thresholds);
+        let count = |pattern: &str| findings.iter().filter(|f| f["pattern"] == pattern).count();
+        Ok(json!({
+            "period": {"since": since.to_rfc3339(), "until": now.to_rfc3339(), "hours": hours},
+            "thresholds": {
+                "window_minutes": thresholds.window.num_minutes(),
+                "ip_threshold": thresholds.ip,
+                "user_threshold": thresholds.user,
+                "stuffing_min_users": thresholds.stuffing_users,
+                "distributed_min_ips": thresholds.distributed_ips
+            },
+            "failures_scanned": failures.len(),
+            "successes_scanned": successes.len(),
+            "pages": failure_pages,
+            "truncated": failures_truncated || successes_truncated,
+            "counts": {
+                "credential_stuffing": count("credential_stuffing"),
+                "brute_force_ip": count("brute_force_ip"),
+                "brute_force_user": count("brute_force_user"),
+                "distributed_brute_force": count("distributed_brute_force"),
+                "followed_by_success": findings.iter().filter(|f| f["followed_by_success"] == true).count()
+            },
+            "findings": findings
+        }))
+    }
+
+    async fn detect_impossible_travel(&self, client: &OneLoginClient, args: DetectImpossibleTravelArgs) -> Result<Value> {
+        let hours = args.hours.unwrap_or(72).max(1);
+        let max_speed_kmh = args.max_speed_kmh.unwrap_or(1000.0).max(1.0);
+        let min_distance_km = args.min_distance_km.unwrap_or(300.0).max(0.0);
+        let max_lookups = args.max_lookups.unwrap_or(200);
+        let max_pages = args.max_pages.unwrap_or(20).max(1);
+        let now = Utc::now();
+        let since = now - Duration::hours(hours);
+
+        let event_types = args.event_type_ids.clone().unwrap_or_else(|| vec![EVENT_LOGIN]);
+        let (mut events, pages, truncated) = fetch_events(client, &event_types, since, max_pages).await?;
+        let exempt: BTreeSet<&str> = args.exempt_ips.iter().flatten().map(String::as_str).collect();
+        events.retain(|e| {
+            e.user_id.is_some_and(|id| id > 0)
+                && args.user_ids.as_ref().is_none_or(|ids| e.user_id.is_some_and(|id| ids.contains(&id)))
+                && e.ipaddr.as_deref().is_some_and(|ip| geoip::is_public(ip) && !exempt.contains(ip))
+        });
+
+        let mut locations: HashMap<String, GeoLocation> = args
+            .locations
+            .into_iter()
+            .flatten()
+            .map(|(ip, l)| (ip, GeoLocation { latitude: l.latitude, longitude: l.longitude, city: l.city, country: l.country }))
+            .collect();
+        let unknown: BTreeSet<String> = events
+            .iter()
+            .filter_map(|e| e.ipaddr.clone())
+            .filter(|ip| !locations.contains_key(ip))
+            .collect();
+        let mut warnings = Vec::new();
+        if !unknown.is_empty() {
+            match geoip::url_from_env() {
+                Some(template) => {
+                    let to_look_up: Vec<String> = unknown.iter().take(max_lookups).cloned().collect();
+                    if unknown.len() > to_look_up.len() {
+                        warnings.push(format!("{} IPs not looked up (max_lookups={}); their logins are skipped", unknown.len() - to_look_up.len(), max_lookups));
+                    }
+                    let (located, errors) = geoip::lookup_all(&template, &to_look_up).await.map_err(|e| anyhow!(e))?;
+                    locations.extend(located);
+                    warnings.extend(errors);
+                }
+                None => warnings.push(format!(
+                    "{} IPs have no location: set {} to a GeoIP URL template or pass locations; their logins are skipped",
+                    unknown.len(),
+                    geoip::GEOIP_URL_ENV_VAR
+                )),
+            }
+        }
+
+        let logins: Vec<Login> = events
+            .iter()
+            .filter_map(|event| {
+                let at = event_time(event)?;
+                let location = locations.get(event.ipaddr.as_deref()?)?;
+                Some(Login { event, at, location })
+            })
+            .collect();
+        let findings = find_impossible_travel(&logins, max_speed_kmh, min_distance_km);
+        let users: BTreeSet<i64> = logins.iter().filter_map(|l| l.event.user_id).collect();
+        Ok(json!({
+            "period": {"since": since.to_rfc3339(), "until": now.to_rfc3339(), "hours": hours},
+            "max_speed_kmh": max_speed_kmh,
+            "min_distance_km": min_distance_km,
+            "logins_scanned": events.len(),
+            "logins_located": logins.len(),
+            "users_checked": users.len(),
+            "pages": pages,
+            "truncated": truncated,
+            "warnings": warnings,
+            "count": findings.len(),
+            "findings": findings
+        }))
+    }
+}
+
+/// A login event with its time and source location
+struct Login<'a> {
+    event: &'a Event,
+    at: DateTime<Utc>,
+    location: &'a GeoLocation,
+}
+
+impl Login<'_> {
+    fn evidence(&self) -> Value {
+        json!({
+            "event_id": self.event.id,
+            "event_type_id": self.event.event_type_id,
+            "created_at": self.at.to_rfc3339(),
+            "ip": self.event.ipaddr,
+            "city": self.location.city,
+            "country": self.location.country,
+            "latitude": self.location.latitude,
+            "longitude": self.location.longitude,
+            "app_name": self.event.app_name
+        })
+    }
+}
+
+/// Consecutive logins of the same user too far apart for the time between them, fastest first
+fn find_impossible_travel(logins: &[Login], max_speed_kmh: f64, min_distance_km: f64) -> Vec<Value> {
+    let mut by_user: BTreeMap<i64, Vec<&Login>> = BTreeMap::new();
+    for login in logins {
+        if let Some(user_id) = login.event.user_id {
+            by_user.entry(user_id).or_default().push(login);
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (user_id, mut sequence) in by_user {
+        sequence.sort_by_key(|l| l.at);
+        for pair in sequence.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if from.event.ipaddr == to.event.ipaddr {
+                continue;
+            }
+            let distance_km = geoip::distance_km(from.location, to.location);
+            if distance_km < min_distance_km {
+                continue;
+            }
+            let hours = (to.at - from.at).num_seconds() as f64 / 3600.0;
+            // Simultaneous logins from two places: no speed could explain them
+            let speed_kmh = if hours > 0.0 { distance_km / hours } else { f64::INFINITY };
+            if speed_kmh <= max_speed_kmh {
+                continue;
+            }
+            let same_browser = match (from.event.browser_fingerprint.as_deref(), to.event.browser_fingerprint.as_deref()) {
+                (Some(a), Some(b)) => Some(a == b),
+                _ => None,
+            };
+            findings.push(json!({
+                "user_id": user_id,
+                "user_name": to.event.user_name.as_ref().or(from.event.user_name.as_ref()),
+                "severity": if speed_kmh > max_speed_kmh * 3.0 { "high" } else { "medium" },
+                "distance_km": distance_km.round(),
+                "elapsed_minutes": (to.at - from.at).num_minutes(),
+                "speed_kmh": speed_kmh.is_finite().then_some(speed_kmh.round()),
+                "same_browser": same_browser,
+                "from": from.evidence(),
+                "to": to.evidence(),
+                "suggested_actions": [
+                    {
+                        "tool": "onelogin_get_user",
+                        "arguments": {"user_id": user_id},
+                        "reason": "confirm with the user whether both logins were theirs, e.g. travel or a VPN"
+                    },
+                    {
+                        "tool": "onelogin_logout_user",
+                        "arguments": {"user_id": user_id},
+                        "reason": "end the sessions the later login may have opened"
+                    },
+                    {
+                        "tool": "onelogin_set_password",
+                        "arguments": {"user_id": user_id},
+                        "reason": "if the user can't explain the login, the password may be known to someone else"
+                    }
+                ]
+            }));
+        }
+    }
+    findings.sort_by(|a, b| {
+        let speed = |f: &Value| f["speed_kmh"].as_f64().unwrap_or(f64::INFINITY);
+        speed(b).total_cmp(&speed(a))
+    });
+    findings
+}
+
+/// Events of `event_types` since `since`, with the pages fetched and whether any type was cut off at max_pages
+async fn fetch_events(
+    client: &OneLoginClient,
+    event_types: &[i32],
+    since: DateTime<Utc>,
+    max_pages: usize,
+) -> Result<(Vec<Event>, usize, bool)> {
+    let mut events = Vec::new();
+    let mut pages = 0;
+    let mut truncated = false;
+    for &event_type_id in event_types {
+        let params = EventQueryParams {
+            since: Some(since.to_rfc3339()),
+            event_type_id: Some(event_type_id),
+            ..Default::default()
+        };
+        let (batch, fetched, next_cursor) = client.events.list_events_paginated(params, max_pages).await
+            .map_err(|e| anyhow!("Failed to list events of type {}: {}", event_type_id, e))?;
+        events.extend(batch);
+        pages += fetched;
+        truncated |= next_cursor.is_some();
+    }
+    Ok((events, pages, truncated))
+}
+
+struct Thresholds {
+    window: Duration,
+    ip: usize,
+    user: usize,
+    stuffing_users: usize,
+    distributed_ips: usize,
+}
+
+fn event_time(event: &Event) -> Option<DateTime<Utc>> {
+    event
+        .created_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Who a login attempt was for: the user id, or the attempted username for unknown users
+fn target(event: &Event) -> Option<String> {
+    match (event.user_id, event.user_name.as_deref()) {
+        (Some(id), _) if id > 0 => Some(id.to_string()),
+        (_, Some(name)) if !name.is_empty() => Some(name.to_lowercase()),
+        _ => None,
+    }
+}
+
+/// Most timestamps (sorted) that fit in one `window`, with the window's start
+fn peak_in_window(times: &[DateTime<Utc>], window: Duration) -> (usize, Option<DateTime<Utc>>) {
+    let mut best = (0, None);
+    let mut start = 0;
+    for (end, &at) in times.iter().enumerate() {
+        while at - times[start] > window {
+            start += 1;
+        }
+        if end + 1 - start > best.0 {
+            best = (end + 1 - start, Some(times[start]));
+        }
+    }
+    best
+}
+
+/// Failed attempts sharing a source IP or a target
+#[derive(Default)]
+struct Group<'a> {
+    events: Vec<(&'a Event, DateTime<Utc>)>,
+    ips: BTreeSet<String>,
+    targets: BTreeSet<String>,
+    user_ids: BTreeSet<i64>,
+}
+
+impl<'a> Group<'a> {
+    fn add(&mut self, event: &'a Event, at: DateTime<Utc>) {
+        self.events.push((event, at));
+        self.ips.extend(event.ipaddr.clone());
+        self.targets.extend(target(event));
+        self.user_ids.extend(event.user_id.filter(|id| *id > 0));
+    }
+
+    fn times(&self) -> Vec<DateTime<Utc>> {
+        let mut times: Vec<DateTime<Utc>> = self.events.iter().map(|(_, at)| *at).collect();
+        times.sort();
+        times
+    }
+
+    fn evidence(&self, window: Duration) -> Value {
+        let times = self.times();
+        let (peak, peak_start) = peak_in_window(&times, window);
+        json!({
+            "failures": self.events.len(),
+            "peak_failures_in_window": peak,
+            "peak_window_start": peak_start.map(|t| t.to_rfc3339()),
+            "first_seen": times.first().map(|t| t.to_rfc3339()),
+            "last_seen": times.last().map(|t| t.to_rfc3339()),
+            "source_ips": self.ips,
+            "targets": self.targets,
+            "event_ids": self.events.iter().take(20).map(|(e, _)| e.id).collect::<Vec<_>>()
+        })
+    }
+}
+
+/// First successful login at or after `after` that `matches`
+fn success_after(successes: &[Event], after: DateTime<Utc>, matches: impl Fn(&Event) -> bool) -> Option<&Event> {
+    successes
+        .iter()
+        .filter(|e| matches(e))
+        .find(|e| event_time(e).is_some_and(|t| t >= after))
+}
+
+fn lock_actions(user_ids: &BTreeSet<i64>, compromised: bool) -> Vec<Value> {
+    let mut actions = Vec::new();
+    for user_id in user_ids {
+        actions.push(json!({
+            "tool": "onelogin_lock_user",
+            "arguments": {"user_id": user_id, "locked_until": SUGGESTED_LOCK_MINUTES},
+            "reason": "stop further guesses against this account"
+        }));
+        if compromised {
+            actions.push(json!({
+                "tool": "onelogin_logout_user",
+                "arguments": {"user_id": user_id},
+                "reason": "a login succeeded after the failures; end the sessions it may have opened"
+            }));
+            actions.push(json!({
+                "tool": "onelogin_set_password",
+                "arguments": {"user_id": user_id},
+                "reason": "the password may have been guessed; reset it and review MFA enrollment"
+            }));
+        }
+    }
+    actions
+}
+
+fn block_ip_action(ip: &str) -> Value {
+    json!({
+        "tool": "onelogin_create_risk_rule",
+        "arguments": {
+            "name": format!("Block {}", ip),
+            "description": "Source of repeated failed logins",
+            "enabled": true,
+            "conditions": [{"field": "ip", "operator": "equals", "value": ip}],
+            "action": {"action_type": "deny"},
+            "priority": 1
+        },
+        "reason": "deny logins from this address; blocking it at the network edge works too"
+    })
+}
+
+/// Brute-force findings in `failures`, most severe first
+fn detect(failures: &[Event], successes: &[Event], thresholds: &Thresholds) -> Vec<Value> {
+    let mut by_ip: BTreeMap<String, Group> = BTreeMap::new();
+    let mut by_target: BTreeMap<String, Group> = BTreeMap::new();
+    for event in failures {
+        let Some(at) = event_time(event) else {
+            continue;
+        };
+        if let Some(ip) = event.ipaddr.as_deref().filter(|ip| !ip.is_empty()) {
+            by_ip.entry(ip.to_string()).or_default().add(event, at);
+        }
+        if let Some(target) = target(event) {
+            by_target.entry(target).or_default().add(event, at);
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (ip, group) in &by_ip {
+        let (peak, _) = peak_in_window(&group.times(), thresholds.window);
+        let stuffing = group.targets.len() >= thresholds.stuffing_users;
+        if peak < thresholds.ip && !stuffing {
+            continue;
+        }
+        let first = group.times()[0];
+        let success = success_after(successes, first, |e| e.ipaddr.as_deref() == Some(ip.as_str()));
+        let mut actions = vec![block_ip_action(ip)];
+        if let Some(user_id) = success.and_then(|s| s.user_id) {
+            actions.extend(lock_actions(&BTreeSet::from([user_id]), true));
+        }
+        findings.push(json!({
+            "pattern": if stuffing { "credential_stuffing" } else { "brute_force_ip" },
+            "severity": if stuffing || success.is_some() { "high" } else { "medium" },
+            "source_ip": ip,
+            "followed_by_success": success.is_some(),
+            "success_event": success.map(|s| json!({"id": s.id, "user_id": s.user_id, "created_at": s.created_at})),
+            "evidence": group.evidence(thresholds.window),
+            "suggested_actions": actions
+        }));
+    }
+    for (target, group) in &by_target {
+        let (peak, _) = peak_in_window(&group.times(), thresholds.window);
+        if peak < thresholds.user {
+            continue;
+        }
+        let distributed = group.ips.len() >= thresholds.distributed_ips;
+        let first = group.times()[0];
+        let success = success_after(successes, first, |e| self::target(e).as_deref() == Some(target.as_str()));
+        let mut actions = lock_actions(&group.user_ids, success.is_some());
+        if !distributed {
+            actions.extend(group.ips.iter().map(|ip| block_ip_action(ip)));
+        }
+        findings.push(json!({
+            "pattern": if distributed { "distributed_brute_force" } else { "brute_force_user" },
+            "severity": if success.is_some() { "high" } else { "medium" },
+            "target": target,
+            "user_id": group.user_ids.iter().next(),
+            "followed_by_success": success.is_some(),
+            "success_event": success.map(|s| json!({"id": s.id, "ip": s.ipaddr, "created_at": s.created_at})),
+            "evidence": group.evidence(thresholds.window),
+            "suggested_actions": actions
+        }));
+    }
+    findings.sort_by_key(|f| (f["severity"] != "high", std::cmp::Reverse(f["evidence"]["peak_failures_in_window"].as_u64())));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: i64, user_id: i64, ip: &str, minute: i64) -> Event {
+        let at = DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z").unwrap() + Duration::minutes(minute);
+        serde_json::from_value(json!({
+            "id": id,
+            "event_type_id": EVENT_LOGIN_FAILED,
+            "user_id": user_id,
+            "ipaddr": ip,
+            "created_at": at.to_rfc3339()
+        }))
+        .unwrap()
+    }
+
+    fn thresholds() -> Thresholds {
+        Thresholds { window: Duration::minutes(15), ip: 5, user: 4, stuffing_users: 3, distributed_ips: 3 }
+    }
+
+    #[test]
+    fn test_find_impossible_travel() {
+        let place = |latitude, longitude| GeoLocation { latitude, longitude, city: None, country: None };
+        let (london, paris, new_york) = (place(51.5074, -0.1278), place(48.8566, 2.3522), place(40.7128, -74.006));
+        let events = [
+            event(1, 7, "81.2.69.1", 0),
+            event(2, 7, "90.3.1.1", 120), // Paris two hours later: plausible
+            event(3, 7, "3.4.5.6", 180), // New York an hour after Paris
+            event(4, 8, "81.2.69.1", 0),
+            event(5, 8, "81.2.69.2", 1), // Different IP, same city
+        ];
+        let locations = [&london, &paris, &new_york, &london, &london];
+        let logins: Vec<Login> = events
+            .iter()
+            .zip(locations)
+            .map(|(event, location)| Login { event, at: event_time(event).unwrap(), location })
+            .collect();
+
+        let findings = find_impossible_travel(&logins, 1000.0, 300.0);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0]["user_id"], 7);
+        assert_eq!(findings[0]["from"]["event_id"], 2);
+        assert_eq!(findings[0]["to"]["event_id"], 3);
+        assert_eq!(findings[0]["elapsed_minutes"], 60);
+        assert!(findings[0]["speed_kmh"].as_f64().unwrap() > 5000.0);
+        assert_eq!(findings[0]["severity"], "high");
+        assert!(find_impossible_travel(&logins, 10000.0, 300.0).is_empty());
+    }
+
+    #[test]
+    fn test_peak_in_window() {
+        let base = Utc::now();
+        let times: Vec<DateTime<Utc>> = [0, 1, 2, 30, 31].iter().map(|m| base + Duration::minutes(*m)).collect();
+        assert_eq!(peak_in_window(&times, Duration::minutes(15)).0, 3);
+        assert_eq!(peak_in_window(&times, Duration::minutes(60)).0, 5);
+        assert_eq!(peak_in_window(&[], Duration::minutes(15)).0, 0);
+    }
+
+    #[test]
+    fn test_detect() {
+        // 10.0.0.1 tries three users; user 7 is hammered from 10.0.0.2 and then logs in
+        let mut failures = vec![event(1, 1, "10.0.0.1", 0), event(2, 2, "10.0.0.1", 1), event(3, 3, "10.0.0.1", 2)];
+        failures.extend((0..4).map(|i| event(10 + i, 7, "10.0.0.2", i)));
+        // Spread over hours: under every threshold
+        failures.extend((0..4).map(|i| event(20 + i, 8, "10.0.0.3", i * 60)));
+        let mut success = event(30, 7, "10.0.0.2", 5);
+        success.event_type_id = EVENT_LOGIN;
+
+        let findings = detect(&failures, &[success], &thresholds());
+        let patterns: Vec<&str> = findings.iter().map(|f| f["pattern"].as_str().unwrap()).collect();
+        assert_eq!(patterns, vec!["brute_force_user", "credential_stuffing"]);
+        assert_eq!(findings[0]["followed_by_success"], true);
+        assert_eq!(findings[0]["user_id"], 7);
+        let tools: Vec<&str> = findings[0]["suggested_actions"].as_array().unwrap().iter().map(|a| a["tool"].as_str().unwrap()).collect();
+        assert!(tools.contains(&"onelogin_logout_user") && tools.contains(&"onelogin_create_risk_rule"));
+        assert_eq!(findings[1]["evidence"]["targets"].as_array().unwrap().len(), 3);
+    }
+}
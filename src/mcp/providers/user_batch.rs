@@ -0,0 +1,175 @@
+use crate::api::OneLoginClient;
+use crate::core::error::OneLoginError;
+use crate::models::users::User;
+use anyhow::{anyhow, Result};
+use futures::stream::{self, StreamExt};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// Most ids accepted in one call
+const MAX_BATCH_IDS: usize = 100;
+const DEFAULT_CONCURRENCY: usize = 8;
+const MAX_CONCURRENCY: usize = 16;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetUsersBatchArgs {
+    /// User ids to fetch (up to 100); duplicates are fetched once
+    pub user_ids: Vec<i64>,
+    /// Requests in flight at once, 1-16. Default: 8
+    pub concurrency: Option<usize>,
+    /// Resolve ids to names: "roles" adds roles [{id, name}], "group" adds group {id, name}
+    pub expand: Option<Vec<UserExpansion>>,
+}
+
+/// User references that `expand` resolves to names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UserExpansion {
+    Roles,
+    Group,
+}
+
+/// Embed role and group names next to `role_ids` and `group_id` in a user object or
+/// array of users. Names come from the cached role and group lists; a list that can't
+/// be fetched is reported in the returned warnings instead of failing the call.
+pub async fn expand_user_references(client: &OneLoginClient, users: &mut Value, expand: &[UserExpansion]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut users: Vec<&mut Value> = if users.is_array() {
+        users.as_array_mut().into_iter().flatten().collect()
+    } else if users.is_object() {
+        vec![users]
+    } else {
+        return warnings;
+    };
+
+    if expand.contains(&UserExpansion::Roles) {
+        match client.roles.role_names().await {
+            Ok(names) => {
+                for user in users.iter_mut() {
+                    let roles: Vec<Value> = user["role_ids"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_i64)
+                        .map(|id| json!({"id": id, "name": names.get(&id)}))
+                        .collect();
+                    user["roles"] = json!(roles);
+                }
+            }
+            Err(e) => warnings.push(format!("Role names unavailable: {}", e)),
+        }
+    }
+    if expand.contains(&UserExpansion::Group) {
+        match client.groups.group_names().await {
+            Ok(names) => {
+                for user in users.iter_mut() {
+                    user["group"] = match user["group_id"].as_i64() {
+                        Some(id) => json!({"id": id, "name": names.get(&id)}),
+                        None => Value::Null,
+                    };
+                }
+            }
+            Err(e) => warnings.push(format!("Group names unavailable: {}", e)),
+        }
+    }
+    warnings
+}
+
+tool_provider! {
+    /// Fetching many users by id in one call
+    pub struct UserBatchProvider("users") {
+        "onelogin_get_users_batch" => get_users_batch(GetUsersBatchArgs):
+            "Get full details for a list of specific users (up to 100 ids) in one call instead of calling onelogin_get_user repeatedly. Users are fetched concurrently (bounded, default 8 at a time, still subject to the client rate limit) and cached users are served from the cache. Returns 'found' (user objects in the order requested), 'missing' (ids that do not exist) and 'failed' (ids whose lookup errored, with the error). expand=[\"roles\", \"group\"] adds role and group names. Read-only.",
+    }
+}
+
+impl UserBatchProvider {
+    async fn get_users_batch(&self, client: &OneLoginClient, args: GetUsersBatchArgs) -> Result<Value> {
+        let ids = unique_ids(&args.user_ids);
+        if ids.is_empty() {
+            return Err(anyhow!("user_ids must contain at least one id"));
+        }
+        if ids.len() > MAX_BATCH_IDS {
+            return Err(anyhow!("At most {} user ids per call; got {}", MAX_BATCH_IDS, ids.len()));
+        }
+        let concurrency = args.concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+
+        // `buffered` keeps the requested order while running up to `concurrency` lookups
+        let outcomes: Vec<(i64, std::result::Result<User, OneLoginError>)> = stream::iter(ids)
+            .map(|id| async move { (id, client.users.get_user(id).await) })
+            .buffered(concurrency)
+            .collect()
+            .await;
+        let batch = partition(outcomes);
+
+        let mut result = json!({
+            "requested": args.user_ids.len(),
+            "counts": {
+                "found": batch.found.len(),
+                "missing": batch.missing.len(),
+                "failed": batch.failed.len(),
+            },
+            "found": batch.found,
+            "missing": batch.missing,
+            "failed": batch.failed,
+        });
+        if let Some(expand) = args.expand.as_deref() {
+            let warnings = expand_user_references(client, &mut result["found"], expand).await;
+            if !warnings.is_empty() {
+                result["expand_warnings"] = json!(warnings);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Lookup outcomes split into found users, ids that don't exist and errors
+struct Batch {
+    found: Vec<User>,
+    missing: Vec<i64>,
+    failed: Vec<Value>,
+}
+
+/// Requested ids without duplicates, first occurrence first
+fn unique_ids(ids: &[i64]) -> Vec<i64> {
+    let mut seen = HashSet::new();
+    ids.iter().copied().filter(|id| seen.insert(*id)).collect()
+}
+
+fn partition(outcomes: Vec<(i64, std::result::Result<User, OneLoginError>)>) -> Batch {
+    let mut batch = Batch {
+        found: Vec::new(),
+        missing: Vec::new(),
+        failed: Vec::new(),
+    };
+    for (id, outcome) in outcomes {
+        match outcome {
+            Ok(user) => batch.found.push(user),
+            Err(e) if e.is_not_found() => batch.missing.push(id),
+            Err(e) => batch.failed.push(json!({"user_id": id, "error": e.to_string()})),
+        }
+    }
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_outcomes() {
+        assert_eq!(unique_ids(&[3, 1, 3, 2, 1]), vec![3, 1, 2]);
+
+        let user: User = serde_json::from_value(json!({"id": 1, "email": "a@example.com"})).unwrap();
+        let batch = partition(vec![
+            (1, Ok(user)),
+            (2, Err(OneLoginError::NotFound("user 2".to_string()))),
+            (3, Err(OneLoginError::RateLimitExceeded)),
+        ]);
+        assert_eq!(batch.found.len(), 1);
+        assert_eq!(batch.missing, vec![2]);
+        assert_eq!(batch.failed[0]["user_id"], 3);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::api::OneLoginClient;
+use crate::mcp::tools::{check_custom_attribute_names, custom_attribute_filters, tool_deadline};
+use crate::models::users::UserQueryParams;
+use crate::utils::anonymize::Anonymizer;
+use crate::utils::export::{resolve_output_path, ExportFormat, RowStreamWriter};
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ExportUsersArgs {
+    /// File to write, relative to the export directory. Absolute paths and '..' are rejected; parent directories are created
+    pub output_path: String,
+    /// File format: ndjson, csv or json. Default: inferred from the file extension, otherwise csv. CSV columns come from the first page
+    pub output_format: Option<String>,
+    /// Replace output_path if it already exists. Default: false
+    pub overwrite: Option<bool>,
+    /// Pseudonymize emails, usernames and names and drop phone numbers. Default: false
+    pub anonymize: Option<bool>,
+    /// Key for the pseudonyms. Exports with the same salt give a person the same pseudonym, so they can be joined; without it every export uses a random key. Keep it secret: with the salt, guessed values can be checked against the pseudonyms
+    pub anonymize_salt: Option<String>,
+    /// User fields to leave out of every row, e.g. ["custom_attributes", "manager_ad_id"]
+    pub drop_fields: Option<Vec<String>>,
+    /// Only export the user with this exact email address
+    pub email: Option<String>,
+    /// Only export users from this directory
+    pub directory_id: Option<i64>,
+    /// Only export users with this role
+    pub role_id: Option<i64>,
+    /// Only export users with these custom attribute values (strings, numbers or booleans), keyed by shortname. All must match
+    pub custom_attributes: Option<HashMap<String, Value>>,
+    /// Stop after this many pages. Default: every page
+    pub max_pages: Option<i32>,
+    /// Stop after this many users. Default: every user
+    pub max_results: Option<u32>,
+}
+
+tool_provider! {
+    /// Streaming user exports to local files
+    pub struct UserExportProvider("users") {
+        "onelogin_export_users" => export_users(ExportUsersArgs):
+            "Write every user (or those matching the filters) to a local file, page by page. With anonymize=true emails, usernames, first and last names are replaced by keyed-hash pseudonyms (emails become <hash>@anonymized.invalid) and phone numbers are dropped, so the file can be shared with analysts or loaded into a test tenant; the same person gets the same pseudonym in every row. Other fields such as custom_attributes and manager ids are kept unless listed in drop_fields. Returns the path, row count, the anonymized and dropped fields, and nextPage if the export stopped early.",
+    }
+}
+
+impl UserExportProvider {
+    async fn export_users(&self, client: &OneLoginClient, args: ExportUsersArgs) -> Result<Value> {
+        let params = UserQueryParams {
+            email: args.email.clone(),
+            directory_id: args.directory_id,
+            role_id: args.role_id,
+            custom_attributes: custom_attribute_filters(&Value::Null, args.custom_attributes.as_ref())?,
+            ..Default::default()
+        };
+        check_custom_attribute_names(client, params.custom_attributes.keys().map(String::as_str)).await?;
+        stream_users_to_file(client, params, &args).await
+    }
+}
+
+/// Page through users straight into `args.output_path`, for exports too large to return inline.
+/// Without max_pages/max_results every matching user is written. With `anonymize` rows are
+/// pseudonymized before they reach the file, and `drop_fields` are removed from every row.
+/// The filters in `args` are not read; `params` already carries them.
+pub(crate) async fn stream_users_to_file(
+    client: &OneLoginClient,
+    mut params: UserQueryParams,
+    args: &ExportUsersArgs,
+) -> Result<Value> {
+    let path = resolve_output_path(&args.output_path)?;
+    let format = ExportFormat::resolve(args.output_format.as_deref(), &path);
+    let mut writer = RowStreamWriter::create(&path, format, args.overwrite.unwrap_or(false))
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let limit = params.limit.unwrap_or(100).clamp(1, 200);
+    params.limit = Some(limit);
+    let max_pages = args.max_pages.map(|p| p.max(1));
+    let max_results = args.max_results.map(|m| m as usize);
+    let anonymizer = args.anonymize.unwrap_or(false).then(|| match args.anonymize_salt.as_deref() {
+        Some(salt) if !salt.is_empty() => Anonymizer::with_salt(salt),
+        _ => Anonymizer::random(),
+    });
+    let drop_fields = args.drop_fields.clone().unwrap_or_default();
+    let started = std::time::Instant::now();
+    let deadline = tool_deadline();
+
+    let mut page = params.page.unwrap_or(1).max(1);
+    let mut pages_fetched = 0;
+    let mut timed_out = false;
+    let mut error = None;
+    let next_page = loop {
+        if max_pages.is_some_and(|m| pages_fetched >= m) || max_results.is_some_and(|m| writer.row_count() >= m) {
+            break Some(page);
+        }
+        params.page = Some(page);
+        let fetch = client.users.list_users(Some(params.clone()));
+        let fetched = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, fetch).await {
+                Ok(fetched) => fetched,
+                Err(_) => {
+                    timed_out = true;
+                    break Some(page);
+                }
+            },
+            None => fetch.await,
+        };
+        let batch = match fetched {
+            Ok(batch) => batch,
+            Err(e) => {
+                warn!("Failed to fetch users page {} for export: {}", page, e);
+                error = Some(format!("Failed to list users (page {}): {}", page, e));
+                break Some(page);
+            }
+        };
+        if batch.is_empty() {
+            break None;
+        }
+        let full_page = batch.len() >= limit as usize;
+        let mut rows = batch.iter().map(serde_json::to_value).collect::<std::result::Result<Vec<_>, _>>()?;
+        if let Some(max) = max_results {
+            rows.truncate(max - writer.row_count());
+        }
+        for row in &mut rows {
+            if let Some(anonymizer) = &anonymizer {
+                anonymizer.anonymize_user(row);
+            }
+            if let Some(map) = row.as_object_mut() {
+                for field in &drop_fields {
+                    map.remove(field);
+                }
+            }
+        }
+        writer.write_rows(&rows).with_context(|| format!("Failed to write to {}", path.display()))?;
+        pages_fetched += 1;
+        info!("Exported users page {} ({} rows so far) to {}", page, writer.row_count(), path.display());
+        page += 1;
+        if !full_page {
+            break None;
+        }
+    };
+
+    let summary = writer.finish().with_context(|| format!("Failed to write to {}", path.display()))?;
+    Ok(json!({
+        "status": if timed_out || error.is_some() { "partial" } else { "exported" },
+        "partial": timed_out || error.is_some(),
+        "path": summary.path.display().to_string(),
+        "format": summary.format.as_str(),
+        "row_count": summary.row_count,
+        "bytes_written": summary.bytes_written,
+        "anonymized_fields": anonymizer.map(|_| Anonymizer::affected_fields()),
+        "dropped_fields": drop_fields,
+        "pagesFetched": pages_fetched,
+        "elapsed_seconds": started.elapsed().as_secs(),
+        "nextPage": next_page,
+        "timedOut": timed_out,
+        "error": error
+    }))
+}
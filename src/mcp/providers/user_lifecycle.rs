@@ -0,0 +1,193 @@
+use crate::api::OneLoginClient;
+use crate::mcp::outcome::Outcome;
+use crate::models::users::{LockUserRequest, UpdateUserRequest};
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// User status codes (see `User::status`)
+const STATUS_UNACTIVATED: i32 = 0;
+const STATUS_ACTIVE: i32 = 1;
+const STATUS_SUSPENDED: i32 = 2;
+const STATUS_LOCKED: i32 = 3;
+
+/// Status a user can be moved to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetStatus {
+    Active,
+    Suspended,
+    Locked,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TransitionUserArgs {
+    /// The user to transition
+    pub user_id: i64,
+    /// Status to move the user to: active, suspended or locked
+    pub to: TargetStatus,
+    /// Minutes to lock for when to=locked. 0 uses the account policy. Default: 0
+    pub lock_minutes: Option<i32>,
+    /// Sign the user out of all sessions when suspending or locking. Default: true
+    pub revoke_sessions: Option<bool>,
+    /// Return the planned steps without making changes. Default: false
+    pub dry_run: Option<bool>,
+}
+
+tool_provider! {
+    /// Guarded user status transitions
+    pub struct UserLifecycleProvider("users") {
+        "onelogin_transition_user_status" => transition_user_status(TransitionUserArgs):
+            "Move a user to 'active', 'suspended' or 'locked', checking the transition is valid from the current status and performing the required side effects. Prefer this over setting raw status integers with onelogin_update_user. Valid transitions: unactivated -> active or suspended; active -> suspended or locked; suspended -> active; locked -> active (unlock) or suspended; password-pending states -> suspended. Suspending or locking also revokes the user's sessions unless revoke_sessions=false. Use dry_run=true to preview the steps. Returns the previous status, the steps taken and the standard composite result: complete, succeeded, failed (with reasons) and not_attempted steps.",
+    }
+}
+
+impl UserLifecycleProvider {
+    async fn transition_user_status(&self, client: &OneLoginClient, args: TransitionUserArgs) -> Result<Value> {
+        let user = client.users.get_user(args.user_id).await
+            .map_err(|e| anyhow!("Failed to get user {}: {}", args.user_id, e))?;
+        let revoke_sessions = args.revoke_sessions.unwrap_or(true);
+        let steps = plan_transition(user.status, args.to, revoke_sessions).map_err(|e| anyhow!(e))?;
+
+        let mut result = json!({
+            "user_id": user.id,
+            "from": status_name(user.status),
+            "to": args.to,
+            "dry_run": args.dry_run.unwrap_or(false)
+        });
+        if steps.is_empty() {
+            result["complete"] = json!(true);
+            result["steps"] = json!([]);
+            result["message"] = json!(format!("User is already {}", status_name(user.status)));
+            return Ok(result);
+        }
+        if args.dry_run.unwrap_or(false) {
+            result["steps"] = json!(steps.iter().map(|s| json!({"step": s.name(), "status": "planned"})).collect::<Vec<_>>());
+            return Ok(result);
+        }
+
+        // Stop at the first failure: later steps assume the earlier ones took effect
+        let mut outcomes = Vec::new();
+        let mut outcome = Outcome::default();
+        for step in &steps {
+            if outcome.has_failures() {
+                outcome.not_attempted(json!({"step": step.name()}));
+                continue;
+            }
+            match run_step(client, user.id, *step, args.lock_minutes.unwrap_or(0)).await {
+                Ok(()) => {
+                    outcome.succeeded(json!({"step": step.name()}));
+                    outcomes.push(json!({"step": step.name(), "status": "done"}));
+                }
+                Err(e) => {
+                    outcome.failed(json!({"step": step.name()}), &e);
+                    outcomes.push(json!({"step": step.name(), "status": "failed", "error": e.to_string()}));
+                }
+            }
+        }
+        result["steps"] = json!(outcomes);
+        outcome.merge_into(&mut result);
+        Ok(result)
+    }
+}
+
+/// One API call in a transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    SetStatus(i32),
+    Lock,
+    Unlock,
+    RevokeSessions,
+}
+
+impl Step {
+    fn name(&self) -> String {
+        match self {
+            Step::SetStatus(status) => format!("set_status:{}", status_name(*status)),
+            Step::Lock => "lock".to_string(),
+            Step::Unlock => "unlock".to_string(),
+            Step::RevokeSessions => "revoke_sessions".to_string(),
+        }
+    }
+}
+
+async fn run_step(client: &OneLoginClient, user_id: i64, step: Step, lock_minutes: i32) -> crate::core::error::Result<()> {
+    match step {
+        Step::SetStatus(status) => {
+            let request = UpdateUserRequest {
+                status: Some(status),
+                ..Default::default()
+            };
+            client.users.update_user(user_id, request).await.map(|_| ())
+        }
+        Step::Lock => client.users.lock_user(user_id, LockUserRequest { locked_until: lock_minutes.max(0) }).await,
+        Step::Unlock => client.users.unlock_user(user_id).await,
+        Step::RevokeSessions => client.users.logout_user(user_id).await,
+    }
+}
+
+/// Steps moving a user from `from` to `to`; empty when already there, `Err` when not allowed
+fn plan_transition(from: i32, to: TargetStatus, revoke_sessions: bool) -> std::result::Result<Vec<Step>, String> {
+    let mut steps = match (from, to) {
+        (STATUS_ACTIVE, TargetStatus::Active)
+        | (STATUS_SUSPENDED, TargetStatus::Suspended)
+        | (STATUS_LOCKED, TargetStatus::Locked) => return Ok(Vec::new()),
+        (STATUS_UNACTIVATED | STATUS_SUSPENDED, TargetStatus::Active) => vec![Step::SetStatus(STATUS_ACTIVE)],
+        (STATUS_LOCKED, TargetStatus::Active) => vec![Step::Unlock],
+        (STATUS_ACTIVE, TargetStatus::Locked) => vec![Step::Lock],
+        (_, TargetStatus::Suspended) => vec![Step::SetStatus(STATUS_SUSPENDED)],
+        (from, to) => {
+            let hint = match to {
+                TargetStatus::Active => "the user must complete their pending password or security step",
+                _ => "only active users can be locked; suspend the user instead",
+            };
+            return Err(format!("Cannot move a {} user to {:?}: {}", status_name(from), to, hint));
+        }
+    };
+    if revoke_sessions && matches!(to, TargetStatus::Suspended | TargetStatus::Locked) {
+        steps.push(Step::RevokeSessions);
+    }
+    Ok(steps)
+}
+
+fn status_name(status: i32) -> &'static str {
+    match status {
+        STATUS_UNACTIVATED => "unactivated",
+        STATUS_ACTIVE => "active",
+        STATUS_SUSPENDED => "suspended",
+        STATUS_LOCKED => "locked",
+        4 => "password_expired",
+        5 => "awaiting_password_reset",
+        7 => "password_pending",
+        8 => "security_questions_required",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_transition() {
+        assert_eq!(
+            plan_transition(STATUS_ACTIVE, TargetStatus::Suspended, true).unwrap(),
+            vec![Step::SetStatus(STATUS_SUSPENDED), Step::RevokeSessions]
+        );
+        assert_eq!(
+            plan_transition(STATUS_ACTIVE, TargetStatus::Suspended, false).unwrap(),
+            vec![Step::SetStatus(STATUS_SUSPENDED)]
+        );
+        assert_eq!(plan_transition(STATUS_LOCKED, TargetStatus::Active, true).unwrap(), vec![Step::Unlock]);
+        assert_eq!(
+            plan_transition(STATUS_UNACTIVATED, TargetStatus::Active, true).unwrap(),
+            vec![Step::SetStatus(STATUS_ACTIVE)]
+        );
+        assert!(plan_transition(STATUS_SUSPENDED, TargetStatus::Suspended, true).unwrap().is_empty());
+
+        assert!(plan_transition(STATUS_SUSPENDED, TargetStatus::Locked, true).is_err());
+        let err = plan_transition(7, TargetStatus::Active, true).unwrap_err();
+        assert!(err.contains("password_pending"));
+    }
+}
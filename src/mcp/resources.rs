@@ -0,0 +1,527 @@
+//! MCP resources for OneLogin objects and change subscriptions.
+//!
+//! Users, apps, roles and password policies can be browsed without tool calls:
+//! `onelogin://users` (likewise `apps`, `roles`, `policies`) reads the first
+//! page of the collection and `onelogin://users/{id}` one object, optionally
+//! with `?tenant=<name>`. `resources/list` names the collections and
+//! `resources/templates/list` the per-object URI templates; each kind follows
+//! the tool config of its list or get tool. Resources can be watched with
+//! `resources/subscribe`: watched ones are fetched again every
+//! `ONELOGIN_RESOURCE_POLL_SECONDS`, bypassing the cache; when one differs from
+//! the copy seen last, the server sends `notifications/resources/updated`.
+//! Subscriptions belong to the client session that made them: a WebSocket
+//! client is only notified about, and can only unsubscribe from, its own.
+
+use crate::api::OneLoginClient;
+use crate::core::cache::FORCE_REFRESH;
+use crate::core::error::OneLoginError;
+use crate::models::apps::AppQueryParams;
+use crate::models::roles::RoleQueryParams;
+use crate::models::users::UserQueryParams;
+use crate::core::tenant_manager::TenantManager;
+use crate::core::tool_config::ToolConfig;
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+pub const POLL_ENV_VAR: &str = "ONELOGIN_RESOURCE_POLL_SECONDS";
+
+const URI_SCHEME: &str = "onelogin://";
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Each subscribed URI costs one API call per poll
+const MAX_SUBSCRIPTIONS: usize = 100;
+
+/// A connected MCP client; subscriptions are tracked per session
+pub type SessionId = u64;
+/// The single client of the stdio transport
+pub const STDIO_SESSION: SessionId = 0;
+/// Objects returned when reading a collection; the list tools filter and page further
+const COLLECTION_PAGE_SIZE: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    User,
+    App,
+    Role,
+    Policy,
+}
+
+impl ResourceKind {
+    const ALL: [Self; 4] = [Self::User, Self::App, Self::Role, Self::Policy];
+
+    fn from_segment(segment: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.segment() == segment)
+    }
+
+    fn segment(&self) -> &'static str {
+        match self {
+            Self::User => "users",
+            Self::App => "apps",
+            Self::Role => "roles",
+            Self::Policy => "policies",
+        }
+    }
+
+    /// Tool whose permission governs reading one resource of this kind
+    fn read_tool(&self) -> &'static str {
+        match self {
+            Self::User => "onelogin_get_user",
+            Self::App => "onelogin_get_app",
+            Self::Role => "onelogin_get_role",
+            Self::Policy => "onelogin_get_password_policy",
+        }
+    }
+
+    /// Tool whose permission governs reading the collection
+    fn list_tool(&self) -> &'static str {
+        match self {
+            Self::User => "onelogin_list_users",
+            Self::App => "onelogin_list_apps",
+            Self::Role => "onelogin_list_roles",
+            Self::Policy => "onelogin_list_password_policies",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::User => "User",
+            Self::App => "App",
+            Self::Role => "Role",
+            Self::Policy => "Password policy",
+        }
+    }
+
+    fn plural(&self) -> &'static str {
+        match self {
+            Self::User => "Users",
+            Self::App => "Apps",
+            Self::Role => "Roles",
+            Self::Policy => "Password policies",
+        }
+    }
+}
+
+/// A parsed `onelogin://` resource URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceUri {
+    pub kind: ResourceKind,
+    /// `None` for the collection
+    pub id: Option<i64>,
+    pub tenant: Option<String>,
+}
+
+impl ResourceUri {
+    pub fn parse(uri: &str) -> Result<Self> {
+        let rest = uri
+            .strip_prefix(URI_SCHEME)
+            .ok_or_else(|| anyhow!("Unsupported resource URI '{}': expected e.g. onelogin://users or onelogin://users/{{id}}", uri))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (segment, id) = path.split_once('/').unwrap_or((path, ""));
+        let kind = ResourceKind::from_segment(segment).ok_or_else(|| {
+            anyhow!("Unsupported resource type '{}' in '{}': expected users, apps, roles or policies", segment, uri)
+        })?;
+        let id = match id.trim_end_matches('/') {
+            "" => None,
+            id => Some(id.parse().map_err(|_| anyhow!("Invalid id '{}' in resource URI '{}'", id, uri))?),
+        };
+        let mut tenant = None;
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            match pair.split_once('=') {
+                Some(("tenant", name)) if !name.is_empty() => tenant = Some(name.to_string()),
+                _ => bail!("Unsupported query parameter '{}' in resource URI '{}'", pair, uri),
+            }
+        }
+        Ok(Self { kind, id, tenant })
+    }
+
+    /// Current object or collection page from OneLogin, skipping the cache.
+    /// `None` when the object no longer exists.
+    pub async fn fetch(&self, tenants: &TenantManager) -> Result<Option<Value>> {
+        let client = tenants.resolve(self.tenant.as_deref())?;
+        let Some(id) = self.id else {
+            return FORCE_REFRESH
+                .scope(true, self.fetch_collection(&client))
+                .await
+                .map(Some)
+                .map_err(|e| anyhow!("Failed to list {}: {}", self.kind.segment(), e));
+        };
+        let result = FORCE_REFRESH
+            .scope(true, async {
+                Ok::<_, OneLoginError>(match self.kind {
+                    ResourceKind::User => serde_json::to_value(client.users.get_user(id).await?)?,
+                    ResourceKind::App => serde_json::to_value(client.apps.get_app(id).await?)?,
+                    ResourceKind::Role => serde_json::to_value(client.roles.get_role(id).await?)?,
+                    ResourceKind::Policy => serde_json::to_value(client.password_policies.get_password_policy(id).await?)?,
+                })
+            })
+            .await;
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(anyhow!("Failed to fetch {} {}: {}", self.kind.label().to_lowercase(), id, e)),
+        }
+    }
+
+    /// First page of the collection with the URI of each object
+    async fn fetch_collection(&self, client: &OneLoginClient) -> std::result::Result<Value, OneLoginError> {
+        let list = match self.kind {
+            ResourceKind::User => {
+                let params = UserQueryParams { limit: Some(COLLECTION_PAGE_SIZE), ..Default::default() };
+                serde_json::to_value(client.users.list_users(Some(params)).await?)?
+            }
+            ResourceKind::App => {
+                let params = AppQueryParams { limit: Some(COLLECTION_PAGE_SIZE), ..Default::default() };
+                serde_json::to_value(client.apps.list_apps(Some(params)).await?)?
+            }
+            ResourceKind::Role => {
+                let params = RoleQueryParams { limit: Some(COLLECTION_PAGE_SIZE), ..Default::default() };
+                serde_json::to_value(client.roles.list_roles_page(params).await?.roles)?
+            }
+            ResourceKind::Policy => serde_json::to_value(client.password_policies.list_password_policies().await?)?,
+        };
+        let Value::Array(items) = list else {
+            return Ok(serde_json::json!({"count": 0, "truncated": false, "items": []}));
+        };
+        let items: Vec<Value> = items
+            .into_iter()
+            .map(|mut item| {
+                if let Some(id) = item["id"].as_i64() {
+                    item["uri"] = Value::String(self.object_uri(id));
+                }
+                item
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "count": items.len(),
+            "truncated": self.kind != ResourceKind::Policy && items.len() >= COLLECTION_PAGE_SIZE as usize,
+            "hint": format!("Read an item's uri for details; {} filters and pages through all of them", self.kind.list_tool()),
+            "items": items
+        }))
+    }
+
+    /// URI of one object of this resource's kind and tenant
+    fn object_uri(&self, id: i64) -> String {
+        match &self.tenant {
+            Some(tenant) => format!("{}{}/{}?tenant={}", URI_SCHEME, self.kind.segment(), id, tenant),
+            None => format!("{}{}/{}", URI_SCHEME, self.kind.segment(), id),
+        }
+    }
+
+    /// Tool whose permission governs reading this resource
+    fn tool(&self) -> &'static str {
+        match self.id {
+            Some(_) => self.kind.read_tool(),
+            None => self.kind.list_tool(),
+        }
+    }
+
+    fn name(&self) -> String {
+        let name = match self.id {
+            Some(id) => format!("{} {}", self.kind.label(), id),
+            None => self.kind.plural().to_string(),
+        };
+        match &self.tenant {
+            Some(tenant) => format!("{} ({})", name, tenant),
+            None => name,
+        }
+    }
+}
+
+struct Watched {
+    resource: ResourceUri,
+    /// Object seen at the last poll; `None` once it has been deleted
+    last: Option<Value>,
+    /// Sessions subscribed to this URI
+    sessions: HashSet<SessionId>,
+}
+
+/// A watched resource changed
+#[derive(Debug, Clone)]
+pub struct ResourceUpdate {
+    pub uri: String,
+    /// Sessions subscribed to `uri` when the change was seen
+    pub sessions: HashSet<SessionId>,
+}
+
+impl Watched {
+    /// Store the latest fetch, reporting whether it differs from the previous one
+    fn record(&mut self, current: Option<Value>) -> bool {
+        if self.last == current {
+            return false;
+        }
+        self.last = current;
+        true
+    }
+}
+
+/// Resources the client subscribed to and the poller that watches them
+pub struct ResourceSubscriptions {
+    tenants: Arc<TenantManager>,
+    tool_config: Arc<ToolConfig>,
+    /// Keyed by the URI exactly as the client sent it, which is what notifications must echo
+    watched: Mutex<HashMap<String, Watched>>,
+    updates: broadcast::Sender<ResourceUpdate>,
+    poll_interval: Duration,
+    next_session: AtomicU64,
+}
+
+impl ResourceSubscriptions {
+    pub fn new(tenants: Arc<TenantManager>, tool_config: Arc<ToolConfig>, poll_interval: Duration) -> Self {
+        let (updates, _) = broadcast::channel(256);
+        Self {
+            tenants,
+            tool_config,
+            watched: Mutex::new(HashMap::new()),
+            updates,
+            poll_interval: poll_interval.max(MIN_POLL_INTERVAL),
+            next_session: AtomicU64::new(STDIO_SESSION + 1),
+        }
+    }
+
+    /// Poll interval from `ONELOGIN_RESOURCE_POLL_SECONDS` (default 60, minimum 5)
+    pub fn poll_interval_from_env() -> Result<Duration> {
+        match std::env::var(POLL_ENV_VAR) {
+            Ok(v) => {
+                let seconds: u64 = v.parse().with_context(|| format!("Invalid {}", POLL_ENV_VAR))?;
+                Ok(Duration::from_secs(seconds).max(MIN_POLL_INTERVAL))
+            }
+            Err(_) => Ok(DEFAULT_POLL_INTERVAL),
+        }
+    }
+
+    /// Changed resources, one message per change; each session forwards only its own
+    pub fn updates(&self) -> broadcast::Receiver<ResourceUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// Identify a newly connected client
+    pub fn open_session(&self) -> SessionId {
+        self.next_session.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Drop the subscriptions of a disconnected client
+    pub fn close_session(&self, session: SessionId) {
+        let mut watched = self.watched.lock().unwrap();
+        watched.retain(|_, w| {
+            w.sessions.remove(&session);
+            !w.sessions.is_empty()
+        });
+    }
+
+    /// Read a resource, honouring the tool config for the matching get tool
+    pub async fn read(&self, uri: &str) -> Result<Option<Value>> {
+        let resource = self.parse_allowed(uri)?;
+        resource.fetch(&self.tenants).await
+    }
+
+    /// Start watching `uri` for `session`. The current object is fetched now so the
+    /// first poll only reports real changes.
+    pub async fn subscribe(&self, session: SessionId, uri: &str) -> Result<()> {
+        let resource = self.parse_allowed(uri)?;
+        {
+            let mut watched = self.watched.lock().unwrap();
+            if let Some(existing) = watched.get_mut(uri) {
+                existing.sessions.insert(session);
+                return Ok(());
+            }
+            if watched.len() >= MAX_SUBSCRIPTIONS {
+                bail!("Too many resource subscriptions (max {}); unsubscribe from some first", MAX_SUBSCRIPTIONS);
+            }
+        }
+        let last = resource
+            .fetch(&self.tenants)
+            .await?
+            .ok_or_else(|| anyhow!("Resource not found: {}", uri))?;
+        info!("Subscribed to {}", uri);
+        // Another session may have subscribed while the fetch was in flight
+        self.watched
+            .lock()
+            .unwrap()
+            .entry(uri.to_string())
+            .or_insert_with(|| Watched {
+                resource,
+                last: Some(last),
+                sessions: HashSet::new(),
+            })
+            .sessions
+            .insert(session);
+        Ok(())
+    }
+
+    /// Stop watching `uri` for `session`; false if the session was not subscribed.
+    /// The URI stays watched while other sessions are subscribed to it.
+    pub fn unsubscribe(&self, session: SessionId, uri: &str) -> bool {
+        let mut watched = self.watched.lock().unwrap();
+        let Some(entry) = watched.get_mut(uri) else {
+            return false;
+        };
+        let removed = entry.sessions.remove(&session);
+        if entry.sessions.is_empty() {
+            watched.remove(uri);
+        }
+        if removed {
+            info!("Unsubscribed from {}", uri);
+        }
+        removed
+    }
+
+    /// `resources/list` entries: the collections that may be read, then the resources `session` subscribed to
+    pub fn list(&self, session: SessionId) -> Vec<Value> {
+        let mut resources: Vec<Value> = ResourceKind::ALL
+            .iter()
+            .filter(|kind| self.tool_config.is_tool_enabled(kind.list_tool()))
+            .map(|kind| {
+                serde_json::json!({
+                    "uri": format!("{}{}", URI_SCHEME, kind.segment()),
+                    "name": kind.plural(),
+                    "description": format!("First {} {} with the URI of each", COLLECTION_PAGE_SIZE, kind.plural().to_lowercase()),
+                    "mimeType": "application/json"
+                })
+            })
+            .collect();
+        let watched = self.watched.lock().unwrap();
+        let mut subscribed: Vec<Value> = watched
+            .iter()
+            .filter(|(uri, w)| w.sessions.contains(&session) && !resources.iter().any(|r| r["uri"] == uri.as_str()))
+            .map(|(uri, w)| {
+                serde_json::json!({
+                    "uri": uri,
+                    "name": w.resource.name(),
+                    "mimeType": "application/json"
+                })
+            })
+            .collect();
+        subscribed.sort_by(|a, b| a["uri"].as_str().cmp(&b["uri"].as_str()));
+        resources.extend(subscribed);
+        resources
+    }
+
+    /// `resources/templates/list` entries for the kinds whose get tool is enabled
+    pub fn templates(&self) -> Vec<Value> {
+        let tenant_hint = if self.tenants.is_multi_tenant() { " Append ?tenant=<name> for a tenant other than the default." } else { "" };
+        ResourceKind::ALL
+            .iter()
+            .filter(|kind| self.tool_config.is_tool_enabled(kind.read_tool()))
+            .map(|kind| {
+                serde_json::json!({
+                    "uriTemplate": format!("{}{}/{{id}}", URI_SCHEME, kind.segment()),
+                    "name": kind.label(),
+                    "description": format!("{} by numeric id, as returned by {}.{}", kind.label(), kind.list_tool(), tenant_hint),
+                    "mimeType": "application/json"
+                })
+            })
+            .collect()
+    }
+
+    /// Poll subscribed resources until the subscriptions are dropped
+    pub fn start(self: &Arc<Self>) {
+        let subscriptions: Weak<Self> = Arc::downgrade(self);
+        let interval = self.poll_interval;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(subscriptions) = subscriptions.upgrade() else {
+                    break;
+                };
+                subscriptions.poll().await;
+            }
+        });
+    }
+
+    async fn poll(&self) {
+        let targets: Vec<(String, ResourceUri)> = self
+            .watched
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(uri, w)| (uri.clone(), w.resource.clone()))
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        debug!("Polling {} subscribed resource(s)", targets.len());
+        for (uri, resource) in targets {
+            let current = match resource.fetch(&self.tenants).await {
+                Ok(current) => current,
+                Err(e) => {
+                    warn!("Resource poll failed for {}: {}", uri, e);
+                    continue;
+                }
+            };
+            // Unsubscribed while the fetch was in flight
+            let changed = self
+                .watched
+                .lock()
+                .unwrap()
+                .get_mut(&uri)
+                .and_then(|watched| watched.record(current).then(|| watched.sessions.clone()));
+            if let Some(sessions) = changed {
+                debug!("Resource changed: {}", uri);
+                let _ = self.updates.send(ResourceUpdate { uri, sessions });
+            }
+        }
+    }
+
+    fn parse_allowed(&self, uri: &str) -> Result<ResourceUri> {
+        let resource = ResourceUri::parse(uri)?;
+        let tool = resource.tool();
+        if !self.tool_config.is_tool_enabled(tool) {
+            bail!("Reading {} is disabled: enable {} in the tool config", uri, tool);
+        }
+        Ok(resource)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_resource_uris() {
+        assert_eq!(
+            ResourceUri::parse("onelogin://users/123").unwrap(),
+            ResourceUri { kind: ResourceKind::User, id: Some(123), tenant: None }
+        );
+        assert_eq!(
+            ResourceUri::parse("onelogin://apps/9?tenant=staging").unwrap(),
+            ResourceUri { kind: ResourceKind::App, id: Some(9), tenant: Some("staging".to_string()) }
+        );
+        assert_eq!(
+            ResourceUri::parse("onelogin://policies?tenant=staging").unwrap(),
+            ResourceUri { kind: ResourceKind::Policy, id: None, tenant: Some("staging".to_string()) }
+        );
+        assert_eq!(ResourceUri::parse("onelogin://roles/1").unwrap().tool(), "onelogin_get_role");
+        assert_eq!(ResourceUri::parse("onelogin://roles/").unwrap().tool(), "onelogin_list_roles");
+        assert_eq!(
+            ResourceUri::parse("onelogin://users?tenant=prod").unwrap().object_uri(5),
+            "onelogin://users/5?tenant=prod"
+        );
+        assert!(ResourceUri::parse("onelogin://groups/1").is_err());
+        assert!(ResourceUri::parse("onelogin://users/abc").is_err());
+        assert!(ResourceUri::parse("https://users/1").is_err());
+        assert!(ResourceUri::parse("onelogin://users/1?region=eu").is_err());
+    }
+
+    #[test]
+    fn test_record_reports_changes_and_deletion() {
+        let mut watched = Watched {
+            resource: ResourceUri::parse("onelogin://users/1").unwrap(),
+            last: Some(json!({"id": 1, "status": 1})),
+            sessions: HashSet::from([STDIO_SESSION]),
+        };
+        assert!(!watched.record(Some(json!({"id": 1, "status": 1}))));
+        assert!(watched.record(Some(json!({"id": 1, "status": 3}))));
+        assert!(watched.record(None));
+        assert!(!watched.record(None));
+    }
+}
@@ -0,0 +1,158 @@
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::{Map, Value};
+
+/// Build a tool `inputSchema` from a request model.
+///
+/// Field doc comments become property descriptions and non-optional fields
+/// become `required`, so the schema always matches what the handler deserializes.
+pub fn input_schema<T: JsonSchema>() -> Value {
+    let generator = SchemaSettings::draft07()
+        .with(|s| {
+            s.option_add_null_type = false;
+            s.inline_subschemas = true;
+        })
+        .into_generator();
+    let root = generator.into_root_schema_for::<T>();
+    let mut schema = serde_json::to_value(root).unwrap_or_else(|_| Value::Object(Map::new()));
+
+    if let Value::Object(map) = &mut schema {
+        map.remove("$schema");
+        map.remove("title");
+        map.remove("definitions");
+        map.entry("type").or_insert_with(|| Value::String("object".to_string()));
+        map.entry("properties").or_insert_with(|| Value::Object(Map::new()));
+    }
+    schema
+}
+
+/// Generate a schema from `T`, then layer tool-specific details on top.
+///
+/// `overrides` is an object of property name -> schema fragment. Keys are merged
+/// into existing properties (e.g. to add an `enum`) or added as new ones (path
+/// parameters such as `user_id`). Names in `required` are appended to the
+/// required list.
+pub fn input_schema_with<T: JsonSchema>(overrides: Value, required: &[&str]) -> Value {
+    let mut schema = input_schema::<T>();
+
+    if let (Some(props), Value::Object(extra)) = (
+        schema.get_mut("properties").and_then(|p| p.as_object_mut()),
+        overrides,
+    ) {
+        for (name, fragment) in extra {
+            match (props.get_mut(&name), fragment) {
+                (Some(Value::Object(existing)), Value::Object(fragment)) => {
+                    existing.extend(fragment);
+                }
+                (_, fragment) => {
+                    props.insert(name, fragment);
+                }
+            }
+        }
+    }
+
+    if !required.is_empty() {
+        if let Value::Object(map) = &mut schema {
+            let list = map
+                .entry("required")
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(items) = list {
+                for name in required {
+                    if !items.iter().any(|v| v == name) {
+                        items.push(Value::String(name.to_string()));
+                    }
+                }
+            }
+        }
+    }
+    schema
+}
+
+/// Coerce string-encoded scalars in `args` to the types declared by `schema`.
+///
+/// MCP clients often send `"12345"` for integer parameters or `"true"` for
+/// booleans. Only top-level properties are converted; anything that doesn't
+/// parse is left as-is so deserialization reports the real error.
+pub fn coerce_to_schema(args: &Value, schema: &Value) -> Value {
+    let mut args = match args {
+        Value::Null => return Value::Object(Map::new()),
+        other => other.clone(),
+    };
+    let (Some(obj), Some(props)) = (
+        args.as_object_mut(),
+        schema.get("properties").and_then(|p| p.as_object()),
+    ) else {
+        return args;
+    };
+
+    for (name, value) in obj.iter_mut() {
+        let Value::String(raw) = value else { continue };
+        let declared = props.get(name).and_then(|p| p.get("type")).and_then(|t| t.as_str());
+        let coerced = match declared {
+            Some("integer") => raw.trim().parse::<i64>().ok().map(Value::from),
+            Some("number") => raw.trim().parse::<f64>().ok().map(Value::from),
+            Some("boolean") => raw.trim().parse::<bool>().ok().map(Value::from),
+            _ => None,
+        };
+        if let Some(v) = coerced {
+            *value = v;
+        }
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::users::{CreateUserRequest, UpdateUserRequest};
+    use serde_json::json;
+
+    #[test]
+    fn test_create_user_schema_matches_model() {
+        let schema = input_schema::<CreateUserRequest>();
+        let props = schema["properties"].as_object().unwrap();
+        for field in ["email", "username", "password", "role_ids", "custom_attributes"] {
+            assert!(props.contains_key(field), "missing {}", field);
+        }
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert!(required.contains(&"email"));
+        assert!(required.contains(&"username"));
+        assert!(!required.contains(&"password"));
+        assert_eq!(props["role_ids"]["type"], "array");
+        assert!(schema.get("$schema").is_none());
+    }
+
+    #[test]
+    fn test_overrides_merge_and_add_properties() {
+        let schema = input_schema_with::<UpdateUserRequest>(
+            json!({
+                "user_id": {"type": "integer", "description": "User to update"},
+                "status": {"enum": [0, 1, 2]}
+            }),
+            &["user_id"],
+        );
+        assert_eq!(schema["properties"]["user_id"]["type"], "integer");
+        assert_eq!(schema["properties"]["status"]["type"], "integer");
+        assert_eq!(schema["properties"]["status"]["enum"], json!([0, 1, 2]));
+        assert_eq!(schema["required"], json!(["user_id"]));
+    }
+
+    #[test]
+    fn test_coerce_to_schema() {
+        let schema = json!({"properties": {
+            "user_id": {"type": "integer"},
+            "enabled": {"type": "boolean"},
+            "name": {"type": "string"}
+        }});
+        let args = json!({"user_id": "42", "enabled": "true", "name": "007", "extra": "1"});
+        let coerced = coerce_to_schema(&args, &schema);
+        assert_eq!(coerced, json!({"user_id": 42, "enabled": true, "name": "007", "extra": "1"}));
+        assert_eq!(coerce_to_schema(&Value::Null, &schema), json!({}));
+        assert_eq!(coerce_to_schema(&json!({"user_id": "abc"}), &schema)["user_id"], "abc");
+    }
+}
@@ -1,13 +1,31 @@
+use crate::core::admin::{self, AdminConfig, AdminState};
+use crate::core::cassette::Cassette;
+use crate::core::checkpoints::CheckpointStore;
+use crate::core::cursors::CursorStore;
 use crate::core::config::Config;
+use crate::core::metrics;
+use crate::core::mock::MockStore;
+use crate::core::role_snapshots::RoleSnapshotStore;
+use crate::core::scheduler::{self, JobRunner};
 use crate::core::tenant_manager::TenantManager;
 use crate::core::tool_config::ToolConfig;
+use crate::core::webhook_receiver::{self, ReceivedEvent, WebhookBuffer, WebhookReceiverConfig};
+use crate::mcp::completions;
+use crate::mcp::prompts::PromptRegistry;
+use crate::mcp::resources::{ResourceSubscriptions, ResourceUpdate, SessionId, STDIO_SESSION};
 use crate::mcp::tools::ToolRegistry;
-use anyhow::{anyhow, Context, Result};
+use crate::mcp::websocket::{self, WebSocketConfig};
+use crate::mcp::writer;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
 use notify::RecommendedWatcher;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
-use tracing::{debug, error, info};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, error, info, warn};
 
 #[allow(dead_code)]
 pub struct McpServer {
@@ -15,14 +33,59 @@ pub struct McpServer {
     tenant_manager: Arc<TenantManager>,
     tool_registry: ToolRegistry,
     tool_config: Arc<ToolConfig>,
+    /// Set when the webhook receiver is running; its events are forwarded as notifications
+    webhooks: Option<Arc<WebhookBuffer>>,
+    /// Resources the client subscribed to; changes are pushed as notifications/resources/updated
+    resources: Arc<ResourceSubscriptions>,
+    prompts: PromptRegistry,
 }
 
+/// Largest Content-Length accepted, so a corrupt header can't trigger a huge allocation
+const MAX_FRAME_BYTES: usize = 64 * 1024 * 1024;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TransportMode {
     ContentLength,
     RawJson,
 }
 
+enum Incoming {
+    Frame(Result<Option<IncomingFrame>>),
+    Webhook(ReceivedEvent),
+    ResourceUpdated(ResourceUpdate),
+}
+
+/// A frame that could not be read, answered with a parse error in its own framing
+#[derive(Debug)]
+struct FrameError {
+    mode: TransportMode,
+    message: String,
+    /// The stream can't be resynchronized, so no further frames are read
+    fatal: bool,
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+impl FrameError {
+    fn recoverable(mode: TransportMode, message: String) -> anyhow::Error {
+        anyhow::Error::new(Self { mode, message, fatal: false })
+    }
+
+    fn fatal(mode: TransportMode, message: String) -> anyhow::Error {
+        anyhow::Error::new(Self { mode, message, fatal: true })
+    }
+
+    fn is_fatal(error: &anyhow::Error) -> bool {
+        error.downcast_ref::<Self>().is_some_and(|e| e.fatal)
+    }
+}
+
 #[derive(Debug)]
 struct IncomingFrame {
     payload: String,
@@ -31,7 +94,9 @@ struct IncomingFrame {
 
 #[allow(dead_code)]
 impl McpServer {
-    pub async fn new(config: Config) -> Result<Self> {
+    /// Create a server for the configured tenant(s). `cassette` records or replays
+    /// HTTP traffic and is only supported in single-tenant mode.
+    pub async fn new(config: Config, cassette: Option<Arc<Cassette>>) -> Result<Self> {
         let config_arc = Arc::new(config.clone());
 
         // Initialize tool configuration (controls which tools are enabled)
@@ -43,6 +108,9 @@ impl McpServer {
         // Determine single vs multi-tenant mode
         let tenant_manager = match Config::load_tenants_file()? {
             Some(tenants_file) if !tenants_file.tenants.is_empty() => {
+                if cassette.is_some() {
+                    anyhow::bail!("--record/--replay are only supported in single-tenant mode");
+                }
                 info!(
                     "Multi-tenant mode: {} tenant(s) configured",
                     tenants_file.tenants.len()
@@ -61,21 +129,96 @@ impl McpServer {
             }
             _ => {
                 info!("Single-tenant mode (credentials from environment)");
-                Arc::new(TenantManager::from_single(config))
+                Arc::new(TenantManager::from_single(config, cassette))
             }
         };
 
+        Self::from_parts(config_arc, tenant_manager, tool_config)
+    }
+
+    /// Create a server backed by fixture data (mock/offline mode)
+    pub async fn new_mock(config: Config, store: Arc<MockStore>) -> Result<Self> {
+        let config_arc = Arc::new(config.clone());
+        let tool_config = Arc::new(
+            ToolConfig::load(config.tool_config_path.clone())
+                .context("Failed to load tool configuration")?
+        );
+        info!("Mock mode: serving fixture data, no requests are sent to OneLogin");
+        let tenant_manager = Arc::new(TenantManager::from_mock(config, store));
+        let mut server = Self::from_parts(config_arc, tenant_manager, tool_config)?;
+        // Fixture event ids must not move the checkpoints used against real tenants
+        server.tool_registry = server
+            .tool_registry
+            .with_checkpoints(Arc::new(CheckpointStore::in_memory()))
+            .with_cursors(Arc::new(CursorStore::in_memory()))
+            .with_role_snapshots(Arc::new(RoleSnapshotStore::in_memory()));
+        Ok(server)
+    }
+
+    fn from_parts(
+        config: Arc<Config>,
+        tenant_manager: Arc<TenantManager>,
+        tool_config: Arc<ToolConfig>,
+    ) -> Result<Self> {
         // Initialize tool registry with tenant manager and tool config
         let tool_registry = ToolRegistry::new(tenant_manager.clone(), tool_config.clone());
+        let resources = Arc::new(ResourceSubscriptions::new(
+            tenant_manager.clone(),
+            tool_config.clone(),
+            ResourceSubscriptions::poll_interval_from_env()?,
+        ));
+        let prompts = PromptRegistry::new(tool_config.clone());
 
         Ok(Self {
-            config: config_arc,
+            config,
             tenant_manager,
             tool_registry,
             tool_config,
+            webhooks: None,
+            resources,
+            prompts,
         })
     }
 
+    /// Start the webhook receiver and expose its events through tools and notifications
+    pub async fn with_webhook_receiver(mut self, config: WebhookReceiverConfig) -> Result<Self> {
+        let buffer = webhook_receiver::start(config).await?;
+        self.tool_registry = self.tool_registry.with_webhooks(buffer.clone());
+        self.webhooks = Some(buffer);
+        Ok(self)
+    }
+
+    /// Start timers for the jobs in the tool config. Job tool calls go through the registry
+    /// like client calls, so disabled tools fail the job rather than running.
+    pub fn start_scheduler(self: &Arc<Self>) {
+        let jobs = self.tool_registry.jobs().clone();
+        if jobs.is_empty() {
+            return;
+        }
+        scheduler::start(jobs, self.clone());
+    }
+
+    /// Serve Prometheus metrics when ENABLE_METRICS is set
+    pub async fn start_metrics_exporter(&self) -> Result<()> {
+        if !self.config.enable_metrics {
+            return Ok(());
+        }
+        let listen = metrics::listen_from_env()?;
+        metrics::start(listen, self.tenant_manager.clone()).await
+    }
+
+    /// Serve the admin API when ONELOGIN_ADMIN_LISTEN is set
+    pub async fn start_admin_api(&self) -> Result<()> {
+        let Some(config) = AdminConfig::from_env()? else {
+            return Ok(());
+        };
+        let state = AdminState {
+            tenants: self.tenant_manager.clone(),
+            tool_config: self.tool_config.clone(),
+        };
+        admin::start(config, state).await
+    }
+
     /// Start file watcher for hot reload if enabled
     pub fn start_config_watcher(&self) -> Result<Option<RecommendedWatcher>> {
         self.tool_config.start_watcher()
@@ -89,27 +232,76 @@ impl McpServer {
     pub async fn run(&self) -> Result<()> {
         info!("OneLogin MCP Server started");
 
-        let stdin = tokio::io::stdin();
-        let stdout = tokio::io::stdout();
-        let mut reader = BufReader::new(stdin);
-        let mut writer = stdout;
+        // Frames are read on their own task so the loop can also wait on webhook events
+        // without cancelling a partially read frame
+        let (frame_tx, mut frames) = tokio::sync::mpsc::channel(16);
+        tokio::spawn(async move {
+            let mut reader = BufReader::new(tokio::io::stdin());
+            loop {
+                let frame = Self::read_frame(&mut reader).await;
+                let closed = match &frame {
+                    Ok(frame) => frame.is_none(),
+                    Err(e) => FrameError::is_fatal(e),
+                };
+                if frame_tx.send(frame).await.is_err() || closed {
+                    break;
+                }
+            }
+        });
+
+        let (writer, writer_task) = writer::spawn(tokio::io::stdout());
+        // Each response uses the framing of the request it answers; pushed notifications
+        // use the framing the client last spoke
         let mut negotiated_transport: Option<TransportMode> = None;
+        let mut webhook_events = self.webhooks.as_ref().map(|w| w.subscribe());
+        let mut resource_updates = self.resources.updates();
+        self.resources.start();
 
         loop {
-            let frame = match Self::read_frame(&mut reader).await {
-                Ok(Some(frame)) => {
+            let next = tokio::select! {
+                frame = frames.recv() => Incoming::Frame(frame.unwrap_or(Ok(None))),
+                event = Self::next_webhook_event(&mut webhook_events) => Incoming::Webhook(event),
+                uri = Self::next_resource_update(&mut resource_updates, STDIO_SESSION) => Incoming::ResourceUpdated(uri),
+            };
+
+            let frame = match next {
+                Incoming::Frame(Ok(Some(frame))) => {
                     info!("Received MCP frame: {} bytes", frame.payload.len());
-                    if negotiated_transport.is_none() {
-                        negotiated_transport = Some(frame.mode);
+                    if negotiated_transport.is_some_and(|mode| mode != frame.mode) {
+                        debug!("Client switched framing to {:?}", frame.mode);
                     }
+                    negotiated_transport = Some(frame.mode);
                     frame
                 }
-                Ok(None) => {
+                Incoming::Frame(Ok(None)) => {
                     info!("Client closed connection");
                     break;
                 }
-                Err(e) => {
+                Incoming::Frame(Err(e)) => {
                     error!("Failed to read MCP frame: {}", e);
+                    // Answer so the client isn't left waiting for a reply it will never get
+                    let mode = e
+                        .downcast_ref::<FrameError>()
+                        .map(|frame_error| frame_error.mode)
+                        .or(negotiated_transport)
+                        .unwrap_or(TransportMode::ContentLength);
+                    let response = Self::parse_error(&e);
+                    writer.send(Self::encode_frame(&serde_json::to_string(&response)?, mode)).await?;
+                    continue;
+                }
+                Incoming::Webhook(event) => {
+                    // Nothing is pushed before the client has spoken and fixed the transport
+                    if let Some(mode) = negotiated_transport {
+                        let notification = Self::webhook_notification(&event);
+                        writer.send(Self::encode_frame(&notification.to_string(), mode)).await?;
+                    }
+                    continue;
+                }
+                Incoming::ResourceUpdated(update) => {
+                    if let Some(mode) = negotiated_transport {
+                        let notification = Self::resource_notification(&update.uri);
+                        writer.send(Self::encode_frame(&notification.to_string(), mode)).await?;
+                    }
                     continue;
                 }
             };
@@ -121,25 +313,158 @@ impl McpServer {
                 }
                 Err(e) => {
                     error!("Failed to parse request: {}", e);
+                    let response = Self::parse_error(&e);
+                    writer.send(Self::encode_frame(&serde_json::to_string(&response)?, frame.mode)).await?;
                     continue;
                 }
             };
 
-            if let Some(response) = self.handle_request(request).await {
+            if let Some(response) = self.handle_request(request, STDIO_SESSION).await {
                 let response_json = serde_json::to_string(&response)?;
-                info!("Sending response: {} bytes", response_json.len());
-                let mode = negotiated_transport.unwrap_or(TransportMode::ContentLength);
-                Self::write_frame(&mut writer, &response_json, mode).await?;
-                writer.flush().await?;
+                info!("Sending response: {} bytes ({} bytes queued)", response_json.len(), writer.queued_bytes());
+                writer.send(Self::encode_frame(&response_json, frame.mode)).await?;
             } else {
                 info!("No response needed (notification)");
             }
         }
 
+        // Let queued responses reach the client before exiting
+        drop(writer);
+        writer_task.await.context("Output writer panicked")?
+    }
+
+    /// Serve MCP clients over WebSocket instead of stdio until the process stops.
+    /// Sessions run concurrently and share the tenants and caches; each keeps its
+    /// own resource subscriptions and handles its requests in order, like the stdio loop.
+    pub async fn run_websocket(self: Arc<Self>, config: WebSocketConfig) -> Result<()> {
+        let mut sessions = websocket::start(config).await?;
+        self.resources.start();
+        while let Some(session) = sessions.recv().await {
+            let server = self.clone();
+            tokio::spawn(async move {
+                let peer = session.peer;
+                info!("WebSocket client {} connected", peer);
+                let id = server.resources.open_session();
+                match server.serve_websocket_session(session.socket, id).await {
+                    Ok(()) => info!("WebSocket client {} disconnected", peer),
+                    Err(e) => warn!("WebSocket session with {} ended: {:#}", peer, e),
+                }
+                server.resources.close_session(id);
+            });
+        }
         Ok(())
     }
 
-    async fn handle_request(&self, request: Request) -> Option<Response> {
+    async fn serve_websocket_session(&self, socket: websocket::Socket, session: SessionId) -> Result<()> {
+        let (mut sink, mut stream) = socket.split();
+        let mut webhook_events = self.webhooks.as_ref().map(|w| w.subscribe());
+        let mut resource_updates = self.resources.updates();
+
+        loop {
+            let payload = tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Binary(bytes))) => String::from_utf8_lossy(&bytes).into_owned(),
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    // Pings are answered by the protocol layer
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e.into()),
+                },
+                event = Self::next_webhook_event(&mut webhook_events) => {
+                    sink.send(Message::Text(Self::webhook_notification(&event).to_string())).await?;
+                    continue;
+                }
+                update = Self::next_resource_update(&mut resource_updates, session) => {
+                    sink.send(Message::Text(Self::resource_notification(&update.uri).to_string())).await?;
+                    continue;
+                }
+            };
+
+            info!("Received WebSocket message: {} bytes", payload.len());
+            let response = match serde_json::from_str::<Request>(&payload) {
+                Ok(request) => self.handle_request(request, session).await,
+                Err(e) => {
+                    error!("Failed to parse request: {}", e);
+                    Some(Self::parse_error(&e))
+                }
+            };
+            if let Some(response) = response {
+                sink.send(Message::Text(serde_json::to_string(&response)?)).await?;
+            }
+        }
+    }
+
+    /// Response to a message that isn't valid JSON-RPC
+    fn parse_error(e: &dyn std::fmt::Display) -> Response {
+        Response {
+            jsonrpc: "2.0".to_string(),
+            id: Some(serde_json::Value::Null),
+            result: None,
+            error: Some(ResponseError {
+                code: -32700,
+                message: format!("Parse error: {}", e),
+                data: None,
+                tool_name: None,
+            }),
+        }
+    }
+
+    fn resource_notification(uri: &str) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/resources/updated",
+            "params": {"uri": uri}
+        })
+    }
+
+    /// Next webhook event, skipping over any the loop fell behind on. Pends forever without a receiver.
+    async fn next_webhook_event(events: &mut Option<broadcast::Receiver<ReceivedEvent>>) -> ReceivedEvent {
+        let Some(receiver) = events.as_mut() else {
+            return std::future::pending().await;
+        };
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Skipped {} webhook notifications; they remain available via onelogin_get_received_events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    *events = None;
+                    return std::future::pending().await;
+                }
+            }
+        }
+    }
+
+    /// Next change to a resource `session` subscribed to. Updates the loop fell behind
+    /// on are dropped; the client re-reads the resource anyway, so a later update covers them.
+    async fn next_resource_update(updates: &mut broadcast::Receiver<ResourceUpdate>, session: SessionId) -> ResourceUpdate {
+        loop {
+            match updates.recv().await {
+                Ok(update) if update.sessions.contains(&session) => return update,
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Skipped {} resource update notifications", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return std::future::pending().await,
+            }
+        }
+    }
+
+    /// MCP logging notification carrying a received webhook event
+    fn webhook_notification(event: &ReceivedEvent) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {
+                "level": "info",
+                "logger": "onelogin.webhooks",
+                "data": event
+            }
+        })
+    }
+
+    async fn handle_request(&self, request: Request, session: SessionId) -> Option<Response> {
         // Notifications don't have an id and don't require a response
         if request.id.is_none() {
             // Handle notifications (currently we just ignore them)
@@ -152,6 +477,12 @@ impl McpServer {
             "tools/call" => self.handle_call_tool(request).await,
             "prompts/list" => self.handle_list_prompts(request).await,
             "prompts/get" => self.handle_get_prompt(request).await,
+            "resources/list" => self.handle_list_resources(request, session).await,
+            "resources/read" => self.handle_read_resource(request).await,
+            "resources/templates/list" => self.handle_list_resource_templates(request).await,
+            "resources/subscribe" => self.handle_subscribe_resource(request, session).await,
+            "resources/unsubscribe" => self.handle_unsubscribe_resource(request, session).await,
+            "completion/complete" => self.handle_complete(request).await,
             _ => Response {
                 jsonrpc: "2.0".to_string(),
                 id: request.id,
@@ -169,15 +500,22 @@ impl McpServer {
     }
 
     async fn handle_initialize(&self, request: Request) -> Response {
+        let mut capabilities = serde_json::json!({
+            "tools": {},
+            "prompts": {},
+            "resources": {"subscribe": true},
+            "completions": {}
+        });
+        if self.webhooks.is_some() {
+            // Received webhook events are pushed as notifications/message
+            capabilities["logging"] = serde_json::json!({});
+        }
         Response {
             jsonrpc: "2.0".to_string(),
             id: request.id,
             result: Some(serde_json::json!({
                 "protocolVersion": "2024-11-05",
-                "capabilities": {
-                    "tools": {},
-                    "prompts": {}
-                },
+                "capabilities": capabilities,
                 "serverInfo": {
                     "name": "onelogin-mcp-server",
                     "version": env!("CARGO_PKG_VERSION")
@@ -188,19 +526,7 @@ impl McpServer {
     }
 
     async fn handle_list_prompts(&self, request: Request) -> Response {
-        Response {
-            jsonrpc: "2.0".to_string(),
-            id: request.id,
-            result: Some(serde_json::json!({
-                "prompts": [
-                    {
-                        "name": "onelogin-usage-guide",
-                        "description": "Important guidelines for using OneLogin MCP tools effectively"
-                    }
-                ]
-            })),
-            error: None,
-        }
+        Self::result(request.id, serde_json::json!({ "prompts": self.prompts.list() }))
     }
 
     async fn handle_get_prompt(&self, request: Request) -> Response {
@@ -208,75 +534,117 @@ impl McpServer {
             .get("name")
             .and_then(|v| v.as_str())
             .unwrap_or("");
+        let arguments = request.params.get("arguments").cloned().unwrap_or_default();
+        match self.prompts.get(name, &arguments) {
+            Ok(prompt) => Self::result(request.id, prompt),
+            Err(message) => Self::error(request.id, -32602, message),
+        }
+    }
 
-        if name == "onelogin-usage-guide" {
-            Response {
-                jsonrpc: "2.0".to_string(),
-                id: request.id,
-                result: Some(serde_json::json!({
-                    "description": "Important guidelines for using OneLogin MCP tools effectively",
-                    "messages": [
-                        {
-                            "role": "user",
-                            "content": {
-                                "type": "text",
-                                "text": r#"# OneLogin MCP Server Usage Guide
-
-## IMPORTANT: Always Read Tool Descriptions
-Before using any tool, carefully read its description. The descriptions contain critical information about:
-- Required vs optional parameters
-- Valid enum values and their meanings
-- Which tools to use together (e.g., unlock vs update_user)
-- Limitations and edge cases
-
-## Key User Status Values
-- 0 = Unactivated (never logged in)
-- 1 = Active (can log in)
-- 2 = Suspended (admin disabled)
-- 3 = Locked (too many failed attempts OR manually locked)
-- 4 = Password expired
-- 5 = Awaiting password reset
-
-## Key User State Values
-- 0 = Unapproved (pending approval)
-- 1 = Approved (licensed, normal user)
-- 2 = Rejected
-- 3 = Unlicensed
-
-## Lock/Unlock Behavior
-- `onelogin_lock_user`: Locks user for specified duration. To unlock early, use `onelogin_update_user` with status=1
-- `onelogin_unlock_user`: ONLY works for users locked due to failed login attempts, NOT for manually locked users
-
-## Role Management
-- Use `onelogin_assign_roles` and `onelogin_remove_roles` instead of update_user for role changes
-- These are additive/subtractive operations, not replacements
-
-## Error Handling
-- 403 errors on Account Owner users are expected - you cannot lock/modify the Account Owner
-- Always check error messages for specific guidance
-
-## Best Practices
-1. Use `onelogin_list_users` with filters (email, username) to find users before operations
-2. Verify user status after lock/unlock operations
-3. Use `onelogin_get_user` to confirm changes were applied"#
-                            }
-                        }
-                    ]
-                })),
-                error: None,
-            }
-        } else {
-            Response {
-                jsonrpc: "2.0".to_string(),
-                id: request.id,
-                result: None,
-                error: Some(ResponseError {
-                    code: -32602,
-                    message: format!("Prompt not found: {}", name),
-                    data: None,
-                    tool_name: None,
+    async fn handle_list_resources(&self, request: Request, session: SessionId) -> Response {
+        Self::result(request.id, serde_json::json!({ "resources": self.resources.list(session) }))
+    }
+
+    async fn handle_list_resource_templates(&self, request: Request) -> Response {
+        Self::result(request.id, serde_json::json!({ "resourceTemplates": self.resources.templates() }))
+    }
+
+    async fn handle_read_resource(&self, request: Request) -> Response {
+        let Some(uri) = request.params.get("uri").and_then(|v| v.as_str()) else {
+            return Self::error(request.id, -32602, "Missing uri".to_string());
+        };
+        match self.resources.read(uri).await {
+            Ok(Some(value)) => Self::result(
+                request.id,
+                serde_json::json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": "application/json",
+                        "text": serde_json::to_string_pretty(&value).unwrap_or_default()
+                    }]
                 }),
+            ),
+            Ok(None) => Self::error(request.id, -32002, format!("Resource not found: {}", uri)),
+            Err(e) => Self::error(request.id, -32602, e.to_string()),
+        }
+    }
+
+    async fn handle_subscribe_resource(&self, request: Request, session: SessionId) -> Response {
+        let Some(uri) = request.params.get("uri").and_then(|v| v.as_str()) else {
+            return Self::error(request.id, -32602, "Missing uri".to_string());
+        };
+        match self.resources.subscribe(session, uri).await {
+            Ok(()) => Self::result(request.id, serde_json::json!({})),
+            Err(e) => Self::error(request.id, -32602, e.to_string()),
+        }
+    }
+
+    async fn handle_unsubscribe_resource(&self, request: Request, session: SessionId) -> Response {
+        let Some(uri) = request.params.get("uri").and_then(|v| v.as_str()) else {
+            return Self::error(request.id, -32602, "Missing uri".to_string());
+        };
+        self.resources.unsubscribe(session, uri);
+        Self::result(request.id, serde_json::json!({}))
+    }
+
+    /// Suggest argument values. Only app rule tool arguments have catalogs; anything else
+    /// completes to an empty list rather than an error.
+    async fn handle_complete(&self, request: Request) -> Response {
+        let params = &request.params;
+        let (Some(ref_type), Some(argument)) = (
+            params["ref"]["type"].as_str(),
+            params["argument"]["name"].as_str(),
+        ) else {
+            return Self::error(request.id, -32602, "completion/complete needs ref.type and argument.name".to_string());
+        };
+        let typed = params["argument"]["value"].as_str().unwrap_or("");
+        let empty = serde_json::Map::new();
+        let context = params["context"]["arguments"].as_object().unwrap_or(&empty);
+
+        let tool = params["ref"]["name"].as_str().map(|name| self.tool_config.resolve_tool_name(name));
+        let values = match tool {
+            Some(tool) if ref_type == "ref/tool"
+                && completions::completes_tool(&tool)
+                && self.tool_config.is_tool_enabled(&tool) =>
+            {
+                let tenant = context.get("tenant").and_then(|v| v.as_str());
+                let result = match self.tenant_manager.resolve(tenant) {
+                    Ok(client) => completions::complete_app_rule_argument(&client, argument, typed, context).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(values) => values.unwrap_or_default(),
+                    Err(e) => {
+                        debug!("No completions for {}.{}: {}", tool, argument, e);
+                        Vec::new()
+                    }
+                }
             }
+            _ => Vec::new(),
+        };
+        Self::result(request.id, completions::completion_result(values))
+    }
+
+    fn result(id: Option<serde_json::Value>, result: serde_json::Value) -> Response {
+        Response {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn error(id: Option<serde_json::Value>, code: i32, message: String) -> Response {
+        Response {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(ResponseError {
+                code,
+                message,
+                data: None,
+                tool_name: None,
+            }),
         }
     }
 
@@ -406,25 +774,43 @@ Before using any tool, carefully read its description. The descriptions contain
 
         loop {
             line.clear();
-            let bytes_read = reader.read_line(&mut line).await?;
+            // Bounded so a payload without newlines can't grow without limit
+            let remaining = (MAX_FRAME_BYTES + 1).saturating_sub(raw_payload.len()) as u64;
+            let bytes_read = (&mut *reader).take(remaining).read_line(&mut line).await?;
+            if raw_payload.len() + line.len() > MAX_FRAME_BYTES {
+                let mode = if raw_mode { TransportMode::RawJson } else { TransportMode::ContentLength };
+                return Err(FrameError::fatal(
+                    mode,
+                    format!("Frame exceeds the {} byte frame limit", MAX_FRAME_BYTES),
+                ));
+            }
 
             if bytes_read == 0 {
                 if raw_mode {
                     if raw_payload.trim().is_empty() {
                         return Ok(None);
                     } else {
-                        return Err(anyhow!("Unexpected EOF while reading JSON payload"));
+                        return Err(FrameError::recoverable(
+                            TransportMode::RawJson,
+                            "Unexpected EOF while reading JSON payload".to_string(),
+                        ));
                     }
                 }
 
                 if headers.is_empty() {
                     return Ok(None);
                 } else {
-                    return Err(anyhow!("Unexpected EOF while reading headers"));
+                    return Err(FrameError::recoverable(
+                        TransportMode::ContentLength,
+                        "Unexpected EOF while reading headers".to_string(),
+                    ));
                 }
             }
 
-            let trimmed = line.trim_end_matches(|c| c == '\r' || c == '\n');
+            // Some Windows hosts start the stream with a byte order mark
+            let trimmed = line
+                .trim_end_matches(['\r', '\n'])
+                .trim_start_matches('\u{feff}');
 
             if raw_mode {
                 raw_payload.push_str(trimmed);
@@ -440,7 +826,7 @@ Before using any tool, carefully read its description. The descriptions contain
                         continue;
                     }
                     Err(e) => {
-                        return Err(anyhow!("Invalid JSON payload: {}", e));
+                        return Err(FrameError::recoverable(TransportMode::RawJson, format!("Invalid JSON payload: {}", e)));
                     }
                 }
             }
@@ -471,7 +857,7 @@ Before using any tool, carefully read its description. The descriptions contain
                         continue;
                     }
                     Err(e) => {
-                        return Err(anyhow!("Invalid JSON payload: {}", e));
+                        return Err(FrameError::recoverable(TransportMode::RawJson, format!("Invalid JSON payload: {}", e)));
                     }
                 }
             }
@@ -479,15 +865,26 @@ Before using any tool, carefully read its description. The descriptions contain
             if let Some((name, value)) = trimmed.split_once(':') {
                 headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
             } else {
-                return Err(anyhow!("Invalid header line: {}", trimmed));
+                return Err(FrameError::recoverable(
+                    TransportMode::ContentLength,
+                    format!("Invalid header line: {}", trimmed),
+                ));
             }
         }
 
         let content_length = headers
             .get("content-length")
-            .ok_or_else(|| anyhow!("Missing Content-Length header"))?
+            .ok_or_else(|| FrameError::recoverable(TransportMode::ContentLength, "Missing Content-Length header".to_string()))?
             .parse::<usize>()
-            .map_err(|e| anyhow!("Invalid Content-Length header: {}", e))?;
+            .map_err(|e| FrameError::recoverable(TransportMode::ContentLength, format!("Invalid Content-Length header: {}", e)))?;
+        if content_length > MAX_FRAME_BYTES {
+            // Skip the body so it isn't read as the next frame
+            tokio::io::copy(&mut (&mut *reader).take(content_length as u64), &mut tokio::io::sink()).await?;
+            return Err(FrameError::recoverable(
+                TransportMode::ContentLength,
+                format!("Content-Length {} exceeds the {} byte frame limit", content_length, MAX_FRAME_BYTES),
+            ));
+        }
 
         let mut buffer = vec![0u8; content_length];
         reader.read_exact(&mut buffer).await?;
@@ -498,24 +895,20 @@ Before using any tool, carefully read its description. The descriptions contain
         }))
     }
 
-    async fn write_frame<W>(writer: &mut W, payload: &str, mode: TransportMode) -> Result<()>
-    where
-        W: AsyncWrite + Unpin,
-    {
+    fn encode_frame(payload: &str, mode: TransportMode) -> Vec<u8> {
         match mode {
             TransportMode::ContentLength => {
-                let bytes = payload.as_bytes();
-                let header = format!("Content-Length: {}\r\n\r\n", bytes.len());
-                writer.write_all(header.as_bytes()).await?;
-                writer.write_all(bytes).await?;
+                let mut frame = format!("Content-Length: {}\r\n\r\n", payload.len()).into_bytes();
+                frame.extend_from_slice(payload.as_bytes());
+                frame
             }
             TransportMode::RawJson => {
-                writer.write_all(payload.as_bytes()).await?;
-                writer.write_all(b"\n").await?;
+                let mut frame = Vec::with_capacity(payload.len() + 1);
+                frame.extend_from_slice(payload.as_bytes());
+                frame.push(b'\n');
+                frame
             }
         }
-
-        Ok(())
     }
 }
 
@@ -557,6 +950,13 @@ pub struct CallToolParams {
     pub arguments: serde_json::Value,
 }
 
+#[async_trait]
+impl JobRunner for McpServer {
+    async fn run_job(&self, tool: &str, arguments: &serde_json::Value) -> Result<serde_json::Value> {
+        self.tool_registry.run_job(tool, arguments).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::McpServer;
@@ -595,4 +995,107 @@ mod tests {
         assert_eq!(frame.payload, payload.to_string());
         assert_eq!(frame.mode, super::TransportMode::RawJson);
     }
+
+    #[tokio::test]
+    async fn read_frame_detects_framing_per_message() {
+        let (mut client, server) = io::duplex(4096);
+        let mut reader = BufReader::new(server);
+        let framed = r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#;
+        let pretty = "{\n  \"jsonrpc\": \"2.0\",\n  \"id\": 2,\n  \"method\": \"ping\"\n}\n";
+        let stream = format!(
+            "\u{feff}Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n{}{}",
+            framed.len(),
+            framed,
+            pretty
+        );
+
+        client.write_all(stream.as_bytes()).await.unwrap();
+        client.shutdown().await.unwrap();
+
+        let first = McpServer::read_frame(&mut reader).await.unwrap().unwrap();
+        assert_eq!(first.payload, framed);
+        assert_eq!(first.mode, super::TransportMode::ContentLength);
+
+        let second = McpServer::read_frame(&mut reader).await.unwrap().unwrap();
+        assert_eq!(second.mode, super::TransportMode::RawJson);
+        let value: serde_json::Value = serde_json::from_str(&second.payload).unwrap();
+        assert_eq!(value["id"], 2);
+
+        assert!(McpServer::read_frame(&mut reader).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn encoded_frames_round_trip() {
+        let (mut client, server) = io::duplex(1024);
+        let mut reader = BufReader::new(server);
+        let payload = r#"{"jsonrpc":"2.0","id":3,"result":{}}"#;
+        for mode in [super::TransportMode::ContentLength, super::TransportMode::RawJson] {
+            client.write_all(&McpServer::encode_frame(payload, mode)).await.unwrap();
+            let frame = McpServer::read_frame(&mut reader).await.unwrap().unwrap();
+            assert_eq!(frame.payload, payload);
+            assert_eq!(frame.mode, mode);
+        }
+    }
+
+    #[tokio::test]
+    async fn read_frame_errors_carry_the_framing_to_answer_in() {
+        let (mut client, server) = io::duplex(1024);
+        let mut reader = BufReader::new(server);
+        client.write_all(b"{\"jsonrpc\": 2.0,]\n").await.unwrap();
+        client.shutdown().await.unwrap();
+
+        let error = McpServer::read_frame(&mut reader).await.unwrap_err();
+        let frame_error = error.downcast_ref::<super::FrameError>().unwrap();
+        assert_eq!(frame_error.mode, super::TransportMode::RawJson);
+        let response = serde_json::to_value(McpServer::parse_error(&error)).unwrap();
+        assert_eq!(response["id"], serde_json::Value::Null);
+        assert_eq!(response["error"]["code"], -32700);
+    }
+
+    #[tokio::test]
+    async fn read_frame_rejects_oversized_content_length() {
+        let (mut client, server) = io::duplex(1024);
+        let mut reader = BufReader::new(server);
+        let oversized = super::MAX_FRAME_BYTES + 1;
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call"}"#;
+        let writer = tokio::spawn(async move {
+            client.write_all(format!("Content-Length: {}\r\n\r\n{}", oversized, body).as_bytes()).await.unwrap();
+            let padding = vec![b' '; 64 * 1024];
+            let mut left = oversized - body.len();
+            while left > 0 {
+                let n = left.min(padding.len());
+                client.write_all(&padding[..n]).await.unwrap();
+                left -= n;
+            }
+            client.shutdown().await.unwrap();
+        });
+
+        let error = McpServer::read_frame(&mut reader).await.unwrap_err();
+        assert!(error.to_string().contains("frame limit"));
+        assert!(!super::FrameError::is_fatal(&error));
+        // The body was discarded rather than read as the next frame
+        assert!(McpServer::read_frame(&mut reader).await.unwrap().is_none());
+        writer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn read_frame_stops_at_oversized_raw_payload() {
+        let (mut client, server) = io::duplex(64 * 1024);
+        let mut reader = BufReader::new(server);
+        let writer = tokio::spawn(async move {
+            client.write_all(b"{\"padding\": \"").await.unwrap();
+            let padding = vec![b'x'; 64 * 1024];
+            for _ in 0..=super::MAX_FRAME_BYTES / padding.len() {
+                if client.write_all(&padding).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        let error = McpServer::read_frame(&mut reader).await.unwrap_err();
+        assert!(error.to_string().contains("frame limit"));
+        assert!(super::FrameError::is_fatal(&error));
+        drop(reader);
+        writer.await.unwrap();
+    }
 }
@@ -0,0 +1,209 @@
+//! Estimated context cost of tool results.
+//!
+//! Every structured result carries `estimate_tokens`, a rough count of the
+//! tokens it takes in an agent's context (serialized bytes / 4). With
+//! `response_token_budget` set in the tool config, a larger result gets a
+//! `token_budget_warning`, or with `over_token_budget: "paginate"` is cut down
+//! at its largest list: the items that fit are returned and the rest are held
+//! for `onelogin_get_result_page` under a `page_token`.
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Tool returning the held items of a paginated result
+pub const RESULT_PAGE_TOOL: &str = "onelogin_get_result_page";
+
+/// Average bytes of serialized JSON per token; a deliberately rough heuristic
+const BYTES_PER_TOKEN: u64 = 4;
+
+/// How long the rest of a paginated result is held
+const PAGE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Most paginated results held; the oldest is dropped first
+const MAX_HELD_RESULTS: usize = 32;
+
+/// Room left on a page for the fields around the items
+const PAGE_OVERHEAD_TOKENS: u64 = 64;
+
+pub fn estimate_tokens(serialized: &str) -> u64 {
+    (serialized.len() as u64).div_ceil(BYTES_PER_TOKEN)
+}
+
+/// Estimated tokens of `value` as it is sent to the client
+pub fn tokens_of(value: &Value) -> u64 {
+    estimate_tokens(&serde_json::to_string_pretty(value).unwrap_or_default())
+}
+
+/// Top-level list field of `result` taking the most room, if any has two or more items
+fn largest_list(result: &Value) -> Option<String> {
+    result
+        .as_object()?
+        .iter()
+        .filter(|(_, value)| value.as_array().is_some_and(|items| items.len() > 1))
+        .max_by_key(|(_, value)| tokens_of(value))
+        .map(|(name, _)| name.clone())
+}
+
+/// Estimated tokens of an item of a top-level list: pretty-printed two levels deep,
+/// followed by a comma and newline
+fn item_tokens(item: &Value) -> u64 {
+    let serialized = serde_json::to_string_pretty(item).unwrap_or_default();
+    let indentation = 4 * serialized.lines().count();
+    estimate_tokens(&serialized) + ((indentation + 2) as u64).div_ceil(BYTES_PER_TOKEN)
+}
+
+/// How many leading `items` fit in `budget` next to `overhead` tokens; at least one,
+/// so paging always makes progress
+fn fitting_prefix(items: &[Value], budget: u64, overhead: u64) -> usize {
+    let mut used = overhead;
+    let fitting = items
+        .iter()
+        .take_while(|item| {
+            used += item_tokens(item);
+            used <= budget
+        })
+        .count();
+    fitting.max(1).min(items.len())
+}
+
+struct Held {
+    tool: String,
+    field: String,
+    items: Vec<Value>,
+    stored_at: Instant,
+}
+
+#[derive(Default)]
+pub struct ResultPages {
+    held: Mutex<HashMap<String, Held>>,
+    counter: AtomicU64,
+}
+
+impl ResultPages {
+    /// Cut `result` at its largest list to fit `budget` and hold the rest.
+    /// Returns false, leaving `result` alone, when it has no list to cut.
+    pub fn paginate(&self, tool: &str, result: &mut Value, budget: u64) -> bool {
+        let Some(field) = largest_list(result) else {
+            return false;
+        };
+        let mut items = match result[&field].take() {
+            Value::Array(items) => items,
+            _ => unreachable!("largest_list returns array fields"),
+        };
+        let keep = fitting_prefix(&items, budget, tokens_of(result) + PAGE_OVERHEAD_TOKENS);
+        let rest = items.split_off(keep);
+        let returned = items.len();
+        result[&field] = Value::Array(items);
+        if rest.is_empty() {
+            return false;
+        }
+
+        let remaining = rest.len();
+        let token = self.hold(tool, &field, rest);
+        result["page_token"] = json!(token);
+        result["paged_field"] = json!(field);
+        result["items_returned"] = json!(returned);
+        result["items_remaining"] = json!(remaining);
+        true
+    }
+
+    fn hold(&self, tool: &str, field: &str, items: Vec<Value>) -> String {
+        let seed = format!(
+            "{}:{}:{:?}",
+            self.counter.fetch_add(1, Ordering::Relaxed),
+            std::process::id(),
+            std::time::SystemTime::now()
+        );
+        let token = hex::encode(&Sha256::digest(seed.as_bytes())[..12]);
+        let mut held = self.held.lock().expect("result pages poisoned");
+        held.retain(|_, h| h.stored_at.elapsed() < PAGE_TTL);
+        if held.len() >= MAX_HELD_RESULTS {
+            if let Some(oldest) = held.iter().min_by_key(|(_, h)| h.stored_at).map(|(k, _)| k.clone()) {
+                held.remove(&oldest);
+            }
+        }
+        held.insert(
+            token.clone(),
+            Held { tool: tool.to_string(), field: field.to_string(), items, stored_at: Instant::now() },
+        );
+        token
+    }
+
+    /// Next items of a paginated result, up to `budget` tokens (all of them without one).
+    /// The same `page_token` keeps working until the last items are returned.
+    pub fn next_page(&self, token: &str, budget: Option<u64>) -> Result<Value, String> {
+        let mut held = self.held.lock().expect("result pages poisoned");
+        let entry = held
+            .remove(token)
+            .filter(|h| h.stored_at.elapsed() < PAGE_TTL)
+            .ok_or_else(|| format!("Unknown or expired page_token '{}'; call the original tool again", token))?;
+        let Held { tool, field, mut items, .. } = entry;
+        let keep = budget.map_or(items.len(), |budget| fitting_prefix(&items, budget, PAGE_OVERHEAD_TOKENS));
+        let rest = items.split_off(keep);
+        let remaining = rest.len();
+        let next_token = (!rest.is_empty()).then(|| {
+            held.insert(
+                token.to_string(),
+                Held { tool: tool.clone(), field: field.clone(), items: rest, stored_at: Instant::now() },
+            );
+            token.to_string()
+        });
+        Ok(json!({
+            "tool": tool,
+            "paged_field": field,
+            field: items,
+            "page_token": next_token,
+            "items_remaining": remaining
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users(count: usize) -> Vec<Value> {
+        (0..count).map(|id| json!({"id": id, "email": format!("user{}@example.com", id)})).collect()
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(largest_list(&json!({"roles": [1, 2], "users": users(3), "single": [1]})), Some("users".to_string()));
+        assert_eq!(largest_list(&json!({"count": 1, "single": [1]})), None);
+    }
+
+    #[test]
+    fn test_paginate_and_next_page() {
+        let pages = ResultPages::default();
+        let mut result = json!({"users": users(100), "total": 100});
+        let budget = tokens_of(&result) / 4;
+        assert!(pages.paginate("onelogin_list_users", &mut result, budget));
+        let returned = result["users"].as_array().unwrap().len();
+        assert!(returned > 0 && returned < 100);
+        assert_eq!(result["items_remaining"], 100 - returned);
+        assert!(tokens_of(&result) <= budget);
+
+        let token = result["page_token"].as_str().unwrap().to_string();
+        let mut seen = returned;
+        loop {
+            let page = pages.next_page(&token, Some(budget)).unwrap();
+            assert_eq!(page["users"][0]["id"], seen);
+            seen += page["users"].as_array().unwrap().len();
+            if page["page_token"].is_null() {
+                break;
+            }
+        }
+        assert_eq!(seen, 100);
+        assert!(pages.next_page(&token, Some(budget)).is_err());
+
+        let mut small = json!({"count": 3});
+        assert!(!pages.paginate("onelogin_get_user", &mut small, 1));
+    }
+}
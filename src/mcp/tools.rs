@@ -1,27 +1,375 @@
 use crate::api::OneLoginClient;
+use crate::api::lookup::LookupKey;
+use crate::core::cache::{CACHE_HIT_AGE, FORCE_REFRESH};
+use crate::core::checkpoints::CheckpointStore;
+use crate::core::cooldowns::ToolCooldowns;
+use crate::core::response_cache::ResponseCache;
+use crate::core::cursors::CursorStore;
+use crate::core::keyed_mutex::KeyedMutex;
+use crate::core::config::{Config, OneLoginRegion};
+use crate::core::onboarding;
+use crate::core::role_snapshots::{self, RoleMember, RoleMembers, RoleSnapshot, RoleSnapshotStore};
+use crate::core::client::{RETRY_DIAGNOSTICS, SERVED_FROM_FALLBACK};
 use crate::core::error::OneLoginError;
 use crate::core::tenant_manager::TenantManager;
-use crate::core::tool_config::ToolConfig;
+use crate::core::scheduler::{JobRunner, JobStore};
+use crate::core::tool_config::{CategoryConfig, OverBudgetAction, ToolConfig, ToolConfigFile};
+use crate::core::webhook_receiver::WebhookBuffer;
+use crate::core::tool_config::TOOL_CATEGORIES;
+use crate::mcp::diff::{diff_source, diff_values};
+use crate::mcp::explain;
+use crate::mcp::providers::compliance::is_sensitive_role;
+use crate::mcp::providers::dependencies::{ImpactLog, DELETION_IMPACT_TOOL};
+use crate::mcp::providers::user_batch::{expand_user_references, UserExpansion};
+use crate::mcp::providers::user_export::{stream_users_to_file, ExportUsersArgs};
+use crate::mcp::providers::{default_providers, ToolProvider};
+use crate::mcp::schema::{input_schema, input_schema_with};
+use crate::mcp::token_budget::{self, ResultPages, RESULT_PAGE_TOOL};
+use crate::models::apps::{AppQueryParams, CreateAppRequest, UpdateAppRequest};
 use crate::models::events::EventQueryParams;
-use crate::models::roles::CreateRoleRequest;
-use crate::models::users::{User, UserQueryParams};
-use crate::utils::{base64_encode, base64_decode};
+use crate::models::roles::{CreateRoleRequest, RoleQueryParams, UpdateRoleRequest};
+use crate::models::users::{CreateUserRequest, UpdateUserRequest, User, UserQueryParams};
+use crate::utils::export::{ExportFormat, RowStreamWriter};
+use crate::utils::{base64_encode, base64_decode, suggest};
 use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{info, debug, warn};
 
+tokio::task_local! {
+    /// Deadline of the tool call being executed; long-running handlers check it to return partial results
+    static TOOL_DEADLINE: Option<tokio::time::Instant>;
+}
+
+/// Per-call argument overriding the configured tool timeout
+const TOOL_TIMEOUT_ARG: &str = "tool_timeout_seconds";
+
+/// Per-call argument that bypasses cached data
+const FORCE_REFRESH_ARG: &str = "force_refresh";
+
+/// Tools whose data may be served from `CacheManager`
+const CACHED_TOOLS: &[&str] = &[
+    "onelogin_get_user",
+    "onelogin_get_app",
+    "onelogin_get_role",
+    "onelogin_get_smart_hook",
+    "onelogin_get_privilege",
+];
+
+/// Tool name verbs that change OneLogin state; calls to these take per-resource write locks
+pub(crate) const WRITE_TOOL_VERBS: &[&str] = &[
+    "create", "update", "delete", "set", "assign", "remove", "add", "sort", "lock", "unlock",
+    "logout", "enroll", "revoke", "restore", "transition", "approve", "register", "track", "bulk",
+];
+
+/// Deadline of the current tool call, if one is set
+pub(crate) fn tool_deadline() -> Option<tokio::time::Instant> {
+    TOOL_DEADLINE.try_with(|deadline| *deadline).ok().flatten()
+}
+
 /// Extract an i64 from a JSON Value, handling both numeric and string representations.
 /// MCP clients often send numbers as strings (e.g., "257299146" instead of 257299146).
-fn value_as_i64(v: &Value) -> Option<i64> {
+pub(crate) fn value_as_i64(v: &Value) -> Option<i64> {
     v.as_i64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
 }
 
+/// Read report polling options (`poll_interval_seconds`, `timeout_seconds`) from tool args.
+fn report_poll_settings(args: &Value) -> (std::time::Duration, std::time::Duration) {
+    let interval = args
+        .get("poll_interval_seconds")
+        .and_then(value_as_i64)
+        .unwrap_or(2)
+        .clamp(1, 60) as u64;
+    let timeout = args
+        .get("timeout_seconds")
+        .and_then(value_as_i64)
+        .unwrap_or(120)
+        .clamp(1, 600) as u64;
+    (std::time::Duration::from_secs(interval), std::time::Duration::from_secs(timeout))
+}
+
+/// Return a report job inline, or write its rows to `output_path` and return a summary.
+fn export_report_job(mut job: crate::models::reports::ReportJob, args: &Value) -> Result<Value> {
+    let Some(output_path) = args.get("output_path").and_then(|v| v.as_str()) else {
+        return Ok(serde_json::to_value(job)?);
+    };
+    let Some(results) = job.results.take() else {
+        return Ok(json!({
+            "status": "not_ready",
+            "message": "Report job has no results yet; nothing was written",
+            "job": job
+        }));
+    };
+
+    let path = crate::utils::export::resolve_output_path(output_path)?;
+    let format = crate::utils::export::ExportFormat::resolve(
+        args.get("output_format").and_then(|v| v.as_str()),
+        &path,
+    );
+    let overwrite = args.get("overwrite").and_then(|v| v.as_bool()).unwrap_or(false);
+    let summary = crate::utils::export::write_rows_to_file(&results, &path, format, overwrite)
+        .with_context(|| format!("Failed to write report results to {}", path.display()))?;
+
+    Ok(json!({
+        "status": "exported",
+        "path": summary.path.display().to_string(),
+        "format": summary.format.as_str(),
+        "row_count": summary.row_count,
+        "bytes_written": summary.bytes_written,
+        "job": job
+    }))
+}
+
+/// Custom attribute filters for list_users, from a `custom_attributes` object and/or
+/// `custom_attributes.<shortname>` arguments. Values must be scalars.
+pub(crate) fn custom_attribute_filters(
+    args: &Value,
+    object: Option<&HashMap<String, Value>>,
+) -> Result<std::collections::BTreeMap<String, String>> {
+    let dotted = args
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| key.strip_prefix("custom_attributes.").map(|name| (name, value)));
+    let mut filters = std::collections::BTreeMap::new();
+    for (shortname, value) in object.into_iter().flatten().map(|(k, v)| (k.as_str(), v)).chain(dotted) {
+        if shortname.is_empty() || !shortname.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(anyhow!("Invalid custom attribute shortname '{}'", shortname));
+        }
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Number(_) | Value::Bool(_) => value.to_string(),
+            _ => return Err(anyhow!("Custom attribute filter '{}' must be a string, number or boolean", shortname)),
+        };
+        filters.insert(shortname.to_string(), value);
+    }
+    Ok(filters)
+}
+
+/// Reject custom attribute shortnames the tenant does not define, suggesting near misses.
+/// Validation is skipped when the catalog can't be read, so a missing permission doesn't block writes.
+pub(crate) async fn check_custom_attribute_names<'a>(
+    client: &OneLoginClient,
+    shortnames: impl IntoIterator<Item = &'a str>,
+) -> Result<()> {
+    let shortnames: Vec<&str> = shortnames.into_iter().collect();
+    match client.custom_attributes.validate_shortnames(&shortnames).await {
+        Ok(()) => Ok(()),
+        Err(OneLoginError::InvalidInput(message)) => Err(anyhow!(message)),
+        Err(e) => {
+            debug!("Skipping custom attribute validation, catalog unavailable: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Shortnames in a `custom_attributes` object argument
+fn custom_attribute_keys(args: &Value) -> impl Iterator<Item = &str> {
+    args.get("custom_attributes")
+        .and_then(|v| v.as_object())
+        .into_iter()
+        .flat_map(|attributes| attributes.keys().map(String::as_str))
+}
+
+/// Shortnames referenced by user mapping conditions (`custom_attribute_<shortname>` sources)
+fn mapping_custom_attribute_keys(args: &Value) -> impl Iterator<Item = &str> {
+    args.get("conditions")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|condition| condition.get("source")?.as_str()?.strip_prefix("custom_attribute_"))
+}
+
+/// Members of every role; an unreadable role is an error, since a gap would diff as mass removals
+async fn current_role_memberships(client: &OneLoginClient) -> Result<Vec<RoleMembers>> {
+    let roles = client.roles.list_roles().await
+        .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+    let mut memberships = Vec::with_capacity(roles.len());
+    for role in &roles {
+        let users = client.roles.get_role_users(role.id).await
+            .map_err(|e| anyhow!("Failed to get users of role {}: {}", role.id, e))?;
+        memberships.push(RoleMembers {
+            role_id: role.id,
+            name: role.name.clone(),
+            members: users
+                .into_iter()
+                .filter_map(|u| Some(RoleMember { id: u.id?, email: u.email, name: u.name }))
+                .collect(),
+        });
+    }
+    Ok(memberships)
+}
+
+/// Schema for the `expand` argument of the user read tools
+fn expand_user_property() -> Value {
+    json!({
+        "type": "array",
+        "items": {"type": "string", "enum": ["roles", "group"]},
+        "description": "Embed names next to IDs from the cached role/group catalogs: \"roles\" adds roles: [{id, name}], \"group\" adds group: {id, name}"
+    })
+}
+
+/// Resolve role/group names into `response[users_key]` (or `response` itself), noting catalogs that failed
+async fn apply_user_expansion(
+    client: &OneLoginClient,
+    response: &mut Value,
+    users_key: Option<&str>,
+    expand: Option<&[UserExpansion]>,
+) {
+    let Some(expand) = expand.filter(|e| !e.is_empty()) else {
+        return;
+    };
+    let warnings = match users_key {
+        Some(key) => expand_user_references(client, &mut response[key], expand).await,
+        None => expand_user_references(client, response, expand).await,
+    };
+    if !warnings.is_empty() {
+        response["expand_warnings"] = json!(warnings);
+    }
+}
+
+/// Fail with every actionable package problem before a hook is deployed
+async fn verify_hook_packages(packages: &HashMap<String, String>) -> Result<()> {
+    if packages.is_empty() {
+        return Ok(());
+    }
+    let checks = crate::utils::npm::check_packages(packages)
+        .await
+        .map_err(|e| anyhow!("{} (pass skip_package_check=true to deploy without the check)", e))?;
+    let problems: Vec<String> = checks.into_iter().flat_map(|c| c.errors).collect();
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!("Smart Hook packages would fail to build:\n- {}", problems.join("\n- ")))
+}
+
+/// Follow event cursors straight into `output_path`. Without max_pages every matching event is written.
+async fn stream_events_to_file(client: &OneLoginClient, args: &ListEventsArgs, output_path: &str) -> Result<Value> {
+    let path = crate::utils::export::resolve_output_path(output_path)?;
+    let format = ExportFormat::resolve(args.output_format.as_deref(), &path);
+    let mut writer = RowStreamWriter::create(&path, format, args.overwrite.unwrap_or(false))
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut query = args.query.clone();
+    query.limit = Some(query.limit.unwrap_or(1000).clamp(1, 1000));
+    let started = std::time::Instant::now();
+    let deadline = tool_deadline();
+
+    let mut pages_fetched = 0;
+    let mut timed_out = false;
+    let mut error = None;
+    let next_cursor = loop {
+        if args.max_pages.is_some_and(|m| pages_fetched >= m.max(1)) {
+            break query.after_cursor.clone();
+        }
+        let fetch = client.events.list_events_page(&query);
+        let fetched = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, fetch).await {
+                Ok(fetched) => fetched,
+                Err(_) => {
+                    timed_out = true;
+                    break query.after_cursor.clone();
+                }
+            },
+            None => fetch.await,
+        };
+        let (batch, cursor) = match fetched {
+            Ok(page) => page,
+            Err(e) => {
+                warn!("Failed to fetch events page for export: {}", e);
+                error = Some(format!("Failed to list events: {}", e));
+                break query.after_cursor.clone();
+            }
+        };
+        let rows = batch.iter().map(serde_json::to_value).collect::<std::result::Result<Vec<_>, _>>()?;
+        writer.write_rows(&rows).with_context(|| format!("Failed to write to {}", path.display()))?;
+        pages_fetched += 1;
+        info!("Exported events page {} ({} rows so far) to {}", pages_fetched, writer.row_count(), path.display());
+        match cursor {
+            Some(cursor) if !batch.is_empty() => query.after_cursor = Some(cursor),
+            _ => break None,
+        }
+    };
+
+    let summary = writer.finish().with_context(|| format!("Failed to write to {}", path.display()))?;
+    Ok(json!({
+        "status": if timed_out || error.is_some() { "partial" } else { "exported" },
+        "partial": timed_out || error.is_some(),
+        "path": summary.path.display().to_string(),
+        "format": summary.format.as_str(),
+        "row_count": summary.row_count,
+        "bytes_written": summary.bytes_written,
+        "pagesFetched": pages_fetched,
+        "elapsed_seconds": started.elapsed().as_secs(),
+        "nextCursor": next_cursor,
+        "timedOut": timed_out,
+        "error": error
+    }))
+}
+
+/// Resource keys a write tool must hold, e.g. `default:user:123`, from its `*_id` and
+/// `*_ids` arguments. Empty for read-only tools and writes that name no resource.
+fn write_lock_keys(tenant: &str, tool: &str, args: &Value) -> Vec<String> {
+    let verb = tool.trim_start_matches("onelogin_").split('_').next().unwrap_or_default();
+    let Some(args) = args.as_object().filter(|_| WRITE_TOOL_VERBS.contains(&verb)) else {
+        return Vec::new();
+    };
+    let mut keys = Vec::new();
+    for (name, value) in args {
+        if let Some(resource) = name.strip_suffix("_ids") {
+            let ids: Vec<String> = value.as_array().map(|ids| ids.iter().filter_map(value_as_id_string).collect()).unwrap_or_default();
+            keys.extend(ids.into_iter().map(|id| format!("{}:{}:{}", tenant, resource, id)));
+        } else if let Some(resource) = name.strip_suffix("_id") {
+            if let Some(id) = value_as_id_string(value) {
+                keys.push(format!("{}:{}:{}", tenant, resource, id));
+            }
+        }
+    }
+    keys
+}
+
+/// Optional `locale` argument of branding and template tools
+fn locale_arg(args: &Value) -> Option<&str> {
+    args.get("locale").and_then(|v| v.as_str()).map(str::trim).filter(|l| !l.is_empty())
+}
+
+/// Extract an identifier as a string, accepting both string and numeric JSON values.
+fn value_as_id_string(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
 #[allow(dead_code)]
 pub struct ToolRegistry {
     tenant_manager: Arc<TenantManager>,
     tool_config: Arc<ToolConfig>,
+    providers: Vec<Box<dyn ToolProvider>>,
+    /// Tool name -> index into `providers`
+    provider_index: HashMap<String, usize>,
+    checkpoints: Arc<CheckpointStore>,
+    /// Where interrupted listings resume
+    cursors: Arc<CursorStore>,
+    /// Role membership history for snapshot diffs
+    role_snapshots: Arc<RoleSnapshotStore>,
+    /// Set when the webhook receiver is running
+    webhooks: Option<Arc<WebhookBuffer>>,
+    jobs: Arc<JobStore>,
+    /// Deletion impact analyses, for `require_deletion_impact`
+    deletion_impacts: ImpactLog,
+    /// Last calls of tools with a configured cooldown
+    cooldowns: ToolCooldowns,
+    /// Computed results of report tools with a response cache TTL
+    responses: ResponseCache,
+    /// Rest of results paginated to fit the response token budget
+    result_pages: ResultPages,
+    /// Serializes concurrent writes to the same user, app, role, ...
+    write_locks: KeyedMutex,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -37,12 +385,133 @@ struct ListUsersArgs {
     auto_paginate: Option<bool>,
     max_pages: Option<i32>,
     max_results: Option<u32>,
+    output_path: Option<String>,
+    output_format: Option<String>,
+    overwrite: Option<bool>,
+    anonymize: Option<bool>,
+    anonymize_salt: Option<String>,
+    drop_fields: Option<Vec<String>>,
+    custom_attributes: Option<HashMap<String, Value>>,
+    expand: Option<Vec<UserExpansion>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListEventsArgs {
+    #[serde(flatten)]
+    query: EventQueryParams,
+    auto_paginate: Option<bool>,
+    max_pages: Option<usize>,
+    output_path: Option<String>,
+    output_format: Option<String>,
+    overwrite: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GetNewEventsArgs {
+    checkpoint: Option<String>,
+    since: Option<String>,
+    user_id: Option<i64>,
+    event_type_id: Option<i32>,
+    limit: Option<i32>,
+    max_pages: Option<usize>,
+    #[serde(default)]
+    peek: bool,
+    #[serde(default)]
+    reset: bool,
 }
 
 #[allow(dead_code)]
 impl ToolRegistry {
     pub fn new(tenant_manager: Arc<TenantManager>, tool_config: Arc<ToolConfig>) -> Self {
-        Self { tenant_manager, tool_config }
+        let providers = default_providers();
+        let provider_index = providers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, p)| {
+                p.tools()
+                    .into_iter()
+                    .filter_map(move |t| t["name"].as_str().map(|n| (n.to_string(), i)))
+            })
+            .collect();
+        Self {
+            tenant_manager,
+            providers,
+            provider_index,
+            checkpoints: Arc::new(CheckpointStore::load(CheckpointStore::default_path())),
+            cursors: Arc::new(CursorStore::load(CursorStore::default_path())),
+            role_snapshots: Arc::new(RoleSnapshotStore::load(RoleSnapshotStore::default_path())),
+            webhooks: None,
+            jobs: Arc::new(JobStore::from_configs(&tool_config.jobs())),
+            deletion_impacts: ImpactLog::default(),
+            cooldowns: ToolCooldowns::default(),
+            responses: ResponseCache::default(),
+            result_pages: ResultPages::default(),
+            write_locks: KeyedMutex::new(),
+            tool_config,
+        }
+    }
+
+    /// Scheduled jobs declared in the tool config
+    pub fn jobs(&self) -> &Arc<JobStore> {
+        &self.jobs
+    }
+
+    /// Serve events received by the webhook receiver
+    pub fn with_webhooks(mut self, webhooks: Arc<WebhookBuffer>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Use a different event checkpoint store (e.g. in-memory for mock mode)
+    pub fn with_checkpoints(mut self, checkpoints: Arc<CheckpointStore>) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    /// Use a different pagination cursor store (e.g. in-memory for mock mode)
+    pub fn with_cursors(mut self, cursors: Arc<CursorStore>) -> Self {
+        self.cursors = cursors;
+        self
+    }
+
+    /// Use a different role snapshot store (e.g. in-memory for mock mode)
+    pub fn with_role_snapshots(mut self, role_snapshots: Arc<RoleSnapshotStore>) -> Self {
+        self.role_snapshots = role_snapshots;
+        self
+    }
+
+    /// Name of the tenant a call targets: its "tenant" argument, else the default tenant
+    fn tenant_name(&self, args: &Value) -> String {
+        args.get("tenant")
+            .and_then(|v| v.as_str())
+            .filter(|t| !t.is_empty())
+            .unwrap_or(self.tenant_manager.default_tenant_name())
+            .to_string()
+    }
+
+    /// Stored position of an interrupted listing when the call asks to `resume`. A call
+    /// that does not resume starts a new chain.
+    fn resume_cursor(&self, tool: &str, args: &Value) -> (String, Option<String>) {
+        let key = CursorStore::key(&self.tenant_name(args), tool, args);
+        if args.get("resume").and_then(|v| v.as_bool()).unwrap_or(false) {
+            let next = self.cursors.get(&key).map(|chain| chain.next);
+            if next.is_none() {
+                debug!("No saved cursor for {}, starting from the first page", key);
+            }
+            (key, next)
+        } else {
+            if let Err(e) = self.cursors.reset(&key) {
+                warn!("Failed to clear pagination cursor {}: {:#}", key, e);
+            }
+            (key, None)
+        }
+    }
+
+    /// Save where a listing stopped; a failed save only means it cannot be resumed
+    fn save_cursor(&self, key: &str, tool: &str, visited: Vec<String>, next: Option<String>) {
+        if let Err(e) = self.cursors.record(key, tool, visited, next) {
+            warn!("Failed to save pagination cursor {}: {:#}", key, e);
+        }
     }
 
     /// Extract the optional "tenant" parameter from tool args and resolve to the correct client.
@@ -51,6 +520,58 @@ impl ToolRegistry {
         self.tenant_manager.resolve(tenant)
     }
 
+    /// Inject the optional per-call timeout parameter into a tool's inputSchema.
+    fn with_timeout_param(tool: Value) -> Value {
+        let mut tool = tool;
+        if let Some(props) = tool
+            .get_mut("inputSchema")
+            .and_then(|schema| schema.get_mut("properties"))
+            .and_then(|props| props.as_object_mut())
+        {
+            props.insert(TOOL_TIMEOUT_ARG.to_string(), json!({
+                "type": "integer",
+                "description": "Abort this call after this many seconds (0 = no deadline). Paginated tools return the results collected so far. Default: server setting"
+            }));
+        }
+        tool
+    }
+
+    /// Inject the `force_refresh` parameter into tools that can serve cached data.
+    fn with_cache_param(&self, tool: Value) -> Value {
+        let mut tool = tool;
+        let cached = tool["name"]
+            .as_str()
+            .is_some_and(|name| CACHED_TOOLS.contains(&name) || self.response_cache_ttl(name).is_some());
+        if let Some(props) = tool
+            .get_mut("inputSchema")
+            .and_then(|schema| schema.get_mut("properties"))
+            .and_then(|props| props.as_object_mut())
+            .filter(|_| cached)
+        {
+            props.insert(FORCE_REFRESH_ARG.to_string(), json!({
+                "type": "boolean",
+                "description": "Bypass the cache and fetch fresh data from OneLogin. Cached results include 'cached': true and 'age_seconds'. Default: false"
+            }));
+        }
+        tool
+    }
+
+    /// Lifetime of `tool`'s cached results; only read-only tools are cached
+    fn response_cache_ttl(&self, tool: &str) -> Option<std::time::Duration> {
+        self.tool_config
+            .response_cache_ttl(tool)
+            .filter(|_| super::explain::classify(tool) == "read_only")
+    }
+
+    /// Deadline for a call: the per-call argument if given, otherwise the configured default
+    fn call_timeout(&self, args: &Value) -> Option<std::time::Duration> {
+        match args.get(TOOL_TIMEOUT_ARG).and_then(value_as_i64) {
+            Some(secs) if secs <= 0 => None,
+            Some(secs) => Some(std::time::Duration::from_secs(secs as u64)),
+            None => self.tool_config.tool_timeout(),
+        }
+    }
+
     /// Inject the optional "tenant" parameter into a tool's inputSchema when in multi-tenant mode.
     fn with_tenant_param(&self, mut tool: Value) -> Value {
         if !self.tenant_manager.is_multi_tenant() {
@@ -88,8 +609,9 @@ impl ToolRegistry {
         &self.tool_config
     }
 
-    pub fn list_tools(&self) -> Vec<Value> {
-        let all_tools = vec![
+    /// Every tool definition, enabled or not, under its canonical name
+    fn tool_definitions(&self) -> Vec<Value> {
+        let mut all_tools = vec![
             // Users API
             self.tool_list_users(),
             self.tool_get_user(),
@@ -245,6 +767,8 @@ impl ToolRegistry {
             self.tool_create_session(),
             // Events API
             self.tool_list_events(),
+            self.tool_get_new_events(),
+            self.tool_get_received_events(),
             self.tool_get_event(),
             self.tool_create_event(),
             self.tool_list_event_types(),
@@ -254,19 +778,12 @@ impl ToolRegistry {
             self.tool_create_api_authorization(),
             self.tool_update_api_authorization(),
             self.tool_delete_api_authorization(),
-            // Rate Limits API
-            self.tool_get_rate_limit_status(),
-            self.tool_get_rate_limits(),
             // Account Settings API
             self.tool_get_account_settings(),
             self.tool_update_account_settings(),
             self.tool_get_account_features(),
             self.tool_get_account_usage(),
-            // Password Policies API
-            self.tool_list_password_policies(),
-            self.tool_get_password_policy(),
-            self.tool_create_password_policy(),
-            self.tool_update_password_policy(),
+            self.tool_get_account_overview(),
             // Certificates API
             self.tool_list_certificates(),
             self.tool_get_certificate(),
@@ -284,6 +801,7 @@ impl ToolRegistry {
             self.tool_create_login_page(),
             self.tool_update_login_page(),
             self.tool_delete_login_page(),
+            self.tool_get_login_page_preview_url(),
             // Trusted IDPs API
             self.tool_list_trusted_idps(),
             self.tool_get_trusted_idp(),
@@ -300,30 +818,90 @@ impl ToolRegistry {
             self.tool_get_role_admins(),
             self.tool_add_role_admins(),
             self.tool_remove_role_admin(),
+            self.tool_snapshot_role_memberships(),
+            self.tool_list_role_snapshots(),
+            self.tool_diff_role_snapshots(),
+            self.tool_privilege_escalation_report(),
             // Note: assign_roles_to_user and remove_roles_from_user omitted - use existing
             // onelogin_assign_roles and onelogin_remove_roles instead (same functionality)
         ];
+        all_tools.extend(self.providers.iter().flat_map(|p| p.tools()));
 
         // Inject tenant parameter into all tools when in multi-tenant mode
         let mut tools: Vec<Value> = all_tools
             .into_iter()
-            .map(|t| self.with_tenant_param(t))
+            .map(|t| self.with_cache_param(Self::with_timeout_param(self.with_tenant_param(t))))
             .collect();
 
         // Add tenant management tools
         tools.push(self.tool_list_tenants());
+        tools.push(self.tool_server_stats());
+        tools.extend([self.tool_list_jobs(), self.tool_get_job_results(), self.tool_run_job()]);
+        tools.push(self.tool_explain_tool());
+        tools.push(self.tool_get_result_page());
+        tools.extend([self.tool_validate_tenant_credentials(), self.tool_add_tenant(), self.tool_set_tool_categories()]);
+        tools
+    }
 
+    pub fn list_tools(&self) -> Vec<Value> {
         // Filter tools based on configuration
-        tools
+        let tools: Vec<Value> = self
+            .tool_definitions()
             .into_iter()
             .filter(|tool| {
                 let name = tool["name"].as_str().unwrap_or("");
                 self.tool_config.is_tool_enabled(name)
             })
+            .collect();
+
+        // Aliases are listed as copies of their (enabled) target tool
+        let mut aliases = Vec::new();
+        for (alias, target) in self.tool_config.aliases() {
+            if let Some(tool) = tools.iter().find(|t| t["name"] == target.as_str()) {
+                let mut tool = tool.clone();
+                let description = tool["description"].as_str().unwrap_or("").to_string();
+                tool["name"] = json!(alias);
+                tool["description"] = json!(format!(
+                    "Alias for {}. {}",
+                    self.tool_config.exposed_name(&target),
+                    description
+                ));
+                aliases.push(tool);
+            }
+        }
+
+        tools
+            .into_iter()
+            .map(|mut tool| {
+                let name = self.tool_config.exposed_name(tool["name"].as_str().unwrap_or(""));
+                tool["name"] = json!(name);
+                tool
+            })
+            .chain(aliases)
+            .map(|mut tool| {
+                if let Some(description) = tool["description"].as_str() {
+                    tool["description"] = json!(self.tool_config.rewrite_tool_references(description));
+                }
+                tool
+            })
             .collect()
     }
 
     pub async fn call_tool(&self, params: &super::server::CallToolParams) -> Result<String> {
+        // Clients call tools by their exposed name (prefixed or alias); dispatch uses the canonical one
+        let canonical = self.tool_config.resolve_tool_name(&params.name);
+        let resolved;
+        let params = if canonical != params.name {
+            debug!("Resolved tool name {} -> {}", params.name, canonical);
+            resolved = super::server::CallToolParams {
+                name: canonical,
+                arguments: params.arguments.clone(),
+            };
+            &resolved
+        } else {
+            params
+        };
+
         // Check if tool is enabled before executing
         if !self.tool_config.is_tool_enabled(&params.name) {
             warn!("Attempted to call disabled tool: {}", params.name);
@@ -338,8 +916,234 @@ impl ToolRegistry {
             ));
         }
 
+        let tenant = params
+            .arguments
+            .get("tenant")
+            .and_then(|v| v.as_str())
+            .unwrap_or(self.tenant_manager.default_tenant_name())
+            .to_string();
+        if self.tool_config.require_deletion_impact() {
+            self.deletion_impacts.check(&tenant, &params.name, &params.arguments)?;
+        }
+        let cooldown = self.tool_config.tool_cooldown(&params.name);
+        let previous_run = match cooldown {
+            Some(interval) => self.cooldowns.claim(&params.name, interval).map_err(|e| {
+                warn!("{}", e);
+                e
+            })?,
+            None => None,
+        };
+
         info!("Calling tool: {}", params.name);
 
+        let timeout = self.call_timeout(&params.arguments);
+        let deadline = timeout.map(|t| tokio::time::Instant::now() + t);
+
+        let force_refresh = params
+            .arguments
+            .get(FORCE_REFRESH_ARG)
+            .is_some_and(|v| v.as_bool().unwrap_or(v.as_str() == Some("true")));
+
+        let response_ttl = self.response_cache_ttl(&params.name);
+        let response_key = response_ttl.map(|_| ResponseCache::key(&tenant, &params.name, &params.arguments));
+        if let Some(hit) = response_key.as_deref().filter(|_| !force_refresh).and_then(|key| self.responses.get(key)) {
+            debug!("Tool {} served from the response cache", params.name);
+            let mut result = hit.result;
+            if let Some(obj) = result.as_object_mut() {
+                obj.insert("cached".to_string(), json!(true));
+                obj.insert("age_seconds".to_string(), json!(hit.age.as_secs()));
+                obj.insert("expires_in_seconds".to_string(), json!(hit.expires_in.as_secs()));
+            }
+            return self.finish_result(&params.name, result);
+        }
+
+        // Writes to the same resource run one at a time, so parallel calls cannot interleave
+        // their read-modify-write steps, diff snapshots or cache invalidation
+        let lock_keys = write_lock_keys(&tenant, &params.name, &params.arguments);
+        if lock_keys.iter().any(|key| self.write_locks.is_locked(key)) {
+            debug!("Tool {} waiting for a concurrent write to {:?}", params.name, lock_keys);
+        }
+
+        // Cache hits, fallback-region responses and retries are recorded so the caller knows
+        // where the result came from and why it took as long as it did
+        let run = CACHE_HIT_AGE.scope(
+            RefCell::new(None),
+            SERVED_FROM_FALLBACK.scope(
+                RefCell::new(None),
+                RETRY_DIAGNOSTICS.scope(
+                    RefCell::new(Default::default()),
+                    TOOL_DEADLINE.scope(deadline, FORCE_REFRESH.scope(force_refresh, async {
+                        let _write_guards = self.write_locks.lock_all(&lock_keys).await;
+                        let outcome = self.dispatch_with_diff(params).await;
+                        let cache_age = CACHE_HIT_AGE.with(|slot| slot.borrow_mut().take());
+                        let fallback = SERVED_FROM_FALLBACK.with(|slot| slot.borrow_mut().take());
+                        let retries = RETRY_DIAGNOSTICS.with(|slot| slot.take());
+                        (outcome, cache_age, fallback, retries)
+                    })),
+                ),
+            ),
+        );
+
+        let (outcome, cache_age, fallback_region, retries) = match (deadline, timeout) {
+            (Some(deadline), Some(timeout)) => match tokio::time::timeout_at(deadline, run).await {
+                Ok(finished) => finished,
+                Err(_) => {
+                    warn!("Tool {} exceeded its {}s deadline", params.name, timeout.as_secs());
+                    return Err(anyhow!(
+                        "Tool '{}' timed out after {} seconds. Narrow the query, or raise the deadline with the '{}' argument or ONELOGIN_MCP_TOOL_TIMEOUT.",
+                        params.name,
+                        timeout.as_secs(),
+                        TOOL_TIMEOUT_ARG
+                    ));
+                }
+            },
+            _ => run.await,
+        };
+
+        // A response that failed typed deserialization is still returned when that failure is
+        // the error the tool ended with, so the caller gets the data; other errors propagate
+        let raw_response = outcome.as_ref().err().and_then(|e| {
+            e.chain()
+                .find_map(|cause| cause.downcast_ref::<OneLoginError>())
+                .and_then(OneLoginError::raw_response)
+                .cloned()
+        });
+        let result = match (outcome, raw_response) {
+            (Ok(mut result), _) => {
+                if params.name == DELETION_IMPACT_TOOL {
+                    self.deletion_impacts.record(&tenant, &params.arguments);
+                }
+                if let (Some(ttl), Some(key)) = (response_ttl, response_key) {
+                    // Results cut short by the deadline or an error carry `partial: true`
+                    // and are not worth reusing
+                    if result["partial"] != true {
+                        self.responses.insert(key, &tenant, ttl, &result);
+                    }
+                } else if super::explain::classify(&params.name) != "read_only" {
+                    self.responses.invalidate_tenant(&tenant);
+                }
+                if let (Some(age), Some(obj)) = (cache_age, result.as_object_mut()) {
+                    obj.insert("cached".to_string(), json!(true));
+                    obj.insert("age_seconds".to_string(), json!(age.as_secs()));
+                }
+                if let (Some(base_url), Some(obj)) = (&fallback_region, result.as_object_mut()) {
+                    obj.insert("served_from_fallback".to_string(), json!(true));
+                    obj.insert("fallback_base_url".to_string(), json!(base_url));
+                }
+                if let (true, Some(obj)) = (retries.is_notable(), result.as_object_mut()) {
+                    info!(
+                        "Tool {} needed {} attempts ({}ms backoff, rate limited: {})",
+                        params.name, retries.attempts, retries.total_backoff_ms, retries.rate_limited
+                    );
+                    obj.insert("retry_diagnostics".to_string(), json!(retries));
+                }
+                result
+            }
+            (Err(e), Some(raw)) => {
+                warn!("Tool {} could not parse the API response, returning raw JSON: {}", params.name, e);
+                if super::explain::classify(&params.name) != "read_only" {
+                    self.responses.invalidate_tenant(&tenant);
+                }
+                json!({
+                    "warning": "The OneLogin API returned a response in an unexpected format. The raw response is included below.",
+                    "error": e.to_string(),
+                    "raw_response": raw
+                })
+            }
+            (Err(e), None) => {
+                if cooldown.is_some() {
+                    self.cooldowns.release(&params.name, previous_run);
+                }
+                return Err(e);
+            }
+        };
+
+        self.finish_result(&params.name, result)
+    }
+
+    /// Add `estimate_tokens` to a structured result and apply the response token budget
+    fn finish_result(&self, tool: &str, mut result: Value) -> Result<String> {
+        if !result.is_object() {
+            return Ok(serde_json::to_string_pretty(&result)?);
+        }
+        let mut tokens = token_budget::tokens_of(&result);
+        // Result pages are already cut to the budget
+        let budget = self.tool_config.token_budget().filter(|(budget, _)| tokens > *budget && tool != RESULT_PAGE_TOOL);
+        if let Some((budget, action)) = budget {
+            if action == OverBudgetAction::Paginate && self.result_pages.paginate(tool, &mut result, budget) {
+                info!("Tool {} result of ~{} tokens paginated to a budget of {}", tool, tokens, budget);
+                tokens = token_budget::tokens_of(&result);
+            } else {
+                warn!("Tool {} returned ~{} tokens, over the response budget of {}", tool, tokens, budget);
+                result["token_budget_warning"] = json!(format!(
+                    "This result is about {} tokens, over the configured budget of {}. Narrow the query with filters, a lower limit or fewer fields.",
+                    tokens, budget
+                ));
+            }
+        }
+        result["estimate_tokens"] = json!(tokens);
+        Ok(serde_json::to_string_pretty(&result)?)
+    }
+
+    /// Run a tool; update tools also snapshot their resource before and after and report the diff
+    async fn dispatch_with_diff(&self, params: &super::server::CallToolParams) -> Result<Value> {
+        let Some(source) = diff_source(&params.name) else {
+            return self.dispatch_tool(params).await;
+        };
+        let get = super::server::CallToolParams {
+            name: source.get_tool.to_string(),
+            arguments: source.get_args(&params.arguments),
+        };
+
+        // Snapshots bypass the cache so the diff reflects what OneLogin actually holds
+        let before = match FORCE_REFRESH.scope(true, self.dispatch_tool(&get)).await {
+            Ok(before) => Some(before),
+            Err(e) => {
+                warn!("Could not snapshot resource before {}: {}", params.name, e);
+                None
+            }
+        };
+        let result = self.dispatch_tool(params).await?;
+        let Some(before) = before else {
+            return Ok(result);
+        };
+        let after = match FORCE_REFRESH.scope(true, self.dispatch_tool(&get)).await {
+            Ok(after) => after,
+            Err(e) => {
+                warn!("Could not snapshot resource after {}: {}", params.name, e);
+                return Ok(result);
+            }
+        };
+
+        let changes = diff_values(&before, &after);
+        let changes_json = Value::Array(changes.clone());
+        info!(
+            target: "audit",
+            tool = %params.name,
+            args = %source.get_args(&params.arguments),
+            changes = %changes_json,
+            "Resource updated"
+        );
+        Ok(json!({
+            "result": result,
+            "diff": {
+                "changed": !changes.is_empty(),
+                "changes": changes
+            }
+        }))
+    }
+
+    async fn dispatch_tool(&self, params: &super::server::CallToolParams) -> Result<Value> {
+        if let Some(&index) = self.provider_index.get(&params.name) {
+            let provider = &self.providers[index];
+            let client = self.resolve_client(&params.arguments)?;
+            debug!("Tool {} handled by {} provider", params.name, provider.name());
+            return provider
+                .call(&client, &params.name, &params.arguments)
+                .await
+                .unwrap_or_else(|| Err(anyhow!("Unknown tool: {}", params.name)));
+        }
+
         let result = match params.name.as_str() {
             // Users
             "onelogin_list_users" => self.handle_list_users(&params.arguments).await?,
@@ -445,6 +1249,8 @@ impl ToolRegistry {
 
             // Events
             "onelogin_list_events" => self.handle_list_events(&params.arguments).await?,
+            "onelogin_get_new_events" => self.handle_get_new_events(&params.arguments).await?,
+            "onelogin_get_received_events" => self.handle_get_received_events(&params.arguments)?,
             "onelogin_get_event" => self.handle_get_event(&params.arguments).await?,
             "onelogin_create_event" => self.handle_create_event(&params.arguments).await?,
             "onelogin_list_event_types" => self.handle_list_event_types(&params.arguments).await?,
@@ -555,21 +1361,12 @@ impl ToolRegistry {
             // Branding
             "onelogin_update_branding_settings" => self.handle_update_branding_settings(&params.arguments).await?,
 
-            // Rate Limits
-            "onelogin_get_rate_limit_status" => self.handle_get_rate_limit_status(&params.arguments).await?,
-            "onelogin_get_rate_limits" => self.handle_get_rate_limits(&params.arguments).await?,
-
             // Account Settings
             "onelogin_get_account_settings" => self.handle_get_account_settings(&params.arguments).await?,
             "onelogin_update_account_settings" => self.handle_update_account_settings(&params.arguments).await?,
             "onelogin_get_account_features" => self.handle_get_account_features(&params.arguments).await?,
             "onelogin_get_account_usage" => self.handle_get_account_usage(&params.arguments).await?,
-
-            // Password Policies
-            "onelogin_list_password_policies" => self.handle_list_password_policies(&params.arguments).await?,
-            "onelogin_get_password_policy" => self.handle_get_password_policy(&params.arguments).await?,
-            "onelogin_create_password_policy" => self.handle_create_password_policy(&params.arguments).await?,
-            "onelogin_update_password_policy" => self.handle_update_password_policy(&params.arguments).await?,
+            "onelogin_get_account_overview" => self.handle_get_account_overview(&params.arguments).await?,
 
             // Certificates
             "onelogin_list_certificates" => self.handle_list_certificates(&params.arguments).await?,
@@ -590,6 +1387,7 @@ impl ToolRegistry {
             "onelogin_create_login_page" => self.handle_create_login_page(&params.arguments).await?,
             "onelogin_update_login_page" => self.handle_update_login_page(&params.arguments).await?,
             "onelogin_delete_login_page" => self.handle_delete_login_page(&params.arguments).await?,
+            "onelogin_get_login_page_preview_url" => self.handle_get_login_page_preview_url(&params.arguments).await?,
 
             // Trusted IDPs
             "onelogin_list_trusted_idps" => self.handle_list_trusted_idps(&params.arguments).await?,
@@ -608,21 +1406,38 @@ impl ToolRegistry {
             "onelogin_get_role_admins" => self.handle_get_role_admins(&params.arguments).await?,
             "onelogin_add_role_admins" => self.handle_add_role_admins(&params.arguments).await?,
             "onelogin_remove_role_admin" => self.handle_remove_role_admin(&params.arguments).await?,
+            "onelogin_snapshot_role_memberships" => self.handle_snapshot_role_memberships(&params.arguments).await?,
+            "onelogin_list_role_snapshots" => json!({"snapshots": self.role_snapshots.list(&self.tenant_name(&params.arguments))}),
+            "onelogin_diff_role_snapshots" => self.handle_diff_role_snapshots(&params.arguments)?,
+            "onelogin_privilege_escalation_report" => self.handle_privilege_escalation_report(&params.arguments).await?,
 
             // Tenant Management
             "onelogin_list_tenants" => self.handle_list_tenants().await?,
+            "onelogin_server_stats" => self.handle_server_stats(&params.arguments)?,
+            "onelogin_explain_tool" => self.handle_explain_tool(&params.arguments)?,
+            "onelogin_get_result_page" => self.handle_get_result_page(&params.arguments)?,
+
+            // Tenant onboarding
+            "onelogin_validate_tenant_credentials" => self.handle_validate_tenant_credentials(&params.arguments).await?,
+            "onelogin_add_tenant" => self.handle_add_tenant(&params.arguments).await?,
+            "onelogin_set_tool_categories" => self.handle_set_tool_categories(&params.arguments)?,
+
+            // Scheduled jobs
+            "onelogin_list_jobs" => json!({"jobs": self.jobs.list()}),
+            "onelogin_get_job_results" => self.handle_get_job_results(&params.arguments)?,
+            "onelogin_run_job" => self.handle_run_job(&params.arguments).await?,
 
             _ => return Err(anyhow!("Unknown tool: {}", params.name)),
         };
 
-        Ok(serde_json::to_string_pretty(&result)?)
+        Ok(result)
     }
 
     // Tool definitions
     fn tool_list_users(&self) -> Value {
         json!({
             "name": "onelogin_list_users",
-            "description": "List users in OneLogin with optional filtering. Use filters to find specific users by email, username, name, role, or directory. Returns user objects with id, email, username, firstname, lastname, status, state, and more. To find a single user by email, use email filter. To find users in a role, use role_id filter.",
+            "description": "List users in OneLogin with optional filtering. Use filters to find specific users by email, username, name, role, directory or custom attribute values. Returns user objects with id, email, username, firstname, lastname, status, state, and more. To find a single user by email, use email filter. To find users in a role, use role_id filter. To filter on a custom attribute pass custom_attributes: {\"department\": \"Engineering\"} (or the argument \"custom_attributes.department\": \"Engineering\"); shortnames come from onelogin_list_custom_attributes.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -650,6 +1465,11 @@ impl ToolRegistry {
                         "type": "integer",
                         "description": "Filter to users with a specific role. Get role IDs from onelogin_list_roles."
                     },
+                    "custom_attributes": {
+                        "type": "object",
+                        "description": "Filter by custom attribute values, keyed by shortname, e.g. {\"department\": \"Engineering\", \"cost_center\": \"4200\"}. All must match.",
+                        "additionalProperties": {"type": ["string", "number", "boolean"]}
+                    },
                     "limit": {
                         "type": "integer",
                         "description": "Results per page (default 100, max 200). Use with page for manual pagination."
@@ -669,7 +1489,25 @@ impl ToolRegistry {
                     "max_results": {
                         "type": "integer",
                         "description": "Max total users to return when auto_paginate=true. Stops pagination early once this limit is reached."
-                    }
+                    },
+                    "resume": {
+                        "type": "boolean",
+                        "description": "With auto_paginate=true, continue from the page where the last auto-paginated call with the same filters stopped (saved for 24 hours) instead of page 1. Default: false"
+                    },
+                    "output_path": {
+                        "type": "string",
+                        "description": "Stream every matching user page by page to this file, relative to the export directory (absolute paths and '..' are rejected), instead of returning them inline (max_pages/max_results still apply if given). The response has the path, row count and nextPage if the export stopped early. Use for all-users exports."
+                    },
+                    "output_format": {
+                        "type": "string",
+                        "enum": ["ndjson", "csv", "json"],
+                        "description": "File format for output_path (default: inferred from the file extension, otherwise csv). CSV columns come from the first page."
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace output_path if it already exists (default: false)"
+                    },
+                    "expand": expand_user_property()
                 }
             }
         })
@@ -685,7 +1523,8 @@ impl ToolRegistry {
                     "user_id": {
                         "type": "integer",
                         "description": "The unique numeric user ID (required). Get this from onelogin_list_users or from user events."
-                    }
+                    },
+                    "expand": expand_user_property()
                 },
                 "required": ["user_id"]
             }
@@ -696,54 +1535,14 @@ impl ToolRegistry {
         json!({
             "name": "onelogin_create_user",
             "description": "Create a new user in OneLogin. Supports importing users with hashed passwords using password_algorithm and salt fields.",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
-                    "email": {"type": "string", "description": "User's email address (required)"},
-                    "username": {"type": "string", "description": "Username (required)"},
-                    "firstname": {"type": "string", "description": "First name"},
-                    "lastname": {"type": "string", "description": "Last name"},
-                    "title": {"type": "string", "description": "Job title"},
-                    "department": {"type": "string", "description": "Department"},
-                    "company": {"type": "string", "description": "Company name"},
-                    "phone": {"type": "string", "description": "Phone number (E.164 format)"},
-                    "comment": {"type": "string", "description": "Free text notes about the user"},
-                    "password": {"type": "string", "description": "User's password (cleartext)"},
-                    "password_confirmation": {"type": "string", "description": "Password confirmation (must match password)"},
-                    "password_algorithm": {
-                        "type": "string",
-                        "enum": ["salt+sha256", "sha256+salt", "bcrypt"],
-                        "description": "Hash algorithm for pre-hashed password import: 'salt+sha256' (salt prepended), 'sha256+salt' (salt appended), 'bcrypt'. Requires 'salt' field for SHA256 variants."
-                    },
-                    "salt": {"type": "string", "description": "Salt value used with password_algorithm"},
-                    "state": {
-                        "type": "integer",
-                        "enum": [0, 1, 2, 3],
-                        "description": "User licensing state: 0=Unapproved (pending approval), 1=Approved (licensed), 2=Rejected, 3=Unlicensed. Default is 1 (Approved)."
-                    },
-                    "status": {
-                        "type": "integer",
-                        "enum": [0, 1, 2, 3, 4, 5, 7, 8],
-                        "description": "User login status: 0=Unactivated, 1=Active (can login), 2=Suspended, 3=Locked, 4=Password expired, 5=Awaiting reset, 7=Password pending, 8=Security questions required. Default is 0 (Unactivated)."
-                    },
-                    "directory_id": {"type": "integer", "description": "OneLogin Directory ID"},
-                    "trusted_idp_id": {"type": "integer", "description": "Trusted IDP ID"},
-                    "samaccountname": {"type": "string", "description": "Active Directory username"},
-                    "userprincipalname": {"type": "string", "description": "User principal name"},
-                    "distinguished_name": {"type": "string", "description": "Distinguished name"},
-                    "external_id": {"type": "string", "description": "External directory ID"},
-                    "member_of": {"type": "string", "description": "Directory membership"},
-                    "openid_name": {"type": "string", "description": "OpenID sign-in name"},
-                    "group_id": {"type": "integer", "description": "Group ID to assign"},
-                    "role_ids": {"type": "array", "items": {"type": "integer"}, "description": "List of Role IDs to assign"},
-                    "manager_ad_id": {"type": "string", "description": "Manager's Active Directory ID"},
-                    "manager_user_id": {"type": "integer", "description": "Manager's OneLogin User ID"},
-                    "invalid_login_attempts": {"type": "integer", "description": "Count of failed login attempts"},
-                    "preferred_locale_code": {"type": "string", "description": "2-character language locale (e.g., 'en', 'es')"},
-                    "custom_attributes": {"type": "object", "description": "Custom attribute key-value pairs"}
-                },
-                "required": ["email", "username"]
-            }
+            "inputSchema": input_schema_with::<CreateUserRequest>(
+                json!({
+                    "password_algorithm": {"enum": ["salt+sha256", "sha256+salt", "bcrypt"]},
+                    "status": {"enum": [0, 1, 2, 3, 4, 5, 7, 8]},
+                    "state": {"enum": [0, 1, 2, 3]}
+                }),
+                &[],
+            )
         })
     }
 
@@ -751,63 +1550,17 @@ impl ToolRegistry {
         json!({
             "name": "onelogin_update_user",
             "description": "Update an existing user. Only provide fields you want to change - omitted fields remain unchanged. Note: To update user roles, use onelogin_assign_roles or onelogin_remove_roles instead.",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
+            "inputSchema": input_schema_with::<UpdateUserRequest>(
+                json!({
                     "user_id": {
                         "type": "integer",
                         "description": "The unique ID of the user to update (required). Get this from onelogin_list_users or onelogin_get_user."
                     },
-                    "email": {
-                        "type": "string",
-                        "description": "User's email address. Must be unique within the OneLogin account."
-                    },
-                    "username": {
-                        "type": "string",
-                        "description": "User's username for login. Must be unique within the OneLogin account."
-                    },
-                    "firstname": {
-                        "type": "string",
-                        "description": "User's first name"
-                    },
-                    "lastname": {
-                        "type": "string",
-                        "description": "User's last name"
-                    },
-                    "title": {
-                        "type": "string",
-                        "description": "User's job title (e.g., 'Software Engineer', 'Sales Manager')"
-                    },
-                    "department": {
-                        "type": "string",
-                        "description": "User's department within the organization (e.g., 'Engineering', 'Human Resources')"
-                    },
-                    "company": {
-                        "type": "string",
-                        "description": "User's company or organization name"
-                    },
-                    "phone": {
-                        "type": "string",
-                        "description": "User's phone number. E.164 format recommended (e.g., '+15551234567')"
-                    },
-                    "status": {
-                        "type": "integer",
-                        "enum": [0, 1, 2, 3, 4, 5, 7, 8],
-                        "description": "User login status. Values: 0=Unactivated (never logged in), 1=Active (can log in), 2=Suspended (admin disabled), 3=Locked (too many failed attempts), 4=Password expired, 5=Awaiting password reset, 7=Password pending (hasn't set password), 8=Security questions required. Only status=1 allows login."
-                    },
-                    "state": {
-                        "type": "integer",
-                        "enum": [0, 1, 2, 3],
-                        "description": "User licensing state. Values: 0=Unapproved (pending admin approval), 1=Approved (licensed, normal user), 2=Rejected (denied access), 3=Unlicensed (no license assigned). Note: Changing FROM state=3 requires using onelogin_set_user_state, not this endpoint."
-                    },
-                    "custom_attributes": {
-                        "type": "object",
-                        "description": "Custom attribute key-value pairs. Keys must match custom attributes defined in OneLogin admin console. Values can be strings, numbers, or booleans.",
-                        "additionalProperties": true
-                    }
-                },
-                "required": ["user_id"]
-            }
+                    "status": {"enum": [0, 1, 2, 3, 4, 5, 7, 8]},
+                    "state": {"enum": [0, 1, 2, 3]}
+                }),
+                &["user_id"],
+            )
         })
     }
 
@@ -969,7 +1722,7 @@ impl ToolRegistry {
     fn tool_set_custom_attributes(&self) -> Value {
         json!({
             "name": "onelogin_set_custom_attributes",
-            "description": "Set custom attributes on a user. Custom attributes must be pre-defined in OneLogin Admin > Users > Custom User Fields. This MERGES with existing attributes (does not delete unspecified ones). To see available custom attributes, use onelogin_list_custom_attributes.",
+            "description": "Set custom attributes on a user. Custom attributes must be pre-defined in OneLogin Admin > Users > Custom User Fields. This MERGES with existing attributes (does not delete unspecified ones). Unknown shortnames are rejected with suggestions for close matches. To see available custom attributes, use onelogin_list_custom_attributes.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -989,96 +1742,77 @@ impl ToolRegistry {
     fn tool_list_apps(&self) -> Value {
         json!({
             "name": "onelogin_list_apps",
-            "description": "List all applications (SSO-enabled services) in OneLogin. Returns app id, name, connector_id, visible status, and more. To see which users have access to an app, check role assignments (apps are assigned to roles, roles to users). To see app details including SSO configuration, use onelogin_get_app.",
-            "inputSchema": {
-                "type": "object",
-                "properties": {}
-            }
-        })
-    }
-
-    fn tool_get_app(&self) -> Value {
-        json!({
-            "name": "onelogin_get_app",
-            "description": "Get detailed information about a specific application. Returns full app configuration including name, description, connector_id, icon_url, configuration (SSO settings), parameters, provisioning settings, and role assignments. Use onelogin_list_apps to find app IDs.",
+            "description": "List applications (SSO-enabled services) in OneLogin. Returns app id, name, connector_id, visible status, and more. On large tenants filter by name, connector_id or auth_method and page with limit/page; paged responses include nextPage when more apps may follow. To see which users have access to an app, check role assignments (apps are assigned to roles, roles to users). To see app details including SSO configuration, use onelogin_get_app.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "app_id": {
+                    "name": {
+                        "type": "string",
+                        "description": "Filter by app name; * wildcards are supported, e.g. 'Salesforce*'"
+                    },
+                    "connector_id": {
                         "type": "integer",
-                        "description": "The unique numeric ID of the application (required). Get from onelogin_list_apps."
+                        "description": "Filter by connector. Get connector IDs from onelogin_list_connectors."
+                    },
+                    "auth_method": {
+                        "type": "integer",
+                        "description": "Filter by auth method: 0 Password, 1 OpenId, 2 SAML, 3 API, 4 Google, 6 Forms-based, 7 WS-Fed, 8 OIDC"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Results per page (max 1000). Use with page for manual pagination."
+                    },
+                    "page": {
+                        "type": "integer",
+                        "description": "Page number starting at 1"
+                    },
+                    "sort": {
+                        "type": "string",
+                        "description": "Sort order, e.g. '+name' or '-id'"
                     }
-                },
-                "required": ["app_id"]
+                }
             }
         })
     }
 
-    fn tool_create_app(&self) -> Value {
+    fn tool_get_app(&self) -> Value {
         json!({
-            "name": "onelogin_create_app",
-            "description": "Create a new application (SSO connector instance) in OneLogin. IMPORTANT: You must first get a connector_id from onelogin_list_connectors - connectors are templates (e.g., 'SAML 2.0', 'Salesforce', 'AWS') and apps are instances of those templates. After creation, assign the app to roles using onelogin_set_role_apps to grant user access.",
+            "name": "onelogin_get_app",
+            "description": "Get detailed information about a specific application. Returns full app configuration including name, description, connector_id, icon_url, configuration (SSO settings), parameters, provisioning settings, and role assignments. Use onelogin_list_apps to find app IDs.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
-                    "connector_id": {
+                    "app_id": {
                         "type": "integer",
-                        "description": "The connector template ID (required). Get from onelogin_list_connectors. Common connectors: SAML 2.0, OIDC, AWS, Salesforce, etc."
-                    },
-                    "name": {
-                        "type": "string",
-                        "description": "Display name for the application (required). This appears in the user portal."
-                    },
-                    "description": {
-                        "type": "string",
-                        "description": "Description of the application's purpose (optional)"
-                    },
-                    "visible": {
-                        "type": "boolean",
-                        "description": "Whether the app appears in users' portal. Set to false for backend-only apps. Default: true"
-                    },
-                    "configuration": {
-                        "type": "object",
-                        "description": "Connector-specific settings. Varies by connector type. For SAML: audience, recipient, acs_url. For OIDC: redirect_uri. Check connector documentation for required fields.",
-                        "additionalProperties": true
+                        "description": "The unique numeric ID of the application (required). Get from onelogin_list_apps."
                     }
                 },
-                "required": ["connector_id", "name"]
+                "required": ["app_id"]
             }
         })
     }
 
+    fn tool_create_app(&self) -> Value {
+        json!({
+            "name": "onelogin_create_app",
+            "description": "Create a new application (SSO connector instance) in OneLogin. IMPORTANT: You must first get a connector_id from onelogin_list_connectors - connectors are templates (e.g., 'SAML 2.0', 'Salesforce', 'AWS') and apps are instances of those templates. After creation, assign the app to roles using onelogin_set_role_apps to grant user access.",
+            "inputSchema": input_schema::<CreateAppRequest>()
+        })
+    }
+
     fn tool_update_app(&self) -> Value {
         json!({
             "name": "onelogin_update_app",
             "description": "Update an existing application's settings. Only provide fields you want to change - omitted fields remain unchanged. IMPORTANT: This updates app metadata and configuration only. To change which roles have access to this app, use onelogin_set_role_apps instead.",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
+            "inputSchema": input_schema_with::<UpdateAppRequest>(
+                json!({
                     "app_id": {
                         "type": "integer",
                         "description": "The unique ID of the application to update (required)"
-                    },
-                    "name": {
-                        "type": "string",
-                        "description": "New display name for the application"
-                    },
-                    "description": {
-                        "type": "string",
-                        "description": "New description for the application"
-                    },
-                    "visible": {
-                        "type": "boolean",
-                        "description": "Whether the app appears in users' portal"
-                    },
-                    "configuration": {
-                        "type": "object",
-                        "description": "Updated connector-specific configuration. Only include settings you want to change.",
-                        "additionalProperties": true
                     }
-                },
-                "required": ["app_id"]
-            }
+                }),
+                &["app_id"],
+            )
         })
     }
 
@@ -1103,10 +1837,43 @@ impl ToolRegistry {
     fn tool_list_roles(&self) -> Value {
         json!({
             "name": "onelogin_list_roles",
-            "description": "List all roles in OneLogin. Roles group users and control access to applications. Returns basic role info (id, name). To see apps/users/admins for a role, use onelogin_get_role_apps, onelogin_get_role_users, or onelogin_get_role_admins.",
+            "description": "List roles in OneLogin. Roles group users and control access to applications. Returns basic role info (id, name). Filter by name, app or user and page with limit plus cursor (or page); filtered or paged responses include nextCursor when more roles follow. To see apps/users/admins for a role, use onelogin_get_role_apps, onelogin_get_role_users, or onelogin_get_role_admins.",
             "inputSchema": {
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Filter by role name; * wildcards are supported, e.g. 'Sales*'"
+                    },
+                    "app_id": {
+                        "type": "integer",
+                        "description": "Only roles that grant this app. Get app IDs from onelogin_list_apps."
+                    },
+                    "app_name": {
+                        "type": "string",
+                        "description": "Only roles that grant an app with this name"
+                    },
+                    "user_id": {
+                        "type": "integer",
+                        "description": "Only roles assigned to this user (applied to each returned page)"
+                    },
+                    "fields": {
+                        "type": "string",
+                        "description": "Comma-separated fields to return, e.g. 'id,name,apps'. id is always included."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Results per page (max 650)"
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "nextCursor from a previous call, to fetch the following page"
+                    },
+                    "page": {
+                        "type": "integer",
+                        "description": "Page number starting at 1 (alternative to cursor)"
+                    }
+                }
             }
         })
     }
@@ -1132,37 +1899,23 @@ impl ToolRegistry {
         json!({
             "name": "onelogin_create_role",
             "description": "Create a new role with a name. IMPORTANT: This endpoint ONLY accepts 'name' - you cannot set apps, users, or admins during creation. After creating the role, use: onelogin_set_role_apps to assign apps, onelogin_assign_roles_to_user to assign users, onelogin_add_role_admins to assign admins.",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
-                    "name": {
-                        "type": "string",
-                        "description": "The name of the role to create (required). This is the ONLY field accepted during creation."
-                    }
-                },
-                "required": ["name"]
-            }
+            "inputSchema": input_schema::<CreateRoleRequest>()
         })
     }
 
     fn tool_update_role(&self) -> Value {
         json!({
             "name": "onelogin_update_role",
-            "description": "Update a role's name ONLY. IMPORTANT: This endpoint can ONLY update the 'name' field. It CANNOT modify apps, users, or admins. To manage apps on a role, use onelogin_set_role_apps. To manage users on a role, use onelogin_assign_roles_to_user. To manage admins on a role, use onelogin_add_role_admins or onelogin_remove_role_admin.",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
+            "description": "Update a role's name and description. IMPORTANT: This endpoint CANNOT modify apps, users, or admins. To manage apps on a role, use onelogin_set_role_apps. To manage users on a role, use onelogin_assign_roles_to_user. To manage admins on a role, use onelogin_add_role_admins or onelogin_remove_role_admin.",
+            "inputSchema": input_schema_with::<UpdateRoleRequest>(
+                json!({
                     "role_id": {
                         "type": "integer",
                         "description": "The unique ID of the role to update (required)"
-                    },
-                    "name": {
-                        "type": "string",
-                        "description": "New name for the role. This is the ONLY field that can be updated via this endpoint."
                     }
-                },
-                "required": ["role_id", "name"]
-            }
+                }),
+                &["role_id"],
+            )
         })
     }
 
@@ -1187,10 +1940,27 @@ impl ToolRegistry {
     fn tool_list_groups(&self) -> Value {
         json!({
             "name": "onelogin_list_groups",
-            "description": "List all groups in OneLogin. Groups are typically synced from directory services (AD, LDAP) and used for user organization and User Mappings. IMPORTANT: Groups are different from Roles - groups organize users, while roles control app access. To assign a user to a group, use onelogin_update_user with group_id.",
+            "description": "List all groups in OneLogin. Groups are typically synced from directory services (AD, LDAP) and used for user organization and User Mappings. IMPORTANT: Groups are different from Roles - groups organize users, while roles control app access. To assign a user to a group, use onelogin_update_user with group_id. OneLogin cannot filter groups server-side, so 'name'/'reference' are matched against the cached group list (the response then includes a 'note'). Without filters every page is listed; pass max_pages (50 groups per page) and cursor to page through large tenants.",
             "inputSchema": {
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "max_pages": {
+                        "type": "integer",
+                        "description": "Stop after this many pages and return nextCursor (default: all pages, or 1 with cursor)"
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "nextCursor from a previous call, to continue listing"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Only groups with this name (case-insensitive, * wildcards, e.g. 'sales*')"
+                    },
+                    "reference": {
+                        "type": "string",
+                        "description": "Only groups with this reference (case-insensitive, * wildcards)"
+                    }
+                }
             }
         })
     }
@@ -1198,16 +1968,19 @@ impl ToolRegistry {
     fn tool_get_group(&self) -> Value {
         json!({
             "name": "onelogin_get_group",
-            "description": "Get details about a specific group by ID. Returns group name and reference ID. To see users in a group, use onelogin_list_users with appropriate filters or check user.group_id.",
+            "description": "Get details about a specific group by ID, or by exact name when the ID is unknown (matched against the cached group list, since OneLogin has no group filters; the response then includes a 'note'). Returns group name and reference ID. To see users in a group, use onelogin_list_users with appropriate filters or check user.group_id.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "group_id": {
                         "type": "integer",
-                        "description": "The unique ID of the group (required). Get from onelogin_list_groups."
+                        "description": "The unique ID of the group. Get from onelogin_list_groups."
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Group name (case-insensitive), used when group_id is not given"
                     }
-                },
-                "required": ["group_id"]
+                }
             }
         })
     }
@@ -1591,8 +2364,12 @@ impl ToolRegistry {
                     },
                     "packages": {
                         "type": "object",
-                        "description": "NPM packages available to your function. Format: {\"package\": \"version\"}. Example: {\"axios\": \"1.4.0\", \"lodash\": \"4.17.21\"}",
+                        "description": "NPM packages available to your function, pinned to exact versions. Format: {\"package\": \"version\"}. Example: {\"axios\": \"1.4.0\", \"lodash\": \"4.17.21\"}. Verified against the npm registry before the hook is created.",
                         "additionalProperties": {"type": "string"}
+                    },
+                    "skip_package_check": {
+                        "type": "boolean",
+                        "description": "Skip verifying packages against the npm registry before deploying. Default: false"
                     }
                 },
                 "required": ["type"]
@@ -1627,9 +2404,13 @@ impl ToolRegistry {
                     },
                     "packages": {
                         "type": "object",
-                        "description": "NPM packages available to the function. Format: {\"package-name\": \"version\"} (e.g., {\"lodash\": \"4.17.21\", \"axios\": \"1.4.0\"})",
+                        "description": "NPM packages available to the function, pinned to exact versions. Format: {\"package-name\": \"version\"} (e.g., {\"lodash\": \"4.17.21\", \"axios\": \"1.4.0\"}). Verified against the npm registry before the hook is updated.",
                         "additionalProperties": {"type": "string"}
                     },
+                    "skip_package_check": {
+                        "type": "boolean",
+                        "description": "Skip verifying packages against the npm registry before deploying. Default: false"
+                    },
                     "env_vars": {
                         "type": "array",
                         "items": {"type": "string"},
@@ -1679,7 +2460,7 @@ impl ToolRegistry {
     fn tool_get_smart_hook(&self) -> Value {
         json!({
             "name": "onelogin_get_smart_hook",
-            "description": "Get full details of a Smart Hook including its type, status, function code (base64), runtime, packages, env_vars, and options. Use to inspect configuration or before updates. Function code is returned base64-encoded.",
+            "description": "Get full details of a Smart Hook including its type, status, function code (base64), runtime, packages, env_vars, and options. Use to inspect configuration or before updates. Function code is returned base64-encoded. Env var values are always masked.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -2636,7 +3417,7 @@ impl ToolRegistry {
     fn tool_introspect_oauth_token(&self) -> Value {
         json!({
             "name": "onelogin_introspect_oauth_token",
-            "description": "Introspect an OAuth 2.0 token to check if it's active and get metadata. Returns token validity, scopes, client_id, username, and expiration info.",
+            "description": "Introspect an OAuth 2.0 token to check if it's active and get metadata. Returns token validity, scopes, client_id, username, and expiration info. JWT access tokens are first validated locally against the tenant's cached JWKS (signature, issuer, exp, and aud when audience is given); invalid ones are answered without calling OneLogin. Local validation cannot detect revoked tokens, so a token that passes is confirmed with the remote introspection endpoint unless accept_unchecked_revocation=true. Opaque tokens always use the remote endpoint. 'validated' in the response says which path answered.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -2648,6 +3429,20 @@ impl ToolRegistry {
                         "type": "string",
                         "enum": ["access_token", "refresh_token"],
                         "description": "Hint about the type of token being introspected. Improves performance but is optional."
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["auto", "local", "remote"],
+                        "description": "'auto' (default): local for JWTs, remote otherwise. 'local': JWT validation only, fails for opaque tokens; a valid token is reported with active=null unless accept_unchecked_revocation=true. 'remote': always call the introspection endpoint."
+                    },
+                    "accept_unchecked_revocation": {
+                        "type": "boolean",
+                        "description": "Report a JWT that passes local validation as active without asking OneLogin whether it was revoked. Default false."
+                    },
+                    "audience": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Accepted aud values for local validation (e.g. the OIDC app's client ID). aud is not checked when omitted."
                     }
                 },
                 "required": ["token"]
@@ -2658,7 +3453,7 @@ impl ToolRegistry {
     fn tool_oidc_get_well_known_config(&self) -> Value {
         json!({
             "name": "onelogin_oidc_get_well_known_config",
-            "description": "Get the OpenID Connect discovery document (/.well-known/openid-configuration). Returns issuer, endpoints (authorization, token, userinfo, JWKS), supported scopes, response types, grant types, and signing algorithms.",
+            "description": "Get the OpenID Connect discovery document (/.well-known/openid-configuration). Returns issuer, endpoints (authorization, token, userinfo, JWKS), supported scopes, response types, grant types, and signing algorithms. Served from the cache when possible; pass force_refresh=true to fetch it again.",
             "inputSchema": {
                 "type": "object",
                 "properties": {}
@@ -2669,7 +3464,7 @@ impl ToolRegistry {
     fn tool_oidc_get_jwks(&self) -> Value {
         json!({
             "name": "onelogin_oidc_get_jwks",
-            "description": "Get the JSON Web Key Set (JWKS) containing public keys used to verify JWT signatures from OneLogin. Use these keys to validate ID tokens and access tokens.",
+            "description": "Get the JSON Web Key Set (JWKS) containing public keys used to verify JWT signatures from OneLogin. Use these keys to validate ID tokens and access tokens. Served from the same cache local token introspection uses, which is refreshed when a token names an unknown key id; pass force_refresh=true to fetch it again.",
             "inputSchema": {
                 "type": "object",
                 "properties": {}
@@ -2832,7 +3627,12 @@ impl ToolRegistry {
             "description": "Get the current branding settings for your OneLogin account. Returns logo URLs, colors, custom CSS, login messages, and other branding customizations applied to the login portal.",
             "inputSchema": {
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "locale": {
+                        "type": "string",
+                        "description": "Return texts translated to this locale where available, sent as Accept-Language (e.g. 'fr', 'de-DE')"
+                    }
+                }
             }
         })
     }
@@ -2915,6 +3715,111 @@ impl ToolRegistry {
                     "limit": {
                         "type": "integer",
                         "description": "Max events to return (default 50, max 1000). Example: 100"
+                    },
+                    "since_id": {
+                        "type": "integer",
+                        "description": "Only return events with an id greater than this."
+                    },
+                    "until_id": {
+                        "type": "integer",
+                        "description": "Only return events with an id lower than this."
+                    },
+                    "after_cursor": {
+                        "type": "string",
+                        "description": "Resume from the 'nextCursor' of a previous auto-paginated call."
+                    },
+                    "resume": {
+                        "type": "boolean",
+                        "description": "With auto_paginate=true, continue where the last auto-paginated call with the same filters stopped (saved for 24 hours) instead of the first page. Default: false"
+                    },
+                    "auto_paginate": {
+                        "type": "boolean",
+                        "description": "Follow pagination cursors and return {events, count, pagesFetched, nextCursor} instead of a single page. Default: false"
+                    },
+                    "max_pages": {
+                        "type": "integer",
+                        "description": "Max pages to fetch when auto_paginate=true (default 10)."
+                    },
+                    "output_path": {
+                        "type": "string",
+                        "description": "Stream every matching event page by page to this file, relative to the export directory (absolute paths and '..' are rejected), instead of returning them inline (max_pages still applies if given). The response has the path, row count and nextCursor if the export stopped early. Use for large audit exports."
+                    },
+                    "output_format": {
+                        "type": "string",
+                        "enum": ["ndjson", "csv", "json"],
+                        "description": "File format for output_path (default: inferred from the file extension, otherwise csv). CSV columns come from the first page."
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace output_path if it already exists (default: false)"
+                    }
+                }
+            }
+        })
+    }
+
+    fn tool_get_received_events(&self) -> Value {
+        json!({
+            "name": "onelogin_get_received_events",
+            "description": "Get events pushed to this server by OneLogin's event broadcaster (webhooks), for near-real-time monitoring without polling the API. Requires the webhook receiver (ONELOGIN_WEBHOOK_LISTEN/ONELOGIN_WEBHOOK_SECRET). Events are numbered by arrival; pass the returned 'latestSeq' as since_seq to get only newer ones. Only the most recent events are buffered.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "since_seq": {
+                        "type": "integer",
+                        "description": "Return events received after this sequence number. Default: 0 (all buffered events)"
+                    },
+                    "event_type_id": {
+                        "type": "integer",
+                        "description": "Only events of this type. Example: 6 for failed logins."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Max events to return (default 100)."
+                    }
+                }
+            }
+        })
+    }
+
+    fn tool_get_new_events(&self) -> Value {
+        json!({
+            "name": "onelogin_get_new_events",
+            "description": "Get events that occurred since the last call. A checkpoint (the last event id returned) is saved per tenant and checkpoint name, and survives restarts, so repeated calls never return the same event twice. The first call returns the most recent events and starts the checkpoint. Use a separate checkpoint name per filter combination.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "checkpoint": {
+                        "type": "string",
+                        "description": "Checkpoint name. Default: 'default'. Example: 'failed-logins'"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "First call only: start from events on or after this ISO 8601 date/time instead of the most recent page."
+                    },
+                    "user_id": {
+                        "type": "integer",
+                        "description": "Filter events for a specific user."
+                    },
+                    "event_type_id": {
+                        "type": "integer",
+                        "description": "Filter by event type. Example: 6 for failed logins."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Events per page (default 100, max 1000)."
+                    },
+                    "max_pages": {
+                        "type": "integer",
+                        "description": "Max pages to fetch per call (default 10). If more remain, 'hasMore' is true and the next call continues."
+                    },
+                    "peek": {
+                        "type": "boolean",
+                        "description": "Return new events without advancing the checkpoint. Default: false"
+                    },
+                    "reset": {
+                        "type": "boolean",
+                        "description": "Discard the checkpoint before fetching, as if this were the first call. Default: false"
                     }
                 }
             }
@@ -3093,9 +3998,26 @@ impl ToolRegistry {
         base_params.directory_id = parsed_args.directory_id;
         base_params.role_id = parsed_args.role_id;
         base_params.page = parsed_args.page;
+        base_params.custom_attributes = custom_attribute_filters(args, parsed_args.custom_attributes.as_ref())?;
+        check_custom_attribute_names(&client, base_params.custom_attributes.keys().map(String::as_str)).await?;
 
         debug!("Built query parameters: {:?}", base_params);
 
+        if let Some(output_path) = &parsed_args.output_path {
+            let export = ExportUsersArgs {
+                output_path: output_path.clone(),
+                output_format: parsed_args.output_format.clone(),
+                overwrite: parsed_args.overwrite,
+                anonymize: parsed_args.anonymize,
+                anonymize_salt: parsed_args.anonymize_salt.clone(),
+                drop_fields: parsed_args.drop_fields.clone(),
+                max_pages: parsed_args.max_pages,
+                max_results: parsed_args.max_results,
+                ..Default::default()
+            };
+            return stream_users_to_file(&client, base_params, &export).await;
+        }
+
         if parsed_args.auto_paginate.unwrap_or(false) {
             info!("Auto-pagination enabled for list_users");
             let limit = base_params.limit.unwrap_or(100).clamp(1, 200);
@@ -3109,11 +4031,17 @@ impl ToolRegistry {
 
             debug!("Pagination config: limit={}, max_pages={}, max_results={}", limit, max_pages, max_results);
 
+            let (cursor_key, resume_page) = self.resume_cursor("onelogin_list_users", args);
+            let resume_page = resume_page.and_then(|page| page.parse::<i32>().ok());
+            let first_page = resume_page.or(base_params.page).unwrap_or(1).max(1);
+
+            let mut timed_out = false;
             let (results, pages_fetched, next_page_hint) = {
                 let mut paged_params = base_params.clone();
                 let mut collected: Vec<User> = Vec::new();
                 let mut fetched_pages = 0;
-                let mut current_page = paged_params.page.unwrap_or(1).max(1);
+                let mut current_page = first_page;
+                let deadline = tool_deadline();
 
                 loop {
                     if fetched_pages >= max_pages {
@@ -3126,10 +4054,20 @@ impl ToolRegistry {
 
                     debug!("Fetching page {} with limit {}", current_page, limit);
 
-                    let batch = client
-                        .users
-                        .list_users(Some(paged_params.clone()))
-                        .await
+                    let fetch = client.users.list_users(Some(paged_params.clone()));
+                    let fetched = match deadline {
+                        // Stop at the deadline with what has been collected rather than losing it
+                        Some(deadline) => match tokio::time::timeout_at(deadline, fetch).await {
+                            Ok(fetched) => fetched,
+                            Err(_) => {
+                                warn!("Deadline reached while fetching page {}, returning partial results", current_page);
+                                timed_out = true;
+                                break (collected, fetched_pages, Some(current_page));
+                            }
+                        },
+                        None => fetch.await,
+                    };
+                    let batch = fetched
                         .context(format!(
                             "Failed to list users (page {})\n\
                              Query Parameters:\n\
@@ -3190,12 +4128,27 @@ impl ToolRegistry {
             };
 
             info!("Auto-pagination completed: fetched {} users across {} pages", results.len(), pages_fetched);
-            return Ok(json!({
+            let visited = (first_page..first_page + pages_fetched).map(|p| p.to_string()).collect();
+            self.save_cursor(&cursor_key, "onelogin_list_users", visited, next_page_hint.map(|p| p.to_string()));
+            let mut response = json!({
                 "count": results.len(),
                 "pagesFetched": pages_fetched,
                 "nextPage": next_page_hint,
                 "users": results
-            }));
+            });
+            if let Some(page) = resume_page {
+                response["resumedFromPage"] = json!(page);
+            }
+            if timed_out {
+                response["partial"] = json!(true);
+                response["timedOut"] = json!(true);
+                response["warning"] = json!(format!(
+                    "Tool deadline reached; results are partial. Resume with resume=true or page={}.",
+                    next_page_hint.unwrap_or(1)
+                ));
+            }
+            apply_user_expansion(&client, &mut response, Some("users"), parsed_args.expand.as_deref()).await;
+            return Ok(response);
         }
 
         let params = if base_params == UserQueryParams::default() {
@@ -3231,11 +4184,13 @@ impl ToolRegistry {
             _ => None,
         };
 
-        Ok(json!({
+        let mut response = json!({
             "count": users.len(),
             "nextPage": next_page,
             "users": users
-        }))
+        });
+        apply_user_expansion(&client, &mut response, Some("users"), parsed_args.expand.as_deref()).await;
+        Ok(response)
     }
 
     async fn handle_get_user(&self, args: &Value) -> Result<Value> {
@@ -3244,13 +4199,22 @@ impl ToolRegistry {
             .get("user_id")
             .and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("user_id is required"))?;
+        let expand: Option<Vec<UserExpansion>> = args
+            .get("expand")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .map_err(|e| anyhow!("Invalid expand (expected \"roles\" and/or \"group\"): {}", e))?;
         let result = client.users.get_user(user_id).await;
 
         match result {
-            Ok(user) => Ok(serde_json::to_value(user)?),
-            Err(OneLoginError::NotFound(msg)) => Ok(json!({
+            Ok(user) => {
+                let mut user = serde_json::to_value(user)?;
+                apply_user_expansion(&client, &mut user, None, expand.as_deref()).await;
+                Ok(user)
+            }
+            Err(e) if e.is_not_found() => Ok(json!({
                 "status": "not_found",
-                "message": msg,
+                "message": e.to_string(),
             })),
             Err(e) => Err(anyhow!("Failed to get user: {}", e)),
         }
@@ -3320,12 +4284,32 @@ impl ToolRegistry {
 
     async fn handle_list_apps(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
+        let params: AppQueryParams = serde_json::from_value(json!({
+            "name": args.get("name").and_then(|v| v.as_str()),
+            "connector_id": args.get("connector_id").and_then(value_as_i64),
+            "auth_method": args.get("auth_method").and_then(value_as_i64),
+            "limit": args.get("limit").and_then(value_as_i64),
+            "page": args.get("page").and_then(value_as_i64),
+            "sort": args.get("sort").and_then(|v| v.as_str()),
+        }))
+        .map_err(|e| anyhow!("Invalid arguments: {}", e))?;
+        let paged = params.limit.is_some() || params.page.is_some();
+        let (limit, page) = (params.limit, params.page.unwrap_or(1));
         let apps = client
             .apps
-            .list_apps()
+            .list_apps((params != AppQueryParams::default()).then_some(params))
             .await
             .map_err(|e| anyhow!("Failed to list apps: {}", e))?;
-        Ok(serde_json::to_value(apps)?)
+        if !paged {
+            return Ok(serde_json::to_value(apps)?);
+        }
+        // A full page means more apps may follow
+        let full_page = limit.is_some_and(|l| apps.len() >= l as usize);
+        Ok(json!({
+            "apps": apps,
+            "page": page,
+            "nextPage": full_page.then_some(page + 1)
+        }))
     }
 
     async fn handle_create_role(&self, args: &Value) -> Result<Value> {
@@ -3350,9 +4334,9 @@ impl ToolRegistry {
 
         match result {
             Ok(_) => Ok(json!({"status": "deleted", "role_id": role_id})),
-            Err(OneLoginError::NotFound(msg)) => Ok(json!({
+            Err(e) if e.is_not_found() => Ok(json!({
                 "status": "not_found",
-                "message": msg,
+                "message": e.to_string(),
             })),
             Err(e) => Err(anyhow!("Failed to delete role: {}", e)),
         }
@@ -3368,9 +4352,9 @@ impl ToolRegistry {
 
         match result {
             Ok(role) => Ok(serde_json::to_value(role)?),
-            Err(OneLoginError::NotFound(msg)) => Ok(json!({
+            Err(e) if e.is_not_found() => Ok(json!({
                 "status": "not_found",
-                "message": msg,
+                "message": e.to_string(),
             })),
             Err(e) => Err(anyhow!("Failed to get role: {}", e)),
         }
@@ -3382,7 +4366,7 @@ impl ToolRegistry {
             .get("role_id")
             .and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("role_id is required"))?;
-        let request = crate::models::roles::UpdateRoleRequest {
+        let request = UpdateRoleRequest {
             name: args
                 .get("name")
                 .and_then(|v| v.as_str().map(|s| s.to_string())),
@@ -3394,9 +4378,9 @@ impl ToolRegistry {
 
         match result {
             Ok(role) => Ok(serde_json::to_value(role)?),
-            Err(OneLoginError::NotFound(msg)) => Ok(json!({
+            Err(e) if e.is_not_found() => Ok(json!({
                 "status": "not_found",
-                "message": msg,
+                "message": e.to_string(),
             })),
             Err(e) => Err(anyhow!("Failed to update role: {}", e)),
         }
@@ -3404,26 +4388,92 @@ impl ToolRegistry {
 
     async fn handle_list_roles(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let roles = client
+        let mut params: RoleQueryParams = serde_json::from_value(json!({
+            "name": args.get("name").and_then(|v| v.as_str()),
+            "app_id": args.get("app_id").and_then(value_as_i64),
+            "app_name": args.get("app_name").and_then(|v| v.as_str()),
+            "fields": args.get("fields").and_then(|v| v.as_str()),
+            "limit": args.get("limit").and_then(value_as_i64),
+            "page": args.get("page").and_then(value_as_i64),
+            "cursor": args.get("cursor").and_then(|v| v.as_str()),
+        }))
+        .map_err(|e| anyhow!("Invalid arguments: {}", e))?;
+        // Roles are matched by id below and in the response, so it is never left out
+        if let Some(fields) = params.fields.as_mut() {
+            if !fields.split(',').any(|f| f.trim() == "id") {
+                *fields = format!("id,{}", fields);
+            }
+        }
+        let user_id = args.get("user_id").and_then(value_as_i64);
+
+        if params == RoleQueryParams::default() && user_id.is_none() {
+            let roles = client
+                .roles
+                .list_roles()
+                .await
+                .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
+            return Ok(serde_json::to_value(roles)?);
+        }
+
+        let page = params.page;
+        let mut result = client
             .roles
-            .list_roles()
+            .list_roles_page(params)
             .await
             .map_err(|e| anyhow!("Failed to list roles: {}", e))?;
-        Ok(serde_json::to_value(roles)?)
+        if let Some(user_id) = user_id {
+            let user_roles = client
+                .users
+                .get_user_roles(user_id)
+                .await
+                .map_err(|e| anyhow!("Failed to get roles for user {}: {}", user_id, e))?;
+            result.roles.retain(|role| user_roles.contains(&role.id));
+        }
+        Ok(json!({
+            "roles": result.roles,
+            "nextCursor": result.next_cursor,
+            "page": page,
+        }))
     }
 
     async fn handle_list_groups(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let groups = client
+        let filter = ["name", "reference"]
+            .into_iter()
+            .find_map(|field| args.get(field).and_then(|v| v.as_str()).map(|pattern| (field, pattern)));
+        if let Some((field, pattern)) = filter {
+            let found = client
+                .groups
+                .find_groups(LookupKey::Filter { field, pattern })
+                .await
+                .map_err(|e| anyhow!("Failed to list groups: {}", e))?;
+            return Ok(json!({"groups": found.items, "note": found.note}));
+        }
+        let cursor = args.get("cursor").and_then(|v| v.as_str()).filter(|c| !c.is_empty());
+        let max_pages = args.get("max_pages").and_then(value_as_i64);
+        if cursor.is_none() && max_pages.is_none() {
+            let groups = client
+                .groups
+                .list_groups()
+                .await
+                .map_err(|e| anyhow!("Failed to list groups: {}", e))?;
+            return Ok(serde_json::to_value(groups)?);
+        }
+        let (groups, pages, next_cursor) = client
             .groups
-            .list_groups()
+            .list_groups_paginated(cursor.map(str::to_string), max_pages.unwrap_or(1).max(1) as usize)
             .await
             .map_err(|e| anyhow!("Failed to list groups: {}", e))?;
-        Ok(serde_json::to_value(groups)?)
+        Ok(json!({
+            "groups": groups,
+            "pages": pages,
+            "nextCursor": next_cursor,
+        }))
     }
 
     async fn handle_create_user(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
+        check_custom_attribute_names(&client, custom_attribute_keys(args)).await?;
         let request =
             serde_json::from_value(args.clone()).map_err(|e| anyhow!("Invalid request: {}", e))?;
         let user = client
@@ -3440,15 +4490,16 @@ impl ToolRegistry {
             .get("user_id")
             .and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("user_id is required"))?;
+        check_custom_attribute_names(&client, custom_attribute_keys(args)).await?;
         let request =
             serde_json::from_value(args.clone()).map_err(|e| anyhow!("Invalid request: {}", e))?;
         let result = client.users.update_user(user_id, request).await;
 
         match result {
             Ok(user) => Ok(serde_json::to_value(user)?),
-            Err(OneLoginError::NotFound(msg)) => Ok(json!({
+            Err(e) if e.is_not_found() => Ok(json!({
                 "status": "not_found",
-                "message": msg,
+                "message": e.to_string(),
             })),
             Err(e) => Err(anyhow!("Failed to update user: {}", e)),
         }
@@ -3464,9 +4515,9 @@ impl ToolRegistry {
 
         match result {
             Ok(_) => Ok(json!({"status": "deleted", "user_id": user_id})),
-            Err(OneLoginError::NotFound(msg)) => Ok(json!({
+            Err(e) if e.is_not_found() => Ok(json!({
                 "status": "not_found",
-                "message": msg,
+                "message": e.to_string(),
             })),
             Err(e) => Err(anyhow!("Failed to delete user: {}", e)),
         }
@@ -3479,7 +4530,7 @@ impl ToolRegistry {
             .list_privileges()
             .await
             .map_err(|e| {
-                if e.to_string().contains("Permission denied") || e.to_string().contains("403") {
+                if e.is_permission_denied() {
                     anyhow!(
                         "Privileges API access denied. This feature requires the 'Manage All' permission or the Delegated Administration add-on. \
                         See: https://developers.onelogin.com/api-docs/2/privileges/overview"
@@ -3494,29 +4545,132 @@ impl ToolRegistry {
 
     async fn handle_list_events(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let params: Option<EventQueryParams> =
-            if args.as_object().map(|o| !o.is_empty()).unwrap_or(false) {
-                Some(
-                    serde_json::from_value(args.clone())
-                        .map_err(|e| anyhow!("Invalid event query: {}", e))?,
-                )
-            } else {
-                None
-            };
+        let parsed: ListEventsArgs = serde_json::from_value(args.clone())
+            .map_err(|e| anyhow!("Invalid event query: {}", e))?;
 
-        let events = client
+        if let Some(output_path) = &parsed.output_path {
+            return stream_events_to_file(&client, &parsed, output_path).await;
+        }
+
+        if !parsed.auto_paginate.unwrap_or(false) {
+            let events = client
+                .events
+                .list_events(Some(parsed.query))
+                .await
+                .map_err(|e| anyhow!("Failed to list events: {}", e))?;
+            return Ok(serde_json::to_value(events)?);
+        }
+
+        let max_pages = parsed.max_pages.unwrap_or(10).max(1);
+        let (cursor_key, resume_cursor) = self.resume_cursor("onelogin_list_events", args);
+        let mut query = parsed.query;
+        if let Some(cursor) = &resume_cursor {
+            query.after_cursor = Some(cursor.clone());
+        }
+        let start = query.after_cursor.clone().unwrap_or_else(|| "start".to_string());
+        let (events, pages_fetched, next_cursor) = client
             .events
-            .list_events(params)
+            .list_events_paginated(query, max_pages)
             .await
             .map_err(|e| anyhow!("Failed to list events: {}", e))?;
-        Ok(serde_json::to_value(events)?)
+        self.save_cursor(&cursor_key, "onelogin_list_events", vec![start], next_cursor.clone());
+        Ok(json!({
+            "count": events.len(),
+            "pagesFetched": pages_fetched,
+            "nextCursor": next_cursor,
+            "resumedFromCursor": resume_cursor,
+            "events": events
+        }))
     }
 
-    async fn handle_list_custom_attributes(&self, args: &Value) -> Result<Value> {
+    async fn handle_get_new_events(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let attributes = client
-            .custom_attributes
-            .list_custom_attributes()
+        let parsed: GetNewEventsArgs = serde_json::from_value(args.clone())
+            .map_err(|e| anyhow!("Invalid arguments: {}", e))?;
+        let tenant = self.tenant_name(args);
+        let name = parsed.checkpoint.clone().unwrap_or_else(|| "default".to_string());
+
+        if parsed.reset {
+            self.checkpoints.reset(&tenant, &name)?;
+        }
+        let previous = self.checkpoints.get(&tenant, &name);
+
+        let mut query = EventQueryParams {
+            user_id: parsed.user_id,
+            event_type_id: parsed.event_type_id,
+            limit: Some(parsed.limit.unwrap_or(100).clamp(1, 1000)),
+            ..Default::default()
+        };
+        let since_id = previous.as_ref().map(|c| c.last_event_id);
+        let fetched = if since_id.is_some() || parsed.since.is_some() {
+            // Oldest first, so stopping at max_pages never skips events below the new checkpoint
+            query.since_id = since_id;
+            query.since = if since_id.is_none() { parsed.since.clone() } else { None };
+            query.sort = Some("+id".to_string());
+            let max_pages = parsed.max_pages.unwrap_or(10).max(1);
+            client.events.list_events_paginated(query, max_pages).await
+        } else {
+            // No checkpoint yet: the most recent page starts the stream
+            client.events.list_events_page(&query).await.map(|(events, _)| (events, 1, None))
+        };
+        let (mut events, pages_fetched, next_cursor) =
+            fetched.map_err(|e| anyhow!("Failed to list events: {}", e))?;
+
+        if let Some(since_id) = since_id {
+            events.retain(|e| e.id > since_id);
+        }
+        events.sort_by_key(|e| e.id);
+
+        let newest = events.last();
+        if let (Some(newest), false) = (newest, parsed.peek) {
+            // The events are still returned; a failed save only means they may be seen again
+            if let Err(e) = self.checkpoints.advance(&tenant, &name, newest.id, newest.created_at.clone()) {
+                warn!("Failed to save event checkpoint {}: {:#}", name, e);
+            }
+        }
+        let checkpoint = if parsed.peek { since_id } else { newest.map(|e| e.id).or(since_id) };
+
+        Ok(json!({
+            "count": events.len(),
+            "checkpoint": name,
+            "previousEventId": since_id,
+            "lastEventId": checkpoint,
+            "pagesFetched": pages_fetched,
+            "hasMore": next_cursor.is_some(),
+            "events": events
+        }))
+    }
+
+    fn handle_get_received_events(&self, args: &Value) -> Result<Value> {
+        let webhooks = self.webhooks.as_ref().ok_or_else(|| {
+            anyhow!(
+                "The webhook receiver is not running. Set ONELOGIN_WEBHOOK_LISTEN (e.g. 0.0.0.0:8787) and ONELOGIN_WEBHOOK_SECRET, then point a OneLogin event broadcaster at it."
+            )
+        })?;
+        let since_seq = args.get("since_seq").and_then(value_as_i64).unwrap_or(0).max(0) as u64;
+        let limit = args.get("limit").and_then(value_as_i64).unwrap_or(100).max(1) as usize;
+        let event_type_id = args.get("event_type_id").and_then(value_as_i64);
+
+        let events: Vec<_> = webhooks
+            .since(since_seq, usize::MAX)
+            .into_iter()
+            .filter(|e| event_type_id.is_none() || e.event.get("event_type_id").and_then(value_as_i64) == event_type_id)
+            .take(limit)
+            .collect();
+        let latest_seq = events.last().map(|e| e.seq).unwrap_or(since_seq);
+        Ok(json!({
+            "count": events.len(),
+            "latestSeq": latest_seq,
+            "receiver": webhooks.status(),
+            "events": events
+        }))
+    }
+
+    async fn handle_list_custom_attributes(&self, args: &Value) -> Result<Value> {
+        let client = self.resolve_client(args)?;
+        let attributes = client
+            .custom_attributes
+            .list_custom_attributes()
             .await
             .map_err(|e| anyhow!("Failed to list custom attributes: {}", e))?;
         Ok(serde_json::to_value(attributes)?)
@@ -3534,9 +4688,10 @@ impl ToolRegistry {
 
     async fn handle_get_branding_settings(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
+        let locale = locale_arg(args);
         let branding = client
             .branding
-            .get_branding_settings()
+            .get_branding_settings(locale)
             .await
             .map_err(|e| anyhow!("Failed to get branding settings: {}", e))?;
         Ok(serde_json::to_value(branding)?)
@@ -3546,7 +4701,7 @@ impl ToolRegistry {
         let client = self.resolve_client(args)?;
         let config = client
             .oidc
-            .get_well_known_configuration()
+            .get_well_known_configuration_cached()
             .await
             .map_err(|e| anyhow!("Failed to get OIDC configuration: {}", e))?;
         Ok(serde_json::to_value(config)?)
@@ -3556,7 +4711,7 @@ impl ToolRegistry {
         let client = self.resolve_client(args)?;
         let jwks = client
             .oidc
-            .get_jwks()
+            .get_jwks_cached(None)
             .await
             .map_err(|e| anyhow!("Failed to get OIDC JWKS: {}", e))?;
         Ok(serde_json::to_value(jwks)?)
@@ -3607,6 +4762,9 @@ impl ToolRegistry {
         if request.packages.is_none() {
             request.packages = Some(std::collections::HashMap::new());
         }
+        if !args.get("skip_package_check").and_then(|v| v.as_bool()).unwrap_or(false) {
+            verify_hook_packages(request.packages.as_ref().unwrap_or(&HashMap::new())).await?;
+        }
         if request.env_vars.is_none() {
             request.env_vars = Some(Vec::new());
         }
@@ -3664,6 +4822,14 @@ impl ToolRegistry {
             current_hook.function.clone().unwrap_or_default()
         };
 
+        let packages: Option<HashMap<String, String>> =
+            args.get("packages").and_then(|v| serde_json::from_value(v.clone()).ok());
+        if let Some(packages) = &packages {
+            if !args.get("skip_package_check").and_then(|v| v.as_bool()).unwrap_or(false) {
+                verify_hook_packages(packages).await?;
+            }
+        }
+
         // Build full update request with all required fields
         let request = crate::models::smart_hooks::FullUpdateHookRequest {
             hook_type: current_hook.hook_type.clone(),
@@ -3675,7 +4841,7 @@ impl ToolRegistry {
                 .unwrap_or(current_hook.timeout.unwrap_or(1)),
             retries: args.get("retries").and_then(|v| value_as_i64(v)).map(|n| n as i32)
                 .unwrap_or(current_hook.retries.unwrap_or(0)),
-            packages: args.get("packages").and_then(|v| serde_json::from_value(v.clone()).ok())
+            packages: packages
                 .or_else(|| current_hook.packages.clone())
                 .unwrap_or_default(),
             env_vars: args.get("env_vars").and_then(|v| serde_json::from_value(v.clone()).ok())
@@ -3744,16 +4910,32 @@ impl ToolRegistry {
 
     async fn handle_get_group(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let group_id: i64 = args
-            .get("group_id")
-            .and_then(|v| value_as_i64(v))
-            .ok_or_else(|| anyhow!("group_id is required"))?;
-        let group = client
+        let key = match (args.get("group_id").and_then(value_as_i64), args.get("name").and_then(|v| v.as_str())) {
+            (Some(group_id), _) => LookupKey::Id(group_id),
+            (None, Some(name)) => LookupKey::Filter { field: "name", pattern: name },
+            (None, None) => return Err(anyhow!("group_id or name is required")),
+        };
+        let found = client
             .groups
-            .get_group(group_id)
+            .find_groups(key)
             .await
             .map_err(|e| anyhow!("Failed to get group: {}", e))?;
-        Ok(serde_json::to_value(group)?)
+        let Some(note) = found.note else {
+            return Ok(serde_json::to_value(&found.items[0])?);
+        };
+        match found.items.len() {
+            0 => Err(anyhow!("No group named '{}'", args["name"].as_str().unwrap_or_default())),
+            1 => {
+                let mut group = serde_json::to_value(&found.items[0])?;
+                group["note"] = json!(note);
+                Ok(group)
+            }
+            n => Ok(json!({
+                "error": format!("{} groups match this name; pass group_id", n),
+                "matches": found.items,
+                "note": note
+            })),
+        }
     }
 
     async fn handle_create_group(&self, args: &Value) -> Result<Value> {
@@ -3940,6 +5122,7 @@ impl ToolRegistry {
             .get("user_id")
             .and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("user_id is required"))?;
+        check_custom_attribute_names(&client, custom_attribute_keys(args)).await?;
         let request = serde_json::from_value(args.clone())
             .map_err(|e| anyhow!("Invalid request: {}", e))?;
         client
@@ -3961,7 +5144,7 @@ impl ToolRegistry {
     }
 
     fn handle_privilege_error(e: crate::core::error::OneLoginError, action: &str) -> anyhow::Error {
-        if e.to_string().contains("Permission denied") || e.to_string().contains("403") {
+        if e.is_permission_denied() {
             Self::privileges_access_denied_error()
         } else {
             anyhow!("Failed to {}: {}", action, e)
@@ -4430,6 +5613,7 @@ impl ToolRegistry {
 
     async fn handle_create_user_mapping(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
+        check_custom_attribute_names(&client, mapping_custom_attribute_keys(args)).await?;
         let request = serde_json::from_value(args.clone())
             .map_err(|e| anyhow!("Invalid request: {}", e))?;
         let mapping = client
@@ -4446,6 +5630,7 @@ impl ToolRegistry {
             .get("mapping_id")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("mapping_id is required"))?;
+        check_custom_attribute_names(&client, mapping_custom_attribute_keys(args)).await?;
         let request = serde_json::from_value(args.clone())
             .map_err(|e| anyhow!("Invalid request: {}", e))?;
         let mapping = client
@@ -4563,7 +5748,56 @@ impl ToolRegistry {
     }
 
     async fn handle_introspect_oauth_token(&self, args: &Value) -> Result<Value> {
+        use crate::utils::jwt::{introspect_locally, key_id, looks_like_jwt};
+
         let client = self.resolve_client(args)?;
+        let token = args
+            .get("token")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("token is required"))?;
+        let mode = args.get("mode").and_then(|v| v.as_str()).unwrap_or("auto");
+        let is_jwt = looks_like_jwt(token);
+        let local = match mode {
+            "remote" => false,
+            "local" if !is_jwt => return Err(anyhow!("Token is not a JWT and cannot be validated locally; use mode 'remote'")),
+            "local" => true,
+            "auto" => is_jwt && args.get("token_type_hint").and_then(|v| v.as_str()) != Some("refresh_token"),
+            other => return Err(anyhow!("Unknown mode '{}': expected auto, local or remote", other)),
+        };
+        if local {
+            let audience: Vec<String> = match args.get("audience") {
+                Some(Value::String(aud)) => vec![aud.clone()],
+                Some(value) => serde_json::from_value(value.clone()).map_err(|e| anyhow!("Invalid audience: {}", e))?,
+                None => Vec::new(),
+            };
+            let accept_unchecked_revocation = args
+                .get("accept_unchecked_revocation")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let issuer = client
+                .oidc
+                .get_well_known_configuration_cached()
+                .await
+                .map_err(|e| anyhow!("Failed to get OIDC configuration: {}", e))?
+                .issuer;
+            let jwks = client
+                .oidc
+                .get_jwks_cached(key_id(token).as_deref())
+                .await
+                .map_err(|e| anyhow!("Failed to get OIDC JWKS: {}", e))?;
+            match introspect_locally(token, &jwks, &issuer, &audience, accept_unchecked_revocation) {
+                // A token that passes locally may still be revoked: unless the caller accepts
+                // that, auto mode lets OneLogin confirm it
+                Ok(result) if mode == "auto" && result["active"].is_null() => {
+                    debug!("Token is valid locally, confirming revocation status remotely")
+                }
+                Ok(result) => return Ok(result),
+                Err(e) if mode == "local" => return Err(anyhow!("Local validation failed: {}", e)),
+                // e.g. an HS256 token or a key outside the JWKS: let OneLogin decide
+                Err(e) => debug!("Falling back to remote introspection: {}", e),
+            }
+        }
+
         let request = serde_json::from_value(args.clone())
             .map_err(|e| anyhow!("Invalid request: {}", e))?;
         let introspection = client
@@ -4571,7 +5805,9 @@ impl ToolRegistry {
             .introspect_token(request)
             .await
             .map_err(|e| anyhow!("Failed to introspect OAuth token: {}", e))?;
-        Ok(serde_json::to_value(introspection)?)
+        let mut result = serde_json::to_value(introspection)?;
+        result["validated"] = json!("remote");
+        Ok(result)
     }
 
     // ==================== EMBED TOKENS OPERATIONS ====================
@@ -5427,13 +6663,17 @@ impl ToolRegistry {
     fn tool_list_message_templates(&self) -> Value {
         json!({
             "name": "onelogin_list_message_templates",
-            "description": "List all message templates for a brand",
+            "description": "List all message templates for a brand. Pass locale to list only the templates in that language (plus locale-independent ones) on multi-locale tenants.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "brand_id": {
                         "type": "integer",
                         "description": "The brand ID"
+                    },
+                    "locale": {
+                        "type": "string",
+                        "description": "Only templates in this locale (e.g. 'en', 'fr-CA'); also sent as Accept-Language"
                     }
                 },
                 "required": ["brand_id"]
@@ -5455,6 +6695,10 @@ impl ToolRegistry {
                     "template_id": {
                         "type": "integer",
                         "description": "The template ID"
+                    },
+                    "locale": {
+                        "type": "string",
+                        "description": "Preferred locale, sent as Accept-Language"
                     }
                 },
                 "required": ["brand_id", "template_id"]
@@ -5465,7 +6709,7 @@ impl ToolRegistry {
     fn tool_get_template_by_type(&self) -> Value {
         json!({
             "name": "onelogin_get_template_by_type",
-            "description": "Get a message template by type",
+            "description": "Get a message template by type, optionally in a specific locale",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -5476,6 +6720,10 @@ impl ToolRegistry {
                     "template_type": {
                         "type": "string",
                         "description": "The template type (e.g., 'email_invitation', 'email_password_reset')"
+                    },
+                    "locale": {
+                        "type": "string",
+                        "description": "Get the translation for this locale (e.g. 'es'); same as onelogin_get_template_by_locale"
                     }
                 },
                 "required": ["brand_id", "template_type"]
@@ -5636,7 +6884,7 @@ impl ToolRegistry {
             .get("brand_id")
             .and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("brand_id is required"))?;
-        let templates = client.branding.list_message_templates(brand_id).await
+        let templates = client.branding.list_message_templates(brand_id, locale_arg(args)).await
             .map_err(|e| anyhow!("Failed to list message templates: {}", e))?;
         Ok(serde_json::to_value(templates)?)
     }
@@ -5651,7 +6899,7 @@ impl ToolRegistry {
             .get("template_id")
             .and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("template_id is required"))?;
-        let template = client.branding.get_message_template(brand_id, template_id).await
+        let template = client.branding.get_message_template(brand_id, template_id, locale_arg(args)).await
             .map_err(|e| anyhow!("Failed to get message template: {}", e))?;
         Ok(serde_json::to_value(template)?)
     }
@@ -5666,7 +6914,7 @@ impl ToolRegistry {
             .get("template_type")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("template_type is required"))?;
-        let template = client.branding.get_template_by_type(brand_id, template_type).await
+        let template = client.branding.get_template_by_type(brand_id, template_type, locale_arg(args)).await
             .map_err(|e| anyhow!("Failed to get template by type: {}", e))?;
         Ok(serde_json::to_value(template)?)
     }
@@ -5825,12 +7073,12 @@ impl ToolRegistry {
                     "domain_whitelist": {
                         "type": "array",
                         "items": {"type": "string"},
-                        "description": "Allowed email domains"
+                        "description": "Allowed email domains, as bare domain names (e.g. 'example.com', without '@')"
                     },
                     "domain_blacklist": {
                         "type": "array",
                         "items": {"type": "string"},
-                        "description": "Blocked email domains"
+                        "description": "Blocked email domains, same format; a domain can't be in both lists"
                     },
                     "helpdesk_message": {
                         "type": "string",
@@ -5857,11 +7105,19 @@ impl ToolRegistry {
                     "url": {"type": "string"},
                     "enabled": {"type": "boolean"},
                     "moderated": {"type": "boolean"},
-                    "email_verification_type": {"type": "string"},
+                    "email_verification_type": {"type": "string", "enum": ["email", "sms", "none"]},
                     "default_role_id": {"type": "integer"},
                     "default_group_id": {"type": "integer"},
-                    "domain_whitelist": {"type": "array", "items": {"type": "string"}},
-                    "domain_blacklist": {"type": "array", "items": {"type": "string"}},
+                    "domain_whitelist": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Replaces the allowed email domains; bare domain names (e.g. 'example.com')"
+                    },
+                    "domain_blacklist": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Replaces the blocked email domains, same format"
+                    },
                     "helpdesk_message": {"type": "string"}
                 },
                 "required": ["profile_id"]
@@ -6050,7 +7306,7 @@ impl ToolRegistry {
     fn tool_run_report(&self) -> Value {
         json!({
             "name": "onelogin_run_report",
-            "description": "Run a report and return results",
+            "description": "Run a report and return results. By default waits for the report job to complete by polling its status; set wait=false to return the job immediately and fetch results later with onelogin_get_report_results.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -6066,6 +7322,31 @@ impl ToolRegistry {
                         "type": "string",
                         "enum": ["json", "csv"],
                         "description": "Output format (default: json)"
+                    },
+                    "wait": {
+                        "type": "boolean",
+                        "description": "Poll until the report job completes (default: true)"
+                    },
+                    "poll_interval_seconds": {
+                        "type": "integer",
+                        "description": "Seconds between status checks while waiting (default: 2)"
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "description": "Maximum seconds to wait for completion (default: 120)"
+                    },
+                    "output_path": {
+                        "type": "string",
+                        "description": "Write the report rows to this file, relative to the export directory, instead of returning them inline. Absolute paths and '..' are rejected. The response contains the file path and row count."
+                    },
+                    "output_format": {
+                        "type": "string",
+                        "enum": ["csv", "json", "ndjson"],
+                        "description": "File format for output_path (default: inferred from the file extension, otherwise csv)"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace output_path if it already exists (default: false)"
                     }
                 },
                 "required": ["report_id"]
@@ -6087,6 +7368,31 @@ impl ToolRegistry {
                     "job_id": {
                         "type": "string",
                         "description": "The job ID from a run_report call"
+                    },
+                    "wait": {
+                        "type": "boolean",
+                        "description": "Poll until the report job completes (default: false)"
+                    },
+                    "poll_interval_seconds": {
+                        "type": "integer",
+                        "description": "Seconds between status checks while waiting (default: 2)"
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "description": "Maximum seconds to wait for completion (default: 120)"
+                    },
+                    "output_path": {
+                        "type": "string",
+                        "description": "Write the report rows to this file, relative to the export directory, instead of returning them inline. Absolute paths and '..' are rejected. The response contains the file path and row count."
+                    },
+                    "output_format": {
+                        "type": "string",
+                        "enum": ["csv", "json", "ndjson"],
+                        "description": "File format for output_path (default: inferred from the file extension, otherwise csv)"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace output_path if it already exists (default: false)"
                     }
                 },
                 "required": ["report_id", "job_id"]
@@ -6126,9 +7432,15 @@ impl ToolRegistry {
             } else {
                 None
             };
-        let job = client.reports.run_report(report_id, request).await
-            .map_err(|e| anyhow!("Failed to run report: {}", e))?;
-        Ok(serde_json::to_value(job)?)
+        let wait = args.get("wait").and_then(|v| v.as_bool()).unwrap_or(true);
+        let job = if wait {
+            let (poll_interval, timeout) = report_poll_settings(args);
+            client.reports.run_report_and_wait(report_id, request, poll_interval, timeout).await
+        } else {
+            client.reports.run_report(report_id, request).await
+        }
+        .map_err(|e| anyhow!("Failed to run report: {}", e))?;
+        export_report_job(job, args)
     }
 
     async fn handle_get_report_results(&self, args: &Value) -> Result<Value> {
@@ -6141,9 +7453,15 @@ impl ToolRegistry {
             .get("job_id")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("job_id is required"))?;
-        let job = client.reports.get_report_results(report_id, job_id).await
-            .map_err(|e| anyhow!("Failed to get report results: {}", e))?;
-        Ok(serde_json::to_value(job)?)
+        let wait = args.get("wait").and_then(|v| v.as_bool()).unwrap_or(false);
+        let job = if wait {
+            let (poll_interval, timeout) = report_poll_settings(args);
+            client.reports.wait_for_results(report_id, job_id, poll_interval, timeout).await
+        } else {
+            client.reports.get_report_results(report_id, job_id).await
+        }
+        .map_err(|e| anyhow!("Failed to get report results: {}", e))?;
+        export_report_job(job, args)
     }
 
     // ==================== LOGIN/SESSION API ====================
@@ -6213,7 +7531,7 @@ impl ToolRegistry {
     fn tool_create_session(&self) -> Value {
         json!({
             "name": "onelogin_create_session",
-            "description": "Create a browser session from a session token. This converts the session token from login into an actual browser session cookie. Note: This endpoint is on the main domain, not the API domain.",
+            "description": "Create a browser session from a session token. Returns an auto-submitting HTML form that posts the session token from the user's browser to the tenant's session endpoint, so OneLogin sets the session cookie in that browser without CORS configuration. The server does not submit the token itself. Session tokens are single-use and expire after a few minutes.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -6286,13 +7604,10 @@ impl ToolRegistry {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow!("session_token is required"))?;
 
-        let request = crate::models::login::CreateSessionRequest {
-            session_token: session_token.to_string(),
-        };
-
-        let response = client.login.create_session(request).await
-            .map_err(|e| anyhow!("Failed to create session: {}", e))?;
-        Ok(serde_json::to_value(response)?)
+        Ok(json!({
+            "browser_form": client.login.session_form_html(session_token),
+            "message": "Serve this form to the user's browser; it submits the session token to OneLogin and sets the session cookie."
+        }))
     }
 
     // ==================== CONNECTORS API ====================
@@ -6431,43 +7746,6 @@ impl ToolRegistry {
         Ok(serde_json::to_value(response)?)
     }
 
-    // ===== RATE LIMITS API =====
-    fn tool_get_rate_limit_status(&self) -> Value {
-        json!({
-            "name": "onelogin_get_rate_limit_status",
-            "description": "Get current API rate limit status including remaining requests and reset time",
-            "inputSchema": {
-                "type": "object",
-                "properties": {}
-            }
-        })
-    }
-
-    fn tool_get_rate_limits(&self) -> Value {
-        json!({
-            "name": "onelogin_get_rate_limits",
-            "description": "Get rate limit configuration for the account",
-            "inputSchema": {
-                "type": "object",
-                "properties": {}
-            }
-        })
-    }
-
-    async fn handle_get_rate_limit_status(&self, args: &Value) -> Result<Value> {
-        let client = self.resolve_client(args)?;
-        let result = client.rate_limits.get_rate_limit_status().await
-            .map_err(|e| anyhow!("Failed to get rate limit status: {}", e))?;
-        Ok(serde_json::to_value(result)?)
-    }
-
-    async fn handle_get_rate_limits(&self, args: &Value) -> Result<Value> {
-        let client = self.resolve_client(args)?;
-        let result = client.rate_limits.get_rate_limits().await
-            .map_err(|e| anyhow!("Failed to get rate limits: {}", e))?;
-        Ok(serde_json::to_value(result)?)
-    }
-
     // ===== ACCOUNT SETTINGS API =====
     fn tool_get_account_settings(&self) -> Value {
         json!({
@@ -6539,6 +7817,22 @@ impl ToolRegistry {
         })
     }
 
+    fn tool_get_account_overview(&self) -> Value {
+        json!({
+            "name": "onelogin_get_account_overview",
+            "description": "Get an overview of the OneLogin account: subdomain, region, account settings (when available), usage derived from recent events (active users, logins, app launches, MFA verifications, failed logins), and the current API rate limit window. Usage figures are computed from an event sample because OneLogin has no public usage endpoint; see the 'notes' field for caveats.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "days": {
+                        "type": "integer",
+                        "description": "Number of days of events to summarize (default: 7, max: 90)"
+                    }
+                }
+            }
+        })
+    }
+
     async fn handle_get_account_settings(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
         let result = client.account.get_account_settings().await
@@ -6582,192 +7876,11 @@ impl ToolRegistry {
         Ok(serde_json::to_value(result)?)
     }
 
-    // ===== PASSWORD POLICIES API =====
-    fn tool_list_password_policies(&self) -> Value {
-        json!({
-            "name": "onelogin_list_password_policies",
-            "description": "List all password policies",
-            "inputSchema": {
-                "type": "object",
-                "properties": {}
-            }
-        })
-    }
-
-    fn tool_get_password_policy(&self) -> Value {
-        json!({
-            "name": "onelogin_get_password_policy",
-            "description": "Get a specific password policy by ID",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
-                    "policy_id": {
-                        "type": "integer",
-                        "description": "The password policy ID"
-                    }
-                },
-                "required": ["policy_id"]
-            }
-        })
-    }
-
-    fn tool_create_password_policy(&self) -> Value {
-        json!({
-            "name": "onelogin_create_password_policy",
-            "description": "Create a new password policy",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
-                    "name": {
-                        "type": "string",
-                        "description": "Policy name"
-                    },
-                    "min_length": {
-                        "type": "integer",
-                        "description": "Minimum password length"
-                    },
-                    "max_length": {
-                        "type": "integer",
-                        "description": "Maximum password length"
-                    },
-                    "require_uppercase": {
-                        "type": "boolean",
-                        "description": "Require uppercase letters"
-                    },
-                    "require_lowercase": {
-                        "type": "boolean",
-                        "description": "Require lowercase letters"
-                    },
-                    "require_numbers": {
-                        "type": "boolean",
-                        "description": "Require numbers"
-                    },
-                    "require_symbols": {
-                        "type": "boolean",
-                        "description": "Require special characters"
-                    },
-                    "password_expiration_days": {
-                        "type": "integer",
-                        "description": "Days until password expires"
-                    },
-                    "password_history_count": {
-                        "type": "integer",
-                        "description": "Number of previous passwords to remember"
-                    }
-                },
-                "required": ["name"]
-            }
-        })
-    }
-
-    fn tool_update_password_policy(&self) -> Value {
-        json!({
-            "name": "onelogin_update_password_policy",
-            "description": "Update an existing password policy",
-            "inputSchema": {
-                "type": "object",
-                "properties": {
-                    "policy_id": {
-                        "type": "integer",
-                        "description": "The password policy ID"
-                    },
-                    "name": {
-                        "type": "string",
-                        "description": "Policy name"
-                    },
-                    "min_length": {
-                        "type": "integer",
-                        "description": "Minimum password length"
-                    },
-                    "max_length": {
-                        "type": "integer",
-                        "description": "Maximum password length"
-                    },
-                    "require_uppercase": {
-                        "type": "boolean",
-                        "description": "Require uppercase letters"
-                    },
-                    "require_lowercase": {
-                        "type": "boolean",
-                        "description": "Require lowercase letters"
-                    },
-                    "require_numbers": {
-                        "type": "boolean",
-                        "description": "Require numbers"
-                    },
-                    "require_symbols": {
-                        "type": "boolean",
-                        "description": "Require special characters"
-                    },
-                    "password_expiration_days": {
-                        "type": "integer",
-                        "description": "Days until password expires"
-                    },
-                    "password_history_count": {
-                        "type": "integer",
-                        "description": "Number of previous passwords to remember"
-                    }
-                },
-                "required": ["policy_id"]
-            }
-        })
-    }
-
-    async fn handle_list_password_policies(&self, args: &Value) -> Result<Value> {
-        let client = self.resolve_client(args)?;
-        let result = client.password_policies.list_password_policies().await
-            .map_err(|e| anyhow!("Failed to list password policies: {}", e))?;
-        Ok(serde_json::to_value(result)?)
-    }
-
-    async fn handle_get_password_policy(&self, args: &Value) -> Result<Value> {
-        let client = self.resolve_client(args)?;
-        let policy_id = args.get("policy_id").and_then(|v| value_as_i64(v))
-            .ok_or_else(|| anyhow!("policy_id is required"))?;
-        let result = client.password_policies.get_password_policy(policy_id).await
-            .map_err(|e| anyhow!("Failed to get password policy: {}", e))?;
-        Ok(serde_json::to_value(result)?)
-    }
-
-    async fn handle_create_password_policy(&self, args: &Value) -> Result<Value> {
-        let client = self.resolve_client(args)?;
-        let name = args.get("name").and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("name is required"))?;
-        let request = crate::models::password_policies::CreatePasswordPolicyRequest {
-            name: name.to_string(),
-            min_length: args.get("min_length").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            require_uppercase: args.get("require_uppercase").and_then(|v| v.as_bool()),
-            require_lowercase: args.get("require_lowercase").and_then(|v| v.as_bool()),
-            require_numbers: args.get("require_numbers").and_then(|v| v.as_bool()),
-            require_special_chars: args.get("require_special_chars").and_then(|v| v.as_bool()),
-            password_history: args.get("password_history").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            expiration_days: args.get("expiration_days").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            max_failed_attempts: args.get("max_failed_attempts").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            lockout_duration_minutes: args.get("lockout_duration_minutes").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-        };
-        let result = client.password_policies.create_password_policy(request).await
-            .map_err(|e| anyhow!("Failed to create password policy: {}", e))?;
-        Ok(serde_json::to_value(result)?)
-    }
-
-    async fn handle_update_password_policy(&self, args: &Value) -> Result<Value> {
+    async fn handle_get_account_overview(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let policy_id = args.get("policy_id").and_then(|v| value_as_i64(v))
-            .ok_or_else(|| anyhow!("policy_id is required"))?;
-        let request = crate::models::password_policies::UpdatePasswordPolicyRequest {
-            name: args.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()),
-            min_length: args.get("min_length").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            require_uppercase: args.get("require_uppercase").and_then(|v| v.as_bool()),
-            require_lowercase: args.get("require_lowercase").and_then(|v| v.as_bool()),
-            require_numbers: args.get("require_numbers").and_then(|v| v.as_bool()),
-            require_special_chars: args.get("require_special_chars").and_then(|v| v.as_bool()),
-            password_history: args.get("password_history").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            expiration_days: args.get("expiration_days").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            max_failed_attempts: args.get("max_failed_attempts").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-            lockout_duration_minutes: args.get("lockout_duration_minutes").and_then(|v| value_as_i64(v)).map(|v| v as i32),
-        };
-        let result = client.password_policies.update_password_policy(policy_id, request).await
-            .map_err(|e| anyhow!("Failed to update password policy: {}", e))?;
+        let days = args.get("days").and_then(value_as_i64).unwrap_or(7).clamp(1, 90);
+        let result = client.account.get_account_overview(days).await
+            .map_err(|e| anyhow!("Failed to get account overview: {}", e))?;
         Ok(serde_json::to_value(result)?)
     }
 
@@ -6894,9 +8007,13 @@ impl ToolRegistry {
                         "type": "string",
                         "description": "Filter by device type"
                     },
-                    "status": {
-                        "type": "string",
-                        "description": "Filter by status"
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of devices to return"
+                    },
+                    "page": {
+                        "type": "integer",
+                        "description": "Page number for pagination"
                     }
                 }
             }
@@ -6911,7 +8028,7 @@ impl ToolRegistry {
                 "type": "object",
                 "properties": {
                     "device_id": {
-                        "type": "integer",
+                        "type": "string",
                         "description": "The device ID"
                     }
                 },
@@ -6933,7 +8050,8 @@ impl ToolRegistry {
                     },
                     "device_type": {
                         "type": "string",
-                        "description": "Type of device (e.g., mobile, desktop)"
+                        "enum": ["desktop", "mobile", "tablet"],
+                        "description": "Type of device"
                     },
                     "device_name": {
                         "type": "string",
@@ -6942,9 +8060,13 @@ impl ToolRegistry {
                     "platform": {
                         "type": "string",
                         "description": "Platform (e.g., iOS, Android, Windows)"
+                    },
+                    "browser": {
+                        "type": "string",
+                        "description": "Browser name (e.g., Chrome, Safari)"
                     }
                 },
-                "required": ["user_id", "device_type"]
+                "required": ["user_id", "device_name"]
             }
         })
     }
@@ -6957,16 +8079,16 @@ impl ToolRegistry {
                 "type": "object",
                 "properties": {
                     "device_id": {
-                        "type": "integer",
+                        "type": "string",
                         "description": "The device ID"
                     },
                     "device_name": {
                         "type": "string",
                         "description": "Updated device name"
                     },
-                    "status": {
+                    "trust_level": {
                         "type": "string",
-                        "description": "Updated status"
+                        "description": "Updated trust level"
                     }
                 },
                 "required": ["device_id"]
@@ -6982,7 +8104,7 @@ impl ToolRegistry {
                 "type": "object",
                 "properties": {
                     "device_id": {
-                        "type": "integer",
+                        "type": "string",
                         "description": "The device ID to remove"
                     }
                 },
@@ -7006,9 +8128,9 @@ impl ToolRegistry {
 
     async fn handle_get_device(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let device_id = args.get("device_id").and_then(|v| v.as_str())
+        let device_id = args.get("device_id").and_then(value_as_id_string)
             .ok_or_else(|| anyhow!("device_id is required"))?;
-        let result = client.device_trust.get_device(device_id).await
+        let result = client.device_trust.get_device(&device_id).await
             .map_err(|e| anyhow!("Failed to get device: {}", e))?;
         Ok(serde_json::to_value(result)?)
     }
@@ -7033,22 +8155,22 @@ impl ToolRegistry {
 
     async fn handle_update_device(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let device_id = args.get("device_id").and_then(|v| v.as_str())
+        let device_id = args.get("device_id").and_then(value_as_id_string)
             .ok_or_else(|| anyhow!("device_id is required"))?;
         let request = crate::models::device_trust::UpdateDeviceRequest {
             device_name: args.get("device_name").and_then(|v| v.as_str()).map(|s| s.to_string()),
             trust_level: args.get("trust_level").and_then(|v| v.as_str()).map(|s| s.to_string()),
         };
-        let result = client.device_trust.update_device(device_id, request).await
+        let result = client.device_trust.update_device(&device_id, request).await
             .map_err(|e| anyhow!("Failed to update device: {}", e))?;
         Ok(serde_json::to_value(result)?)
     }
 
     async fn handle_delete_device(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
-        let device_id = args.get("device_id").and_then(|v| v.as_str())
+        let device_id = args.get("device_id").and_then(value_as_id_string)
             .ok_or_else(|| anyhow!("device_id is required"))?;
-        client.device_trust.delete_device(device_id).await
+        client.device_trust.delete_device(&device_id).await
             .map_err(|e| anyhow!("Failed to delete device: {}", e))?;
         Ok(json!({"success": true}))
     }
@@ -7093,17 +8215,25 @@ impl ToolRegistry {
                         "type": "string",
                         "description": "Login page name"
                     },
-                    "html": {
+                    "html_content": {
                         "type": "string",
-                        "description": "HTML content for the login page"
+                        "description": "HTML content for the login page (max 256 KB)"
                     },
-                    "css": {
+                    "css_content": {
                         "type": "string",
-                        "description": "CSS styles for the login page"
+                        "description": "CSS styles for the login page (max 128 KB)"
                     },
-                    "javascript": {
+                    "javascript_content": {
                         "type": "string",
-                        "description": "JavaScript for the login page"
+                        "description": "JavaScript for the login page (max 128 KB)"
+                    },
+                    "subdomain": {
+                        "type": "string",
+                        "description": "Subdomain the login page is served on"
+                    },
+                    "enabled": {
+                        "type": "boolean",
+                        "description": "Whether the login page is enabled"
                     }
                 },
                 "required": ["name"]
@@ -7126,19 +8256,27 @@ impl ToolRegistry {
                         "type": "string",
                         "description": "Login page name"
                     },
-                    "html": {
+                    "html_content": {
                         "type": "string",
-                        "description": "HTML content for the login page"
+                        "description": "HTML content for the login page (max 256 KB)"
                     },
-                    "css": {
+                    "css_content": {
                         "type": "string",
-                        "description": "CSS styles for the login page"
+                        "description": "CSS styles for the login page (max 128 KB)"
                     },
-                    "javascript": {
+                    "javascript_content": {
                         "type": "string",
-                        "description": "JavaScript for the login page"
-                    }
-                },
+                        "description": "JavaScript for the login page (max 128 KB)"
+                    },
+                    "subdomain": {
+                        "type": "string",
+                        "description": "Subdomain the login page is served on"
+                    },
+                    "enabled": {
+                        "type": "boolean",
+                        "description": "Whether the login page is enabled"
+                    }
+                },
                 "required": ["page_id"]
             }
         })
@@ -7161,6 +8299,23 @@ impl ToolRegistry {
         })
     }
 
+    fn tool_get_login_page_preview_url(&self) -> Value {
+        json!({
+            "name": "onelogin_get_login_page_preview_url",
+            "description": "Get the preview URL for a custom login page so changes can be reviewed in a browser before enabling the page",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "page_id": {
+                        "type": "integer",
+                        "description": "The login page ID"
+                    }
+                },
+                "required": ["page_id"]
+            }
+        })
+    }
+
     async fn handle_list_login_pages(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
         let result = client.login_pages.list_login_pages().await
@@ -7220,6 +8375,17 @@ impl ToolRegistry {
         Ok(json!({"success": true}))
     }
 
+    async fn handle_get_login_page_preview_url(&self, args: &Value) -> Result<Value> {
+        let client = self.resolve_client(args)?;
+        let page_id = args.get("page_id").and_then(value_as_i64)
+            .ok_or_else(|| anyhow!("page_id is required"))?;
+        match client.login_pages.get_login_page_preview_url(page_id).await {
+            Ok(preview) => Ok(serde_json::to_value(preview)?),
+            Err(e) if e.is_not_found() => Ok(json!({"status": "not_found", "message": e.to_string()})),
+            Err(e) => Err(anyhow!("Failed to get login page preview URL: {}", e)),
+        }
+    }
+
     // ===== TRUSTED IDPS API =====
     fn tool_list_trusted_idps(&self) -> Value {
         json!({
@@ -7262,19 +8428,44 @@ impl ToolRegistry {
                     },
                     "idp_type": {
                         "type": "string",
+                        "enum": ["saml", "oidc"],
                         "description": "Type of IDP (saml, oidc)"
                     },
-                    "issuer_url": {
+                    "enabled": {
+                        "type": "boolean",
+                        "description": "Whether the IDP is enabled (default: true)"
+                    },
+                    "issuer": {
                         "type": "string",
-                        "description": "IDP issuer URL"
+                        "description": "IDP issuer URL / entity ID"
                     },
-                    "sso_url": {
+                    "sso_endpoint": {
                         "type": "string",
-                        "description": "Single sign-on URL"
+                        "description": "SAML single sign-on endpoint URL"
+                    },
+                    "slo_endpoint": {
+                        "type": "string",
+                        "description": "SAML single logout endpoint URL"
                     },
                     "certificate": {
                         "type": "string",
                         "description": "X.509 certificate for SAML"
+                    },
+                    "client_id": {
+                        "type": "string",
+                        "description": "OIDC client ID"
+                    },
+                    "client_secret": {
+                        "type": "string",
+                        "description": "OIDC client secret"
+                    },
+                    "authorization_endpoint": {
+                        "type": "string",
+                        "description": "OIDC authorization endpoint URL"
+                    },
+                    "token_endpoint": {
+                        "type": "string",
+                        "description": "OIDC token endpoint URL"
                     }
                 },
                 "required": ["name", "idp_type"]
@@ -7297,13 +8488,21 @@ impl ToolRegistry {
                         "type": "string",
                         "description": "IDP name"
                     },
-                    "issuer_url": {
+                    "enabled": {
+                        "type": "boolean",
+                        "description": "Enable or disable the IDP"
+                    },
+                    "issuer": {
                         "type": "string",
-                        "description": "IDP issuer URL"
+                        "description": "IDP issuer URL / entity ID"
                     },
-                    "sso_url": {
+                    "sso_endpoint": {
                         "type": "string",
-                        "description": "Single sign-on URL"
+                        "description": "SAML single sign-on endpoint URL"
+                    },
+                    "slo_endpoint": {
+                        "type": "string",
+                        "description": "SAML single logout endpoint URL"
                     },
                     "certificate": {
                         "type": "string",
@@ -7457,23 +8656,23 @@ impl ToolRegistry {
         let client = self.resolve_client(args)?;
         let idp_id = args.get("idp_id").and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("idp_id is required"))?;
-        let result = client.trusted_idps.get_trusted_idp_metadata(idp_id).await
+        let metadata = client.trusted_idps.get_trusted_idp_metadata(idp_id).await
             .map_err(|e| anyhow!("Failed to get trusted IDP metadata: {}", e))?;
-        Ok(serde_json::to_value(result)?)
+        Ok(json!({"idp_id": idp_id, "metadata_xml": metadata}))
     }
 
     async fn handle_update_trusted_idp_metadata(&self, args: &Value) -> Result<Value> {
         let client = self.resolve_client(args)?;
         let idp_id = args.get("idp_id").and_then(|v| value_as_i64(v))
             .ok_or_else(|| anyhow!("idp_id is required"))?;
-        let metadata = args.get("metadata").and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow!("metadata is required"))?;
+        let metadata = args.get("metadata_xml").and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("metadata_xml is required"))?;
         let request = crate::models::trusted_idps::UpdateTrustedIdpMetadataRequest {
             metadata: metadata.to_string(),
         };
-        let result = client.trusted_idps.update_trusted_idp_metadata(idp_id, request).await
+        client.trusted_idps.update_trusted_idp_metadata(idp_id, request).await
             .map_err(|e| anyhow!("Failed to update trusted IDP metadata: {}", e))?;
-        Ok(serde_json::to_value(result)?)
+        Ok(json!({"success": true, "idp_id": idp_id}))
     }
 
     async fn handle_get_trusted_idp_issuer(&self, args: &Value) -> Result<Value> {
@@ -7602,6 +8801,228 @@ impl ToolRegistry {
         })
     }
 
+    fn tool_snapshot_role_memberships(&self) -> Value {
+        json!({
+            "name": "onelogin_snapshot_role_memberships",
+            "description": "Record the current members of every role as a snapshot, stored per tenant and kept across restarts. Schedule it (e.g. @daily in the jobs config) to build a membership history that onelogin_diff_role_snapshots can compare, even after the matching events have rolled off. Returns the snapshot id and size.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "keep": {
+                        "type": "integer",
+                        "description": "Snapshots to keep for this tenant; older ones are deleted (default 90)."
+                    }
+                }
+            }
+        })
+    }
+
+    fn tool_list_role_snapshots(&self) -> Value {
+        json!({
+            "name": "onelogin_list_role_snapshots",
+            "description": "List stored role membership snapshots (oldest first) with their id, time, and role and membership counts.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        })
+    }
+
+    fn tool_diff_role_snapshots(&self) -> Value {
+        json!({
+            "name": "onelogin_diff_role_snapshots",
+            "description": "Compare two role membership snapshots and list, per role, the users added and removed, plus roles created or deleted in between. Answers questions like 'who was added to the Admin role this month': from='2024-06-01', roles=['Admin']. Snapshots are chosen by id, 'latest', or a date (the newest snapshot taken on or before it).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "from": {
+                        "type": "string",
+                        "description": "Earlier snapshot: id, 'latest', or ISO 8601 date/time (required). Example: '2024-06-01'"
+                    },
+                    "to": {
+                        "type": "string",
+                        "description": "Later snapshot, same formats (default 'latest')."
+                    },
+                    "roles": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Only diff these roles, by ID or exact name (case-insensitive). Default: all roles"
+                    }
+                },
+                "required": ["from"]
+            }
+        })
+    }
+
+    async fn handle_snapshot_role_memberships(&self, args: &Value) -> Result<Value> {
+        let client = self.resolve_client(args)?;
+        let tenant = self.tenant_name(args);
+        let keep = args
+            .get("keep")
+            .and_then(value_as_i64)
+            .map(|k| k.max(1) as usize)
+            .unwrap_or(role_snapshots::DEFAULT_KEEP);
+        let memberships = current_role_memberships(&client).await?;
+        let snapshot = RoleSnapshot::new(chrono::Utc::now(), memberships);
+        let summary = snapshot.summary();
+        let kept = self.role_snapshots.add(&tenant, snapshot, keep)?;
+        Ok(json!({"tenant": tenant, "snapshot": summary, "snapshots_kept": kept}))
+    }
+
+    fn handle_diff_role_snapshots(&self, args: &Value) -> Result<Value> {
+        let tenant = self.tenant_name(args);
+        let from = args
+            .get("from")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("from is required (snapshot id, 'latest' or a date)"))?;
+        let to = args.get("to").and_then(|v| v.as_str()).unwrap_or("latest");
+        let roles: Option<Vec<String>> = args
+            .get("roles")
+            .and_then(|v| v.as_array())
+            .map(|roles| roles.iter().filter_map(value_as_id_string).collect());
+        let from = self.role_snapshots.find(&tenant, from)?;
+        let to = self.role_snapshots.find(&tenant, to)?;
+        if from.taken_at > to.taken_at {
+            return Err(anyhow!("'from' snapshot {} is newer than 'to' snapshot {}", from.id, to.id));
+        }
+        let changes = role_snapshots::diff_snapshots(&from, &to, roles.as_deref());
+        Ok(json!({
+            "tenant": tenant,
+            "from": from.summary(),
+            "to": to.summary(),
+            "roles_changed": changes.len(),
+            "changes": changes
+        }))
+    }
+
+    fn tool_privilege_escalation_report(&self) -> Value {
+        json!({
+            "name": "onelogin_privilege_escalation_report",
+            "description": "Security review of newly granted high-privilege access: compares current role memberships with a stored role snapshot and lists every user added to a high-privilege role since, with the admin events (who granted it, and when) found for each grant. High-privilege roles are roles named like 'admin' and roles holding a delegated administration privilege, or the roles given in sensitive_roles. Grants without a matching event were made outside the event window or not logged and deserve a closer look. Needs a baseline from onelogin_snapshot_role_memberships. Read-only.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "baseline": {
+                        "type": "string",
+                        "description": "Snapshot to compare against: id, 'latest', or ISO 8601 date/time (default 'latest')."
+                    },
+                    "sensitive_roles": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "High-privilege roles by ID or exact name (case-insensitive), replacing the name-based default. Roles holding a privilege are always included."
+                    },
+                    "max_event_pages": {
+                        "type": "integer",
+                        "description": "Event pages scanned since the baseline to attribute grants (default 10)."
+                    }
+                }
+            }
+        })
+    }
+
+    async fn handle_privilege_escalation_report(&self, args: &Value) -> Result<Value> {
+        let client = self.resolve_client(args)?;
+        let tenant = self.tenant_name(args);
+        let baseline = args.get("baseline").and_then(|v| v.as_str()).unwrap_or("latest");
+        let sensitive_roles: Option<Vec<String>> = args
+            .get("sensitive_roles")
+            .and_then(|v| v.as_array())
+            .map(|roles| roles.iter().filter_map(value_as_id_string).collect());
+        let max_pages = args
+            .get("max_event_pages")
+            .and_then(value_as_i64)
+            .map(|p| p.max(1) as usize)
+            .unwrap_or(10);
+        let baseline = self.role_snapshots.find(&tenant, baseline)?;
+        let current = RoleSnapshot::new(chrono::Utc::now(), current_role_memberships(&client).await?);
+
+        let mut reasons: HashMap<i64, Vec<String>> = HashMap::new();
+        for role in &current.roles {
+            if is_sensitive_role(role.role_id, role.name.as_deref(), sensitive_roles.as_deref()) {
+                let reason = if sensitive_roles.is_some() { "listed in sensitive_roles" } else { "name contains 'admin'" };
+                reasons.entry(role.role_id).or_default().push(reason.to_string());
+            }
+        }
+        let mut warnings = Vec::new();
+        // Delegated administration is a subscription feature; report it as missing rather than failing
+        match client.privileges.list_privileges().await {
+            Ok(privileges) => {
+                for privilege in privileges {
+                    let role_ids = client.privileges.get_assigned_roles(&privilege.id).await
+                        .map_err(|e| anyhow!("Failed to get roles for privilege {}: {}", privilege.id, e))?;
+                    for role_id in role_ids {
+                        reasons.entry(role_id).or_default().push(format!("holds privilege '{}'", privilege.name));
+                    }
+                }
+            }
+            Err(e) => warnings.push(format!("Privileges were not checked: {}", e)),
+        }
+
+        let mut grants = role_snapshots::new_grants(&baseline, &current, &reasons);
+        let params = EventQueryParams {
+            since: Some(baseline.taken_at.clone()),
+            ..Default::default()
+        };
+        let (events, pages, next_cursor) = client.events.list_events_paginated(params, max_pages).await
+            .map_err(|e| anyhow!("Failed to list events: {}", e))?;
+        let role_events: Vec<&crate::models::events::Event> = events
+            .iter()
+            .filter(|e| e.role_id.is_some_and(|id| reasons.contains_key(&id)))
+            .collect();
+        let event_json = |e: &crate::models::events::Event| {
+            json!({
+                "event_id": e.id,
+                "event_type": e.event_type_name,
+                "created_at": e.created_at,
+                "user_id": e.user_id,
+                "role_id": e.role_id,
+                "actor_user_id": e.actor_user_id,
+                "actor_user_name": e.actor_user_name
+            })
+        };
+        let mut unattributed = 0;
+        for grant in &mut grants {
+            let matching: Vec<Value> = role_events
+                .iter()
+                .filter(|e| e.user_id == grant["user_id"].as_i64() && e.role_id == grant["role_id"].as_i64())
+                .map(|e| event_json(e))
+                .collect();
+            if matching.is_empty() {
+                unattributed += 1;
+            }
+            grant["events"] = Value::Array(matching);
+        }
+        // High-privilege role activity not explained by a grant still present, e.g. access granted and revoked again
+        let other_events: Vec<Value> = role_events
+            .iter()
+            .filter(|e| {
+                !grants.iter().any(|g| e.user_id == g["user_id"].as_i64() && e.role_id == g["role_id"].as_i64())
+            })
+            .map(|e| event_json(e))
+            .collect();
+
+        let mut high_privilege_roles: Vec<Value> = current
+            .roles
+            .iter()
+            .filter_map(|r| reasons.get(&r.role_id).map(|why| json!({"id": r.role_id, "name": r.name, "reasons": why})))
+            .collect();
+        high_privilege_roles.sort_by_key(|r| r["id"].as_i64());
+        Ok(json!({
+            "tenant": tenant,
+            "baseline": baseline.summary(),
+            "generated_at": current.taken_at,
+            "high_privilege_roles": high_privilege_roles,
+            "new_grants": grants.len(),
+            "grants_without_events": unattributed,
+            "events_scanned": events.len(),
+            "event_pages": pages,
+            "events_truncated": next_cursor.is_some(),
+            "warnings": warnings,
+            "grants": grants,
+            "other_events": other_events
+        }))
+    }
+
     fn tool_assign_roles_to_user(&self) -> Value {
         json!({
             "name": "onelogin_assign_roles_to_user",
@@ -7764,4 +9185,481 @@ impl ToolRegistry {
         }))
     }
 
+    fn tool_server_stats(&self) -> Value {
+        json!({
+            "name": "onelogin_server_stats",
+            "description": "Live counters of this server's HTTP clients, per tenant: rate limiter (configured requests/second, tokens available now, permits granted, throttled requests, accumulated wait time, 429s received) and the limiter shared by all tenants when GLOBAL_RATE_LIMIT_RPS is set, circuit breaker (closed/open/half_open state, trips, requests rejected while open, failures), response compression, the retry budget shared by all tenants (retries made, skipped for budget or because a POST/PATCH may already have been processed), today's API usage against the daily budget (requests, reads, remaining, refused) and the response cache (entries, writes stored compressed, bytes saved). No OneLogin API call is made. The same counters are exported to Prometheus when ENABLE_METRICS is set.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "section": {
+                        "type": "string",
+                        "enum": ["rate_limiter", "global_rate_limiter", "circuit_breaker", "compression", "retry_budget", "usage", "cache"],
+                        "description": "Only return this section. Default: all sections"
+                    }
+                }
+            }
+        })
+    }
+
+    fn handle_server_stats(&self, args: &Value) -> Result<Value> {
+        let section = args.get("section").and_then(|v| v.as_str());
+        if let Some(section) = section.filter(|s| !["rate_limiter", "global_rate_limiter", "circuit_breaker", "compression", "retry_budget", "usage", "cache"].contains(s)) {
+            return Err(anyhow!("Unknown section '{}'. Expected rate_limiter, global_rate_limiter, circuit_breaker, compression, retry_budget, usage or cache", section));
+        }
+        let only = args.get("tenant").and_then(|v| v.as_str()).filter(|t| !t.is_empty());
+        let mut tenants = serde_json::Map::new();
+        for (name, stats) in self.tenant_manager.client_stats() {
+            if only.is_some_and(|t| t != name) {
+                continue;
+            }
+            let mut stats = serde_json::to_value(stats)?;
+            if let Some(section) = section {
+                stats = json!({ section: stats[section].take() });
+            }
+            tenants.insert(name, stats);
+        }
+        if let Some(tenant) = only.filter(|_| tenants.is_empty()) {
+            return Err(anyhow!("Unknown tenant '{}'", tenant));
+        }
+        Ok(json!({
+            "server_version": env!("CARGO_PKG_VERSION"),
+            "tenants": tenants
+        }))
+    }
+
+    fn tool_explain_tool(&self) -> Value {
+        json!({
+            "name": "onelogin_explain_tool",
+            "description": "Explain one tool of this server before calling it: its full input schema and required arguments, whether it is read-only, writes or is destructive, the least OneLogin API credential scope it needs (Read users < Read All < Manage users < Manage All), whether it calls OneLogin at all, example arguments, related tools, its category, aliases and whether it is enabled. Disabled tools are explained too. No OneLogin API call is made.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tool": {
+                        "type": "string",
+                        "description": "Tool name as listed (prefixed or alias names work too). Example: 'onelogin_delete_user'"
+                    }
+                },
+                "required": ["tool"]
+            }
+        })
+    }
+
+    fn handle_explain_tool(&self, args: &Value) -> Result<Value> {
+        let requested = args
+            .get("tool")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow!("tool is required"))?;
+        let canonical = self.tool_config.resolve_tool_name(requested);
+        let definitions = self.tool_definitions();
+        let Some(definition) = definitions.iter().find(|t| t["name"] == canonical.as_str()) else {
+            let names: Vec<String> = definitions
+                .iter()
+                .filter_map(|t| t["name"].as_str())
+                .map(|name| self.tool_config.exposed_name(name))
+                .collect();
+            let suggestions = suggest::closest(requested, names.iter().map(String::as_str));
+            if suggestions.is_empty() {
+                return Err(anyhow!("Unknown tool '{}'", requested));
+            }
+            return Err(anyhow!("Unknown tool '{}' (did you mean {}?)", requested, suggestions.join(", ")));
+        };
+
+        let category = TOOL_CATEGORIES.iter().find(|c| c.tools.contains(&canonical.as_str()));
+        let catalog: Vec<&str> = definitions.iter().filter_map(|t| t["name"].as_str()).collect();
+        let description = definition["description"].as_str().unwrap_or_default();
+        let related: Vec<Value> = explain::related_tools(&canonical, description, category.map_or(&[][..], |c| c.tools), &catalog)
+            .into_iter()
+            .map(|name| json!({"name": self.tool_config.exposed_name(name), "enabled": self.tool_config.is_tool_enabled(name)}))
+            .collect();
+        let aliases: Vec<String> = self
+            .tool_config
+            .aliases()
+            .into_iter()
+            .filter(|(_, target)| *target == canonical)
+            .map(|(alias, _)| alias)
+            .collect();
+        let classification = explain::classify(&canonical);
+        let schema = &definition["inputSchema"];
+
+        Ok(json!({
+            "name": self.tool_config.exposed_name(&canonical),
+            "canonical_name": canonical,
+            "aliases": aliases,
+            "category": category.map(|c| c.name),
+            "enabled": self.tool_config.is_tool_enabled(&canonical),
+            "description": self.tool_config.rewrite_tool_references(description),
+            "classification": classification,
+            "read_only": classification == "read_only",
+            "destructive": classification == "destructive",
+            "calls_onelogin_api": !explain::is_local(&canonical),
+            "minimum_api_scope": explain::minimum_api_scope(&canonical, category.map(|c| c.name)),
+            "cooldown_seconds": self.tool_config.tool_cooldown(&canonical).map(|d| d.as_secs()),
+            "required_arguments": schema.get("required").cloned().unwrap_or_else(|| json!([])),
+            "example_arguments": explain::example_arguments(schema),
+            "input_schema": schema,
+            "related_tools": related
+        }))
+    }
+
+    fn tool_get_result_page(&self) -> Value {
+        json!({
+            "name": RESULT_PAGE_TOOL,
+            "description": "Fetch the next part of a tool result that was paginated because it exceeded the configured response token budget. Pass the page_token from that result; keep calling with the returned page_token until it is null. Held results expire after 15 minutes. No OneLogin API call is made.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "page_token": {
+                        "type": "string",
+                        "description": "page_token from the paginated result or the previous page"
+                    }
+                },
+                "required": ["page_token"]
+            }
+        })
+    }
+
+    fn handle_get_result_page(&self, args: &Value) -> Result<Value> {
+        let token = args
+            .get("page_token")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow!("page_token is required"))?;
+        let budget = self.tool_config.token_budget().map(|(budget, _)| budget);
+        self.result_pages.next_page(token, budget).map_err(|e| anyhow!(e))
+    }
+
+    fn tool_validate_tenant_credentials(&self) -> Value {
+        json!({
+            "name": "onelogin_validate_tenant_credentials",
+            "description": "Check OneLogin API credentials before adding a tenant: requests an OAuth token from the tenant and probes whether the credential can read users (Read users scope) and apps (Read All scope). Nothing is saved and the secret is never echoed back. Part of the 'onelogin-onboard-tenant' prompt.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "subdomain": {
+                        "type": "string",
+                        "description": "Tenant subdomain, e.g. 'acme' for acme.onelogin.com. A full URL such as https://acme.eu.onelogin.com also works and sets the region"
+                    },
+                    "region": {
+                        "type": "string",
+                        "enum": ["us", "eu"],
+                        "description": "Tenant region. Default: taken from the subdomain URL, else 'us'"
+                    },
+                    "client_id": {
+                        "type": "string",
+                        "description": "Client ID from Developers > API Credentials"
+                    },
+                    "client_secret": {
+                        "type": "string",
+                        "description": "Client secret of the same credential"
+                    }
+                },
+                "required": ["subdomain", "client_id", "client_secret"]
+            }
+        })
+    }
+
+    /// Subdomain, region and credentials of an onboarding call
+    fn onboarding_target(args: &Value) -> Result<(String, OneLoginRegion, String, String)> {
+        let text = |name: &str| {
+            args.get(name)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("{} is required", name))
+        };
+        let (subdomain, url_region) = onboarding::normalize_subdomain(&text("subdomain")?)?;
+        let region = match args.get("region").and_then(|v| v.as_str()) {
+            Some(name) => {
+                let region = onboarding::parse_region(name)?;
+                if url_region.as_ref().is_some_and(|r| *r != region) {
+                    return Err(anyhow!("region '{}' contradicts the region of the subdomain URL", name));
+                }
+                region
+            }
+            None => url_region.unwrap_or(OneLoginRegion::US),
+        };
+        Ok((subdomain, region, text("client_id")?, text("client_secret")?))
+    }
+
+    async fn handle_validate_tenant_credentials(&self, args: &Value) -> Result<Value> {
+        let (subdomain, region, client_id, client_secret) = Self::onboarding_target(args)?;
+        Ok(onboarding::check_credentials(&subdomain, region, &client_id, &client_secret).await)
+    }
+
+    fn tool_add_tenant(&self) -> Value {
+        json!({
+            "name": "onelogin_add_tenant",
+            "description": "Add a OneLogin tenant to tenants.json (ONELOGIN_TENANTS_CONFIG, else the platform config directory), creating the file if needed. The credentials are checked first and nothing is written if no token can be issued. The file is written atomically and readable by its owner only. Restart the server to load the tenant, then confirm with onelogin_list_tenants. Part of the 'onelogin-onboard-tenant' prompt.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Tenant name used as the 'tenant' argument of other tools, e.g. 'prod' (letters, digits, '-' or '_')"
+                    },
+                    "subdomain": {
+                        "type": "string",
+                        "description": "Tenant subdomain, e.g. 'acme' for acme.onelogin.com, or the full URL"
+                    },
+                    "region": {
+                        "type": "string",
+                        "enum": ["us", "eu"],
+                        "description": "Tenant region. Default: taken from the subdomain URL, else 'us'"
+                    },
+                    "client_id": {
+                        "type": "string",
+                        "description": "Client ID from Developers > API Credentials"
+                    },
+                    "client_secret": {
+                        "type": "string",
+                        "description": "Client secret of the same credential"
+                    },
+                    "default": {
+                        "type": "boolean",
+                        "description": "Make this the default tenant (clears the flag on the others). Default: false"
+                    },
+                    "rate_limit_rps": {
+                        "type": "integer",
+                        "description": "Requests per second for this tenant, overriding RATE_LIMIT_RPS"
+                    },
+                    "daily_request_budget": {
+                        "type": "integer",
+                        "description": "OneLogin API requests allowed per UTC day for this tenant (0 disables)"
+                    },
+                    "fallback_base_url": {
+                        "type": "string",
+                        "description": "Secondary base URL to fail over to"
+                    },
+                    "replace": {
+                        "type": "boolean",
+                        "description": "Overwrite an existing tenant of the same name. Default: false"
+                    },
+                    "skip_validation": {
+                        "type": "boolean",
+                        "description": "Write without checking the credentials, e.g. when OneLogin isn't reachable from here. Default: false"
+                    }
+                },
+                "required": ["name", "subdomain", "client_id", "client_secret"]
+            }
+        })
+    }
+
+    async fn handle_add_tenant(&self, args: &Value) -> Result<Value> {
+        let name = args.get("name").and_then(|v| v.as_str()).map(str::trim).unwrap_or_default();
+        onboarding::validate_tenant_name(name)?;
+        let (subdomain, region, client_id, client_secret) = Self::onboarding_target(args)?;
+        if args.get("rate_limit_rps").and_then(|v| v.as_u64()) == Some(0) {
+            return Err(anyhow!("rate_limit_rps must be greater than 0"));
+        }
+        let path = Config::tenants_file_path()
+            .ok_or_else(|| anyhow!("Could not determine the tenants.json location; set ONELOGIN_TENANTS_CONFIG"))?;
+
+        let validation = if args.get("skip_validation").and_then(|v| v.as_bool()).unwrap_or(false) {
+            Value::Null
+        } else {
+            let check = onboarding::check_credentials(&subdomain, region.clone(), &client_id, &client_secret).await;
+            if check["valid"] != true {
+                return Err(anyhow!(
+                    "Credentials were not saved: {}",
+                    check["error"].as_str().unwrap_or("validation failed")
+                ));
+            }
+            check
+        };
+
+        let mut entry = json!({
+            "name": name,
+            "subdomain": subdomain,
+            "region": if region == OneLoginRegion::EU { "eu" } else { "us" },
+            "client_id": client_id,
+            "client_secret": client_secret,
+            "default": args.get("default").and_then(|v| v.as_bool()).unwrap_or(false)
+        });
+        for field in ["rate_limit_rps", "daily_request_budget", "fallback_base_url"] {
+            if let Some(value) = args.get(field).filter(|v| !v.is_null()) {
+                entry[field] = value.clone();
+            }
+        }
+        let replace = args.get("replace").and_then(|v| v.as_bool()).unwrap_or(false);
+        let mut result = onboarding::upsert_tenant(&path, entry, replace)?;
+        info!("Added tenant '{}' to {}", name, path.display());
+        result["tenant"] = json!(name);
+        result["validation"] = validation;
+        result["restart_required"] = json!(true);
+        result["next_step"] = json!("Restart the server, then run onelogin_list_tenants to confirm the tenant is loaded");
+        Ok(result)
+    }
+
+    fn tool_set_tool_categories(&self) -> Value {
+        let categories: Vec<&str> = TOOL_CATEGORIES.iter().map(|c| c.name).collect();
+        json!({
+            "name": "onelogin_set_tool_categories",
+            "description": "Enable or disable tool categories in the tool config file (the same file 'config enable/disable' edits) and reload it, so the change applies without a restart. Clients may need to list tools again to see it. Part of the 'onelogin-onboard-tenant' prompt.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "enable": {
+                        "type": "array",
+                        "items": {"type": "string", "enum": categories},
+                        "description": "Categories to enable"
+                    },
+                    "disable": {
+                        "type": "array",
+                        "items": {"type": "string", "enum": categories},
+                        "description": "Categories to disable"
+                    }
+                }
+            }
+        })
+    }
+
+    fn handle_set_tool_categories(&self, args: &Value) -> Result<Value> {
+        let names = |field: &str| -> Result<Vec<String>> {
+            let names: Vec<String> = args
+                .get(field)
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect();
+            for name in &names {
+                if !TOOL_CATEGORIES.iter().any(|c| c.name == name) {
+                    let suggestions = suggest::closest(name, TOOL_CATEGORIES.iter().map(|c| c.name));
+                    if suggestions.is_empty() {
+                        return Err(anyhow!("Unknown category '{}'", name));
+                    }
+                    return Err(anyhow!("Unknown category '{}' (did you mean {}?)", name, suggestions.join(", ")));
+                }
+            }
+            Ok(names)
+        };
+        let (enable, disable) = (names("enable")?, names("disable")?);
+        if enable.is_empty() && disable.is_empty() {
+            return Err(anyhow!("Pass at least one category in enable or disable"));
+        }
+        if let Some(both) = enable.iter().find(|c| disable.contains(c)) {
+            return Err(anyhow!("Category '{}' is in both enable and disable", both));
+        }
+
+        let path = match self.tool_config.config_path() {
+            Some(path) => path.clone(),
+            None => crate::cli::get_config_path()?,
+        };
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+            serde_json::from_str::<ToolConfigFile>(&content)
+                .with_context(|| format!("Refusing to overwrite unparseable config file: {}", path.display()))?;
+        }
+        let mut config = crate::cli::load_config(&path);
+        for (names, enabled) in [(&enable, true), (&disable, false)] {
+            for name in names {
+                config.categories.insert(name.clone(), CategoryConfig::Simple(enabled));
+            }
+        }
+        crate::cli::save_config(&path, &config)?;
+
+        let reloaded = self.tool_config.config_path() == Some(&path);
+        if reloaded {
+            self.tool_config.reload()?;
+        }
+        Ok(json!({
+            "path": path.display().to_string(),
+            "enabled": enable,
+            "disabled": disable,
+            "reloaded": reloaded,
+            "restart_required": !reloaded,
+            "enabled_tool_count": self.tool_config.enabled_count()
+        }))
+    }
+
+    fn tool_list_jobs(&self) -> Value {
+        json!({
+            "name": "onelogin_list_jobs",
+            "description": "List scheduled background jobs from the 'jobs' section of the tool config: name, cron schedule (UTC), tool, next run time and the status of the last run.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        })
+    }
+
+    fn tool_get_job_results(&self) -> Value {
+        json!({
+            "name": "onelogin_get_job_results",
+            "description": "Get the stored results of a scheduled job's most recent runs (newest first), including errors. Use onelogin_list_jobs for job names.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Job name. Example: 'nightly-mfa-report'"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Number of runs to return (default 1)."
+                    }
+                },
+                "required": ["name"]
+            }
+        })
+    }
+
+    fn tool_run_job(&self) -> Value {
+        json!({
+            "name": "onelogin_run_job",
+            "description": "Run a scheduled job immediately, outside its schedule. The result is stored like a scheduled run and returned.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Job name. Example: 'hourly-event-export'"
+                    }
+                },
+                "required": ["name"]
+            }
+        })
+    }
+
+    fn handle_get_job_results(&self, args: &Value) -> Result<Value> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("name is required"))?;
+        let limit = args.get("limit").and_then(value_as_i64).unwrap_or(1).max(1) as usize;
+        let runs = self
+            .jobs
+            .runs(name, limit)
+            .ok_or_else(|| anyhow!("Unknown job '{}'. Use onelogin_list_jobs to see configured jobs.", name))?;
+        Ok(json!({"name": name, "runs": runs}))
+    }
+
+    async fn handle_run_job(&self, args: &Value) -> Result<Value> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("name is required"))?;
+        let run = self.jobs.run_now(name, self).await?;
+        Ok(json!({"name": name, "run": run}))
+    }
+
+}
+
+#[async_trait]
+impl JobRunner for ToolRegistry {
+    async fn run_job(&self, tool: &str, arguments: &Value) -> Result<Value> {
+        let params = super::server::CallToolParams {
+            name: tool.to_string(),
+            arguments: arguments.clone(),
+        };
+        let output = self.call_tool(&params).await?;
+        Ok(serde_json::from_str(&output).unwrap_or(Value::String(output)))
+    }
 }
@@ -0,0 +1,317 @@
+//! WebSocket transport for hosting the server behind a reverse proxy.
+//!
+//! Enabled by `ONELOGIN_WS_LISTEN`; MCP clients are then served over WebSocket
+//! at `ONELOGIN_WS_PATH` (default `/mcp`) instead of stdio. It is also the
+//! transport of the `service` run mode, which requires it. Every text message
+//! carries one JSON-RPC message, and the `mcp` subprotocol is selected when the
+//! client offers it. Clients authenticate with `Authorization: Bearer
+//! <ONELOGIN_WS_TOKEN>` or, for browsers that can't set headers, a `token` query
+//! parameter; the token is required unless the listener is bound to loopback.
+//! `ONELOGIN_WS_ALLOWED_ORIGINS` restricts which web origins may connect; when
+//! it is empty and no token is set, requests from web pages (any `Origin`) are
+//! refused, so a site open in the operator's browser can't reach a loopback
+//! listener.
+//!
+//! TLS is terminated here when `ONELOGIN_WS_TLS_CERT` and `ONELOGIN_WS_TLS_KEY`
+//! name PEM files; leave them unset when a reverse proxy terminates TLS.
+
+use crate::core::admin::authorized;
+use anyhow::{anyhow, Context, Result};
+use secrecy::SecretString;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_rustls::rustls;
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{HeaderValue, StatusCode};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig as ProtocolConfig;
+use tokio_tungstenite::WebSocketStream;
+use tracing::{debug, info, warn};
+
+pub const LISTEN_ENV_VAR: &str = "ONELOGIN_WS_LISTEN";
+pub const PATH_ENV_VAR: &str = "ONELOGIN_WS_PATH";
+pub const TOKEN_ENV_VAR: &str = "ONELOGIN_WS_TOKEN";
+pub const ORIGINS_ENV_VAR: &str = "ONELOGIN_WS_ALLOWED_ORIGINS";
+pub const TLS_CERT_ENV_VAR: &str = "ONELOGIN_WS_TLS_CERT";
+pub const TLS_KEY_ENV_VAR: &str = "ONELOGIN_WS_TLS_KEY";
+
+const DEFAULT_PATH: &str = "/mcp";
+const SUBPROTOCOL: &str = "mcp";
+const MIN_TOKEN_LEN: usize = 16;
+/// Largest message accepted, matching the stdio frame limit
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+/// Deadline for the TLS and WebSocket handshakes of a new connection
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct WebSocketConfig {
+    pub listen: SocketAddr,
+    pub path: String,
+    pub token: Option<SecretString>,
+    /// Allowed `Origin` values; empty allows any when a token is set, and none otherwise
+    pub allowed_origins: Vec<String>,
+    /// PEM certificate chain and private key when TLS is terminated here
+    pub tls: Option<(PathBuf, PathBuf)>,
+}
+
+impl WebSocketConfig {
+    /// Read WebSocket settings from the environment. `None` when `ONELOGIN_WS_LISTEN` is unset.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(listen) = std::env::var(LISTEN_ENV_VAR) else {
+            return Ok(None);
+        };
+        let listen: SocketAddr = listen
+            .parse()
+            .with_context(|| format!("Invalid {}: expected host:port, e.g. 127.0.0.1:8765", LISTEN_ENV_VAR))?;
+        let path = std::env::var(PATH_ENV_VAR).unwrap_or_else(|_| DEFAULT_PATH.to_string());
+        if !path.starts_with('/') {
+            return Err(anyhow!("{} must start with '/'", PATH_ENV_VAR));
+        }
+
+        let token = std::env::var(TOKEN_ENV_VAR).ok().map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        match &token {
+            Some(token) if token.len() < MIN_TOKEN_LEN => {
+                return Err(anyhow!("{} must be at least {} characters", TOKEN_ENV_VAR, MIN_TOKEN_LEN));
+            }
+            None if !listen.ip().is_loopback() => {
+                return Err(anyhow!(
+                    "{} binds {}, which is reachable from other hosts; set {} or bind 127.0.0.1 behind a reverse proxy",
+                    LISTEN_ENV_VAR,
+                    listen,
+                    TOKEN_ENV_VAR
+                ));
+            }
+            _ => {}
+        }
+
+        let allowed_origins = std::env::var(ORIGINS_ENV_VAR)
+            .map(|v| v.split(',').map(|o| o.trim().trim_end_matches('/').to_string()).filter(|o| !o.is_empty()).collect())
+            .unwrap_or_default();
+
+        let tls = match (std::env::var(TLS_CERT_ENV_VAR), std::env::var(TLS_KEY_ENV_VAR)) {
+            (Ok(cert), Ok(key)) => Some((PathBuf::from(cert), PathBuf::from(key))),
+            (Err(_), Err(_)) => None,
+            _ => return Err(anyhow!("Set both {} and {} to terminate TLS", TLS_CERT_ENV_VAR, TLS_KEY_ENV_VAR)),
+        };
+
+        Ok(Some(Self {
+            listen,
+            path,
+            token: token.map(SecretString::new),
+            allowed_origins,
+            tls,
+        }))
+    }
+}
+
+/// Byte stream of a connection, plain TCP or TLS
+pub trait Io: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Io for T {}
+
+pub type Socket = WebSocketStream<Box<dyn Io>>;
+
+/// A client that completed the handshake
+pub struct Session {
+    pub socket: Socket,
+    pub peer: SocketAddr,
+}
+
+fn tls_acceptor(cert: &Path, key: &Path) -> Result<TlsAcceptor> {
+    let mut cert_reader = std::io::BufReader::new(
+        std::fs::File::open(cert).with_context(|| format!("Failed to open TLS certificate {}", cert.display()))?,
+    );
+    let certs: Vec<rustls::Certificate> = rustls_pemfile::certs(&mut cert_reader)
+        .with_context(|| format!("Failed to parse TLS certificate {}", cert.display()))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+    if certs.is_empty() {
+        return Err(anyhow!("No certificate found in {}", cert.display()));
+    }
+
+    let mut key_reader = std::io::BufReader::new(
+        std::fs::File::open(key).with_context(|| format!("Failed to open TLS key {}", key.display()))?,
+    );
+    let key = rustls_pemfile::read_all(&mut key_reader)
+        .with_context(|| format!("Failed to parse TLS key {}", key.display()))?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(k) | rustls_pemfile::Item::RSAKey(k) | rustls_pemfile::Item::ECKey(k) => {
+                Some(rustls::PrivateKey(k))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("No private key found in {}", key.display()))?;
+
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("Invalid TLS certificate or key")?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Decide whether to accept an upgrade request: the subprotocol to select, or
+/// the HTTP status and reason to refuse with
+fn check_request(
+    config: &WebSocketConfig,
+    target: &str,
+    authorization: Option<&str>,
+    origin: Option<&str>,
+    protocols: Option<&str>,
+) -> std::result::Result<Option<&'static str>, (StatusCode, &'static str)> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != config.path {
+        return Err((StatusCode::NOT_FOUND, "Not found"));
+    }
+    if let Some(token) = &config.token {
+        let from_query = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == "token")
+            .map(|(_, value)| format!("Bearer {}", value));
+        if !authorized(authorization.or(from_query.as_deref()), token) {
+            return Err((StatusCode::UNAUTHORIZED, "Missing or invalid token"));
+        }
+    }
+    if let Some(origin) = origin {
+        let allowed = if config.allowed_origins.is_empty() {
+            // Without a token, a browser page could connect to a loopback listener on the operator's behalf
+            config.token.is_some()
+        } else {
+            config.allowed_origins.iter().any(|allowed| allowed == origin.trim_end_matches('/'))
+        };
+        if !allowed {
+            return Err((StatusCode::FORBIDDEN, "Origin not allowed"));
+        }
+    }
+    let offers_mcp = protocols.is_some_and(|p| p.split(',').any(|p| p.trim() == SUBPROTOCOL));
+    Ok(offers_mcp.then_some(SUBPROTOCOL))
+}
+
+/// Bind the listener and hand every client that completes the handshake to the returned channel
+pub async fn start(config: WebSocketConfig) -> Result<mpsc::Receiver<Session>> {
+    let tls = config.tls.as_ref().map(|(cert, key)| tls_acceptor(cert, key)).transpose()?;
+    let listener = TcpListener::bind(config.listen)
+        .await
+        .with_context(|| format!("Failed to bind WebSocket transport on {}", config.listen))?;
+    info!(
+        "MCP WebSocket transport listening on {}://{}{}",
+        if tls.is_some() { "wss" } else { "ws" },
+        config.listen,
+        config.path
+    );
+
+    let (tx, rx) = mpsc::channel(16);
+    let config = Arc::new(config);
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let (config, tls, tx) = (config.clone(), tls.clone(), tx.clone());
+                    tokio::spawn(async move {
+                        let stream: Box<dyn Io> = match tls {
+                            Some(tls) => match tokio::time::timeout(HANDSHAKE_TIMEOUT, tls.accept(stream)).await {
+                                Ok(Ok(stream)) => Box::new(stream),
+                                Ok(Err(e)) => {
+                                    debug!("TLS handshake with {} failed: {}", peer, e);
+                                    return;
+                                }
+                                Err(_) => {
+                                    debug!("TLS handshake with {} timed out", peer);
+                                    return;
+                                }
+                            },
+                            None => Box::new(stream),
+                        };
+                        match tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake(stream, &config)).await {
+                            Ok(Ok(socket)) => {
+                                let _ = tx.send(Session { socket, peer }).await;
+                            }
+                            Ok(Err(e)) => debug!("WebSocket handshake with {} failed: {}", peer, e),
+                            Err(_) => debug!("WebSocket handshake with {} timed out", peer),
+                        }
+                    });
+                }
+                Err(e) => warn!("WebSocket accept failed: {}", e),
+            }
+        }
+    });
+    Ok(rx)
+}
+
+// tungstenite's handshake callback must return its full ErrorResponse
+#[allow(clippy::result_large_err)]
+async fn handshake(stream: Box<dyn Io>, config: &WebSocketConfig) -> Result<Socket> {
+    let callback = |request: &Request, mut response: Response| -> std::result::Result<Response, ErrorResponse> {
+        let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok());
+        let target = request.uri().path_and_query().map_or("/", |p| p.as_str());
+        match check_request(config, target, header("authorization"), header("origin"), header("sec-websocket-protocol")) {
+            Ok(protocol) => {
+                if let Some(protocol) = protocol {
+                    response.headers_mut().insert("sec-websocket-protocol", HeaderValue::from_static(protocol));
+                }
+                Ok(response)
+            }
+            Err((status, reason)) => {
+                let mut error = ErrorResponse::new(Some(reason.to_string()));
+                *error.status_mut() = status;
+                Err(error)
+            }
+        }
+    };
+    let protocol = ProtocolConfig {
+        max_message_size: Some(MAX_MESSAGE_BYTES),
+        max_frame_size: Some(MAX_MESSAGE_BYTES),
+        ..Default::default()
+    };
+    Ok(tokio_tungstenite::accept_hdr_async_with_config(stream, callback, Some(protocol)).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(token: Option<&str>, origins: &[&str]) -> WebSocketConfig {
+        WebSocketConfig {
+            listen: "127.0.0.1:8765".parse().unwrap(),
+            path: DEFAULT_PATH.to_string(),
+            token: token.map(|t| SecretString::new(t.to_string())),
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            tls: None,
+        }
+    }
+
+    #[test]
+    fn test_check_request() {
+        let open = config(None, &[]);
+        assert_eq!(check_request(&open, "/mcp", None, None, None), Ok(None));
+        assert_eq!(check_request(&open, "/mcp", None, None, Some("json, mcp")), Ok(Some("mcp")));
+        assert_eq!(check_request(&open, "/other", None, None, None).unwrap_err().0, StatusCode::NOT_FOUND);
+        assert_eq!(
+            check_request(&open, "/mcp", None, Some("https://evil.example"), None).unwrap_err().0,
+            StatusCode::FORBIDDEN
+        );
+
+        let token = "0123456789abcdef";
+        let guarded = config(Some(token), &["https://app.example.com"]);
+        assert_eq!(check_request(&guarded, "/mcp", None, None, None).unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert!(check_request(&guarded, "/mcp", Some("Bearer 0123456789abcdef"), None, None).is_ok());
+        assert!(check_request(&guarded, "/mcp?token=0123456789abcdef", None, Some("https://app.example.com/"), None).is_ok());
+        assert_eq!(
+            check_request(&guarded, "/mcp?token=0123456789abcdef", None, Some("https://evil.example"), None).unwrap_err().0,
+            StatusCode::FORBIDDEN
+        );
+
+        let any_origin = config(Some(token), &[]);
+        assert!(check_request(&any_origin, "/mcp", Some("Bearer 0123456789abcdef"), Some("https://app.example.com"), None).is_ok());
+        assert_eq!(
+            check_request(&any_origin, "/mcp", None, Some("https://app.example.com"), None).unwrap_err().0,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Outbound stdio writer.
+//!
+//! Responses and notifications are queued on a bounded channel and written by a
+//! dedicated task, so a large tool result never holds up the request loop and
+//! frames are never interleaved. The queue is bounded both by message count and
+//! by the bytes it holds: senders wait once `MAX_QUEUED_BYTES` are pending, which
+//! pushes back on producers when the client reads slowly.
+
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Frames that may wait in the queue
+const QUEUE_CAPACITY: usize = 64;
+
+/// Bytes that may wait in the queue before senders block
+const MAX_QUEUED_BYTES: usize = 8 * 1024 * 1024;
+
+/// Frames at least this large are logged with the queue depth when written
+const LARGE_FRAME_BYTES: usize = 1024 * 1024;
+
+struct OutboundFrame {
+    bytes: Vec<u8>,
+    /// Returns the frame's share of the byte budget once written
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Handle for queueing encoded frames; cheap to clone
+#[derive(Clone)]
+pub struct FrameSender {
+    tx: mpsc::Sender<OutboundFrame>,
+    budget: Arc<Semaphore>,
+    queued_bytes: Arc<AtomicUsize>,
+}
+
+impl FrameSender {
+    /// Queue an encoded frame, waiting while the queue is full. A frame larger
+    /// than the whole budget waits for the queue to drain and is then sent alone.
+    pub async fn send(&self, bytes: Vec<u8>) -> Result<()> {
+        let permits = bytes.len().clamp(1, MAX_QUEUED_BYTES) as u32;
+        let permit = self
+            .budget
+            .clone()
+            .acquire_many_owned(permits)
+            .await
+            .map_err(|_| anyhow!("Output writer has stopped"))?;
+        self.queued_bytes.fetch_add(bytes.len(), Ordering::Relaxed);
+        self.tx
+            .send(OutboundFrame { bytes, _permit: permit })
+            .await
+            .map_err(|_| anyhow!("Output writer has stopped"))
+    }
+
+    /// Bytes queued but not yet written
+    pub fn queued_bytes(&self) -> usize {
+        self.queued_bytes.load(Ordering::Relaxed)
+    }
+}
+
+/// Start the writer task. It runs until every `FrameSender` is dropped and the
+/// queue is drained, or a write fails.
+pub fn spawn<W>(mut writer: W) -> (FrameSender, JoinHandle<Result<()>>)
+where
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel::<OutboundFrame>(QUEUE_CAPACITY);
+    let queued_bytes = Arc::new(AtomicUsize::new(0));
+    let sender = FrameSender {
+        tx,
+        budget: Arc::new(Semaphore::new(MAX_QUEUED_BYTES)),
+        queued_bytes: queued_bytes.clone(),
+    };
+
+    let task = tokio::spawn(async move {
+        while let Some(frame) = rx.recv().await {
+            let len = frame.bytes.len();
+            if len >= LARGE_FRAME_BYTES {
+                debug!(
+                    "Writing {} byte frame ({} bytes queued behind it)",
+                    len,
+                    queued_bytes.load(Ordering::Relaxed).saturating_sub(len)
+                );
+            }
+            writer.write_all(&frame.bytes).await?;
+            writer.flush().await?;
+            queued_bytes.fetch_sub(len, Ordering::Relaxed);
+        }
+        Ok(())
+    });
+
+    (sender, task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::{self, AsyncReadExt};
+
+    #[tokio::test]
+    async fn test_frames_written_in_order() {
+        let (client, mut server) = io::duplex(64);
+        let (sender, task) = spawn(client);
+        for i in 0..3 {
+            sender.send(format!("frame-{}\n", i).into_bytes()).await.unwrap();
+        }
+        drop(sender);
+        task.await.unwrap().unwrap();
+
+        let mut out = String::new();
+        server.read_to_string(&mut out).await.unwrap();
+        assert_eq!(out, "frame-0\nframe-1\nframe-2\n");
+    }
+
+    #[tokio::test]
+    async fn test_slow_reader_applies_backpressure() {
+        // Nothing reads the other end, so the writer stalls after the duplex buffer fills
+        let (client, _server) = io::duplex(1024);
+        let (sender, _task) = spawn(client);
+        let chunk = vec![b'x'; MAX_QUEUED_BYTES / 2];
+        sender.send(chunk.clone()).await.unwrap();
+        sender.send(chunk.clone()).await.unwrap();
+
+        let blocked = tokio::time::timeout(Duration::from_millis(50), sender.send(chunk)).await;
+        assert!(blocked.is_err());
+        assert!(sender.queued_bytes() >= MAX_QUEUED_BYTES / 2);
+    }
+}
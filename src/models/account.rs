@@ -1,4 +1,7 @@
+use crate::models::events::Event;
+use crate::models::rate_limits::RateLimitData;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// Global OneLogin account settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,3 +126,79 @@ pub struct AccountUsageQuery {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_date: Option<String>,
 }
+
+impl AccountUsage {
+    /// Derive usage counters from a sample of events.
+    ///
+    /// OneLogin has no public usage endpoint, so counts are based on event type
+    /// names (e.g. `USER_LOGGED_INTO_ONELOGIN`) and only cover the events sampled.
+    pub fn from_events(events: &[Event], billing_period: Option<String>) -> Self {
+        let mut users = HashSet::new();
+        let mut authentication_count = 0;
+        let mut app_launch_count = 0;
+        let mut mfa_verification_count = 0;
+        let mut failed_login_count = 0;
+
+        for event in events {
+            if let Some(user_id) = event.user_id {
+                users.insert(user_id);
+            }
+            let name = event
+                .event_type_name
+                .as_deref()
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+                .replace('_', " ");
+
+            if name.contains("fail") || name.contains("rejected") {
+                if name.contains("login") || name.contains("logged") || name.contains("auth") {
+                    failed_login_count += 1;
+                }
+            } else if name.contains("logged into onelogin") {
+                authentication_count += 1;
+            } else if name.contains("logged into") || name.contains("launched") {
+                app_launch_count += 1;
+            } else if (name.contains("otp") || name.contains("mfa"))
+                && (name.contains("verif") || name.contains("authenticated"))
+            {
+                mfa_verification_count += 1;
+            }
+        }
+
+        Self {
+            active_users_count: Some(users.len() as i32),
+            total_users_count: None,
+            authentication_count: Some(authentication_count),
+            app_launch_count: Some(app_launch_count),
+            mfa_verification_count: Some(mfa_verification_count),
+            failed_login_count: Some(failed_login_count),
+            api_calls_count: None,
+            storage_used: None,
+            billing_period,
+        }
+    }
+}
+
+/// Combined account overview built from the endpoints that actually exist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountOverview {
+    /// Configured subdomain for this tenant
+    pub subdomain: String,
+    /// Configured region (us, eu)
+    pub region: String,
+    /// Account settings, if the tenant exposes them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<AccountSettings>,
+    /// Usage derived from recent events
+    pub usage: AccountUsage,
+    /// Number of events the usage figures are based on
+    pub events_sampled: usize,
+    /// True if more events exist in the window than were sampled
+    pub events_truncated: bool,
+    /// Current API rate limit window from /auth/rate_limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitData>,
+    /// Caveats about derived or unavailable data
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
@@ -4,8 +4,10 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiAuthorization {
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
     pub configuration: ApiAuthConfig,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
@@ -13,9 +15,11 @@ pub struct ApiAuthorization {
     pub onelogin: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ApiAuthConfig {
+    #[serde(default)]
     pub resource_identifier: String,
+    #[serde(default)]
     pub audiences: Vec<String>,
     #[serde(alias = "token_lifetime_minutes")]
     pub access_token_expiration_minutes: Option<i32>,
@@ -26,12 +30,14 @@ pub struct ApiAuthConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiAuthScope {
+    #[serde(default)]
     pub value: String,
     pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClaimSource {
+    #[serde(default)]
     pub attribute: String,
     pub transform: Option<String>,
 }
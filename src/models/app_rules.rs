@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppRule {
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     #[serde(default)]
     pub enabled: bool,
@@ -22,10 +23,13 @@ pub struct AppRule {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppRuleCondition {
     /// The source field to evaluate (e.g., "has_role", "member_of", custom attribute name)
+    #[serde(default)]
     pub source: String,
     /// The comparison operator (e.g., "=", "!=", "contains", "regex")
+    #[serde(default)]
     pub operator: String,
     /// The value to compare against
+    #[serde(default)]
     pub value: String,
 }
 
@@ -33,8 +37,10 @@ pub struct AppRuleCondition {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppRuleAction {
     /// The action type (e.g., "set_*", "add_*", "put_*")
+    #[serde(default)]
     pub action: String,
     /// Values for the action (can be single value or array depending on action type)
+    #[serde(default)]
     pub value: Vec<String>,
     /// Optional expression for dynamic values
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,8 +117,10 @@ pub struct UpdateAppRuleRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleConditionDef {
     /// Unique identifier for this condition type
+    #[serde(default)]
     pub value: String,
     /// Human-readable name
+    #[serde(default)]
     pub name: String,
 }
 
@@ -120,8 +128,10 @@ pub struct RuleConditionDef {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleActionDef {
     /// Unique identifier for this action type
+    #[serde(default)]
     pub value: String,
     /// Human-readable name
+    #[serde(default)]
     pub name: String,
 }
 
@@ -129,8 +139,10 @@ pub struct RuleActionDef {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleConditionOperator {
     /// Operator value (e.g., "=", "!=", "ri", "nri")
+    #[serde(default)]
     pub value: String,
     /// Human-readable name (e.g., "equals", "does not equal", "contains", "does not contain")
+    #[serde(default)]
     pub name: String,
 }
 
@@ -138,8 +150,10 @@ pub struct RuleConditionOperator {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleConditionValue {
     /// Value identifier
+    #[serde(default)]
     pub value: String,
     /// Human-readable name
+    #[serde(default)]
     pub name: String,
 }
 
@@ -147,8 +161,10 @@ pub struct RuleConditionValue {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleActionValue {
     /// Value identifier
+    #[serde(default)]
     pub value: String,
     /// Human-readable name
+    #[serde(default)]
     pub name: String,
 }
 
@@ -1,13 +1,18 @@
+use crate::utils::serde_helpers::flexible_i64;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct App {
+    #[serde(deserialize_with = "flexible_i64")]
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     pub description: Option<String>,
     pub icon_url: Option<String>,
     pub visible: Option<bool>,
+    #[serde(default, deserialize_with = "flexible_i64")]
     pub connector_id: i64,
     pub auth_method: Option<i32>,
     pub policy_id: Option<i64>,
@@ -26,6 +31,26 @@ pub struct App {
     pub extra: Option<HashMap<String, serde_json::Value>>,
 }
 
+/// Query parameters for `GET /api/2/apps`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppQueryParams {
+    /// App name; `*` wildcards are supported, e.g. "Sales*"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connector_id: Option<i64>,
+    /// 0 Password, 1 OpenId, 2 SAML, 3 API, 4 Google, 6 Forms-based, 7 WS-Fed, 8 OIDC
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_method: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<i32>,
+    /// Sort order, e.g. "+name" or "-id"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+}
+
 /// Strongly-typed app parameter - used when we control the data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppParameter {
@@ -47,23 +72,34 @@ pub struct AppParameter {
     pub user_attribute_macros: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateAppRequest {
+    /// The connector template ID. Get from onelogin_list_connectors. Common connectors:
+    /// SAML 2.0, OIDC, AWS, Salesforce, etc.
     pub connector_id: i64,
+    /// Display name for the application. This appears in the user portal.
     pub name: String,
+    /// Description of the application's purpose
     pub description: Option<String>,
+    /// Whether the app appears in users' portal. Set to false for backend-only apps. Default: true
     pub visible: Option<bool>,
+    /// Connector-specific settings. Varies by connector type. For SAML: audience, recipient,
+    /// acs_url. For OIDC: redirect_uri. Check connector documentation for required fields.
     pub configuration: Option<HashMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateAppRequest {
+    /// New display name for the application
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// New description for the application
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Whether the app appears in users' portal
     #[serde(skip_serializing_if = "Option::is_none")]
     pub visible: Option<bool>,
+    /// Updated connector-specific configuration. Only include settings you want to change.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub configuration: Option<HashMap<String, serde_json::Value>>,
 }
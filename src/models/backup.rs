@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Identifies backup archives written by this server
+pub const BACKUP_FORMAT: &str = "onelogin-mcp-backup";
+
+/// Current archive layout version. Bump when a section's shape changes.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Sections a backup can contain, in export order
+pub const BACKUP_SECTIONS: &[&str] = &[
+    "custom_attributes",
+    "password_policies",
+    "roles",
+    "apps",
+    "user_mappings",
+    "branding",
+];
+
+/// A tenant configuration backup.
+///
+/// Sections hold the API objects as returned by OneLogin so archives stay
+/// readable even if the typed models change between versions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupArchive {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    /// Tenant name in multi-tenant mode
+    #[serde(default)]
+    pub tenant: Option<String>,
+    pub subdomain: String,
+    pub sections: BTreeMap<String, Value>,
+    /// Sections that could not be exported, with the reason
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl BackupArchive {
+    pub fn new(tenant: Option<String>, subdomain: String) -> Self {
+        Self {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            tenant,
+            subdomain,
+            sections: BTreeMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Number of items per section (objects count as one)
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        self.sections
+            .iter()
+            .map(|(name, value)| (name.clone(), value.as_array().map_or(1, Vec::len)))
+            .collect()
+    }
+}
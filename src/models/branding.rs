@@ -1,9 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+/// Check a locale code such as `en`, `fr-CA` or `pt_BR`: a 2-3 letter language,
+/// optionally followed by region or variant subtags
+pub fn validate_locale(locale: &str) -> Result<(), String> {
+    let mut parts = locale.split(['-', '_']);
+    let language = parts.next().unwrap_or_default();
+    let language_ok = (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic());
+    let subtags_ok = parts.all(|p| (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()));
+    if language_ok && subtags_ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "locale '{}' is not a valid locale code (expected e.g. 'en', 'fr-CA' or 'pt_BR')",
+            locale
+        ))
+    }
+}
+
 // Account Brand (v2 API)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountBrand {
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enabled: Option<bool>,
@@ -61,6 +79,7 @@ pub struct UpdateBrandRequest {
 // Legacy BrandingSettings (for backward compatibility)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrandingSettings {
+    #[serde(default)]
     pub brands: Vec<AccountBrand>,
 }
 
@@ -91,7 +110,7 @@ pub struct UpdateBrandingRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageTemplate {
     pub id: i64,
-    #[serde(rename = "type")]
+    #[serde(default, rename = "type")]
     pub template_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub locale: Option<String>,
@@ -130,3 +149,15 @@ pub struct UpdateMessageTemplateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub locale: Option<String>,
 }
+
+impl CreateMessageTemplateRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.locale.as_deref().map_or(Ok(()), validate_locale)
+    }
+}
+
+impl UpdateMessageTemplateRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        self.locale.as_deref().map_or(Ok(()), validate_locale)
+    }
+}
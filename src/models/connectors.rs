@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Connector {
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     pub description: Option<String>,
     pub icon_url: Option<String>,
@@ -5,7 +5,9 @@ pub struct CustomAttribute {
     pub id: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub position: Option<i64>,
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub shortname: String,
     // These fields are optional in list responses but required in create/update
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1,15 +1,22 @@
+use crate::utils::serde_helpers::flexible_i32;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectoryConnector {
     pub id: String,
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub connector_type: String,
+    #[serde(default)]
     pub status: String,
+    #[serde(default)]
     pub configuration: HashMap<String, serde_json::Value>,
     pub last_sync_at: Option<String>,
+    #[serde(default)]
     pub created_at: String,
+    #[serde(default)]
     pub updated_at: String,
 }
 
@@ -30,11 +37,17 @@ pub struct UpdateDirectoryConnectorRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncStatus {
+    #[serde(default)]
     pub status: String,
+    #[serde(default)]
     pub started_at: String,
     pub completed_at: Option<String>,
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub users_added: i32,
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub users_updated: i32,
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub users_deleted: i32,
+    #[serde(default)]
     pub errors: Vec<String>,
 }
@@ -2,7 +2,9 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbedToken {
+    #[serde(default)]
     pub token: String,
+    #[serde(default)]
     pub expires_at: String,
 }
 
@@ -16,6 +18,7 @@ pub struct GenerateEmbedTokenRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddableApp {
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     pub icon_url: Option<String>,
 }
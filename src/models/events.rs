@@ -59,7 +59,7 @@ pub struct Event {
     pub browser_fingerprint: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct EventQueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub since: Option<String>,
@@ -75,6 +75,18 @@ pub struct EventQueryParams {
     pub directory_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<i32>,
+    /// Only events with an id greater than this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_id: Option<i64>,
+    /// Only events with an id lower than this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until_id: Option<i64>,
+    /// Cursor from a previous page's pagination.after_cursor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_cursor: Option<String>,
+    /// Sort order, e.g. "+id" (oldest first) or "-id"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
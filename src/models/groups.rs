@@ -1,8 +1,11 @@
+use crate::utils::serde_helpers::flexible_i64;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Group {
+    #[serde(deserialize_with = "flexible_i64")]
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     pub reference: Option<String>,
 }
@@ -3,12 +3,14 @@ use serde::{Deserialize, Serialize};
 /// Response from generate_invite_link - contains the activation URL
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InviteLinkResponse {
+    #[serde(default)]
     pub invite_link: String,
 }
 
 /// Response from send_invite_link - confirmation that email was sent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendInviteResponse {
+    #[serde(default)]
     pub message: String,
 }
 
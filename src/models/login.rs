@@ -93,8 +93,4 @@ pub struct VerifyFactorLoginRequest {
     pub do_not_notify: Option<bool>,
 }
 
-/// Request to create a session from a session token
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateSessionRequest {
-    pub session_token: String,
-}
+
@@ -1,5 +1,38 @@
 use serde::{Deserialize, Serialize};
 
+/// Maximum size of login page HTML content in bytes
+pub const MAX_HTML_BYTES: usize = 256 * 1024;
+/// Maximum size of login page CSS content in bytes
+pub const MAX_CSS_BYTES: usize = 128 * 1024;
+/// Maximum size of login page JavaScript content in bytes
+pub const MAX_JAVASCRIPT_BYTES: usize = 128 * 1024;
+
+/// Check HTML/CSS/JavaScript content against the login page size limits
+pub fn validate_content_sizes(
+    html: Option<&str>,
+    css: Option<&str>,
+    javascript: Option<&str>,
+) -> Result<(), String> {
+    let checks = [
+        ("html_content", html, MAX_HTML_BYTES),
+        ("css_content", css, MAX_CSS_BYTES),
+        ("javascript_content", javascript, MAX_JAVASCRIPT_BYTES),
+    ];
+    for (field, content, limit) in checks {
+        if let Some(content) = content {
+            if content.len() > limit {
+                return Err(format!(
+                    "{} is {} bytes, which exceeds the {} byte limit",
+                    field,
+                    content.len(),
+                    limit
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Custom login page configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoginPage {
@@ -35,6 +68,14 @@ pub struct LoginPage {
     pub last_modified: Option<String>,
 }
 
+/// Preview URL for a login page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginPagePreview {
+    pub page_id: i64,
+    #[serde(default)]
+    pub preview_url: Option<String>,
+}
+
 /// Request to create a custom login page
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateLoginPageRequest {
@@ -79,3 +120,23 @@ pub struct UpdateLoginPageRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enabled: Option<bool>,
 }
+
+impl CreateLoginPageRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_content_sizes(
+            self.html_content.as_deref(),
+            self.css_content.as_deref(),
+            self.javascript_content.as_deref(),
+        )
+    }
+}
+
+impl UpdateLoginPageRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_content_sizes(
+            self.html_content.as_deref(),
+            self.css_content.as_deref(),
+            self.javascript_content.as_deref(),
+        )
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MfaFactor {
     pub factor_id: i64,
+    #[serde(default)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub active: Option<bool>,
@@ -13,8 +14,11 @@ pub struct MfaFactor {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MfaDevice {
     pub device_id: String,
+    #[serde(default)]
     pub user_display_name: String,
+    #[serde(default)]
     pub type_display_name: String,
+    #[serde(default)]
     pub auth_factor_name: String,
     #[serde(default)]
     pub default: bool,
@@ -35,6 +39,7 @@ pub struct MfaVerification {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MfaVerificationResponse {
+    #[serde(default)]
     pub status: String,
     pub message: Option<String>,
 }
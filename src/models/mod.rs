@@ -9,6 +9,8 @@ pub mod app_rules;
 #[allow(dead_code)]
 pub mod apps;
 #[allow(dead_code)]
+pub mod backup;
+#[allow(dead_code)]
 pub mod branding;
 #[allow(dead_code)]
 pub mod certificates;
@@ -67,6 +69,7 @@ pub mod vigilance;
 #[allow(dead_code)]
 pub mod webhooks;
 
+use crate::utils::serde_helpers::{flexible_bool, flexible_i32};
 use serde::{Deserialize, Serialize};
 
 // Common response wrapper
@@ -79,9 +82,9 @@ pub struct ApiResponse<T> {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Status {
-    #[serde(default)]
+    #[serde(default, deserialize_with = "flexible_bool")]
     pub error: bool,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub code: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
@@ -1,3 +1,4 @@
+use crate::utils::serde_helpers::flexible_i64;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -11,8 +12,11 @@ pub struct TokenRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenResponse {
+    #[serde(default)]
     pub access_token: String,
+    #[serde(default)]
     pub token_type: String,
+    #[serde(default, deserialize_with = "flexible_i64")]
     pub expires_in: i64,
     pub refresh_token: Option<String>,
     pub scope: Option<String>,
@@ -1,20 +1,34 @@
+use crate::utils::serde_helpers::flexible_bool;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OidcConfiguration {
+    #[serde(default)]
     pub issuer: String,
+    #[serde(default)]
     pub authorization_endpoint: String,
+    #[serde(default)]
     pub token_endpoint: String,
+    #[serde(default)]
     pub userinfo_endpoint: String,
+    #[serde(default)]
     pub jwks_uri: String,
     pub registration_endpoint: Option<String>,
+    #[serde(default)]
     pub scopes_supported: Vec<String>,
+    #[serde(default)]
     pub response_types_supported: Vec<String>,
+    #[serde(default)]
     pub response_modes_supported: Vec<String>,
+    #[serde(default)]
     pub grant_types_supported: Vec<String>,
+    #[serde(default)]
     pub subject_types_supported: Vec<String>,
+    #[serde(default)]
     pub id_token_signing_alg_values_supported: Vec<String>,
+    #[serde(default)]
     pub claims_supported: Vec<String>,
+    #[serde(default)]
     pub token_endpoint_auth_methods_supported: Vec<String>,
     pub revocation_endpoint: Option<String>,
     pub introspection_endpoint: Option<String>,
@@ -22,13 +36,16 @@ pub struct OidcConfiguration {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Jwks {
+    #[serde(default)]
     pub keys: Vec<Jwk>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Jwk {
+    #[serde(default)]
     pub kty: String,
     pub use_field: Option<String>,
+    #[serde(default)]
     pub kid: String,
     pub n: Option<String>,
     pub e: Option<String>,
@@ -37,6 +54,7 @@ pub struct Jwk {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserInfo {
+    #[serde(default)]
     pub sub: String,
     pub email: Option<String>,
     pub email_verified: Option<bool>,
@@ -50,6 +68,7 @@ pub struct UserInfo {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenIntrospection {
+    #[serde(default, deserialize_with = "flexible_bool")]
     pub active: bool,
     pub scope: Option<String>,
     pub client_id: Option<String>,
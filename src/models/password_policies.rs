@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Password policy configuration
@@ -54,50 +55,76 @@ pub struct PasswordPolicy {
 }
 
 /// Request to create a password policy
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreatePasswordPolicyRequest {
+    /// Policy name
     pub name: String,
+    /// Minimum password length
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_length: Option<i32>,
+    /// Require uppercase letters
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_uppercase: Option<bool>,
+    /// Require lowercase letters
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_lowercase: Option<bool>,
+    /// Require numbers
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_numbers: Option<bool>,
+    /// Require special characters
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_special_chars: Option<bool>,
+    /// Number of previous passwords that cannot be reused (0-24)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password_history: Option<i32>,
+    /// Days until password expires (0 = never)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expiration_days: Option<i32>,
+    /// Lock the account after this many failed attempts (0 = disabled)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_failed_attempts: Option<i32>,
+    /// Minutes an account stays locked after too many failed attempts
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lockout_duration_minutes: Option<i32>,
 }
 
 /// Request to update a password policy
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct UpdatePasswordPolicyRequest {
+    /// Policy name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// Minimum password length
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_length: Option<i32>,
+    /// Require uppercase letters
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_uppercase: Option<bool>,
+    /// Require lowercase letters
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_lowercase: Option<bool>,
+    /// Require numbers
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_numbers: Option<bool>,
+    /// Require special characters
     #[serde(skip_serializing_if = "Option::is_none")]
     pub require_special_chars: Option<bool>,
+    /// Number of previous passwords that cannot be reused (0-24)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password_history: Option<i32>,
+    /// Days until password expires (0 = never)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expiration_days: Option<i32>,
+    /// Lock the account after this many failed attempts (0 = disabled)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_failed_attempts: Option<i32>,
+    /// Minutes an account stays locked after too many failed attempts
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lockout_duration_minutes: Option<i32>,
 }
+
+/// Request to assign a password policy to a user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignPasswordPolicyRequest {
+    pub policy_id: i64,
+}
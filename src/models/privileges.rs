@@ -4,27 +4,29 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Privilege {
     pub id: String,
+    #[serde(default)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(default)]
     pub privilege: PrivilegeStatement,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PrivilegeStatement {
-    #[serde(rename = "Version")]
+    #[serde(default, rename = "Version")]
     pub version: String,
-    #[serde(rename = "Statement")]
+    #[serde(default, rename = "Statement")]
     pub statement: Vec<StatementItem>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatementItem {
-    #[serde(rename = "Effect")]
+    #[serde(default, rename = "Effect")]
     pub effect: String,
-    #[serde(rename = "Action")]
+    #[serde(default, rename = "Action")]
     pub action: Vec<String>,
-    #[serde(rename = "Scope")]
+    #[serde(default, rename = "Scope")]
     pub scope: Vec<String>,
 }
 
@@ -1,3 +1,7 @@
+use crate::core::circuit_breaker::CircuitBreakerStats;
+use crate::core::compression::CompressionStatsSnapshot;
+use crate::core::rate_limit::RateLimiterStats;
+use crate::core::retry::RetryBudgetStats;
 use serde::{Deserialize, Serialize};
 
 /// Rate limit data from /auth/rate_limit endpoint
@@ -37,6 +41,29 @@ pub struct RateLimitStatus {
     pub data: Option<RateLimitData>,
 }
 
+/// OneLogin's reported rate limit combined with the local limiter state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitOverview {
+    /// Values reported by /auth/rate_limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub onelogin: Option<RateLimitData>,
+    /// Error from /auth/rate_limit, if the call failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub onelogin_error: Option<String>,
+    /// Client-side limiter configuration and counters
+    pub local: RateLimiterStats,
+    /// Response sizes on the wire and after decoding
+    pub compression: CompressionStatsSnapshot,
+    /// Circuit breaker state and trip counters
+    pub circuit_breaker: CircuitBreakerStats,
+    /// Retry settings applied to failed requests
+    pub max_retries: u32,
+    pub retry_initial_delay_ms: u64,
+    pub retry_max_delay_ms: u64,
+    /// Retries made and skipped (shared budget, non-idempotent requests)
+    pub retry_budget: RetryBudgetStats,
+}
+
 /// Rate limit configuration for API endpoints (legacy)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitConfig {
@@ -45,6 +45,20 @@ pub struct ReportJob {
     pub results: Option<Value>,
 }
 
+impl ReportJob {
+    /// Whether the job has reached a terminal state (completed, failed, etc.)
+    pub fn is_finished(&self) -> bool {
+        match self.status.as_deref().map(|s| s.to_ascii_lowercase()) {
+            Some(s) => matches!(
+                s.as_str(),
+                "completed" | "complete" | "done" | "success" | "succeeded" | "failed" | "error" | "cancelled"
+            ),
+            // No status but results present means the report ran synchronously
+            None => self.results.is_some(),
+        }
+    }
+}
+
 /// Request to run a report
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RunReportRequest {
@@ -1,7 +1,10 @@
+use crate::utils::serde_helpers::flexible_i64;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Role {
+    #[serde(deserialize_with = "flexible_i64")]
     pub id: i64,
     // Note: name may not be present in create response (only id is returned)
     #[serde(default)]
@@ -12,17 +15,50 @@ pub struct Role {
     pub apps: Option<Vec<i64>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Query parameters for `GET /api/2/roles`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RoleQueryParams {
+    /// Role name; `*` wildcards are supported, e.g. "Sales*"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Only roles that grant this app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// Comma-separated fields to return, e.g. "id,name,apps"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<i32>,
+    /// `After-Cursor` value from a previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// One page of roles and where the next one starts
+#[derive(Debug, Clone, Serialize)]
+pub struct RolePage {
+    pub roles: Vec<Role>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateRoleRequest {
+    /// The name of the role to create. This is the ONLY field accepted during creation.
     pub name: String,
     // Note: OneLogin API v2 does not accept 'description' for role creation
     // Description can only be set via update
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateRoleRequest {
+    /// New name for the role
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// New description for the role
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
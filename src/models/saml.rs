@@ -11,6 +11,7 @@ pub struct SamlAssertionRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SamlAssertionResponse {
+    #[serde(default)]
     pub status: String,
     pub data: Option<String>,
     pub message: Option<String>,
@@ -22,6 +23,7 @@ pub struct SamlAssertionResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MfaDeviceInfo {
     pub device_id: i64,
+    #[serde(default)]
     pub device_type: String,
 }
 
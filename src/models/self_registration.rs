@@ -1,5 +1,78 @@
 use serde::{Deserialize, Serialize};
 
+/// Accepted `email_verification_type` values
+pub const EMAIL_VERIFICATION_TYPES: &[&str] = &["email", "sms", "none"];
+
+/// Check an `email_verification_type` value
+pub fn validate_email_verification_type(value: Option<&str>) -> Result<(), String> {
+    match value {
+        Some(v) if !EMAIL_VERIFICATION_TYPES.contains(&v) => Err(format!(
+            "email_verification_type '{}' is not valid; use one of: {}",
+            v,
+            EMAIL_VERIFICATION_TYPES.join(", ")
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Check that `domain` is a bare domain name such as `example.com`: no `@`, scheme,
+/// path or port, at least two labels of letters, digits and inner hyphens
+fn validate_domain(field: &str, domain: &str) -> Result<(), String> {
+    let invalid = |why: &str| Err(format!("{} entry '{}' is not a valid domain: {}", field, domain, why));
+    if domain.is_empty() {
+        return invalid("it is empty");
+    }
+    if domain.contains('@') {
+        return invalid("give the domain only, without the user part (e.g. example.com)");
+    }
+    if domain.contains("://") || domain.contains('/') || domain.contains(':') {
+        return invalid("give the domain only, without scheme, path or port");
+    }
+    if domain.len() > 253 {
+        return invalid("it is longer than 253 characters");
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return invalid("it needs a top-level domain (e.g. example.com)");
+    }
+    for label in labels {
+        if label.is_empty() || label.len() > 63 {
+            return invalid("each dot-separated part must be 1-63 characters");
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return invalid("only letters, digits, hyphens and dots are allowed");
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return invalid("parts can't start or end with a hyphen");
+        }
+    }
+    Ok(())
+}
+
+/// Check domain whitelist and blacklist entries, and that no domain is on both
+pub fn validate_domain_lists(
+    whitelist: Option<&[String]>,
+    blacklist: Option<&[String]>,
+) -> Result<(), String> {
+    for (field, domains) in [("domain_whitelist", whitelist), ("domain_blacklist", blacklist)] {
+        for domain in domains.unwrap_or_default() {
+            validate_domain(field, domain.trim())?;
+        }
+    }
+    if let (Some(allowed), Some(blocked)) = (whitelist, blacklist) {
+        if let Some(domain) = allowed
+            .iter()
+            .find(|a| blocked.iter().any(|b| b.trim().eq_ignore_ascii_case(a.trim())))
+        {
+            return Err(format!(
+                "'{}' is in both domain_whitelist and domain_blacklist",
+                domain.trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Response wrapper for list self-registration profiles endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SelfRegistrationProfilesResponse {
@@ -11,6 +84,7 @@ pub struct SelfRegistrationProfilesResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SelfRegistrationProfile {
     pub id: i64,
+    #[serde(default)]
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
@@ -63,6 +137,11 @@ pub struct Registration {
     pub firstname: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lastname: Option<String>,
+    /// Address the registration was submitted from, when OneLogin records it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -124,3 +203,17 @@ pub struct ApproveRegistrationRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rejection_reason: Option<String>,
 }
+
+impl CreateSelfRegistrationProfileRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_email_verification_type(self.email_verification_type.as_deref())?;
+        validate_domain_lists(self.domain_whitelist.as_deref(), self.domain_blacklist.as_deref())
+    }
+}
+
+impl UpdateSelfRegistrationProfileRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_email_verification_type(self.email_verification_type.as_deref())?;
+        validate_domain_lists(self.domain_whitelist.as_deref(), self.domain_blacklist.as_deref())
+    }
+}
@@ -1,15 +1,18 @@
+use crate::utils::secrets::mask_secret;
+use crate::utils::serde_helpers::flexible_i64;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmartHook {
     pub id: String,
-    #[serde(rename = "type")]
+    #[serde(default, rename = "type")]
     pub hook_type: String,
     #[serde(default)]
     pub status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub function: Option<String>,
+    #[serde(default)]
     pub runtime: String,
     #[serde(default)]
     pub disabled: Option<bool>,
@@ -23,13 +26,18 @@ pub struct SmartHook {
     pub env_vars: Option<Vec<EnvVar>>,
     pub conditions: Option<Vec<serde_json::Value>>,
     pub options: Option<HookOptions>,
+    #[serde(default)]
     pub created_at: String,
+    #[serde(default)]
     pub updated_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvVar {
+    #[serde(default)]
     pub name: String,
+    /// Never serialized in cleartext
+    #[serde(default, serialize_with = "mask_secret", skip_serializing_if = "Option::is_none")]
     pub value: Option<String>,
 }
 
@@ -99,9 +107,13 @@ pub struct FullUpdateHookRequest {
 pub struct HookLog {
     pub id: String,
     pub hook_id: String,
+    #[serde(default)]
     pub timestamp: String,
+    #[serde(default)]
     pub status: String,
+    #[serde(default, deserialize_with = "flexible_i64")]
     pub execution_time_ms: i64,
+    #[serde(default)]
     pub logs: Vec<String>,
     pub error: Option<String>,
 }
@@ -111,7 +123,11 @@ pub struct HookLog {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookEnvVar {
     pub id: String,
+    #[serde(default)]
     pub name: String,
+    /// Only returned by some tenants; never serialized in cleartext
+    #[serde(default, serialize_with = "mask_secret", skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
     #[serde(default)]
@@ -1,3 +1,4 @@
+use crate::utils::serde_helpers::flexible_bool;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -12,6 +13,7 @@ pub struct SmartMfaValidateRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmartMfaValidateResponse {
+    #[serde(default, deserialize_with = "flexible_bool")]
     pub mfa_required: bool,
     pub device_id: Option<String>,
     pub state_token: Option<String>,
@@ -21,6 +23,8 @@ pub struct SmartMfaValidateResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmartMfaUser {
     pub id: i64,
+    #[serde(default)]
     pub username: String,
+    #[serde(default)]
     pub email: String,
 }
@@ -1,27 +1,37 @@
+use crate::utils::serde_helpers::flexible_bool;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserMapping {
     pub id: i64,
+    #[serde(default)]
     pub name: String,
-    #[serde(rename = "match")]
+    #[serde(default, rename = "match")]
     pub match_type: String,
+    #[serde(default, deserialize_with = "flexible_bool")]
     pub enabled: bool,
     pub position: Option<i32>,
+    #[serde(default)]
     pub conditions: Vec<MappingCondition>,
+    #[serde(default)]
     pub actions: Vec<MappingAction>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MappingCondition {
+    #[serde(default)]
     pub source: String,
+    #[serde(default)]
     pub operator: String,
+    #[serde(default)]
     pub value: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MappingAction {
+    #[serde(default)]
     pub action: String,
+    #[serde(default)]
     pub value: Vec<String>,
 }
 
@@ -66,6 +76,8 @@ pub struct SortMappingsRequest {
 /// Available condition types that can be used in user mappings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AvailableCondition {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub value: String,
 }
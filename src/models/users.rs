@@ -1,8 +1,11 @@
+use crate::utils::serde_helpers::{flexible_bool, flexible_i32, flexible_i64, flexible_option_i64};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
+    #[serde(deserialize_with = "flexible_i64")]
     pub id: i64,
     #[serde(default)]
     pub email: Option<String>,
@@ -14,117 +17,181 @@ pub struct User {
     pub department: Option<String>,
     pub company: Option<String>,
     pub phone: Option<String>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub status: i32,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub state: i32,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
     pub locked_until: Option<String>,
-    #[serde(default)]
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub invalid_login_attempts: i32,
     pub last_login: Option<String>,
     pub activated_at: Option<String>,
+    #[serde(default)]
+    pub password_changed_at: Option<String>,
+    /// Assigned password policy; `None` means the account default policy applies
+    #[serde(default, deserialize_with = "flexible_option_i64")]
+    pub policy_id: Option<i64>,
     pub custom_attributes: Option<HashMap<String, serde_json::Value>>,
     pub role_ids: Option<Vec<i64>>,
+    #[serde(default, deserialize_with = "flexible_option_i64")]
     pub group_id: Option<i64>,
+    #[serde(default, deserialize_with = "flexible_option_i64")]
     pub directory_id: Option<i64>,
+    #[serde(default, deserialize_with = "flexible_option_i64")]
     pub trusted_idp_id: Option<i64>,
     pub manager_ad_id: Option<String>,
+    #[serde(default, deserialize_with = "flexible_option_i64")]
     pub manager_user_id: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateUserRequest {
     // Required fields
+    /// User's email address
     pub email: String,
+    /// Username
     pub username: String,
     // Basic information
+    /// First name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub firstname: Option<String>,
+    /// Last name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lastname: Option<String>,
+    /// Job title
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    /// Department
     #[serde(skip_serializing_if = "Option::is_none")]
     pub department: Option<String>,
+    /// Company name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub company: Option<String>,
+    /// Phone number (E.164 format)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub phone: Option<String>,
+    /// Free text notes about the user
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
     // Authentication & Password
+    /// User's password (cleartext)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password: Option<String>,
+    /// Password confirmation (must match password)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password_confirmation: Option<String>,
+    /// Hash algorithm for pre-hashed password import: 'salt+sha256' (salt prepended),
+    /// 'sha256+salt' (salt appended), 'bcrypt'. Requires 'salt' field for SHA256 variants.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub password_algorithm: Option<String>, // "salt+sha256", "sha256+salt", or "bcrypt"
+    pub password_algorithm: Option<String>,
+    /// Salt value used with password_algorithm
     #[serde(skip_serializing_if = "Option::is_none")]
     pub salt: Option<String>,
     // Status & State
+    /// User licensing state: 0=Unapproved (pending approval), 1=Approved (licensed),
+    /// 2=Rejected, 3=Unlicensed. Default is 1 (Approved).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub state: Option<i32>, // 0=Unapproved, 1=Approved, 2=Rejected, 3=Unlicensed
+    pub state: Option<i32>,
+    /// User login status: 0=Unactivated, 1=Active (can login), 2=Suspended, 3=Locked,
+    /// 4=Password expired, 5=Awaiting reset, 7=Password pending, 8=Security questions
+    /// required. Default is 0 (Unactivated).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub status: Option<i32>, // 0=Unactivated, 1=Active, 2=Suspended, 3=Locked, etc.
+    pub status: Option<i32>,
     // Directory & Authentication
+    /// OneLogin Directory ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub directory_id: Option<i64>,
+    /// Trusted IDP ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trusted_idp_id: Option<i64>,
+    /// Active Directory username
     #[serde(skip_serializing_if = "Option::is_none")]
     pub samaccountname: Option<String>,
+    /// User principal name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub userprincipalname: Option<String>,
+    /// Distinguished name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub distinguished_name: Option<String>,
+    /// External directory ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub external_id: Option<String>,
+    /// Directory membership
     #[serde(skip_serializing_if = "Option::is_none")]
     pub member_of: Option<String>,
+    /// OpenID sign-in name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub openid_name: Option<String>,
     // Management & Assignments
+    /// Group ID to assign
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group_id: Option<i64>,
+    /// List of Role IDs to assign
     #[serde(skip_serializing_if = "Option::is_none")]
     pub role_ids: Option<Vec<i64>>,
+    /// Manager's Active Directory ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manager_ad_id: Option<String>,
+    /// Manager's OneLogin User ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manager_user_id: Option<i64>,
     // Other
+    /// Count of failed login attempts
     #[serde(skip_serializing_if = "Option::is_none")]
     pub invalid_login_attempts: Option<i32>,
+    /// 2-character language locale (e.g., 'en', 'es')
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preferred_locale_code: Option<String>,
+    /// Custom attribute key-value pairs
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_attributes: Option<HashMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateUserRequest {
+    /// User's email address. Must be unique within the OneLogin account.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    /// User's username for login. Must be unique within the OneLogin account.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub username: Option<String>,
+    /// User's first name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub firstname: Option<String>,
+    /// User's last name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lastname: Option<String>,
+    /// User's job title (e.g., 'Software Engineer', 'Sales Manager')
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    /// User's department within the organization (e.g., 'Engineering', 'Human Resources')
     #[serde(skip_serializing_if = "Option::is_none")]
     pub department: Option<String>,
+    /// User's company or organization name
     #[serde(skip_serializing_if = "Option::is_none")]
     pub company: Option<String>,
+    /// User's phone number. E.164 format recommended (e.g., '+15551234567')
     #[serde(skip_serializing_if = "Option::is_none")]
     pub phone: Option<String>,
+    /// User login status. Values: 0=Unactivated (never logged in), 1=Active (can log in),
+    /// 2=Suspended (admin disabled), 3=Locked (too many failed attempts), 4=Password expired,
+    /// 5=Awaiting password reset, 7=Password pending (hasn't set password), 8=Security
+    /// questions required. Only status=1 allows login.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<i32>,
+    /// User licensing state. Values: 0=Unapproved (pending admin approval), 1=Approved
+    /// (licensed, normal user), 2=Rejected (denied access), 3=Unlicensed (no license
+    /// assigned). Note: Changing FROM state=3 requires using onelogin_set_user_state,
+    /// not this endpoint.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<i32>,
+    /// Group ID to move the user into. Get from onelogin_list_groups.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<i64>,
+    /// Custom attribute key-value pairs. Keys must match custom attributes defined in
+    /// OneLogin admin console. Values can be strings, numbers, or booleans.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_attributes: Option<HashMap<String, serde_json::Value>>,
     // Note: role_ids is NOT supported by the OneLogin Update User API
@@ -151,6 +218,28 @@ pub struct UserQueryParams {
     pub role_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<i32>,
+    /// Custom attribute filters by shortname, sent as `custom_attributes.<shortname>=<value>`
+    /// (serde_qs would nest them as `custom_attributes[shortname]`, which OneLogin ignores)
+    #[serde(skip)]
+    pub custom_attributes: BTreeMap<String, String>,
+}
+
+impl UserQueryParams {
+    /// Query string for `GET /api/2/users`, including custom attribute filters
+    pub fn to_query(&self) -> String {
+        let mut query = serde_qs::to_string(self).unwrap_or_default();
+        for (shortname, value) in &self.custom_attributes {
+            if !query.is_empty() {
+                query.push('&');
+            }
+            query.push_str(&format!(
+                "custom_attributes.{}={}",
+                urlencoding::encode(shortname),
+                urlencoding::encode(value)
+            ));
+        }
+        query
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -193,27 +282,60 @@ pub struct LockUserRequest {
 /// Response from lock_user API (v1)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockUserResponse {
+    #[serde(default)]
     pub status: LockUserApiStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LockUserApiStatus {
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub status_type: String,
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub code: i32,
+    #[serde(default)]
     pub message: String,
+    #[serde(default, deserialize_with = "flexible_bool")]
     pub error: bool,
 }
 
 /// Response from unlock_user API (v2)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnlockUserResponse {
+    #[serde(default)]
     pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DelegatedPrivilege {
     pub id: String,
+    #[serde(default)]
     pub name: String,
     pub description: Option<String>,
 }
+
+/// Security factor registered to a user (`GET /api/1/users/{id}/otp_devices`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtpDevice {
+    #[serde(deserialize_with = "flexible_i64")]
+    pub id: i64,
+    #[serde(default, deserialize_with = "flexible_bool")]
+    pub active: bool,
+    #[serde(default, deserialize_with = "flexible_bool")]
+    pub default: bool,
+    /// Factor type, e.g. "Google Authenticator" or "OneLogin SMS"
+    pub auth_factor_name: Option<String>,
+    pub type_display_name: Option<String>,
+    /// Name the user gave the device
+    pub user_display_name: Option<String>,
+    pub phone_number: Option<String>,
+    /// The factor needs a push or code sent before it can be verified
+    #[serde(default, deserialize_with = "flexible_bool")]
+    pub needs_trigger: bool,
+    pub state_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OtpDevicesData {
+    #[serde(default)]
+    pub otp_devices: Vec<OtpDevice>,
+}
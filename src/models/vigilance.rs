@@ -1,17 +1,25 @@
+use crate::utils::serde_helpers::{flexible_bool, flexible_i32};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RiskScore {
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub score: i32,
+    #[serde(default)]
     pub risk_level: String,
+    #[serde(default)]
     pub factors: Vec<RiskFactor>,
+    #[serde(default)]
     pub timestamp: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskFactor {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub value: String,
+    #[serde(default)]
     pub weight: f64,
 }
 
@@ -44,8 +52,11 @@ pub struct UserValidationRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub validation_id: String,
+    #[serde(default)]
     pub status: String,
+    #[serde(default)]
     pub risk_score: RiskScore,
+    #[serde(default, deserialize_with = "flexible_bool")]
     pub mfa_required: bool,
     pub mfa_token: Option<String>,
 }
@@ -53,23 +64,32 @@ pub struct ValidationResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskRule {
     pub id: String,
+    #[serde(default)]
     pub name: String,
     pub description: Option<String>,
+    #[serde(default, deserialize_with = "flexible_bool")]
     pub enabled: bool,
+    #[serde(default)]
     pub conditions: Vec<RiskCondition>,
+    #[serde(default)]
     pub action: RiskAction,
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub priority: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskCondition {
+    #[serde(default)]
     pub field: String,
+    #[serde(default)]
     pub operator: String,
+    #[serde(default)]
     pub value: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RiskAction {
+    #[serde(default)]
     pub action_type: String,
     pub parameters: Option<serde_json::Value>,
 }
@@ -87,8 +107,11 @@ pub struct CreateRiskRuleRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskEvent {
     pub user_id: String,
+    #[serde(default)]
     pub event_type: String,
+    #[serde(default, deserialize_with = "flexible_i32")]
     pub risk_score: i32,
+    #[serde(default)]
     pub timestamp: String,
     pub details: Option<serde_json::Value>,
 }
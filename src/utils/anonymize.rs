@@ -0,0 +1,119 @@
+//! Pseudonymization of user records for exports shared outside the tenant.
+//!
+//! Emails, usernames and names are replaced by keyed hashes (HMAC-SHA256), so
+//! the same person maps to the same pseudonym across rows and joins still work,
+//! while the original values can't be recovered by hashing guesses without the
+//! key. Phone numbers are dropped. With a caller-supplied salt pseudonyms are
+//! stable across exports; without one each export gets a random key.
+
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Hex characters kept from each hash
+const PSEUDONYM_LENGTH: usize = 16;
+/// Reserved domain, so anonymized emails can never reach a real mailbox
+const ANONYMIZED_EMAIL_DOMAIN: &str = "anonymized.invalid";
+
+/// User fields replaced by a pseudonym, with the prefix each pseudonym gets
+const HASHED_FIELDS: &[(&str, &str)] = &[("username", "user_"), ("firstname", "first_"), ("lastname", "last_")];
+/// User fields removed outright
+const DROPPED_FIELDS: &[&str] = &["phone"];
+
+pub struct Anonymizer {
+    key: Vec<u8>,
+}
+
+impl Anonymizer {
+    /// Pseudonyms stable for a given salt
+    pub fn with_salt(salt: &str) -> Self {
+        Self { key: salt.as_bytes().to_vec() }
+    }
+
+    /// Pseudonyms that only match within this export
+    pub fn random() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default(),
+        );
+        hasher.write_u32(std::process::id());
+        Self { key: Sha256::digest(hasher.finish().to_le_bytes()).to_vec() }
+    }
+
+    /// Keyed hash of `value`, ignoring case and surrounding whitespace
+    pub fn pseudonym(&self, value: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC can take key of any size");
+        mac.update(value.trim().to_lowercase().as_bytes());
+        let mut hash = hex::encode(mac.finalize().into_bytes());
+        hash.truncate(PSEUDONYM_LENGTH);
+        hash
+    }
+
+    /// Replace the identifying fields of a serialized user in place
+    pub fn anonymize_user(&self, user: &mut Value) {
+        let Some(map) = user.as_object_mut() else {
+            return;
+        };
+        if let Some(Value::String(email)) = map.get("email") {
+            let pseudonym = format!("{}@{}", self.pseudonym(email), ANONYMIZED_EMAIL_DOMAIN);
+            map.insert("email".to_string(), Value::String(pseudonym));
+        }
+        for (field, prefix) in HASHED_FIELDS {
+            if let Some(Value::String(value)) = map.get(*field) {
+                let pseudonym = format!("{}{}", prefix, self.pseudonym(value));
+                map.insert(field.to_string(), Value::String(pseudonym));
+            }
+        }
+        for field in DROPPED_FIELDS {
+            map.remove(*field);
+        }
+    }
+
+    /// Fields `anonymize_user` changes, for reporting what an export hides
+    pub fn affected_fields() -> Vec<&'static str> {
+        let mut fields = vec!["email"];
+        fields.extend(HASHED_FIELDS.iter().map(|(field, _)| *field));
+        fields.extend(DROPPED_FIELDS);
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_anonymize_user() {
+        let anonymizer = Anonymizer::with_salt("analytics");
+        let mut user = json!({
+            "id": 7,
+            "email": "Jane.Doe@example.com",
+            "username": "jdoe",
+            "firstname": "Jane",
+            "lastname": null,
+            "phone": "+1 555 0100",
+            "department": "Finance"
+        });
+        anonymizer.anonymize_user(&mut user);
+
+        let email = user["email"].as_str().unwrap();
+        assert!(email.ends_with("@anonymized.invalid"));
+        assert!(!email.contains("jane"));
+        assert_eq!(email, format!("{}@anonymized.invalid", anonymizer.pseudonym("jane.doe@example.com ")));
+        assert!(user["username"].as_str().unwrap().starts_with("user_"));
+        assert!(user["lastname"].is_null());
+        assert!(user.get("phone").is_none());
+        assert_eq!(user["id"], 7);
+        assert_eq!(user["department"], "Finance");
+
+        assert_eq!(Anonymizer::with_salt("analytics").pseudonym("jdoe"), anonymizer.pseudonym("jdoe"));
+        assert_ne!(Anonymizer::with_salt("other").pseudonym("jdoe"), anonymizer.pseudonym("jdoe"));
+        assert_ne!(Anonymizer::random().pseudonym("jdoe"), anonymizer.pseudonym("jdoe"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Minimal X.509 reading: just enough DER to get a certificate's validity period.
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_CONTEXT_0: u8 = 0xa0;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+
+/// Validity period of a certificate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateValidity {
+    #[allow(dead_code)]
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+/// Read the validity period from a PEM certificate, or bare base64 DER as some
+/// API responses return it.
+pub fn certificate_validity(pem: &str) -> Result<CertificateValidity, String> {
+    let body: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .flat_map(|line| line.chars().filter(|c| !c.is_whitespace()))
+        .collect();
+    let der = general_purpose::STANDARD
+        .decode(body)
+        .map_err(|e| format!("Certificate is not valid base64: {}", e))?;
+    validity_from_der(&der).ok_or_else(|| "Certificate is not a valid X.509 DER structure".to_string())
+}
+
+fn validity_from_der(der: &[u8]) -> Option<CertificateValidity> {
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signature }
+    let (_, certificate, _) = read_tlv(der, TAG_SEQUENCE)?;
+    let (_, tbs, _) = read_tlv(certificate, TAG_SEQUENCE)?;
+    // tbsCertificate ::= SEQUENCE { [0] version OPTIONAL, serialNumber, signature, issuer, validity, ... }
+    let mut rest = tbs;
+    if rest.first() == Some(&TAG_CONTEXT_0) {
+        rest = read_any(rest)?.2;
+    }
+    for _ in 0..3 {
+        rest = read_any(rest)?.2;
+    }
+    let (_, validity, _) = read_tlv(rest, TAG_SEQUENCE)?;
+    let (tag, not_before, rest) = read_any(validity)?;
+    let not_before = parse_time(tag, not_before)?;
+    let (tag, not_after, _) = read_any(rest)?;
+    let not_after = parse_time(tag, not_after)?;
+    Some(CertificateValidity { not_before, not_after })
+}
+
+fn read_tlv(data: &[u8], expected: u8) -> Option<(u8, &[u8], &[u8])> {
+    read_any(data).filter(|(tag, _, _)| *tag == expected)
+}
+
+/// Split one DER element into (tag, content, remaining bytes)
+fn read_any(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let tag = *data.first()?;
+    let first = *data.get(1)? as usize;
+    let (len, header) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let count = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let len = data.get(2..2 + count)?.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, 2 + count)
+    };
+    let content = data.get(header..header + len)?;
+    Some((tag, content, &data[header + len..]))
+}
+
+fn parse_time(tag: u8, content: &[u8]) -> Option<DateTime<Utc>> {
+    let text = std::str::from_utf8(content).ok()?;
+    let full = match tag {
+        // RFC 5280: two-digit years 50-99 are 19xx, 00-49 are 20xx
+        TAG_UTC_TIME => {
+            let year: u32 = text.get(..2)?.parse().ok()?;
+            format!("{}{}", if year >= 50 { "19" } else { "20" }, text)
+        }
+        TAG_GENERALIZED_TIME => text.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%SZ")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIBXzCCAQagAwIBAgIBATAKBggqhkjOPQQDAjAPMQ0wCwYDVQQDDAR0ZXN0MB4X
+DTI2MTAxNjAwMjQyNFoXDTI2MTExNTAwMjQyNFowDzENMAsGA1UEAwwEdGVzdDBZ
+MBMGByqGSM49AgEGCCqGSM49AwEHA0IABDosZc2//riAfwiIgdmi88lJOxMFNV4s
+D8O5VuT88pZxQPvr6AyxRShxF6f+C/DZuqn+vcTuXRMSSHy4L6OstUujUzBRMB0G
+A1UdDgQWBBT0nQU1mHcezq+5UczdhBqVPfoxZTAfBgNVHSMEGDAWgBT0nQU1mHce
+zq+5UczdhBqVPfoxZTAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0cAMEQC
+IBMFhPormm2ynWN5yToURTt5wL82QI7uJVBz/OZyzQg1AiAfeGaY2T6gf6mJEqH9
+ilwX/1g9wBgxvUY3A2QVwaQktg==
+-----END CERTIFICATE-----";
+
+    #[test]
+    fn test_certificate_validity() {
+        let validity = certificate_validity(TEST_CERT).unwrap();
+        assert_eq!(validity.not_before.to_rfc3339(), "2026-10-16T00:24:24+00:00");
+        assert_eq!(validity.not_after.to_rfc3339(), "2026-11-15T00:24:24+00:00");
+
+        // Bare base64 body without PEM armor
+        let bare: String = TEST_CERT.lines().filter(|l| !l.starts_with("-----")).collect();
+        assert_eq!(certificate_validity(&bare).unwrap(), validity);
+
+        assert!(certificate_validity("not a certificate").is_err());
+    }
+}
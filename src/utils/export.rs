@@ -0,0 +1,456 @@
+// Allow dead code - export helpers are shared by several tools
+#![allow(dead_code)]
+
+use serde_json::{Map, Value};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Environment variable overriding the directory exports are written under
+pub const EXPORT_DIR_ENV_VAR: &str = "ONELOGIN_MCP_EXPORT_DIR";
+
+/// Export directory: `ONELOGIN_MCP_EXPORT_DIR`, then `exports` in the platform config dir
+pub fn export_dir() -> Option<PathBuf> {
+    std::env::var(EXPORT_DIR_ENV_VAR)
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| dirs::config_dir().map(|d| d.join("onelogin-mcp").join("exports")))
+}
+
+/// Resolve a tool-supplied `output_path` inside the export directory
+pub fn resolve_output_path(requested: &str) -> std::io::Result<PathBuf> {
+    let dir = export_dir().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No export directory available; set {}", EXPORT_DIR_ENV_VAR),
+        )
+    })?;
+    let path = resolve_under(&dir, requested)?;
+
+    // Create the directories, then make sure no symlink leads back out of the export dir
+    let parent = path.parent().unwrap_or(&dir);
+    std::fs::create_dir_all(parent)?;
+    let root = dir.canonicalize()?;
+    if !parent.canonicalize()?.starts_with(&root) {
+        return Err(invalid_path(requested, "resolves outside the export directory"));
+    }
+    Ok(path)
+}
+
+/// Join a relative `requested` path onto `dir`, refusing absolute paths and `..`
+fn resolve_under(dir: &Path, requested: &str) -> std::io::Result<PathBuf> {
+    let relative = Path::new(requested.trim());
+    let mut has_name = false;
+    for component in relative.components() {
+        match component {
+            Component::Normal(_) => has_name = true,
+            Component::CurDir => {}
+            Component::ParentDir => return Err(invalid_path(requested, "must not contain '..'")),
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(invalid_path(requested, "must be relative to the export directory"))
+            }
+        }
+    }
+    if !has_name {
+        return Err(invalid_path(requested, "must name a file"));
+    }
+    Ok(dir.join(relative))
+}
+
+fn invalid_path(requested: &str, reason: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("output_path '{}' {}", requested, reason),
+    )
+}
+
+/// Open `path` for writing; an existing file is only replaced when `overwrite` is set
+fn open_export_file(path: &Path, overwrite: bool) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    options.open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AlreadyExists {
+            std::io::Error::new(
+                e.kind(),
+                format!("{} already exists; pass overwrite: true to replace it", path.display()),
+            )
+        } else {
+            e
+        }
+    })
+}
+
+/// Output format for file exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// Parse an explicit format name, falling back to the file extension, then CSV
+    pub fn resolve(format: Option<&str>, path: &Path) -> Self {
+        match format.map(|f| f.to_ascii_lowercase()) {
+            Some(f) if f == "json" => ExportFormat::Json,
+            Some(f) if f == "csv" => ExportFormat::Csv,
+            Some(f) if f == "ndjson" || f == "jsonl" => ExportFormat::Ndjson,
+            _ => match path.extension().and_then(|e| e.to_str()) {
+                Some(ext) if ext.eq_ignore_ascii_case("json") => ExportFormat::Json,
+                Some(ext) if ext.eq_ignore_ascii_case("ndjson") || ext.eq_ignore_ascii_case("jsonl") => {
+                    ExportFormat::Ndjson
+                }
+                _ => ExportFormat::Csv,
+            },
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Summary of a completed file export
+#[derive(Debug, Clone)]
+pub struct ExportSummary {
+    pub path: PathBuf,
+    pub format: ExportFormat,
+    pub row_count: usize,
+    pub bytes_written: usize,
+}
+
+/// Extract the row list from a result payload.
+///
+/// Accepts a bare array, or an object wrapping the rows in `data`, `rows`,
+/// `results` or `records`. Any other value is treated as a single row.
+pub fn rows_from_value(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items.clone(),
+        Value::Object(map) => {
+            for key in ["data", "rows", "results", "records"] {
+                if let Some(Value::Array(items)) = map.get(key) {
+                    return items.clone();
+                }
+            }
+            vec![value.clone()]
+        }
+        Value::Null => Vec::new(),
+        other => vec![other.clone()],
+    }
+}
+
+/// Render rows as CSV. Columns are the union of object keys in first-seen order;
+/// nested values are written as compact JSON.
+pub fn rows_to_csv(rows: &[Value]) -> String {
+    let headers = csv_headers(rows);
+
+    let mut out = String::new();
+    if headers.is_empty() {
+        // Scalar rows: single "value" column
+        out.push_str("value\n");
+        for row in rows {
+            out.push_str(&csv_field(row));
+            out.push('\n');
+        }
+        return out;
+    }
+
+    out.push_str(
+        &headers
+            .iter()
+            .map(|h| csv_escape(h))
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    out.push('\n');
+
+    let empty = Map::new();
+    for row in rows {
+        let map = row.as_object().unwrap_or(&empty);
+        let line = headers
+            .iter()
+            .map(|h| map.get(h).map(csv_field).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_escape(s),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        other => csv_escape(&other.to_string()),
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Write a result payload to `path` as CSV or JSON, creating parent directories as needed.
+/// An existing file is left alone unless `overwrite` is set.
+pub fn write_rows_to_file(
+    value: &Value,
+    path: &Path,
+    format: ExportFormat,
+    overwrite: bool,
+) -> std::io::Result<ExportSummary> {
+    let rows = rows_from_value(value);
+    let body = match format {
+        ExportFormat::Csv => rows_to_csv(&rows),
+        ExportFormat::Json => serde_json::to_string_pretty(&rows)?,
+        ExportFormat::Ndjson => rows.iter().map(|row| format!("{}\n", row)).collect(),
+    };
+
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    let mut file = open_export_file(path, overwrite)?;
+    file.write_all(body.as_bytes())?;
+
+    Ok(ExportSummary {
+        path: path.to_path_buf(),
+        format,
+        row_count: rows.len(),
+        bytes_written: body.len(),
+    })
+}
+
+/// Writes rows to a file as they arrive, so large exports never sit in memory.
+///
+/// CSV columns are fixed by the first batch written (union of its keys); keys
+/// first seen in later rows are left out. NDJSON and JSON keep every field.
+pub struct RowStreamWriter {
+    out: BufWriter<File>,
+    path: PathBuf,
+    format: ExportFormat,
+    headers: Option<Vec<String>>,
+    row_count: usize,
+    bytes_written: usize,
+}
+
+impl RowStreamWriter {
+    /// Create `path`, creating parent directories as needed. An existing file is
+    /// only truncated when `overwrite` is set.
+    pub fn create(path: &Path, format: ExportFormat, overwrite: bool) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let mut writer = Self {
+            out: BufWriter::new(open_export_file(path, overwrite)?),
+            path: path.to_path_buf(),
+            format,
+            headers: None,
+            row_count: 0,
+            bytes_written: 0,
+        };
+        if format == ExportFormat::Json {
+            writer.write_str("[")?;
+        }
+        Ok(writer)
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Append a batch of rows
+    pub fn write_rows(&mut self, rows: &[Value]) -> std::io::Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        match self.format {
+            ExportFormat::Ndjson => {
+                for row in rows {
+                    self.write_str(&format!("{}\n", row))?;
+                }
+            }
+            ExportFormat::Json => {
+                for row in rows {
+                    let separator = if self.row_count == 0 { "\n" } else { ",\n" };
+                    self.write_str(&format!("{}  {}", separator, row))?;
+                    self.row_count += 1;
+                }
+                return self.out.flush();
+            }
+            ExportFormat::Csv => {
+                if self.headers.is_none() {
+                    let headers = csv_headers(rows);
+                    let line = if headers.is_empty() {
+                        "value".to_string()
+                    } else {
+                        headers.iter().map(|h| csv_escape(h)).collect::<Vec<_>>().join(",")
+                    };
+                    self.write_str(&format!("{}\n", line))?;
+                    self.headers = Some(headers);
+                }
+                let headers = self.headers.clone().unwrap_or_default();
+                let empty = Map::new();
+                for row in rows {
+                    let line = if headers.is_empty() {
+                        csv_field(row)
+                    } else {
+                        let map = row.as_object().unwrap_or(&empty);
+                        headers
+                            .iter()
+                            .map(|h| map.get(h).map(csv_field).unwrap_or_default())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    };
+                    self.write_str(&format!("{}\n", line))?;
+                }
+            }
+        }
+        self.row_count += rows.len();
+        self.out.flush()
+    }
+
+    /// Close the file and summarize what was written
+    pub fn finish(mut self) -> std::io::Result<ExportSummary> {
+        if self.format == ExportFormat::Json {
+            let close = if self.row_count == 0 { "]\n" } else { "\n]\n" };
+            self.write_str(close)?;
+        }
+        self.out.flush()?;
+        Ok(ExportSummary {
+            path: self.path,
+            format: self.format,
+            row_count: self.row_count,
+            bytes_written: self.bytes_written,
+        })
+    }
+
+    fn write_str(&mut self, s: &str) -> std::io::Result<()> {
+        self.out.write_all(s.as_bytes())?;
+        self.bytes_written += s.len();
+        Ok(())
+    }
+}
+
+/// Union of object keys in first-seen order
+fn csv_headers(rows: &[Value]) -> Vec<String> {
+    let mut headers: Vec<String> = Vec::new();
+    for row in rows {
+        if let Value::Object(map) = row {
+            for key in map.keys() {
+                if !headers.iter().any(|h| h == key) {
+                    headers.push(key.clone());
+                }
+            }
+        }
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rows_to_csv_union_headers_and_escaping() {
+        let rows = vec![
+            json!({"id": 1, "name": "Smith, John"}),
+            json!({"id": 2, "email": "a\"b@example.com"}),
+        ];
+        let csv = rows_to_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "id,name,email");
+        assert_eq!(lines[1], "1,\"Smith, John\",");
+        assert_eq!(lines[2], "2,,\"a\"\"b@example.com\"");
+    }
+
+    #[test]
+    fn test_rows_from_wrapped_value() {
+        assert_eq!(rows_from_value(&json!({"data": [1, 2, 3]})).len(), 3);
+        assert_eq!(rows_from_value(&json!({"id": 1})).len(), 1);
+        assert!(rows_from_value(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_format_resolution() {
+        assert_eq!(ExportFormat::resolve(None, Path::new("out.json")), ExportFormat::Json);
+        assert_eq!(ExportFormat::resolve(None, Path::new("out.txt")), ExportFormat::Csv);
+        assert_eq!(ExportFormat::resolve(Some("json"), Path::new("out.csv")), ExportFormat::Json);
+        assert_eq!(ExportFormat::resolve(None, Path::new("events.jsonl")), ExportFormat::Ndjson);
+        assert_eq!(ExportFormat::resolve(Some("ndjson"), Path::new("out.csv")), ExportFormat::Ndjson);
+    }
+
+    #[test]
+    fn test_resolve_under_rejects_escapes() {
+        let dir = Path::new("/exports");
+        assert_eq!(resolve_under(dir, "mfa.csv").unwrap(), dir.join("mfa.csv"));
+        assert_eq!(resolve_under(dir, "./nightly/mfa.csv").unwrap(), dir.join("./nightly/mfa.csv"));
+        assert!(resolve_under(dir, "/etc/passwd").is_err());
+        assert!(resolve_under(dir, "../mfa.csv").is_err());
+        assert!(resolve_under(dir, "nightly/../../mfa.csv").is_err());
+        assert!(resolve_under(dir, "").is_err());
+        assert!(resolve_under(dir, ".").is_err());
+    }
+
+    #[test]
+    fn test_write_rows_refuses_to_overwrite() {
+        let dir = std::env::temp_dir().join(format!("onelogin-mcp-overwrite-{}", std::process::id()));
+        let path = dir.join("report.csv");
+        let rows = json!([{"id": 1}]);
+        write_rows_to_file(&rows, &path, ExportFormat::Csv, false).unwrap();
+        assert!(write_rows_to_file(&rows, &path, ExportFormat::Csv, false).is_err());
+        assert!(write_rows_to_file(&rows, &path, ExportFormat::Csv, true).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_stream_writer_formats() {
+        let dir = std::env::temp_dir().join(format!("onelogin-mcp-export-{}", std::process::id()));
+        let first = vec![json!({"id": 1, "name": "Ann"}), json!({"id": 2})];
+        let second = vec![json!({"id": 3, "name": "Bo", "extra": true})];
+
+        let path = dir.join("users.csv");
+        let mut writer = RowStreamWriter::create(&path, ExportFormat::Csv, true).unwrap();
+        writer.write_rows(&first).unwrap();
+        writer.write_rows(&second).unwrap();
+        let summary = writer.finish().unwrap();
+        assert_eq!(summary.row_count, 3);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "id,name\n1,Ann\n2,\n3,Bo\n");
+
+        let path = dir.join("users.ndjson");
+        let mut writer = RowStreamWriter::create(&path, ExportFormat::Ndjson, true).unwrap();
+        writer.write_rows(&first).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        let path = dir.join("users.json");
+        let mut writer = RowStreamWriter::create(&path, ExportFormat::Json, true).unwrap();
+        writer.write_rows(&first).unwrap();
+        writer.write_rows(&second).unwrap();
+        let summary = writer.finish().unwrap();
+        let parsed: Vec<Value> = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(summary.bytes_written as u64, std::fs::metadata(&path).unwrap().len());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,159 @@
+//! IP geolocation for login analysis.
+//!
+//! Lookups go to the service in `GEOIP_URL`, a URL template with an `{ip}`
+//! placeholder, e.g. `https://ipinfo.io/{ip}/json?token=...`. There is no
+//! default: every looked-up login IP is sent to that service, so it has to be
+//! chosen explicitly. Responses may give coordinates as `lat`/`lon`,
+//! `latitude`/`longitude` or ipinfo's `"loc": "lat,lon"`.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+pub const GEOIP_URL_ENV_VAR: &str = "GEOIP_URL";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GeoLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub city: Option<String>,
+    pub country: Option<String>,
+}
+
+/// The lookup URL template from `GEOIP_URL`, if set
+pub fn url_from_env() -> Option<String> {
+    std::env::var(GEOIP_URL_ENV_VAR).ok().filter(|url| url.contains("{ip}"))
+}
+
+/// Whether `ip` is a routable address a GeoIP service can place (not private, loopback or link-local)
+pub fn is_public(ip: &str) -> bool {
+    match ip.trim().parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => {
+            !(v4.is_private() || v4.is_loopback() || v4.is_link_local() || v4.is_unspecified() || v4.is_broadcast())
+        }
+        Ok(IpAddr::V6(v6)) => {
+            let first = v6.segments()[0];
+            // fc00::/7 unique local, fe80::/10 link-local
+            !(v6.is_loopback() || v6.is_unspecified() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80)
+        }
+        Err(_) => false,
+    }
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn text(body: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|k| body.get(*k).and_then(Value::as_str))
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+/// Location from a GeoIP service response, whichever common field names it uses
+pub fn parse_location(body: &Value) -> Option<GeoLocation> {
+    let (latitude, longitude) = match body.get("loc").and_then(Value::as_str) {
+        Some(loc) => {
+            let (lat, lon) = loc.split_once(',')?;
+            (lat.trim().parse().ok()?, lon.trim().parse().ok()?)
+        }
+        None => (
+            number(body.get("lat").or_else(|| body.get("latitude")))?,
+            number(body.get("lon").or_else(|| body.get("lng")).or_else(|| body.get("longitude")))?,
+        ),
+    };
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+    Some(GeoLocation {
+        latitude,
+        longitude,
+        city: text(body, &["city"]),
+        country: text(body, &["country_code", "countryCode", "country", "country_name"]),
+    })
+}
+
+/// Great-circle distance in kilometres
+pub fn distance_km(a: &GeoLocation, b: &GeoLocation) -> f64 {
+    let (lat1, lat2) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.longitude - a.longitude).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+/// Look up each IP with the service at `template`. Failed lookups are returned as
+/// messages instead of failing the whole batch.
+pub async fn lookup_all(template: &str, ips: &[String]) -> Result<(HashMap<String, GeoLocation>, Vec<String>), String> {
+    let http = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build GeoIP client: {}", e))?;
+    let mut located = HashMap::new();
+    let mut errors = Vec::new();
+    for ip in ips {
+        let url = template.replace("{ip}", &urlencoding::encode(ip));
+        let body = match http.get(&url).send().await {
+            Ok(response) if response.status().is_success() => response.json::<Value>().await.ok(),
+            Ok(response) => {
+                errors.push(format!("GeoIP lookup of {} returned {}", ip, response.status()));
+                continue;
+            }
+            Err(e) => {
+                errors.push(format!("GeoIP lookup of {} failed: {}", ip, e));
+                continue;
+            }
+        };
+        match body.as_ref().and_then(parse_location) {
+            Some(location) => {
+                located.insert(ip.clone(), location);
+            }
+            None => errors.push(format!("GeoIP service has no location for {}", ip)),
+        }
+    }
+    Ok((located, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_location() {
+        let ipinfo = parse_location(&json!({"ip": "8.8.8.8", "city": "Mountain View", "country": "US", "loc": "37.4056,-122.0775"})).unwrap();
+        assert_eq!(ipinfo.latitude, 37.4056);
+        assert_eq!(ipinfo.country.as_deref(), Some("US"));
+        let ip_api = parse_location(&json!({"status": "success", "countryCode": "DE", "city": "Berlin", "lat": 52.52, "lon": 13.405})).unwrap();
+        assert_eq!(ip_api.country.as_deref(), Some("DE"));
+        assert!(parse_location(&json!({"latitude": "48.85", "longitude": "2.35"})).is_some());
+        assert!(parse_location(&json!({"status": "fail"})).is_none());
+        assert!(parse_location(&json!({"lat": 123.0, "lon": 0.0})).is_none());
+    }
+
+    #[test]
+    fn test_distance_and_public_ips() {
+        let london = GeoLocation { latitude: 51.5074, longitude: -0.1278, city: None, country: None };
+        let new_york = GeoLocation { latitude: 40.7128, longitude: -74.006, city: None, country: None };
+        let km = distance_km(&london, &new_york);
+        assert!((5550.0..5600.0).contains(&km), "{}", km);
+        assert_eq!(distance_km(&london, &london), 0.0);
+
+        assert!(is_public("8.8.8.8"));
+        assert!(is_public("2001:4860:4860::8888"));
+        assert!(!is_public("10.1.2.3"));
+        assert!(!is_public("127.0.0.1"));
+        assert!(!is_public("fd00::1"));
+        assert!(!is_public("not-an-ip"));
+    }
+}
@@ -0,0 +1,348 @@
+//! Local execution of Smart Hook functions in an embedded JavaScript engine (Boa).
+//!
+//! Runs `exports.handler(context)` with a sample payload so a hook can be tried
+//! before it is deployed. The sandbox has no network or filesystem: `require`
+//! only returns caller-supplied mocks, `process.env` holds the given variables,
+//! and `console` output is captured.
+//!
+//! The engine's loop and recursion limits only stop single runaway loops; nested
+//! loops or builtins such as `Array(1e9).fill(0)` can still run for an unbounded
+//! time. The server therefore runs each hook in a child process of its own
+//! executable (`run_hook_isolated`), killed after `RUN_TIMEOUT` and, on Unix,
+//! limited to `MEMORY_LIMIT_BYTES` of heap. At most `MAX_CONCURRENT_RUNS` run at once.
+
+use anyhow::{anyhow, Context as _};
+use boa_engine::builtins::promise::PromiseState;
+use boa_engine::object::builtins::JsPromise;
+use boa_engine::{Context, JsValue, Source};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
+
+const LOOP_ITERATION_LIMIT: u64 = 1_000_000;
+const RECURSION_LIMIT: usize = 256;
+/// Wall-clock limit for one isolated run; the child process is killed when it passes
+const RUN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Heap an isolated run may use (Unix only)
+const MEMORY_LIMIT_BYTES: u64 = 512 * 1024 * 1024;
+/// Isolated runs at a time; further calls wait for a slot
+const MAX_CONCURRENT_RUNS: usize = 2;
+static RUN_SLOTS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_RUNS);
+
+/// Hook types with a built-in sample payload
+pub const SAMPLE_HOOK_TYPES: &[&str] = &["pre-authentication", "user-migration"];
+
+/// A hook run as handed to the child process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookJob {
+    pub function: String,
+    pub payload: Value,
+    pub env: HashMap<String, String>,
+    pub mocks: HashMap<String, Value>,
+}
+
+/// Result of running a hook locally
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookRun {
+    /// The value the handler returned (or resolved to)
+    pub result: Option<Value>,
+    /// Thrown error or rejection reason
+    pub error: Option<String>,
+    /// Captured console output
+    pub logs: Vec<String>,
+}
+
+/// Defines `exports`, `console`, `process.env` and `require` before the hook code runs
+fn prelude(env: &HashMap<String, String>, mocks: &HashMap<String, Value>) -> String {
+    format!(
+        r#"var exports = {{}};
+var module = {{ exports: exports }};
+var __logs = [];
+var console = {{ log: function () {{
+  __logs.push(Array.prototype.map.call(arguments, function (a) {{
+    return typeof a === 'string' ? a : JSON.stringify(a);
+  }}).join(' '));
+}} }};
+console.info = console.warn = console.error = console.debug = console.log;
+var process = {{ env: JSON.parse({env}) }};
+var __mocks = JSON.parse({mocks});
+function require(name) {{
+  if (Object.prototype.hasOwnProperty.call(__mocks, name)) return __mocks[name];
+  throw new Error("Package '" + name + "' is not available in the local harness; pass a mock for it");
+}}
+"#,
+        env = js_string_literal(&json!(env)),
+        mocks = js_string_literal(&json!(mocks)),
+    )
+}
+
+/// A JS string literal holding `value` as JSON text
+fn js_string_literal(value: &Value) -> String {
+    serde_json::to_string(&value.to_string()).unwrap_or_else(|_| "\"{}\"".to_string())
+}
+
+/// Run `function` (plain JavaScript defining `exports.handler`) against `payload` in this process.
+/// Only the loop and recursion limits apply; the server uses `run_hook_isolated`.
+pub fn run_hook(
+    function: &str,
+    payload: &Value,
+    env: &HashMap<String, String>,
+    mocks: &HashMap<String, Value>,
+) -> HookRun {
+    let mut context = Context::default();
+    context.runtime_limits_mut().set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+    context.runtime_limits_mut().set_recursion_limit(RECURSION_LIMIT);
+
+    let mut run = HookRun {
+        result: None,
+        error: None,
+        logs: Vec::new(),
+    };
+    let invoke = format!(
+        "(module.exports.handler || exports.handler)(JSON.parse({}))",
+        js_string_literal(payload)
+    );
+    let outcome = context
+        .eval(Source::from_bytes(&prelude(env, mocks)))
+        .and_then(|_| context.eval(Source::from_bytes(function)))
+        .and_then(|_| context.eval(Source::from_bytes(
+            "if (typeof (module.exports.handler || exports.handler) !== 'function') \
+             throw new Error('The function must export a handler: exports.handler = async (context) => { ... }');",
+        )))
+        .and_then(|_| context.eval(Source::from_bytes(&invoke)));
+
+    match outcome {
+        Ok(value) => match settle(value, &mut context) {
+            Ok(value) if value.is_undefined() => run.error = Some("The handler returned undefined".to_string()),
+            Ok(value) => match value.to_json(&mut context) {
+                Ok(json) => run.result = Some(integral_numbers(json)),
+                Err(e) => run.error = Some(format!("The handler returned a value that is not JSON: {}", e)),
+            },
+            Err(reason) => run.error = Some(reason),
+        },
+        Err(e) => run.error = Some(e.to_string()),
+    }
+
+    if let Ok(logs) = context.eval(Source::from_bytes("__logs")).and_then(|l| l.to_json(&mut context)) {
+        run.logs = serde_json::from_value(logs).unwrap_or_default();
+    }
+    run
+}
+
+impl HookRun {
+    fn failed(error: String) -> Self {
+        Self {
+            result: None,
+            error: Some(error),
+            logs: Vec::new(),
+        }
+    }
+}
+
+/// Run `job` in a child process of this executable (the hidden `run-hook` command),
+/// killed after `RUN_TIMEOUT` or when the returned future is dropped
+pub async fn run_hook_isolated(job: &HookJob) -> anyhow::Result<HookRun> {
+    let _slot = RUN_SLOTS.acquire().await?;
+    let exe = std::env::current_exe().context("Failed to locate the server executable")?;
+    let mut child = tokio::process::Command::new(exe)
+        .arg("run-hook")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start the hook runner")?;
+    let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("Hook runner has no stdin"))?;
+    stdin.write_all(&serde_json::to_vec(job)?).await?;
+    drop(stdin);
+
+    let output = match tokio::time::timeout(RUN_TIMEOUT, child.wait_with_output()).await {
+        Ok(output) => output.context("Hook runner failed")?,
+        Err(_) => {
+            return Ok(HookRun::failed(format!(
+                "The handler did not finish within {} seconds and was stopped",
+                RUN_TIMEOUT.as_secs()
+            )))
+        }
+    };
+    if !output.status.success() {
+        return Ok(HookRun::failed(format!(
+            "The handler was stopped ({}); it may have used more than {} MiB of memory",
+            output.status,
+            MEMORY_LIMIT_BYTES / (1024 * 1024)
+        )));
+    }
+    serde_json::from_slice(&output.stdout).context("Hook runner returned an unreadable result")
+}
+
+/// Entry point of the `run-hook` command: read a `HookJob` from stdin and write the `HookRun` to stdout
+pub fn run_job_from_stdin() -> anyhow::Result<()> {
+    limit_memory();
+    let mut input = Vec::new();
+    std::io::stdin().read_to_end(&mut input)?;
+    let job: HookJob = serde_json::from_slice(&input).context("Invalid hook job")?;
+    let run = run_hook(&job.function, &job.payload, &job.env, &job.mocks);
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &run)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Cap the heap of the current process so an allocating handler aborts instead of exhausting memory
+#[cfg(unix)]
+fn limit_memory() {
+    let limit = libc::rlimit {
+        rlim_cur: MEMORY_LIMIT_BYTES as libc::rlim_t,
+        rlim_max: MEMORY_LIMIT_BYTES as libc::rlim_t,
+    };
+    // SAFETY: setrlimit only reads the struct it is given
+    unsafe {
+        libc::setrlimit(libc::RLIMIT_DATA, &limit);
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_memory() {}
+
+/// Boa converts every JS number to an `f64`; turn whole ones back into integers
+/// so IDs such as `policy_id` compare and serialize as they would from Node
+fn integral_numbers(value: Value) -> Value {
+    match value {
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < i64::MAX as f64 => json!(f as i64),
+            _ => Value::Number(n),
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(integral_numbers).collect()),
+        Value::Object(map) => Value::Object(map.into_iter().map(|(k, v)| (k, integral_numbers(v))).collect()),
+        other => other,
+    }
+}
+
+/// Resolve a returned promise by running the job queue
+fn settle(value: JsValue, context: &mut Context) -> Result<JsValue, String> {
+    let Some(promise) = value.as_object().and_then(|object| JsPromise::from_object(object.clone()).ok()) else {
+        return Ok(value);
+    };
+    context.run_jobs();
+    match promise.state() {
+        PromiseState::Fulfilled(value) => Ok(value),
+        PromiseState::Rejected(reason) => Err(reason
+            .to_string(context)
+            .map(|s| s.to_std_string_escaped())
+            .unwrap_or_else(|_| "The handler's promise was rejected".to_string())),
+        PromiseState::Pending => Err("The handler's promise never settled (awaiting something that is not available locally?)".to_string()),
+    }
+}
+
+/// Sample `context` for a hook type, shaped like OneLogin's payloads
+pub fn sample_payload(hook_type: &str) -> Option<Value> {
+    match hook_type {
+        "pre-authentication" => Some(json!({
+            "user": {
+                "user_identifier": "jane.doe@example.com",
+                "id": 12345678,
+                "policy_id": 100,
+                "groups": [{"id": 1, "name": "Default"}],
+                "roles": [{"id": 10, "name": "Employee"}]
+            },
+            "app": {"id": 555, "name": "Example App"},
+            "risk": {"score": 12, "reasons": []},
+            "location": {"ip": "203.0.113.10", "country_code": "US", "city": "Boston"},
+            "correlation_id": "00000000-0000-0000-0000-000000000000"
+        })),
+        "user-migration" => Some(json!({
+            "user_identifier": "jane.doe@example.com",
+            "password": "sample-password",
+            "correlation_id": "00000000-0000-0000-0000-000000000000"
+        })),
+        _ => None,
+    }
+}
+
+/// Problems with a handler's return value for the given hook type
+pub fn check_decision(hook_type: &str, result: &Value) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let Some(object) = result.as_object() else {
+        warnings.push("The handler must return an object such as { success: true, user: {...} }".to_string());
+        return warnings;
+    };
+    match object.get("success") {
+        Some(Value::Bool(_)) => {}
+        _ => warnings.push("'success' must be a boolean".to_string()),
+    }
+    let success = object.get("success") == Some(&Value::Bool(true));
+    match hook_type {
+        "pre-authentication" => {
+            if let Some(policy_id) = result.pointer("/user/policy_id") {
+                if !policy_id.is_i64() && !policy_id.is_null() {
+                    warnings.push("user.policy_id must be a numeric policy ID".to_string());
+                }
+            } else if success {
+                warnings.push("No user.policy_id returned; the user keeps their current policy".to_string());
+            }
+        }
+        "user-migration" if success => {
+            let user = object.get("user").and_then(Value::as_object);
+            if !user.is_some_and(|u| u.contains_key("email") || u.contains_key("username")) {
+                warnings.push("A successful migration must return user with at least an email or username".to_string());
+            }
+        }
+        _ => {}
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(function: &str, payload: Value) -> HookRun {
+        let mocks = HashMap::from([("greeting".to_string(), json!({"text": "hi"}))]);
+        let env = HashMap::from([("POLICY".to_string(), "42".to_string())]);
+        run_hook(function, &payload, &env, &mocks)
+    }
+
+    #[test]
+    fn test_async_handler_with_env_mocks_and_logs() {
+        let function = r#"
+            const greeting = require('greeting');
+            exports.handler = async (context) => {
+              console.log('user', context.user.id, greeting.text);
+              return { success: true, user: { policy_id: parseInt(process.env.POLICY) } };
+            };
+        "#;
+        let result = run(function, sample_payload("pre-authentication").unwrap());
+        assert_eq!(result.error, None);
+        assert_eq!(result.result, Some(json!({"success": true, "user": {"policy_id": 42}})));
+        assert_eq!(result.logs, vec!["user 12345678 hi"]);
+        assert!(check_decision("pre-authentication", &result.result.unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        let thrown = run("exports.handler = async () => { throw new Error('boom'); };", json!({}));
+        assert!(thrown.error.unwrap().contains("boom"));
+
+        let missing = run("exports.handler = () => require('axios');", json!({}));
+        assert!(missing.error.unwrap().contains("pass a mock"));
+
+        let runaway = run("exports.handler = () => { while (true) {} };", json!({}));
+        assert!(runaway.error.is_some());
+
+        let no_handler = run("const x = 1;", json!({}));
+        assert!(no_handler.error.unwrap().contains("must export a handler"));
+    }
+
+    #[test]
+    fn test_check_decision() {
+        assert!(check_decision("pre-authentication", &json!("yes"))[0].contains("must return an object"));
+        assert!(check_decision("pre-authentication", &json!({"success": "true"}))[0].contains("boolean"));
+        assert!(check_decision("user-migration", &json!({"success": true, "user": {}}))[0].contains("email or username"));
+        assert!(check_decision("user-migration", &json!({"success": false})).is_empty());
+    }
+}
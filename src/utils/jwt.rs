@@ -0,0 +1,180 @@
+//! Local validation of JWT access tokens against a tenant's JWKS.
+//!
+//! Used by `onelogin_introspect_oauth_token` to answer for JWTs without a call to
+//! the introspection endpoint: the RS256/RS384/RS512 signature, `iss`,
+//! `exp`/`nbf` and, when an audience is given, `aud` are checked. Revocation
+//! cannot be seen locally, so a token that passes is only reported `active`
+//! when the caller accepts that; opaque tokens still go to the remote endpoint.
+
+use crate::models::oidc::{Jwk, Jwks};
+use base64::{engine::general_purpose, Engine as _};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde_json::{json, Value};
+
+/// Seconds of clock skew tolerated for `exp` and `nbf`
+const LEEWAY_SECONDS: u64 = 30;
+
+/// Whether `token` is a JWT (three segments with a JSON header naming an algorithm)
+pub fn looks_like_jwt(token: &str) -> bool {
+    let mut parts = token.split('.');
+    let (Some(header), Some(_), Some(_), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(header.trim_end_matches('='))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+        .is_some_and(|h| h.get("alg").is_some())
+}
+
+/// The `kid` from a JWT header
+pub fn key_id(token: &str) -> Option<String> {
+    decode_header(token).ok()?.kid
+}
+
+/// All claims of a JWT, without verifying its signature. Only for reporting what a token
+/// carries; use `introspect_locally` to decide whether it is valid.
+pub fn decode_claims(token: &str) -> Option<Value> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = general_purpose::URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice::<Value>(&bytes).ok().filter(Value::is_object)
+}
+
+/// Validate `token` and build an introspection-shaped response; `valid` says whether every
+/// local check passed. Invalid tokens are reported as `active: false` with a reason, like the
+/// remote endpoint. Valid ones are `active: true` only with `accept_unchecked_revocation`,
+/// otherwise `active: null`, since a revoked token looks the same locally.
+pub fn introspect_locally(
+    token: &str,
+    jwks: &Jwks,
+    issuer: &str,
+    audience: &[String],
+    accept_unchecked_revocation: bool,
+) -> Result<Value, String> {
+    let header = decode_header(token).map_err(|e| format!("Invalid JWT header: {}", e))?;
+    if !matches!(header.alg, Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512) {
+        return Err(format!("Unsupported JWT algorithm {:?}; only RS256/RS384/RS512 are validated locally", header.alg));
+    }
+    let jwk = find_key(jwks, header.kid.as_deref())
+        .ok_or_else(|| format!("No key in the tenant JWKS matches kid {:?}", header.kid))?;
+    let (Some(n), Some(e)) = (jwk.n.as_deref(), jwk.e.as_deref()) else {
+        return Err(format!("JWKS key {} is not an RSA key", jwk.kid));
+    };
+    let key = DecodingKey::from_rsa_components(n, e).map_err(|e| format!("Invalid JWKS key {}: {}", jwk.kid, e))?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.leeway = LEEWAY_SECONDS;
+    validation.validate_nbf = true;
+    validation.set_issuer(&[issuer]);
+    if audience.is_empty() {
+        validation.validate_aud = false;
+    } else {
+        validation.set_audience(audience);
+    }
+
+    let checks = json!({"signature": true, "iss": true, "exp": true, "aud": !audience.is_empty(), "revocation": false});
+    match decode::<Value>(token, &key, &validation) {
+        Ok(data) => Ok(claims_to_introspection(&data.claims, checks, accept_unchecked_revocation)),
+        Err(e) => Ok(json!({
+            "active": false,
+            "valid": false,
+            "reason": e.to_string(),
+            "validated": "local",
+            "checks": checks
+        })),
+    }
+}
+
+fn find_key<'a>(jwks: &'a Jwks, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => jwks.keys.iter().find(|k| k.kid == kid),
+        // Without a kid only an unambiguous single key can be used
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+}
+
+/// Map verified claims to the fields the introspection endpoint returns
+fn claims_to_introspection(claims: &Value, checks: Value, accept_unchecked_revocation: bool) -> Value {
+    let aud = match claims.get("aud") {
+        Some(Value::String(aud)) => json!([aud]),
+        Some(aud) => aud.clone(),
+        None => Value::Null,
+    };
+    let mut result = json!({
+        "active": accept_unchecked_revocation,
+        "valid": true,
+        "scope": claims.get("scope"),
+        "client_id": claims.get("client_id").or_else(|| claims.get("azp")),
+        "username": claims.get("username").or_else(|| claims.get("preferred_username")),
+        "token_type": "access_token",
+        "exp": claims.get("exp"),
+        "iat": claims.get("iat"),
+        "nbf": claims.get("nbf"),
+        "sub": claims.get("sub"),
+        "aud": aud,
+        "iss": claims.get("iss"),
+        "jti": claims.get("jti"),
+        "validated": "local",
+        "checks": checks
+    });
+    if !accept_unchecked_revocation {
+        result["active"] = Value::Null;
+        result["reason"] = json!("Signature and claims are valid, but revocation was not checked");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUER: &str = "https://example.onelogin.com/oidc/2";
+
+    fn segment(value: Value) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(value.to_string())
+    }
+
+    #[test]
+    fn test_looks_like_jwt() {
+        let header = segment(json!({"alg": "RS256", "kid": "k1"}));
+        let token = format!("{}.{}.sig", header, segment(json!({"sub": "1"})));
+        assert!(looks_like_jwt(&token));
+        assert_eq!(key_id(&token).as_deref(), Some("k1"));
+
+        assert!(!looks_like_jwt("opaque-token-value"));
+        assert!(!looks_like_jwt("a.b.c"));
+        assert!(!looks_like_jwt(&format!("{}.x.y.z", header)));
+    }
+
+    #[test]
+    fn test_decode_claims() {
+        let token = format!("{}.{}.sig", segment(json!({"alg": "RS256"})), segment(json!({"sub": "1", "scope": "read"})));
+        assert_eq!(decode_claims(&token).unwrap()["scope"], "read");
+        assert!(decode_claims("opaque-token-value").is_none());
+    }
+
+    #[test]
+    fn test_rejects_unknown_key_and_algorithm() {
+        let jwks = Jwks { keys: Vec::new() };
+        let rs = format!("{}.{}.sig", segment(json!({"alg": "RS256", "kid": "gone"})), segment(json!({})));
+        assert!(introspect_locally(&rs, &jwks, ISSUER, &[], false).unwrap_err().contains("No key"));
+
+        let hs = format!("{}.{}.sig", segment(json!({"alg": "HS256"})), segment(json!({})));
+        assert!(introspect_locally(&hs, &jwks, ISSUER, &[], false).unwrap_err().contains("Unsupported JWT algorithm"));
+    }
+
+    #[test]
+    fn test_claims_to_introspection() {
+        let claims = json!({"sub": "42", "aud": "app-client", "exp": 1700000000, "scope": "openid", "azp": "app-client"});
+        let result = claims_to_introspection(&claims, json!({}), true);
+        assert_eq!(result["active"], true);
+        assert_eq!(result["aud"], json!(["app-client"]));
+        assert_eq!(result["client_id"], "app-client");
+        assert_eq!(result["validated"], "local");
+
+        let unchecked = claims_to_introspection(&claims, json!({}), false);
+        assert_eq!(unchecked["active"], Value::Null);
+        assert_eq!(unchecked["valid"], true);
+    }
+}
@@ -1,5 +1,18 @@
+pub mod anonymize;
+pub mod certs;
+pub mod export;
+pub mod geoip;
+pub mod hook_runtime;
+pub mod jwt;
+pub mod npm;
 pub mod pagination;
+pub mod redact;
+pub mod saml_metadata;
+pub mod schema_drift;
+pub mod secrets;
 pub mod serde_helpers;
+pub mod suggest;
+pub mod terraform;
 
 use base64::{engine::general_purpose, Engine as _};
 
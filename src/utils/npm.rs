@@ -0,0 +1,225 @@
+//! Pre-deploy check of Smart Hook `packages` against the npm registry.
+//!
+//! OneLogin installs hook packages when the hook is built; a missing package or
+//! version only shows up later as a failed build. Each `name: version` entry is
+//! checked up front: the name must be a valid npm name and not a Node built-in,
+//! the version must be exact, the package must be in `SMART_HOOK_ALLOWED_PACKAGES`
+//! when that is set, and the version must exist on the registry (`NPM_REGISTRY_URL`,
+//! default https://registry.npmjs.org).
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub const REGISTRY_ENV_VAR: &str = "NPM_REGISTRY_URL";
+pub const ALLOWED_PACKAGES_ENV_VAR: &str = "SMART_HOOK_ALLOWED_PACKAGES";
+
+const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Abbreviated package metadata: versions, dist-tags and deprecations only
+const ABBREVIATED_METADATA: &str = "application/vnd.npm.install-v1+json";
+
+/// Node modules that ship with the runtime and must not be listed as packages
+const NODE_BUILTINS: &[&str] = &[
+    "assert", "buffer", "child_process", "crypto", "dns", "events", "fs", "http", "https",
+    "net", "os", "path", "querystring", "stream", "string_decoder", "timers", "tls", "url",
+    "util", "zlib",
+];
+
+/// Outcome for one `name: version` entry
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageCheck {
+    pub name: String,
+    pub version: String,
+    pub ok: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Packages allowed by `SMART_HOOK_ALLOWED_PACKAGES` (comma-separated); `None` allows any
+pub fn allowed_from_env() -> Option<Vec<String>> {
+    std::env::var(ALLOWED_PACKAGES_ENV_VAR).ok().map(|list| {
+        list.split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect()
+    })
+}
+
+/// Check every package; registry lookups that fail outright (network, 5xx) are an `Err`
+pub async fn check_packages(packages: &HashMap<String, String>) -> Result<Vec<PackageCheck>, String> {
+    let registry = std::env::var(REGISTRY_ENV_VAR).unwrap_or_else(|_| DEFAULT_REGISTRY.to_string());
+    let allowed = allowed_from_env();
+    let http = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build npm registry client: {}", e))?;
+
+    let mut names: Vec<&String> = packages.keys().collect();
+    names.sort();
+    let mut checks = Vec::new();
+    for name in names {
+        let version = &packages[name];
+        let mut check = PackageCheck {
+            name: name.clone(),
+            version: version.clone(),
+            ok: true,
+            errors: validate_spec(name, version, allowed.as_deref()),
+            warnings: Vec::new(),
+        };
+        if check.errors.is_empty() {
+            match fetch_metadata(&http, &registry, name).await? {
+                Some(metadata) => check_version(&mut check, &metadata),
+                None => check.errors.push(format!(
+                    "Package '{}' does not exist on the npm registry; check the spelling",
+                    name
+                )),
+            }
+        }
+        check.ok = check.errors.is_empty();
+        checks.push(check);
+    }
+    Ok(checks)
+}
+
+async fn fetch_metadata(http: &reqwest::Client, registry: &str, name: &str) -> Result<Option<Value>, String> {
+    // Scoped names keep the '@' but encode the '/'
+    let url = format!("{}/{}", registry.trim_end_matches('/'), name.replace('/', "%2f"));
+    let response = http
+        .get(&url)
+        .header(reqwest::header::ACCEPT, ABBREVIATED_METADATA)
+        .send()
+        .await
+        .map_err(|e| format!("Could not reach the npm registry at {}: {}", registry, e))?;
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(None),
+        status if status.is_success() => response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| format!("Invalid npm registry response for '{}': {}", name, e)),
+        status => Err(format!("npm registry returned {} for '{}'", status, name)),
+    }
+}
+
+/// Checks that need no registry lookup
+fn validate_spec(name: &str, version: &str, allowed: Option<&[String]>) -> Vec<String> {
+    let mut errors = Vec::new();
+    if NODE_BUILTINS.contains(&name.trim_start_matches("node:")) {
+        errors.push(format!("'{}' is a Node.js built-in module; require it directly and remove it from packages", name));
+        return errors;
+    }
+    if !is_valid_name(name) {
+        errors.push(format!(
+            "'{}' is not a valid npm package name (lowercase, no spaces, optional @scope/ prefix)",
+            name
+        ));
+    }
+    if !is_exact_version(version) {
+        errors.push(format!(
+            "Version '{}' for '{}' is not an exact version; pin it, e.g. \"1.4.0\" instead of \"^1.4.0\" or \"latest\"",
+            version, name
+        ));
+    }
+    if let Some(allowed) = allowed {
+        if !allowed.iter().any(|a| a == name) {
+            errors.push(format!(
+                "'{}' is not in the supported package set ({}): {}",
+                name,
+                ALLOWED_PACKAGES_ENV_VAR,
+                allowed.join(", ")
+            ));
+        }
+    }
+    errors
+}
+
+/// Compare the requested version with the registry metadata
+fn check_version(check: &mut PackageCheck, metadata: &Value) {
+    let versions = metadata.get("versions").and_then(Value::as_object);
+    let latest = metadata.pointer("/dist-tags/latest").and_then(Value::as_str);
+    match versions.and_then(|v| v.get(&check.version)) {
+        Some(info) => {
+            if let Some(reason) = info.get("deprecated").and_then(Value::as_str) {
+                check.warnings.push(format!("{}@{} is deprecated: {}", check.name, check.version, reason));
+            }
+        }
+        None => {
+            let hint = latest.map(|l| format!("; the latest version is {}", l)).unwrap_or_default();
+            check.errors.push(format!(
+                "Version {} of '{}' does not exist on the npm registry{}",
+                check.version, check.name, hint
+            ));
+        }
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && !part.starts_with('_')
+            && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-._~".contains(c))
+    };
+    if name.len() > 214 {
+        return false;
+    }
+    match name.strip_prefix('@') {
+        Some(scoped) => matches!(scoped.split_once('/'), Some((scope, pkg)) if valid_part(scope) && valid_part(pkg)),
+        None => valid_part(name),
+    }
+}
+
+/// `major.minor.patch` with an optional pre-release/build suffix
+fn is_exact_version(version: &str) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or("");
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3 && parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_spec() {
+        assert!(validate_spec("axios", "1.4.0", None).is_empty());
+        assert!(validate_spec("@scope/pkg", "2.0.0-beta.1", None).is_empty());
+        assert!(validate_spec("crypto", "1.0.0", None)[0].contains("built-in"));
+        assert!(validate_spec("Axios", "1.4.0", None)[0].contains("not a valid npm package name"));
+        assert!(validate_spec("axios", "^1.4.0", None)[0].contains("not an exact version"));
+        assert!(validate_spec("axios", "latest", None)[0].contains("not an exact version"));
+
+        let allowed = vec!["lodash".to_string()];
+        assert!(validate_spec("lodash", "4.17.21", Some(&allowed)).is_empty());
+        assert!(validate_spec("axios", "1.4.0", Some(&allowed))[0].contains("supported package set"));
+    }
+
+    #[test]
+    fn test_check_version() {
+        let metadata = json!({
+            "dist-tags": {"latest": "1.6.0"},
+            "versions": {
+                "1.4.0": {},
+                "1.5.0": {"deprecated": "use 1.6.0"}
+            }
+        });
+        let check = |version: &str| {
+            let mut check = PackageCheck {
+                name: "axios".to_string(),
+                version: version.to_string(),
+                ok: true,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+            };
+            check_version(&mut check, &metadata);
+            check
+        };
+
+        assert!(check("1.4.0").errors.is_empty());
+        assert_eq!(check("1.5.0").warnings, vec!["axios@1.5.0 is deprecated: use 1.6.0"]);
+        assert!(check("9.9.9").errors[0].ends_with("the latest version is 1.6.0"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Redaction of credentials in logged HTTP traffic (`LOG_HTTP_BODIES`).
+//!
+//! Keys are matched by fragment, so `client_secret`, `state_token` and
+//! `new_password` are all caught without listing every field OneLogin uses.
+
+use serde_json::Value;
+
+/// Placeholder logged instead of a secret value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Key fragments marking a value as secret, matched case-insensitively
+const SENSITIVE_KEY_PARTS: &[&str] = &[
+    "password",
+    "secret",
+    "token",
+    "authorization",
+    "api_key",
+    "api-key",
+    "apikey",
+    "private_key",
+    "credential",
+    "cookie",
+];
+
+pub fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SENSITIVE_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+/// Copy of `value` with every sensitive field replaced by `REDACTED`
+pub fn redact_json(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| {
+                    let v = match v {
+                        Value::Null => Value::Null,
+                        _ if is_sensitive_key(k) => Value::String(REDACTED.to_string()),
+                        _ => redact_json(v),
+                    };
+                    (k.clone(), v)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_json).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Redact a JSON or form-encoded body; other text is returned unchanged
+pub fn redact_body(body: &str) -> String {
+    if let Ok(json) = serde_json::from_str::<Value>(body) {
+        return redact_json(&json).to_string();
+    }
+    let trimmed = body.trim();
+    if trimmed.contains('=') && !trimmed.contains(char::is_whitespace) {
+        return trimmed
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if is_sensitive_key(key) => format!("{}={}", key, REDACTED),
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+    }
+    body.to_string()
+}
+
+/// Header value safe to log; credentials keep only their scheme (e.g. `Bearer [REDACTED]`)
+pub fn redact_header(name: &str, value: &str) -> String {
+    if !is_sensitive_key(name) {
+        return value.to_string();
+    }
+    match value.split_once(' ') {
+        Some((scheme, _)) => format!("{} {}", scheme, REDACTED),
+        None => REDACTED.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redacts_bodies_and_headers() {
+        let body = json!({
+            "email": "a@example.com",
+            "password": "hunter2",
+            "mfa": {"state_token": "abc", "otp_token": "123456"},
+            "users": [{"id": 1, "client_secret": "s"}],
+            "access_token": null,
+        })
+        .to_string();
+        let redacted: Value = serde_json::from_str(&redact_body(&body)).unwrap();
+        assert_eq!(redacted["email"], "a@example.com");
+        assert_eq!(redacted["password"], REDACTED);
+        assert_eq!(redacted["mfa"]["state_token"], REDACTED);
+        assert_eq!(redacted["mfa"]["otp_token"], REDACTED);
+        assert_eq!(redacted["users"][0]["client_secret"], REDACTED);
+        assert_eq!(redacted["users"][0]["id"], 1);
+        assert!(redacted["access_token"].is_null());
+
+        assert_eq!(
+            redact_body("grant_type=client_credentials&client_secret=s"),
+            "grant_type=client_credentials&client_secret=[REDACTED]"
+        );
+        assert_eq!(redact_body("<html>oops</html>"), "<html>oops</html>");
+        assert_eq!(redact_header("Authorization", "Bearer abc.def"), "Bearer [REDACTED]");
+        assert_eq!(redact_header("X-Api-Key", "k"), "[REDACTED]");
+        assert_eq!(redact_header("Content-Type", "application/json"), "application/json");
+    }
+}
@@ -0,0 +1,314 @@
+//! SAML 2.0 metadata: generate service provider (SP) metadata, and read IdP
+//! metadata into the arguments for creating a trusted IdP.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+const BINDING_POST: &str = "urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST";
+const BINDING_REDIRECT: &str = "urn:oasis:names:tc:SAML:2.0:bindings:HTTP-Redirect";
+
+/// NameID format from a short name (`email`, `persistent`, `transient`, `unspecified`) or a full URN
+pub fn name_id_format_urn(format: &str) -> Result<String, String> {
+    let urn = match format {
+        "email" | "emailAddress" => "urn:oasis:names:tc:SAML:1.1:nameid-format:emailAddress",
+        "persistent" => "urn:oasis:names:tc:SAML:2.0:nameid-format:persistent",
+        "transient" => "urn:oasis:names:tc:SAML:2.0:nameid-format:transient",
+        "unspecified" => "urn:oasis:names:tc:SAML:1.1:nameid-format:unspecified",
+        urn if urn.starts_with("urn:oasis:names:tc:SAML:") => urn,
+        other => return Err(format!(
+            "Unknown NameID format '{}': use email, persistent, transient, unspecified or a full URN",
+            other
+        )),
+    };
+    Ok(urn.to_string())
+}
+
+/// Binding URN from `post`/`redirect` or a full URN
+fn binding_urn(binding: &str) -> String {
+    match binding.to_ascii_lowercase().as_str() {
+        "post" | "http-post" => BINDING_POST.to_string(),
+        "redirect" | "http-redirect" => BINDING_REDIRECT.to_string(),
+        _ => binding.to_string(),
+    }
+}
+
+/// Inputs for SP metadata
+#[derive(Debug, Clone, Default)]
+pub struct SpMetadata {
+    pub entity_id: String,
+    /// Assertion consumer service URLs; the first is the default
+    pub acs_urls: Vec<String>,
+    pub acs_binding: Option<String>,
+    pub slo_url: Option<String>,
+    pub name_id_format: String,
+    /// PEM or bare base64 signing/encryption certificate of the SP
+    pub certificate: Option<String>,
+    pub authn_requests_signed: bool,
+    pub want_assertions_signed: bool,
+}
+
+/// Render SP metadata XML
+pub fn generate_sp_metadata(sp: &SpMetadata) -> Result<String, String> {
+    if sp.entity_id.trim().is_empty() {
+        return Err("entity_id is required".to_string());
+    }
+    if sp.acs_urls.is_empty() {
+        return Err("At least one ACS URL is required".to_string());
+    }
+    for url in sp.acs_urls.iter().chain(sp.slo_url.iter()) {
+        let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+        if parsed.scheme() != "https" && parsed.host_str() != Some("localhost") {
+            return Err(format!("'{}' must use https", url));
+        }
+    }
+    let binding = binding_urn(sp.acs_binding.as_deref().unwrap_or("post"));
+    let name_id_format = name_id_format_urn(&sp.name_id_format)?;
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<md:EntityDescriptor xmlns:md=\"urn:oasis:names:tc:SAML:2.0:metadata\" entityID=\"{}\">\n",
+        escape(&sp.entity_id)
+    ));
+    xml.push_str(&format!(
+        "  <md:SPSSODescriptor AuthnRequestsSigned=\"{}\" WantAssertionsSigned=\"{}\" protocolSupportEnumeration=\"urn:oasis:names:tc:SAML:2.0:protocol\">\n",
+        sp.authn_requests_signed, sp.want_assertions_signed
+    ));
+    if let Some(certificate) = &sp.certificate {
+        let body = certificate_body(certificate);
+        for usage in ["signing", "encryption"] {
+            xml.push_str(&format!(
+                "    <md:KeyDescriptor use=\"{}\">\n      <ds:KeyInfo xmlns:ds=\"http://www.w3.org/2000/09/xmldsig#\">\n        <ds:X509Data>\n          <ds:X509Certificate>{}</ds:X509Certificate>\n        </ds:X509Data>\n      </ds:KeyInfo>\n    </md:KeyDescriptor>\n",
+                usage, body
+            ));
+        }
+    }
+    if let Some(slo_url) = &sp.slo_url {
+        xml.push_str(&format!(
+            "    <md:SingleLogoutService Binding=\"{}\" Location=\"{}\"/>\n",
+            BINDING_REDIRECT,
+            escape(slo_url)
+        ));
+    }
+    xml.push_str(&format!("    <md:NameIDFormat>{}</md:NameIDFormat>\n", escape(&name_id_format)));
+    for (index, acs_url) in sp.acs_urls.iter().enumerate() {
+        xml.push_str(&format!(
+            "    <md:AssertionConsumerService Binding=\"{}\" Location=\"{}\" index=\"{}\"{}/>\n",
+            escape(&binding),
+            escape(acs_url),
+            index,
+            if index == 0 { " isDefault=\"true\"" } else { "" }
+        ));
+    }
+    xml.push_str("  </md:SPSSODescriptor>\n</md:EntityDescriptor>\n");
+    Ok(xml)
+}
+
+/// A service endpoint from IdP metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub binding: String,
+    pub location: String,
+}
+
+/// What an IdP's metadata says
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdpMetadata {
+    pub entity_id: Option<String>,
+    pub sso_endpoints: Vec<Endpoint>,
+    pub slo_endpoints: Vec<Endpoint>,
+    /// Base64 DER bodies of the signing certificates
+    pub signing_certificates: Vec<String>,
+    pub name_id_formats: Vec<String>,
+}
+
+impl IdpMetadata {
+    /// Redirect binding preferred, as OneLogin sends AuthnRequests by redirect
+    pub fn preferred(endpoints: &[Endpoint]) -> Option<&Endpoint> {
+        endpoints
+            .iter()
+            .find(|e| e.binding == BINDING_REDIRECT)
+            .or_else(|| endpoints.iter().find(|e| e.binding == BINDING_POST))
+            .or_else(|| endpoints.first())
+    }
+}
+
+/// Read IdP metadata. Namespace prefixes are ignored; the first IDPSSODescriptor is used.
+pub fn parse_idp_metadata(xml: &str) -> Result<IdpMetadata, String> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut metadata = IdpMetadata::default();
+    let mut path: Vec<String> = Vec::new();
+    let mut in_idp = false;
+    let mut seen_idp = false;
+    let mut key_use: Option<String> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid metadata XML at position {}: {}", reader.buffer_position(), e))?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let name = local_name(e);
+                match name.as_str() {
+                    "EntityDescriptor" if metadata.entity_id.is_none() => {
+                        metadata.entity_id = attribute(e, "entityID")?;
+                    }
+                    "IDPSSODescriptor" if !seen_idp => {
+                        in_idp = true;
+                        seen_idp = true;
+                    }
+                    "KeyDescriptor" if in_idp => key_use = attribute(e, "use")?,
+                    "SingleSignOnService" | "SingleLogoutService" if in_idp => {
+                        let endpoint = Endpoint {
+                            binding: attribute(e, "Binding")?.unwrap_or_default(),
+                            location: attribute(e, "Location")?.unwrap_or_default(),
+                        };
+                        if name == "SingleSignOnService" {
+                            metadata.sso_endpoints.push(endpoint);
+                        } else {
+                            metadata.slo_endpoints.push(endpoint);
+                        }
+                    }
+                    _ => {}
+                }
+                if matches!(event, Event::Start(_)) {
+                    path.push(name);
+                }
+            }
+            Event::Text(text) if in_idp => {
+                let text = text.unescape().map_err(|e| format!("Invalid metadata text: {}", e))?;
+                match path.last().map(String::as_str) {
+                    // Keys without a use attribute serve both signing and encryption
+                    Some("X509Certificate")
+                        if path.iter().any(|p| p == "KeyDescriptor")
+                            && key_use.as_deref().is_none_or(|u| u == "signing") =>
+                    {
+                        metadata.signing_certificates.push(text.split_whitespace().collect());
+                    }
+                    Some("NameIDFormat") => metadata.name_id_formats.push(text.trim().to_string()),
+                    _ => {}
+                }
+            }
+            Event::End(_) => {
+                match path.pop().as_deref() {
+                    Some("IDPSSODescriptor") => in_idp = false,
+                    Some("KeyDescriptor") => key_use = None,
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_idp {
+        return Err("No IDPSSODescriptor found: this is not IdP metadata (SP metadata, or an error page?)".to_string());
+    }
+    Ok(metadata)
+}
+
+fn local_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, String> {
+    for attr in element.attributes() {
+        let attr = attr.map_err(|e| format!("Invalid metadata attribute: {}", e))?;
+        if attr.key.local_name().as_ref() == name.as_bytes() {
+            let value = attr.unescape_value().map_err(|e| format!("Invalid metadata attribute: {}", e))?;
+            return Ok(Some(value.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Base64 body of a PEM certificate (or an already bare body), on one line
+fn certificate_body(pem: &str) -> String {
+    pem.lines()
+        .filter(|line| !line.starts_with("-----"))
+        .flat_map(|line| line.split_whitespace())
+        .collect()
+}
+
+/// PEM wrapping of a base64 DER body
+pub fn to_pem(body: &str) -> String {
+    let lines: Vec<String> = body
+        .as_bytes()
+        .chunks(64)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect();
+    format!("-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----", lines.join("\n"))
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_sp_metadata() {
+        let sp = SpMetadata {
+            entity_id: "https://app.example.com/saml?a=1&b=2".to_string(),
+            acs_urls: vec!["https://app.example.com/acs".to_string(), "https://app.example.com/acs2".to_string()],
+            name_id_format: "email".to_string(),
+            certificate: Some("-----BEGIN CERTIFICATE-----\nMIIB\nAAAA\n-----END CERTIFICATE-----".to_string()),
+            want_assertions_signed: true,
+            ..Default::default()
+        };
+        let xml = generate_sp_metadata(&sp).unwrap();
+        assert!(xml.contains("entityID=\"https://app.example.com/saml?a=1&amp;b=2\""));
+        assert!(xml.contains("Location=\"https://app.example.com/acs\" index=\"0\" isDefault=\"true\"/>"));
+        assert!(xml.contains("index=\"1\"/>"));
+        assert!(xml.contains("<ds:X509Certificate>MIIBAAAA</ds:X509Certificate>"));
+        assert!(xml.contains("nameid-format:emailAddress"));
+
+        // Generated metadata is well-formed enough for our own reader
+        assert!(parse_idp_metadata(&xml).unwrap_err().contains("No IDPSSODescriptor"));
+
+        let insecure = SpMetadata { acs_urls: vec!["http://app.example.com/acs".to_string()], ..sp.clone() };
+        assert!(generate_sp_metadata(&insecure).unwrap_err().contains("https"));
+        let bad_format = SpMetadata { name_id_format: "mail".to_string(), ..sp };
+        assert!(generate_sp_metadata(&bad_format).is_err());
+    }
+
+    #[test]
+    fn test_parse_idp_metadata() {
+        let xml = r#"<?xml version="1.0"?>
+<EntityDescriptor xmlns="urn:oasis:names:tc:SAML:2.0:metadata" entityID="https://idp.example.com/metadata">
+  <IDPSSODescriptor protocolSupportEnumeration="urn:oasis:names:tc:SAML:2.0:protocol">
+    <KeyDescriptor use="encryption">
+      <ds:KeyInfo xmlns:ds="http://www.w3.org/2000/09/xmldsig#"><ds:X509Data><ds:X509Certificate>ENC</ds:X509Certificate></ds:X509Data></ds:KeyInfo>
+    </KeyDescriptor>
+    <KeyDescriptor use="signing">
+      <ds:KeyInfo xmlns:ds="http://www.w3.org/2000/09/xmldsig#"><ds:X509Data><ds:X509Certificate>
+        MIIC SIGN
+      </ds:X509Certificate></ds:X509Data></ds:KeyInfo>
+    </KeyDescriptor>
+    <NameIDFormat>urn:oasis:names:tc:SAML:1.1:nameid-format:emailAddress</NameIDFormat>
+    <SingleSignOnService Binding="urn:oasis:names:tc:SAML:2.0:bindings:HTTP-POST" Location="https://idp.example.com/sso/post"/>
+    <SingleSignOnService Binding="urn:oasis:names:tc:SAML:2.0:bindings:HTTP-Redirect" Location="https://idp.example.com/sso/redirect"/>
+    <SingleLogoutService Binding="urn:oasis:names:tc:SAML:2.0:bindings:HTTP-Redirect" Location="https://idp.example.com/slo"/>
+  </IDPSSODescriptor>
+</EntityDescriptor>"#;
+        let metadata = parse_idp_metadata(xml).unwrap();
+        assert_eq!(metadata.entity_id.as_deref(), Some("https://idp.example.com/metadata"));
+        assert_eq!(metadata.signing_certificates, vec!["MIICSIGN"]);
+        assert_eq!(metadata.name_id_formats.len(), 1);
+        assert_eq!(
+            IdpMetadata::preferred(&metadata.sso_endpoints).unwrap().location,
+            "https://idp.example.com/sso/redirect"
+        );
+        assert_eq!(metadata.slo_endpoints[0].location, "https://idp.example.com/slo");
+        assert!(to_pem("MIICSIGN").starts_with("-----BEGIN CERTIFICATE-----\nMIICSIGN\n"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Compare live API objects with the crate's models.
+//!
+//! A sample is deserialized into the model and serialized back; keys that do
+//! not survive the round trip are fields the model does not know, and keys the
+//! model writes that the sample lacks are fields OneLogin did not send.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+/// Differences between one sample object and its model
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ModelDrift {
+    /// Fields in the sample that the model drops (dotted paths)
+    pub unknown_fields: Vec<String>,
+    /// Model fields absent from the sample (dotted paths)
+    pub missing_fields: Vec<String>,
+    /// The sample could not be deserialized into the model at all
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_error: Option<String>,
+}
+
+impl ModelDrift {
+    pub fn is_clean(&self) -> bool {
+        self.unknown_fields.is_empty() && self.parse_error.is_none()
+    }
+}
+
+/// Compare `sample` with model `T`. `strip_catch_all` clears any `#[serde(flatten)]`
+/// map that would otherwise carry unknown fields through the round trip.
+pub fn compare<T, F>(sample: &Value, strip_catch_all: F) -> ModelDrift
+where
+    T: DeserializeOwned + Serialize,
+    F: FnOnce(&mut T),
+{
+    let mut model: T = match serde_json::from_value(sample.clone()) {
+        Ok(model) => model,
+        Err(e) => {
+            return ModelDrift {
+                parse_error: Some(e.to_string()),
+                ..Default::default()
+            }
+        }
+    };
+    strip_catch_all(&mut model);
+    let round_trip = match serde_json::to_value(&model) {
+        Ok(value) => value,
+        Err(e) => {
+            return ModelDrift {
+                parse_error: Some(e.to_string()),
+                ..Default::default()
+            }
+        }
+    };
+
+    let mut drift = ModelDrift::default();
+    if let (Value::Object(sample), Value::Object(known)) = (sample, &round_trip) {
+        diff_objects("", sample, known, &mut drift);
+    }
+    drift
+}
+
+fn diff_objects(prefix: &str, sample: &Map<String, Value>, known: &Map<String, Value>, drift: &mut ModelDrift) {
+    let path = |key: &str| if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+    for (key, value) in sample {
+        match known.get(key) {
+            // Always-null fields say nothing about the schema
+            None if value.is_null() => {}
+            None => drift.unknown_fields.push(path(key)),
+            Some(known_value) => {
+                if let (Some(sample), Some(known)) = (first_object(value), first_object(known_value)) {
+                    diff_objects(&path(key), sample, known, drift);
+                }
+            }
+        }
+    }
+    for key in known.keys() {
+        if !sample.contains_key(key) {
+            drift.missing_fields.push(path(key));
+        }
+    }
+}
+
+/// The object itself, or the first element of an array of objects
+fn first_object(value: &Value) -> Option<&Map<String, Value>> {
+    match value {
+        Value::Object(map) => Some(map),
+        Value::Array(items) => items.first().and_then(Value::as_object),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[derive(Serialize, Deserialize)]
+    struct Widget {
+        id: i64,
+        name: Option<String>,
+        owner: Option<Owner>,
+        #[serde(flatten)]
+        extra: Option<HashMap<String, Value>>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Owner {
+        email: String,
+    }
+
+    #[test]
+    fn test_reports_unknown_and_missing_fields() {
+        let sample = json!({"id": 1, "owner": {"email": "a@b.c", "phone": "1"}, "colour": "red", "legacy": null});
+        let drift = compare(&sample, |w: &mut Widget| w.extra = None);
+        assert_eq!(drift.unknown_fields, vec!["colour", "owner.phone"]);
+        assert_eq!(drift.missing_fields, vec!["name"]);
+        assert!(!drift.is_clean());
+
+        // Without stripping the catch-all, unknown fields round-trip and go unnoticed
+        let drift = compare(&sample, |_: &mut Widget| {});
+        assert_eq!(drift.unknown_fields, vec!["owner.phone"]);
+
+        let drift = compare(&json!({"id": "x"}), |_: &mut Widget| {});
+        assert!(drift.parse_error.is_some());
+    }
+}
@@ -0,0 +1,61 @@
+//! Masking and fingerprinting of secret values (e.g. Smart Hook env vars).
+//!
+//! Secrets are never serialized in cleartext: models mark their secret fields
+//! with `serialize_with = "mask_secret"`. A value can still be checked without
+//! revealing it by comparing SHA-256 fingerprints.
+
+use serde::Serializer;
+use sha2::{Digest, Sha256};
+
+/// Placeholder returned instead of a secret value
+pub const MASK: &str = "********";
+
+/// Serialize `Some(secret)` as `MASK`; pair with `skip_serializing_if = "Option::is_none"`
+pub fn mask_secret<S: Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(_) => serializer.serialize_str(MASK),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Hex-encoded SHA-256 of a secret value
+pub fn fingerprint(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
+
+/// Whether a caller-supplied SHA-256 (hex, any case) matches a fingerprint
+pub fn fingerprint_matches(expected: &str, candidate_sha256: &str) -> bool {
+    let candidate = candidate_sha256.trim().to_ascii_lowercase();
+    // Compare every byte so the time taken does not reveal the matching prefix
+    expected.len() == candidate.len()
+        && expected.bytes().zip(candidate.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Secretive {
+        #[serde(serialize_with = "mask_secret", skip_serializing_if = "Option::is_none")]
+        value: Option<String>,
+    }
+
+    #[test]
+    fn test_mask_secret() {
+        let masked = serde_json::to_value(Secretive { value: Some("hunter2".to_string()) }).unwrap();
+        assert_eq!(masked["value"], MASK);
+        let absent = serde_json::to_value(Secretive { value: None }).unwrap();
+        assert!(absent.get("value").is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_matches() {
+        let expected = fingerprint("hunter2");
+        assert_eq!(expected.len(), 64);
+        assert!(fingerprint_matches(&expected, &expected.to_uppercase()));
+        assert!(!fingerprint_matches(&expected, &fingerprint("hunter3")));
+        assert!(!fingerprint_matches(&expected, "abc"));
+    }
+}
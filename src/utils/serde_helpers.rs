@@ -1,4 +1,4 @@
-// Allow dead code - serde helpers available for future use
+// Allow dead code - not every helper is used by a model yet
 #![allow(dead_code)]
 
 use serde::{Deserialize, Deserializer};
@@ -29,3 +29,101 @@ where
         other => Some(other.to_string()),
     }))
 }
+
+fn value_to_i64(v: &Value) -> Option<i64> {
+    match v {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => s.trim().parse::<i64>().ok(),
+        Value::Bool(b) => Some(*b as i64),
+        _ => None,
+    }
+}
+
+/// Deserialize an i64 that the API may send as a number or a numeric string.
+/// Null and missing values become 0; non-numeric values are an error.
+pub fn flexible_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(0),
+        Some(v) => value_to_i64(&v)
+            .ok_or_else(|| serde::de::Error::custom(format!("expected integer, got {}", v))),
+    }
+}
+
+/// Like `flexible_i64`, for i32 fields such as status codes
+pub fn flexible_i32<'de, D>(deserializer: D) -> Result<i32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let n = flexible_i64(deserializer)?;
+    i32::try_from(n).map_err(|_| serde::de::Error::custom(format!("integer {} out of range", n)))
+}
+
+/// Deserialize an Option<i64> from a number or numeric string; unparseable values become None
+pub fn flexible_option_i64<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v = Option::<Value>::deserialize(deserializer)?;
+    Ok(v.as_ref().and_then(value_to_i64))
+}
+
+/// Deserialize a bool from a JSON bool, 0/1, or "true"/"false" string
+pub fn flexible_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(b)) => Ok(b),
+        Some(Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Ok(true),
+            "false" | "0" | "no" | "" => Ok(false),
+            _ => Err(serde::de::Error::custom(format!("expected boolean, got \"{}\"", s))),
+        },
+        Some(v) => value_to_i64(&v)
+            .map(|n| n != 0)
+            .ok_or_else(|| serde::de::Error::custom(format!("expected boolean, got {}", v))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Sample {
+        #[serde(default, deserialize_with = "flexible_i64")]
+        id: i64,
+        #[serde(default, deserialize_with = "flexible_i32")]
+        status: i32,
+        #[serde(default, deserialize_with = "flexible_option_i64")]
+        group_id: Option<i64>,
+        #[serde(default, deserialize_with = "flexible_bool")]
+        error: bool,
+    }
+
+    #[test]
+    fn test_flexible_numbers_accept_strings() {
+        let s: Sample =
+            serde_json::from_value(json!({"id": "42", "status": 1, "group_id": "7", "error": "false"}))
+                .unwrap();
+        assert_eq!(s.id, 42);
+        assert_eq!(s.status, 1);
+        assert_eq!(s.group_id, Some(7));
+        assert!(!s.error);
+    }
+
+    #[test]
+    fn test_flexible_numbers_defaults() {
+        let s: Sample = serde_json::from_value(json!({"status": null, "error": 1})).unwrap();
+        assert_eq!(s.id, 0);
+        assert_eq!(s.status, 0);
+        assert_eq!(s.group_id, None);
+        assert!(s.error);
+        assert!(serde_json::from_value::<Sample>(json!({"id": "abc"})).is_err());
+    }
+}
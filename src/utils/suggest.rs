@@ -0,0 +1,70 @@
+//! "Did you mean" suggestions for names the model got slightly wrong.
+
+/// Most suggestions returned for one name
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Edit distance between two strings (insertions, deletions, substitutions)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Candidates close to `name`, best first: case-insensitive matches, names that
+/// contain it (or are contained in it), then small edit distances relative to length
+pub fn closest<'a, I>(name: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let wanted = name.to_lowercase();
+    let threshold = (wanted.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let score = if lower == wanted {
+                0
+            } else if lower.contains(&wanted) || wanted.contains(&lower) {
+                1
+            } else {
+                let distance = edit_distance(&wanted, &lower);
+                if distance > threshold {
+                    return None;
+                }
+                distance + 1
+            };
+            Some((score, candidate))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest_matches() {
+        let names = ["department", "cost_center", "employee_id", "manager_email"];
+        assert_eq!(edit_distance("departmnet", "department"), 2);
+        assert_eq!(closest("Department", names), vec!["department"]);
+        assert_eq!(closest("departmnet", names), vec!["department"]);
+        assert_eq!(closest("employee", names), vec!["employee_id"]);
+        assert!(closest("location", names).is_empty());
+    }
+}
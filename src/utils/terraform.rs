@@ -0,0 +1,228 @@
+//! Rendering of Terraform/OpenTofu configuration (HCL) and import commands.
+
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+/// An HCL block such as `resource "onelogin_roles" "admins" { ... }` or a
+/// nested `conditions { ... }` block
+#[derive(Debug, Clone, Default)]
+pub struct HclBlock {
+    pub kind: String,
+    pub labels: Vec<String>,
+    /// Attributes in output order; `null` values are omitted
+    pub attributes: Vec<(String, Value)>,
+    pub blocks: Vec<HclBlock>,
+}
+
+impl HclBlock {
+    pub fn new(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn attr(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.attributes.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn block(mut self, block: HclBlock) -> Self {
+        self.blocks.push(block);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.render_into(&mut out, 0);
+        out
+    }
+
+    fn render_into(&self, out: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth);
+        out.push_str(&indent);
+        out.push_str(&self.kind);
+        for label in &self.labels {
+            let _ = write!(out, " {}", hcl_string(label));
+        }
+        out.push_str(" {\n");
+        let present: Vec<&(String, Value)> = self.attributes.iter().filter(|(_, v)| !v.is_null()).collect();
+        let width = present.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+        for (name, value) in present {
+            let _ = writeln!(out, "{}  {:width$} = {}", indent, name, hcl_value(value, depth + 1), width = width);
+        }
+        for block in &self.blocks {
+            out.push('\n');
+            block.render_into(out, depth + 1);
+        }
+        let _ = writeln!(out, "{}}}", indent);
+    }
+}
+
+/// Quote a string as an HCL literal, escaping template sequences
+pub fn hcl_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '$' | '%' if chars.peek() == Some(&'{') => {
+                out.push(c);
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Render a JSON value as an HCL expression (objects become maps)
+pub fn hcl_value(value: &Value, depth: usize) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => hcl_string(s),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(|v| hcl_value(v, depth)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(map) if map.is_empty() => "{}".to_string(),
+        Value::Object(map) => {
+            let indent = "  ".repeat(depth);
+            let mut out = String::from("{\n");
+            for (key, v) in map {
+                let _ = writeln!(out, "{}  {} = {}", indent, hcl_string(key), hcl_value(v, depth + 1));
+            }
+            out.push_str(&indent);
+            out.push('}');
+            out
+        }
+    }
+}
+
+/// A set of resources being exported, with unique resource names and import IDs
+#[derive(Debug, Default)]
+pub struct TerraformExport {
+    resources: Vec<HclBlock>,
+    /// (resource address, remote ID)
+    imports: Vec<(String, String)>,
+    names: HashSet<String>,
+}
+
+impl TerraformExport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `resource "<resource_type>" "<name>"` built from `body`; returns its address
+    pub fn add_resource(&mut self, resource_type: &str, name_hint: &str, id: &str, body: HclBlock) -> String {
+        let name = self.unique_name(name_hint, id);
+        let address = format!("{}.{}", resource_type, name);
+        let mut block = body;
+        block.kind = "resource".to_string();
+        block.labels = vec![resource_type.to_string(), name];
+        self.resources.push(block);
+        self.imports.push((address.clone(), id.to_string()));
+        address
+    }
+
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// Resource blocks, optionally followed by `import` blocks (Terraform 1.5+ / OpenTofu)
+    pub fn render_hcl(&self, include_imports: bool) -> String {
+        let mut out = String::new();
+        for resource in &self.resources {
+            out.push_str(&resource.render());
+            out.push('\n');
+        }
+        if include_imports {
+            for (address, id) in &self.imports {
+                let _ = writeln!(out, "import {{\n  to = {}\n  id = {}\n}}\n", address, hcl_string(id));
+            }
+        }
+        out
+    }
+
+    /// Shell script running `terraform import` for every resource. Set `TF=tofu` to use OpenTofu.
+    pub fn render_import_script(&self) -> String {
+        let mut out = String::from("#!/bin/sh\nset -e\nTF=\"${TF:-terraform}\"\n\n");
+        for (address, id) in &self.imports {
+            let _ = writeln!(out, "\"$TF\" import '{}' '{}'", address, id.replace('\'', "'\\''"));
+        }
+        out
+    }
+
+    /// Terraform identifier from a display name: lowercase, `[a-z0-9_]`, never starting with a digit
+    fn unique_name(&mut self, hint: &str, id: &str) -> String {
+        let mut name = String::new();
+        for c in hint.trim().to_lowercase().chars() {
+            if c.is_ascii_alphanumeric() {
+                name.push(c);
+            } else if !name.ends_with('_') {
+                name.push('_');
+            }
+        }
+        let mut name = name.trim_matches('_').to_string();
+        if name.is_empty() {
+            name = format!("resource_{}", id);
+        } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert(0, '_');
+        }
+        if self.names.contains(&name) {
+            name = format!("{}_{}", name, id);
+        }
+        self.names.insert(name.clone());
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_resource_with_nested_blocks() {
+        let mut export = TerraformExport::new();
+        let body = HclBlock::new("")
+            .attr("name", "Eng ${team}")
+            .attr("enabled", true)
+            .attr("position", Value::Null)
+            .block(HclBlock::new("conditions").attr("source", "department").attr("value", "Eng"));
+        let address = export.add_resource("onelogin_user_mappings", "Eng Mapping!", "42", body);
+        assert_eq!(address, "onelogin_user_mappings.eng_mapping");
+        let hcl = export.render_hcl(true);
+        assert!(hcl.starts_with("resource \"onelogin_user_mappings\" \"eng_mapping\" {\n"));
+        assert!(hcl.contains("  name    = \"Eng $${team}\"\n"));
+        assert!(hcl.contains("  enabled = true\n"));
+        assert!(!hcl.contains("position"));
+        assert!(hcl.contains("\n  conditions {\n    source = \"department\"\n"));
+        assert!(hcl.contains("import {\n  to = onelogin_user_mappings.eng_mapping\n  id = \"42\"\n}"));
+    }
+
+    #[test]
+    fn test_unique_names_and_import_script() {
+        let mut export = TerraformExport::new();
+        assert_eq!(export.add_resource("onelogin_roles", "Admins", "1", HclBlock::new("")), "onelogin_roles.admins");
+        assert_eq!(export.add_resource("onelogin_roles", "admins", "2", HclBlock::new("")), "onelogin_roles.admins_2");
+        assert_eq!(export.add_resource("onelogin_roles", "2FA users", "3", HclBlock::new("")), "onelogin_roles._2fa_users");
+        assert_eq!(export.add_resource("onelogin_roles", "???", "4", HclBlock::new("")), "onelogin_roles.resource_4");
+        let script = export.render_import_script();
+        assert!(script.contains("\"$TF\" import 'onelogin_roles.admins_2' '2'\n"));
+        assert_eq!(hcl_value(&json!({"a": [1, "b"]}), 0), "{\n  \"a\" = [1, \"b\"]\n}");
+    }
+}